@@ -83,6 +83,24 @@ for extra_path in (BACKEND_ROOT_DIR, API_SERVER_DIR):
 
 _write_debug(f"sys.path after backend insert (first 5): {sys.path[:5]}")
 
+def _extra_ca_pems():
+    """Certificados raíz adicionales del shell (NA_EXTRA_CA_CERTS, ver src-tauri/src/proxy.rs)."""
+    import ssl
+
+    pems = []
+    for path in filter(None, os.environ.get("NA_EXTRA_CA_CERTS", "").split(os.pathsep)):
+        try:
+            data = Path(path).read_bytes()
+        except OSError as e:
+            _write_debug(f"Extra CA not readable {path}: {e}")
+            continue
+        if b"-----BEGIN" in data:
+            pems.append(data.decode("ascii", errors="ignore"))
+        else:
+            pems.append(ssl.DER_cert_to_PEM_cert(data))
+    return pems
+
+
 # CRITICAL: Configure SSL with certifi BEFORE any network operations
 # This fixes SSL certificate errors in embedded Python on macOS
 def _configure_ssl_with_certifi():
@@ -95,6 +113,23 @@ def _configure_ssl_with_certifi():
         # Create default context with certifi
         ssl_context = ssl.create_default_context(cafile=certifi.where())
 
+        # CA de proxies corporativos: al contexto de urllib y a un bundle
+        # certifi + extras para requests/httpx (huggingface_hub, LLM remotos)
+        extra_pems = _extra_ca_pems()
+        if extra_pems:
+            import tempfile
+
+            for pem in extra_pems:
+                ssl_context.load_verify_locations(cadata=pem)
+            bundle = Path(tempfile.gettempdir()) / "narrative_assistant_ca_bundle.pem"
+            bundle.write_text(
+                Path(certifi.where()).read_text(encoding="ascii") + "\n" + "\n".join(extra_pems),
+                encoding="ascii",
+            )
+            for var in ("SSL_CERT_FILE", "REQUESTS_CA_BUNDLE"):
+                os.environ[var] = str(bundle)
+            _write_debug(f"Added {len(extra_pems)} extra CA file(s): {bundle}")
+
         # Monkey-patch urllib.request.urlopen to use certifi
         import urllib.request
         _original_urlopen = urllib.request.urlopen
@@ -539,6 +574,11 @@ try:
     )
     _early_logger.info("FastAPI app created successfully")
 
+    # Token de sesión del shell. Se registra antes que CORS para que este lo
+    # envuelva y los 401 lleven cabeceras CORS (el frontend los ve como error HTTP).
+    from middleware.session_token import TOKEN_HEADER, SessionTokenMiddleware
+    app.add_middleware(SessionTokenMiddleware)
+
     # Configure CORS
     _early_logger.info("Adding CORS middleware...")
     app.add_middleware(
@@ -559,6 +599,7 @@ try:
             "Authorization",
             "X-Requested-With",
             "X-Response-Time",  # Para timing metrics
+            TOKEN_HEADER,
         ],
         expose_headers=["X-Response-Time"],  # Permitir que frontend lea el header
     )
@@ -674,6 +715,10 @@ try:
     from routers import events
     _early_logger.info("events router imported")
 
+    _early_logger.info("Importing plugins router...")
+    from routers import plugins
+    _early_logger.info("plugins router imported")
+
     _early_logger.info("All routers imported successfully")
 
 except Exception as e:
@@ -740,6 +785,9 @@ try:
     _early_logger.info("Registering events router...")
     app.include_router(events.router)
 
+    _early_logger.info("Registering plugins router...")
+    app.include_router(plugins.router)
+
     _early_logger.info("All routers registered successfully")
 
 except Exception as e:
@@ -0,0 +1,100 @@
+"""
+Token de sesión emitido por el shell de Tauri.
+
+El backend escucha en 127.0.0.1, pero cualquier proceso local podía leer los
+manuscritos a través de la API. El shell genera un token aleatorio en cada
+arranque, lo pasa al backend en `NA_SESSION_TOKEN` y al frontend con el
+comando `get_backend_session_token`; toda petición debe traerlo en la cabecera
+`X-NA-Session-Token`.
+
+Las rutas que escriben en una ruta del disco elegida por el cliente
+(`SHELL_ONLY_PATHS`) exigen además el token del shell (`NA_SHELL_TOKEN`, en
+`X-NA-Shell-Token`), que el frontend no recibe: solo llegan a través de
+`backend_request`, que antes comprueba que el usuario eligió esa carpeta
+(src-tauri/src/export_scope.rs).
+
+Sin `NA_SESSION_TOKEN` (backend arrancado a mano en desarrollo) no se exige
+ninguno de los dos.
+"""
+
+import hmac
+import logging
+import os
+import re
+from typing import Callable
+
+from fastapi import Request, Response
+from fastapi.responses import JSONResponse
+from starlette.middleware.base import BaseHTTPMiddleware
+
+logger = logging.getLogger(__name__)
+
+TOKEN_ENV = "NA_SESSION_TOKEN"
+TOKEN_HEADER = "X-NA-Session-Token"
+SHELL_TOKEN_ENV = "NA_SHELL_TOKEN"
+SHELL_TOKEN_HEADER = "X-NA-Shell-Token"
+
+# El shell sondea /api/health antes de verificar el token; no expone datos
+EXEMPT_PATHS = {"/api/health"}
+
+# Escriben en la ruta que indica el cliente: solo a través del shell
+SHELL_ONLY_PATHS = [re.compile(r"^/api/projects/\d+/save-file/?$")]
+
+
+def _matches(supplied: str, expected: str) -> bool:
+    return hmac.compare_digest(supplied.encode(), expected.encode())
+
+
+class SessionTokenMiddleware(BaseHTTPMiddleware):
+    """
+    Rechaza con 401 las peticiones sin el token de sesión del shell.
+
+    - OPTIONS (preflight CORS) y EXEMPT_PATHS: siempre permitidos
+    - SHELL_ONLY_PATHS: 403 sin el token del shell
+    - Sin token configurado: todo permitido
+    """
+
+    def __init__(self, app, token: str | None = None, shell_token: str | None = None):
+        super().__init__(app)
+        self.token = token if token is not None else os.environ.get(TOKEN_ENV, "")
+        self.shell_token = (
+            shell_token if shell_token is not None else os.environ.get(SHELL_TOKEN_ENV, "")
+        )
+        if self.token:
+            logger.info("Token de sesión del shell exigido en la API")
+        else:
+            logger.warning(f"{TOKEN_ENV} no definido: la API no exige token de sesión")
+
+    async def dispatch(self, request: Request, call_next: Callable) -> Response:
+        if (
+            not self.token
+            or request.method == "OPTIONS"
+            or request.url.path in EXEMPT_PATHS
+        ):
+            return await call_next(request)
+
+        supplied = request.headers.get(TOKEN_HEADER, "")
+        if not _matches(supplied, self.token):
+            logger.warning(
+                f"Petición sin token de sesión válido rechazada: {request.method} {request.url.path}"
+            )
+            return JSONResponse(
+                status_code=401,
+                content={"success": False, "error": "Token de sesión no válido"},
+            )
+
+        if any(pattern.match(request.url.path) for pattern in SHELL_ONLY_PATHS):
+            supplied = request.headers.get(SHELL_TOKEN_HEADER, "")
+            # Sin token del shell configurado no hay forma de probar el origen: se rechaza
+            if not self.shell_token or not _matches(supplied, self.shell_token):
+                logger.warning(
+                    f"Petición fuera del shell rechazada: {request.method} {request.url.path}"
+                )
+                return JSONResponse(
+                    status_code=403,
+                    content={
+                        "success": False,
+                        "error": "Esta operación solo está disponible desde la aplicación",
+                    },
+                )
+        return await call_next(request)
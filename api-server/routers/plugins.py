@@ -0,0 +1,81 @@
+"""
+Router: plugins
+
+Complementos de terceros con análisis propios. El shell de escritorio lee los
+manifiestos de ~/.narrative_assistant/plugins/ y pasa sus puntos de entrada en
+la variable NA_PLUGINS (JSON: [{"id", "dir", "entry_point": "modulo:funcion"}]).
+
+Cada punto de entrada recibe un APIRouter con prefijo /api/plugins/{id} y
+registra en él sus endpoints. Un complemento que falla al cargar se registra
+en el log y se omite: nunca impide arrancar el servidor.
+
+Endpoints:
+- GET /api/plugins - Complementos cargados y errores de carga
+- /api/plugins/{id}/... - Endpoints de cada complemento
+"""
+
+import importlib
+import json
+import logging
+import os
+import re
+import sys
+from typing import Any, Optional
+
+from deps import ApiResponse
+from fastapi import APIRouter
+
+logger = logging.getLogger(__name__)
+
+_PLUGIN_ID = re.compile(r"^[a-z0-9_-]{1,64}$")
+
+
+def load_plugins(raw: Optional[str]) -> tuple[APIRouter, list[dict[str, Any]]]:
+    """
+    Carga los complementos descritos en `raw` (contenido de NA_PLUGINS).
+
+    Returns:
+        Router con los endpoints de todos los complementos y estado de cada uno
+    """
+    router = APIRouter()
+    status: list[dict[str, Any]] = []
+    if not raw:
+        return router, status
+
+    try:
+        entries = json.loads(raw)
+    except json.JSONDecodeError as e:
+        logger.warning(f"NA_PLUGINS no es JSON válido: {e}")
+        return router, status
+
+    for entry in entries if isinstance(entries, list) else []:
+        plugin_id = str(entry.get("id", ""))
+        try:
+            if not _PLUGIN_ID.match(plugin_id):
+                raise ValueError(f"id no válido '{plugin_id}'")
+            module_name, function_name = str(entry["entry_point"]).split(":", 1)
+            plugin_dir = str(entry["dir"])
+            if plugin_dir not in sys.path:
+                sys.path.insert(0, plugin_dir)
+
+            register = getattr(importlib.import_module(module_name), function_name)
+            plugin_router = APIRouter(prefix=f"/api/plugins/{plugin_id}", tags=[f"plugin:{plugin_id}"])
+            register(plugin_router)
+            router.include_router(plugin_router)
+
+            status.append({"id": plugin_id, "loaded": True, "error": None})
+            logger.info(f"Complemento cargado: {plugin_id} ({entry['entry_point']})")
+        except Exception as e:
+            status.append({"id": plugin_id, "loaded": False, "error": f"{type(e).__name__}: {e}"})
+            logger.warning(f"No se pudo cargar el complemento '{plugin_id}': {e}", exc_info=True)
+
+    return router, status
+
+
+router, _status = load_plugins(os.environ.get("NA_PLUGINS"))
+
+
+@router.get("/api/plugins", response_model=ApiResponse)
+def list_plugins():
+    """Complementos cargados en este arranque del servidor."""
+    return ApiResponse(success=True, data=_status)
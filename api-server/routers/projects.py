@@ -977,6 +977,112 @@ def update_project_settings(
         return ApiResponse(success=False, error="Error interno del servidor")
 
 
+# Notas rápidas del shell (atajo global de la app de escritorio).
+# Se guardan en settings["quick_notes"] del proyecto, sin tabla propia.
+QUICK_NOTES_KEY = "quick_notes"
+MAX_QUICK_NOTE_CHARS = 4000
+MAX_QUICK_NOTES = 1000
+
+
+def _project_settings_dict(project) -> dict:
+    import json
+
+    if isinstance(project.settings, dict):
+        return project.settings
+    return json.loads(project.settings) if project.settings else {}
+
+
+@router.get("/api/projects/{project_id}/notes", response_model=ApiResponse)
+def list_project_notes(project_id: int):
+    """
+    Lista las notas rápidas del proyecto, de la más reciente a la más antigua.
+
+    Args:
+        project_id: ID del proyecto
+
+    Returns:
+        ApiResponse con la lista de notas
+    """
+    try:
+        if not deps.project_manager:
+            return ApiResponse(success=False, error="Project manager not initialized")
+
+        result = deps.project_manager.get(project_id)
+        if result.is_failure:
+            raise HTTPException(status_code=404, detail="Proyecto no encontrado")
+
+        notes = _project_settings_dict(result.value).get(QUICK_NOTES_KEY, [])
+        return ApiResponse(success=True, data=list(reversed(notes)))
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"Error listing notes for project {project_id}: {e}", exc_info=True)
+        return ApiResponse(success=False, error="Error interno del servidor")
+
+
+@router.post("/api/projects/{project_id}/notes", response_model=ApiResponse)
+def add_project_note(
+    project_id: int,
+    text: str = Body(...),
+    source: str = Body("quick_note"),
+):
+    """
+    Añade una nota rápida al proyecto.
+
+    Args:
+        project_id: ID del proyecto
+        text: Texto de la nota
+        source: Origen de la nota (p. ej. "quick_note")
+
+    Returns:
+        ApiResponse con la nota creada
+    """
+    try:
+        if not deps.project_manager:
+            return ApiResponse(success=False, error="Project manager not initialized")
+
+        text = text.strip()
+        if not text:
+            raise HTTPException(status_code=400, detail="La nota está vacía")
+        if len(text) > MAX_QUICK_NOTE_CHARS:
+            raise HTTPException(
+                status_code=400,
+                detail=f"La nota supera los {MAX_QUICK_NOTE_CHARS} caracteres",
+            )
+
+        result = deps.project_manager.get(project_id)
+        if result.is_failure:
+            raise HTTPException(status_code=404, detail="Proyecto no encontrado")
+
+        import datetime
+        import uuid
+
+        project = result.value
+        current_settings = _project_settings_dict(project)
+        note = {
+            "id": uuid.uuid4().hex,
+            "text": text,
+            "source": source,
+            "created_at": datetime.datetime.utcnow().isoformat() + "Z",
+        }
+        notes = current_settings.get(QUICK_NOTES_KEY, [])
+        notes.append(note)
+        current_settings[QUICK_NOTES_KEY] = notes[-MAX_QUICK_NOTES:]
+
+        project.settings = current_settings
+        update_result = deps.project_manager.update(project)
+        if update_result.is_failure:
+            logger.error(f"Failed to save note: {update_result.error}")
+            return ApiResponse(success=False, error="Error al guardar la nota")
+
+        return ApiResponse(success=True, data=note)
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"Error adding note to project {project_id}: {e}", exc_info=True)
+        return ApiResponse(success=False, error="Error interno del servidor")
+
+
 @router.post("/api/projects", response_model=ApiResponse)
 def create_project(
     name: str = Body(...),
@@ -1248,6 +1248,34 @@ async def get_llm_readiness():
         )
 
 
+@router.post("/api/services/embeddings/warmup", response_model=ApiResponse)
+def warmup_embeddings():
+    """
+    Carga el modelo de embeddings en memoria para que el próximo análisis no
+    espere a la carga. Lo llama el shell cuando el usuario está inactivo.
+    No descarga nada: sin el modelo instalado responde success=False.
+    """
+    try:
+        from narrative_assistant.core.config import get_config
+        from narrative_assistant.core.model_manager import ModelType, get_model_manager
+        from narrative_assistant.nlp.embeddings import get_embeddings_model
+
+        installed = (
+            get_config().embeddings_model_path is not None
+            or get_model_manager().get_model_path(ModelType.EMBEDDINGS) is not None
+        )
+        if not installed:
+            return ApiResponse(success=False, error="Modelo de embeddings no instalado")
+
+        model = get_embeddings_model()
+        # Una codificación corta inicializa también el dispositivo (CUDA/MPS)
+        model.encode("precarga")
+        return ApiResponse(success=True, data={"model": model.model_name, "device": model.device})
+    except Exception as e:
+        logger.warning(f"Embeddings warm-up failed: {e}")
+        return ApiResponse(success=False, error="No se pudo cargar el modelo de embeddings")
+
+
 @router.get("/api/services/llm/models")
 async def get_llm_models():
     """Lista modelos con estado (installed, available, legacy, canRun)."""
@@ -0,0 +1,81 @@
+// Consola de desarrollador: muestra las últimas líneas del log del shell
+// (`get_recent_logs`) y añade cada nueva que llega como `dev-console-log`.
+// El filtro y el nivel solo afectan a lo que se ve, no a lo que se registra.
+(function () {
+  const lines = document.getElementById('console-lines')
+  const filter = document.getElementById('console-filter')
+  const level = document.getElementById('console-level')
+  const follow = document.getElementById('console-follow')
+  const status = document.getElementById('console-status')
+  const tauri = window.__TAURI__
+  if (!tauri || !tauri.core) return
+
+  const LEVELS = ['TRACE', 'DEBUG', 'INFO', 'WARN', 'ERROR']
+  const MAX_LINES = 5000
+  const entries = []
+
+  function levelOf(line) {
+    const match = line.match(/\b(TRACE|DEBUG|INFO|WARN|ERROR)\b/)
+    return match ? match[1] : 'INFO'
+  }
+
+  function visible(entry) {
+    const term = filter.value.trim().toLowerCase()
+    return (
+      LEVELS.indexOf(entry.level) >= LEVELS.indexOf(level.value) &&
+      (!term || entry.text.toLowerCase().includes(term))
+    )
+  }
+
+  function render(entry) {
+    const row = document.createElement('div')
+    row.className = `console__line console__line--${entry.level.toLowerCase()}`
+    row.textContent = entry.text
+    row.hidden = !visible(entry)
+    return row
+  }
+
+  function updateStatus() {
+    status.textContent = `${entries.length} líneas`
+  }
+
+  function append(text) {
+    const entry = { text, level: levelOf(text) }
+    entries.push(entry)
+    lines.appendChild(render(entry))
+    if (entries.length > MAX_LINES) {
+      entries.shift()
+      lines.firstChild.remove()
+    }
+    if (follow.checked) lines.scrollTop = lines.scrollHeight
+  }
+
+  function refilter() {
+    Array.from(lines.children).forEach((row, index) => {
+      row.hidden = !visible(entries[index])
+    })
+    if (follow.checked) lines.scrollTop = lines.scrollHeight
+  }
+
+  filter.addEventListener('input', refilter)
+  level.addEventListener('change', refilter)
+  document.getElementById('console-clear').addEventListener('click', () => {
+    entries.length = 0
+    lines.replaceChildren()
+    updateStatus()
+  })
+
+  tauri.core
+    .invoke('get_recent_logs', { limit: 500 })
+    .then((recent) => {
+      recent.forEach(append)
+      updateStatus()
+      return tauri.webviewWindow.getCurrentWebviewWindow().listen('dev-console-log', (event) => {
+        append(event.payload)
+        updateStatus()
+      })
+    })
+    .catch((err) => {
+      status.textContent = String(err)
+    })
+})()
@@ -0,0 +1,68 @@
+// Ventana de notas rápidas: guarda la nota en el proyecto que estaba activo al
+// abrirla (`save_quick_note`). Ctrl/Cmd+Enter guarda y Escape cierra.
+(function () {
+  const projectLabel = document.getElementById('note-project')
+  const text = document.getElementById('note-text')
+  const status = document.getElementById('note-status')
+  const save = document.getElementById('note-save')
+  const tauri = window.__TAURI__
+  if (!tauri || !tauri.core) return
+
+  let projectId = null
+  let saving = false
+
+  function setStatus(message, isError) {
+    status.textContent = message
+    status.classList.toggle('note__status--error', Boolean(isError))
+  }
+
+  function updateButton() {
+    save.disabled = saving || projectId == null || text.value.trim() === ''
+  }
+
+  function close() {
+    tauri.webviewWindow.getCurrentWebviewWindow().close()
+  }
+
+  async function submit() {
+    if (save.disabled) return
+    saving = true
+    updateButton()
+    setStatus('Guardando...')
+    try {
+      await tauri.core.invoke('save_quick_note', { text: text.value })
+      close()
+    } catch (err) {
+      setStatus(String(err), true)
+    } finally {
+      saving = false
+      updateButton()
+    }
+  }
+
+  text.addEventListener('input', updateButton)
+  save.addEventListener('click', submit)
+  document.addEventListener('keydown', (event) => {
+    if (event.key === 'Escape') close()
+    if (event.key === 'Enter' && (event.ctrlKey || event.metaKey)) {
+      event.preventDefault()
+      submit()
+    }
+  })
+
+  tauri.core
+    .invoke('get_quick_note_context')
+    .then((context) => {
+      projectId = context.project_id
+      if (projectId == null) {
+        projectLabel.textContent = 'Abra un proyecto para guardar notas'
+      } else {
+        projectLabel.textContent = `Proyecto: ${context.project_name || `#${projectId}`}`
+      }
+      setStatus(`${context.shortcut} para abrir o cerrar`)
+      updateButton()
+    })
+    .catch((err) => setStatus(String(err), true))
+
+  text.focus()
+})()
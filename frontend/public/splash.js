@@ -0,0 +1,20 @@
+// Ventana de carga: muestra las fases de arranque del backend (tema `boot` del
+// canal de estado, src-tauri/src/status_channel.rs) hasta que el shell revela
+// la ventana principal y cierra esta.
+(function () {
+  const message = document.getElementById('splash-message')
+  const progress = document.getElementById('splash-progress')
+  const tauri = window.__TAURI__
+  if (!tauri || !tauri.event) return
+
+  tauri.event.listen('status-event', (event) => {
+    const { topic, payload } = event.payload
+    if (topic === 'boot') {
+      if (payload.message) message.textContent = payload.message
+      if (typeof payload.progress === 'number') progress.style.width = `${Math.round(payload.progress * 100)}%`
+    } else if (topic === 'backend' && payload.status === 'error') {
+      message.textContent = payload.message
+      message.classList.add('splash__message--error')
+    }
+  })
+})()
@@ -0,0 +1,83 @@
+// Ventana de desbloqueo: envía la contraseña al shell (`unlock_project` o, en
+// modo "set", `set_project_password`) y vacía los campos en cuanto sale.
+// La clave se deriva en Rust; esta ventana se destruye al terminar.
+(function () {
+  const form = document.getElementById('unlock-form')
+  const title = document.getElementById('unlock-title')
+  const password = document.getElementById('unlock-password')
+  const confirm = document.getElementById('unlock-confirm')
+  const status = document.getElementById('unlock-status')
+  const submit = document.getElementById('unlock-submit')
+  const tauri = window.__TAURI__
+  if (!tauri || !tauri.core) return
+
+  let mode = 'unlock'
+  let minLength = 8
+  let busy = false
+
+  function setStatus(message, isError) {
+    status.textContent = message
+    status.classList.toggle('unlock__status--error', Boolean(isError))
+  }
+
+  function updateButton() {
+    const length = password.value.length
+    submit.disabled = busy || length === 0 || (mode === 'set' && length < minLength)
+  }
+
+  function close() {
+    tauri.webviewWindow.getCurrentWebviewWindow().close()
+  }
+
+  async function send() {
+    if (submit.disabled) return
+    if (mode === 'set' && password.value !== confirm.value) {
+      setStatus('Las contraseñas no coinciden', true)
+      return
+    }
+    const value = password.value
+    password.value = ''
+    confirm.value = ''
+    busy = true
+    updateButton()
+    setStatus(mode === 'set' ? 'Cifrando la base de datos...' : 'Comprobando...')
+    try {
+      const command = mode === 'set' ? 'set_project_password' : 'unlock_project'
+      await tauri.core.invoke(command, { password: value })
+      close()
+    } catch (err) {
+      setStatus(String(err), true)
+    } finally {
+      busy = false
+      updateButton()
+      password.focus()
+    }
+  }
+
+  password.addEventListener('input', updateButton)
+  form.addEventListener('submit', (event) => {
+    event.preventDefault()
+    send()
+  })
+  document.addEventListener('keydown', (event) => {
+    if (event.key === 'Escape' && !busy) close()
+  })
+
+  tauri.core
+    .invoke('get_unlock_state')
+    .then((state) => {
+      mode = state.mode
+      minLength = state.min_password_len
+      if (mode === 'set') {
+        document.title = 'Proteger con contraseña'
+        title.textContent = 'Elija una contraseña para cifrar los proyectos'
+        confirm.hidden = false
+        submit.textContent = 'Proteger'
+        setStatus(`Mínimo ${minLength} caracteres. Sin ella no se podrán recuperar.`)
+      }
+      updateButton()
+    })
+    .catch((err) => setStatus(String(err), true))
+
+  password.focus()
+})()
@@ -5,12 +5,24 @@
  * incremental data loading during analysis, and cancel/complete lifecycle.
  */
 
-import { ref, computed, watch, type Ref, type ComputedRef } from 'vue'
+import { ref, computed, watch, onUnmounted, type Ref, type ComputedRef } from 'vue'
 import { useAnalysisStore } from '@/stores/analysis'
 import { useProjectsStore } from '@/stores/projects'
 import { useNotifications } from '@/composables/useNotifications'
 import type { Entity, Alert, Chapter } from '@/types'
 import { logError } from '@/services/logger'
+import { trackAnalysisCompleted } from '@/services/telemetry'
+import { setTaskbarProgress } from '@/services/taskbarProgress'
+import { subscribeStatus } from '@/services/statusChannel'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+// En la app de escritorio el shell reenvía el stream del análisis por el canal
+// de estado; el sondeo queda como red de seguridad, con este intervalo
+const RELAYED_POLL_INTERVAL = 5000
+
+// Mínimo entre dos consultas de progreso provocadas por eventos del canal
+const RELAYED_POLL_THROTTLE = 500
 
 interface AnalysisPollingOptions {
   /** Reactive project computed */
@@ -35,6 +47,10 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
   const cancellingAnalysis = ref(false)
 
   let pollingInterval: ReturnType<typeof setInterval> | null = null
+  let unsubscribeAnalysis: (() => void) | null = null
+  let watchedProjectId: number | null = null
+  let lastRelayedPoll = 0
+  let relayedPollTimer: ReturnType<typeof setTimeout> | null = null
   let chaptersLoadedDuringAnalysis = false
   let entitiesLoadedDuringAnalysis = false
   let alertsPartialLoaded = false
@@ -54,6 +70,16 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
     return (p.chapterCount || 0) > 0 || (p.entityCount || 0) > 0
   })
 
+  // ── Taskbar / Dock ───────────────────────────────────────
+
+  /** Refleja el estado del análisis en el icono de la app */
+  function reportTaskbarProgress(status: string, progress: number) {
+    if (status === 'running') void setTaskbarProgress(progress, 'normal')
+    else if (status === 'queued' || status === 'queued_for_heavy') void setTaskbarProgress(null, 'indeterminate')
+    else if (status === 'error' || status === 'failed') void setTaskbarProgress(100, 'error')
+    else void setTaskbarProgress(null, 'none')
+  }
+
   // ── Polling ──────────────────────────────────────────────
 
   async function pollProgress() {
@@ -107,6 +133,7 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
 
       // Ajustar rate de polling según progreso (adaptive polling)
       adjustPollingRate()
+      reportTaskbarProgress(progressData.status, progressData.progress ?? 0)
 
       // Idle or pending — no active analysis in backend
       // IMPORTANT: must clear store state, otherwise cached progress persists
@@ -131,6 +158,9 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
 
         if (progressData.status === 'completed') {
           notifyAnalysisComplete(project.value?.name)
+          trackAnalysisCompleted(
+            (progressData.phases ?? []).reduce((total, phase) => total + (phase.duration ?? 0), 0) * 1000,
+          )
           // ME-01: Mark all data tabs as stale so UI can show refresh signal
           analysisStore.markTabsStale(project.value!.id)
         } else {
@@ -180,19 +210,61 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
 
   let currentInterval = 1500
 
+  /** Consulta el progreso al llegar un evento del análisis, como mucho cada RELAYED_POLL_THROTTLE */
+  function pollOnRelayedEvent() {
+    if (relayedPollTimer) return
+    const wait = Math.max(0, lastRelayedPoll + RELAYED_POLL_THROTTLE - Date.now())
+    relayedPollTimer = setTimeout(() => {
+      relayedPollTimer = null
+      lastRelayedPoll = Date.now()
+      if (pollingInterval) pollProgress()
+    }, wait)
+  }
+
+  function watchRelayedAnalysis(projectId: number) {
+    watchedProjectId = projectId
+    unsubscribeAnalysis = subscribeStatus('analysis', (event) => {
+      if (event.project_id === projectId) pollOnRelayedEvent()
+    })
+    import('@tauri-apps/api/core')
+      .then(({ invoke }) => invoke('watch_analysis', { projectId }))
+      .catch(err => logError('AnalysisPolling', 'No se pudo seguir el análisis desde el shell', err))
+  }
+
+  function unwatchRelayedAnalysis() {
+    unsubscribeAnalysis?.()
+    unsubscribeAnalysis = null
+    if (relayedPollTimer) {
+      clearTimeout(relayedPollTimer)
+      relayedPollTimer = null
+    }
+    if (watchedProjectId === null) return
+    const projectId = watchedProjectId
+    watchedProjectId = null
+    import('@tauri-apps/api/core')
+      .then(({ invoke }) => invoke('unwatch_analysis', { projectId }))
+      .catch(err => logError('AnalysisPolling', 'No se pudo dejar de seguir el análisis', err))
+  }
+
   function startPolling() {
     if (pollingInterval) return
     chaptersLoadedDuringAnalysis = false
     entitiesLoadedDuringAnalysis = false
     alertsPartialLoaded = false
     alertsFullLoaded = false
-    currentInterval = 1500
+    if (isTauriEnv && project.value) {
+      watchRelayedAnalysis(project.value.id)
+      currentInterval = RELAYED_POLL_INTERVAL
+    } else {
+      currentInterval = 1500
+    }
     pollingInterval = setInterval(pollProgress, currentInterval)
     pollProgress()
   }
 
   function adjustPollingRate() {
-    if (!pollingInterval || !project.value) return
+    // Con el canal de estado el progreso llega por eventos; el intervalo es fijo
+    if (!pollingInterval || !project.value || watchedProjectId !== null) return
 
     const currentProgress = analysisStore.currentAnalysis?.progress ?? 0
     const progress = currentProgress / 100
@@ -210,6 +282,7 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
       clearInterval(pollingInterval)
       pollingInterval = null
     }
+    unwatchRelayedAnalysis()
   }
 
   // ── Cancel ───────────────────────────────────────────────
@@ -235,6 +308,11 @@ export function useAnalysisPolling(options: AnalysisPollingOptions) {
     else stopPolling()
   }, { immediate: true })
 
+  onUnmounted(() => {
+    unwatchRelayedAnalysis()
+    void setTaskbarProgress(null, 'none')
+  })
+
   return {
     cancellingAnalysis,
     isAnalyzing,
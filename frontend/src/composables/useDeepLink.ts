@@ -0,0 +1,53 @@
+/**
+ * Composable para los enlaces narrassist:// (informes HTML exportados)
+ *
+ * - Escucha `deep-link`, que el shell envía a la ventana del proyecto si está
+ *   abierta o a la principal
+ * - Al montar recoge el enlace con el que se lanzó la app (`take_pending_deep_link`)
+ * - Navega a la pestaña de texto del proyecto, desplazándose al capítulo indicado
+ */
+import { onMounted, onUnmounted } from 'vue'
+import { useRouter } from 'vue-router'
+import { logError } from '@/services/logger'
+import { trackFeature } from '@/services/telemetry'
+
+export interface DeepLinkTarget {
+  project_id: number
+  chapter: number | null
+  url: string
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+export function useDeepLink() {
+  const router = useRouter()
+  let unlisten: (() => void) | null = null
+
+  async function openTarget(target: DeepLinkTarget) {
+    const query: Record<string, string> = { tab: 'text' }
+    if (target.chapter != null) query.scrollChapter = String(target.chapter)
+    trackFeature('deep_link.open')
+    await router.push({ name: 'project', params: { id: String(target.project_id) }, query })
+  }
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+      unlisten = await getCurrentWebviewWindow().listen<DeepLinkTarget>('deep-link', (event) => {
+        // Consumir el pendiente para no volver a abrirlo al recargar la ventana
+        void invoke('take_pending_deep_link')
+        void openTarget(event.payload)
+      })
+      const pending = await invoke<DeepLinkTarget | null>('take_pending_deep_link')
+      if (pending) await openTarget(pending)
+    } catch (err) {
+      logError('DeepLink', 'No se pudieron registrar los enlaces narrassist://', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+  })
+}
@@ -0,0 +1,55 @@
+/**
+ * Composable para guardar cada pocos segundos el texto de un editor abierto
+ * (ver services/editRecovery.ts)
+ *
+ * - `source` devuelve el editor activo o `null` si no hay ninguno abierto
+ * - Los cambios se guardan como mucho cada `SAVE_INTERVAL_MS`
+ * - `clear()` al guardar o cancelar: el texto ya no hace falta recuperarlo
+ */
+import { onUnmounted, watch } from 'vue'
+import { clearEditBuffer, saveEditBuffer, type EditBuffer } from '@/services/editRecovery'
+
+const SAVE_INTERVAL_MS = 3000
+
+export function useEditRecovery(source: () => EditBuffer | null) {
+  let timer: ReturnType<typeof setTimeout> | null = null
+  let pending: EditBuffer | null = null
+  let savedId: string | null = null
+
+  const flush = () => {
+    timer = null
+    if (!pending) return
+    savedId = pending.id
+    void saveEditBuffer(pending)
+    pending = null
+  }
+
+  const stopWatching = watch(
+    source,
+    (buffer) => {
+      if (!buffer) return
+      pending = buffer
+      if (!timer) timer = setTimeout(flush, SAVE_INTERVAL_MS)
+    },
+    { deep: true },
+  )
+
+  const clear = () => {
+    if (timer) clearTimeout(timer)
+    timer = null
+    pending = null
+    if (savedId) void clearEditBuffer(savedId)
+    savedId = null
+  }
+
+  onUnmounted(() => {
+    stopWatching()
+    // Sin guardar al desmontar: se conserva lo último escrito
+    if (timer) {
+      clearTimeout(timer)
+      flush()
+    }
+  })
+
+  return { clear, flush }
+}
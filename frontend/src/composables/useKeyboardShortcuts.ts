@@ -1,5 +1,6 @@
 import { onMounted, onUnmounted } from 'vue'
 import { useRouter } from 'vue-router'
+import { logError } from '@/services/logger'
 
 /**
  * Mapeo de números de teclado a pestañas del workspace.
@@ -29,6 +30,19 @@ const TAB_BY_NUMBER: Record<string, string> = {
  *   de UI, 'keyboard:' solo para acciones sin equivalente en menú (alertas, escape)
  * - Modo corrección secuencial tiene sus propios atajos (A/D/S/F/N/P)
  */
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** DevTools + log del shell en vivo (src-tauri/src/dev_console.rs), también en release */
+async function toggleDeveloperConsole() {
+  if (!isTauriEnv) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('toggle_developer_console')
+  } catch (err) {
+    logError('Shortcuts', 'No se pudo abrir la consola de desarrollador', err)
+  }
+}
+
 export function useKeyboardShortcuts() {
   const router = useRouter()
 
@@ -92,6 +106,12 @@ export function useKeyboardShortcuts() {
       window.dispatchEvent(new CustomEvent('menubar:export'))
     }
 
+    // Paleta de comandos (Ctrl+K)
+    else if (modifier && !shiftKey && key === 'k') {
+      event.preventDefault()
+      window.dispatchEvent(new CustomEvent('menubar:command-palette'))
+    }
+
     // Configuración
     else if (modifier && key === ',') {
       event.preventDefault()
@@ -110,6 +130,13 @@ export function useKeyboardShortcuts() {
       window.dispatchEvent(new CustomEvent('menubar:toggle-history'))
     }
 
+    // Consola de desarrollador (Ctrl+Alt+Shift+D — oculto, no va en KEYBOARD_SHORTCUTS).
+    // Por `code`: con Alt, macOS cambia la tecla ('∂', 'Î')
+    else if (modifier && shiftKey && altKey && event.code === 'KeyD') {
+      event.preventDefault()
+      void toggleDeveloperConsole()
+    }
+
     // Tema (Ctrl+Shift+D)
     else if (modifier && shiftKey && (key === 'D' || key === 'd')) {
       event.preventDefault()
@@ -182,6 +209,7 @@ export const KEYBOARD_SHORTCUTS = [
   {
     category: 'Interfaz',
     shortcuts: [
+      { keys: ['Ctrl/Cmd', 'K'], description: 'Paleta de comandos' },
       { keys: ['Ctrl/Cmd', 'B'], description: 'Mostrar/ocultar sidebar' },
       { keys: ['Ctrl/Cmd', 'Shift', 'I'], description: 'Mostrar/ocultar inspector' },
       { keys: ['Ctrl/Cmd', 'Shift', 'H'], description: 'Mostrar/ocultar historial' },
@@ -0,0 +1,72 @@
+/**
+ * Composable para los cambios externos en los manuscritos importados
+ * (ver src-tauri/src/manuscript_watch.rs)
+ *
+ * - Escucha `manuscript-changed` (el autor ha guardado el manuscrito en Word)
+ *   y avisa con un toast
+ * - `changedManuscripts` guarda la ruta modificada de cada proyecto para que la
+ *   vista del proyecto ofrezca reimportarlo
+ * - Pide al shell que relea los proyectos vigilados al cambiar de proyecto
+ */
+import { onMounted, onUnmounted, ref, watch, type Ref } from 'vue'
+import { useToast } from 'primevue/usetoast'
+import { logWarn } from '@/services/logger'
+
+interface ManuscriptChanged {
+  project_id: number
+  path: string
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Ruta modificada por proyecto, hasta que se reimporta o se descarta */
+export const changedManuscripts = ref<Record<number, string>>({})
+
+export function dismissManuscriptChange(projectId: number) {
+  const { [projectId]: _, ...rest } = changedManuscripts.value
+  changedManuscripts.value = rest
+}
+
+/** Relee los proyectos vigilados (tras crear, importar o reemplazar un manuscrito) */
+export async function refreshManuscriptWatch() {
+  if (!isTauriEnv) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('refresh_manuscript_watch')
+  } catch (err) {
+    logWarn('ManuscriptWatch', 'No se pudo actualizar la vigilancia de manuscritos', err)
+  }
+}
+
+export function useManuscriptWatch(currentProjectId: Ref<number | null>) {
+  const toast = useToast()
+  let unlisten: (() => void) | null = null
+
+  watch(currentProjectId, () => void refreshManuscriptWatch())
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+      unlisten = await getCurrentWebviewWindow().listen<ManuscriptChanged>('manuscript-changed', (event) => {
+        const { project_id: projectId, path } = event.payload
+        const alreadyNotified = projectId in changedManuscripts.value
+        changedManuscripts.value = { ...changedManuscripts.value, [projectId]: path }
+        if (alreadyNotified) return
+        const name = path.split(/[\\/]/).pop() || path
+        toast.add({
+          severity: 'info',
+          summary: 'Manuscrito modificado',
+          detail: `"${name}" ha cambiado fuera de la aplicación. Reimpórtalo desde el proyecto para actualizar el análisis.`,
+          life: 8000,
+        })
+      })
+    } catch (err) {
+      logWarn('ManuscriptWatch', 'No se pudo escuchar manuscript-changed', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+  })
+}
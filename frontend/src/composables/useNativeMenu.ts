@@ -4,6 +4,7 @@
 import { onMounted, onUnmounted, ref } from 'vue'
 import { useRouter } from 'vue-router'
 import { logWarn } from '@/services/logger'
+import { trackFeature } from '@/services/telemetry'
 
 // Variable para guardar la función listen de Tauri
 let tauriListen: ((event: string, handler: (event: { payload: string }) => void) => Promise<() => void>) | null = null
@@ -18,8 +19,10 @@ const tauriReady = ref(false)
 const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
 if (isTauriEnv) {
   const importWithTimeout = Promise.race([
-    import('@tauri-apps/api/event').then(module => {
-      tauriListen = module.listen as typeof tauriListen
+    // Escuchar en la ventana actual: el shell envía los eventos del menú solo a la ventana activa
+    import('@tauri-apps/api/webviewWindow').then(module => {
+      const currentWindow = module.getCurrentWebviewWindow()
+      tauriListen = currentWindow.listen.bind(currentWindow) as typeof tauriListen
       tauriReady.value = true
     }),
     new Promise<void>((_, reject) =>
@@ -68,6 +71,7 @@ export function useNativeMenu(handlers: MenuEventHandlers = {}) {
   }
 
   const handleMenuEvent = async (eventId: string) => {
+    trackFeature(`menu.${eventId}`)
     switch (eventId) {
       // Archivo
       case 'new_project':
@@ -171,6 +175,10 @@ export function useNativeMenu(handlers: MenuEventHandlers = {}) {
         window.dispatchEvent(new CustomEvent('menubar:toggle-theme'))
         break
 
+      case 'command_palette':
+        window.dispatchEvent(new CustomEvent('menubar:command-palette'))
+        break
+
       case 'find':
         window.dispatchEvent(new CustomEvent('menubar:find'))
         break
@@ -201,6 +209,10 @@ export function useNativeMenu(handlers: MenuEventHandlers = {}) {
         invoke('manage_data', handlers.onManageData)
         break
 
+      case 'report_problem':
+        window.dispatchEvent(new CustomEvent('menubar:report-problem'))
+        break
+
       case 'check_updates':
         invoke('check_updates', handlers.onCheckUpdates)
         break
@@ -0,0 +1,81 @@
+/**
+ * Composable para la conectividad a internet (monitor del shell, ver src-tauri/src/network.rs)
+ *
+ * - `isOnline` se comparte entre componentes; fuera de Tauri sigue a `navigator.onLine`
+ * - `ensureOnline()` comprueba la conexión en el momento, antes de una descarga
+ * - Con `notify`, avisa con un toast al perder y recuperar la conexión (una vez, en App.vue)
+ */
+import { onMounted, onUnmounted, readonly, ref } from 'vue'
+import { useToast } from 'primevue/usetoast'
+import { logError } from '@/services/logger'
+
+interface NetworkStatus {
+  online: boolean
+  checked_at: number
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+const isOnline = ref(typeof navigator === 'undefined' ? true : navigator.onLine)
+
+/** Comprueba la conexión ahora. Devuelve si hay salida a internet. */
+export async function ensureOnline(): Promise<boolean> {
+  if (!isTauriEnv) return isOnline.value
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const status = await invoke<NetworkStatus>('check_connectivity')
+    isOnline.value = status.online
+  } catch (err) {
+    logError('Network', 'Error comprobando la conexión', err)
+  }
+  return isOnline.value
+}
+
+export function useNetworkStatus(options: { notify?: boolean } = {}) {
+  const toast = useToast()
+  let unlisten: (() => void) | null = null
+
+  function update(online: boolean) {
+    if (online === isOnline.value) return
+    isOnline.value = online
+    if (!options.notify) return
+    if (online) {
+      toast.add({ severity: 'success', summary: 'Conexión recuperada', life: 3000 })
+    } else {
+      toast.add({
+        severity: 'warn',
+        summary: 'Sin conexión a internet',
+        detail: 'Las descargas de modelos y las actualizaciones esperarán a que vuelva la conexión.',
+        life: 6000,
+      })
+    }
+  }
+
+  const onBrowserOnline = () => update(true)
+  const onBrowserOffline = () => update(false)
+
+  onMounted(async () => {
+    if (!isTauriEnv) {
+      window.addEventListener('online', onBrowserOnline)
+      window.addEventListener('offline', onBrowserOffline)
+      return
+    }
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { listen } = await import('@tauri-apps/api/event')
+      unlisten = await listen<NetworkStatus>('network-status', (event) => update(event.payload.online))
+      const status = await invoke<NetworkStatus | null>('get_network_status')
+      if (status) isOnline.value = status.online
+    } catch (err) {
+      logError('Network', 'No se pudo registrar el estado de la red', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+    window.removeEventListener('online', onBrowserOnline)
+    window.removeEventListener('offline', onBrowserOffline)
+  })
+
+  return { isOnline: readonly(isOnline), ensureOnline }
+}
@@ -11,7 +11,7 @@ import { ref, computed } from 'vue'
 import { api } from '@/services/apiClient'
 import { useToast } from 'primevue/usetoast'
 import { useSystemStore } from '@/stores/system'
-import { logError } from '@/services/logger'
+import { logError, logWarn } from '@/services/logger'
 
 // ── Types ──────────────────────────────────────────────────
 
@@ -23,6 +23,8 @@ const CONFIGURING_STATUSES = new Set(['installing', 'starting', 'downloading_mod
 /** HI-01: Max poll iterations for model download (1 iteration ≈ 1s) */
 export const MODEL_DOWNLOAD_POLL_MAX_ITERATIONS = 900  // 15 minutes
 
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
 export function isAlreadyDownloadingError(errorMessage: string | null | undefined): boolean {
   return typeof errorMessage === 'string' && errorMessage.toLowerCase().includes('descarga en curso')
 }
@@ -144,6 +146,12 @@ export function useOllamaManagement() {
   async function installOllama() {
     ollamaStarting.value = true
     try {
+      // En la app el shell descarga, verifica y lanza el instalador oficial (src-tauri/src/ollama_install.rs)
+      if (isTauriEnv) {
+        await installWithShell()
+        return
+      }
+
       const result = await api.postRaw<{ success: boolean }>('/api/ollama/install')
       if (result.success) {
         toast.add({ severity: 'info', summary: 'Instalando', detail: 'Descargando e instalando el analizador...', life: 5000 })
@@ -162,11 +170,41 @@ export function useOllamaManagement() {
     }
   }
 
+  async function installWithShell() {
+    const { invoke } = await import('@tauri-apps/api/core')
+    type InstallOutcome =
+      | { outcome: 'installed' }
+      | { outcome: 'manual'; commands: string[]; url: string }
+    toast.add({ severity: 'info', summary: 'Instalando', detail: 'Descargando el instalador del analizador...', life: 5000 })
+    try {
+      const result = await invoke<InstallOutcome>('install_ollama')
+      if (result.outcome === 'manual') {
+        toast.add({ severity: 'info', summary: 'Instalación manual', detail: `Ejecuta en una terminal: ${result.commands.join('  o  ')}`, life: 15000 })
+        return
+      }
+      await reloadCapabilities()
+      toast.add({ severity: 'success', summary: 'Instalado', detail: 'El analizador se ha instalado correctamente', life: 3000 })
+    } catch (error) {
+      logError('OllamaManagement', 'Error installing Ollama', error)
+      const detail = typeof error === 'string' ? error : 'No se pudo instalar el analizador'
+      toast.add({ severity: 'error', summary: 'Error al instalar', detail, life: 5000 })
+    }
+  }
+
   // ── Start ───────────────────────────────────────────────
 
   async function startOllama() {
     ollamaStarting.value = true
     try {
+      // En la app el shell lanza `ollama serve` y espera a que responda (src-tauri/src/ollama.rs)
+      if (isTauriEnv) {
+        const { invoke } = await import('@tauri-apps/api/core')
+        await invoke('start_ollama')
+        await reloadCapabilities()
+        toast.add({ severity: 'success', summary: 'Analizador iniciado', detail: 'El análisis inteligente está disponible', life: 3000 })
+        return
+      }
+
       const result = await api.postRaw<{ success: boolean; data?: any; error?: string }>('/api/ollama/start')
 
       if (result.success) {
@@ -187,7 +225,8 @@ export function useOllamaManagement() {
       }
     } catch (error) {
       logError('OllamaManagement', 'Error starting Ollama', error)
-      toast.add({ severity: 'error', summary: 'Error', detail: 'No se pudo iniciar el analizador', life: 3000 })
+      const detail = isTauriEnv && typeof error === 'string' ? error : 'No se pudo iniciar el analizador'
+      toast.add({ severity: 'error', summary: 'Error', detail, life: 3000 })
     } finally {
       ollamaStarting.value = false
     }
@@ -247,6 +286,9 @@ export function useOllamaManagement() {
       life: 5000,
     })
 
+    // En la app el shell descarga directamente de Ollama y emite el progreso (src-tauri/src/ollama.rs)
+    if (isTauriEnv) return pullWithShell(normalized)
+
     try {
       const result = await api.postRaw<{ success: boolean; error?: string }>(`/api/ollama/pull/${normalized}`)
       if (!result.success) {
@@ -312,6 +354,40 @@ export function useOllamaManagement() {
     }
   }
 
+  async function pullWithShell(normalized: string): Promise<boolean> {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const { listen } = await import('@tauri-apps/api/event')
+    const unlisten = await listen<{ model: string; status: string; percent: number | null }>('ollama-pull-progress', (event) => {
+      if (event.payload.model !== normalized) return
+      ollamaDownloadProgress.value = { percentage: event.payload.percent ?? 0, status: event.payload.status }
+    })
+    try {
+      await invoke('pull_ollama_model', { name: normalized })
+      await reloadCapabilities()
+      toast.add({ severity: 'success', summary: 'Motor instalado', detail: 'Motor de análisis disponible', life: 3000 })
+      return true
+    } catch (error) {
+      if (error !== 'Descarga cancelada') {
+        logError('OllamaManagement', `Error downloading ${normalized}`, error)
+        const detail = typeof error === 'string' ? error : 'No se pudo descargar el motor de análisis'
+        toast.add({ severity: 'error', summary: 'Error', detail, life: 5000 })
+      }
+      return false
+    } finally {
+      unlisten()
+      ollamaDownloadProgress.value = null
+      modelDownloading.value = false
+      delete modelOperations.value[normalized]
+    }
+  }
+
+  /** Cancela la descarga en curso de un modelo (solo en la app) */
+  async function cancelModelDownload(modelName: string): Promise<boolean> {
+    if (!isTauriEnv) return false
+    const { invoke } = await import('@tauri-apps/api/core')
+    return invoke<boolean>('cancel_ollama_pull', { name: modelName.split(':')[0] })
+  }
+
   async function installModel(modelName: string): Promise<boolean> {
     return downloadModel(modelName)
   }
@@ -320,6 +396,7 @@ export function useOllamaManagement() {
     const normalized = modelName.split(':')[0]
     modelOperations.value[normalized] = 'uninstalling'
     try {
+      if (isTauriEnv) return await uninstallWithShell(normalized)
       const result = await api.del<{ success: boolean; error?: string; data?: { remaining_models?: string[] } }>(`/api/ollama/model/${normalized}`)
       if (!result.success) {
         toast.add({
@@ -352,6 +429,27 @@ export function useOllamaManagement() {
     }
   }
 
+  /** Borra el modelo con la API de Ollama desde el shell (sin pasar por el backend) */
+  async function uninstallWithShell(normalized: string): Promise<boolean> {
+    const { invoke } = await import('@tauri-apps/api/core')
+    try {
+      await invoke('delete_ollama_model', { name: normalized })
+    } catch (error) {
+      logWarn('OllamaManagement', `Error deleting ${normalized}`, error)
+      const detail = typeof error === 'string' ? error : `No se pudo desinstalar ${normalized}`
+      toast.add({ severity: 'warn', summary: 'No se pudo desinstalar', detail, life: 4500 })
+      return false
+    }
+    await reloadCapabilities()
+    toast.add({
+      severity: 'success',
+      summary: 'Motor desinstalado',
+      detail: 'Motor de análisis eliminado correctamente',
+      life: 3000,
+    })
+    return true
+  }
+
   function isModelBusy(modelName: string): boolean {
     const normalized = modelName.split(':')[0]
     return Boolean(modelOperations.value[normalized])
@@ -390,6 +488,7 @@ export function useOllamaManagement() {
     uninstallModel,
     downloadModel,
     downloadDefaultModel,
+    cancelModelDownload,
     cleanup,
   }
 }
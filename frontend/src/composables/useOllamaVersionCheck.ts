@@ -0,0 +1,75 @@
+/**
+ * Composable para el aviso de Ollama antiguo (ver `check_version` en src-tauri/src/ollama.rs)
+ *
+ * El shell comprueba la versión de Ollama al arrancar y emite
+ * `ollama-version-warning` si es anterior a la mínima. Se ofrece actualizarlo
+ * con el instalador oficial (`install_ollama` con `update`) o, donde no lo hay,
+ * los comandos para hacerlo desde una terminal. Se usa una vez, en App.vue.
+ */
+import { onMounted, onUnmounted } from 'vue'
+import { useToast } from 'primevue/usetoast'
+import { useAppConfirm } from '@/composables/useAppConfirm'
+import { logError } from '@/services/logger'
+
+interface OllamaVersionWarning {
+  version: string
+  min_version: string
+  action: 'update_ollama'
+  can_update: boolean
+}
+
+type InstallOutcome =
+  | { outcome: 'installed' }
+  | { outcome: 'manual'; commands: string[]; url: string }
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+export function useOllamaVersionCheck() {
+  const toast = useToast()
+  const { ask } = useAppConfirm()
+  let unlisten: (() => void) | null = null
+
+  async function updateOllama() {
+    const { invoke } = await import('@tauri-apps/api/core')
+    toast.add({ severity: 'info', summary: 'Actualizando', detail: 'Descargando la última versión del analizador...', life: 5000 })
+    try {
+      const result = await invoke<InstallOutcome>('install_ollama', { update: true })
+      if (result.outcome === 'manual') {
+        toast.add({ severity: 'info', summary: 'Actualización manual', detail: `Ejecuta en una terminal: ${result.commands.join('  o  ')}`, life: 15000 })
+        return
+      }
+      toast.add({ severity: 'success', summary: 'Analizador actualizado', life: 3000 })
+    } catch (err) {
+      logError('OllamaVersionCheck', 'Error updating Ollama', err)
+      toast.add({ severity: 'error', summary: 'Error al actualizar', detail: String(err), life: 6000 })
+    }
+  }
+
+  async function onWarning(warning: OllamaVersionWarning) {
+    const accepted = await ask({
+      header: 'Analizador desactualizado',
+      message: `El analizador instalado (Ollama ${warning.version}) es anterior a la versión ${warning.min_version}, `
+        + 'necesaria para las funciones de inteligencia artificial. Sin actualizarlo, las sugerencias fallarán.',
+      icon: 'pi pi-exclamation-triangle',
+      acceptLabel: warning.can_update ? 'Actualizar' : 'Ver cómo actualizar',
+      rejectLabel: 'Más tarde',
+    })
+    if (accepted) await updateOllama()
+  }
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { listen } = await import('@tauri-apps/api/event')
+      unlisten = await listen<OllamaVersionWarning>('ollama-version-warning', (event) => {
+        void onWarning(event.payload)
+      })
+    } catch (err) {
+      logError('OllamaVersionCheck', 'No se pudo registrar el aviso de versión', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+  })
+}
@@ -0,0 +1,134 @@
+/**
+ * Composable para los archivos abiertos desde el explorador (asociación de archivos)
+ *
+ * - Escucha `open-file` (doble clic en un `.nra` o "Abrir con" en un `.docx`)
+ * - Al montar recoge los archivos con los que se lanzó la app (`take_pending_open_files`)
+ * - Los `.nra` se abren como proyecto; los `.docx` crean un proyecto nuevo tras confirmarlo
+ * - Escucha `import-file` / `import-file-rejected` (manuscrito soltado sobre la
+ *   ventana, ya copiado por el shell; ver src-tauri/src/drop_import.rs)
+ */
+import { onMounted, onUnmounted } from 'vue'
+import { useRouter } from 'vue-router'
+import { useToast } from 'primevue/usetoast'
+import { useAppConfirm } from './useAppConfirm'
+import { useProjectFile } from './useProjectFile'
+import { useProjectsStore } from '@/stores/projects'
+import { useAnalysisStore } from '@/stores/analysis'
+import { logError } from '@/services/logger'
+import { trackFeature } from '@/services/telemetry'
+
+export interface OpenFileRequest {
+  path: string
+  kind: 'project' | 'manuscript'
+}
+
+interface StagedImport {
+  path: string
+  original_path: string
+  name: string
+  size_bytes: number
+}
+
+interface RejectedImport {
+  name: string
+  reason: string
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Nombre del archivo sin directorio ni extensión */
+function fileStem(path: string): string {
+  const name = path.split(/[\\/]/).pop() || path
+  return name.replace(/\.[^.]+$/, '')
+}
+
+export function useOpenWith() {
+  const router = useRouter()
+  const toast = useToast()
+  const { ask } = useAppConfirm()
+  const { openProjectPath } = useProjectFile()
+  const projectsStore = useProjectsStore()
+  const analysisStore = useAnalysisStore()
+
+  const unlisteners: (() => void)[] = []
+
+  async function openProject(path: string) {
+    const result = await openProjectPath(path)
+    if (!result) return
+    toast.add({ severity: 'success', summary: 'Abierto', detail: `Proyecto "${result.projectName}" abierto`, life: 3000 })
+    for (const warning of result.warnings) {
+      toast.add({ severity: 'warn', summary: 'Aviso', detail: warning, life: 8000 })
+    }
+    await router.push({ name: 'project', params: { id: result.projectId } })
+  }
+
+  /** `displayPath`: ruta que da nombre al proyecto si `path` es una copia */
+  async function createFromManuscript(path: string, displayPath: string = path) {
+    const name = fileStem(displayPath)
+    const accepted = await ask({
+      header: 'Nuevo proyecto',
+      message: `¿Crear un proyecto con el manuscrito "${name}" y analizarlo?`,
+      icon: 'pi pi-file-word',
+      acceptLabel: 'Crear proyecto',
+      rejectLabel: 'Cancelar',
+    })
+    if (!accepted) return
+    const project = await projectsStore.createProject(name, undefined, undefined, path)
+    await router.push({ name: 'project', params: { id: project.id } })
+    analysisStore.startAnalysis(project.id).catch((err) => {
+      logError('OpenWith', 'Error iniciando el análisis', err)
+    })
+  }
+
+  async function handle(requests: OpenFileRequest[]) {
+    for (const request of requests) {
+      trackFeature(`open_with.${request.kind}`)
+      try {
+        if (request.kind === 'project') {
+          await openProject(request.path)
+        } else {
+          await createFromManuscript(request.path)
+        }
+      } catch (err) {
+        logError('OpenWith', `Error abriendo ${request.path}`, err)
+        toast.add({ severity: 'error', summary: 'Error', detail: `Error al abrir: ${err}`, life: 5000 })
+      }
+    }
+  }
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+      const webview = getCurrentWebviewWindow()
+      unlisteners.push(await webview.listen<OpenFileRequest[]>('open-file', (event) => {
+        // Consumir los pendientes para no volver a abrirlos al recargar la ventana
+        void invoke('take_pending_open_files')
+        void handle(event.payload)
+      }))
+      unlisteners.push(await webview.listen<StagedImport>('import-file', (event) => {
+        trackFeature('drop_import.manuscript')
+        createFromManuscript(event.payload.path, event.payload.name).catch((err) => {
+          logError('OpenWith', `Error importando ${event.payload.name}`, err)
+          toast.add({ severity: 'error', summary: 'Error', detail: `Error al importar: ${err}`, life: 5000 })
+        })
+      }))
+      unlisteners.push(await webview.listen<RejectedImport>('import-file-rejected', (event) => {
+        toast.add({
+          severity: 'warn',
+          summary: `No se puede importar "${event.payload.name}"`,
+          detail: event.payload.reason,
+          life: 6000,
+        })
+      }))
+      await handle(await invoke<OpenFileRequest[]>('take_pending_open_files'))
+    } catch (err) {
+      logError('OpenWith', 'No se pudieron registrar los archivos asociados', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisteners.forEach(unlisten => unlisten())
+  })
+}
@@ -0,0 +1,75 @@
+/**
+ * Composable para los comandos de complementos (ver src-tauri/src/plugins.rs)
+ *
+ * - Escucha `plugin-command`, que el shell envía a la ventana activa al pulsar un
+ *   elemento del submenú Complementos o elegirlo en la paleta de comandos
+ * - Lo ejecuta con `invoke_plugin_command`, pasando el proyecto abierto
+ * - Avisa con un toast del resultado (`data.message` si el complemento lo da)
+ */
+import { onMounted, onUnmounted } from 'vue'
+import { useRoute } from 'vue-router'
+import { useToast } from 'primevue/usetoast'
+import { logError } from '@/services/logger'
+import { trackFeature } from '@/services/telemetry'
+
+interface PluginCommandRequest {
+  plugin_id: string
+  command_id: string
+  label: string
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+export function usePluginCommands() {
+  const route = useRoute()
+  const toast = useToast()
+  let unlisten: (() => void) | null = null
+
+  function currentProjectId(): number | null {
+    const raw = route.params.projectId ?? route.params.id
+    const id = route.name === 'project' || route.params.projectId ? Number(raw) : NaN
+    return Number.isInteger(id) && id > 0 ? id : null
+  }
+
+  async function run(request: PluginCommandRequest) {
+    trackFeature('plugin.command')
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const data = await invoke<{ message?: string } | null>('invoke_plugin_command', {
+        pluginId: request.plugin_id,
+        commandId: request.command_id,
+        payload: { project_id: currentProjectId() },
+      })
+      toast.add({
+        severity: 'success',
+        summary: request.label,
+        detail: data?.message ?? 'Completado',
+        life: 4000,
+      })
+    } catch (err) {
+      logError('Plugins', `Error en ${request.plugin_id}/${request.command_id}`, err)
+      toast.add({
+        severity: 'error',
+        summary: request.label,
+        detail: String(err),
+        life: 6000,
+      })
+    }
+  }
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+      unlisten = await getCurrentWebviewWindow().listen<PluginCommandRequest>('plugin-command', (event) => {
+        void run(event.payload)
+      })
+    } catch (err) {
+      logError('Plugins', 'No se pudieron registrar los comandos de complementos', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+  })
+}
@@ -1,9 +1,8 @@
 import { afterEach, beforeEach, describe, expect, it, vi } from 'vitest'
 
-const { apiPostMock, dialogSaveMock, dialogOpenMock } = vi.hoisted(() => ({
+const { apiPostMock, invokeMock } = vi.hoisted(() => ({
   apiPostMock: vi.fn(),
-  dialogSaveMock: vi.fn(),
-  dialogOpenMock: vi.fn(),
+  invokeMock: vi.fn(),
 }))
 
 vi.mock('@/services/apiClient', () => ({
@@ -12,9 +11,8 @@ vi.mock('@/services/apiClient', () => ({
   },
 }))
 
-vi.mock('@tauri-apps/plugin-dialog', () => ({
-  save: dialogSaveMock,
-  open: dialogOpenMock,
+vi.mock('@tauri-apps/api/core', () => ({
+  invoke: invokeMock,
 }))
 
 describe('useProjectFile', () => {
@@ -38,14 +36,15 @@ describe('useProjectFile', () => {
   }
 
   it('saveProject sanitizes the default filename and posts the selected path', async () => {
-    dialogSaveMock.mockResolvedValueOnce('D:/Exports/proyecto.nra')
+    invokeMock.mockResolvedValueOnce('D:/Exports/proyecto.nra')
     apiPostMock.mockResolvedValueOnce({ path: 'D:/Exports/proyecto.nra', size_bytes: 1024 })
 
     const { saveProject, saving } = await loadComposableInTauri()
     const result = await saveProject(7, 'Mi:Proyecto/Con*Caracteres?')
 
     expect(result).toBe(true)
-    expect(dialogSaveMock).toHaveBeenCalledWith(expect.objectContaining({
+    // El diálogo lo abre el shell, que concede la carpeta elegida
+    expect(invokeMock).toHaveBeenCalledWith('pick_export_path', expect.objectContaining({
       title: 'Guardar proyecto',
       defaultPath: 'Mi_Proyecto_Con_Caracteres_.nra',
     }))
@@ -56,7 +55,7 @@ describe('useProjectFile', () => {
   })
 
   it('saveProject returns false when the user cancels the dialog', async () => {
-    dialogSaveMock.mockResolvedValueOnce(null)
+    invokeMock.mockResolvedValueOnce(null)
 
     const { saveProject } = await loadComposableInTauri()
     const result = await saveProject(7, 'Proyecto')
@@ -66,7 +65,7 @@ describe('useProjectFile', () => {
   })
 
   it('openProjectFile normalizes imported project data and warnings', async () => {
-    dialogOpenMock.mockResolvedValueOnce('D:/Imports/proyecto.nra')
+    invokeMock.mockResolvedValueOnce(['D:/Imports/proyecto.nra'])
     apiPostMock.mockResolvedValueOnce({
       project_id: 15,
       project_name: null,
@@ -76,11 +75,7 @@ describe('useProjectFile', () => {
     const { openProjectFile, opening } = await loadComposableInTauri()
     const result = await openProjectFile()
 
-    expect(dialogOpenMock).toHaveBeenCalledWith(expect.objectContaining({
-      title: 'Abrir proyecto',
-      multiple: false,
-      directory: false,
-    }))
+    expect(invokeMock).toHaveBeenCalledWith('pick_project', { multiple: false })
     expect(apiPostMock).toHaveBeenCalledWith('/api/projects/open-file', {
       file_path: 'D:/Imports/proyecto.nra',
     })
@@ -92,6 +87,31 @@ describe('useProjectFile', () => {
     expect(opening.value).toBe(false)
   })
 
+  it('openProjectPath imports .nazip archives through the shell', async () => {
+    invokeMock.mockResolvedValueOnce({
+      project_id: 21,
+      project_name: 'El faro',
+      document_path: '/datos/documents/abc_el_faro.docx',
+      warnings: [],
+    })
+
+    const { openProjectPath } = await loadComposableInTauri()
+    const result = await openProjectPath('D:/Imports/El_faro.NAZIP')
+
+    expect(invokeMock).toHaveBeenCalledWith('import_project_archive', { path: 'D:/Imports/El_faro.NAZIP' })
+    expect(apiPostMock).not.toHaveBeenCalled()
+    expect(result).toEqual({ projectId: 21, projectName: 'El faro', warnings: [] })
+  })
+
+  it('openProjectFile returns null when the user cancels the dialog', async () => {
+    invokeMock.mockResolvedValueOnce([])
+
+    const { openProjectFile } = await loadComposableInTauri()
+
+    expect(await openProjectFile()).toBeNull()
+    expect(apiPostMock).not.toHaveBeenCalled()
+  })
+
   it('throws a desktop-only error outside Tauri', async () => {
     const { useProjectFile } = await import('./useProjectFile')
     const { saveProject, openProjectFile } = useProjectFile()
@@ -1,42 +1,48 @@
 /**
  * Composable para Guardar/Abrir archivos de proyecto .nra
  *
- * Usa el diálogo nativo de Tauri para seleccionar archivos y
- * llama a los endpoints del backend para exportar/importar.
+ * Usa los diálogos del shell para seleccionar archivos y llama a los
+ * endpoints del backend para exportar/importar. Para abrir se usa
+ * `pick_project`, que recuerda la última carpeta; para guardar,
+ * `pickExportPath`, que concede la carpeta de destino (export_scope.rs).
+ *
+ * Los archivos `.nazip` (el `.nra` con el manuscrito, para pasar proyectos
+ * entre máquinas) los empaqueta y abre el shell: src-tauri/src/project_archive.rs.
  */
 import { ref } from 'vue'
 import { api } from '@/services/apiClient'
-import { logWarn } from '@/services/logger'
+import { pickExportPath } from '@/services/exportPath'
 
 const saving = ref(false)
 const opening = ref(false)
 
-/** Tauri dialog API — cargado dinámicamente */
-let dialogModule: {
-  save: (opts: Record<string, unknown>) => Promise<string | null>
-  open: (opts: Record<string, unknown>) => Promise<string | null>
-} | null = null
-
 const isTauriEnv =
   typeof window !== 'undefined' &&
   ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
 
-/** Promise que resuelve cuando el plugin de diálogo está listo (o falla) */
-const dialogReady: Promise<void> = isTauriEnv
-  ? import('@tauri-apps/plugin-dialog')
-      .then((mod) => {
-        dialogModule = mod as unknown as typeof dialogModule
-      })
-      .catch((err) => {
-        logWarn('ProjectFile', 'Failed to load dialog plugin', err)
-      })
-  : Promise.resolve()
-
 const NRA_FILTER = {
   name: 'Proyecto Narrative Assistant',
   extensions: ['nra'],
 }
 
+const NAZIP_FILTER = {
+  name: 'Archivo de proyecto Narrative Assistant',
+  extensions: ['nazip'],
+}
+
+interface ArchiveExportResult {
+  path: string
+  size_bytes: number
+  includes_document: boolean
+}
+
+interface ArchiveImportResult {
+  project_id: number
+  project_name: string | null
+  document_path: string | null
+  warnings: string[]
+}
+
 interface SaveResult {
   path: string
   size_bytes: number
@@ -55,14 +61,11 @@ export function useProjectFile() {
   async function saveProject(projectId: number, projectName: string): Promise<boolean> {
     if (saving.value) return false
 
-    // Esperar a que el plugin de diálogo cargue (evita race condition)
-    await dialogReady
-
-    if (!dialogModule) {
+    if (!isTauriEnv) {
       throw new Error('Guardar proyectos como archivo solo esta disponible en la app de escritorio.')
     }
 
-    const filePath = await dialogModule.save({
+    const filePath = await pickExportPath({
       title: 'Guardar proyecto',
       defaultPath: `${sanitizeFileName(projectName)}.nra`,
       filters: [NRA_FILTER],
@@ -82,6 +85,34 @@ export function useProjectFile() {
     }
   }
 
+  /**
+   * Exporta el proyecto con su manuscrito como archivo .nazip
+   * @returns El resultado, o null si se canceló
+   */
+  async function exportArchive(projectId: number, projectName: string): Promise<ArchiveExportResult | null> {
+    if (saving.value) return null
+
+    if (!isTauriEnv) {
+      throw new Error('Exportar archivos de proyecto solo esta disponible en la app de escritorio.')
+    }
+
+    const filePath = await pickExportPath({
+      title: 'Exportar archivo de proyecto',
+      defaultPath: `${sanitizeFileName(projectName)}.nazip`,
+      filters: [NAZIP_FILTER],
+    })
+
+    if (!filePath) return null // Usuario canceló
+
+    saving.value = true
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      return await invoke<ArchiveExportResult>('export_project_archive', { projectId, path: filePath })
+    } finally {
+      saving.value = false
+    }
+  }
+
   /**
    * Abre un proyecto desde un archivo .nra
    * @returns El project_id del proyecto importado, o null si se canceló/falló
@@ -93,24 +124,40 @@ export function useProjectFile() {
   } | null> {
     if (opening.value) return null
 
-    // Esperar a que el plugin de diálogo cargue (evita race condition)
-    await dialogReady
-
-    if (!dialogModule) {
+    if (!isTauriEnv) {
       throw new Error('Abrir proyectos desde archivo solo esta disponible en la app de escritorio.')
     }
 
-    const filePath = (await dialogModule.open({
-      title: 'Abrir proyecto',
-      filters: [NRA_FILTER],
-      multiple: false,
-      directory: false,
-    })) as string | null
+    const { invoke } = await import('@tauri-apps/api/core')
+    const [filePath] = await invoke<string[]>('pick_project', { multiple: false })
 
     if (!filePath) return null // Usuario canceló
 
+    return openProjectPath(filePath)
+  }
+
+  /**
+   * Abre un proyecto .nra o .nazip a partir de su ruta (diálogo o asociación de archivos)
+   */
+  async function openProjectPath(filePath: string): Promise<{
+    projectId: number
+    projectName: string
+    warnings: string[]
+  } | null> {
+    if (opening.value) return null
+
     opening.value = true
     try {
+      if (filePath.toLowerCase().endsWith('.nazip')) {
+        const { invoke } = await import('@tauri-apps/api/core')
+        const imported = await invoke<ArchiveImportResult>('import_project_archive', { path: filePath })
+        return {
+          projectId: imported.project_id,
+          projectName: imported.project_name || 'Proyecto importado',
+          warnings: imported.warnings || [],
+        }
+      }
+
       const data = await api.post<OpenResult>(
         '/api/projects/open-file',
         { file_path: filePath },
@@ -130,7 +177,9 @@ export function useProjectFile() {
     saving,
     opening,
     saveProject,
+    exportArchive,
     openProjectFile,
+    openProjectPath,
   }
 }
 
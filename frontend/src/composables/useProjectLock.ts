@@ -0,0 +1,137 @@
+/**
+ * Composable para el bloqueo del proyecto abierto (ver src-tauri/src/project_lock.rs)
+ *
+ * - Al entrar en un proyecto pide el bloqueo; si otra instancia (otro equipo
+ *   con la base de datos sincronizada, u otra copia de la app) lo tiene, pregunta
+ *   si abrirlo en solo lectura o quitarle el bloqueo
+ * - En solo lectura el cliente de la API rechaza las peticiones que modifican
+ *   el proyecto (`setReadOnlyProject`)
+ * - Si otra instancia fuerza el bloqueo (`project-lock-lost`) pasa a solo lectura
+ */
+import { onMounted, onUnmounted, ref, watch, type Ref } from 'vue'
+import { useConfirm } from 'primevue/useconfirm'
+import { useToast } from 'primevue/usetoast'
+import { setReadOnlyProject } from '@/services/apiClient'
+import { logWarn } from '@/services/logger'
+
+export interface LockInfo {
+  pid: number
+  hostname: string
+  instance: string
+  opened_at: number
+  heartbeat_at: number
+}
+
+interface ProjectLockStatus {
+  acquired: boolean
+  holder: LockInfo | null
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+function describeHolder(holder: LockInfo) {
+  const since = new Date(holder.opened_at * 1000).toLocaleString()
+  return `${holder.hostname} (proceso ${holder.pid}) desde ${since}`
+}
+
+export function useProjectLock(projectId: Ref<number | null>) {
+  const confirm = useConfirm()
+  const toast = useToast()
+  /** Quien tiene el proyecto si esta ventana lo ha abierto en solo lectura */
+  const lockedBy = ref<LockInfo | null>(null)
+  let heldProjectId: number | null = null
+  let unlisten: (() => void) | null = null
+
+  const setReadOnly = (id: number, holder: LockInfo | null) => {
+    lockedBy.value = holder
+    setReadOnlyProject(holder ? id : null)
+  }
+
+  const release = async () => {
+    const id = heldProjectId
+    heldProjectId = null
+    setReadOnlyProject(null)
+    lockedBy.value = null
+    if (id === null || !isTauriEnv) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      await invoke('release_project_lock', { projectId: id })
+    } catch (err) {
+      logWarn('ProjectLock', 'No se pudo soltar el bloqueo del proyecto', err)
+    }
+  }
+
+  const acquire = async (id: number, force = false) => {
+    if (!isTauriEnv) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const status = await invoke<ProjectLockStatus>('acquire_project_lock', { projectId: id, force })
+      if (projectId.value !== id) return
+      if (status.acquired) {
+        heldProjectId = id
+        setReadOnly(id, null)
+        return
+      }
+      const holder = status.holder!
+      setReadOnly(id, holder)
+      confirm.require({
+        header: 'Proyecto abierto en otro equipo',
+        message: `Este proyecto está abierto en ${describeHolder(holder)}. Si lo editáis a la vez, las revisiones de uno pueden perderse.`,
+        icon: 'pi pi-lock',
+        acceptLabel: 'Abrir en solo lectura',
+        rejectLabel: 'Abrir igualmente',
+        rejectClass: 'p-button-danger p-button-outlined',
+        accept: () => {},
+        reject: () => void takeOver(),
+      })
+    } catch (err) {
+      // Sin bloqueo no se impide trabajar
+      logWarn('ProjectLock', 'No se pudo bloquear el proyecto', err)
+    }
+  }
+
+  /** Quita el bloqueo a la otra instancia y permite editar */
+  const takeOver = async () => {
+    const id = projectId.value
+    if (id !== null) await acquire(id, true)
+  }
+
+  watch(
+    projectId,
+    async (id) => {
+      await release()
+      if (id !== null) await acquire(id)
+    },
+    { immediate: true },
+  )
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+      unlisten = await getCurrentWebviewWindow().listen<{ project_id: number; holder: LockInfo }>(
+        'project-lock-lost',
+        (event) => {
+          if (event.payload.project_id !== projectId.value) return
+          heldProjectId = null
+          setReadOnly(event.payload.project_id, event.payload.holder)
+          toast.add({
+            severity: 'warn',
+            summary: 'Proyecto en solo lectura',
+            detail: `Se ha abierto en ${event.payload.holder.hostname}. Los cambios de esta ventana ya no se guardan.`,
+            life: 8000,
+          })
+        },
+      )
+    } catch (err) {
+      logWarn('ProjectLock', 'No se pudo escuchar project-lock-lost', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+    void release()
+  })
+
+  return { lockedBy, takeOver }
+}
@@ -0,0 +1,104 @@
+/**
+ * Composable para restaurar la sesión de trabajo (ver src-tauri/src/session.rs)
+ *
+ * - Guarda en el shell el proyecto abierto, la pestaña activa y la posición de
+ *   lectura del texto cada vez que cambian (con un pequeño retardo)
+ * - Escucha `restore-session`, que el shell emite al arrancar cuando el backend
+ *   está listo, y al montar recoge la sesión si llegó antes (`take_pending_session_restore`)
+ * - Solo restaura si el usuario sigue en la pantalla inicial: si ya ha abierto
+ *   otra cosa, no se le mueve
+ */
+import { onMounted, onUnmounted, watch } from 'vue'
+import { useRoute, useRouter } from 'vue-router'
+import { useWorkspaceStore } from '@/stores/workspace'
+import { logError, logWarn } from '@/services/logger'
+
+export interface SessionState {
+  project_id: number | null
+  tab: string | null
+  scroll_position: number | null
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Retardo antes de guardar (el scroll genera muchos cambios seguidos) */
+const SAVE_DEBOUNCE_MS = 1000
+
+/** Rutas desde las que se puede restaurar sin interrumpir al usuario */
+const START_ROUTES = new Set(['home', 'projects'])
+
+export function useSessionRestore() {
+  const router = useRouter()
+  const route = useRoute()
+  const workspaceStore = useWorkspaceStore()
+  let unlisten: (() => void) | null = null
+  let saveTimer: ReturnType<typeof setTimeout> | null = null
+
+  function currentSession(): SessionState | null {
+    if (route.name === 'project') {
+      const projectId = Number(route.params.id)
+      if (!Number.isInteger(projectId) || projectId <= 0) return null
+      return {
+        project_id: projectId,
+        tab: workspaceStore.activeTab,
+        scroll_position: workspaceStore.textScrollPosition > 0 ? workspaceStore.textScrollPosition : null,
+      }
+    }
+    if (START_ROUTES.has(String(route.name))) {
+      return { project_id: null, tab: null, scroll_position: null }
+    }
+    // Otras vistas (personajes, colecciones, ajustes): se conserva la última sesión
+    return null
+  }
+
+  function scheduleSave() {
+    if (saveTimer) clearTimeout(saveTimer)
+    saveTimer = setTimeout(async () => {
+      saveTimer = null
+      const session = currentSession()
+      if (!session) return
+      try {
+        const { invoke } = await import('@tauri-apps/api/core')
+        await invoke('save_session', { session })
+      } catch (err) {
+        logWarn('Session', 'No se pudo guardar la sesión', err)
+      }
+    }, SAVE_DEBOUNCE_MS)
+  }
+
+  async function restore(session: SessionState) {
+    if (session.project_id == null) return
+    await router.isReady()
+    if (!START_ROUTES.has(String(route.name))) return
+    const query: Record<string, string> = {}
+    if (session.tab) query.tab = session.tab
+    if (session.scroll_position != null) query.scrollPos = String(session.scroll_position)
+    await router.push({ name: 'project', params: { id: String(session.project_id) }, query })
+  }
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    watch(
+      [() => route.name, () => route.params.id, () => workspaceStore.activeTab, () => workspaceStore.textScrollPosition],
+      scheduleSave,
+    )
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+      unlisten = await getCurrentWebviewWindow().listen<SessionState>('restore-session', (event) => {
+        // Consumir el pendiente para no volver a restaurar al recargar la ventana
+        void invoke('take_pending_session_restore')
+        void restore(event.payload)
+      })
+      const pending = await invoke<SessionState | null>('take_pending_session_restore')
+      if (pending) await restore(pending)
+    } catch (err) {
+      logError('Session', 'No se pudo registrar la restauración de sesión', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisten?.()
+    if (saveTimer) clearTimeout(saveTimer)
+  })
+}
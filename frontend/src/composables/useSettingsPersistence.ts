@@ -22,6 +22,19 @@ import type {
 } from '@/types/api/projects'
 import { logError, logWarn } from '@/services/logger'
 
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Anota el restablecimiento en el registro de operaciones del shell (src-tauri/src/audit.rs) */
+async function recordSettingsReset() {
+  if (!isTauriEnv) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('record_settings_reset')
+  } catch (err) {
+    logWarn('Settings', 'Could not record the settings reset', err)
+  }
+}
+
 // ── Types ──────────────────────────────────────────────────
 
 export interface EnabledMethods {
@@ -267,6 +280,7 @@ export function useSettingsPersistence() {
 
   function resetSettings(systemCapabilities: SystemCapabilities | null) {
     settings.value = createDefaultSettings()
+    void recordSettingsReset()
 
     if (systemCapabilities) {
       applyDefaultsFromCapabilities(systemCapabilities)
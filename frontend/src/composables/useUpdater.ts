@@ -0,0 +1,152 @@
+/**
+ * Composable para las actualizaciones de la app (comandos del updater de Tauri)
+ *
+ * - checkForUpdates(): comprobación manual desde Ayuda > Buscar actualizaciones
+ * - Escucha `update-available` (comprobación semanal en segundo plano),
+ *   `update-download-progress` y `update-ready`
+ */
+import { onMounted, onUnmounted, ref } from 'vue'
+import { useToast } from 'primevue/usetoast'
+import { useAppConfirm } from './useAppConfirm'
+import { logError } from '@/services/logger'
+
+export interface UpdateInfo {
+  version: string
+  current_version: string
+  notes: string | null
+  published_at: string | null
+  release_url: string
+  asset_name: string | null
+  asset_size: number | null
+  can_install: boolean
+}
+
+interface DownloadProgress {
+  downloaded: number
+  total: number | null
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+export function useUpdater() {
+  const toast = useToast()
+  const { ask } = useAppConfirm()
+
+  const checking = ref(false)
+  const downloading = ref(false)
+  /** Progreso de la descarga (0-100), null si el tamaño es desconocido */
+  const progress = ref<number | null>(null)
+
+  const unlisteners: Array<() => void> = []
+
+  async function offerInstall(version: string) {
+    const accepted = await ask({
+      header: 'Actualización lista',
+      message: `La versión ${version} se ha descargado y verificado. ¿Reiniciar ahora para instalarla? Si no, se instalará al cerrar la aplicación.`,
+      icon: 'pi pi-download',
+      acceptLabel: 'Reiniciar e instalar',
+      rejectLabel: 'Más tarde',
+    })
+    if (!accepted) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      await invoke('install_update')
+    } catch (err) {
+      logError('Updater', 'Error instalando la actualización', err)
+      toast.add({ severity: 'error', summary: 'Actualizaciones', detail: String(err), life: 6000 })
+    }
+  }
+
+  async function download() {
+    downloading.value = true
+    progress.value = 0
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const version = await invoke<string>('download_update')
+      await offerInstall(version)
+    } catch (err) {
+      logError('Updater', 'Error descargando la actualización', err)
+      toast.add({ severity: 'error', summary: 'Actualizaciones', detail: String(err), life: 6000 })
+    } finally {
+      downloading.value = false
+      progress.value = null
+    }
+  }
+
+  async function offerUpdate(info: UpdateInfo) {
+    if (!info.can_install) {
+      toast.add({
+        severity: 'info',
+        summary: `Versión ${info.version} disponible`,
+        detail: `Descárguela desde ${info.release_url}`,
+        life: 10000,
+      })
+      return
+    }
+    const accepted = await ask({
+      header: 'Nueva versión disponible',
+      message: `Narrative Assistant ${info.version} está disponible (tiene la ${info.current_version}). ¿Descargarla ahora?`,
+      icon: 'pi pi-cloud-download',
+      acceptLabel: 'Descargar',
+      rejectLabel: 'Ahora no',
+    })
+    if (accepted) await download()
+  }
+
+  async function checkForUpdates() {
+    if (!isTauriEnv) {
+      toast.add({
+        severity: 'info',
+        summary: 'Actualizaciones',
+        detail: 'Las actualizaciones solo están disponibles en la aplicación de escritorio.',
+        life: 4000,
+      })
+      return
+    }
+    if (checking.value || downloading.value) return
+    checking.value = true
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const info = await invoke<UpdateInfo | null>('check_for_updates')
+      if (info) {
+        await offerUpdate(info)
+      } else {
+        toast.add({ severity: 'success', summary: 'Actualizaciones', detail: 'Tiene la última versión.', life: 4000 })
+      }
+    } catch (err) {
+      logError('Updater', 'Error comprobando actualizaciones', err)
+      toast.add({ severity: 'error', summary: 'Actualizaciones', detail: String(err), life: 6000 })
+    } finally {
+      checking.value = false
+    }
+  }
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { listen } = await import('@tauri-apps/api/event')
+      unlisteners.push(
+        await listen<UpdateInfo>('update-available', (event) => {
+          if (!checking.value && !downloading.value) void offerUpdate(event.payload)
+        }),
+        await listen<DownloadProgress>('update-download-progress', (event) => {
+          const { downloaded, total } = event.payload
+          progress.value = total ? Math.round((downloaded / total) * 100) : null
+        }),
+      )
+    } catch (err) {
+      logError('Updater', 'No se pudieron registrar los eventos del updater', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisteners.forEach((unlisten) => unlisten())
+  })
+
+  return {
+    checking,
+    downloading,
+    progress,
+    checkForUpdates,
+  }
+}
@@ -0,0 +1,50 @@
+/**
+ * Composable para la inactividad del usuario (monitor del shell, ver src-tauri/src/idle.rs)
+ *
+ * - `isIdle` se comparte entre componentes; fuera de Tauri se queda en `false`
+ * - `onIdle`/`onActive` registran callbacks para trabajo pesado (precargas,
+ *   limpiezas) que solo debe correr mientras el usuario no está escribiendo;
+ *   se eliminan al desmontar el componente
+ */
+import { onMounted, onUnmounted, readonly, ref } from 'vue'
+import { logError } from '@/services/logger'
+
+interface IdleStatus {
+  idle: boolean
+  idle_secs: number
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+const isIdle = ref(false)
+
+export function useUserIdle(handlers: { onIdle?: () => void; onActive?: () => void } = {}) {
+  const unlisteners: (() => void)[] = []
+
+  onMounted(async () => {
+    if (!isTauriEnv) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { listen } = await import('@tauri-apps/api/event')
+      unlisteners.push(
+        await listen<IdleStatus>('user-idle', () => {
+          isIdle.value = true
+          handlers.onIdle?.()
+        }),
+        await listen<IdleStatus>('user-active', () => {
+          isIdle.value = false
+          handlers.onActive?.()
+        }),
+      )
+      isIdle.value = (await invoke<IdleStatus>('get_idle_state')).idle
+    } catch (err) {
+      logError('Idle', 'No se pudo registrar la inactividad del usuario', err)
+    }
+  })
+
+  onUnmounted(() => {
+    unlisteners.forEach((unlisten) => unlisten())
+  })
+
+  return { isIdle: readonly(isIdle) }
+}
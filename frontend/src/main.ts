@@ -1,5 +1,5 @@
 // Interceptor de console -> archivo (DEBE ser lo primero)
-import { installConsoleInterceptor } from '@/services/logger'
+import { installConsoleInterceptor, installErrorReporting } from '@/services/logger'
 installConsoleInterceptor()
 
 import { createApp } from 'vue'
@@ -37,6 +37,9 @@ import { useThemeStore } from './stores/theme'
 const pinia = createPinia()
 const app = createApp(App)
 
+// Errores no capturados -> log del shell (shell.log)
+installErrorReporting(app)
+
 // Plugins
 app.use(pinia)
 app.use(router)
@@ -69,6 +69,14 @@ const routes: RouteRecordRaw[] = [
       title: 'Colecciones - Narrative Assistant'
     }
   },
+  {
+    // Entrada de las ventanas de proyecto del shell (`index.html?project=<id>`)
+    path: '/index.html',
+    name: 'window-entry',
+    redirect: to => to.query.project
+      ? { name: 'project', params: { id: String(to.query.project) } }
+      : { name: 'home' }
+  },
   {
     path: '/collections/:id',
     name: 'collection-detail',
@@ -112,12 +112,37 @@ export interface BlobResponse {
   response: Response
 }
 
+// Proyecto abierto en solo lectura (bloqueado por otra instancia, ver useProjectLock)
+let readOnlyProjectId: number | null = null
+
+/** Marca el proyecto como de solo lectura (`null` lo quita) */
+export function setReadOnlyProject(projectId: number | null) {
+  readOnlyProjectId = projectId
+}
+
+// Peticiones POST que solo leen o generan un archivo
+const READ_ONLY_POST = /\/(export[\w-]*|search|preview)(\/|\?|$)/
+
+/** Rechaza las peticiones que modificarían un proyecto en solo lectura */
+function assertWritable(input: RequestInfo | URL, init?: RequestInit) {
+  if (readOnlyProjectId === null) return
+  const method = (init?.method ?? 'GET').toUpperCase()
+  if (method === 'GET' || method === 'HEAD') return
+  const url = typeof input === 'string' ? input : input instanceof URL ? input.href : input.url
+  const path = url.replace(/^[a-z]+:\/\/[^/]+/i, '')
+  const prefix = `/api/projects/${readOnlyProjectId}`
+  if (!path.startsWith(prefix) || !/^[/?]|^$/.test(path.slice(prefix.length))) return
+  if (method === 'POST' && READ_ONLY_POST.test(path)) return
+  throw new ApiError('El proyecto está abierto en solo lectura: otro equipo lo está editando.', 423)
+}
+
 /**
  * Wrapper de fetch que alimenta el monitor de conexión.
  * Si backendDown=true, los componentes pueden mostrar un banner
  * en vez de múltiples toasts de error individuales.
  */
 async function monitoredFetch(input: RequestInfo | URL, init?: RequestInit): Promise<Response> {
+  assertWritable(input, init)
   try {
     const response = await rawRequest(input, init)
     onRequestSuccess()
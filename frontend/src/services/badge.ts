@@ -0,0 +1,23 @@
+/**
+ * Contador de alertas en el icono de la app (ver src-tauri/src/badge.rs).
+ *
+ * Dock en macOS, icono superpuesto en la barra de tareas de Windows. Fuera de
+ * Tauri no hace nada. Los errores solo se registran: el contador es informativo.
+ */
+import { logWarn } from '@/services/logger'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+let lastCount = 0
+
+/** Muestra `count` alertas sin resolver (0 lo quita) */
+export async function setBadgeCount(count: number) {
+  if (!isTauriEnv || count === lastCount) return
+  lastCount = count
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('set_badge_count', { count: Math.max(0, Math.floor(count)) })
+  } catch (err) {
+    logWarn('Badge', 'No se pudo actualizar el contador de alertas', err)
+  }
+}
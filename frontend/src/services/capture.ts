@@ -0,0 +1,48 @@
+/**
+ * Exportar la vista como imagen PNG o SVG (ver src-tauri/src/capture.rs).
+ *
+ *   import { captureElementToImage } from '@/services/capture'
+ *   const path = await captureElementToImage(graphContainer.value, 'grafo-relaciones.png')
+ *
+ * En la app de escritorio el shell captura el webview y recorta la zona del
+ * elemento (tal como se ve: debe estar visible en pantalla). En el navegador
+ * solo se pueden descargar en PNG los elementos dibujados en un `<canvas>`.
+ * Devuelve la ruta guardada, o `null` si el usuario cancela o no es la app de escritorio.
+ */
+
+import { pickExportPath } from '@/services/exportPath'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+function downloadCanvas(element: HTMLElement, defaultName: string) {
+  const canvas = element instanceof HTMLCanvasElement ? element : element.querySelector('canvas')
+  if (!canvas) throw new Error('Exportar esta vista como imagen solo está disponible en la aplicación de escritorio')
+  const link = document.createElement('a')
+  link.href = canvas.toDataURL('image/png')
+  link.download = defaultName.replace(/\.svg$/i, '.png')
+  link.click()
+}
+
+export async function captureElementToImage(element: HTMLElement | null, defaultName: string): Promise<string | null> {
+  if (!element) return null
+  if (!isTauriEnv) {
+    downloadCanvas(element, defaultName)
+    return null
+  }
+  const path = await pickExportPath({
+    defaultPath: defaultName,
+    filters: [
+      { name: 'Imagen PNG', extensions: ['png'] },
+      { name: 'Imagen SVG', extensions: ['svg'] },
+    ],
+  })
+  if (!path) return null
+  // Tras cerrar el diálogo, esperar a que se repinte la vista sin él
+  await new Promise((resolve) => requestAnimationFrame(() => requestAnimationFrame(resolve)))
+  const rect = element.getBoundingClientRect()
+  const { invoke } = await import('@tauri-apps/api/core')
+  return invoke<string>('capture_view', {
+    region: { x: rect.x, y: rect.y, width: rect.width, height: rect.height },
+    path,
+  })
+}
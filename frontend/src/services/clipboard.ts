@@ -0,0 +1,41 @@
+/**
+ * Portapapeles con formato (ver src-tauri/src/clipboard.rs).
+ *
+ * En la app de escritorio el shell copia HTML o RTF junto a una alternativa en
+ * texto plano, para pegar fichas e informes en Word sin perder el formato.
+ * Fuera de Tauri se usa la API del navegador (HTML solo si la soporta).
+ *
+ *   import { copyToClipboard } from '@/services/clipboard'
+ *   await copyToClipboard(html, 'html')
+ */
+
+export type ClipboardFormat = 'text' | 'html' | 'rtf'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+async function copyInBrowser(content: string, format: ClipboardFormat, plainText?: string) {
+  if (format === 'html' && typeof ClipboardItem !== 'undefined') {
+    const plain = plainText ?? (new DOMParser().parseFromString(content, 'text/html').body.textContent || '')
+    await navigator.clipboard.write([
+      new ClipboardItem({
+        'text/html': new Blob([content], { type: 'text/html' }),
+        'text/plain': new Blob([plain], { type: 'text/plain' }),
+      }),
+    ])
+    return
+  }
+  if (format === 'rtf') {
+    throw new Error('Copiar en RTF solo está disponible en la aplicación de escritorio')
+  }
+  await navigator.clipboard.writeText(format === 'text' ? content : (plainText ?? content))
+}
+
+/** Copia `content` en el formato indicado; `plainText` es la alternativa sin formato */
+export async function copyToClipboard(content: string, format: ClipboardFormat = 'text', plainText?: string) {
+  if (!isTauriEnv) {
+    await copyInBrowser(content, format, plainText)
+    return
+  }
+  const { invoke } = await import('@tauri-apps/api/core')
+  await invoke('copy_to_clipboard', { content, format, plainText })
+}
@@ -0,0 +1,59 @@
+/**
+ * Registro de comandos del shell (ver src-tauri/src/commands.rs).
+ *
+ * La paleta de comandos lista lo mismo que el menú nativo, con los mismos
+ * nombres y atajos, más acciones del shell (reiniciar el motor, preferencias).
+ * Fuera de Tauri no hay comandos.
+ */
+import { logError } from '@/services/logger'
+
+export interface ShellCommand {
+  id: string
+  group: string
+  label: string
+  /** Acelerador en formato Tauri ("CmdOrCtrl+Shift+O") */
+  shortcut: string | null
+  kind: 'menu' | 'shell'
+  /** Estado de las preferencias que alterna */
+  checked: boolean | null
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+const isMac = typeof navigator !== 'undefined' && /Mac/i.test(navigator.platform)
+
+/** Comandos disponibles, en el orden del menú */
+export async function listCommands(): Promise<ShellCommand[]> {
+  if (!isTauriEnv) return []
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<ShellCommand[]>('list_commands')
+  } catch (err) {
+    logError('Commands', 'No se pudo leer el registro de comandos', err)
+    return []
+  }
+}
+
+/** Ejecuta un comando por id. Devuelve si se ejecutó. */
+export async function invokeCommand(id: string): Promise<boolean> {
+  if (!isTauriEnv) return false
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('invoke_command', { id })
+    return true
+  } catch (err) {
+    logError('Commands', `No se pudo ejecutar el comando ${id}`, err)
+    return false
+  }
+}
+
+/** Teclas de un acelerador para mostrarlas ("CmdOrCtrl+K" -> ["Ctrl", "K"]) */
+export function shortcutKeys(shortcut: string | null): string[] {
+  if (!shortcut) return []
+  return shortcut.split('+').map((key) => {
+    if (key === 'CmdOrCtrl' || key === 'CommandOrControl') return isMac ? '⌘' : 'Ctrl'
+    if (key === 'Shift') return isMac ? '⇧' : 'Shift'
+    if (key === 'Alt') return isMac ? '⌥' : 'Alt'
+    return key
+  })
+}
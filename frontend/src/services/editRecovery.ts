@@ -0,0 +1,84 @@
+/**
+ * Textos sin guardar recuperables tras un cierre inesperado (ver src-tauri/src/edit_recovery.rs).
+ *
+ * Cada carga de página tiene su propio `SESSION_ID`: el shell solo ofrece
+ * recuperar los textos de sesiones que ya no están vivas. Fuera de Tauri no
+ * se guarda nada.
+ */
+import { logWarn } from '@/services/logger'
+
+export interface RecoveredEdit {
+  id: string
+  session: string
+  window: string
+  project_id: number | null
+  label: string
+  content: string
+  updated_at: number
+}
+
+export interface EditBuffer {
+  /** Editor dentro de la página, p. ej. `scene-notes:12:3` */
+  id: string
+  /** Descripción para el usuario, p. ej. "Notas de la escena 3" */
+  label: string
+  projectId?: number | null
+  content: string
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Identificador de esta carga de página */
+export const SESSION_ID = typeof crypto !== 'undefined' && 'randomUUID' in crypto
+  ? crypto.randomUUID()
+  : `${Date.now()}-${Math.random().toString(36).slice(2)}`
+
+export async function saveEditBuffer(buffer: EditBuffer): Promise<void> {
+  if (!isTauriEnv) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('save_edit_buffer', {
+      session: SESSION_ID,
+      id: buffer.id,
+      label: buffer.label,
+      projectId: buffer.projectId ?? null,
+      content: buffer.content,
+    })
+  } catch (err) {
+    logWarn('EditRecovery', 'No se pudo guardar el texto de recuperación', err)
+  }
+}
+
+export async function clearEditBuffer(id: string): Promise<void> {
+  if (!isTauriEnv) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('clear_edit_buffer', { session: SESSION_ID, id })
+  } catch (err) {
+    logWarn('EditRecovery', 'No se pudo borrar el texto de recuperación', err)
+  }
+}
+
+/** Textos de sesiones anteriores que no llegaron a guardarse */
+export async function listRecoveredEdits(): Promise<RecoveredEdit[]> {
+  if (!isTauriEnv) return []
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<RecoveredEdit[]>('list_recovered_edits', { session: SESSION_ID })
+  } catch (err) {
+    logWarn('EditRecovery', 'No se pudieron consultar los textos recuperados', err)
+    return []
+  }
+}
+
+export async function discardRecoveredEdits(edits: RecoveredEdit[]): Promise<void> {
+  if (!isTauriEnv || edits.length === 0) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('discard_recovered_edits', {
+      keys: edits.map((edit) => ({ session: edit.session, id: edit.id })),
+    })
+  } catch (err) {
+    logWarn('EditRecovery', 'No se pudieron descartar los textos recuperados', err)
+  }
+}
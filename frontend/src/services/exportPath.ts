@@ -0,0 +1,25 @@
+/**
+ * Diálogo de guardar para las exportaciones (ver src-tauri/src/export_scope.rs).
+ *
+ *   import { pickExportPath } from '@/services/exportPath'
+ *   const path = await pickExportPath({ defaultPath: 'informe.pdf', filters: [{ name: 'PDF', extensions: ['pdf'] }] })
+ *
+ * Mismas opciones que `save()` de @tauri-apps/plugin-dialog, pero el diálogo lo
+ * abre el shell y concede la carpeta elegida: los comandos de exportación solo
+ * escriben en carpetas elegidas así. Devuelve la ruta, o `null` si se cancela.
+ */
+
+export interface ExportDialogOptions {
+  title?: string
+  defaultPath?: string
+  filters?: { name: string; extensions: string[] }[]
+}
+
+export async function pickExportPath(options: ExportDialogOptions): Promise<string | null> {
+  const { invoke } = await import('@tauri-apps/api/core')
+  return invoke<string | null>('pick_export_path', {
+    title: options.title ?? null,
+    defaultPath: options.defaultPath ?? null,
+    filters: options.filters ?? null,
+  })
+}
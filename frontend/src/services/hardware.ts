@@ -0,0 +1,73 @@
+/**
+ * Capacidad del equipo (ver src-tauri/src/hardware.rs).
+ *
+ * El shell la detecta sin esperar al backend, así que el asistente de primer
+ * arranque puede recomendar el nivel de los modelos y avisar de la falta de
+ * disco antes de empezar las descargas. Fuera de Tauri devuelve `null`.
+ */
+
+export interface GpuInfo {
+  name: string
+  vram_mb: number | null
+  unified_memory: boolean
+  cuda: boolean
+}
+
+export interface HardwareInfo {
+  os: string
+  arch: string
+  cpu_brand: string
+  physical_cores: number | null
+  logical_cores: number
+  total_ram_mb: number
+  available_ram_mb: number
+  free_disk_mb: number | null
+  data_dir: string
+  gpus: GpuInfo[]
+  llm_budget_gb: number
+  recommended_level: 'rapida' | 'completa' | 'experta'
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+let cached: Promise<HardwareInfo | null> | null = null
+
+/** Detecta el hardware una vez por sesión */
+export function getHardwareInfo(): Promise<HardwareInfo | null> {
+  if (!isTauriEnv) return Promise.resolve(null)
+  cached ??= import('@tauri-apps/api/core')
+    .then(({ invoke }) => invoke<HardwareInfo>('get_hardware_info'))
+    .catch(() => {
+      cached = null
+      return null
+    })
+  return cached
+}
+
+export interface RecommendedModel {
+  id: string
+  display_name: string
+  size_mb: number
+  installed: boolean
+  reason: string
+}
+
+export interface ModelRecommendation {
+  models: RecommendedModel[]
+  llm_budget_gb: number
+  recommended_level: HardwareInfo['recommended_level']
+  download_mb: number
+  fits_on_disk: boolean
+  llm_note: string | null
+}
+
+/** Modelos recomendados para este equipo (NLP requeridos + el LLM más grande que cabe) */
+export async function recommendModels(): Promise<ModelRecommendation | null> {
+  if (!isTauriEnv) return null
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<ModelRecommendation>('recommend_models')
+  } catch {
+    return null
+  }
+}
@@ -1,5 +1,8 @@
 import { afterEach, describe, expect, it, vi } from 'vitest'
-import { apiRequest, rawRequest } from './httpTransport'
+import { invoke } from '@tauri-apps/api/core'
+import { apiRequest, BackendTransportError, rawRequest, SESSION_TOKEN_HEADER } from './httpTransport'
+
+vi.mock('@tauri-apps/api/core', () => ({ invoke: vi.fn() }))
 
 describe('httpTransport', () => {
   const originalFetch = globalThis.fetch
@@ -30,4 +33,62 @@ describe('httpTransport', () => {
 
     expect(fetchMock).toHaveBeenCalledWith('/api/health', { method: 'GET' })
   })
+
+  it('rawRequest adds the shell session token to uploads in the desktop app', async () => {
+    const fetchMock = vi.fn().mockResolvedValue(new Response(null, { status: 204 }))
+    globalThis.fetch = fetchMock
+    vi.mocked(invoke).mockResolvedValue('token-de-sesion')
+    const win = window as unknown as Record<string, unknown>
+    win.__TAURI_INTERNALS__ = {}
+    try {
+      await rawRequest('http://localhost:8008/api/projects', {
+        method: 'POST',
+        headers: { Accept: 'application/json' },
+        body: new FormData(),
+      })
+    } finally {
+      delete win.__TAURI_INTERNALS__
+    }
+
+    const [, init] = fetchMock.mock.calls[0]
+    const headers = init.headers as Headers
+    expect(headers.get(SESSION_TOKEN_HEADER)).toBe('token-de-sesion')
+    expect(headers.get('Accept')).toBe('application/json')
+  })
+
+  it('rawRequest sends JSON API calls through the shell in the desktop app', async () => {
+    const fetchMock = vi.fn()
+    globalThis.fetch = fetchMock
+    vi.mocked(invoke).mockImplementation(async (command: string) => {
+      if (command !== 'backend_request') throw new Error(command)
+      return { status: 200, headers: { 'content-type': 'application/json' }, body: '{"success":true}', base64: false }
+    })
+    const win = window as unknown as Record<string, unknown>
+    win.__TAURI_INTERNALS__ = {}
+    try {
+      const response = await rawRequest('/api/projects/3', {
+        method: 'PUT',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ name: 'Nuevo' }),
+      })
+      expect(response.status).toBe(200)
+      expect(await response.json()).toEqual({ success: true })
+
+      vi.mocked(invoke).mockRejectedValue({ code: 'unreachable', message: 'connection refused' })
+      const error = await rawRequest('/api/health').catch(err => err)
+      expect(error).toBeInstanceOf(BackendTransportError)
+      expect(error.code).toBe('unreachable')
+    } finally {
+      delete win.__TAURI_INTERNALS__
+    }
+
+    expect(fetchMock).not.toHaveBeenCalled()
+    expect(invoke).toHaveBeenCalledWith('backend_request', {
+      method: 'PUT',
+      path: '/api/projects/3',
+      body: { name: 'Nuevo' },
+      headers: {},
+      timeoutMs: undefined,
+    })
+  })
 })
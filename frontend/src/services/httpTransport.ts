@@ -1,7 +1,125 @@
-import { apiUrl } from '@/config/api'
+import { API_BASE, apiUrl } from '@/config/api'
 
+/**
+ * Token de sesión del shell (src-tauri/src/backend_auth.rs). En la app de
+ * escritorio el backend rechaza con 401 las peticiones sin él; en el navegador
+ * (backend arrancado a mano) no hace falta.
+ */
+export const SESSION_TOKEN_HEADER = 'X-NA-Session-Token'
+
+let sessionToken: Promise<string | null> | null = null
+
+function getSessionToken(): Promise<string | null> {
+  const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+  if (!isTauriEnv) return Promise.resolve(null)
+  sessionToken ??= import('@tauri-apps/api/core')
+    .then(({ invoke }) => invoke<string>('get_backend_session_token'))
+    .catch(() => {
+      // Reintentar en la siguiente petición
+      sessionToken = null
+      return null
+    })
+  return sessionToken
+}
+
+/**
+ * Fallo de `backend_request` (src-tauri/src/backend_proxy.rs). Es un
+ * `TypeError`, como los fallos de red de `fetch`, para que
+ * `isConnectionError` lo trate igual.
+ */
+export type BackendErrorCode = 'unreachable' | 'timeout' | 'invalid_request' | 'transport'
+
+export class BackendTransportError extends TypeError {
+  constructor(
+    public readonly code: BackendErrorCode,
+    message: string,
+  ) {
+    super(code === 'invalid_request' ? message : `network error (${code}): ${message}`)
+    this.name = 'BackendTransportError'
+  }
+}
+
+interface BackendProxyResponse {
+  status: number
+  headers: Record<string, string>
+  body: string
+  base64: boolean
+}
+
+// Tiempo máximo que admite el shell; si hay `signal`, manda el del frontend
+const PROXY_MAX_TIMEOUT_MS = 600_000
+
+// Códigos de estado cuya respuesta no puede llevar cuerpo
+const NULL_BODY_STATUS = new Set([101, 204, 205, 304])
+
+/**
+ * Ruta y cuerpo JSON si la petición puede ir por el shell: en la app de
+ * escritorio, a la API y con cuerpo JSON (o sin cuerpo). Las subidas de
+ * archivos siguen yendo por `fetch`.
+ */
+function proxyTarget(input: RequestInfo | URL, init?: RequestInit): { path: string; body?: unknown } | null {
+  const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+  if (!isTauriEnv || input instanceof Request) return null
+  const url = input.toString()
+  const path = API_BASE && url.startsWith(API_BASE) ? url.slice(API_BASE.length) : url
+  if (!path.startsWith('/api/')) return null
+  if (init?.body === undefined || init.body === null) return { path }
+  if (typeof init.body !== 'string') return null
+  try {
+    return { path, body: JSON.parse(init.body) }
+  } catch {
+    return null
+  }
+}
+
+async function proxyRequest(target: { path: string; body?: unknown }, init?: RequestInit): Promise<Response> {
+  const headers: Record<string, string> = {}
+  new Headers(init?.headers).forEach((value, name) => {
+    // El shell pone el Content-Type del cuerpo JSON
+    if (name !== 'content-type') headers[name] = value
+  })
+  const { invoke } = await import('@tauri-apps/api/core')
+  const request = invoke<BackendProxyResponse>('backend_request', {
+    method: init?.method ?? 'GET',
+    path: target.path,
+    body: target.body,
+    headers,
+    timeoutMs: init?.signal ? PROXY_MAX_TIMEOUT_MS : undefined,
+  }).catch((err: { code?: BackendErrorCode; message?: string } | string) => {
+    if (typeof err === 'object' && err?.code) throw new BackendTransportError(err.code, err.message ?? '')
+    throw new BackendTransportError('transport', String(err))
+  })
+
+  // `invoke` no se puede cancelar: al abortar se deja de esperar, como `fetch`
+  const signal = init?.signal
+  const result = await (signal
+    ? new Promise<BackendProxyResponse>((resolve, reject) => {
+        const abort = () => reject(signal.reason ?? new DOMException('Aborted', 'AbortError'))
+        if (signal.aborted) return abort()
+        signal.addEventListener('abort', abort, { once: true })
+        request.then(resolve, reject).finally(() => signal.removeEventListener('abort', abort))
+      })
+    : request)
+
+  let body: BodyInit | null = result.body
+  if (NULL_BODY_STATUS.has(result.status)) body = null
+  else if (result.base64) body = Uint8Array.from(atob(result.body), char => char.charCodeAt(0))
+  return new Response(body, { status: result.status, headers: result.headers })
+}
+
+/**
+ * Petición al backend. En la app de escritorio, las peticiones JSON a la API
+ * van por el shell (`backend_request`), que añade el token y reintenta; el
+ * resto, por `fetch` con el token de sesión.
+ */
 export async function rawRequest(input: RequestInfo | URL, init?: RequestInit): Promise<Response> {
-  return fetch(input, init)
+  const target = proxyTarget(input, init)
+  if (target) return proxyRequest(target, init)
+  const token = await getSessionToken()
+  if (!token) return fetch(input, init)
+  const headers = new Headers(init?.headers)
+  headers.set(SESSION_TOKEN_HEADER, token)
+  return fetch(input, { ...init, headers })
 }
 
 export async function apiRequest(path: string, init?: RequestInit): Promise<Response> {
@@ -21,6 +21,7 @@
  * flush de beforeunload, donde las garantías de apiClient no aplican.
  */
 
+import type { App } from 'vue'
 import { apiRequest } from '@/services/httpTransport'
 
 type LogLevel = 'debug' | 'info' | 'warn' | 'error'
@@ -178,6 +179,57 @@ export function logWarn(tag: string, message: string, err?: unknown) {
   }
 }
 
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Máximo de errores enviados al log del shell por carga de página (evita inundarlo con bucles) */
+const MAX_SHELL_REPORTS = 50
+let shellReports = 0
+
+/**
+ * Envía un error al log del shell (shell.log) con `log_frontend_error`.
+ * En release nadie tiene abiertas las DevTools: así quedan junto al resto
+ * del log, con el id de sesión del arranque.
+ */
+export async function reportToShell(level: 'error' | 'warn' | 'info', message: string, stack?: string) {
+  if (!isTauriEnv || shellReports >= MAX_SHELL_REPORTS) return
+  shellReports++
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('log_frontend_error', { level, message, stack: stack ?? null })
+  } catch {
+    // Sin shell no hay dónde registrarlo; el console ya lo tiene
+  }
+}
+
+function describe(err: unknown): { message: string; stack?: string } {
+  if (err instanceof Error) return { message: `${err.name}: ${err.message}`, stack: err.stack }
+  return { message: String(err) }
+}
+
+/**
+ * Instala los manejadores de errores no capturados (Vue, window.onerror y
+ * promesas rechazadas) que los envían al log del shell.
+ */
+export function installErrorReporting(app: App) {
+  app.config.errorHandler = (err, _instance, info) => {
+    const { message, stack } = describe(err)
+    // Con errorHandler Vue ya no lo escribe en consola: se hace aquí (y llega a frontend.log)
+    console.error(`[Vue] ${message} (${info})`, err)
+    void reportToShell('error', `[Vue] ${message} (${info})`, stack)
+  }
+
+  window.addEventListener('error', (event) => {
+    const { message, stack } = event.error ? describe(event.error) : { message: event.message, stack: undefined }
+    const where = event.filename ? ` @ ${event.filename}:${event.lineno}:${event.colno}` : ''
+    void reportToShell('error', `[window.onerror] ${message}${where}`, stack)
+  })
+
+  window.addEventListener('unhandledrejection', (event) => {
+    const { message, stack } = describe(event.reason)
+    void reportToShell('error', `[unhandledrejection] ${message}`, stack)
+  })
+}
+
 /** Force immediate flush of buffered entries */
 export function flushLogs() {
   flush()
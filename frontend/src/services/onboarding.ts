@@ -0,0 +1,60 @@
+/**
+ * Progreso del primer uso (ver src-tauri/src/onboarding.rs).
+ *
+ * El shell lo guarda fuera del webview, así que sobrevive a que se vacíe su
+ * caché. Fuera de Tauri se usa localStorage como antes.
+ */
+import { logWarn } from '@/services/logger'
+
+export type OnboardingStage = 'download_models' | 'import_manuscript' | 'tutorial' | 'complete'
+export type OnboardingMilestone = 'models_downloaded' | 'first_import' | 'tutorial_completed'
+
+export interface OnboardingState {
+  stage: OnboardingStage
+  models_downloaded: boolean
+  first_import: boolean
+  tutorial_completed: boolean
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Clave con la que el tutorial se marcaba como completado en el navegador */
+const TUTORIAL_COMPLETED_KEY = 'narrative_assistant_tutorial_completed'
+
+/** Estado del primer uso, o `null` fuera de Tauri o si el shell no responde */
+export async function getOnboardingState(): Promise<OnboardingState | null> {
+  if (!isTauriEnv) return null
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const state = await invoke<OnboardingState>('get_onboarding_state')
+    // Instalaciones anteriores: el tutorial completado estaba en localStorage
+    if (!state.tutorial_completed && localStorage.getItem(TUTORIAL_COMPLETED_KEY) === 'true') {
+      return (await advanceOnboarding('tutorial_completed')) ?? state
+    }
+    return state
+  } catch (err) {
+    logWarn('Onboarding', 'No se pudo leer el progreso del primer uso', err)
+    return null
+  }
+}
+
+/** Marca un hito del primer uso (idempotente) */
+export async function advanceOnboarding(milestone: OnboardingMilestone): Promise<OnboardingState | null> {
+  if (!isTauriEnv) {
+    if (milestone === 'tutorial_completed') localStorage.setItem(TUTORIAL_COMPLETED_KEY, 'true')
+    return null
+  }
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<OnboardingState>('advance_onboarding', { milestone })
+  } catch (err) {
+    logWarn('Onboarding', `No se pudo guardar el hito ${milestone}`, err)
+    return null
+  }
+}
+
+/** Si el tutorial ya se completó (shell en la app, localStorage en el navegador) */
+export async function isTutorialCompleted(): Promise<boolean> {
+  const state = await getOnboardingState()
+  return state?.tutorial_completed ?? localStorage.getItem(TUTORIAL_COMPLETED_KEY) === 'true'
+}
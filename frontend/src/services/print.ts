@@ -0,0 +1,49 @@
+/**
+ * Impresión de informes a PDF con el motor del webview (ver src-tauri/src/print.rs).
+ *
+ *   import { printReportToPdf } from '@/services/print'
+ *   const path = await printReportToPdf('informe-revision.pdf', html)
+ *
+ * Sin `html` se imprime la vista actual con sus estilos `@media print`.
+ * Devuelve la ruta del PDF, o `null` si el usuario cancela o no es la app de escritorio.
+ */
+
+import { pickExportPath } from '@/services/exportPath'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+export async function printReportToPdf(defaultName: string, html?: string): Promise<string | null> {
+  if (!isTauriEnv) {
+    // En el navegador, el diálogo de impresión permite guardar como PDF
+    const target = html ? window.open('', '_blank') : window
+    if (target && html) {
+      target.document.write(html)
+      target.document.close()
+    }
+    target?.print()
+    return null
+  }
+  const outputPath = await pickExportPath({
+    defaultPath: defaultName,
+    filters: [{ name: 'PDF', extensions: ['pdf'] }],
+  })
+  if (!outputPath) return null
+  const { invoke } = await import('@tauri-apps/api/core')
+  return invoke<string>('print_report', { html: html ?? null, outputPath })
+}
+
+/**
+ * Informe de análisis a PDF generado en el shell, sin backend ni webview
+ * (ver src-tauri/src/report_pdf.rs). `report` es el JSON de
+ * `/export/report` con los eventos de `/timeline` en `timeline`.
+ * Devuelve la ruta del PDF, o `null` si el usuario cancela.
+ */
+export async function exportReportPdf(defaultName: string, report: unknown): Promise<string | null> {
+  const outputPath = await pickExportPath({
+    defaultPath: defaultName,
+    filters: [{ name: 'PDF', extensions: ['pdf'] }],
+  })
+  if (!outputPath) return null
+  const { invoke } = await import('@tauri-apps/api/core')
+  return invoke<string>('export_report_pdf', { report, outputPath })
+}
@@ -0,0 +1,37 @@
+/**
+ * Estadísticas instantáneas de un manuscrito (ver src-tauri/src/quick_stats.rs).
+ *
+ * Palabras, caracteres, páginas estándar y tiempo de lectura calculados en el
+ * shell, sin pasar por el backend. Fuera de Tauri devuelve `null`.
+ */
+import { logWarn } from '@/services/logger'
+
+export interface QuickStats {
+  words: number
+  characters: number
+  characters_no_spaces: number
+  pages: number
+  reading_minutes: number
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Cifras de un archivo (`path`) o de un texto (`text`) */
+export async function getQuickStats(source: { path: string } | { text: string }): Promise<QuickStats | null> {
+  if (!isTauriEnv) return null
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<QuickStats>('quick_stats', source)
+  } catch (err) {
+    logWarn('QuickStats', 'No se pudieron calcular las estadísticas', err)
+    return null
+  }
+}
+
+/** "45 min", "7 h 30 min" */
+export function formatReadingTime(minutes: number): string {
+  if (minutes < 60) return `${minutes} min`
+  const hours = Math.floor(minutes / 60)
+  const rest = minutes % 60
+  return rest > 0 ? `${hours} h ${rest} min` : `${hours} h`
+}
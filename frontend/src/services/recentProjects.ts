@@ -0,0 +1,60 @@
+/**
+ * Proyectos recientes del shell (ver src-tauri/src/recent_projects.rs).
+ *
+ * La misma lista alimenta el submenú Archivo > Abrir reciente y la pantalla de
+ * inicio. Fuera de Tauri no hay lista (`null`) y el store ordena por fecha de
+ * modificación. Los errores solo se registran: los recientes son un atajo.
+ */
+import { logWarn } from '@/services/logger'
+
+export interface RecentProject {
+  project_id: number
+  title: string
+  path: string | null
+  /** Segundos desde epoch */
+  last_opened: number
+  pinned: boolean
+}
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+async function invokeRecent(command: string, args?: Record<string, unknown>): Promise<RecentProject[] | null> {
+  if (!isTauriEnv) return null
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<RecentProject[]>(command, args)
+  } catch (err) {
+    logWarn('RecentProjects', `Fallo en ${command}`, err)
+    return null
+  }
+}
+
+/** Recientes, los fijados primero */
+export function listRecentProjects() {
+  return invokeRecent('list_recent_projects')
+}
+
+/** Registra que se ha abierto el proyecto */
+export function recordRecentProject(projectId: number, title: string, path?: string | null) {
+  return invokeRecent('record_recent_project', { projectId, title, path: path ?? null })
+}
+
+export function pinRecentProject(projectId: number, pinned: boolean) {
+  return invokeRecent('pin_project', { projectId, pinned })
+}
+
+export function removeRecentProject(projectId: number) {
+  return invokeRecent('remove_recent', { projectId })
+}
+
+/** Escucha `recent-projects-changed` (cambios desde otra ventana o el menú) */
+export async function onRecentProjectsChanged(handler: (entries: RecentProject[]) => void): Promise<() => void> {
+  if (!isTauriEnv) return () => {}
+  try {
+    const { listen } = await import('@tauri-apps/api/event')
+    return await listen<RecentProject[]>('recent-projects-changed', (event) => handler(event.payload))
+  } catch (err) {
+    logWarn('RecentProjects', 'No se pudo escuchar recent-projects-changed', err)
+    return () => {}
+  }
+}
@@ -0,0 +1,96 @@
+/**
+ * Canal de estado del shell (ver src-tauri/src/status_channel.rs).
+ *
+ *   import { subscribeStatus } from '@/services/statusChannel'
+ *   const unsubscribe = subscribeStatus('backend', (event) => { ... event.payload })
+ *
+ * El shell numera cada evento (`seq`). Si falta alguno (la página empezó a
+ * escuchar tarde o se perdió un evento), se piden los que faltan con
+ * `status_replay` antes de seguir, así que los suscriptores los reciben todos y
+ * en orden. La primera suscripción abre el canal y recibe además el historial
+ * del shell, con el último estado del backend. Solo en la app de escritorio.
+ */
+
+import { logError, logWarn } from '@/services/logger'
+
+export type StatusTopic = 'boot' | 'backend' | 'analysis'
+
+export interface StatusEvent<T = any> {
+  seq: number
+  topic: StatusTopic
+  project_id: number | null
+  kind: string
+  payload: T
+  at: number
+}
+
+type StatusHandler = (event: StatusEvent) => void
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+const handlers = new Map<StatusTopic, Set<StatusHandler>>()
+let lastSeq = 0
+let started: Promise<void> | null = null
+// Eventos que llegan mientras se recupera un hueco
+let catchingUp: Promise<void> | null = null
+const pending: StatusEvent[] = []
+
+function dispatch(event: StatusEvent) {
+  if (event.seq <= lastSeq) return
+  lastSeq = event.seq
+  for (const handler of handlers.get(event.topic) ?? []) {
+    try {
+      handler(event)
+    } catch (err) {
+      logError('StatusChannel', `Error en un suscriptor de "${event.topic}"`, err)
+    }
+  }
+}
+
+async function catchUp() {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const replay = await invoke<{ events: StatusEvent[]; gap: boolean }>('status_replay', { after: lastSeq })
+    if (replay.gap) logWarn('StatusChannel', `Eventos de estado perdidos tras el ${lastSeq}`)
+    replay.events.forEach(dispatch)
+  } catch (err) {
+    logError('StatusChannel', 'No se pudieron recuperar los eventos de estado', err)
+  }
+  pending.splice(0).forEach(dispatch)
+  catchingUp = null
+}
+
+function receive(event: StatusEvent) {
+  if (catchingUp) {
+    pending.push(event)
+  } else if (event.seq > lastSeq + 1) {
+    pending.push(event)
+    catchingUp = catchUp()
+  } else {
+    dispatch(event)
+  }
+}
+
+function start(): Promise<void> {
+  started ??= (async () => {
+    const { listen } = await import('@tauri-apps/api/event')
+    await listen<StatusEvent>('status-event', (event) => receive(event.payload))
+    // Historial: lo emitido antes de que la página escuchara
+    if (!catchingUp) catchingUp = catchUp()
+    await catchingUp
+  })().catch((err) => {
+    started = null
+    logError('StatusChannel', 'No se pudo escuchar el canal de estado', err)
+  })
+  return started
+}
+
+/** Recibe los eventos de `topic`; devuelve la función para dejar de recibirlos */
+export function subscribeStatus(topic: StatusTopic, handler: StatusHandler): () => void {
+  if (!handlers.has(topic)) handlers.set(topic, new Set())
+  handlers.get(topic)!.add(handler)
+  if (isTauriEnv) void start()
+  return () => {
+    handlers.get(topic)?.delete(handler)
+  }
+}
@@ -0,0 +1,30 @@
+/**
+ * Progreso del análisis en la barra de tareas / el Dock (ver src-tauri/src/progress.rs).
+ *
+ *   import { setTaskbarProgress } from '@/services/taskbarProgress'
+ *   setTaskbarProgress(40, 'normal')
+ *   setTaskbarProgress(null, 'none')   // quitar el indicador
+ *
+ * Fuera de Tauri no hace nada. Solo se avisa al shell cuando cambia el valor.
+ */
+import { logWarn } from '@/services/logger'
+
+export type TaskbarProgressState = 'none' | 'normal' | 'indeterminate' | 'paused' | 'error'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+let last = 'none:'
+
+export async function setTaskbarProgress(percent: number | null, state: TaskbarProgressState) {
+  if (!isTauriEnv) return
+  const rounded = percent == null ? null : Math.round(percent)
+  const key = `${state}:${rounded ?? ''}`
+  if (key === last) return
+  last = key
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('set_progress', { percent: rounded, state })
+  } catch (err) {
+    logWarn('TaskbarProgress', 'No se pudo actualizar el progreso en la barra de tareas', err)
+  }
+}
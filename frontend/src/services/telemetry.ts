@@ -0,0 +1,35 @@
+/**
+ * Telemetría anónima del shell (ver src-tauri/src/telemetry.rs).
+ *
+ * Los eventos se acumulan en una cola local de agregados; el shell solo los
+ * envía si el usuario lo ha autorizado en Configuración. Fuera de Tauri no hace nada.
+ *
+ *   import { trackFeature } from '@/services/telemetry'
+ *   trackFeature('export.docx')
+ */
+
+type TelemetryEvent =
+  | { type: 'feature_used'; feature: string }
+  | { type: 'analysis_completed'; duration_ms: number }
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+async function record(event: TelemetryEvent) {
+  if (!isTauriEnv) return
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    await invoke('record_telemetry_event', { event })
+  } catch {
+    // La telemetría nunca debe interferir con la app
+  }
+}
+
+/** Uso de una función: nombre estable en minúsculas (`[a-z0-9._-]`) */
+export function trackFeature(feature: string) {
+  void record({ type: 'feature_used', feature })
+}
+
+/** Duración de un análisis terminado */
+export function trackAnalysisCompleted(durationMs: number) {
+  void record({ type: 'analysis_completed', duration_ms: Math.max(0, Math.round(durationMs)) })
+}
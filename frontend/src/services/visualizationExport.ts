@@ -0,0 +1,50 @@
+/**
+ * Exportar el grafo de relaciones o la cronología a SVG/PNG vectorial
+ * (ver src-tauri/src/visualization_export.rs).
+ *
+ *   import { exportVisualization } from '@/services/visualizationExport'
+ *   await exportVisualization('graph', { nodes, edges }, 'grafo-relaciones.svg')
+ *
+ * A diferencia de `captureElementToImage`, no depende de lo que se ve en
+ * pantalla: el shell dibuja la visualización a partir de los datos, a cualquier
+ * tamaño. Solo en la app de escritorio. Devuelve la ruta guardada, o `null` si
+ * el usuario cancela.
+ */
+
+import { pickExportPath } from '@/services/exportPath'
+
+export interface VisualizationGraph {
+  title?: string
+  nodes: { id: string; label: string; x?: number; y?: number; color?: string; size?: number }[]
+  edges: { source: string; target: string; label?: string; color?: string; width?: number; dashed?: boolean }[]
+}
+
+export interface VisualizationTimeline {
+  title?: string
+  events: { label: string; detail?: string; position: number; color?: string }[]
+  ticks?: { position: number; label: string }[]
+}
+
+export async function exportVisualization(kind: 'graph', data: VisualizationGraph, defaultName: string): Promise<string | null>
+export async function exportVisualization(kind: 'timeline', data: VisualizationTimeline, defaultName: string): Promise<string | null>
+export async function exportVisualization(
+  kind: 'graph' | 'timeline',
+  data: VisualizationGraph | VisualizationTimeline,
+  defaultName: string,
+): Promise<string | null> {
+  const path = await pickExportPath({
+    defaultPath: defaultName,
+    filters: [
+      { name: 'Imagen SVG', extensions: ['svg'] },
+      { name: 'Imagen PNG', extensions: ['png'] },
+    ],
+  })
+  if (!path) return null
+  const { invoke } = await import('@tauri-apps/api/core')
+  const result = await invoke<{ path: string; width: number; height: number }>('export_visualization', {
+    kind,
+    data,
+    path,
+  })
+  return result.path
+}
@@ -17,10 +17,26 @@ type BackendStatusPayload = {
   message: string
 }
 
+// Evento del canal de estado del shell (src-tauri/src/status_channel.rs)
+let seq = 0
+
+// `status_replay` responde sin historial; el resto de comandos, con `startBackend`
+function mockInvoke(startBackend: () => Promise<unknown>) {
+  invokeMock.mockImplementation((command: string) =>
+    command === 'status_replay' ? Promise.resolve({ events: [], gap: false }) : startBackend(),
+  )
+}
+function backendEvent(payload: BackendStatusPayload) {
+  seq += 1
+  return { payload: { seq, topic: 'backend', project_id: null, kind: 'status', payload, at: 0 } }
+}
+
 describe('appStore', () => {
   beforeEach(() => {
     vi.resetModules()
     vi.clearAllMocks()
+    seq = 0
+    mockInvoke(async () => undefined)
     setActivePinia(createPinia())
 
     Object.defineProperty(window, '__TAURI__', {
@@ -39,7 +55,8 @@ describe('appStore', () => {
     const appStore = useAppStore()
     const systemStore = useSystemStore()
     await vi.dynamicImportSettled()
-    await Promise.resolve()
+    // El canal de estado pide el historial (`status_replay`) al abrirse
+    await new Promise(resolve => setTimeout(resolve, 0))
     return { appStore, systemStore }
   }
 
@@ -53,12 +70,10 @@ describe('appStore', () => {
     const { systemStore } = await loadStores()
 
     expect(handler).toBeTypeOf('function')
-    handler!({
-      payload: {
-        status: 'running',
-        message: 'Servidor listo',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'running',
+      message: 'Servidor listo',
+    }))
 
     expect(systemStore.backendConnected).toBe(true)
     expect(systemStore.backendStartupError).toBeNull()
@@ -75,12 +90,10 @@ describe('appStore', () => {
     systemStore.backendConnected = true
     systemStore.backendStartupError = 'old error'
 
-    handler!({
-      payload: {
-        status: 'starting',
-        message: 'Iniciando...',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'starting',
+      message: 'Iniciando...',
+    }))
 
     expect(systemStore.backendConnected).toBe(false)
     expect(systemStore.backendStartupError).toBeNull()
@@ -88,12 +101,10 @@ describe('appStore', () => {
     systemStore.backendConnected = true
     systemStore.backendStartupError = 'old error'
 
-    handler!({
-      payload: {
-        status: 'restarting',
-        message: 'Reiniciando...',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'restarting',
+      message: 'Reiniciando...',
+    }))
 
     expect(systemStore.backendConnected).toBe(false)
     expect(systemStore.backendStartupError).toBeNull()
@@ -109,12 +120,10 @@ describe('appStore', () => {
     const { systemStore } = await loadStores()
     const retrySpy = vi.spyOn(systemStore, 'startRetrying').mockImplementation(() => {})
 
-    handler!({
-      payload: {
-        status: 'error',
-        message: 'No se pudo iniciar el backend',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'error',
+      message: 'No se pudo iniciar el backend',
+    }))
 
     expect(systemStore.backendConnected).toBe(false)
     expect(systemStore.backendStartupError).toBe('No se pudo iniciar el backend')
@@ -127,7 +136,7 @@ describe('appStore', () => {
       handler = cb
       return () => {}
     })
-    invokeMock.mockResolvedValue('Backend server started successfully')
+    mockInvoke(async () => 'Backend server started successfully')
 
     const { appStore, systemStore } = await loadStores()
     const retrySpy = vi.spyOn(systemStore, 'startRetrying').mockImplementation(() => {})
@@ -136,48 +145,38 @@ describe('appStore', () => {
     expect(startResult).toBe('Backend server started successfully')
     expect(handler).toBeTypeOf('function')
 
-    handler!({
-      payload: {
-        status: 'starting',
-        message: 'Iniciando...',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'starting',
+      message: 'Iniciando...',
+    }))
     expect(systemStore.backendConnected).toBe(false)
     expect(systemStore.backendStartupError).toBeNull()
 
-    handler!({
-      payload: {
-        status: 'running',
-        message: 'Servidor listo',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'running',
+      message: 'Servidor listo',
+    }))
     expect(systemStore.backendConnected).toBe(true)
     expect(systemStore.backendStartupError).toBeNull()
 
-    handler!({
-      payload: {
-        status: 'restarting',
-        message: 'Reiniciando...',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'restarting',
+      message: 'Reiniciando...',
+    }))
     expect(systemStore.backendConnected).toBe(false)
     expect(systemStore.backendStartupError).toBeNull()
 
-    handler!({
-      payload: {
-        status: 'running',
-        message: 'Servidor listo',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'running',
+      message: 'Servidor listo',
+    }))
     expect(systemStore.backendConnected).toBe(true)
     expect(systemStore.backendStartupError).toBeNull()
 
-    handler!({
-      payload: {
-        status: 'error',
-        message: 'Backend detenido inesperadamente',
-      } satisfies BackendStatusPayload,
-    })
+    handler!(backendEvent({
+      status: 'error',
+      message: 'Backend detenido inesperadamente',
+    }))
     expect(systemStore.backendConnected).toBe(false)
     expect(systemStore.backendStartupError).toBe('Backend detenido inesperadamente')
     expect(retrySpy).toHaveBeenCalledTimes(1)
@@ -185,7 +184,7 @@ describe('appStore', () => {
 
   it('invokes the Tauri command to start the backend server', async () => {
     listenMock.mockResolvedValue(() => {})
-    invokeMock.mockResolvedValue('Backend server started successfully')
+    mockInvoke(async () => 'Backend server started successfully')
 
     const { appStore } = await loadStores()
     const result = await appStore.startBackendServer()
@@ -196,7 +195,9 @@ describe('appStore', () => {
 
   it('maps start_backend_server failures to a user-facing startup error', async () => {
     listenMock.mockResolvedValue(() => {})
-    invokeMock.mockRejectedValue(new Error('spawn failed'))
+    mockInvoke(async () => {
+      throw new Error('spawn failed')
+    })
 
     const { appStore, systemStore } = await loadStores()
     const result = await appStore.startBackendServer()
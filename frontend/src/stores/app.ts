@@ -2,9 +2,9 @@ import { ref } from 'vue'
 import { defineStore } from 'pinia'
 import { useSystemStore } from './system'
 import { logError } from '@/services/logger'
+import { subscribeStatus } from '@/services/statusChannel'
 
 // Tauri imports (only available in Tauri environment)
-let tauriListen: ((event: string, handler: (event: { payload: unknown }) => void) => Promise<() => void>) | null = null
 let tauriInvoke: ((cmd: string) => Promise<string>) | null = null
 const isTauriRuntime =
   typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
@@ -13,11 +13,7 @@ let tauriApisReady: Promise<void> | null = null
 function ensureTauriApis(): Promise<void> {
   if (!isTauriRuntime) return Promise.resolve()
   if (!tauriApisReady) {
-    tauriApisReady = Promise.all([
-      import('@tauri-apps/api/event'),
-      import('@tauri-apps/api/core'),
-    ]).then(([eventModule, coreModule]) => {
-      tauriListen = eventModule.listen
+    tauriApisReady = import('@tauri-apps/api/core').then((coreModule) => {
       tauriInvoke = coreModule.invoke
     })
   }
@@ -27,33 +23,37 @@ function ensureTauriApis(): Promise<void> {
 export const useAppStore = defineStore('app', () => {
   const listenerInitialized = ref(false)
 
-  async function initTauriListener() {
-    if (listenerInitialized.value) return
+  function initTauriListener() {
+    if (listenerInitialized.value || !isTauriRuntime) return
 
-    await ensureTauriApis()
-    if (!tauriListen) return
-
-    try {
-      await tauriListen('backend-status', (event) => {
-        const payload = event.payload as { status: string; message: string }
+    subscribeStatus('backend', (event) => {
+      // `message` ya viene en el idioma de la interfaz; `message_key`/`message_params`
+      // identifican el texto en el catálogo del shell (src-tauri/src/i18n.rs)
+      const payload = event.payload as {
+        status: string
+        message: string
+        message_key?: string
+        message_params?: Record<string, string>
+      }
 
-        const systemStore = useSystemStore()
-        if (payload.status === 'running') {
-          systemStore.backendConnected = true
-          systemStore.backendStartupError = null
-        } else if (payload.status === 'starting' || payload.status === 'restarting') {
-          systemStore.backendConnected = false
-          systemStore.backendStartupError = null
-        } else if (payload.status === 'error') {
-          systemStore.backendConnected = false
-          systemStore.backendStartupError = payload.message
-          systemStore.startRetrying()
-        }
-      })
-      listenerInitialized.value = true
-    } catch (err) {
-      logError('App', 'Failed to listen for Tauri events', err)
-    }
+      const systemStore = useSystemStore()
+      if (payload.status === 'running') {
+        systemStore.backendConnected = true
+        systemStore.backendStartupError = null
+      } else if (payload.status === 'starting' || payload.status === 'restarting') {
+        systemStore.backendConnected = false
+        systemStore.backendStartupError = null
+      } else if (payload.status === 'locked') {
+        // Esperando la contraseña en la ventana de desbloqueo: no es un error
+        systemStore.backendConnected = false
+        systemStore.backendStartupError = null
+      } else if (payload.status === 'error') {
+        systemStore.backendConnected = false
+        systemStore.backendStartupError = payload.message
+        systemStore.startRetrying()
+      }
+    })
+    listenerInitialized.value = true
   }
 
   async function startBackendServer(): Promise<string | null> {
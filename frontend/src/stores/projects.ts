@@ -5,7 +5,55 @@ import type { ApiProject } from '@/types/api'
 import { transformProject, transformProjects } from '@/types/transformers'
 import { api } from '@/services/apiClient'
 import { ensureBackendReady } from '@/composables/useBackendReady'
-import { logError } from '@/services/logger'
+import { logError, logWarn } from '@/services/logger'
+import { advanceOnboarding } from '@/services/onboarding'
+import {
+  listRecentProjects,
+  pinRecentProject,
+  recordRecentProject,
+  removeRecentProject,
+  type RecentProject,
+} from '@/services/recentProjects'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
+/** Proyecto de `list_projects_fast` (src-tauri/src/project_list.rs) */
+interface ProjectSummary {
+  id: number
+  name: string
+  document_path: string | null
+  document_format: string
+  created_at: string | null
+  last_modified: string | null
+  last_opened: string | null
+  analysis_status: ApiProject['analysis_status']
+  word_count: number
+  chapter_count: number
+  last_analysis_at: string | null
+}
+
+/** Proyectos leídos por el shell directamente de SQLite, antes de que arranque el backend */
+async function listProjectsFast(): Promise<Project[] | null> {
+  if (!isTauriEnv) return null
+  try {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const rows = await invoke<ProjectSummary[]>('list_projects_fast')
+    return rows.map(({ last_analysis_at, ...row }) => ({
+      ...transformProject({
+        ...row,
+        description: null,
+        analysis_progress: row.analysis_status === 'completed' ? 100 : 0,
+        entity_count: 0,
+        open_alerts_count: 0,
+        highest_alert_severity: null,
+      }),
+      lastAnalysisAt: last_analysis_at ? new Date(last_analysis_at) : undefined,
+    }))
+  } catch (err) {
+    logWarn('Projects', 'Lista rápida de proyectos no disponible', err)
+    return null
+  }
+}
 
 export const useProjectsStore = defineStore('projects', () => {
   const projects = ref<Project[]>([])
@@ -15,20 +63,57 @@ export const useProjectsStore = defineStore('projects', () => {
 
   const projectCount = computed(() => projects.value.length)
   const hasProjects = computed(() => projectCount.value > 0)
-  const recentProjects = computed(() =>
-    [...projects.value]
+  // Lista del shell (recent_projects.rs); null fuera de Tauri
+  const recentEntries = ref<RecentProject[] | null>(null)
+  const recentProjects = computed(() => {
+    if (recentEntries.value) {
+      const byId = new Map(projects.value.map(p => [p.id, p]))
+      return recentEntries.value
+        .map(entry => byId.get(entry.project_id))
+        .filter((p): p is Project => p !== undefined)
+    }
+    return [...projects.value]
       .sort((a, b) => b.lastModified.getTime() - a.lastModified.getTime())
       .slice(0, 5)
-  )
+  })
+
+  function isPinned(projectId: number) {
+    return recentEntries.value?.some(entry => entry.project_id === projectId && entry.pinned) ?? false
+  }
+
+  function setRecentEntries(entries: RecentProject[] | null) {
+    if (entries) recentEntries.value = entries
+  }
+
+  async function loadRecentProjects() {
+    setRecentEntries(await listRecentProjects())
+  }
+
+  async function pinProject(projectId: number, pinned: boolean) {
+    setRecentEntries(await pinRecentProject(projectId, pinned))
+  }
+
+  async function removeRecent(projectId: number) {
+    setRecentEntries(await removeRecentProject(projectId))
+  }
 
   async function fetchProjects() {
     loading.value = true
     error.value = null
 
     try {
+      // Sin nada que mostrar, la lista rápida del shell evita esperar al backend
+      if (projects.value.length === 0) {
+        const quick = await listProjectsFast()
+        if (quick && quick.length > 0) {
+          projects.value = quick
+          loading.value = false
+        }
+      }
       await ensureBackendReady()
       const data = await api.get<ApiProject[]>('/api/projects')
-      projects.value = transformProjects(data)
+      const lastAnalysis = new Map(projects.value.map(p => [p.id, p.lastAnalysisAt]))
+      projects.value = transformProjects(data).map(p => ({ ...p, lastAnalysisAt: lastAnalysis.get(p.id) }))
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'No se pudo completar la operación. Si persiste, reinicia la aplicación.'
       logError('Projects', 'Failed to fetch projects', err)
@@ -46,6 +131,7 @@ export const useProjectsStore = defineStore('projects', () => {
       const data = await api.get<ApiProject>(`/api/projects/${id}`)
       const transformed = transformProject(data)
       currentProject.value = transformed
+      void recordRecentProject(id, transformed.name, transformed.documentPath).then(setRecentEntries)
 
       const index = projects.value.findIndex(p => p.id === id)
       if (index !== -1) {
@@ -59,7 +145,11 @@ export const useProjectsStore = defineStore('projects', () => {
     }
   }
 
-  async function createProject(name: string, description?: string, file?: File) {
+  /**
+   * Crea un proyecto con el manuscrito subido (`file`) o, en la app de escritorio,
+   * con la ruta local del manuscrito (`filePath`)
+   */
+  async function createProject(name: string, description?: string, file?: File, filePath?: string) {
     loading.value = true
     error.value = null
 
@@ -68,11 +158,13 @@ export const useProjectsStore = defineStore('projects', () => {
       formData.append('name', name)
       if (description) formData.append('description', description)
       if (file) formData.append('file', file)
+      if (filePath) formData.append('file_path', filePath)
 
       const data = await api.postForm<ApiProject>('/api/projects', formData)
       const transformed = transformProject(data)
       projects.value.push(transformed)
       currentProject.value = transformed
+      void advanceOnboarding('first_import')
       return transformed
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'No se pudo completar la operación. Si persiste, reinicia la aplicación.'
@@ -107,6 +199,12 @@ export const useProjectsStore = defineStore('projects', () => {
     projectCount,
     hasProjects,
     recentProjects,
+    recentEntries,
+    isPinned,
+    loadRecentProjects,
+    setRecentEntries,
+    pinProject,
+    removeRecent,
     fetchProjects,
     fetchProject,
     createProject,
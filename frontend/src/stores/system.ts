@@ -23,6 +23,8 @@ export interface LTInstallProgress {
 export type LTState = 'not_installed' | 'installing' | 'installed_not_running' | 'running'
 
 // Download progress info for real-time updates
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
+
 export interface DownloadProgressInfo {
   model_type: string
   phase: string
@@ -321,6 +323,9 @@ export const useSystemStore = defineStore('system', () => {
     modelsDownloading.value = true
     modelsError.value = null
 
+    // En la app el shell descarga los modelos con reanudación (src-tauri/src/downloads.rs)
+    if (isTauriEnv && !force) return downloadModelsWithShell(models)
+
     try {
       await api.post('/api/models/download', { models, force })
       pollModelsStatus()
@@ -333,6 +338,40 @@ export const useSystemStore = defineStore('system', () => {
     return false
   }
 
+  async function downloadModelsWithShell(models: string[]): Promise<boolean> {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const { listen } = await import('@tauri-apps/api/event')
+    const unlisten = await listen<DownloadProgressInfo>('model-download-progress', (event) => {
+      downloadProgress.value = { ...downloadProgress.value, [event.payload.model_type]: event.payload }
+    })
+    const failed: string[] = []
+    try {
+      for (const model of models) {
+        try {
+          await invoke<string>('download_model', { id: model })
+        } catch (err) {
+          // El NER principal puede no estar disponible: se prueba la alternativa
+          const fallback = model === 'transformer_ner'
+            ? await invoke<string>('download_model', { id: 'transformer_ner_fallback' }).then(() => true, () => false)
+            : false
+          if (!fallback) {
+            logError('models', `Error descargando ${model}`, err)
+            failed.push(model)
+          }
+        }
+      }
+      const status = await checkModelsStatus()
+      if (failed.length > 0 && !status?.all_required_installed) {
+        modelsError.value = `Error descargando modelo(s): ${failed.join(', ')}. Verifica tu conexión e intenta de nuevo.`
+        return false
+      }
+      return true
+    } finally {
+      unlisten()
+      modelsDownloading.value = false
+    }
+  }
+
   // Poll models status and download progress while downloading
   let pollInterval: number | null = null
   let progressPollInterval: number | null = null
@@ -64,6 +64,8 @@ export interface ThemeConfig {
 // ============================================================================
 
 const STORAGE_KEY = 'narrative_assistant_theme_config'
+
+const isTauriEnv = typeof window !== 'undefined' && ('__TAURI__' in window || '__TAURI_INTERNALS__' in window)
 const LEGACY_MODE_KEY = 'narrative_assistant_theme'
 
 /** Info de un preset para UI */
@@ -381,6 +383,10 @@ export const useThemeStore = defineStore('theme', () => {
   // System preference detection
   const prefersDark = window.matchMedia('(prefers-color-scheme: dark)')
   const prefersReducedMotion = window.matchMedia('(prefers-reduced-motion: reduce)')
+  // En la app de escritorio la preferencia del sistema la notifica el shell
+  const systemDark = ref(prefersDark.matches)
+  // "Reducir movimiento" del sistema, leído por el shell (ver src-tauri/src/accessibility.rs)
+  const systemReducedMotion = ref(false)
 
   // Computed
   const currentPreset = computed(() => PRESETS[config.value.preset])
@@ -394,7 +400,7 @@ export const useThemeStore = defineStore('theme', () => {
 
   function applyDarkMode() {
     if (config.value.mode === 'auto') {
-      isDark.value = prefersDark.matches
+      isDark.value = systemDark.value
     } else {
       isDark.value = config.value.mode === 'dark'
     }
@@ -408,6 +414,59 @@ export const useThemeStore = defineStore('theme', () => {
 
   }
 
+  /** Aplica el modo a los marcos y menús nativos de las ventanas (solo escritorio) */
+  async function syncWindowTheme() {
+    if (!isTauriEnv) return
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const theme = config.value.mode === 'auto' ? 'system' : config.value.mode
+      await invoke('set_window_theme', { theme })
+    } catch (e) {
+      logWarn('Theme', 'Error applying window theme', e)
+    }
+  }
+
+  /** Sigue la preferencia del sistema que emite el shell */
+  async function listenSystemTheme() {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { listen } = await import('@tauri-apps/api/event')
+      await listen<{ theme: 'light' | 'dark' }>('system-theme-changed', (event) => {
+        onSystemThemeChange(event.payload.theme === 'dark')
+      })
+      onSystemThemeChange((await invoke<'light' | 'dark'>('get_system_theme')) === 'dark')
+    } catch (e) {
+      logWarn('Theme', 'Error reading system theme', e)
+    }
+  }
+
+  /** Sigue "reducir movimiento" del sistema que emite el shell */
+  async function listenAccessibility() {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core')
+      const { listen } = await import('@tauri-apps/api/event')
+      const update = (reduce: boolean) => {
+        systemReducedMotion.value = reduce
+        applyReducedMotion()
+      }
+      await listen<{ reduce_motion: boolean }>('accessibility-changed', (event) => {
+        update(event.payload.reduce_motion)
+      })
+      update((await invoke<{ reduce_motion: boolean }>('get_accessibility_preferences')).reduce_motion)
+    } catch (e) {
+      logWarn('Theme', 'Error reading accessibility preferences', e)
+    }
+  }
+
+  function onSystemThemeChange(dark: boolean) {
+    if (systemDark.value === dark) return
+    systemDark.value = dark
+    if (config.value.mode === 'auto') {
+      applyDarkMode()
+      applyPresetAndColor() // Re-apply colors for new mode
+    }
+  }
+
   /** Verifica si es un preset personalizado */
   function isCustomPreset(preset: ThemePreset): preset is CustomThemePreset {
     return preset in CUSTOM_PRESETS
@@ -476,7 +535,7 @@ export const useThemeStore = defineStore('theme', () => {
   }
 
   function applyReducedMotion() {
-    const shouldReduce = config.value.reducedMotion || prefersReducedMotion.matches
+    const shouldReduce = config.value.reducedMotion || prefersReducedMotion.matches || systemReducedMotion.value
     if (shouldReduce) {
       document.documentElement.classList.add('reduced-motion')
     } else {
@@ -566,6 +625,7 @@ export const useThemeStore = defineStore('theme', () => {
     applyDarkMode()
     applyPresetAndColor() // Re-apply to ensure colors work in new mode
     saveConfig()
+    void syncWindowTheme()
   }
 
   function toggleMode() {
@@ -575,6 +635,7 @@ export const useThemeStore = defineStore('theme', () => {
     applyDarkMode()
     applyPresetAndColor() // Re-apply to ensure colors work in new mode
     saveConfig()
+    void syncWindowTheme()
   }
 
   function setFontSize(size: FontSize) {
@@ -623,6 +684,7 @@ export const useThemeStore = defineStore('theme', () => {
     config.value = { ...DEFAULT_CONFIG }
     applyAllStyles()
     saveConfig()
+    void syncWindowTheme()
   }
 
   // ============================================================================
@@ -634,12 +696,13 @@ export const useThemeStore = defineStore('theme', () => {
     applyAllStyles()
 
     // Listen for system preference changes
-    prefersDark.addEventListener('change', () => {
-      if (config.value.mode === 'auto') {
-        applyDarkMode()
-        applyPresetAndColor() // Re-apply colors for new mode
-      }
-    })
+    if (isTauriEnv) {
+      void listenSystemTheme()
+      void listenAccessibility()
+      void syncWindowTheme()
+    } else {
+      prefersDark.addEventListener('change', () => onSystemThemeChange(prefersDark.matches))
+    }
 
     prefersReducedMotion.addEventListener('change', () => {
       applyReducedMotion()
@@ -70,6 +70,8 @@ export interface Project {
   createdAt: Date
   lastModified: Date
   lastOpened?: Date
+  /** Fin del último análisis completado (solo en la lista rápida del shell) */
+  lastAnalysisAt?: Date
   analysisStatus: AnalysisStatus
   analysisProgress: number
   wordCount: number
@@ -163,15 +163,11 @@ export function useProjectDetailAnalysis(options: UseProjectDetailAnalysisOption
     options.replaceDocumentInputRef.value?.click()
   }
 
-  const onReplaceDocumentSelected = async (event: Event) => {
-    const input = event.target as HTMLInputElement
-    const file = input.files?.[0]
+  const replaceDocument = async (formData: FormData): Promise<boolean> => {
     const project = options.project.value
-    if (!file || !project) return
+    if (!project) return false
 
     try {
-      const formData = new FormData()
-      formData.append('file', file)
       const result = await api.postForm<{
         project_id: number
         classification: string
@@ -189,6 +185,7 @@ export function useProjectDetailAnalysis(options: UseProjectDetailAnalysisOption
       await options.loadChapters(project.id, project)
       options.entities.value = []
       options.alerts.value = []
+      return true
     } catch (err) {
       const detail = err instanceof Error
         ? err.message
@@ -199,11 +196,31 @@ export function useProjectDetailAnalysis(options: UseProjectDetailAnalysisOption
         detail,
         life: 5000,
       })
+      return false
+    }
+  }
+
+  const onReplaceDocumentSelected = async (event: Event) => {
+    const input = event.target as HTMLInputElement
+    const file = input.files?.[0]
+    if (!file || !options.project.value) return
+
+    try {
+      const formData = new FormData()
+      formData.append('file', file)
+      await replaceDocument(formData)
     } finally {
       input.value = ''
     }
   }
 
+  /** Reimporta el manuscrito desde su ruta (modificado fuera de la app) */
+  const reimportDocument = async (filePath: string): Promise<boolean> => {
+    const formData = new FormData()
+    formData.append('file_path', filePath)
+    return replaceDocument(formData)
+  }
+
   return {
     reanalyzing,
     retryingTimeline,
@@ -211,5 +228,6 @@ export function useProjectDetailAnalysis(options: UseProjectDetailAnalysisOption
     startReanalysis,
     openUpdateDocumentDialog,
     onReplaceDocumentSelected,
+    reimportDocument,
   }
 }
@@ -0,0 +1,76 @@
+#!/usr/bin/env python3
+"""
+Prepara un paquete offline de modelos NLP para equipos sin internet.
+
+El paquete se importa en la app con import_model_bundle
+(src-tauri/src/model_bundle.rs). Se genera en un equipo con los modelos ya
+descargados por la app (las revisiones pinneadas de
+src-tauri/resources/model_manifest.json); la app rechaza modelos de otra
+revisión.
+
+Contenido del .zip:
+    bundle.json          {"format": 1, "models": [{"id", "revision", "files": [...]}]}
+    models/<id>/...      ficheros del directorio instalado de cada modelo
+
+Uso:
+    python scripts/build_model_bundle.py modelos-offline.zip
+    python scripts/build_model_bundle.py modelos.zip --models-dir DIR
+    python scripts/build_model_bundle.py modelos.zip --only spacy --only embeddings
+
+Variables de entorno:
+    NA_MODELS_DIR: Directorio alternativo para modelos (default: ~/.narrative_assistant/models/)
+"""
+
+import argparse
+import json
+import os
+import sys
+import zipfile
+from pathlib import Path
+
+from generate_model_files_manifest import MODEL_MANIFEST, model_files
+
+BUNDLE_FORMAT = 1
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__, formatter_class=argparse.RawDescriptionHelpFormatter)
+    default_dir = os.getenv("NA_MODELS_DIR") or str(Path.home() / ".narrative_assistant" / "models")
+    parser.add_argument("output", help="Fichero .zip a crear")
+    parser.add_argument("--models-dir", default=default_dir, help="Directorio de modelos instalados")
+    parser.add_argument("--only", action="append", help="Id del manifiesto a incluir (repetible)")
+    args = parser.parse_args()
+
+    models_dir = Path(args.models_dir)
+    manifest = json.loads(MODEL_MANIFEST.read_text(encoding="utf-8"))
+    bundled = []
+
+    # Los modelos ya están comprimidos: se guardan sin comprimir (ZIP64 para >4 GB)
+    with zipfile.ZipFile(args.output, "w", compression=zipfile.ZIP_STORED, allowZip64=True) as bundle:
+        for model in manifest["models"]:
+            if model.get("subdirectory") is None or (args.only and model["id"] not in args.only):
+                continue
+            model_dir = models_dir / model["subdirectory"] / (model.get("local_name") or model["name"])
+            if not model_dir.is_dir():
+                print(f"  [omitido] {model['id']}: no instalado en {model_dir}")
+                continue
+            files = model_files(model_dir)
+            for entry in files:
+                bundle.write(model_dir / entry["path"], f"models/{model['id']}/{entry['path']}")
+            bundled.append({"id": model["id"], "revision": model.get("revision"), "files": files})
+            size_mb = sum(entry["size"] for entry in files) / (1024 * 1024)
+            print(f"  [ok] {model['id']}: {len(files)} ficheros ({size_mb:.0f} MB)")
+
+        bundle.writestr("bundle.json", json.dumps({"format": BUNDLE_FORMAT, "models": bundled}, indent=2))
+
+    if not bundled:
+        Path(args.output).unlink()
+        print("No hay modelos instalados que empaquetar", file=sys.stderr)
+        return 1
+
+    print(f"Paquete escrito en {args.output}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
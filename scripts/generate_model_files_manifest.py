@@ -0,0 +1,80 @@
+#!/usr/bin/env python3
+"""
+Genera src-tauri/resources/model_files.json a partir de una instalación de
+modelos verificada.
+
+El shell compara los modelos instalados con este manifiesto (verify_models en
+src-tauri/src/integrity.rs). Se genera tras descargar los modelos con las
+revisiones pinneadas de src-tauri/resources/model_manifest.json y se firma en
+la release con `cargo tauri signer sign`.
+
+Uso:
+    python scripts/generate_model_files_manifest.py                 # ~/.narrative_assistant/models
+    python scripts/generate_model_files_manifest.py --models-dir DIR
+    python scripts/generate_model_files_manifest.py --only spacy --only embeddings
+
+Variables de entorno:
+    NA_MODELS_DIR: Directorio alternativo para modelos (default: ~/.narrative_assistant/models/)
+"""
+
+import argparse
+import hashlib
+import json
+import os
+import sys
+from pathlib import Path
+
+PROJECT_ROOT = Path(__file__).parent.parent
+MODEL_MANIFEST = PROJECT_ROOT / "src-tauri" / "resources" / "model_manifest.json"
+OUTPUT = PROJECT_ROOT / "src-tauri" / "resources" / "model_files.json"
+
+# Marcadores que escriben el backend y el shell (no forman parte del modelo)
+MARKERS = {".version", ".files.json"}
+
+
+def sha256_of(path: Path) -> str:
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for chunk in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+def model_files(model_dir: Path) -> list[dict]:
+    entries = []
+    for path in sorted(p for p in model_dir.rglob("*") if p.is_file()):
+        relative = path.relative_to(model_dir).as_posix()
+        if relative in MARKERS:
+            continue
+        entries.append({"path": relative, "size": path.stat().st_size, "sha256": sha256_of(path)})
+    return entries
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__, formatter_class=argparse.RawDescriptionHelpFormatter)
+    default_dir = os.getenv("NA_MODELS_DIR") or str(Path.home() / ".narrative_assistant" / "models")
+    parser.add_argument("--models-dir", default=default_dir, help="Directorio de modelos instalados")
+    parser.add_argument("--only", action="append", help="Id del manifiesto a incluir (repetible)")
+    args = parser.parse_args()
+
+    models_dir = Path(args.models_dir)
+    manifest = json.loads(MODEL_MANIFEST.read_text(encoding="utf-8"))
+    result: dict[str, list[dict]] = {}
+
+    for model in manifest["models"]:
+        if model.get("subdirectory") is None or (args.only and model["id"] not in args.only):
+            continue
+        model_dir = models_dir / model["subdirectory"] / (model.get("local_name") or model["name"])
+        if not model_dir.is_dir():
+            print(f"  [omitido] {model['id']}: no instalado en {model_dir}")
+            continue
+        result[model["id"]] = model_files(model_dir)
+        print(f"  [ok] {model['id']}: {len(result[model['id']])} ficheros")
+
+    OUTPUT.write_text(json.dumps({"version": 1, "models": result}, indent=2) + "\n", encoding="utf-8")
+    print(f"Manifiesto escrito en {OUTPUT}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
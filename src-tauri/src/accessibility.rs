@@ -0,0 +1,253 @@
+// Preferencias de accesibilidad del sistema
+//
+// Los webviews no aplican el tamaño de texto del sistema (Windows "Tamaño del
+// texto", GNOME "Factor de escala del texto") ni todos exponen
+// `prefers-reduced-motion`. El shell los lee del sistema:
+//   - Windows: HKCU\Software\Microsoft\Accessibility\TextScaleFactor y las
+//     animaciones del cliente (SPI_GETCLIENTAREAANIMATION)
+//   - macOS: "Reducir movimiento" (com.apple.universalaccess); macOS no tiene
+//     un tamaño de texto global para las apps
+//   - Linux: org.gnome.desktop.interface text-scaling-factor y enable-animations
+//
+// `get_accessibility_preferences` los devuelve y un monitor emite
+// `accessibility-changed` cuando cambian. El zoom de las ventanas es
+// `Settings::ui_scale` si el usuario eligió uno con `set_ui_scale`, o el
+// tamaño de texto del sistema si no.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+
+use crate::settings::SettingsStore;
+
+/// Límites del zoom de las ventanas
+const MIN_UI_SCALE: f64 = 0.5;
+const MAX_UI_SCALE: f64 = 3.0;
+
+/// Cada cuánto se vuelven a leer las preferencias del sistema
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Preferencias del sistema y zoom aplicado (payload de `accessibility-changed`)
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct AccessibilityPreferences {
+    /// Factor de tamaño de texto del sistema (1.0 = normal)
+    pub text_scale: f64,
+    /// El usuario ha pedido reducir las animaciones
+    pub reduce_motion: bool,
+    /// Zoom aplicado a las ventanas
+    pub ui_scale: f64,
+}
+
+/// Últimas preferencias leídas, registrado con `.manage()`
+pub struct AccessibilityState(Mutex<Option<AccessibilityPreferences>>);
+
+impl AccessibilityState {
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Guarda las preferencias; devuelve `true` si cambiaron
+    fn record(&self, preferences: AccessibilityPreferences) -> bool {
+        let mut last = self.0.lock().unwrap();
+        let changed = *last != Some(preferences);
+        *last = Some(preferences);
+        changed
+    }
+}
+
+#[cfg(windows)]
+fn system_preferences() -> (Option<f64>, Option<bool>) {
+    use ::windows::core::{w, BOOL};
+    use ::windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+    use ::windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    // Porcentaje (100-225); no existe si nunca se ha cambiado
+    let mut percent: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    // SAFETY: `percent` y `size` son válidos y del tamaño de un REG_DWORD
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Accessibility"),
+            w!("TextScaleFactor"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut percent as *mut u32 as *mut _),
+            Some(&mut size),
+        )
+    };
+    let text_scale = status.is_ok().then(|| f64::from(percent) / 100.0);
+
+    let mut animations = BOOL(1);
+    // SAFETY: SPI_GETCLIENTAREAANIMATION escribe un BOOL en `animations`
+    let reduce_motion = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut animations as *mut BOOL as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .ok()
+    .map(|_| !animations.as_bool());
+    (text_scale, reduce_motion)
+}
+
+#[cfg(target_os = "macos")]
+fn system_preferences() -> (Option<f64>, Option<bool>) {
+    let reduce_motion = std::process::Command::new("defaults")
+        .args(["read", "com.apple.universalaccess", "reduceMotion"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "1");
+    (Some(1.0), reduce_motion)
+}
+
+#[cfg(target_os = "linux")]
+fn system_preferences() -> (Option<f64>, Option<bool>) {
+    let gsetting = |key: &str| {
+        std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", key])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let text_scale = gsetting("text-scaling-factor").and_then(|value| value.parse().ok());
+    let reduce_motion = gsetting("enable-animations").map(|value| value == "false");
+    (text_scale, reduce_motion)
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn system_preferences() -> (Option<f64>, Option<bool>) {
+    (None, None)
+}
+
+/// Zoom de las ventanas: el elegido por el usuario o el tamaño de texto del sistema
+fn effective_scale(chosen: Option<f64>, text_scale: f64) -> f64 {
+    chosen
+        .unwrap_or(text_scale)
+        .clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+}
+
+/// Lee las preferencias del sistema (sin datos: tamaño normal y con animaciones)
+fn read(app: &AppHandle) -> AccessibilityPreferences {
+    let (text_scale, reduce_motion) = system_preferences();
+    let text_scale = text_scale
+        .filter(|scale| scale.is_finite() && *scale > 0.0)
+        .unwrap_or(1.0);
+    let chosen = app.state::<SettingsStore>().get().ui_scale;
+    AccessibilityPreferences {
+        text_scale,
+        reduce_motion: reduce_motion.unwrap_or(false),
+        ui_scale: effective_scale(chosen, text_scale),
+    }
+}
+
+/// Aplica el zoom actual a una ventana recién creada
+pub(crate) fn apply_to(window: &WebviewWindow) {
+    let state = window.state::<AccessibilityState>();
+    let scale = state.0.lock().unwrap().map_or(1.0, |p| p.ui_scale);
+    if (scale - 1.0).abs() > f64::EPSILON {
+        if let Err(e) = window.set_zoom(scale) {
+            tracing::warn!(target: "accessibility", "No se pudo aplicar el zoom a {}: {}", window.label(), e);
+        }
+    }
+}
+
+fn apply_all(app: &AppHandle, scale: f64) {
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.set_zoom(scale) {
+            tracing::warn!(target: "accessibility", "No se pudo aplicar el zoom a {}: {}", window.label(), e);
+        }
+    }
+}
+
+/// Relee las preferencias; si cambiaron, aplica el zoom y emite `accessibility-changed`
+fn refresh(app: &AppHandle, preferences: AccessibilityPreferences) {
+    let state = app.state::<AccessibilityState>();
+    let previous = *state.0.lock().unwrap();
+    if !state.record(preferences) {
+        return;
+    }
+    if previous.map(|p| p.ui_scale) != Some(preferences.ui_scale) {
+        apply_all(app, preferences.ui_scale);
+    }
+    tracing::info!(
+        target: "accessibility",
+        "Texto del sistema x{:.2}, reducir movimiento: {}, zoom x{:.2}",
+        preferences.text_scale,
+        preferences.reduce_motion,
+        preferences.ui_scale
+    );
+    let _ = app.emit("accessibility-changed", preferences);
+}
+
+/// Lee las preferencias periódicamente (la primera vez, al arrancar)
+pub async fn monitor(app: AppHandle) {
+    loop {
+        let handle = app.clone();
+        if let Ok(preferences) = tauri::async_runtime::spawn_blocking(move || read(&handle)).await {
+            refresh(&app, preferences);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Tamaño de texto y movimiento del sistema, y zoom aplicado
+#[tauri::command]
+pub async fn get_accessibility_preferences(
+    app: AppHandle,
+) -> Result<AccessibilityPreferences, String> {
+    let handle = app.clone();
+    let preferences = tauri::async_runtime::spawn_blocking(move || read(&handle))
+        .await
+        .map_err(|e| e.to_string())?;
+    refresh(&app, preferences);
+    Ok(preferences)
+}
+
+/// Fija el zoom de las ventanas (`None` = seguir el tamaño de texto del sistema)
+#[tauri::command]
+pub async fn set_ui_scale(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    scale: Option<f64>,
+) -> Result<AccessibilityPreferences, String> {
+    if let Some(scale) = scale {
+        if !(MIN_UI_SCALE..=MAX_UI_SCALE).contains(&scale) {
+            return Err(format!(
+                "El zoom debe estar entre {} y {}",
+                MIN_UI_SCALE, MAX_UI_SCALE
+            ));
+        }
+    }
+    let updated = store.update(serde_json::json!({ "ui_scale": scale }))?;
+    let _ = app.emit("shell-settings-changed", &updated);
+    get_accessibility_preferences(app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chosen_scale_overrides_system_text_scale_within_limits() {
+        assert_eq!(effective_scale(None, 1.25), 1.25);
+        assert_eq!(effective_scale(Some(1.5), 1.25), 1.5);
+        assert_eq!(effective_scale(None, 9.0), MAX_UI_SCALE);
+
+        let state = AccessibilityState::new();
+        let preferences = AccessibilityPreferences {
+            text_scale: 1.25,
+            reduce_motion: true,
+            ui_scale: 1.25,
+        };
+        assert!(state.record(preferences));
+        assert!(!state.record(preferences));
+    }
+}
@@ -0,0 +1,528 @@
+// Manuscrito anotado en DOCX para Narrative Assistant
+//
+// Muchos autores solo trabajan en Word: en vez de pasarles el informe, el
+// editor les entrega el manuscrito con cada alerta como comentario de Word
+// anclado al fragmento que la provoca. `export_annotated_docx` recibe los
+// capítulos (`/api/projects/{id}/chapters`) y las alertas abiertas
+// (`/api/projects/{id}/alerts`) tal como los devuelve el backend y escribe un
+// DOCX nuevo (no toca el original, que puede no ser un DOCX).
+//
+// - Un párrafo por línea del capítulo; el capítulo va como "Heading 1" (en
+//   página nueva) para que el panel de navegación de Word lo liste.
+// - Las posiciones `start_char`/`end_char` de las alertas son caracteres del
+//   texto completo, como `position_start` de los capítulos: cada alerta se
+//   ancla a ese rango, aunque cruce párrafos.
+// - Una alerta sin posición se ancla al título de su capítulo y una sin
+//   capítulo al título del documento; se cuentan en `approximate_count`.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Seek, Write};
+use std::path::PathBuf;
+use tauri::State;
+use zip::write::SimpleFileOptions;
+
+use crate::export_scope::ExportGrants;
+use crate::report_pdf;
+
+const COMMENT_AUTHOR: &str = "Narrative Assistant";
+const COMMENT_INITIALS: &str = "NA";
+
+const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/><Override PartName="/word/comments.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.comments+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+const DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="comments.xml"/></Relationships>"#;
+
+/// Estilos mínimos: texto a 12 pt con interlineado 1,5, título, capítulo y comentarios
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Times New Roman" w:hAnsi="Times New Roman" w:cs="Times New Roman"/><w:sz w:val="24"/><w:lang w:val="es-ES"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="120" w:line="360" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style><w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="480"/><w:jc w:val="center"/></w:pPr><w:rPr><w:b/><w:sz w:val="40"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="240"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="CommentText"><w:name w:val="annotation text"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr><w:rPr><w:sz w:val="20"/></w:rPr></w:style><w:style w:type="character" w:styleId="CommentReference"><w:name w:val="annotation reference"/><w:rPr><w:sz w:val="16"/></w:rPr></w:style></w:styles>"#;
+
+/// A4 con márgenes de 2,5 cm
+const SECTION: &str = r#"<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1418" w:right="1418" w:bottom="1418" w:left="1418" w:header="708" w:footer="708" w:gutter="0"/></w:sectPr>"#;
+
+/// Capítulo de `/api/projects/{id}/chapters`
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ManuscriptChapter {
+    pub chapter_number: u32,
+    pub title: String,
+    pub content: String,
+    /// Carácter del texto completo donde empieza `content`
+    pub position_start: Option<usize>,
+}
+
+/// Alerta de `/api/projects/{id}/alerts`
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ManuscriptAlert {
+    pub severity: String,
+    pub title: String,
+    pub description: String,
+    pub suggestion: Option<String>,
+    pub chapter: Option<u32>,
+    pub start_char: Option<usize>,
+    pub end_char: Option<usize>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct AnnotatedManuscript {
+    pub title: String,
+    pub chapters: Vec<ManuscriptChapter>,
+    pub alerts: Vec<ManuscriptAlert>,
+}
+
+/// Resultado de `export_annotated_docx`
+#[derive(Serialize, Clone, Debug)]
+pub struct AnnotatedDocx {
+    pub path: String,
+    pub comment_count: usize,
+    /// Comentarios anclados a un título por no tener posición
+    pub approximate_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Title,
+    Heading,
+    Normal,
+}
+
+/// Párrafo del documento
+struct Paragraph {
+    style: Style,
+    page_break: bool,
+    chapter: Option<u32>,
+    chars: Vec<char>,
+    /// Carácter del texto completo donde empieza (`None` en títulos añadidos)
+    start: Option<usize>,
+    /// `(posición en el párrafo, es inicio, id del comentario)`
+    marks: Vec<(usize, bool, usize)>,
+}
+
+impl Paragraph {
+    fn new(style: Style, chapter: Option<u32>, text: &str, start: Option<usize>) -> Self {
+        Self {
+            style,
+            page_break: false,
+            chapter,
+            chars: text.chars().collect(),
+            start,
+            marks: Vec::new(),
+        }
+    }
+
+    fn end(&self) -> Option<usize> {
+        self.start.map(|start| start + self.chars.len())
+    }
+}
+
+/// Párrafos de los capítulos, con su posición en el texto completo
+fn paragraphs(manuscript: &AnnotatedManuscript) -> Vec<Paragraph> {
+    let title = match manuscript.title.trim() {
+        "" => "Manuscrito",
+        title => title,
+    };
+    let mut paragraphs = vec![Paragraph::new(Style::Title, None, title, None)];
+    for (index, chapter) in manuscript.chapters.iter().enumerate() {
+        let first = paragraphs.len();
+        let mut offset = 0;
+        for line in chapter.content.split('\n') {
+            let length = line.chars().count();
+            let leading = line.chars().take_while(|c| c.is_whitespace()).count();
+            let text = line.trim();
+            if !text.is_empty() {
+                let start = chapter.position_start.map(|p| p + offset + leading);
+                paragraphs.push(Paragraph::new(
+                    Style::Normal,
+                    Some(chapter.chapter_number),
+                    text,
+                    start,
+                ));
+            }
+            offset += length + 1;
+        }
+        // El capítulo suele empezar por su título; si no, se añade
+        let heading = chapter.title.trim();
+        let starts_with_title = paragraphs
+            .get(first)
+            .is_some_and(|p| p.chars.iter().collect::<String>() == heading);
+        if starts_with_title {
+            paragraphs[first].style = Style::Heading;
+        } else {
+            let heading = match heading {
+                "" => format!("Capítulo {}", chapter.chapter_number),
+                heading => heading.to_string(),
+            };
+            paragraphs.insert(
+                first,
+                Paragraph::new(Style::Heading, Some(chapter.chapter_number), &heading, None),
+            );
+        }
+        paragraphs[first].page_break = index > 0;
+    }
+    paragraphs
+}
+
+/// Ancla el comentario `id` a su rango; `false` si va a un título por no tener posición
+fn anchor(paragraphs: &mut [Paragraph], alert: &ManuscriptAlert, id: usize) -> bool {
+    let located = match (alert.start_char, alert.end_char) {
+        (Some(start), Some(end)) if end > start => {
+            let first = paragraphs
+                .iter()
+                .position(|p| p.end().is_some_and(|p_end| p_end > start));
+            let last = paragraphs
+                .iter()
+                .rposition(|p| p.start.is_some_and(|p_start| p_start < end));
+            match (first, last) {
+                (Some(first), Some(last)) if first <= last => {
+                    let from = start.saturating_sub(paragraphs[first].start.unwrap_or(0));
+                    let to = (end - paragraphs[last].start.unwrap_or(0))
+                        .min(paragraphs[last].chars.len());
+                    Some((first, from, last, to))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    let (first, from, last, to, exact) = match located {
+        Some((first, from, last, to)) => (first, from, last, to, true),
+        None => {
+            let heading = alert
+                .chapter
+                .and_then(|n| {
+                    paragraphs
+                        .iter()
+                        .position(|p| p.style == Style::Heading && p.chapter == Some(n))
+                })
+                .unwrap_or(0);
+            (heading, 0, heading, paragraphs[heading].chars.len(), false)
+        }
+    };
+    paragraphs[first].marks.push((from, true, id));
+    paragraphs[last].marks.push((to, false, id));
+    exact
+}
+
+/// Texto apto para XML (sin caracteres de control, que Word rechaza)
+fn xml_text(text: &str) -> String {
+    let clean: String = text
+        .chars()
+        .map(|c| if c == '\t' { ' ' } else { c })
+        .filter(|c| !c.is_control())
+        .collect();
+    quick_xml::escape::escape(clean.as_str()).into_owned()
+}
+
+fn run(xml: &mut String, text: &str, bold: bool) {
+    if text.is_empty() {
+        return;
+    }
+    xml.push_str("<w:r>");
+    if bold {
+        xml.push_str("<w:rPr><w:b/></w:rPr>");
+    }
+    xml.push_str(&format!(
+        r#"<w:t xml:space="preserve">{}</w:t></w:r>"#,
+        xml_text(text)
+    ));
+}
+
+fn paragraph_xml(xml: &mut String, paragraph: &mut Paragraph) {
+    xml.push_str("<w:p>");
+    let style = match paragraph.style {
+        Style::Title => Some("Title"),
+        Style::Heading => Some("Heading1"),
+        Style::Normal => None,
+    };
+    if style.is_some() || paragraph.page_break {
+        xml.push_str("<w:pPr>");
+        if let Some(style) = style {
+            xml.push_str(&format!(r#"<w:pStyle w:val="{}"/>"#, style));
+        }
+        if paragraph.page_break {
+            xml.push_str("<w:pageBreakBefore/>");
+        }
+        xml.push_str("</w:pPr>");
+    }
+    // En la misma posición, los finales antes que los inicios
+    paragraph
+        .marks
+        .sort_by_key(|&(at, is_start, _)| (at, is_start));
+    let mut cursor = 0;
+    for &(at, is_start, id) in &paragraph.marks {
+        let text: String = paragraph.chars[cursor..at].iter().collect();
+        run(xml, &text, false);
+        cursor = at;
+        if is_start {
+            xml.push_str(&format!(r#"<w:commentRangeStart w:id="{}"/>"#, id));
+        } else {
+            xml.push_str(&format!(
+                r#"<w:commentRangeEnd w:id="{id}"/><w:r><w:rPr><w:rStyle w:val="CommentReference"/></w:rPr><w:commentReference w:id="{id}"/></w:r>"#
+            ));
+        }
+    }
+    let rest: String = paragraph.chars[cursor..].iter().collect();
+    run(xml, &rest, false);
+    xml.push_str("</w:p>");
+}
+
+/// Comentario: severidad y título en negrita, explicación y sugerencia
+fn comment_xml(xml: &mut String, alert: &ManuscriptAlert, id: usize) {
+    xml.push_str(&format!(
+        r#"<w:comment w:id="{}" w:author="{}" w:initials="{}">"#,
+        id, COMMENT_AUTHOR, COMMENT_INITIALS
+    ));
+    let heading = format!(
+        "{}: {}",
+        report_pdf::severity_label(&alert.severity),
+        alert.title.trim()
+    );
+    let mut lines = vec![(heading, true)];
+    let description = alert.description.trim();
+    if !description.is_empty() && description != alert.title.trim() {
+        lines.push((description.to_string(), false));
+    }
+    if let Some(suggestion) = alert.suggestion.as_deref().map(str::trim) {
+        if !suggestion.is_empty() {
+            lines.push((format!("Sugerencia: {}", suggestion), false));
+        }
+    }
+    for (index, (text, bold)) in lines.iter().enumerate() {
+        xml.push_str(r#"<w:p><w:pPr><w:pStyle w:val="CommentText"/></w:pPr>"#);
+        if index == 0 {
+            xml.push_str(r#"<w:r><w:rPr><w:rStyle w:val="CommentReference"/></w:rPr><w:annotationRef/></w:r>"#);
+        }
+        run(xml, text, *bold);
+        xml.push_str("</w:p>");
+    }
+    xml.push_str("</w:comment>");
+}
+
+/// Escribe el paquete DOCX en `writer`; devuelve `(comentarios, aproximados)`
+fn write_docx<W: Write + Seek>(
+    manuscript: &AnnotatedManuscript,
+    writer: W,
+) -> Result<(usize, usize), String> {
+    let mut paragraphs = paragraphs(manuscript);
+    let mut comments = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:comments xmlns:w="{}">"#,
+        W_NS
+    );
+    let mut approximate = 0;
+    for (id, alert) in manuscript.alerts.iter().enumerate() {
+        if !anchor(&mut paragraphs, alert, id) {
+            approximate += 1;
+        }
+        comment_xml(&mut comments, alert, id);
+    }
+    comments.push_str("</w:comments>");
+
+    let mut document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="{}"><w:body>"#,
+        W_NS
+    );
+    for paragraph in &mut paragraphs {
+        paragraph_xml(&mut document, paragraph);
+    }
+    document.push_str(SECTION);
+    document.push_str("</w:body></w:document>");
+
+    let core = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>{}</dc:title><dc:creator>{}</dc:creator></cp:coreProperties>"#,
+        xml_text(&manuscript.title),
+        COMMENT_AUTHOR
+    );
+
+    let error = |e: zip::result::ZipError| format!("Error generando el DOCX: {}", e);
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", PACKAGE_RELS),
+        ("docProps/core.xml", &core),
+        ("word/_rels/document.xml.rels", DOCUMENT_RELS),
+        ("word/styles.xml", STYLES),
+        ("word/comments.xml", &comments),
+        ("word/document.xml", &document),
+    ];
+    for (name, content) in parts {
+        zip.start_file(name, options).map_err(error)?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| format!("Error generando el DOCX: {}", e))?;
+    }
+    zip.finish().map_err(error)?;
+    Ok((manuscript.alerts.len(), approximate))
+}
+
+/// Ruta final: absoluta, con extensión `.docx` y en un directorio existente
+fn resolve_output_path(output_path: &str) -> Result<PathBuf, String> {
+    let mut path = PathBuf::from(output_path.trim());
+    if !path.is_absolute() {
+        return Err("La ruta del documento debe ser absoluta".into());
+    }
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("docx"))
+    {
+        path.set_extension("docx");
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(format!(
+            "No existe la carpeta de destino de {}",
+            path.display()
+        )),
+    }
+}
+
+/// Escribe el manuscrito con las alertas como comentarios de Word
+#[tauri::command]
+pub async fn export_annotated_docx(
+    grants: State<'_, ExportGrants>,
+    manuscript: AnnotatedManuscript,
+    output_path: String,
+) -> Result<AnnotatedDocx, String> {
+    let path = resolve_output_path(&output_path)?;
+    grants.authorize(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let file = File::create(&path)
+            .map_err(|e| format!("No se pudo crear {}: {}", path.display(), e))?;
+        let (comment_count, approximate_count) = write_docx(&manuscript, file)?;
+        tracing::info!(
+            target: "annotated_docx",
+            "{} comentarios ({} aproximados) exportados a {}",
+            comment_count,
+            approximate_count,
+            path.display()
+        );
+        Ok(AnnotatedDocx {
+            path: path.to_string_lossy().into_owned(),
+            comment_count,
+            approximate_count,
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de exportacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    fn manuscript() -> AnnotatedManuscript {
+        let chapter = |n: u32, title: &str, content: &str, start: usize| ManuscriptChapter {
+            chapter_number: n,
+            title: title.into(),
+            content: content.into(),
+            position_start: Some(start),
+        };
+        let alert =
+            |title: &str, chapter: Option<u32>, range: Option<(usize, usize)>| ManuscriptAlert {
+                severity: "warning".into(),
+                title: title.into(),
+                description: "Ana tiene los ojos verdes en el capítulo 1.".into(),
+                suggestion: Some("Unificar el color de ojos".into()),
+                chapter,
+                start_char: range.map(|r| r.0),
+                end_char: range.map(|r| r.1),
+            };
+        let first = "Capítulo 1\nAna & Luis llegaron.\n\n  Sus ojos azules brillaban.";
+        AnnotatedManuscript {
+            title: "El faro".into(),
+            chapters: vec![
+                chapter(1, "Capítulo 1", first, 0),
+                chapter(2, "La tormenta", "Llovía.", first.chars().count() + 1),
+            ],
+            alerts: vec![
+                // "ojos azules"
+                alert("Color de ojos", Some(1), Some((39, 50))),
+                // "Luis llegaron.\n\n  Sus": cruza párrafos
+                alert("Rango largo", Some(1), Some((17, 38))),
+                alert("Sin posición", Some(2), None),
+                alert("General", None, None),
+            ],
+        }
+    }
+
+    #[test]
+    fn alerts_are_anchored_to_their_ranges() {
+        let manuscript = manuscript();
+        let mut paragraphs = paragraphs(&manuscript);
+        let texts: Vec<String> = paragraphs
+            .iter()
+            .map(|p| p.chars.iter().collect())
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "El faro",
+                "Capítulo 1",
+                "Ana & Luis llegaron.",
+                "Sus ojos azules brillaban.",
+                "La tormenta",
+                "Llovía."
+            ]
+        );
+        assert_eq!(paragraphs[1].style, Style::Heading);
+        assert!(paragraphs[4].page_break);
+
+        let exact: Vec<bool> = manuscript
+            .alerts
+            .iter()
+            .enumerate()
+            .map(|(id, alert)| anchor(&mut paragraphs, alert, id))
+            .collect();
+        assert_eq!(exact, [true, true, false, false]);
+        assert_eq!(paragraphs[3].marks[0], (4, true, 0));
+        assert_eq!(paragraphs[3].marks[1], (15, false, 0));
+        assert_eq!(paragraphs[2].marks, [(6, true, 1)]);
+        assert_eq!(paragraphs[3].marks[2], (3, false, 1));
+        // Sin posición: al título del capítulo 2; sin capítulo: al del documento
+        assert_eq!(paragraphs[4].marks, [(0, true, 2), (11, false, 2)]);
+        assert_eq!(paragraphs[0].marks, [(0, true, 3), (7, false, 3)]);
+
+        let mut xml = String::new();
+        paragraph_xml(&mut xml, &mut paragraphs[3]);
+        assert!(xml
+            .contains(r#"<w:t xml:space="preserve">Sus</w:t></w:r><w:commentRangeEnd w:id="1"/>"#));
+        assert!(xml.contains(
+            r#"<w:commentRangeStart w:id="0"/><w:r><w:t xml:space="preserve">ojos azules</w:t></w:r><w:commentRangeEnd w:id="0"/>"#
+        ));
+    }
+
+    #[test]
+    fn package_has_comments_and_escaped_text() {
+        let mut buffer = Cursor::new(Vec::new());
+        assert_eq!(write_docx(&manuscript(), &mut buffer).unwrap(), (4, 2));
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        let mut read = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        let comments = read("word/comments.xml");
+        assert_eq!(comments.matches("<w:comment ").count(), 4);
+        assert!(comments.contains("Aviso: Color de ojos"));
+        assert!(comments.contains("Sugerencia: Unificar el color de ojos"));
+        let document = read("word/document.xml");
+        assert!(document.contains(">Ana &amp; </w:t>"));
+        assert_eq!(document.matches("<w:commentReference ").count(), 4);
+    }
+}
@@ -0,0 +1,191 @@
+// Registro de operaciones destructivas en Narrative Assistant
+//
+// Ante un "la aplicación me borró el proyecto" solo quedaba shell.log, que
+// rota y mezcla estas operaciones con todo lo demás. Ahora cada borrado de
+// categoría de datos (`delete_data_category`), restauración de snapshot,
+// eliminación de proyecto (DELETE /api/projects/<id> por `backend_request`) y
+// restablecimiento de la configuración añade una línea JSON a
+// ~/.narrative_assistant/logs/audit.jsonl: cuándo, quién (usuario del
+// sistema), qué y cuántos bytes, y si salió bien.
+//
+// El fichero solo se abre en modo append y no rota ni se recorta (son pocas
+// operaciones). Está en logs/, que no borra ninguna categoría de
+// `delete_data_category`. `get_audit_log` lo devuelve, lo más reciente primero,
+// y el informe para soporte (feedback.rs) lo incluye.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::logging;
+
+/// Nombre del fichero del registro (en logging::log_dir)
+pub const AUDIT_FILE_NAME: &str = "audit.jsonl";
+
+/// Entradas que devuelve `get_audit_log` si no se indica otro límite
+const DEFAULT_LIMIT: usize = 200;
+
+/// Una escritura a la vez (las líneas no se intercalan)
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Una operación registrada
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Segundos UNIX
+    pub timestamp: u64,
+    /// Usuario del sistema
+    pub user: String,
+    /// `delete_data_category`, `restore_snapshot`, `delete_project`, `reset_settings`
+    pub action: String,
+    /// Sobre qué: categoría, snapshot o proyecto
+    pub target: String,
+    /// Tamaño de lo borrado o sustituido, si se conoce
+    pub bytes: Option<u64>,
+    /// `None` si salió bien; si no, el error
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(action: &str, target: impl Into<String>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            user: current_user(),
+            action: action.into(),
+            target: target.into(),
+            bytes: None,
+            error: None,
+        }
+    }
+
+    pub fn bytes(mut self, bytes: u64) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    /// Resultado de la operación
+    pub fn outcome<T, E: ToString>(mut self, result: &Result<T, E>) -> Self {
+        self.error = result.as_ref().err().map(ToString::to_string);
+        self
+    }
+}
+
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "desconocido".into())
+}
+
+fn audit_path() -> Option<PathBuf> {
+    logging::log_dir().map(|dir| dir.join(AUDIT_FILE_NAME))
+}
+
+fn append_to(path: &Path, entry: &AuditEntry) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Error creando {}: {}", dir.display(), e))?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Error abriendo {}: {}", path.display(), e))?;
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))
+}
+
+/// Añade `entry` al registro. Un fallo se anota en shell.log pero no detiene la operación.
+pub fn record(entry: AuditEntry) {
+    tracing::info!(
+        target: "audit",
+        "{} {} ({})",
+        entry.action,
+        entry.target,
+        entry.error.as_deref().unwrap_or("ok")
+    );
+    let Some(path) = audit_path() else {
+        return;
+    };
+    if let Err(e) = append_to(&path, &entry) {
+        tracing::warn!(target: "audit", "{}", e);
+    }
+}
+
+/// Las últimas `limit` entradas de `path`, la más reciente primero (omite líneas dañadas)
+fn read_from(path: &Path, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Error leyendo {}: {}", path.display(), e))?;
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect())
+}
+
+/// Registro de operaciones destructivas, lo más reciente primero
+#[tauri::command]
+pub async fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    let path = audit_path().ok_or("No se pudo determinar el directorio home")?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || read_from(&path, limit))
+        .await
+        .map_err(|e| format!("Error leyendo el registro: {}", e))?
+}
+
+/// El frontend restableció su configuración (vive en el localStorage del webview)
+#[tauri::command]
+pub fn record_settings_reset() {
+    record(AuditEntry::new("reset_settings", "frontend"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_appended_and_read_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join(AUDIT_FILE_NAME);
+
+        let deleted: Result<(), String> = Ok(());
+        append_to(
+            &path,
+            &AuditEntry::new("delete_data_category", "user_data")
+                .bytes(4096)
+                .outcome(&deleted),
+        )
+        .unwrap();
+        let failed: Result<(), String> = Err("disco lleno".into());
+        append_to(
+            &path,
+            &AuditEntry::new("restore_snapshot", "narrative_assistant-1.db").outcome(&failed),
+        )
+        .unwrap();
+        // Una línea dañada (p. ej. un corte a media escritura) no impide leer el resto
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"timestamp\": 1").unwrap();
+
+        let entries = read_from(&path, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "restore_snapshot");
+        assert_eq!(entries[0].error.as_deref(), Some("disco lleno"));
+        assert_eq!(entries[1].bytes, Some(4096));
+        assert_eq!(entries[1].error, None);
+        assert_eq!(read_from(&path, 1).unwrap().len(), 1);
+        assert!(read_from(&dir.path().join("otro.jsonl"), 10)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+// Arranque al iniciar sesión de Narrative Assistant
+//
+// `set_autostart` registra la app en el mecanismo de inicio de cada sistema
+// (plugin autostart: LaunchAgent en macOS, clave `Run` del registro en
+// Windows, `~/.config/autostart` en Linux), con el argumento `--autostart`
+// para distinguir ese arranque del manual.
+//
+// Con `start_minimized`, un arranque al iniciar sesión no muestra la ventana
+// de carga ni la principal: la app queda en la bandeja con el backend
+// cargando, lista para abrirla al momento. Sin icono de bandeja (algunos
+// escritorios Linux) se muestra siempre, para no dejar la app inaccesible.
+//
+// El registro del sistema manda sobre `settings.launch_at_login`: si el
+// usuario quita la app del inicio desde el sistema, la preferencia se
+// corrige al arrancar.
+
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::settings::SettingsStore;
+use crate::tray;
+
+/// Argumento con el que el sistema lanza la app al iniciar sesión
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// Si el proceso lo ha lanzado el inicio de sesión
+fn launched_at_login(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// Si este arranque debe quedarse en la bandeja sin mostrar ventanas
+pub fn start_hidden(app: &AppHandle) -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    launched_at_login(&args)
+        && app.state::<SettingsStore>().get().start_minimized
+        && tray::is_available(app)
+}
+
+/// Alinea `settings.launch_at_login` con el registro real del sistema
+pub fn setup(app: &AppHandle) {
+    let enabled = match app.autolaunch().is_enabled() {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            tracing::warn!(target: "autostart", "No se pudo consultar el inicio automatico: {}", e);
+            return;
+        }
+    };
+    let store = app.state::<SettingsStore>();
+    if store.get().launch_at_login != enabled {
+        if let Err(e) = store.update(serde_json::json!({ "launch_at_login": enabled })) {
+            tracing::warn!(target: "autostart", "{}", e);
+        }
+    }
+}
+
+/// Activa o desactiva el arranque al iniciar sesión y, opcionalmente,
+/// si ese arranque se queda en la bandeja
+#[tauri::command]
+pub fn set_autostart(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+    start_minimized: Option<bool>,
+) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    }
+    .map_err(|e| format!("No se pudo cambiar el inicio automatico: {}", e))?;
+
+    let mut patch = serde_json::json!({ "launch_at_login": enabled });
+    if let Some(start_minimized) = start_minimized {
+        patch["start_minimized"] = start_minimized.into();
+    }
+    let updated = store.update(patch)?;
+    tracing::info!(
+        target: "autostart",
+        "Inicio automatico {} (minimizado: {})",
+        if enabled { "activado" } else { "desactivado" },
+        updated.start_minimized
+    );
+    let _ = app.emit("shell-settings-changed", &updated);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_autostart_flag_marks_a_login_launch() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(launched_at_login(&args(&[
+            "narrative-assistant",
+            "--autostart"
+        ])));
+        assert!(!launched_at_login(&args(&["narrative-assistant"])));
+        assert!(!launched_at_login(&args(&[
+            "narrative-assistant",
+            "/libros/novela.nra"
+        ])));
+        // El propio ejecutable no cuenta aunque se llame así
+        assert!(!launched_at_login(&args(&["--autostart"])));
+    }
+}
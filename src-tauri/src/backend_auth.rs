@@ -0,0 +1,202 @@
+// Aislamiento del backend local en Narrative Assistant
+//
+// El backend Python escucha en 127.0.0.1:8008 sin autenticación: cualquier
+// proceso de la máquina (o de la red, si por error escuchara en todas las
+// interfaces) podía leer los manuscritos a través de la API. Ahora:
+//
+// - El shell genera un token aleatorio por arranque, se lo pasa al backend en
+//   `NA_SESSION_TOKEN` y al frontend con `get_backend_session_token`. El
+//   backend (api-server/middleware/session_token.py) rechaza con 401 toda
+//   petición sin la cabecera `X-NA-Session-Token`, salvo /api/health.
+// - Un segundo token, `NA_SHELL_TOKEN`, no sale del shell: el backend lo exige
+//   en las rutas que escriben en una ruta elegida por el cliente (save-file).
+//   Solo lo añade `with_shell_token`: `backend_request` tras comprobar las
+//   carpetas concedidas (export_scope.rs) y el propio shell en sus
+//   exportaciones. Un `fetch` del webview no basta.
+// - Tras lanzar el backend, `verify_backend` comprueba que no acepta
+//   conexiones en las interfaces de red del equipo y que exige el token. Si
+//   falla algo, el shell lo mata y no sigue.
+//
+// Las llamadas del shell al backend usan `backend_client`, que ya lleva el
+// token de sesión; `proxy::local_client` queda para otros servicios locales (Ollama).
+
+#[cfg(any(not(debug_assertions), test))]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::sync::OnceLock;
+#[cfg(any(not(debug_assertions), test))]
+use std::time::Duration;
+
+use crate::downloads;
+#[cfg(not(debug_assertions))]
+use crate::proxy;
+use crate::settings::DEFAULT_BACKEND_PORT;
+
+/// Variable de entorno con la que el backend recibe el token
+#[cfg(not(debug_assertions))]
+pub const TOKEN_ENV: &str = "NA_SESSION_TOKEN";
+
+/// Cabecera HTTP del token (TOKEN_HEADER en session_token.py)
+pub const TOKEN_HEADER: &str = "X-NA-Session-Token";
+
+/// Variable de entorno con la que el backend recibe el token del shell
+#[cfg(not(debug_assertions))]
+pub const SHELL_TOKEN_ENV: &str = "NA_SHELL_TOKEN";
+
+/// Cabecera HTTP del token del shell (SHELL_TOKEN_HEADER en session_token.py)
+const SHELL_TOKEN_HEADER: &str = "X-NA-Shell-Token";
+
+/// Endpoint protegido con el que se comprueba que el backend exige el token
+#[cfg(not(debug_assertions))]
+const PROTECTED_PROBE_PATH: &str = "/api/projects";
+
+/// Espera máxima al intentar conectar por una interfaz de red
+#[cfg(any(not(debug_assertions), test))]
+const EXPOSURE_PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// 256 bits aleatorios del sistema operativo, en hexadecimal
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("el sistema no ofrece numeros aleatorios");
+    downloads::to_hex(&bytes)
+}
+
+/// URL de `path` (p. ej. `/api/health`) en el backend local. El servidor
+/// Python escucha en un puerto fijo (`settings::DEFAULT_BACKEND_PORT`): todas
+/// las URL del backend en el shell salen de aquí.
+pub fn backend_url(path: &str) -> String {
+    format!("http://127.0.0.1:{}{}", DEFAULT_BACKEND_PORT, path)
+}
+
+/// Token de esta sesión del shell (64 caracteres hexadecimales)
+pub fn session_token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(random_token)
+}
+
+/// Token que solo conocen el shell y el backend (nunca se entrega al frontend)
+pub fn shell_token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(random_token)
+}
+
+/// Cliente para el backend local: sin proxy y con el token de sesión
+pub fn backend_client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(
+                TOKEN_HEADER,
+                reqwest::header::HeaderValue::from_static(session_token()),
+            );
+            reqwest::Client::builder()
+                .no_proxy()
+                .default_headers(headers)
+                .build()
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+/// Añade el token del shell a una petición ya autorizada por el propio shell
+pub fn with_shell_token(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    request.header(SHELL_TOKEN_HEADER, shell_token())
+}
+
+/// Dirección local con la que el sistema saldría hacia `remote` (no envía nada)
+#[cfg(any(not(debug_assertions), test))]
+fn route_address(bind: IpAddr, remote: SocketAddr) -> Option<IpAddr> {
+    let socket = UdpSocket::bind(SocketAddr::new(bind, 0)).ok()?;
+    socket.connect(remote).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// Direcciones de red del equipo (IPv4 e IPv6) en las que `port` acepta conexiones
+#[cfg(any(not(debug_assertions), test))]
+fn exposed_addresses(port: u16) -> Vec<SocketAddr> {
+    // Direcciones de documentación (RFC 5737 / RFC 3849): solo sirven para elegir ruta
+    let candidates = [
+        route_address(
+            Ipv4Addr::UNSPECIFIED.into(),
+            SocketAddr::new(Ipv4Addr::new(192, 0, 2, 1).into(), 9),
+        ),
+        route_address(
+            Ipv6Addr::UNSPECIFIED.into(),
+            SocketAddr::new("2001:db8::1".parse::<Ipv6Addr>().unwrap().into(), 9),
+        ),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .map(|ip| SocketAddr::new(ip, port))
+        .filter(|addr| TcpStream::connect_timeout(addr, EXPOSURE_PROBE_TIMEOUT).is_ok())
+        .collect()
+}
+
+/// Comprueba que el backend solo escucha en loopback y que exige el token de sesión
+#[cfg(not(debug_assertions))]
+pub async fn verify_backend() -> Result<(), String> {
+    let exposed = tauri::async_runtime::spawn_blocking(|| exposed_addresses(DEFAULT_BACKEND_PORT))
+        .await
+        .map_err(|e| format!("Error comprobando el backend: {}", e))?;
+    if let Some(addr) = exposed.first() {
+        tracing::error!(target: "backend_auth", "El backend acepta conexiones en {}", addr);
+        return Err(format!(
+            "El backend es accesible desde la red ({}); solo debe escuchar en 127.0.0.1",
+            addr
+        ));
+    }
+
+    let response = proxy::local_client()
+        .get(backend_url(PROTECTED_PROBE_PATH))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("No se pudo comprobar el token del backend: {}", e))?;
+    if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+        tracing::error!(
+            target: "backend_auth",
+            "El backend responde {} sin token de sesion",
+            response.status()
+        );
+        return Err("El backend no exige el token de sesion".into());
+    }
+    tracing::info!(target: "backend_auth", "Backend limitado a 127.0.0.1 y con token de sesion");
+    Ok(())
+}
+
+/// Token de sesión para las peticiones del frontend al backend
+#[tauri::command]
+pub fn get_backend_session_token() -> String {
+    session_token().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn loopback_listener_is_not_exposed() {
+        let token = session_token();
+        assert_eq!(token.len(), 64);
+        assert_eq!(token, get_backend_session_token());
+        // El token del shell es otro y no se entrega al frontend
+        assert_eq!(shell_token().len(), 64);
+        assert_ne!(shell_token(), token);
+
+        let local = TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(exposed_addresses(local.local_addr().unwrap().port()).is_empty());
+
+        // Sin interfaz de red (p. ej. un contenedor aislado) no hay nada que sondear
+        let all = TcpListener::bind("0.0.0.0:0").unwrap();
+        let port = all.local_addr().unwrap().port();
+        if let Some(ip) = route_address(
+            Ipv4Addr::UNSPECIFIED.into(),
+            SocketAddr::new(Ipv4Addr::new(192, 0, 2, 1).into(), 9),
+        ) {
+            assert!(exposed_addresses(port).contains(&SocketAddr::new(ip, port)));
+        }
+    }
+}
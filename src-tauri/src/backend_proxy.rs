@@ -0,0 +1,507 @@
+// Peticiones del frontend al backend a través del shell en Narrative Assistant
+//
+// El frontend llamaba al backend con `fetch` directamente: cada petición tenía
+// que llevar el token de sesión, los reintentos los decidía cada store y un
+// fallo de red llegaba como un `TypeError` genérico, igual si el backend se
+// estaba reiniciando que si la petición era inválida.
+//
+// En la app de escritorio, services/httpTransport.ts envía las peticiones JSON
+// por `backend_request`: el shell añade el token (`backend_auth`), aplica el
+// tiempo máximo, reintenta mientras el backend no acepta conexiones (p. ej.
+// durante un reinicio del watchdog) y devuelve el error con un código
+// (`BackendErrorCode`). La URL del backend la pone el shell
+// (`backend_auth::backend_url`): el frontend no sabe nada del puerto.
+//
+// Los métodos no idempotentes (POST, PUT, PATCH, DELETE) solo se reintentan si
+// la conexión ni siquiera se estableció: así no se repite una petición que el
+// backend pudo haber procesado. Las subidas de archivos (`FormData`) siguen
+// yendo por `fetch`.
+//
+// La eliminación de un proyecto (DELETE /api/projects/<id>) se anota en el
+// registro de operaciones destructivas (audit.rs). El destino de
+// POST /api/projects/<id>/save-file, que escribe el backend, tiene que estar
+// en una carpeta de exportación elegida por el usuario (export_scope.rs). El
+// backend solo acepta esa ruta con el token del shell (backend_auth.rs), así
+// que el frontend no puede saltarse la comprobación llamándola con `fetch`.
+// Las rutas se comprueban ya decodificadas y en forma canónica (`api_route`),
+// como las enruta el backend: `save-file/` o `%37` no esquivan la comprobación.
+
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tauri::State;
+
+use crate::audit::{self, AuditEntry};
+use crate::backend_auth;
+use crate::export_scope::ExportGrants;
+use crate::ipc_scope;
+
+/// Tiempo máximo de una petición si el frontend no indica otro
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tiempo máximo admitido (análisis y exportaciones largas)
+const MAX_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Reintentos tras el primer intento fallido
+const MAX_RETRIES: u32 = 3;
+
+/// Espera antes del primer reintento; se dobla en cada uno
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Causa de un fallo de `backend_request`
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendErrorCode {
+    /// El backend no acepta conexiones (arrancando, reiniciándose o caído)
+    Unreachable,
+    /// El backend no respondió a tiempo
+    Timeout,
+    /// Método o ruta no válidos (no se llegó a enviar)
+    InvalidRequest,
+    /// La conexión se cortó o la respuesta no se pudo leer
+    Transport,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BackendError {
+    pub code: BackendErrorCode,
+    pub message: String,
+}
+
+impl BackendError {
+    fn new(code: BackendErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn from_reqwest(error: &reqwest::Error) -> Self {
+        let code = if error.is_timeout() {
+            BackendErrorCode::Timeout
+        } else if error.is_connect() {
+            BackendErrorCode::Unreachable
+        } else {
+            BackendErrorCode::Transport
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+/// Respuesta del backend tal cual (el frontend la convierte en un `Response`)
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BackendResponse {
+    pub status: u16,
+    /// Cabeceras que usa el frontend (tipo de contenido y nombre de archivo)
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    /// Si `body` va en base64 (contenido binario, p. ej. un .docx exportado)
+    pub base64: bool,
+}
+
+/// Cabeceras de la respuesta que se devuelven al frontend
+const FORWARDED_HEADERS: &[&str] = &["content-type", "content-disposition"];
+
+/// Decodifica los `%XX` de una ruta (como hace el backend antes de enrutar)
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Ruta de la API tal como la enruta el backend: decodificada, sin consulta y
+/// sin barra final. Solo se admiten rutas relativas al backend bajo /api y en
+/// forma canónica (sin fragmento, `//`, `.` ni `..`), para que las
+/// comprobaciones de `forward` vean la misma ruta que el backend.
+fn api_route(path: &str) -> Result<String, BackendError> {
+    let invalid = || {
+        BackendError::new(
+            BackendErrorCode::InvalidRequest,
+            format!("Ruta no valida para el backend: {}", path),
+        )
+    };
+    if path.contains('#') || path.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return Err(invalid());
+    }
+    let raw = path.split('?').next().unwrap_or_default();
+    let route = percent_decode(raw).ok_or_else(invalid)?;
+    let route = route.strip_suffix('/').unwrap_or(&route);
+    let mut segments = route.split('/');
+    let canonical = segments.next() == Some("")
+        && segments.next() == Some("api")
+        && segments.all(|s| !s.is_empty() && s != "." && s != ".." && !s.contains('\\'));
+    if canonical {
+        Ok(route.to_string())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Id del proyecto si la petición lo elimina (`route` viene de `api_route`)
+fn deleted_project<'a>(method: &reqwest::Method, route: &'a str) -> Option<&'a str> {
+    let id = route.strip_prefix("/api/projects/")?;
+    (*method == reqwest::Method::DELETE && !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        .then_some(id)
+}
+
+/// Si la petición guarda un proyecto en disco (SHELL_ONLY_PATHS en session_token.py)
+fn is_save_file(method: &reqwest::Method, route: &str) -> bool {
+    *method == reqwest::Method::POST
+        && route
+            .strip_prefix("/api/projects/")
+            .and_then(|p| p.strip_suffix("/save-file"))
+            .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// El destino de un guardado de proyecto por el backend tiene que estar
+/// concedido. Devuelve si la petición es un guardado autorizado (solo entonces
+/// lleva el token del shell).
+fn check_save_destination(
+    grants: &ExportGrants,
+    method: &reqwest::Method,
+    route: &str,
+    body: Option<&serde_json::Value>,
+) -> Result<bool, BackendError> {
+    if !is_save_file(method, route) {
+        return Ok(false);
+    }
+    let destination = body
+        .and_then(|body| body["file_path"].as_str())
+        .ok_or_else(|| BackendError::new(BackendErrorCode::InvalidRequest, "Falta file_path"))?;
+    grants
+        .authorize(Path::new(destination))
+        .map_err(|e| BackendError::new(BackendErrorCode::InvalidRequest, e))?;
+    Ok(true)
+}
+
+fn is_idempotent(method: &reqwest::Method) -> bool {
+    matches!(
+        *method,
+        reqwest::Method::GET | reqwest::Method::HEAD | reqwest::Method::OPTIONS
+    )
+}
+
+/// Si un intento fallido con `code` se puede repetir sin riesgo de duplicar la petición
+fn should_retry(method: &reqwest::Method, code: BackendErrorCode) -> bool {
+    match code {
+        BackendErrorCode::Unreachable => true,
+        BackendErrorCode::Timeout | BackendErrorCode::Transport => is_idempotent(method),
+        BackendErrorCode::InvalidRequest => false,
+    }
+}
+
+fn is_text(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    mime.starts_with("text/") || mime == "application/json" || mime.ends_with("+json")
+}
+
+async fn read_response(response: reqwest::Response) -> Result<BackendResponse, BackendError> {
+    let status = response.status().as_u16();
+    let headers: HashMap<String, String> = FORWARDED_HEADERS
+        .iter()
+        .filter_map(|name| {
+            let value = response.headers().get(*name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect();
+    let text = headers
+        .get("content-type")
+        .is_none_or(|content_type| is_text(content_type));
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| BackendError::from_reqwest(&e))?;
+    let (body, base64) = if text {
+        (String::from_utf8_lossy(&bytes).into_owned(), false)
+    } else {
+        (
+            base64::engine::general_purpose::STANDARD.encode(&bytes),
+            true,
+        )
+    };
+    Ok(BackendResponse {
+        status,
+        headers,
+        body,
+        base64,
+    })
+}
+
+/// Envía una petición a la API del backend con el token de sesión y reintentos
+#[tauri::command]
+pub async fn backend_request(
+    webview: tauri::Webview,
+    grants: State<'_, ExportGrants>,
+    method: String,
+    path: String,
+    body: Option<serde_json::Value>,
+    headers: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+) -> Result<BackendResponse, BackendError> {
+    forward(
+        webview.label(),
+        &grants,
+        method,
+        path,
+        body,
+        headers,
+        timeout_ms,
+    )
+    .await
+}
+
+async fn forward(
+    label: &str,
+    grants: &ExportGrants,
+    method: String,
+    path: String,
+    body: Option<serde_json::Value>,
+    headers: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+) -> Result<BackendResponse, BackendError> {
+    let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| BackendError::new(BackendErrorCode::InvalidRequest, "Metodo no valido"))?;
+    let route = api_route(&path)?;
+    // Las ventanas de proyecto no eliminan proyectos (ipc_scope.rs)
+    if deleted_project(&method, &route).is_some() {
+        ipc_scope::authorize(label, ipc_scope::DELETE_PROJECT)
+            .map_err(|e| BackendError::new(BackendErrorCode::InvalidRequest, e))?;
+    }
+    let authorized_save = check_save_destination(grants, &method, &route, body.as_ref())?;
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT)
+        .min(MAX_TIMEOUT);
+
+    let result = send(
+        &method,
+        &path,
+        body.as_ref(),
+        headers.as_ref(),
+        timeout,
+        authorized_save,
+    )
+    .await;
+    if let Some(id) = deleted_project(&method, &route) {
+        let outcome = match &result {
+            Ok(response) if (200..300).contains(&response.status) => Ok(()),
+            Ok(response) => Err(format!("HTTP {}", response.status)),
+            Err(e) => Err(e.message.clone()),
+        };
+        audit::record(
+            AuditEntry::new("delete_project", format!("project {}", id)).outcome(&outcome),
+        );
+    }
+    result
+}
+
+async fn send(
+    method: &reqwest::Method,
+    path: &str,
+    body: Option<&serde_json::Value>,
+    headers: Option<&HashMap<String, String>>,
+    timeout: Duration,
+    shell_token: bool,
+) -> Result<BackendResponse, BackendError> {
+    let mut attempt = 0;
+    loop {
+        let mut request = backend_auth::backend_client()
+            .request(method.clone(), backend_auth::backend_url(path))
+            .timeout(timeout);
+        for (name, value) in headers.into_iter().flatten() {
+            request = request.header(name, value);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        if shell_token {
+            request = backend_auth::with_shell_token(request);
+        }
+
+        let error = match request.send().await {
+            Ok(response) => match read_response(response).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            },
+            Err(e) => BackendError::from_reqwest(&e),
+        };
+        if attempt >= MAX_RETRIES || !should_retry(method, error.code) {
+            tracing::warn!(
+                target: "backend_proxy",
+                "{} {} fallo ({:?}): {}",
+                method,
+                path,
+                error.code,
+                error.message
+            );
+            return Err(error);
+        }
+        tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_safe_failures_are_retried() {
+        let post = reqwest::Method::POST;
+        let get = reqwest::Method::GET;
+        // Sin conexión la petición no llegó: se puede repetir siempre
+        assert!(should_retry(&post, BackendErrorCode::Unreachable));
+        assert!(should_retry(&get, BackendErrorCode::Timeout));
+        assert!(!should_retry(&post, BackendErrorCode::Timeout));
+        assert!(!should_retry(&post, BackendErrorCode::Transport));
+        assert!(!should_retry(&get, BackendErrorCode::InvalidRequest));
+
+        assert_eq!(
+            api_route("/api/projects/3?x=1%2F").unwrap(),
+            "/api/projects/3"
+        );
+        assert_eq!(
+            api_route("/api/projects/3/characters/Mar%C3%ADa/").unwrap(),
+            "/api/projects/3/characters/María"
+        );
+        assert!(api_route("http://example.com/api/projects").is_err());
+        assert!(api_route("/etc/passwd").is_err());
+        for path in [
+            "/api//projects/3",
+            "/api/projects/3#x",
+            "/api/projects/./3",
+            "/api/projects/%2E%2E/3",
+            "/api/projects/3%2",
+            "/api/projects/%FF",
+        ] {
+            assert!(api_route(path).is_err(), "{}", path);
+        }
+        let delete = reqwest::Method::DELETE;
+        assert_eq!(deleted_project(&delete, "/api/projects/12"), Some("12"));
+        assert_eq!(
+            deleted_project(&delete, "/api/projects/12/glossary/3"),
+            None
+        );
+        assert_eq!(deleted_project(&get, "/api/projects/12"), None);
+        assert!(is_text("application/json; charset=utf-8"));
+        assert!(!is_text(
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_are_rejected_before_sending() {
+        let dir = tempfile::tempdir().unwrap();
+        let grants = ExportGrants::load(dir.path().join("export_grants.json"));
+        // Fuera de /api/ no se envía nada
+        let error = forward(
+            "main",
+            &grants,
+            "GET".into(),
+            "/health".into(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.code, BackendErrorCode::InvalidRequest);
+        let error = forward(
+            "main",
+            &grants,
+            "NO VALE".into(),
+            "/api/health".into(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.code, BackendErrorCode::InvalidRequest);
+        // Una ventana de proyecto no elimina proyectos
+        let error = forward(
+            "project-3",
+            &grants,
+            "DELETE".into(),
+            "/api/projects/3/".into(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.code, BackendErrorCode::InvalidRequest);
+        // El backend no guarda proyectos fuera de las carpetas elegidas
+        let body = serde_json::json!({ "file_path": dir.path().join("p.nra") });
+        let error = forward(
+            "main",
+            &grants,
+            "POST".into(),
+            "/api/projects/7/save-file".into(),
+            Some(body),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.code, BackendErrorCode::InvalidRequest);
+    }
+
+    #[tokio::test]
+    async fn save_file_variants_are_checked_like_the_canonical_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let grants = ExportGrants::load(dir.path().join("export_grants.json"));
+        let body = serde_json::json!({ "file_path": dir.path().join("p.nra") });
+        let post = reqwest::Method::POST;
+        // Todas las formas que el backend enruta a save-file pasan por las carpetas concedidas
+        for path in [
+            "/api/projects/7/save-file/",
+            "/api/projects/7/save-file?x=1",
+            "/api/projects/%37/save-file",
+            "/api/projects/7/save%2Dfile",
+        ] {
+            let route = api_route(path).unwrap();
+            assert!(is_save_file(&post, &route), "{}", path);
+            let error = forward(
+                "main",
+                &grants,
+                "POST".into(),
+                path.into(),
+                Some(body.clone()),
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(error.code, BackendErrorCode::InvalidRequest, "{}", path);
+        }
+        // Las que no son canónicas no llegan al backend
+        for path in [
+            "/api/projects/7/save-file#x",
+            "/api/projects//7/save-file",
+            "/api/projects/7/x/../save-file",
+        ] {
+            assert!(api_route(path).is_err(), "{}", path);
+        }
+        // Sin guardado autorizado no se añade el token del shell
+        assert!(!check_save_destination(&grants, &post, "/api/projects/7", Some(&body)).unwrap());
+        grants.grant(dir.path()).unwrap();
+        assert!(
+            check_save_destination(&grants, &post, "/api/projects/7/save-file", Some(&body))
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// Contador de alertas en el Dock y la barra de tareas
+//
+// `set_badge_count` muestra el número de alertas de coherencia sin resolver
+// del proyecto abierto, para que quien tenga la app minimizada vea que hay
+// algo que revisar. El frontend lo actualiza al cargar alertas nuevas y lo
+// pone a 0 al cerrar el proyecto.
+//
+// - macOS (Dock) y Linux (lanzadores compatibles con Unity, vía el
+//   `.desktop` de la app): contador nativo de Tauri.
+// - Windows: la barra de tareas no tiene contador; se dibuja un icono
+//   superpuesto (un círculo rojo con el número, "9+" a partir de 10).
+
+use tauri::{AppHandle, Manager};
+
+use crate::windows::MAIN_WINDOW;
+
+/// Lado del icono superpuesto (Windows lo escala a 16 px lógicos)
+#[cfg_attr(not(windows), allow(dead_code))]
+const OVERLAY_SIZE: u32 = 32;
+
+/// Color del círculo (rojo de las alertas críticas del frontend)
+#[cfg_attr(not(windows), allow(dead_code))]
+const BADGE_RGB: [u8; 3] = [0xd3, 0x2f, 0x2f];
+
+/// Glifos de 3x5 píxeles (cada fila, 3 bits de izquierda a derecha)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        _ => [0b000, 0b010, 0b111, 0b010, 0b000], // '+'
+    }
+}
+
+/// Texto del contador en el icono superpuesto
+#[cfg_attr(not(windows), allow(dead_code))]
+fn overlay_text(count: u32) -> String {
+    if count > 9 {
+        "9+".into()
+    } else {
+        count.to_string()
+    }
+}
+
+/// Icono superpuesto RGBA (`OVERLAY_SIZE` x `OVERLAY_SIZE`) con el contador
+#[cfg_attr(not(windows), allow(dead_code))]
+fn overlay_icon(count: u32) -> Vec<u8> {
+    let size = OVERLAY_SIZE as usize;
+    let mut rgba = vec![0u8; size * size * 4];
+
+    // Círculo con el borde suavizado
+    let center = size as f32 / 2.0;
+    for y in 0..size {
+        for x in 0..size {
+            let distance =
+                ((x as f32 + 0.5 - center).powi(2) + (y as f32 + 0.5 - center).powi(2)).sqrt();
+            let coverage = (center - distance + 0.5).clamp(0.0, 1.0);
+            let pixel = &mut rgba[(y * size + x) * 4..][..4];
+            pixel[..3].copy_from_slice(&BADGE_RGB);
+            pixel[3] = (coverage * 255.0).round() as u8;
+        }
+    }
+
+    // Texto en blanco, centrado, con píxeles de glifo de `scale` x `scale`
+    let text: Vec<char> = overlay_text(count).chars().collect();
+    let scale = if text.len() == 1 { 4 } else { 3 };
+    let width = text.len() * 3 * scale + (text.len() - 1) * scale;
+    let height = 5 * scale;
+    let (left, top) = ((size - width) / 2, (size - height) / 2);
+    for (i, c) in text.iter().enumerate() {
+        let origin = left + i * 4 * scale;
+        for (row, bits) in glyph(*c).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let (x, y) = (origin + col * scale + dx, top + row * scale + dy);
+                        rgba[(y * size + x) * 4..][..4].copy_from_slice(&[255, 255, 255, 255]);
+                    }
+                }
+            }
+        }
+    }
+    rgba
+}
+
+#[cfg(windows)]
+fn apply(window: &tauri::WebviewWindow, count: u32) -> tauri::Result<()> {
+    let icon = (count > 0)
+        .then(|| tauri::image::Image::new_owned(overlay_icon(count), OVERLAY_SIZE, OVERLAY_SIZE));
+    window.set_overlay_icon(icon)
+}
+
+#[cfg(not(windows))]
+fn apply(window: &tauri::WebviewWindow, count: u32) -> tauri::Result<()> {
+    window.set_badge_count((count > 0).then_some(count as i64))
+}
+
+/// Muestra `count` alertas pendientes en el icono de la app (0 lo quita)
+#[tauri::command]
+pub fn set_badge_count(app: AppHandle, count: u32) -> Result<(), String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW)
+        .ok_or("Ventana principal no disponible")?;
+    apply(&window, count).map_err(|e| format!("No se pudo actualizar el contador: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_draws_white_count_on_red_circle() {
+        assert_eq!(overlay_text(0), "0");
+        assert_eq!(overlay_text(7), "7");
+        assert_eq!(overlay_text(42), "9+");
+
+        let icon = overlay_icon(1);
+        let size = OVERLAY_SIZE as usize;
+        assert_eq!(icon.len(), size * size * 4);
+        let pixel = |x: usize, y: usize| &icon[(y * size + x) * 4..][..4];
+        // Esquina transparente, borde rojo y trazo vertical del "1" en blanco
+        assert_eq!(pixel(0, 0)[3], 0);
+        assert_eq!(pixel(size / 2, 1), &[0xd3, 0x2f, 0x2f, 255]);
+        assert_eq!(pixel(size / 2, size / 2), &[255, 255, 255, 255]);
+    }
+}
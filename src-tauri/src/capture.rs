@@ -0,0 +1,454 @@
+// Captura de la vista de Narrative Assistant como imagen
+//
+// `capture_view` guarda en PNG o SVG lo que muestra el webview de la ventana
+// que lo pide, entero o solo una región (el `getBoundingClientRect()` de un
+// elemento, en píxeles CSS), para compartir grafos de relaciones y líneas
+// temporales con los coautores.
+//
+// Cada plataforma usa la captura nativa de su webview: `get_snapshot` en
+// WebKitGTK, `CapturePreview` en WebView2 y `takeSnapshot` en WKWebView. La
+// región se recorta después a la resolución real de la captura (en pantallas
+// HiDPI, el doble de píxeles que CSS). El SVG incrusta la captura en PNG con
+// el tamaño en CSS: no es vectorial, pero se inserta en documentos y
+// presentaciones que solo aceptan SVG.
+
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use base64::Engine;
+use serde::Deserialize;
+use tauri::webview::PlatformWebview;
+use tauri::{State, WebviewWindow};
+
+use crate::export_scope::ExportGrants;
+use crate::print::Completion;
+
+/// Tiempo máximo para obtener la captura del webview
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Región a capturar, en píxeles CSS relativos al visor
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CaptureRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ImageFormat {
+    Png,
+    Svg,
+}
+
+/// Imagen RGBA de 8 bits sin premultiplicar
+#[derive(Clone, Debug, PartialEq)]
+struct Raster {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+type SnapshotResult = Result<Raster, String>;
+
+/// Ruta final de la imagen: absoluta, `.png` o `.svg` (por defecto `.png`) y en un directorio existente
+fn resolve_output_path(path: &str) -> Result<(PathBuf, ImageFormat), String> {
+    let mut path = PathBuf::from(path.trim());
+    if !path.is_absolute() {
+        return Err("La ruta de la imagen debe ser absoluta".into());
+    }
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    let format = match extension.as_deref() {
+        Some("svg") => ImageFormat::Svg,
+        Some("png") => ImageFormat::Png,
+        _ => {
+            path.set_extension("png");
+            ImageFormat::Png
+        }
+    };
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok((path, format)),
+        _ => Err(format!(
+            "No existe la carpeta de destino de {}",
+            path.display()
+        )),
+    }
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))] // WebKitGTK entrega la captura sin comprimir
+fn decode_png(bytes: &[u8]) -> SnapshotResult {
+    let error = |e: png::DecodingError| format!("Captura no valida: {}", e);
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::ALPHA | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(error)?;
+    let mut buffer = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .ok_or("Captura demasiado grande")?
+    ];
+    let info = reader.next_frame(&mut buffer).map_err(error)?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err("Captura con paleta no admitida".into()),
+    };
+    Ok(Raster {
+        width: info.width,
+        height: info.height,
+        rgba,
+    })
+}
+
+fn encode_png(raster: &Raster) -> Result<Vec<u8>, String> {
+    let error = |e: png::EncodingError| format!("Error codificando la imagen: {}", e);
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, raster.width, raster.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(error)?;
+    writer.write_image_data(&raster.rgba).map_err(error)?;
+    writer.finish().map_err(error)?;
+    Ok(bytes)
+}
+
+/// Superficie cairo ARGB32 (premultiplicada, BGRA en memoria) a RGBA
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn from_premultiplied_bgra(
+    width: u32,
+    height: u32,
+    stride: usize,
+    data: &[u8],
+    alpha: bool,
+) -> Raster {
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in data.chunks(stride).take(height as usize) {
+        for pixel in row[..width as usize * 4].chunks_exact(4) {
+            let (b, g, r) = (pixel[0], pixel[1], pixel[2]);
+            let a = if alpha { pixel[3] } else { 255 };
+            let unpremultiply = |c: u8| match a {
+                0 => 0,
+                255 => c,
+                a => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+            };
+            rgba.extend([unpremultiply(r), unpremultiply(g), unpremultiply(b), a]);
+        }
+    }
+    Raster {
+        width,
+        height,
+        rgba,
+    }
+}
+
+/// Recorta la región (en píxeles CSS) con `scale` píxeles de la captura por píxel CSS
+fn crop(raster: &Raster, region: CaptureRegion, scale: f64) -> SnapshotResult {
+    let to_px = |v: f64, max: u32| ((v * scale).round().max(0.0) as u32).min(max);
+    let left = to_px(region.x, raster.width);
+    let top = to_px(region.y, raster.height);
+    let right = to_px(region.x + region.width, raster.width);
+    let bottom = to_px(region.y + region.height, raster.height);
+    if right <= left || bottom <= top {
+        return Err("La region indicada no es visible en la ventana".into());
+    }
+
+    let row_bytes = raster.width as usize * 4;
+    let rgba = raster
+        .rgba
+        .chunks_exact(row_bytes)
+        .skip(top as usize)
+        .take((bottom - top) as usize)
+        .flat_map(|row| &row[left as usize * 4..right as usize * 4])
+        .copied()
+        .collect();
+    Ok(Raster {
+        width: right - left,
+        height: bottom - top,
+        rgba,
+    })
+}
+
+/// SVG con la captura incrustada, a su tamaño en píxeles CSS
+fn svg_document(png: &[u8], width: f64, height: f64) -> String {
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            r#"<image width="{w}" height="{h}" href="data:image/png;base64,{data}"/></svg>"#,
+            "\n"
+        ),
+        w = (width * 100.0).round() / 100.0,
+        h = (height * 100.0).round() / 100.0,
+        data = base64::engine::general_purpose::STANDARD.encode(png),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn snapshot(webview: PlatformWebview, done: Completion<SnapshotResult>) {
+    use gtk::cairo::ImageSurface;
+    use webkit2gtk::{SnapshotOptions, SnapshotRegion, WebViewExt};
+
+    webview.inner().snapshot(
+        SnapshotRegion::Visible,
+        SnapshotOptions::NONE,
+        None::<&gtk::gio::Cancellable>,
+        move |result| {
+            let raster = result
+                .map_err(|e| format!("Error capturando la vista: {}", e))
+                .and_then(|surface| {
+                    ImageSurface::try_from(surface).map_err(|_| "Captura no valida".to_string())
+                })
+                .and_then(|surface| {
+                    surface.flush();
+                    let alpha = surface.format() == gtk::cairo::Format::ARgb32;
+                    let mut raster = None;
+                    surface
+                        .with_data(|data| {
+                            raster = Some(from_premultiplied_bgra(
+                                surface.width() as u32,
+                                surface.height() as u32,
+                                surface.stride() as usize,
+                                data,
+                                alpha,
+                            ))
+                        })
+                        .map_err(|e| format!("Captura no valida: {}", e))?;
+                    raster.ok_or_else(|| "Captura vacia".to_string())
+                });
+            done.finish(raster);
+        },
+    );
+}
+
+#[cfg(windows)]
+fn snapshot(webview: PlatformWebview, done: Completion<SnapshotResult>) {
+    use ::windows::Win32::System::Com::{IStream, STATFLAG_NONAME, STATSTG, STREAM_SEEK_SET};
+    use ::windows::Win32::UI::Shell::SHCreateMemStream;
+    use webview2_com::CapturePreviewCompletedHandler;
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG;
+
+    /// # Safety
+    /// Solo en el hilo del webview, una vez completada la captura
+    unsafe fn stream_bytes(stream: &IStream) -> Result<Vec<u8>, String> {
+        let error = |e: ::windows::core::Error| format!("Error leyendo la captura: {}", e);
+        let mut stat = STATSTG::default();
+        stream.Stat(&mut stat, STATFLAG_NONAME).map_err(error)?;
+        stream.Seek(0, STREAM_SEEK_SET, None).map_err(error)?;
+        let mut bytes = vec![0u8; stat.cbSize as usize];
+        let mut read = 0u32;
+        stream
+            .Read(
+                bytes.as_mut_ptr().cast(),
+                bytes.len() as u32,
+                Some(&mut read as *mut u32),
+            )
+            .ok()
+            .map_err(error)?;
+        bytes.truncate(read as usize);
+        Ok(bytes)
+    }
+
+    // SAFETY: SHCreateMemStream no tiene precondiciones
+    let Some(stream) = (unsafe { SHCreateMemStream(None) }) else {
+        return done.finish(Err("No se pudo preparar la captura".into()));
+    };
+    let captured = stream.clone();
+    let completed = done.clone();
+    let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+        completed.finish(
+            result
+                .map_err(|e| format!("Error capturando la vista: {}", e))
+                // SAFETY: la captura ha terminado de escribir en el stream
+                .and_then(|()| unsafe { stream_bytes(&captured) })
+                .and_then(|bytes| decode_png(&bytes)),
+        );
+        Ok(())
+    }));
+    // SAFETY: llamadas COM en el hilo del webview (with_webview se ejecuta en el hilo principal)
+    let started = unsafe {
+        webview.controller().CoreWebView2().and_then(|core| {
+            core.CapturePreview(
+                COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+                &stream,
+                &handler,
+            )
+        })
+    };
+    if let Err(e) = started {
+        done.finish(Err(format!("Error capturando la vista: {}", e)));
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn snapshot(webview: PlatformWebview, done: Completion<SnapshotResult>) {
+    use block2::RcBlock;
+    use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
+    use objc2_foundation::{NSDictionary, NSError};
+    use objc2_web_kit::WKWebView;
+
+    let handler = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
+        // SAFETY: WebKit pasa punteros válidos (o nulos) durante la llamada al bloque
+        let result = match unsafe { (image.as_ref(), error.as_ref()) } {
+            (Some(image), _) => image
+                .TIFFRepresentation()
+                .and_then(|tiff| NSBitmapImageRep::imageRepWithData(&tiff))
+                // SAFETY: sin propiedades, el diccionario vacío tiene el tipo correcto
+                .and_then(|rep| unsafe {
+                    rep.representationUsingType_properties(
+                        NSBitmapImageFileType::PNG,
+                        &NSDictionary::new(),
+                    )
+                })
+                .ok_or_else(|| "No se pudo convertir la captura a PNG".to_string())
+                .and_then(|png| decode_png(&png.to_vec())),
+            (None, Some(error)) => Err(format!(
+                "Error capturando la vista: {}",
+                error.localizedDescription()
+            )),
+            (None, None) => Err("WebKit no pudo capturar la vista".into()),
+        };
+        done.finish(result);
+    });
+    // SAFETY: `inner()` es el WKWebView de la ventana y with_webview corre en el hilo principal
+    unsafe {
+        let webview: &WKWebView = &*webview.inner().cast();
+        webview.takeSnapshotWithConfiguration_completionHandler(None, &handler);
+    }
+}
+
+/// Captura el área visible del webview de `window`
+async fn snapshot_window(window: &WebviewWindow) -> SnapshotResult {
+    let (done, rx) = Completion::new();
+    window
+        .with_webview(move |webview| snapshot(webview, done))
+        .map_err(|e| format!("No se pudo acceder al visor web: {}", e))?;
+    tokio::time::timeout(CAPTURE_TIMEOUT, rx)
+        .await
+        .map_err(|_| "Tiempo de espera agotado capturando la vista".to_string())?
+        .map_err(|_| "La captura se interrumpio".to_string())?
+}
+
+/// Captura el área visible de `window` en PNG (p.ej. para el informe de soporte)
+pub(crate) async fn snapshot_png(window: &WebviewWindow) -> Result<Vec<u8>, String> {
+    encode_png(&snapshot_window(window).await?)
+}
+
+/// Guarda como imagen la vista actual o, con `region`, solo esa zona.
+/// El formato sale de la extensión de `path` (`.png` o `.svg`). Devuelve la ruta guardada.
+#[tauri::command]
+pub async fn capture_view(
+    window: WebviewWindow,
+    grants: State<'_, ExportGrants>,
+    region: Option<CaptureRegion>,
+    path: String,
+) -> Result<String, String> {
+    let (path, format) = resolve_output_path(&path)?;
+    grants.authorize(&path)?;
+    let raster = snapshot_window(&window).await?;
+
+    // Píxeles de la captura por píxel CSS (no todas las plataformas capturan a la escala de la pantalla)
+    let viewport_width = window
+        .inner_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(window.scale_factor().map_err(|e| e.to_string())?)
+        .width;
+    let scale = if viewport_width > 0.0 {
+        raster.width as f64 / viewport_width
+    } else {
+        1.0
+    };
+    let raster = match region {
+        Some(region) => crop(&raster, region, scale)?,
+        None => raster,
+    };
+
+    let png = encode_png(&raster)?;
+    let contents = match format {
+        ImageFormat::Png => png,
+        ImageFormat::Svg => svg_document(
+            &png,
+            raster.width as f64 / scale,
+            raster.height as f64 / scale,
+        )
+        .into_bytes(),
+    };
+    std::fs::write(&path, contents).map_err(|e| format!("Error guardando la imagen: {}", e))?;
+    tracing::info!(
+        target: "capture",
+        "Vista capturada en {} ({}x{} px)",
+        path.display(),
+        raster.width,
+        raster.height
+    );
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_path_picks_format_from_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let svg = dir.path().join("grafo.SVG");
+        assert_eq!(
+            resolve_output_path(svg.to_str().unwrap()).unwrap(),
+            (svg.clone(), ImageFormat::Svg)
+        );
+        let base = dir.path().join("linea-temporal");
+        assert_eq!(
+            resolve_output_path(base.to_str().unwrap()).unwrap(),
+            (dir.path().join("linea-temporal.png"), ImageFormat::Png)
+        );
+        assert!(resolve_output_path("grafo.png").is_err());
+    }
+
+    #[test]
+    fn region_is_cropped_at_capture_scale_and_round_trips_through_png() {
+        // 4x2 píxeles de captura para 2x1 píxeles CSS (escala 2)
+        let raster = Raster {
+            width: 4,
+            height: 2,
+            rgba: (0..32).collect(),
+        };
+        let region = CaptureRegion {
+            x: 1.0,
+            y: 0.0,
+            width: 5.0,
+            height: 1.0,
+        };
+        let cropped = crop(&raster, region, 2.0).unwrap();
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(&cropped.rgba[..8], &raster.rgba[8..16]);
+        assert_eq!(&cropped.rgba[8..], &raster.rgba[24..32]);
+
+        let outside = CaptureRegion { x: 10.0, ..region };
+        assert!(crop(&raster, outside, 2.0).is_err());
+
+        assert_eq!(decode_png(&encode_png(&cropped).unwrap()).unwrap(), cropped);
+        let svg = svg_document(&encode_png(&cropped).unwrap(), 1.0, 1.0);
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="1" height="1""#));
+    }
+
+    #[test]
+    fn cairo_pixels_are_unpremultiplied() {
+        // BGRA premultiplicado: rojo al 50 % y un píxel transparente
+        let data = [0, 0, 128, 128, 9, 9, 9, 0, 0xff, 0xff];
+        let raster = from_premultiplied_bgra(2, 1, 10, &data, true);
+        assert_eq!(raster.rgba, vec![255, 0, 0, 128, 0, 0, 0, 0]);
+        let opaque = from_premultiplied_bgra(1, 1, 4, &[1, 2, 3, 0], false);
+        assert_eq!(opaque.rgba, vec![3, 2, 1, 255]);
+    }
+}
@@ -0,0 +1,274 @@
+// División en capítulos de borradores en texto plano y Markdown en Narrative Assistant
+//
+// Un `.txt` o `.md` no tiene estilos de título: sin dividirlo, el manuscrito
+// llega al análisis como un único capítulo gigante. `split` lo recorre línea a
+// línea y abre un capítulo en:
+//
+// - Títulos Markdown (`# Título`). Si solo hay un `#` (el título del libro) y
+//   hay `##`, los capítulos son los `##`. Se ignoran los bloques de código.
+// - Líneas cortas que encajan con `HEADING_PATTERNS` ("Capítulo 3",
+//   "CAPÍTULO IV", "Capítulo tres", "Prólogo"...) o con los patrones añadidos
+//   en `settings.chapter_split`.
+//
+// Los separadores de escena (`* * *`, `#`, `~~~`, `§`...) no abren capítulo,
+// cuentan escenas. La usan `split_chapters` y la vista previa de importación
+// (manuscript_preview.rs).
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::settings::SettingsStore;
+use crate::text_encoding;
+
+/// Títulos de capítulo por defecto (sobre la línea sin espacios alrededor)
+const HEADING_PATTERNS: &[&str] = &[
+    r"(?i)^(cap[íi]tulo|chapter|parte|part|libro)\s+([0-9]+|[ivxlcdm]+)\b",
+    r"(?i)^(cap[íi]tulo|parte|libro)\s+(uno|dos|tres|cuatro|cinco|seis|siete|ocho|nueve|diez|once|doce|trece|catorce|quince|diecis[ée]is|diecisiete|dieciocho|diecinueve|veinte|primer[oa]?|segund[oa]|tercer[oa]?|cuart[oa]|quint[oa]|sext[oa]|s[ée]ptim[oa]|octav[oa]|noven[oa]|d[ée]cim[oa])\b",
+    r"(?i)^(pr[óo]logo|ep[íi]logo|interludio|prologue|epilogue)\b",
+];
+
+/// Separadores de escena por defecto: solo símbolos, p. ej. `* * *` o `~`
+const SCENE_SEPARATOR_PATTERNS: &[&str] = &[r"^[*#~§•·=_-](\s*[*#~§•·=_-])*$"];
+
+/// Una línea con más palabras es texto aunque empiece como un título
+const MAX_HEADING_WORDS: usize = 12;
+
+/// Patrones añadidos a los de por defecto (`settings.chapter_split`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct ChapterSplitSettings {
+    /// Expresiones regulares de título de capítulo
+    pub heading_patterns: Vec<String>,
+    /// Expresiones regulares de separador de escena
+    pub scene_separators: Vec<String>,
+}
+
+fn compile(defaults: &[&str], extra: &[String], kind: &str) -> Result<Vec<Regex>, String> {
+    defaults
+        .iter()
+        .copied()
+        .chain(extra.iter().map(String::as_str))
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|e| format!("Patron de {} no valido '{}': {}", kind, pattern, e))
+        })
+        .collect()
+}
+
+impl ChapterSplitSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        Splitter::new(self).map(|_| ())
+    }
+}
+
+/// Patrones compilados
+pub(crate) struct Splitter {
+    headings: Vec<Regex>,
+    separators: Vec<Regex>,
+}
+
+impl Splitter {
+    pub(crate) fn new(settings: &ChapterSplitSettings) -> Result<Self, String> {
+        Ok(Self {
+            headings: compile(HEADING_PATTERNS, &settings.heading_patterns, "capitulo")?,
+            separators: compile(
+                SCENE_SEPARATOR_PATTERNS,
+                &settings.scene_separators,
+                "escena",
+            )?,
+        })
+    }
+
+    pub(crate) fn is_heading(&self, line: &str) -> bool {
+        line.split_whitespace().count() <= MAX_HEADING_WORDS
+            && self.headings.iter().any(|re| re.is_match(line))
+    }
+
+    fn is_separator(&self, line: &str) -> bool {
+        self.separators.iter().any(|re| re.is_match(line))
+    }
+}
+
+/// Capítulo detectado
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SplitChapter {
+    /// `None` para el texto anterior al primer título
+    pub title: Option<String>,
+    /// Línea (1-based) donde empieza
+    pub start_line: usize,
+    pub word_count: usize,
+    pub scene_count: usize,
+}
+
+impl SplitChapter {
+    fn new(title: Option<String>, start_line: usize) -> Self {
+        Self {
+            title,
+            start_line,
+            word_count: 0,
+            scene_count: 1,
+        }
+    }
+}
+
+/// Nivel y texto de un título Markdown (`## Título ##`)
+pub(crate) fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim_end();
+    (!title.is_empty()).then_some((level, title))
+}
+
+/// Líneas numeradas sin las marcas ``` y si son estructurales (fuera de un
+/// bloque de código: dentro solo son texto)
+fn outside_fences(text: &str) -> Vec<(usize, &str, bool)> {
+    let mut in_fence = false;
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.trim();
+            if line.starts_with("```") {
+                in_fence = !in_fence;
+                return None;
+            }
+            Some((index + 1, line, !in_fence))
+        })
+        .collect()
+}
+
+/// Divide `text` en capítulos
+pub(crate) fn split(text: &str, splitter: &Splitter) -> Vec<SplitChapter> {
+    let lines = outside_fences(text);
+    let h1_count = lines
+        .iter()
+        .filter(|(_, line, structural)| {
+            *structural && matches!(markdown_heading(line), Some((1, _)))
+        })
+        .count();
+    let has_h2 = lines
+        .iter()
+        .any(|(_, line, structural)| *structural && matches!(markdown_heading(line), Some((2, _))));
+    let chapter_level = if h1_count < 2 && has_h2 { 2 } else { 1 };
+
+    let mut chapters = vec![SplitChapter::new(None, 1)];
+    let mut pending_scene = false;
+    for (number, line, structural) in lines {
+        if structural {
+            let title = match markdown_heading(line) {
+                Some((level, title)) if level == chapter_level => Some(title),
+                Some(_) => None,
+                None => splitter.is_heading(line).then_some(line),
+            };
+            if let Some(title) = title {
+                chapters.push(SplitChapter::new(Some(title.to_string()), number));
+                pending_scene = false;
+                continue;
+            }
+            if splitter.is_separator(line) {
+                pending_scene = true;
+                continue;
+            }
+        }
+        let words = line.split_whitespace().count();
+        if words == 0 {
+            continue;
+        }
+        let current = chapters.last_mut().expect("siempre hay un capitulo");
+        // Un separador cuenta si le sigue texto del mismo capítulo
+        if pending_scene && current.word_count > 0 {
+            current.scene_count += 1;
+        }
+        pending_scene = false;
+        current.word_count += words;
+    }
+    // Sin texto antes del primer título no hay capítulo sin título
+    if chapters.len() > 1 && chapters[0].word_count == 0 {
+        chapters.remove(0);
+    }
+    chapters
+}
+
+/// Lee un borrador en la codificación que tenga (text_encoding.rs)
+pub(crate) fn read_text(path: &Path) -> Result<String, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?;
+    Ok(text_encoding::decode(&bytes).0)
+}
+
+/// Capítulos de un `.txt` o `.md` con los patrones configurados
+#[tauri::command]
+pub async fn split_chapters(
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<Vec<SplitChapter>, String> {
+    let splitter = Splitter::new(&store.get().chapter_split)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        read_text(Path::new(&path)).map(|text| split(&text, &splitter))
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de division en capitulos: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(chapters: &[SplitChapter]) -> Vec<Option<&str>> {
+        chapters.iter().map(|c| c.title.as_deref()).collect()
+    }
+
+    #[test]
+    fn plain_text_splits_on_chapter_lines_and_counts_scenes() {
+        let splitter = Splitter::new(&ChapterSplitSettings::default()).unwrap();
+        let text = "Dedicado a Marta.\n\n\
+                    CAPÍTULO PRIMERO\n\nAna llegó al faro.\n\n* * *\n\nLuis la esperaba.\n\n\
+                    Capítulo 2: La tormenta\nLlovía.\n~\n\n\
+                    Parte de la culpa era suya, pensó mientras cerraba la puerta del faro con llave.\n";
+        let chapters = split(text, &splitter);
+        assert_eq!(
+            titles(&chapters),
+            [
+                None,
+                Some("CAPÍTULO PRIMERO"),
+                Some("Capítulo 2: La tormenta")
+            ]
+        );
+        assert_eq!(chapters[1].start_line, 3);
+        assert_eq!(chapters[1].word_count, 7);
+        assert_eq!(chapters[1].scene_count, 2);
+        // "Parte de la culpa..." es texto, y abre la escena que separa el `~`
+        assert_eq!(chapters[2].scene_count, 2);
+        assert_eq!(chapters[2].word_count, 16);
+    }
+
+    #[test]
+    fn markdown_uses_the_repeated_heading_level_and_custom_patterns() {
+        let settings = ChapterSplitSettings {
+            heading_patterns: vec![r"^Jornada \d+$".into()],
+            scene_separators: vec![],
+        };
+        let splitter = Splitter::new(&settings).unwrap();
+        let text = "# El faro\n\n## Uno\nTexto.\n```\n## no es un título\n```\n\
+                    ## Dos\nMás texto.\n### Escena\nJornada 3\nFin.\n";
+        let chapters = split(text, &splitter);
+        assert_eq!(
+            titles(&chapters),
+            [None, Some("Uno"), Some("Dos"), Some("Jornada 3")]
+        );
+        // El `#` del libro no es capítulo: cuenta como texto del preámbulo
+        assert_eq!(chapters[0].word_count, 3);
+        assert_eq!(chapters[1].word_count, 6);
+
+        assert!(ChapterSplitSettings {
+            heading_patterns: vec!["(".into()],
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
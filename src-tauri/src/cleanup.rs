@@ -1,20 +1,94 @@
 // Gestión de datos y limpieza para Narrative Assistant
 //
 // Proporciona comandos Tauri para:
-//   - Listar categorías de datos con tamaño en disco
-//   - Eliminar categorías individuales (solo datos propios, nunca compartidos)
+//   - Listar categorías de datos con tamaño en disco (y desglose por subdirectorio)
+//   - Eliminar categorías individuales (solo datos propios, nunca compartidos),
+//     deteniendo el backend mientras se borran los ficheros que tiene abiertos
+//   - Exportar un informe de almacenamiento (JSON/CSV)
+//   - Migrar el layout de ~/.narrative_assistant al arrancar (layout.json)
 //
 // Usado por el diálogo "Gestionar datos" (macOS y Windows)
 
-use serde::Serialize;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::audit::{self, AuditEntry};
+use crate::database;
+use crate::downloads;
+use crate::export_scope::ExportGrants;
+use crate::i18n::{self, Message};
+use crate::model_dedup;
+use crate::models::{self, ModelManifest, ModelUsage};
+use crate::settings::{Settings, SettingsStore};
+use crate::BackendServer;
+
+/// Identificador de categoría de datos (se serializa en snake_case: "webview_cache", ...)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryId {
+    /// Caché del WebView (se regenera sola)
+    WebviewCache,
+    /// Configuración, estado de ventanas y localStorage del WebView
+    AppSettings,
+    UserData,
+    /// Copias automáticas de la base de datos (snapshots/)
+    Snapshots,
+    Models,
+    Ollama,
+    Huggingface,
+}
+
+impl CategoryId {
+    /// Identificador tal como se serializa
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CategoryId::WebviewCache => "webview_cache",
+            CategoryId::AppSettings => "app_settings",
+            CategoryId::UserData => "user_data",
+            CategoryId::Snapshots => "snapshots",
+            CategoryId::Models => "models",
+            CategoryId::Ollama => "ollama",
+            CategoryId::Huggingface => "huggingface",
+        }
+    }
+
+    /// Si el directorio es compartido con otras aplicaciones (nunca se borra)
+    pub fn is_shared(self) -> bool {
+        matches!(self, CategoryId::Ollama | CategoryId::Huggingface)
+    }
+
+    /// Si la eliminación destruye datos del usuario (requiere token de confirmación)
+    pub fn is_destructive(self) -> bool {
+        matches!(
+            self,
+            CategoryId::UserData | CategoryId::AppSettings | CategoryId::Snapshots
+        )
+    }
+
+    /// Si admite sobrescribir los ficheros antes de borrarlos
+    pub fn supports_secure_wipe(self) -> bool {
+        matches!(self, CategoryId::UserData)
+    }
+
+    /// Si el backend mantiene abiertos sus ficheros (SQLite, modelos cargados).
+    /// En Windows no se pueden borrar mientras el proceso los tiene abiertos.
+    fn requires_backend_pause(self) -> bool {
+        matches!(self, CategoryId::UserData | CategoryId::Models)
+    }
+}
 
 /// Categoría de datos almacenada en disco
 #[derive(Serialize, Clone)]
 pub struct DataCategory {
-    /// Identificador único (app_cache, user_data, models, ollama, huggingface)
-    pub id: String,
+    /// Identificador único
+    pub id: CategoryId,
     /// Nombre para mostrar en la UI
     pub label: String,
     /// Descripción breve
@@ -23,50 +97,285 @@ pub struct DataCategory {
     pub path: String,
     /// Tamaño en bytes (0 si el directorio no existe)
     pub size_bytes: u64,
+    /// Si el tamaño aún se está calculando (ver `get_data_categories`)
+    pub size_pending: bool,
     /// Si el directorio es compartido con otras aplicaciones
     pub is_shared: bool,
     /// Si la eliminación destruye datos del usuario (irreversible)
     pub is_destructive: bool,
     /// Si el directorio existe en disco
     pub exists: bool,
+    /// Desglose por subdirectorios (2 niveles), ordenado de mayor a menor
+    pub breakdown: Option<Vec<SizeNode>>,
+    /// Modelos encontrados en la categoría, marcando los que usa Narrative Assistant
+    /// (categorías compartidas, para que el usuario limpie el resto a mano). En la
+    /// de modelos, cada modelo instalado de la app, incluidos los suyos de Ollama.
+    pub models: Option<Vec<ModelUsage>>,
+    /// Espacio que se recupera enlazando los modelos repetidos en la cache de
+    /// HuggingFace (solo la categoría de modelos, ver `model_dedup`)
+    pub reclaimable_bytes: Option<u64>,
+    /// Aviso sobre el borrado seguro, si la categoría lo admite (ver `secure_wipe_path`)
+    pub secure_wipe_notice: Option<String>,
+}
+
+/// Nodo del desglose de tamaño de una categoría (para el treemap de la UI)
+#[derive(Serialize, Clone, Debug)]
+pub struct SizeNode {
+    /// Nombre del fichero o directorio
+    pub name: String,
+    /// Ruta absoluta en disco
+    pub path: String,
+    /// Tamaño en bytes (incluye todos los descendientes)
+    pub size_bytes: u64,
+    /// Subdirectorios (vacío en el último nivel)
+    pub children: Vec<SizeNode>,
+}
+
+/// Niveles de subdirectorios que se desglosan
+const BREAKDOWN_DEPTH: u32 = 2;
+
+/// Nombre del nodo que agrupa los ficheros sueltos de un directorio
+const LOOSE_FILES_NODE: &str = "(archivos)";
+
+/// Opciones del cálculo de tamaños
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SizeOptions {
+    /// Seguir enlaces simbólicos dentro del árbol (la raíz siempre se resuelve).
+    /// Desactivado por defecto: la cache de HuggingFace enlaza snapshots/ a blobs/.
+    pub follow_symlinks: bool,
+}
+
+/// Identidad de un fichero o directorio en disco
+#[cfg(unix)]
+type FileKey = (u64, u64);
+#[cfg(not(unix))]
+type FileKey = PathBuf;
+
+#[cfg(unix)]
+fn file_key(_path: &Path, meta: &fs::Metadata) -> Option<FileKey> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_key(path: &Path, _meta: &fs::Metadata) -> Option<FileKey> {
+    fs::canonicalize(path).ok()
 }
 
-/// Calcula el tamaño total de un directorio recursivamente
-fn dir_size(path: &Path) -> u64 {
-    if !path.exists() {
-        return 0;
+/// Si un fichero puede aparecer por más de una ruta (hardlinks)
+#[cfg(unix)]
+fn has_multiple_links(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn has_multiple_links(_meta: &fs::Metadata) -> bool {
+    false
+}
+
+/// Recorrido de directorios que no cuenta dos veces el mismo fichero
+/// ni entra en ciclos de enlaces simbólicos.
+///
+/// Los subdirectorios se recorren en paralelo (rayon): en discos mecánicos la
+/// latencia de `read_dir` domina y un árbol de Ollama de 40 GB tarda minutos en serie.
+struct SizeWalker {
+    options: SizeOptions,
+    visited: Mutex<HashSet<FileKey>>,
+    /// Rutas que no se cuentan (pertenecen a otra categoría)
+    excluded: Vec<PathBuf>,
+}
+
+impl SizeWalker {
+    fn new(options: SizeOptions) -> Self {
+        Self {
+            options,
+            visited: Mutex::new(HashSet::new()),
+            excluded: Vec::new(),
+        }
+    }
+
+    /// Omite estas rutas (y todo lo que cuelga de ellas) del recorrido
+    fn excluding(mut self, paths: Vec<PathBuf>) -> Self {
+        self.excluded = paths;
+        self
     }
-    // Recorrido manual sin dependencia extra
-    fn walk(dir: &std::path::Path) -> u64 {
-        let mut total: u64 = 0;
+
+    /// Metadatos de una entrada del árbol; `None` si es un symlink que no se sigue
+    /// (o está roto)
+    fn entry_metadata(&self, path: &Path) -> Option<fs::Metadata> {
+        let meta = fs::symlink_metadata(path).ok()?;
+        if !meta.file_type().is_symlink() {
+            return Some(meta);
+        }
+        if !self.options.follow_symlinks {
+            return None;
+        }
+        fs::metadata(path).ok()
+    }
+
+    /// Registra la entrada; devuelve false si ya se había contado
+    fn first_visit(&self, path: &Path, meta: &fs::Metadata) -> bool {
+        match file_key(path, meta) {
+            Some(key) => self.visited.lock().unwrap().insert(key),
+            None => true,
+        }
+    }
+
+    /// Tamaño de un fichero del árbol, deduplicando los que pueden tener varias rutas
+    /// (hardlinks, o cualquier fichero si se siguen symlinks)
+    fn file_size(&self, path: &Path, meta: &fs::Metadata) -> u64 {
+        let may_repeat = self.options.follow_symlinks || has_multiple_links(meta);
+        if may_repeat && !self.first_visit(path, meta) {
+            return 0;
+        }
+        meta.len()
+    }
+
+    /// Tamaño total de una ruta (fichero o directorio)
+    fn size(&self, path: &Path) -> u64 {
+        match fs::metadata(path) {
+            Ok(meta) if meta.is_file() => self.file_size(path, &meta),
+            Ok(meta) if meta.is_dir() => self.walk_dir(path, &meta),
+            _ => 0,
+        }
+    }
+
+    /// Lee un directorio separando ficheros (ya sumados) y subdirectorios pendientes
+    fn scan_dir(&self, dir: &Path) -> (u64, Vec<(PathBuf, fs::Metadata)>) {
+        let mut files: u64 = 0;
+        let mut subdirs = Vec::new();
         if let Ok(entries) = fs::read_dir(dir) {
             for entry in entries.flatten() {
-                if let Ok(meta) = entry.metadata() {
+                let path = entry.path();
+                if self.excluded.contains(&path) {
+                    continue;
+                }
+                if let Some(meta) = self.entry_metadata(&path) {
                     if meta.is_file() {
-                        total += meta.len();
+                        files += self.file_size(&path, &meta);
                     } else if meta.is_dir() {
-                        total += walk(&entry.path());
+                        subdirs.push((path, meta));
                     }
                 }
             }
         }
-        total
+        (files, subdirs)
+    }
+
+    fn walk_dir(&self, dir: &Path, meta: &fs::Metadata) -> u64 {
+        if !self.first_visit(dir, meta) {
+            return 0;
+        }
+        let (files, subdirs) = self.scan_dir(dir);
+        files
+            + subdirs
+                .par_iter()
+                .map(|(path, meta)| self.walk_dir(path, meta))
+                .sum::<u64>()
+    }
+
+    /// Desglosa el tamaño de un directorio hasta `depth` niveles.
+    /// Devuelve el tamaño total y los nodos hijos ordenados de mayor a menor.
+    /// Los ficheros sueltos de cada nivel se agrupan en un único nodo.
+    fn breakdown(&self, path: &Path, depth: u32) -> (u64, Vec<SizeNode>) {
+        if depth == 0 {
+            return (self.size(path), vec![]);
+        }
+        let Ok(root_meta) = fs::metadata(path) else {
+            return (0, vec![]);
+        };
+        if !root_meta.is_dir() || !self.first_visit(path, &root_meta) {
+            return (0, vec![]);
+        }
+
+        let (loose_files, subdirs) = self.scan_dir(path);
+        let mut nodes: Vec<SizeNode> = subdirs
+            .par_iter()
+            .map(|(child_path, meta)| {
+                let (size, children) = if depth == 1 {
+                    (self.walk_dir(child_path, meta), vec![])
+                } else {
+                    self.breakdown(child_path, depth - 1)
+                };
+                SizeNode {
+                    name: child_path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into())
+                        .unwrap_or_default(),
+                    path: child_path.to_string_lossy().into(),
+                    size_bytes: size,
+                    children,
+                }
+            })
+            .collect();
+        let total = loose_files + nodes.iter().map(|n| n.size_bytes).sum::<u64>();
+        if loose_files > 0 && !nodes.is_empty() {
+            nodes.push(SizeNode {
+                name: LOOSE_FILES_NODE.into(),
+                path: path.to_string_lossy().into(),
+                size_bytes: loose_files,
+                children: vec![],
+            });
+        }
+        nodes.sort_by_key(|n| std::cmp::Reverse(n.size_bytes));
+        (total, nodes)
     }
-    walk(path)
 }
 
-/// Lista todas las categorías de datos con su tamaño actual
-#[tauri::command]
-pub fn get_data_categories() -> Vec<DataCategory> {
-    let home = match dirs::home_dir() {
-        Some(h) => h,
-        None => return vec![],
+/// Calcula el tamaño total de un directorio recursivamente (sin seguir symlinks internos)
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    SizeWalker::new(SizeOptions::default()).size(path)
+}
+
+/// Desglose de un directorio con un recorrido propio (ver `SizeWalker::breakdown`)
+fn dir_breakdown(path: &Path, depth: u32, options: SizeOptions) -> (u64, Vec<SizeNode>) {
+    SizeWalker::new(options).breakdown(path, depth)
+}
+
+/// Nodo de desglose para una ruta concreta (fichero o directorio)
+fn path_node(name: &str, path: &Path, depth: u32, options: SizeOptions) -> SizeNode {
+    let (size_bytes, children) = if path.is_file() {
+        (fs::metadata(path).map(|m| m.len()).unwrap_or(0), vec![])
+    } else {
+        dir_breakdown(path, depth, options)
     };
+    SizeNode {
+        name: name.into(),
+        path: path.to_string_lossy().into(),
+        size_bytes,
+        children,
+    }
+}
+
+/// Todas las categorías, en el orden en que se muestran
+pub const ALL_CATEGORIES: [CategoryId; 7] = [
+    CategoryId::WebviewCache,
+    CategoryId::AppSettings,
+    CategoryId::UserData,
+    CategoryId::Snapshots,
+    CategoryId::Models,
+    CategoryId::Ollama,
+    CategoryId::Huggingface,
+];
 
-    let na = home.join(".narrative_assistant");
+/// Identificador de la app (tauri.conf.json): WebKit guarda su caché bajo este nombre
+const APP_IDENTIFIER: &str = "com.tfm.narrative-assistant";
 
-    // Ruta de datos de la app (Tauri LOCALAPPDATA en Windows, Application Support en macOS)
-    let app_data_path = if cfg!(target_os = "windows") {
+/// Subdirectorios de caché de WebView2 dentro de los datos de la app.
+/// El resto de EBWebView (Local Storage, IndexedDB, Preferences) es configuración.
+const WEBVIEW2_CACHE_SUBDIRS: [&str; 6] = [
+    "EBWebView/Default/Cache",
+    "EBWebView/Default/Code Cache",
+    "EBWebView/Default/GPUCache",
+    "EBWebView/Default/Service Worker/CacheStorage",
+    "EBWebView/GrShaderCache",
+    "EBWebView/ShaderCache",
+];
+
+/// Datos de la app (LOCALAPPDATA en Windows, Application Support en macOS, XDG en Linux)
+pub(crate) fn app_data_dir() -> PathBuf {
+    if cfg!(target_os = "windows") {
         dirs::data_local_dir()
             .unwrap_or_default()
             .join("Narrative Assistant")
@@ -78,137 +387,1459 @@ pub fn get_data_categories() -> Vec<DataCategory> {
         dirs::data_dir()
             .unwrap_or_default()
             .join("narrative-assistant")
-    };
+    }
+}
 
-    let categories = vec![
-        DataCategory {
-            id: "app_cache".into(),
-            label: "Datos de la aplicacion".into(),
-            description: "Configuracion, cache, logs del WebView".into(),
-            path: app_data_path.to_string_lossy().into(),
-            size_bytes: dir_size(&app_data_path),
-            is_shared: false,
-            is_destructive: false,
-            exists: app_data_path.exists(),
-        },
-        DataCategory {
-            id: "user_data".into(),
-            label: "Proyectos y base de datos".into(),
-            description: "Proyectos, anotaciones, historial de cambios".into(),
-            path: na.to_string_lossy().into(),
-            size_bytes: {
-                // Solo contar DB + data/, no models/
-                let mut s = 0u64;
-                let db = na.join("narrative_assistant.db");
-                if db.exists() {
-                    if let Ok(m) = fs::metadata(&db) {
-                        s += m.len();
-                    }
-                }
-                s += dir_size(&na.join("data"));
-                s += dir_size(&na.join("documents"));
-                s
+/// Rutas base de las categorías de datos
+struct DataRoots {
+    home: PathBuf,
+    /// ~/.narrative_assistant
+    na: PathBuf,
+    /// Modelos NLP (~/.narrative_assistant/models, o donde los movió `set_models_location`)
+    models: PathBuf,
+    /// Datos de la app (Tauri LOCALAPPDATA en Windows, Application Support en macOS)
+    app_data: PathBuf,
+    /// Caché de WebKit (macOS y Linux; en Windows WebView2 la guarda en `app_data`)
+    webkit_cache: Option<PathBuf>,
+}
+
+impl DataRoots {
+    fn resolve(settings: &Settings) -> Option<Self> {
+        let home = dirs::home_dir()?;
+        let na = home.join(".narrative_assistant");
+        let models = downloads::models_root_for(settings)?;
+        let app_data = app_data_dir();
+        let webkit_cache = if cfg!(target_os = "windows") {
+            None
+        } else {
+            dirs::cache_dir().map(|c| c.join(APP_IDENTIFIER))
+        };
+        Some(Self {
+            home,
+            na,
+            models,
+            app_data,
+            webkit_cache,
+        })
+    }
+
+    /// Rutas de la caché del WebView (borrarlas solo obliga a regenerarla)
+    fn webview_cache_paths(&self) -> Vec<PathBuf> {
+        WEBVIEW2_CACHE_SUBDIRS
+            .iter()
+            .map(|sub| self.app_data.join(sub))
+            .chain(self.webkit_cache.clone())
+            .collect()
+    }
+
+    /// Ruta que se muestra para la categoría
+    fn path(&self, id: CategoryId) -> PathBuf {
+        match id {
+            CategoryId::WebviewCache => match &self.webkit_cache {
+                Some(cache) => cache.clone(),
+                None => self.app_data.join("EBWebView"),
             },
-            is_shared: false,
-            is_destructive: true,
-            exists: na.join("narrative_assistant.db").exists() || na.join("data").exists(),
-        },
-        DataCategory {
-            id: "models".into(),
-            label: "Modelos NLP".into(),
-            description: "spaCy, sentence-transformers (se pueden volver a descargar)".into(),
-            path: na.join("models").to_string_lossy().into(),
-            size_bytes: dir_size(&na.join("models")),
-            is_shared: false,
-            is_destructive: false,
-            exists: na.join("models").exists(),
-        },
-        DataCategory {
-            id: "ollama".into(),
-            label: "Ollama (compartido)".into(),
-            description: "Modelos LLM - compartido con otras aplicaciones".into(),
-            path: home.join(".ollama").to_string_lossy().into(),
-            size_bytes: dir_size(&home.join(".ollama")),
-            is_shared: true,
-            is_destructive: false,
-            exists: home.join(".ollama").exists(),
-        },
-        DataCategory {
-            id: "huggingface".into(),
-            label: "HuggingFace (compartido)".into(),
-            description: "Cache de modelos - compartido con otras aplicaciones".into(),
-            path: home
-                .join(".cache")
-                .join("huggingface")
-                .to_string_lossy()
-                .into(),
-            size_bytes: dir_size(&home.join(".cache").join("huggingface")),
-            is_shared: true,
-            is_destructive: false,
-            exists: home.join(".cache").join("huggingface").exists(),
-        },
-    ];
+            CategoryId::AppSettings => self.app_data.clone(),
+            CategoryId::UserData => self.na.clone(),
+            CategoryId::Snapshots => self.na.join(SNAPSHOTS_DIR),
+            CategoryId::Models => self.models.clone(),
+            CategoryId::Ollama => self.home.join(".ollama"),
+            CategoryId::Huggingface => self.home.join(".cache").join("huggingface"),
+        }
+    }
 
-    categories
+    /// Si la categoría tiene datos en disco (comprobación rápida, sin recorrer)
+    fn exists(&self, id: CategoryId) -> bool {
+        match id {
+            CategoryId::UserData => {
+                self.na.join(database::DB_FILE_NAME).exists() || self.na.join("data").exists()
+            }
+            CategoryId::WebviewCache => self.webview_cache_paths().iter().any(|p| p.exists()),
+            _ => self.path(id).exists(),
+        }
+    }
 }
 
-/// Elimina una categoría de datos. Rechaza eliminar directorios compartidos.
+impl CategoryId {
+    /// Nombre para mostrar en la UI (clave de i18n.rs)
+    fn label(self) -> Message {
+        Message::new(match self {
+            CategoryId::WebviewCache => "cleanup.category.webview_cache",
+            CategoryId::AppSettings => "cleanup.category.app_settings",
+            CategoryId::UserData => "cleanup.category.user_data",
+            CategoryId::Snapshots => "cleanup.category.snapshots",
+            CategoryId::Models => "cleanup.category.models",
+            CategoryId::Ollama => "cleanup.category.ollama",
+            CategoryId::Huggingface => "cleanup.category.huggingface",
+        })
+    }
+
+    fn description(self) -> Message {
+        Message::new(match self {
+            CategoryId::WebviewCache => "cleanup.category.webview_cache.description",
+            CategoryId::AppSettings => "cleanup.category.app_settings.description",
+            CategoryId::UserData => "cleanup.category.user_data.description",
+            CategoryId::Snapshots => "cleanup.category.snapshots.description",
+            CategoryId::Models => "cleanup.category.models.description",
+            CategoryId::Ollama => "cleanup.category.ollama.description",
+            CategoryId::Huggingface => "cleanup.category.huggingface.description",
+        })
+    }
+}
+
+impl DataCategory {
+    /// Textos en el idioma de la interfaz (por defecto se generan en español)
+    fn localize(mut self, language: &str) -> Self {
+        self.label = self.id.label().render(language);
+        self.description = self.id.description().render(language);
+        if self.secure_wipe_notice.is_some() {
+            self.secure_wipe_notice = Some(Message::new(SECURE_WIPE_NOTICE).render(language));
+        }
+        self
+    }
+}
+
+/// Categoría sin medir: rutas y existencia, sin recorrer el disco
+fn category_skeleton(id: CategoryId, roots: &DataRoots) -> DataCategory {
+    DataCategory {
+        id,
+        label: id.label().render("es"),
+        description: id.description().render("es"),
+        path: roots.path(id).to_string_lossy().into(),
+        size_bytes: 0,
+        size_pending: true,
+        is_shared: id.is_shared(),
+        is_destructive: id.is_destructive(),
+        exists: roots.exists(id),
+        breakdown: None,
+        models: None,
+        reclaimable_bytes: None,
+        secure_wipe_notice: id
+            .supports_secure_wipe()
+            .then(|| Message::new(SECURE_WIPE_NOTICE).render("es")),
+    }
+}
+
+/// Mide una categoría: tamaño, desglose y modelos (recorre el disco, puede tardar)
+fn measure_category(id: CategoryId, roots: &DataRoots, options: SizeOptions) -> DataCategory {
+    let mut category = category_skeleton(id, roots);
+    let path = roots.path(id);
+
+    let (size_bytes, breakdown) = match id {
+        CategoryId::UserData => {
+            // Solo contar DB + data/ + documents/, no models/
+            let db = roots.na.join(database::DB_FILE_NAME);
+            // Volcar el WAL para que el tamaño refleje el estado real de la DB
+            database::checkpoint_wal_best_effort(&db);
+            let mut nodes = vec![
+                SizeNode {
+                    name: database::DB_FILE_NAME.into(),
+                    path: db.to_string_lossy().into(),
+                    size_bytes: database::database_size(&db),
+                    children: vec![],
+                },
+                path_node("data", &roots.na.join("data"), BREAKDOWN_DEPTH - 1, options),
+                path_node(
+                    "documents",
+                    &roots.na.join("documents"),
+                    BREAKDOWN_DEPTH - 1,
+                    options,
+                ),
+            ];
+            nodes.retain(|n| n.size_bytes > 0);
+            nodes.sort_by_key(|n| std::cmp::Reverse(n.size_bytes));
+            (nodes.iter().map(|n| n.size_bytes).sum(), nodes)
+        }
+        CategoryId::WebviewCache => {
+            let mut nodes: Vec<SizeNode> = roots
+                .webview_cache_paths()
+                .iter()
+                .map(|p| {
+                    let name = p.strip_prefix(&roots.app_data).unwrap_or(p);
+                    path_node(&name.to_string_lossy(), p, BREAKDOWN_DEPTH - 1, options)
+                })
+                .collect();
+            nodes.retain(|n| n.size_bytes > 0);
+            nodes.sort_by_key(|n| std::cmp::Reverse(n.size_bytes));
+            (nodes.iter().map(|n| n.size_bytes).sum(), nodes)
+        }
+        // La caché de WebView2 vive dentro de app_data: no contarla dos veces
+        CategoryId::AppSettings => SizeWalker::new(options)
+            .excluding(roots.webview_cache_paths())
+            .breakdown(&path, BREAKDOWN_DEPTH),
+        _ => dir_breakdown(&path, BREAKDOWN_DEPTH, options),
+    };
+
+    category.models = match id {
+        CategoryId::Ollama => Some(models::ollama_usage(&path, &ModelManifest::bundled())),
+        CategoryId::Huggingface => {
+            Some(models::huggingface_usage(&path, &ModelManifest::bundled()))
+        }
+        CategoryId::Models => Some(models::app_model_usage(
+            &path,
+            &roots.path(CategoryId::Ollama),
+            &ModelManifest::bundled(),
+        )),
+        _ => None,
+    };
+    if id == CategoryId::Models {
+        let duplicates = model_dedup::find_duplicates(
+            &ModelManifest::bundled(),
+            &roots.models,
+            &roots.path(CategoryId::Huggingface),
+        );
+        category.reclaimable_bytes = Some(duplicates.iter().map(|d| d.reclaimable_bytes).sum());
+    }
+    category.size_bytes = size_bytes;
+    category.breakdown = Some(breakdown);
+    category.size_pending = false;
+    category
+}
+
+/// Contador de escaneos, para que el frontend descarte resultados de uno anterior
+static NEXT_SCAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Respuesta inmediata de `get_data_categories`
+#[derive(Serialize, Clone)]
+pub struct DataCategoriesScan {
+    /// Identificador del escaneo (se repite en cada evento `data-category-size`)
+    pub scan_id: u64,
+    /// Categorías con `size_pending: true`
+    pub categories: Vec<DataCategory>,
+}
+
+/// Lista todas las categorías de datos sin bloquear el hilo de IPC.
+///
+/// Devuelve enseguida rutas y existencia; los tamaños se calculan en segundo
+/// plano y llegan uno a uno en eventos `data-category-size` ({scan_id, category}).
+/// `follow_symlinks` permite medir enlaces simbólicos internos (p.ej. modelos en otro disco).
 #[tauri::command]
-pub fn delete_data_category(id: String) -> Result<String, String> {
-    let home = dirs::home_dir().ok_or("No se pudo determinar el directorio home")?;
-    let na = home.join(".narrative_assistant");
-
-    match id.as_str() {
-        "app_cache" => {
-            let path = if cfg!(target_os = "windows") {
-                dirs::data_local_dir()
-                    .unwrap_or_default()
-                    .join("Narrative Assistant")
-            } else {
-                dirs::data_dir()
-                    .unwrap_or_default()
-                    .join("Narrative Assistant")
+pub async fn get_data_categories(
+    app: AppHandle,
+    follow_symlinks: Option<bool>,
+) -> Result<DataCategoriesScan, String> {
+    let options = SizeOptions {
+        follow_symlinks: follow_symlinks.unwrap_or(false),
+    };
+    let settings = app.state::<SettingsStore>().get();
+    let language = settings.language.clone();
+    let roots = Arc::new(resolve_roots(&settings)?);
+    let scan_id = NEXT_SCAN_ID.fetch_add(1, Ordering::Relaxed);
+
+    let categories = ALL_CATEGORIES
+        .iter()
+        .map(|id| category_skeleton(*id, &roots).localize(&language))
+        .collect();
+
+    for id in ALL_CATEGORIES {
+        let app = app.clone();
+        let roots = Arc::clone(&roots);
+        let language = language.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let category = measure_category(id, &roots, options).localize(&language);
+            let _ = app.emit(
+                "data-category-size",
+                serde_json::json!({ "scan_id": scan_id, "category": category }),
+            );
+        });
+    }
+
+    Ok(DataCategoriesScan {
+        scan_id,
+        categories,
+    })
+}
+
+/// Rutas de datos, o el error "sin directorio home" en el idioma de `settings`
+fn resolve_roots(settings: &Settings) -> Result<DataRoots, String> {
+    DataRoots::resolve(settings)
+        .ok_or_else(|| Message::new(NO_HOME_ERROR).render(&settings.language))
+}
+
+/// Mide todas las categorías de forma síncrona
+pub(crate) fn measure_all_categories(
+    settings: &Settings,
+    options: SizeOptions,
+) -> Vec<DataCategory> {
+    let Some(roots) = DataRoots::resolve(settings) else {
+        return vec![];
+    };
+    ALL_CATEGORIES
+        .iter()
+        .map(|id| measure_category(*id, &roots, options))
+        .collect()
+}
+
+/// Formato del informe de almacenamiento
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+/// Informe de almacenamiento (formato JSON)
+#[derive(Serialize)]
+struct StorageReport<'a> {
+    app_version: &'a str,
+    platform: &'static str,
+    /// Segundos desde epoch (UTC)
+    generated_at: u64,
+    /// Datos propios de la app
+    own_bytes: u64,
+    /// Directorios compartidos (Ollama, HuggingFace)
+    shared_bytes: u64,
+    categories: &'a [DataCategory],
+}
+
+/// Cabecera del informe CSV: una fila por categoría, nodo de desglose y modelo
+const CSV_HEADER: &str = "category,kind,level,name,path,size_bytes,shared,destructive,used_by_app";
+
+/// Escapa un campo CSV (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn push_csv_nodes(out: &mut Vec<String>, category: &DataCategory, nodes: &[SizeNode], level: u32) {
+    for node in nodes {
+        out.push(format!(
+            "{},directory,{},{},{},{},{},{},",
+            csv_field(category.id.as_str()),
+            level,
+            csv_field(&node.name),
+            csv_field(&node.path),
+            node.size_bytes,
+            category.is_shared,
+            category.is_destructive,
+        ));
+        push_csv_nodes(out, category, &node.children, level + 1);
+    }
+}
+
+fn storage_report_csv(categories: &[DataCategory]) -> String {
+    let mut rows = vec![CSV_HEADER.to_string()];
+    for category in categories {
+        rows.push(format!(
+            "{},category,0,{},{},{},{},{},",
+            csv_field(category.id.as_str()),
+            csv_field(&category.label),
+            csv_field(&category.path),
+            category.size_bytes,
+            category.is_shared,
+            category.is_destructive,
+        ));
+        push_csv_nodes(
+            &mut rows,
+            category,
+            category.breakdown.as_deref().unwrap_or_default(),
+            1,
+        );
+        for model in category.models.as_deref().unwrap_or_default() {
+            rows.push(format!(
+                "{},model,1,{},{},{},{},{},{}",
+                csv_field(category.id.as_str()),
+                csv_field(&model.name),
+                csv_field(&model.path),
+                model.size_bytes,
+                category.is_shared,
+                category.is_destructive,
+                model.used_by_app,
+            ));
+        }
+    }
+    rows.push(String::new());
+    rows.join("\n")
+}
+
+/// Genera el informe en el formato pedido
+fn render_storage_report(
+    categories: &[DataCategory],
+    format: ReportFormat,
+    app_version: &str,
+) -> Result<String, String> {
+    match format {
+        ReportFormat::Csv => Ok(storage_report_csv(categories)),
+        ReportFormat::Json => {
+            let total = |shared: bool| {
+                categories
+                    .iter()
+                    .filter(|c| c.is_shared == shared)
+                    .map(|c| c.size_bytes)
+                    .sum()
+            };
+            let report = StorageReport {
+                app_version,
+                platform: std::env::consts::OS,
+                generated_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                own_bytes: total(false),
+                shared_bytes: total(true),
+                categories,
             };
+            serde_json::to_string_pretty(&report)
+                .map_err(|e| format!("Error generando el informe: {}", e))
+        }
+    }
+}
+
+/// Exporta a `path` el desglose completo de categorías, tamaños y rutas.
+///
+/// Pensado para soporte y departamentos de IT ("¿por qué ocupa 60 GB?").
+/// Devuelve la ruta escrita.
+#[tauri::command]
+pub async fn export_storage_report(
+    app: AppHandle,
+    grants: State<'_, ExportGrants>,
+    path: String,
+    format: ReportFormat,
+) -> Result<String, String> {
+    grants.authorize(Path::new(&path))?;
+    let app_version = app.package_info().version.to_string();
+    let settings = app.state::<SettingsStore>().get();
+    let language = settings.language.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let categories: Vec<DataCategory> =
+            measure_all_categories(&settings, SizeOptions::default())
+                .into_iter()
+                .map(|category| category.localize(&language))
+                .collect();
+        let contents = render_storage_report(&categories, format, &app_version)?;
+        fs::write(&path, contents).map_err(|e| format!("Error escribiendo {}: {}", path, e))?;
+        Ok(path)
+    })
+    .await
+    .map_err(|e| format!("Error generando el informe: {}", e))?
+}
+
+/// Directorio de copias automáticas dentro de ~/.narrative_assistant
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Extensión de los ficheros de snapshot (copias de la DB SQLite)
+const SNAPSHOT_EXTENSION: &str = "db";
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Política de retención de snapshots (esquema abuelo-padre-hijo).
+/// Un snapshot se conserva si cumple cualquiera de las reglas.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Los N más recientes, sin importar la fecha
+    pub keep_last: u32,
+    /// El más reciente de cada uno de los últimos N días con snapshots
+    pub keep_daily: u32,
+    /// El más reciente de cada una de las últimas N semanas con snapshots
+    pub keep_weekly: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 5,
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+/// Resultado de aplicar la política de retención
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RetentionReport {
+    pub kept: usize,
+    pub deleted: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Snapshot en disco
+pub(crate) struct SnapshotFile {
+    pub path: PathBuf,
+    /// Segundos desde epoch (fecha de modificación)
+    pub created: u64,
+    pub size_bytes: u64,
+}
+
+pub(crate) fn list_snapshot_files(dir: &Path) -> Vec<SnapshotFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut snapshots: Vec<SnapshotFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(SNAPSHOT_EXTENSION) {
+                return None;
+            }
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            let created = meta
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            Some(SnapshotFile {
+                path,
+                created,
+                size_bytes: meta.len(),
+            })
+        })
+        .collect();
+    // Más reciente primero
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created));
+    snapshots
+}
+
+/// Índices (sobre `created`, ordenado de más reciente a más antiguo) que conserva la política
+fn retained_indices(created: &[u64], policy: RetentionPolicy) -> HashSet<usize> {
+    let mut keep: HashSet<usize> = (0..created.len().min(policy.keep_last as usize)).collect();
+
+    // El más reciente de cada periodo, hasta `limit` periodos distintos
+    let mut keep_per_period = |period: &dyn Fn(u64) -> u64, limit: u32| {
+        let mut seen = HashSet::new();
+        for (i, ts) in created.iter().enumerate() {
+            if seen.len() >= limit as usize {
+                break;
+            }
+            if seen.insert(period(*ts)) {
+                keep.insert(i);
+            }
+        }
+    };
+    keep_per_period(&|ts| ts / SECS_PER_DAY, policy.keep_daily);
+    // El 1/1/1970 fue jueves: desplazar 3 días para que las semanas empiecen en lunes
+    keep_per_period(&|ts| (ts / SECS_PER_DAY + 3) / 7, policy.keep_weekly);
+    keep
+}
+
+/// Aplica la política sobre los snapshots de `dir`, borrando los que no conserva
+pub(crate) fn apply_retention(dir: &Path, policy: RetentionPolicy) -> RetentionReport {
+    let snapshots = list_snapshot_files(dir);
+    let created: Vec<u64> = snapshots.iter().map(|s| s.created).collect();
+    let keep = retained_indices(&created, policy);
+
+    let mut report = RetentionReport::default();
+    for (i, snapshot) in snapshots.iter().enumerate() {
+        if keep.contains(&i) {
+            report.kept += 1;
+            continue;
+        }
+        match fs::remove_file(&snapshot.path) {
+            Ok(()) => {
+                report.reclaimed_bytes += snapshot.size_bytes;
+                report.deleted.push(snapshot.path.to_string_lossy().into());
+            }
+            Err(e) => {
+                tracing::warn!(
+                    target: "cleanup",
+                    "No se pudo eliminar el snapshot {}: {}",
+                    snapshot.path.display(),
+                    e
+                );
+                report.kept += 1;
+            }
+        }
+    }
+    report
+}
+
+/// Aplica la retención a ~/.narrative_assistant/snapshots
+/// (la política de las preferencias si no se indica otra)
+#[tauri::command]
+pub async fn apply_snapshot_retention(
+    store: State<'_, SettingsStore>,
+    policy: Option<RetentionPolicy>,
+) -> Result<RetentionReport, String> {
+    let settings = store.get();
+    let roots = resolve_roots(&settings)?;
+    let dir = roots.na.join(SNAPSHOTS_DIR);
+    let policy = policy.unwrap_or(settings.snapshot_retention);
+    tauri::async_runtime::spawn_blocking(move || apply_retention(&dir, policy))
+        .await
+        .map_err(|e| format!("Error aplicando la retención: {}", e))
+}
+
+/// Descriptor de la estructura de ~/.narrative_assistant (versión del layout)
+const LAYOUT_FILE: &str = "layout.json";
+
+/// Versión del layout que espera esta build
+const CURRENT_LAYOUT_VERSION: u32 = 1;
+
+/// Contenido de `layout.json`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct LayoutDescriptor {
+    version: u32,
+}
+
+/// Paso de migración: lleva el layout a la versión `to`.
+/// Debe ser idempotente: si se interrumpe, se vuelve a aplicar en el siguiente arranque.
+struct LayoutMigration {
+    to: u32,
+    description: &'static str,
+    apply: fn(&Path) -> Result<(), String>,
+}
+
+/// Migraciones en orden. Para reorganizar el directorio: subir
+/// CURRENT_LAYOUT_VERSION y añadir aquí el paso (usando `move_if_present`).
+const LAYOUT_MIGRATIONS: &[LayoutMigration] = &[LayoutMigration {
+    to: 1,
+    description: "Descriptor inicial del layout",
+    apply: |_| Ok(()),
+}];
+
+/// Resultado de la migración de layout al arrancar
+#[derive(Debug, PartialEq, Eq)]
+pub struct LayoutMigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Descripción de los pasos aplicados
+    pub applied: Vec<&'static str>,
+}
+
+/// Mueve `from` a `to` (relativos a `root`) si existe el origen y no el destino
+#[allow(dead_code)] // Para futuras migraciones
+fn move_if_present(root: &Path, from: &str, to: &str) -> Result<(), String> {
+    let source = root.join(from);
+    let target = root.join(to);
+    if !source.exists() || target.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando {}: {}", parent.display(), e))?;
+    }
+    fs::rename(&source, &target).map_err(|e| {
+        format!(
+            "Error moviendo {} a {}: {}",
+            source.display(),
+            target.display(),
+            e
+        )
+    })
+}
+
+fn read_layout_version(root: &Path) -> Result<u32, String> {
+    let file = root.join(LAYOUT_FILE);
+    match fs::read_to_string(&file) {
+        Ok(raw) => serde_json::from_str::<LayoutDescriptor>(&raw)
+            .map(|d| d.version)
+            .map_err(|e| format!("{} invalido: {}", file.display(), e)),
+        // Instalaciones anteriores al descriptor
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(format!("Error leyendo {}: {}", file.display(), e)),
+    }
+}
+
+fn write_layout_version(root: &Path, version: u32) -> Result<(), String> {
+    let file = root.join(LAYOUT_FILE);
+    let json =
+        serde_json::to_string_pretty(&LayoutDescriptor { version }).map_err(|e| e.to_string())?;
+    fs::write(&file, json).map_err(|e| format!("Error escribiendo {}: {}", file.display(), e))
+}
+
+/// Aplica las migraciones pendientes sobre `root`, guardando la versión tras cada paso.
+/// Si el layout es más nuevo que esta build (downgrade) no se toca nada.
+fn migrate_layout(
+    root: &Path,
+    migrations: &[LayoutMigration],
+    target: u32,
+) -> Result<LayoutMigrationReport, String> {
+    let from_version = read_layout_version(root)?;
+    let mut report = LayoutMigrationReport {
+        from_version,
+        to_version: from_version,
+        applied: vec![],
+    };
+    if from_version >= target {
+        return Ok(report);
+    }
+    for migration in migrations
+        .iter()
+        .filter(|m| m.to > from_version && m.to <= target)
+    {
+        (migration.apply)(root)
+            .map_err(|e| format!("Migracion a v{} fallida: {}", migration.to, e))?;
+        write_layout_version(root, migration.to)?;
+        report.to_version = migration.to;
+        report.applied.push(migration.description);
+    }
+    Ok(report)
+}
+
+/// Migra ~/.narrative_assistant al layout actual. Llamar al arrancar, antes que el backend.
+/// Devuelve `None` si el directorio aún no existe (instalación nueva).
+pub fn run_layout_migrations() -> Result<Option<LayoutMigrationReport>, String> {
+    // Solo usa ~/.narrative_assistant, que no depende de las preferencias
+    let roots = DataRoots::resolve(&Settings::default())
+        .ok_or("No se pudo determinar el directorio home")?;
+    if !roots.na.is_dir() {
+        return Ok(None);
+    }
+    migrate_layout(&roots.na, LAYOUT_MIGRATIONS, CURRENT_LAYOUT_VERSION).map(Some)
+}
+
+/// Mensaje cuando no se puede resolver el directorio home
+const NO_HOME_ERROR: &str = "cleanup.error.no_home";
+
+/// Mensaje al intentar eliminar un directorio compartido
+const SHARED_DELETE_ERROR: &str = "cleanup.error.shared";
+
+/// Texto que acompaña a la opción de borrado seguro en la UI (clave de i18n.rs)
+const SECURE_WIPE_NOTICE: &str = "cleanup.secure_wipe_notice";
+
+/// Tamaño del bloque de ceros usado al sobrescribir
+const WIPE_CHUNK: usize = 64 * 1024;
+
+/// Sobrescribe un fichero con ceros, fuerza la escritura a disco y lo elimina
+fn wipe_file(path: &Path) -> std::io::Result<()> {
+    use std::io::Write;
+    let len = fs::metadata(path)?.len();
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = vec![0_u8; WIPE_CHUNK];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(WIPE_CHUNK as u64) as usize;
+        file.write_all(&zeros[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+/// Borrado seguro (best-effort) de un fichero o directorio.
+/// Los symlinks se eliminan sin tocar su destino.
+fn secure_wipe_path(path: &Path) -> std::io::Result<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            secure_wipe_path(&entry?.path())?;
+        }
+        fs::remove_dir(path)
+    } else if meta.is_file() {
+        wipe_file(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Elimina un fichero o directorio de datos de usuario
+fn remove_user_path(path: &Path, secure_wipe: bool) -> std::io::Result<()> {
+    if secure_wipe {
+        secure_wipe_path(path)
+    } else if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Borra del disco los ficheros de una categoría. Rechaza eliminar directorios compartidos.
+fn remove_category_files(
+    roots: &DataRoots,
+    id: CategoryId,
+    secure_wipe: bool,
+) -> Result<Message, Message> {
+    if secure_wipe && !id.supports_secure_wipe() {
+        return Err(Message::new("cleanup.error.secure_wipe_unsupported"));
+    }
+    let na = &roots.na;
+    let delete_error = |path: &Path, e: std::io::Error| {
+        Message::new("cleanup.error.delete")
+            .with("path", path.display())
+            .with("error", e)
+    };
+
+    match id {
+        CategoryId::WebviewCache => {
+            let failures: Vec<String> = roots
+                .webview_cache_paths()
+                .iter()
+                .filter(|p| p.exists())
+                .filter_map(|p| {
+                    fs::remove_dir_all(p)
+                        .err()
+                        .map(|e| format!("{}: {}", p.display(), e))
+                })
+                .collect();
+            if !failures.is_empty() {
+                return Err(Message::new("cleanup.error.webview_cache")
+                    .with("details", failures.join("; ")));
+            }
+            Ok(Message::new("cleanup.deleted.webview_cache"))
+        }
+        CategoryId::AppSettings => {
+            // Incluye la caché de WebView2 (Windows), que se regenera sola
+            let path = &roots.app_data;
             if path.exists() {
-                fs::remove_dir_all(&path)
-                    .map_err(|e| format!("Error eliminando {}: {}", path.display(), e))?;
+                fs::remove_dir_all(path).map_err(|e| delete_error(path, e))?;
             }
-            Ok("Datos de la aplicacion eliminados".into())
+            Ok(Message::new("cleanup.deleted.app_settings"))
         }
-        "user_data" => {
+        CategoryId::UserData => {
+            let mut failures = Vec::new();
             // Delete DB files
             for ext in &["", "-shm", "-wal"] {
-                let db = na.join(format!("narrative_assistant.db{}", ext));
+                let db = na.join(format!("{}{}", database::DB_FILE_NAME, ext));
                 if db.exists() {
-                    let _ = fs::remove_file(&db);
+                    if let Err(e) = remove_user_path(&db, secure_wipe) {
+                        failures.push(format!("{}: {}", db.display(), e));
+                    }
                 }
             }
             // Delete data and documents directories
             for subdir in &["data", "documents"] {
                 let path = na.join(subdir);
                 if path.exists() {
-                    let _ = fs::remove_dir_all(&path);
+                    if let Err(e) = remove_user_path(&path, secure_wipe) {
+                        failures.push(format!("{}: {}", path.display(), e));
+                    }
                 }
             }
+            if !failures.is_empty() {
+                return Err(
+                    Message::new("cleanup.error.user_data").with("details", failures.join("; "))
+                );
+            }
             // Remove parent if empty
-            let _ = fs::remove_dir(&na);
-            Ok("Proyectos y base de datos eliminados".into())
+            let _ = fs::remove_dir(na);
+            if secure_wipe {
+                Ok(Message::new("cleanup.wiped.user_data"))
+            } else {
+                Ok(Message::new("cleanup.deleted.user_data"))
+            }
         }
-        "models" => {
-            let path = na.join("models");
+        CategoryId::Snapshots => {
+            let path = na.join(SNAPSHOTS_DIR);
             if path.exists() {
-                fs::remove_dir_all(&path)
-                    .map_err(|e| format!("Error eliminando modelos: {}", e))?;
+                fs::remove_dir_all(&path).map_err(|e| delete_error(&path, e))?;
+            }
+            Ok(Message::new("cleanup.deleted.snapshots"))
+        }
+        CategoryId::Models => {
+            let path = &roots.models;
+            if path.exists() {
+                fs::remove_dir_all(path).map_err(|e| delete_error(path, e))?;
             }
             // Remove parent if empty
-            let _ = fs::remove_dir(&na);
-            Ok("Modelos NLP eliminados".into())
-        }
-        "ollama" | "huggingface" => Err(
-            "Los directorios compartidos no se pueden eliminar automaticamente. \
-                 Eliminelos manualmente si no los utiliza con otras aplicaciones."
-                .into(),
-        ),
-        _ => Err(format!("Categoria desconocida: {}", id)),
+            let _ = fs::remove_dir(na);
+            Ok(Message::new("cleanup.deleted.models"))
+        }
+        CategoryId::Ollama | CategoryId::Huggingface => Err(Message::new(SHARED_DELETE_ERROR)),
+    }
+}
+
+/// Validez de un token de confirmación emitido por `preview_delete`
+const CONFIRMATION_TOKEN_TTL: Duration = Duration::from_secs(120);
+
+/// Tokens de confirmación pendientes para eliminaciones destructivas.
+///
+/// `preview_delete` emite un token de un solo uso por categoría; `delete_data_category`
+/// lo exige para las categorías destructivas, de modo que una llamada errónea del
+/// frontend no pueda borrar datos del usuario sin pasar por la confirmación.
+pub struct DeletionGuard {
+    ttl: Duration,
+    pending: Mutex<HashMap<CategoryId, (String, Instant)>>,
+}
+
+impl DeletionGuard {
+    pub fn new() -> Self {
+        Self::with_ttl(CONFIRMATION_TOKEN_TTL)
+    }
+
+    fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Emite un token nuevo para la categoría (invalida el anterior)
+    fn issue(&self, id: CategoryId) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(id, (token.clone(), Instant::now()));
+        token
+    }
+
+    /// Consume el token: solo es válido una vez, para su categoría y antes de caducar
+    fn consume(&self, id: CategoryId, token: Option<&str>) -> Result<(), Message> {
+        let token = token.ok_or(Message::new("cleanup.error.confirmation_required"))?;
+        let mut pending = self.pending.lock().unwrap();
+        match pending.remove(&id) {
+            Some((expected, issued_at)) if expected == token => {
+                if issued_at.elapsed() > self.ttl {
+                    Err(Message::new("cleanup.error.confirmation_expired"))
+                } else {
+                    Ok(())
+                }
+            }
+            Some(other) => {
+                // Token incorrecto: conservar el válido
+                pending.insert(id, other);
+                Err(Message::new("cleanup.error.confirmation_invalid"))
+            }
+            None => Err(Message::new("cleanup.error.confirmation_invalid")),
+        }
+    }
+}
+
+/// Lo que se eliminaría al borrar una categoría (paso previo a la confirmación)
+#[derive(Serialize, Clone)]
+pub struct DeletePreview {
+    pub id: CategoryId,
+    pub label: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_destructive: bool,
+    /// Token a enviar a `delete_data_category` (solo categorías destructivas)
+    pub confirmation_token: Option<String>,
+}
+
+/// Prepara la eliminación de una categoría: devuelve qué se borraría y, si es
+/// destructiva, el token de confirmación que exige `delete_data_category`.
+///
+/// La medición recorre el disco, así que va en un hilo bloqueante (como
+/// `list_snapshots`) para no congelar la interfaz.
+#[tauri::command]
+pub async fn preview_delete(
+    store: State<'_, SettingsStore>,
+    guard: State<'_, DeletionGuard>,
+    id: CategoryId,
+) -> Result<DeletePreview, String> {
+    let settings = store.get();
+    let language = settings.language.clone();
+    if id.is_shared() {
+        return Err(Message::new(SHARED_DELETE_ERROR).render(&language));
+    }
+    let roots = resolve_roots(&settings)?;
+    let category = tauri::async_runtime::spawn_blocking(move || {
+        measure_category(id, &roots, SizeOptions::default()).localize(&language)
+    })
+    .await
+    .map_err(|e| format!("Error midiendo la categoria: {}", e))?;
+    Ok(DeletePreview {
+        id,
+        label: category.label,
+        path: category.path,
+        size_bytes: category.size_bytes,
+        is_destructive: id.is_destructive(),
+        confirmation_token: id.is_destructive().then(|| guard.issue(id)),
+    })
+}
+
+/// Notifica al frontend la fase actual de una eliminación
+fn emit_deletion_phase(app: &AppHandle, id: CategoryId, phase: &str, message: &Message) {
+    let mut payload = message.to_json(&i18n::language(app));
+    payload.insert("category".into(), serde_json::json!(id));
+    payload.insert("phase".into(), phase.into());
+    let _ = app.emit("data-deletion-progress", payload);
+}
+
+/// Elimina una categoría de datos. Rechaza eliminar directorios compartidos.
+///
+/// Para las categorías que usa el backend, lo detiene antes de borrar y lo
+/// relanza después, emitiendo cada fase en `data-deletion-progress`.
+/// `secure_wipe` sobrescribe los ficheros antes de borrarlos (solo `user_data`).
+#[tauri::command]
+pub async fn delete_data_category(
+    app: AppHandle,
+    server_state: State<'_, BackendServer>,
+    guard: State<'_, DeletionGuard>,
+    id: CategoryId,
+    confirmation_token: Option<String>,
+    secure_wipe: Option<bool>,
+) -> Result<String, String> {
+    let settings = app.state::<SettingsStore>().get();
+    let language = settings.language.clone();
+    let secure_wipe = secure_wipe.unwrap_or(false);
+    if id.is_destructive() {
+        guard
+            .consume(id, confirmation_token.as_deref())
+            .map_err(|e| e.render(&language))?;
+    }
+    let roots = resolve_roots(&settings)?;
+    let audit = AuditEntry::new("delete_data_category", id.as_str());
+    // Medir y borrar recorren el disco: fuera del hilo del comando
+    let measure_and_remove = move || {
+        let bytes = measure_category(id, &roots, SizeOptions::default()).size_bytes;
+        (bytes, remove_category_files(&roots, id, secure_wipe))
+    };
+    if !id.requires_backend_pause() {
+        let (bytes, result) = run_deletion(measure_and_remove).await;
+        let result = result
+            .map(|m| m.render(&language))
+            .map_err(|e| e.render(&language));
+        audit::record(audit.bytes(bytes).outcome(&result));
+        return result;
+    }
+
+    emit_deletion_phase(
+        &app,
+        id,
+        "stopping_backend",
+        &Message::new("cleanup.stopping_backend"),
+    );
+    let was_running = crate::pause_backend(&app, &server_state);
+
+    let deleting_msg = if secure_wipe {
+        Message::new("cleanup.wiping")
+    } else {
+        Message::new("cleanup.deleting")
+    };
+    emit_deletion_phase(&app, id, "deleting", &deleting_msg);
+    let (bytes, result) = run_deletion(measure_and_remove).await;
+
+    if was_running {
+        emit_deletion_phase(
+            &app,
+            id,
+            "restarting_backend",
+            &Message::new("cleanup.restarting_backend"),
+        );
+    }
+    crate::resume_backend(&app, server_state, was_running).await;
+
+    match &result {
+        Ok(msg) => emit_deletion_phase(&app, id, "completed", msg),
+        Err(e) => emit_deletion_phase(&app, id, "error", e),
+    }
+    let result = result
+        .map(|m| m.render(&language))
+        .map_err(|e| e.render(&language));
+    audit::record(audit.bytes(bytes).outcome(&result));
+    result
+}
+
+/// Ejecuta la medición y el borrado en un hilo bloqueante: (bytes medidos, resultado)
+async fn run_deletion<F>(task: F) -> (u64, Result<Message, Message>)
+where
+    F: FnOnce() -> (u64, Result<Message, Message>) + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .unwrap_or_else(|e| {
+            (
+                0,
+                Err(Message::new("cleanup.error.delete_task").with("error", e)),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(path: &Path, len: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0_u8; len]).unwrap();
+    }
+
+    fn test_roots(home: &Path) -> DataRoots {
+        DataRoots {
+            home: home.to_path_buf(),
+            na: home.join(".narrative_assistant"),
+            models: home.join(".narrative_assistant").join("models"),
+            app_data: home.join("app"),
+            webkit_cache: Some(home.join("cache").join(APP_IDENTIFIER)),
+        }
+    }
+
+    #[test]
+    fn webview_cache_is_measured_apart_from_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots = test_roots(dir.path());
+        write_file(&roots.app_data.join("EBWebView/Default/Cache/data_0"), 5000);
+        write_file(&roots.app_data.join("EBWebView/GrShaderCache/f"), 300);
+        write_file(
+            &roots
+                .app_data
+                .join("EBWebView/Default/Local Storage/leveldb/1.log"),
+            40,
+        );
+        write_file(&roots.app_data.join("window-state.json"), 2);
+        write_file(
+            &roots.webkit_cache.clone().unwrap().join("WebKitCache/x"),
+            700,
+        );
+
+        let options = SizeOptions::default();
+        let cache = measure_category(CategoryId::WebviewCache, &roots, options);
+        let settings = measure_category(CategoryId::AppSettings, &roots, options);
+
+        assert_eq!(cache.size_bytes, 6000);
+        assert_eq!(cache.breakdown.unwrap()[0].name, "EBWebView/Default/Cache");
+        assert!(!cache.is_destructive);
+        assert_eq!(settings.size_bytes, 42);
+        assert!(settings.is_destructive);
+    }
+
+    #[test]
+    fn skeleton_is_pending_and_measure_fills_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots = test_roots(dir.path());
+        write_file(&roots.na.join("models/spacy/model.bin"), 1234);
+
+        let skeleton = category_skeleton(CategoryId::Models, &roots);
+        assert!(skeleton.exists);
+        assert!(skeleton.size_pending);
+        assert_eq!(skeleton.size_bytes, 0);
+        assert!(skeleton.breakdown.is_none());
+
+        let measured = measure_category(CategoryId::Models, &roots, SizeOptions::default());
+        assert!(!measured.size_pending);
+        assert_eq!(measured.size_bytes, 1234);
+        assert_eq!(measured.path, skeleton.path);
+
+        assert!(!category_skeleton(CategoryId::Ollama, &roots).exists);
+    }
+
+    #[test]
+    fn dir_breakdown_sums_two_levels_and_sorts_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&root.join("EBWebView/Cache/data_0"), 3000);
+        write_file(&root.join("EBWebView/Cache/data_1"), 1000);
+        write_file(&root.join("EBWebView/Local State"), 50);
+        write_file(&root.join("logs/app.log"), 200);
+        write_file(&root.join("settings.json"), 10);
+
+        let (total, nodes) = dir_breakdown(root, BREAKDOWN_DEPTH, SizeOptions::default());
+
+        assert_eq!(total, 4260);
+        assert_eq!(total, dir_size(root));
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["EBWebView", "logs", LOOSE_FILES_NODE]);
+
+        let webview = &nodes[0];
+        assert_eq!(webview.size_bytes, 4050);
+        assert_eq!(webview.children[0].name, "Cache");
+        assert_eq!(webview.children[0].size_bytes, 4000);
+        assert!(
+            webview.children[0].children.is_empty(),
+            "el desglose no debe pasar de 2 niveles"
+        );
+    }
+
+    const FOLLOW: SizeOptions = SizeOptions {
+        follow_symlinks: true,
+    };
+
+    #[cfg(unix)]
+    #[test]
+    fn dir_size_skips_symlinks_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&root.join("blobs/abc"), 1000);
+        std::os::unix::fs::symlink(root.join("blobs/abc"), root.join("snapshot_file")).unwrap();
+        std::os::unix::fs::symlink(root.join("blobs"), root.join("snapshot_dir")).unwrap();
+
+        assert_eq!(dir_size(root), 1000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn followed_symlinks_are_counted_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&root.join("blobs/abc"), 1000);
+        std::os::unix::fs::symlink(root.join("blobs/abc"), root.join("snapshot_file")).unwrap();
+        std::os::unix::fs::symlink(root.join("blobs"), root.join("snapshot_dir")).unwrap();
+
+        assert_eq!(SizeWalker::new(FOLLOW).size(root), 1000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn followed_symlinks_reach_other_drives_and_skip_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let external = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&external.path().join("model.bin"), 700);
+        write_file(&root.join("cache/a.bin"), 300);
+        std::os::unix::fs::symlink(external.path(), root.join("cache/moved")).unwrap();
+        // Ciclo: cache/loop -> raíz
+        std::os::unix::fs::symlink(root, root.join("cache/loop")).unwrap();
+
+        assert_eq!(SizeWalker::new(FOLLOW).size(root), 1000);
+        assert_eq!(dir_size(root), 300);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hardlinked_files_are_counted_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&root.join("a/model.bin"), 500);
+        fs::create_dir_all(root.join("b")).unwrap();
+        fs::hard_link(root.join("a/model.bin"), root.join("b/model.bin")).unwrap();
+
+        assert_eq!(dir_size(root), 500);
+    }
+
+    fn move_legacy_db(root: &Path) -> Result<(), String> {
+        move_if_present(root, "legacy.db", "data/legacy.db")
+    }
+
+    const TEST_MIGRATIONS: &[LayoutMigration] = &[
+        LayoutMigration {
+            to: 1,
+            description: "inicial",
+            apply: |_| Ok(()),
+        },
+        LayoutMigration {
+            to: 2,
+            description: "mover legacy.db",
+            apply: move_legacy_db,
+        },
+    ];
+
+    #[test]
+    fn layout_migrations_upgrade_old_installs_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&root.join("legacy.db"), 10);
+
+        let report = migrate_layout(root, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, 2);
+        assert_eq!(report.applied, vec!["inicial", "mover legacy.db"]);
+        assert!(root.join("data/legacy.db").exists());
+        assert!(!root.join("legacy.db").exists());
+        assert_eq!(read_layout_version(root).unwrap(), 2);
+
+        let again = migrate_layout(root, TEST_MIGRATIONS, 2).unwrap();
+        assert!(again.applied.is_empty());
+    }
+
+    #[test]
+    fn layout_migrations_resume_and_skip_newer_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        write_layout_version(root, 1).unwrap();
+        let report = migrate_layout(root, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(report.applied, vec!["mover legacy.db"]);
+
+        write_layout_version(root, 7).unwrap();
+        let report = migrate_layout(root, TEST_MIGRATIONS, 2).unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(read_layout_version(root).unwrap(), 7);
+    }
+
+    #[test]
+    fn move_if_present_never_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(&root.join("a"), 1);
+        write_file(&root.join("b"), 2);
+
+        move_if_present(root, "a", "b").unwrap();
+        move_if_present(root, "missing", "c").unwrap();
+
+        assert!(root.join("a").exists());
+        assert_eq!(fs::metadata(root.join("b")).unwrap().len(), 2);
+        assert!(!root.join("c").exists());
+    }
+
+    #[test]
+    fn retention_keeps_last_daily_and_weekly() {
+        let day = SECS_PER_DAY;
+        // Lunes 2024-01-01 00:00 UTC = 19723 días desde epoch
+        let monday = 19_723 * day;
+        // Más reciente primero: 3 el mismo día, luego uno por día hacia atrás, y semanas antiguas
+        let created = vec![
+            monday + 14 * day + 300,
+            monday + 14 * day + 200,
+            monday + 14 * day + 100,
+            monday + 13 * day,
+            monday + 12 * day,
+            monday + 6 * day,
+            monday + 1,
+            monday - 30 * day,
+        ];
+        let policy = RetentionPolicy {
+            keep_last: 2,
+            keep_daily: 3,
+            keep_weekly: 3,
+        };
+
+        let mut keep: Vec<usize> = retained_indices(&created, policy).into_iter().collect();
+        keep.sort();
+
+        // 0,1: últimos; 0,3,4: diarios; 0, 3 y 5: el más reciente de cada semana
+        assert_eq!(keep, vec![0, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn retention_deletes_only_snapshot_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for i in 0..4 {
+            write_file(&root.join(format!("narrative_assistant-{}.db", i)), 100);
+        }
+        write_file(&root.join("notes.txt"), 5);
+
+        let policy = RetentionPolicy {
+            keep_last: 1,
+            keep_daily: 0,
+            keep_weekly: 0,
+        };
+        let report = apply_retention(root, policy);
+
+        assert_eq!(report.kept, 1);
+        assert_eq!(report.deleted.len(), 3);
+        assert_eq!(report.reclaimed_bytes, 300);
+        assert!(root.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn secure_wipe_overwrites_and_removes_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        write_file(&root.join("a/manuscrito.docx"), WIPE_CHUNK * 2 + 17);
+        write_file(&root.join("b.txt"), 0);
+        let outside = dir.path().join("outside.txt");
+        write_file(&outside, 10);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        secure_wipe_path(&root).unwrap();
+
+        assert!(!root.exists());
+        // El destino de un symlink no se toca
+        assert_eq!(fs::metadata(&outside).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn secure_wipe_only_for_supported_categories() {
+        assert!(
+            remove_category_files(&test_roots(Path::new("/")), CategoryId::Models, true).is_err()
+        );
+        assert!(CategoryId::UserData.supports_secure_wipe());
+    }
+
+    #[test]
+    fn category_as_str_matches_serde() {
+        for id in ALL_CATEGORIES {
+            assert_eq!(serde_json::to_value(id).unwrap(), id.as_str());
+        }
+    }
+
+    #[test]
+    fn storage_report_csv_flattens_breakdown_and_models() {
+        let category = DataCategory {
+            id: CategoryId::Ollama,
+            label: "Ollama, compartido".into(),
+            description: String::new(),
+            path: "/home/u/.ollama".into(),
+            size_bytes: 30,
+            size_pending: false,
+            is_shared: true,
+            is_destructive: false,
+            exists: true,
+            breakdown: Some(vec![SizeNode {
+                name: "models".into(),
+                path: "/home/u/.ollama/models".into(),
+                size_bytes: 30,
+                children: vec![SizeNode {
+                    name: "blobs \"x\"".into(),
+                    path: "/home/u/.ollama/models/blobs".into(),
+                    size_bytes: 30,
+                    children: vec![],
+                }],
+            }]),
+            models: Some(vec![ModelUsage {
+                name: "llama3.2:latest".into(),
+                path: "/m".into(),
+                size_bytes: 20,
+                used_by_app: true,
+                model_id: Some("ollama_llama3_2".into()),
+            }]),
+            reclaimable_bytes: None,
+            secure_wipe_notice: None,
+        };
+
+        let csv = storage_report_csv(&[category]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "ollama,category,0,\"Ollama, compartido\",/home/u/.ollama,30,true,false,"
+        );
+        assert_eq!(
+            lines[2],
+            "ollama,directory,1,models,/home/u/.ollama/models,30,true,false,"
+        );
+        assert_eq!(
+            lines[3],
+            "ollama,directory,2,\"blobs \"\"x\"\"\",/home/u/.ollama/models/blobs,30,true,false,"
+        );
+        assert_eq!(
+            lines[4],
+            "ollama,model,1,llama3.2:latest,/m,20,true,false,true"
+        );
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn category_ids_serialize_as_snake_case() {
+        assert_eq!(
+            serde_json::to_value(CategoryId::WebviewCache).unwrap(),
+            "webview_cache"
+        );
+        let id: CategoryId = serde_json::from_value("user_data".into()).unwrap();
+        assert_eq!(id, CategoryId::UserData);
+        assert!(serde_json::from_value::<CategoryId>("../etc".into()).is_err());
+    }
+
+    #[test]
+    fn confirmation_token_is_single_use() {
+        let guard = DeletionGuard::new();
+        let token = guard.issue(CategoryId::UserData);
+
+        assert!(guard.consume(CategoryId::UserData, Some(&token)).is_ok());
+        assert!(guard.consume(CategoryId::UserData, Some(&token)).is_err());
+    }
+
+    #[test]
+    fn confirmation_token_is_required_and_bound_to_category() {
+        let guard = DeletionGuard::new();
+        let token = guard.issue(CategoryId::UserData);
+
+        assert!(guard.consume(CategoryId::UserData, None).is_err());
+        assert!(guard.consume(CategoryId::Models, Some(&token)).is_err());
+        assert!(guard.consume(CategoryId::UserData, Some("otro")).is_err());
+        // Un token incorrecto no invalida el válido
+        assert!(guard.consume(CategoryId::UserData, Some(&token)).is_ok());
+    }
+
+    #[test]
+    fn confirmation_token_expires() {
+        let guard = DeletionGuard::with_ttl(Duration::ZERO);
+        let token = guard.issue(CategoryId::UserData);
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert!(guard.consume(CategoryId::UserData, Some(&token)).is_err());
+    }
+
+    #[test]
+    fn dir_breakdown_of_missing_dir_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let (total, nodes) = dir_breakdown(
+            &dir.path().join("missing"),
+            BREAKDOWN_DEPTH,
+            SizeOptions::default(),
+        );
+
+        assert_eq!(total, 0);
+        assert!(nodes.is_empty());
     }
 }
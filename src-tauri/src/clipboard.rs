@@ -0,0 +1,263 @@
+// Portapapeles con formato de Narrative Assistant
+//
+// Las fichas de entidades y los informes de estilo se copian como HTML o RTF
+// para pegarlos en Word sin perder el formato, algo que la API de
+// portapapeles del webview no hace de forma fiable. Junto al formato rico
+// siempre se copia una alternativa en texto plano.
+//
+// Texto y HTML van por arboard; RTF usa directamente el portapapeles de
+// Windows ("Rich Text Format") y de macOS (public.rtf).
+
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::State;
+
+/// Formato del contenido a copiar
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardFormat {
+    Text,
+    Html,
+    Rtf,
+}
+
+/// Portapapeles del sistema, registrado con `.manage()`. Se conserva abierto
+/// porque en Linux el contenido lo sirve la propia aplicación mientras vive.
+pub struct ClipboardState(Mutex<Option<arboard::Clipboard>>);
+
+impl ClipboardState {
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    fn with<T>(
+        &self,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    ) -> Result<T, String> {
+        let mut guard = self.0.lock().unwrap();
+        if guard.is_none() {
+            *guard = Some(
+                arboard::Clipboard::new()
+                    .map_err(|e| format!("No se pudo abrir el portapapeles: {}", e))?,
+            );
+        }
+        f(guard.as_mut().expect("portapapeles inicializado"))
+            .map_err(|e| format!("Error copiando al portapapeles: {}", e))
+    }
+}
+
+/// Texto plano aproximado de un fragmento HTML (alternativa para pegar sin formato)
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    let mut tag = String::new();
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or("")
+                    .to_ascii_lowercase();
+                if matches!(
+                    name.as_str(),
+                    "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+                ) && !text.ends_with('\n')
+                {
+                    text.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ => text.push(c),
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Texto plano aproximado de un documento RTF: descarta grupos de control
+/// (`{\*...}`, tablas de fuentes y colores) y palabras de control
+fn rtf_to_text(rtf: &str) -> String {
+    const SKIP_GROUPS: &[&str] = &["fonttbl", "colortbl", "stylesheet", "info", "*"];
+    let mut text = String::new();
+    let mut chars = rtf.chars().peekable();
+    // Por cada grupo abierto, si su contenido se descarta
+    let mut skipping: Vec<bool> = Vec::new();
+    let skip = |stack: &Vec<bool>| stack.last().copied().unwrap_or(false);
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let parent = skip(&skipping);
+                skipping.push(parent);
+            }
+            '}' => {
+                skipping.pop();
+            }
+            '\\' => match chars.peek().copied() {
+                Some(escaped @ ('\\' | '{' | '}')) => {
+                    chars.next();
+                    if !skip(&skipping) {
+                        text.push(escaped);
+                    }
+                }
+                Some('*') => {
+                    chars.next();
+                    if let Some(top) = skipping.last_mut() {
+                        *top = true;
+                    }
+                }
+                // Carácter de la página de códigos: \'e1 (Latin-1 coincide con cp1252 en español)
+                Some('\'') => {
+                    chars.next();
+                    let hex: String = chars.by_ref().take(2).collect();
+                    if let (Ok(byte), false) = (u8::from_str_radix(&hex, 16), skip(&skipping)) {
+                        text.push(char::from(byte));
+                    }
+                }
+                _ => {
+                    let mut word = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_ascii_alphabetic() {
+                            word.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    // Parámetro numérico y espacio delimitador
+                    let mut param = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_ascii_digit() || (c == '-' && param.is_empty()) {
+                            param.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    if chars.peek() == Some(&' ') {
+                        chars.next();
+                    }
+                    if word == "u" {
+                        // \uN seguido de un carácter alternativo para lectores sin Unicode
+                        let code = param.parse::<i32>().unwrap_or(0);
+                        let code = if code < 0 { code + 65536 } else { code };
+                        if let (Some(c), false) = (char::from_u32(code as u32), skip(&skipping)) {
+                            text.push(c);
+                        }
+                        if chars.peek().is_some_and(|c| !matches!(c, '\\' | '{' | '}')) {
+                            chars.next();
+                        }
+                    } else if SKIP_GROUPS.contains(&word.as_str()) {
+                        if let Some(top) = skipping.last_mut() {
+                            *top = true;
+                        }
+                    } else if matches!(word.as_str(), "par" | "line") && !skip(&skipping) {
+                        text.push('\n');
+                    } else if word == "tab" && !skip(&skipping) {
+                        text.push('\t');
+                    }
+                }
+            },
+            '\r' | '\n' => {}
+            _ if !skip(&skipping) => text.push(c),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+#[cfg(windows)]
+fn set_rtf(rtf: &str, plain: &str) -> Result<(), String> {
+    use clipboard_win::{options::NoClear, raw, register_format, Clipboard};
+
+    let format =
+        register_format("Rich Text Format").ok_or("No se pudo registrar el formato RTF")?;
+    let _clipboard = Clipboard::new_attempts(10)
+        .map_err(|e| format!("No se pudo abrir el portapapeles: {}", e))?;
+    let mut data = rtf.as_bytes().to_vec();
+    data.push(0);
+    raw::empty().map_err(|e| format!("Error vaciando el portapapeles: {}", e))?;
+    raw::set_without_clear(format.get(), &data)
+        .map_err(|e| format!("Error copiando RTF al portapapeles: {}", e))?;
+    raw::set_string_with(plain, NoClear)
+        .map_err(|e| format!("Error copiando texto al portapapeles: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn set_rtf(rtf: &str, plain: &str) -> Result<(), String> {
+    use objc2_app_kit::{NSPasteboard, NSPasteboardTypeRTF, NSPasteboardTypeString};
+    use objc2_foundation::{NSData, NSString};
+
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    let data = NSData::with_bytes(rtf.as_bytes());
+    // SAFETY: constantes de AppKit, inicializadas al cargar el framework
+    let (rtf_type, string_type) = unsafe { (NSPasteboardTypeRTF, NSPasteboardTypeString) };
+    let copied = pasteboard.setData_forType(Some(&data), rtf_type)
+        && pasteboard.setString_forType(&NSString::from_str(plain), string_type);
+    if copied {
+        Ok(())
+    } else {
+        Err("Error copiando RTF al portapapeles".into())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn set_rtf(_rtf: &str, _plain: &str) -> Result<(), String> {
+    Err("Copiar en RTF no esta disponible en este sistema; use HTML".into())
+}
+
+/// Copia `content` en el portapapeles en el formato indicado. Para HTML y RTF
+/// se añade `plain_text` (o una versión sin formato del contenido) como alternativa.
+#[tauri::command]
+pub fn copy_to_clipboard(
+    state: State<'_, ClipboardState>,
+    content: String,
+    format: ClipboardFormat,
+    plain_text: Option<String>,
+) -> Result<(), String> {
+    match format {
+        ClipboardFormat::Text => state.with(|clipboard| clipboard.set_text(content)),
+        ClipboardFormat::Html => {
+            let plain = plain_text.unwrap_or_else(|| html_to_text(&content));
+            state.with(|clipboard| clipboard.set_html(content, Some(plain)))
+        }
+        ClipboardFormat::Rtf => {
+            let plain = plain_text.unwrap_or_else(|| rtf_to_text(&content));
+            set_rtf(&content, &plain)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_fallback_keeps_text_and_line_breaks() {
+        let html = "<h2>Ana</h2><p>Ojos <b>verdes</b> &amp; pelo corto</p><ul><li>Cap. 1</li></ul>";
+        assert_eq!(html_to_text(html), "Ana\nOjos verdes & pelo corto\nCap. 1");
+    }
+
+    #[test]
+    fn rtf_fallback_drops_control_groups() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0 Arial;}}{\*\generator Word;}\f0\fs24 Ana\par Ojos \b verdes\b0\tab 1\}}";
+        assert_eq!(rtf_to_text(rtf), "Ana\nOjos verdes\t1}");
+
+        let accents = r"{\rtf1 Mar\'eda y \u241?o\u-3913?}";
+        assert_eq!(rtf_to_text(accents), "María y ño\u{f0b7}");
+    }
+}
@@ -0,0 +1,14 @@
+// Hora del sistema en Narrative Assistant
+//
+// Las marcas de tiempo que se guardan en disco (bloqueos, instantáneas,
+// proyectos recientes, actualizaciones pendientes...) son segundos Unix.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Segundos Unix actuales; 0 si el reloj del sistema es anterior a 1970
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,537 @@
+// Registro de comandos de Narrative Assistant
+//
+// Una sola tabla con todas las acciones que se pueden invocar: las del menú
+// nativo, operaciones del shell sobre el backend y preferencias que se
+// activan o desactivan. El menú se construye a partir de ella (nombre y
+// atajo) y la paleta de comandos del frontend (Ctrl+K) la lee con
+// `list_commands`, de modo que ambas no pueden desincronizarse.
+//
+// `invoke_command` ejecuta un comando por id: los del menú se reenvían a la
+// ventana activa como `menu-event`, igual que si se hubiera pulsado el
+// elemento del menú; el resto se ejecutan en el shell.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::i18n::{text, Text};
+use crate::menu::{analysis_menu, file_menu, help_menu, view_menu};
+use crate::plugins::PluginRegistry;
+use crate::settings::{Settings, SettingsStore};
+use crate::{autostart, dev_console, ipc_scope, menu, quick_note};
+
+/// Grupo en el que se muestra el comando (el submenú, para los del menú)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Group {
+    File,
+    Edit,
+    View,
+    Analysis,
+    Help,
+    /// Acciones del shell sin elemento en el menú
+    App,
+    /// Elementos de menú de los complementos (plugins.rs)
+    Plugins,
+}
+
+impl Group {
+    pub fn label(self) -> Text {
+        match self {
+            Group::File => text("Archivo", "File"),
+            Group::Edit => text("Edición", "Edit"),
+            Group::View => text("Ver", "View"),
+            Group::Analysis => text("Análisis", "Analysis"),
+            Group::Help => text("Ayuda", "Help"),
+            Group::App => text("Aplicación", "Application"),
+            Group::Plugins => text("Complementos", "Plugins"),
+        }
+    }
+}
+
+/// Qué hace el comando al invocarlo
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Elemento del menú nativo; lo ejecuta el frontend al recibir `menu-event`
+    Menu,
+    /// Reinicia el backend (como tras un mantenimiento de datos)
+    RestartBackend,
+    /// Abre o cierra la ventana de nota rápida
+    QuickNote,
+    /// Abre o cierra la consola de desarrollador (solo con `developer_tools`)
+    DevConsole,
+    /// Alterna una preferencia booleana de `Settings`
+    ToggleSetting(&'static str),
+}
+
+/// Entrada del registro
+#[derive(Clone, Copy, Debug)]
+pub struct CommandSpec {
+    pub id: &'static str,
+    pub group: Group,
+    pub label: Text,
+    /// Acelerador por defecto ("CmdOrCtrl+O")
+    pub shortcut: Option<&'static str>,
+    pub action: Action,
+}
+
+const fn entry(
+    id: &'static str,
+    group: Group,
+    label: Text,
+    shortcut: Option<&'static str>,
+    action: Action,
+) -> CommandSpec {
+    CommandSpec {
+        id,
+        group,
+        label,
+        shortcut,
+        action,
+    }
+}
+
+/// Id del elemento Buscar del menú Edición
+pub const FIND: &str = "find";
+
+/// Todos los comandos, en el orden en que aparecen en el menú
+pub const COMMANDS: &[CommandSpec] = &[
+    // Archivo
+    entry(
+        file_menu::NEW_PROJECT,
+        Group::File,
+        text("Nuevo proyecto...", "New project..."),
+        Some("CmdOrCtrl+N"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::OPEN_PROJECT,
+        Group::File,
+        text("Abrir proyecto...", "Open project..."),
+        Some("CmdOrCtrl+O"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::SAVE_PROJECT,
+        Group::File,
+        text("Guardar proyecto...", "Save project..."),
+        Some("CmdOrCtrl+S"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::OPEN_FILE,
+        Group::File,
+        text("Abrir archivo .nra...", "Open .nra file..."),
+        Some("CmdOrCtrl+Shift+O"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::CLOSE_PROJECT,
+        Group::File,
+        text("Cerrar proyecto", "Close project"),
+        Some("CmdOrCtrl+W"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::IMPORT,
+        Group::File,
+        text("Importar manuscrito...", "Import manuscript..."),
+        Some("CmdOrCtrl+I"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::UPDATE_MANUSCRIPT,
+        Group::File,
+        text("Actualizar manuscrito...", "Update manuscript..."),
+        None,
+        Action::Menu,
+    ),
+    entry(
+        file_menu::EXPORT,
+        Group::File,
+        text("Exportar informe...", "Export report..."),
+        Some("CmdOrCtrl+E"),
+        Action::Menu,
+    ),
+    entry(
+        file_menu::SETTINGS,
+        Group::File,
+        text("Configuración...", "Settings..."),
+        Some("CmdOrCtrl+,"),
+        Action::Menu,
+    ),
+    // Edición
+    entry(
+        FIND,
+        Group::Edit,
+        text("Buscar", "Find"),
+        Some("CmdOrCtrl+F"),
+        Action::Menu,
+    ),
+    // Ver
+    entry(
+        view_menu::CHAPTERS,
+        Group::View,
+        text("Texto", "Text"),
+        Some("CmdOrCtrl+1"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::ENTITIES,
+        Group::View,
+        text("Entidades", "Entities"),
+        Some("CmdOrCtrl+2"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::RELATIONSHIPS,
+        Group::View,
+        text("Relaciones", "Relationships"),
+        Some("CmdOrCtrl+3"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::ALERTS,
+        Group::View,
+        text("Revision", "Review"),
+        Some("CmdOrCtrl+4"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::TIMELINE,
+        Group::View,
+        text("Cronologia", "Timeline"),
+        Some("CmdOrCtrl+5"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::STYLE,
+        Group::View,
+        text("Escritura", "Writing"),
+        Some("CmdOrCtrl+6"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::GLOSSARY,
+        Group::View,
+        text("Glosario", "Glossary"),
+        Some("CmdOrCtrl+7"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::SUMMARY,
+        Group::View,
+        text("Resumen", "Summary"),
+        Some("CmdOrCtrl+8"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::TOGGLE_SIDEBAR,
+        Group::View,
+        text("Mostrar/ocultar sidebar", "Toggle sidebar"),
+        Some("CmdOrCtrl+B"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::TOGGLE_INSPECTOR,
+        Group::View,
+        text("Mostrar/ocultar inspector", "Toggle inspector"),
+        Some("CmdOrCtrl+Shift+I"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::TOGGLE_HISTORY,
+        Group::View,
+        text("Mostrar/ocultar historial", "Toggle history"),
+        Some("CmdOrCtrl+Shift+H"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::TOGGLE_THEME,
+        Group::View,
+        text("Cambiar tema", "Toggle theme"),
+        Some("CmdOrCtrl+Shift+D"),
+        Action::Menu,
+    ),
+    entry(
+        view_menu::COMMAND_PALETTE,
+        Group::View,
+        text("Paleta de comandos...", "Command palette..."),
+        Some("CmdOrCtrl+K"),
+        Action::Menu,
+    ),
+    // Análisis (sin atajo global — evita conflicto con Ctrl+R del navegador)
+    entry(
+        analysis_menu::RUN,
+        Group::Analysis,
+        text("Ejecutar análisis", "Run analysis"),
+        None,
+        Action::Menu,
+    ),
+    // Ayuda
+    entry(
+        help_menu::TUTORIAL,
+        Group::Help,
+        text("Tutorial de bienvenida", "Welcome tutorial"),
+        None,
+        Action::Menu,
+    ),
+    entry(
+        help_menu::KEYBOARD_SHORTCUTS,
+        Group::Help,
+        text("Atajos de teclado", "Keyboard shortcuts"),
+        Some("CmdOrCtrl+/"),
+        Action::Menu,
+    ),
+    entry(
+        help_menu::USER_GUIDE,
+        Group::Help,
+        text("Guia de usuario", "User guide"),
+        Some("F1"),
+        Action::Menu,
+    ),
+    entry(
+        help_menu::MANAGE_DATA,
+        Group::Help,
+        text("Gestionar datos...", "Manage data..."),
+        None,
+        Action::Menu,
+    ),
+    entry(
+        help_menu::REPORT_PROBLEM,
+        Group::Help,
+        text("Informar de un problema...", "Report a problem..."),
+        None,
+        Action::Menu,
+    ),
+    entry(
+        help_menu::CHECK_UPDATES,
+        Group::Help,
+        text("Buscar actualizaciones...", "Check for updates..."),
+        None,
+        Action::Menu,
+    ),
+    entry(
+        help_menu::ABOUT,
+        Group::Help,
+        text("Acerca de Narrative Assistant", "About Narrative Assistant"),
+        None,
+        Action::Menu,
+    ),
+    // Shell
+    entry(
+        "quick_note",
+        Group::App,
+        text("Nota rápida", "Quick note"),
+        None,
+        Action::QuickNote,
+    ),
+    entry(
+        "restart_backend",
+        Group::App,
+        text("Reiniciar el motor de análisis", "Restart analysis engine"),
+        None,
+        Action::RestartBackend,
+    ),
+    entry(
+        "toggle_minimize_to_tray",
+        Group::App,
+        text(
+            "Seguir en segundo plano al cerrar la ventana",
+            "Keep running in the background when closed",
+        ),
+        None,
+        Action::ToggleSetting("minimize_to_tray"),
+    ),
+    entry(
+        "toggle_launch_at_login",
+        Group::App,
+        text("Abrir al iniciar sesión", "Open at login"),
+        None,
+        Action::ToggleSetting("launch_at_login"),
+    ),
+    entry(
+        "toggle_weekly_update_check",
+        Group::App,
+        text(
+            "Buscar actualizaciones cada semana",
+            "Check for updates weekly",
+        ),
+        None,
+        Action::ToggleSetting("check_updates_weekly"),
+    ),
+    entry(
+        "developer_console",
+        Group::App,
+        text("Consola de desarrollador", "Developer console"),
+        Some("CmdOrCtrl+Alt+Shift+D"),
+        Action::DevConsole,
+    ),
+];
+
+/// Busca un comando por id
+pub fn find(id: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|command| command.id == id)
+}
+
+/// Valor actual de una preferencia booleana
+fn setting_flag(settings: &Settings, key: &str) -> Option<bool> {
+    serde_json::to_value(settings).ok()?.get(key)?.as_bool()
+}
+
+/// Comando tal como lo muestra la paleta
+#[derive(Serialize, Clone, Debug)]
+pub struct CommandInfo {
+    pub id: String,
+    pub group: &'static str,
+    pub label: String,
+    pub shortcut: Option<String>,
+    /// "menu" si lo ejecuta el frontend, "shell" si lo ejecuta el shell,
+    /// "plugin" si es de un complemento
+    pub kind: &'static str,
+    /// Estado actual de las preferencias que alterna (None en el resto)
+    pub checked: Option<bool>,
+}
+
+fn describe(command: &CommandSpec, settings: &Settings) -> CommandInfo {
+    let shortcut = match command.action {
+        Action::QuickNote => Some(quick_note::shortcut_for(settings).to_string()),
+        _ => command.shortcut.map(str::to_string),
+    };
+    let checked = match command.action {
+        Action::ToggleSetting(key) => setting_flag(settings, key),
+        _ => None,
+    };
+    CommandInfo {
+        id: command.id.to_string(),
+        group: command.group.label().get(&settings.language),
+        label: command.label.get(&settings.language).to_string(),
+        shortcut,
+        kind: if command.action == Action::Menu {
+            "menu"
+        } else {
+            "shell"
+        },
+        checked,
+    }
+}
+
+fn toggle_setting(app: &AppHandle, key: &str) -> Result<(), String> {
+    let store = app.state::<SettingsStore>();
+    let enabled = !setting_flag(&store.get(), key)
+        .ok_or_else(|| format!("Preferencia desconocida: {}", key))?;
+    // El inicio automático también cambia el registro del sistema
+    if key == "launch_at_login" {
+        return autostart::set_autostart(app.clone(), store, enabled, None);
+    }
+    let mut patch = serde_json::Map::new();
+    patch.insert(key.to_string(), enabled.into());
+    let updated = store.update(patch.into())?;
+    let _ = app.emit("shell-settings-changed", &updated);
+    Ok(())
+}
+
+/// Comandos disponibles, con nombres en el idioma de la interfaz, seguidos
+/// de los elementos de menú de los complementos
+#[tauri::command]
+pub fn list_commands(
+    store: State<'_, SettingsStore>,
+    plugins: State<'_, PluginRegistry>,
+) -> Vec<CommandInfo> {
+    let settings = store.get();
+    let group = Group::Plugins.label().get(&settings.language);
+    let plugin_items = plugins.plugins().iter().flat_map(|plugin| {
+        plugin.manifest.menu.iter().map(|item| CommandInfo {
+            id: crate::plugins::menu_id(&plugin.manifest.id, &item.command),
+            group,
+            label: item.label.clone(),
+            shortcut: None,
+            kind: "plugin",
+            checked: None,
+        })
+    });
+    COMMANDS
+        .iter()
+        .filter(|command| command.action != Action::DevConsole || settings.developer_tools)
+        .map(|command| describe(command, &settings))
+        .chain(plugin_items)
+        .collect()
+}
+
+/// Ejecuta un comando del registro (o un elemento de complemento) por id
+#[tauri::command]
+pub async fn invoke_command(
+    app: AppHandle,
+    webview: tauri::Webview,
+    id: String,
+) -> Result<(), String> {
+    if crate::plugins::dispatch_menu_item(&app, &id) {
+        return Ok(());
+    }
+    let command = find(&id).ok_or_else(|| format!("Comando desconocido: {}", id))?;
+    tracing::info!(target: "commands", "Ejecutando {}", command.id);
+    match command.action {
+        Action::Menu => menu::handle_menu_event(&app, command.id),
+        Action::QuickNote => quick_note::toggle_window(&app),
+        Action::RestartBackend => {
+            ipc_scope::authorize(webview.label(), ipc_scope::RESTART_BACKEND)?;
+            crate::restart_backend(&app).await
+        }
+        Action::DevConsole => {
+            let label = crate::windows::active_window_label(&app);
+            let window = app
+                .get_webview_window(&label)
+                .ok_or_else(|| format!("Ventana no encontrada: {}", label))?;
+            dev_console::toggle(&app, &window)?;
+        }
+        Action::ToggleSetting(key) => toggle_setting(&app, key)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn registry_covers_the_menu_with_unique_ids() {
+        let mut seen = HashSet::new();
+        for command in COMMANDS {
+            assert!(
+                seen.insert(command.id),
+                "Comando duplicado: '{}'",
+                command.id
+            );
+        }
+        for id in menu::ALL_MENU_IDS.iter().chain([&FIND]) {
+            let command = find(id).unwrap_or_else(|| panic!("'{}' no esta en el registro", id));
+            assert_eq!(command.action, Action::Menu);
+        }
+    }
+
+    #[test]
+    fn toggles_point_to_boolean_settings_and_labels_follow_language() {
+        let mut settings = Settings::default();
+        for command in COMMANDS {
+            if let Action::ToggleSetting(key) = command.action {
+                assert!(
+                    setting_flag(&settings, key).is_some(),
+                    "'{}' no es una preferencia booleana",
+                    key
+                );
+            }
+        }
+
+        let open = find(file_menu::OPEN_PROJECT).unwrap();
+        assert_eq!(describe(open, &settings).label, "Abrir proyecto...");
+        assert_eq!(describe(open, &settings).kind, "menu");
+        settings.language = "en-GB".into();
+        let info = describe(open, &settings);
+        assert_eq!(info.label, "Open project...");
+        assert_eq!(info.group, "File");
+        assert_eq!(info.shortcut.as_deref(), Some("CmdOrCtrl+O"));
+
+        let tray = describe(find("toggle_minimize_to_tray").unwrap(), &settings);
+        assert_eq!(tray.kind, "shell");
+        assert_eq!(tray.checked, Some(settings.minimize_to_tray));
+    }
+}
@@ -0,0 +1,357 @@
+// Informes de fallo del shell de Narrative Assistant
+//
+// Un panic hook (y el error de `tauri::Builder::run`) escriben un informe en
+// ~/.narrative_assistant/crashes/ con el mensaje, el backtrace, el sistema y
+// las últimas líneas del log. En el siguiente arranque el frontend consulta
+// `list_crash_reports` y ofrece revisarlos, guardarlos para enviarlos o
+// descartarlos.
+
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+use crate::export_scope::ExportGrants;
+use crate::logging::{LogState, RecentLogs};
+use crate::settings;
+
+/// Líneas de log que se adjuntan a cada informe
+const CRASH_LOG_LINES: usize = 200;
+
+/// Informes que se conservan (los más antiguos se borran al escribir uno nuevo)
+const MAX_CRASH_REPORTS: usize = 20;
+
+/// Extensión de los informes
+const CRASH_EXTENSION: &str = "json";
+
+/// Directorio de informes (~/.narrative_assistant/crashes)
+fn crash_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".narrative_assistant").join("crashes"))
+}
+
+/// Origen del fallo
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    /// Error devuelto por el runtime de Tauri
+    RuntimeError,
+}
+
+impl CrashKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CrashKind::Panic => "panic",
+            CrashKind::RuntimeError => "runtime_error",
+        }
+    }
+}
+
+/// Sistema en el que se produjo el fallo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OsInfo {
+    pub os: String,
+    pub family: String,
+    pub arch: String,
+}
+
+impl OsInfo {
+    fn current() -> Self {
+        Self {
+            os: std::env::consts::OS.into(),
+            family: std::env::consts::FAMILY.into(),
+            arch: std::env::consts::ARCH.into(),
+        }
+    }
+}
+
+/// Informe de fallo tal como se guarda en disco
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CrashReport {
+    /// Nombre del fichero sin extensión
+    pub id: String,
+    /// Segundos UNIX
+    pub timestamp: u64,
+    pub app_version: String,
+    pub kind: CrashKind,
+    pub message: String,
+    /// Fichero:línea del pánico
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub os: OsInfo,
+    /// Últimas líneas del log del shell
+    pub logs: Vec<String>,
+    /// Si el usuario ya lo ha revisado
+    #[serde(default)]
+    pub reviewed: bool,
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, message: String, logs: Vec<String>) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            id: format!("crash-{}-{:03}", now.as_secs(), now.subsec_millis()),
+            timestamp: now.as_secs(),
+            app_version: env!("CARGO_PKG_VERSION").into(),
+            kind,
+            message,
+            location: None,
+            thread: std::thread::current().name().map(String::from),
+            backtrace: Backtrace::force_capture().to_string(),
+            os: OsInfo::current(),
+            logs,
+            reviewed: false,
+        }
+    }
+
+    fn from_panic(info: &PanicHookInfo<'_>, logs: Vec<String>) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic sin mensaje".into());
+        Self {
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            ..Self::new(CrashKind::Panic, message, logs)
+        }
+    }
+}
+
+/// Resumen para la lista de informes pendientes
+#[derive(Serialize, Clone, Debug)]
+pub struct CrashSummary {
+    pub id: String,
+    pub timestamp: u64,
+    pub app_version: String,
+    pub kind: CrashKind,
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl From<&CrashReport> for CrashSummary {
+    fn from(report: &CrashReport) -> Self {
+        Self {
+            id: report.id.clone(),
+            timestamp: report.timestamp,
+            app_version: report.app_version.clone(),
+            kind: report.kind,
+            message: report.message.clone(),
+            location: report.location.clone(),
+        }
+    }
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(id).with_extension(CRASH_EXTENSION)
+}
+
+/// Evita que un id recibido del frontend salga del directorio de informes
+fn validate_id(id: &str) -> Result<(), String> {
+    if id.starts_with("crash-") && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        Ok(())
+    } else {
+        Err(format!("Id de informe no valido: {}", id))
+    }
+}
+
+/// Todos los informes de `dir`, del más reciente al más antiguo
+fn read_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| ext == CRASH_EXTENSION)
+        })
+        .filter_map(|e| serde_json::from_str(&fs::read_to_string(e.path()).ok()?).ok())
+        .collect();
+    reports.sort_by(|a, b| b.id.cmp(&a.id));
+    reports
+}
+
+fn read_report(dir: &Path, id: &str) -> Result<CrashReport, String> {
+    validate_id(id)?;
+    let raw = fs::read_to_string(report_path(dir, id))
+        .map_err(|e| format!("No se encontro el informe {}: {}", id, e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Informe {} corrupto: {}", id, e))
+}
+
+/// Guarda el informe y borra los más antiguos por encima de `MAX_CRASH_REPORTS`
+fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    let path = report_path(dir, &report.id);
+    let json = serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?;
+    settings::write_atomic(&path, &json)?;
+    for old in read_reports(dir).iter().skip(MAX_CRASH_REPORTS) {
+        let _ = fs::remove_file(report_path(dir, &old.id));
+    }
+    Ok(path)
+}
+
+/// Escribe un informe sin propagar errores (se usa desde el panic hook)
+fn record(report: &CrashReport) {
+    let Some(dir) = crash_dir() else {
+        return;
+    };
+    match write_report(&dir, report) {
+        Ok(path) => eprintln!("Informe de fallo guardado en {}", path.display()),
+        Err(e) => eprintln!("No se pudo guardar el informe de fallo: {}", e),
+    }
+}
+
+thread_local! {
+    /// El hilo está dentro de `catch_quietly`: el pánico no es un fallo de la app
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Ejecuta `f` capturando un pánico sin escribir informe. Para código de
+/// terceros que entra en pánico con entradas mal formadas (pdf_text.rs).
+pub fn catch_quietly<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    let was_quiet = QUIET.replace(true);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+    QUIET.set(was_quiet);
+    result.map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic sin mensaje".into())
+    })
+}
+
+/// Instala el panic hook. El hook anterior (mensaje por stderr) se sigue ejecutando.
+pub fn install(log_state: &LogState) {
+    let logs = log_state.recent_logs();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if QUIET.get() {
+            return;
+        }
+        // Sin `tracing` aquí: el pánico puede venir del propio logging con sus locks tomados
+        record(&CrashReport::from_panic(info, logs.lines(CRASH_LOG_LINES)));
+        previous(info);
+    }));
+}
+
+/// Registra un error fatal del runtime de Tauri
+pub fn record_runtime_error(logs: &RecentLogs, error: &tauri::Error) {
+    record(&CrashReport::new(
+        CrashKind::RuntimeError,
+        error.to_string(),
+        logs.lines(CRASH_LOG_LINES),
+    ));
+}
+
+/// Momento y tipo de cada informe guardado (para contar fallos en la telemetría)
+pub(crate) fn report_occurrences() -> Vec<(u64, CrashKind)> {
+    crash_dir()
+        .map(|dir| read_reports(&dir))
+        .unwrap_or_default()
+        .iter()
+        .map(|r| (r.timestamp, r.kind))
+        .collect()
+}
+
+/// Informes de fallos anteriores que el usuario aún no ha revisado
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<CrashSummary> {
+    crash_dir()
+        .map(|dir| read_reports(&dir))
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| !r.reviewed)
+        .map(CrashSummary::from)
+        .collect()
+}
+
+/// Informe completo para revisarlo
+#[tauri::command]
+pub fn get_crash_report(id: String) -> Result<CrashReport, String> {
+    let dir = crash_dir().ok_or("No se pudo determinar el directorio home")?;
+    read_report(&dir, &id)
+}
+
+/// Guarda una copia del informe en `path` para enviarla
+#[tauri::command]
+pub fn export_crash_report(
+    grants: State<'_, ExportGrants>,
+    id: String,
+    path: String,
+) -> Result<String, String> {
+    grants.authorize(Path::new(&path))?;
+    let report = get_crash_report(id)?;
+    let json = serde_json::to_vec_pretty(&report).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Error escribiendo {}: {}", path, e))?;
+    Ok(path)
+}
+
+/// Marca el informe como revisado para no volver a ofrecerlo
+#[tauri::command]
+pub fn dismiss_crash_report(id: String) -> Result<(), String> {
+    let dir = crash_dir().ok_or("No se pudo determinar el directorio home")?;
+    let mut report = read_report(&dir, &id)?;
+    report.reviewed = true;
+    let json = serde_json::to_vec_pretty(&report).map_err(|e| e.to_string())?;
+    settings::write_atomic(&report_path(&dir, &id), &json)
+        .map_err(|e| format!("Error guardando el informe {}: {}", id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(id: &str) -> CrashReport {
+        CrashReport {
+            id: id.into(),
+            ..CrashReport::new(CrashKind::Panic, "boom".into(), vec!["linea".into()])
+        }
+    }
+
+    #[test]
+    fn reports_roundtrip_and_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..(MAX_CRASH_REPORTS + 3) {
+            write_report(dir.path(), &report(&format!("crash-{:04}-000", i))).unwrap();
+        }
+
+        let reports = read_reports(dir.path());
+        assert_eq!(reports.len(), MAX_CRASH_REPORTS);
+        assert_eq!(
+            reports[0].id,
+            format!("crash-{:04}-000", MAX_CRASH_REPORTS + 2)
+        );
+        let read = read_report(dir.path(), &reports[0].id).unwrap();
+        assert_eq!(read, reports[0]);
+        assert_eq!(read.logs, vec!["linea".to_string()]);
+        assert_eq!(read.os, OsInfo::current());
+    }
+
+    #[test]
+    fn ids_cannot_escape_the_crash_directory() {
+        assert!(validate_id("crash-1700000000-123").is_ok());
+        assert!(validate_id("../settings").is_err());
+        assert!(validate_id("crash-1/../../x").is_err());
+    }
+
+    #[test]
+    fn quiet_panics_are_returned_as_errors() {
+        assert_eq!(catch_quietly(|| 2 + 2), Ok(4));
+        assert_eq!(
+            catch_quietly(|| -> () { panic!("MediaBox") }),
+            Err("MediaBox".to_string())
+        );
+        assert!(!QUIET.get());
+    }
+}
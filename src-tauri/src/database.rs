@@ -0,0 +1,457 @@
+// Mantenimiento de la base de datos SQLite de Narrative Assistant
+//
+// Proporciona comandos Tauri para:
+//   - Compactar la base de datos (VACUUM) y recuperar páginas libres
+//   - Verificar la integridad tras cierres inesperados o cortes de luz
+//   - Volcar el WAL (checkpoint) antes de copias de seguridad e informes de tamaño
+//   - Copiar la base de datos de forma consistente y restaurar una copia (snapshots.rs)
+//
+// Si está cifrada (db_encryption.rs), las conexiones se abren con su clave.
+//
+// La base de datos pertenece al backend Python. Las operaciones que reescriben
+// el fichero completo pausan el backend gestionado mientras se ejecutan.
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+
+use crate::db_encryption;
+use crate::BackendServer;
+
+/// Nombre del fichero de la base de datos principal
+pub const DB_FILE_NAME: &str = "narrative_assistant.db";
+
+/// Sufijos de los ficheros auxiliares de SQLite en modo WAL
+const DB_SIDECAR_SUFFIXES: &[&str] = &["-wal", "-shm"];
+
+/// Tiempo máximo de espera si el backend tiene la base de datos bloqueada
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tiempo de espera del checkpoint: si el backend está escribiendo no merece la pena bloquear
+const CHECKPOINT_BUSY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Resultado de `PRAGMA wal_checkpoint(TRUNCATE)`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct WalCheckpoint {
+    /// Si el checkpoint no pudo completarse porque otra conexión bloqueaba la base de datos
+    pub busy: bool,
+    /// Frames presentes en el WAL
+    pub wal_frames: i64,
+    /// Frames volcados a la base de datos
+    pub checkpointed_frames: i64,
+}
+
+/// Resultado de una compactación
+#[derive(Serialize, Clone, Debug)]
+pub struct CompactionReport {
+    /// Ruta absoluta de la base de datos
+    pub path: String,
+    /// Tamaño antes de compactar (DB + -wal + -shm)
+    pub size_before_bytes: u64,
+    /// Tamaño después de compactar
+    pub size_after_bytes: u64,
+    /// Bytes recuperados (0 si el fichero no se redujo)
+    pub reclaimed_bytes: u64,
+    /// Si el backend se pausó durante la operación
+    pub backend_paused: bool,
+}
+
+/// Resultado de un PRAGMA de verificación
+#[derive(Serialize, Clone, Debug)]
+pub struct CheckResult {
+    /// Si SQLite devolvió "ok"
+    pub ok: bool,
+    /// Problemas detectados (vacío si `ok`)
+    pub errors: Vec<String>,
+}
+
+/// Resultado de `check_database`
+#[derive(Serialize, Clone, Debug)]
+pub struct IntegrityReport {
+    /// Ruta absoluta de la base de datos
+    pub path: String,
+    /// Si ambas verificaciones pasaron
+    pub ok: bool,
+    /// Resultado de `PRAGMA quick_check` (rápido, no verifica índices)
+    pub quick_check: CheckResult,
+    /// Resultado de `PRAGMA integrity_check` (completo)
+    pub integrity_check: CheckResult,
+    /// Duración total en milisegundos
+    pub duration_ms: u64,
+}
+
+/// Máximo de errores que devuelve cada PRAGMA
+const MAX_CHECK_ERRORS: u32 = 100;
+
+/// Ruta de la base de datos principal (~/.narrative_assistant/narrative_assistant.db)
+pub fn database_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".narrative_assistant").join(DB_FILE_NAME))
+}
+
+/// Ruta de un fichero auxiliar de SQLite (`-wal`, `-shm`) junto a la base de datos
+fn sidecar_path(db: &Path, suffix: &str) -> PathBuf {
+    let mut name = db.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Tamaño total en disco de la base de datos, incluyendo `-wal` y `-shm`
+pub fn database_size(db: &Path) -> u64 {
+    std::iter::once(db.to_path_buf())
+        .chain(DB_SIDECAR_SUFFIXES.iter().map(|s| sidecar_path(db, s)))
+        .filter_map(|p| fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Abre la base de datos existente (nunca la crea)
+pub(crate) fn open_existing(db: &Path) -> Result<Connection, String> {
+    if !db.exists() {
+        return Err(format!("Base de datos no encontrada: {}", db.display()));
+    }
+    let conn =
+        Connection::open(db).map_err(|e| format!("Error abriendo {}: {}", db.display(), e))?;
+    db_encryption::apply_key(&conn, db)?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Error configurando la conexion: {}", e))?;
+    Ok(conn)
+}
+
+/// Abre la base de datos existente en modo solo lectura
+pub(crate) fn open_read_only(db: &Path) -> Result<Connection, String> {
+    if !db.exists() {
+        return Err(format!("Base de datos no encontrada: {}", db.display()));
+    }
+    let conn = Connection::open_with_flags(
+        db,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Error abriendo {}: {}", db.display(), e))?;
+    db_encryption::apply_key(&conn, db)?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Error configurando la conexion: {}", e))?;
+    Ok(conn)
+}
+
+/// Vuelca el WAL a la base de datos y trunca el fichero `-wal`.
+/// El `-wal` puede ocupar cientos de MB y hace que una copia del `.db` sola sea inconsistente.
+pub fn checkpoint_wal(db: &Path) -> Result<WalCheckpoint, String> {
+    let conn = open_existing(db)?;
+    conn.busy_timeout(CHECKPOINT_BUSY_TIMEOUT)
+        .map_err(|e| format!("Error configurando la conexion: {}", e))?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+        Ok(WalCheckpoint {
+            busy: row.get::<_, i64>(0)? != 0,
+            wal_frames: row.get(1)?,
+            checkpointed_frames: row.get(2)?,
+        })
+    })
+    .map_err(|e| format!("Error en el checkpoint del WAL: {}", e))
+}
+
+/// Checkpoint sin fallar: si la base de datos no existe o el backend la tiene bloqueada,
+/// se registra y se continúa con los datos tal cual están en disco.
+pub fn checkpoint_wal_best_effort(db: &Path) -> Option<WalCheckpoint> {
+    if !sidecar_path(db, "-wal").exists() {
+        return None;
+    }
+    match checkpoint_wal(db) {
+        Ok(result) => {
+            if result.busy {
+                tracing::warn!(
+                    target: "database",
+                    "WAL checkpoint incomplete (database busy): {}/{} frames",
+                    result.checkpointed_frames, result.wal_frames
+                );
+            }
+            Some(result)
+        }
+        Err(e) => {
+            tracing::warn!(target: "database", "WAL checkpoint skipped: {}", e);
+            None
+        }
+    }
+}
+
+/// Ejecuta un PRAGMA de verificación (`integrity_check`, `quick_check`).
+/// Un error de SQLite (p.ej. "file is not a database") cuenta como verificación fallida.
+fn run_check(conn: &Connection, pragma: &str) -> CheckResult {
+    let sql = format!("PRAGMA {}({})", pragma, MAX_CHECK_ERRORS);
+    let rows: Result<Vec<String>, rusqlite::Error> = conn
+        .prepare(&sql)
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect());
+
+    match rows {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => CheckResult {
+            ok: true,
+            errors: vec![],
+        },
+        Ok(rows) => CheckResult {
+            ok: false,
+            errors: rows,
+        },
+        Err(e) => CheckResult {
+            ok: false,
+            errors: vec![e.to_string()],
+        },
+    }
+}
+
+/// Ejecuta `quick_check` e `integrity_check` sobre la base de datos
+pub fn check_integrity(db: &Path) -> Result<IntegrityReport, String> {
+    let start = Instant::now();
+    let conn = open_read_only(db)?;
+
+    let quick_check = run_check(&conn, "quick_check");
+    let integrity_check = run_check(&conn, "integrity_check");
+
+    Ok(IntegrityReport {
+        path: db.to_string_lossy().into(),
+        ok: quick_check.ok && integrity_check.ok,
+        quick_check,
+        integrity_check,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Ejecuta VACUUM sobre la base de datos y devuelve el tamaño antes/después
+pub fn vacuum(db: &Path) -> Result<CompactionReport, String> {
+    let size_before = database_size(db);
+    let conn = open_existing(db)?;
+    conn.execute_batch("VACUUM;")
+        .map_err(|e| format!("Error compactando la base de datos: {}", e))?;
+    drop(conn);
+
+    let size_after = database_size(db);
+    Ok(CompactionReport {
+        path: db.to_string_lossy().into(),
+        size_before_bytes: size_before,
+        size_after_bytes: size_after,
+        reclaimed_bytes: size_before.saturating_sub(size_after),
+        backend_paused: false,
+    })
+}
+
+/// Copia consistente de la base de datos en `dest` (no debe existir). Devuelve su tamaño.
+///
+/// Tras el checkpoint del WAL, `VACUUM INTO` copia dentro de una transacción de
+/// lectura: el backend puede seguir escribiendo y la copia nunca queda a medias.
+pub fn copy_to(db: &Path, dest: &Path) -> Result<u64, String> {
+    checkpoint_wal_best_effort(db);
+    let conn = open_read_only(db)?;
+    let tmp = dest.with_extension("tmp");
+    let _ = fs::remove_file(&tmp);
+    conn.execute("VACUUM INTO ?1", [tmp.to_string_lossy()])
+        .map_err(|e| format!("Error copiando la base de datos: {}", e))?;
+    drop(conn);
+    fs::rename(&tmp, dest).map_err(|e| format!("Error guardando {}: {}", dest.display(), e))?;
+    Ok(fs::metadata(dest).map(|m| m.len()).unwrap_or(0))
+}
+
+/// Sustituye la base de datos por `source` (con el backend detenido).
+/// Se eliminan `-wal` y `-shm`: pertenecen a la base de datos anterior.
+pub fn replace_with(db: &Path, source: &Path) -> Result<(), String> {
+    let tmp = sidecar_path(db, ".restore");
+    fs::copy(source, &tmp).map_err(|e| format!("Error copiando {}: {}", source.display(), e))?;
+    for suffix in DB_SIDECAR_SUFFIXES {
+        let sidecar = sidecar_path(db, suffix);
+        if sidecar.exists() {
+            fs::remove_file(&sidecar)
+                .map_err(|e| format!("Error eliminando {}: {}", sidecar.display(), e))?;
+        }
+    }
+    fs::rename(&tmp, db).map_err(|e| format!("Error restaurando {}: {}", db.display(), e))
+}
+
+/// Compacta la base de datos con VACUUM, pausando el backend mientras dura.
+/// Los proyectos con muchos re-análisis acumulan páginas libres que VACUUM devuelve al disco.
+#[tauri::command]
+pub async fn compact_database(
+    app: AppHandle,
+    server_state: State<'_, BackendServer>,
+) -> Result<CompactionReport, String> {
+    let db = database_path().ok_or("No se pudo determinar el directorio home")?;
+    if !db.exists() {
+        return Err(format!("Base de datos no encontrada: {}", db.display()));
+    }
+
+    let was_running = crate::pause_backend(&app, &server_state);
+
+    let db_clone = db.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || vacuum(&db_clone))
+        .await
+        .map_err(|e| format!("Error en la tarea de compactacion: {}", e))
+        .and_then(|r| r);
+
+    // Reanudar siempre, incluso si VACUUM falló
+    crate::resume_backend(&app, server_state, was_running).await;
+
+    result.map(|mut report| {
+        report.backend_paused = was_running;
+        report
+    })
+}
+
+/// Verifica la integridad de la base de datos (botón "Verificar" de Gestionar datos).
+/// Solo lectura: no necesita pausar el backend.
+#[tauri::command]
+pub async fn check_database() -> Result<IntegrityReport, String> {
+    let db = database_path().ok_or("No se pudo determinar el directorio home")?;
+    tauri::async_runtime::spawn_blocking(move || check_integrity(&db))
+        .await
+        .map_err(|e| format!("Error en la tarea de verificacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_db_with_free_pages(path: &Path) {
+        let conn = Connection::open(path).expect("open db");
+        conn.execute_batch(
+            "CREATE TABLE chunks (id INTEGER PRIMARY KEY, body TEXT);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+             INSERT INTO chunks (body) SELECT hex(randomblob(512)) FROM n;
+             DELETE FROM chunks;",
+        )
+        .expect("seed db");
+    }
+
+    #[test]
+    fn copy_includes_uncheckpointed_wal_and_replace_restores_it() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        let conn = open_wal_without_autocheckpoint(&db);
+
+        let snapshot = dir.path().join("snapshot.db");
+        assert!(copy_to(&db, &snapshot).unwrap() > 0);
+        assert!(check_integrity(&snapshot).unwrap().ok);
+
+        conn.execute_batch("DELETE FROM notes;").unwrap();
+        drop(conn);
+        replace_with(&db, &snapshot).unwrap();
+        assert!(!sidecar_path(&db, "-wal").exists());
+        let restored: i64 = Connection::open(&db)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(restored, 3);
+    }
+
+    #[test]
+    fn sidecar_path_appends_suffix() {
+        let db = Path::new("/tmp/na/narrative_assistant.db");
+        assert_eq!(
+            sidecar_path(db, "-wal"),
+            PathBuf::from("/tmp/na/narrative_assistant.db-wal")
+        );
+    }
+
+    #[test]
+    fn database_size_is_zero_when_missing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert_eq!(database_size(&dir.path().join(DB_FILE_NAME)), 0);
+    }
+
+    #[test]
+    fn vacuum_reclaims_free_pages() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        create_db_with_free_pages(&db);
+
+        let report = vacuum(&db).expect("vacuum");
+
+        assert!(report.size_after_bytes < report.size_before_bytes);
+        assert_eq!(
+            report.reclaimed_bytes,
+            report.size_before_bytes - report.size_after_bytes
+        );
+    }
+
+    #[test]
+    fn check_integrity_passes_on_healthy_database() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        create_db_with_free_pages(&db);
+
+        let report = check_integrity(&db).expect("check");
+
+        assert!(report.ok);
+        assert!(report.quick_check.ok && report.quick_check.errors.is_empty());
+        assert!(report.integrity_check.ok && report.integrity_check.errors.is_empty());
+    }
+
+    #[test]
+    fn check_integrity_reports_corrupted_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        fs::write(&db, vec![0xAB_u8; 8192]).expect("write garbage");
+
+        let report = check_integrity(&db).expect("check");
+
+        assert!(!report.ok);
+        assert!(!report.integrity_check.errors.is_empty());
+    }
+
+    fn open_wal_without_autocheckpoint(path: &Path) -> Connection {
+        let conn = Connection::open(path).expect("open db");
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA wal_autocheckpoint=0;
+             CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);
+             INSERT INTO notes (body) VALUES ('uno'), ('dos'), ('tres');",
+        )
+        .expect("seed wal db");
+        conn
+    }
+
+    #[test]
+    fn checkpoint_wal_truncates_wal_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        let _writer = open_wal_without_autocheckpoint(&db);
+        let wal = sidecar_path(&db, "-wal");
+        assert!(fs::metadata(&wal).unwrap().len() > 0);
+
+        let result = checkpoint_wal(&db).expect("checkpoint");
+
+        assert!(!result.busy);
+        assert_eq!(fs::metadata(&wal).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn checkpoint_wal_reports_busy_when_reader_holds_snapshot() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        let _writer = open_wal_without_autocheckpoint(&db);
+        let reader = Connection::open(&db).expect("open reader");
+        reader
+            .execute_batch("BEGIN; SELECT count(*) FROM notes;")
+            .expect("hold read snapshot");
+
+        let result = checkpoint_wal_best_effort(&db).expect("checkpoint result");
+
+        assert!(result.busy);
+    }
+
+    #[test]
+    fn checkpoint_wal_best_effort_skips_without_wal() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+        create_db_with_free_pages(&db);
+
+        assert_eq!(checkpoint_wal_best_effort(&db), None);
+    }
+
+    #[test]
+    fn vacuum_rejects_missing_database() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join(DB_FILE_NAME);
+
+        assert!(vacuum(&db).is_err());
+        assert!(!db.exists(), "vacuum no debe crear la base de datos");
+    }
+}
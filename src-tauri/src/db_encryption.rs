@@ -0,0 +1,413 @@
+// Cifrado de la base de datos de Narrative Assistant (SQLCipher)
+//
+// La base de datos guarda los manuscritos completos en claro: quien tuviera
+// acceso al disco (una copia de seguridad, un portátil perdido) podía leerlos.
+// Para encargos confidenciales se puede cifrar con SQLCipher:
+//
+// - `encrypt_database` genera una clave aleatoria, la guarda en el almacén del
+//   sistema (como las claves de API de secrets.rs, pero sin exponerla al
+//   frontend) y reescribe la base de datos cifrada con `sqlcipher_export`,
+//   con el backend pausado. Si algo falla, la base de datos en claro queda
+//   intacta. Los snapshots existentes se cifran con la misma clave.
+// - Al lanzar el backend, la clave se le pasa en `NA_DB_KEY`
+//   (persistence/encryption.py en el backend).
+// - Las operaciones del shell sobre la base de datos (database.rs) aplican la
+//   clave al abrirla con `apply_key`.
+//
+// En lugar del almacén del sistema, la clave puede salir de una contraseña
+// (project_unlock.rs); entonces solo está en memoria mientras la base de
+// datos está desbloqueada.
+//
+// El cifrado es opcional: sin clave guardada todo sigue como antes. Se decide
+// por la cabecera del fichero (`is_plaintext`), no por la clave, para que una
+// base de datos en claro (p. ej. restaurada de un snapshot anterior) siga
+// abriéndose.
+
+use keyring::Entry;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::project_unlock;
+use crate::secrets::KEYRING_SERVICE;
+use crate::BackendServer;
+use crate::{cleanup, database, downloads};
+
+/// Entrada del almacén del sistema con la clave (nunca accesible con `get_secret`)
+const KEY_SECRET: &str = "database_key";
+
+/// Variable de entorno con la que el backend recibe la clave
+#[cfg_attr(debug_assertions, allow(dead_code))] // En desarrollo el backend se lanza a mano
+pub const KEY_ENV: &str = "NA_DB_KEY";
+
+/// Cabecera de un fichero SQLite sin cifrar
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Clave leída del almacén del sistema (se consulta una vez por sesión)
+static KEY_CACHE: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+/// Estado del cifrado (sección "Gestionar datos")
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct DatabaseEncryption {
+    pub encrypted: bool,
+    /// Si la base de datos existe (sin ella no hay nada que cifrar)
+    pub exists: bool,
+}
+
+fn key_entry() -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, KEY_SECRET)
+        .map_err(|e| format!("No se pudo acceder al almacen de claves: {}", e))
+}
+
+/// Clave guardada (64 caracteres hexadecimales), si se cifró la base de datos
+pub(crate) fn stored_key() -> Option<String> {
+    let mut cache = KEY_CACHE.lock().unwrap();
+    cache
+        .get_or_insert_with(|| {
+            // Con contraseña, la clave solo existe mientras está desbloqueada
+            if project_unlock::is_password_protected() {
+                return None;
+            }
+            read_keychain_key()
+        })
+        .clone()
+}
+
+fn read_keychain_key() -> Option<String> {
+    match key_entry().and_then(|entry| {
+        entry.get_password().map(Some).or_else(|e| match e {
+            keyring::Error::NoEntry => Ok(None),
+            e => Err(format!("Error leyendo la clave de la base de datos: {}", e)),
+        })
+    }) {
+        Ok(key) => key,
+        Err(e) => {
+            tracing::warn!(target: "db_encryption", "{}", e);
+            None
+        }
+    }
+}
+
+/// Clave de esta sesión, derivada de la contraseña (`None` al bloquear)
+pub(crate) fn set_session_key(key: Option<String>) {
+    *KEY_CACHE.lock().unwrap() = Some(key);
+}
+
+/// Borra la clave del almacén del sistema (la base de datos pasa a depender de la contraseña)
+pub(crate) fn delete_keychain_key() -> Result<(), String> {
+    match key_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!(
+            "Error borrando la clave de la base de datos: {}",
+            e
+        )),
+    }
+}
+
+/// Guarda la clave en el almacén del sistema
+pub(crate) fn store_key(key: &str) -> Result<(), String> {
+    key_entry()?
+        .set_password(key)
+        .map_err(|e| format!("Error guardando la clave de la base de datos: {}", e))?;
+    *KEY_CACHE.lock().unwrap() = Some(Some(key.to_string()));
+    Ok(())
+}
+
+/// 256 bits aleatorios del sistema operativo, en hexadecimal
+fn generate_key() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| format!("Error generando la clave de la base de datos: {}", e))?;
+    Ok(downloads::to_hex(&bytes))
+}
+
+/// Si el fichero es una base de datos SQLite sin cifrar
+pub fn is_plaintext(db: &Path) -> bool {
+    let mut header = [0u8; 16];
+    fs::File::open(db)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|()| &header == PLAINTEXT_HEADER)
+}
+
+/// Literal de clave en bruto de SQLCipher (`x'…'`): sin derivación de contraseña
+fn raw_key(key: &str) -> String {
+    format!("\"x'{}'\"", key)
+}
+
+fn use_key(conn: &Connection, key: &str) -> Result<(), String> {
+    conn.execute_batch(&format!("PRAGMA key = {};", raw_key(key)))
+        .map_err(|e| format!("Error aplicando la clave de la base de datos: {}", e))
+}
+
+/// Si `key` abre la base de datos cifrada `db`
+pub(crate) fn key_opens(db: &Path, key: &str) -> bool {
+    Connection::open(db).is_ok_and(|conn| {
+        use_key(&conn, key).is_ok()
+            && conn
+                .query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+                    row.get::<_, i64>(0)
+                })
+                .is_ok()
+    })
+}
+
+/// Cambia la clave de la base de datos cifrada `db` (con el backend detenido)
+pub(crate) fn rekey_file(db: &Path, old_key: &str, new_key: &str) -> Result<(), String> {
+    let conn =
+        Connection::open(db).map_err(|e| format!("Error abriendo {}: {}", db.display(), e))?;
+    use_key(&conn, old_key)?;
+    // SQLCipher no cambia la clave en modo WAL. Salir de él vuelca el WAL en la
+    // base de datos; el backend lo reactiva al conectar
+    conn.query_row("PRAGMA journal_mode = DELETE", [], |_| Ok(()))
+        .map_err(|e| format!("Error preparando la base de datos: {}", e))?;
+    conn.execute_batch(&format!("PRAGMA rekey = {};", raw_key(new_key)))
+        .map_err(|e| format!("Error cambiando la clave de la base de datos: {}", e))
+}
+
+/// Aplica la clave a una conexión recién abierta si la base de datos está cifrada
+pub(crate) fn apply_key(conn: &Connection, db: &Path) -> Result<(), String> {
+    if is_plaintext(db) {
+        return Ok(());
+    }
+    match stored_key() {
+        Some(key) => use_key(conn, &key),
+        None => Ok(()),
+    }
+}
+
+/// Variable de entorno con la clave para el backend, si la base de datos está
+/// cifrada o todavía no existe (el backend la creará cifrada)
+#[cfg_attr(debug_assertions, allow(dead_code))] // En desarrollo el backend se lanza a mano
+pub fn backend_env() -> Option<(&'static str, String)> {
+    let db = database::database_path()?;
+    if db.exists() && is_plaintext(&db) {
+        return None;
+    }
+    stored_key().map(|key| (KEY_ENV, key))
+}
+
+/// Reescribe la base de datos en claro `db` cifrada con `key`.
+/// La copia cifrada se verifica antes de sustituir el fichero original.
+pub(crate) fn encrypt_file(db: &Path, key: &str) -> Result<(), String> {
+    if !is_plaintext(db) {
+        return Err("La base de datos ya esta cifrada".into());
+    }
+    database::checkpoint_wal(db)?;
+    let tmp = db.with_extension("encrypting");
+    let _ = fs::remove_file(&tmp);
+
+    let result = (|| {
+        let conn = database::open_existing(db)?;
+        let user_version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| format!("Error leyendo la base de datos: {}", e))?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            [tmp.to_string_lossy().into_owned(), format!("x'{}'", key)],
+        )
+        .map_err(|e| format!("Error creando la copia cifrada: {}", e))?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+            .map_err(|e| format!("Error cifrando la base de datos: {}", e))?;
+        conn.execute_batch(&format!(
+            "PRAGMA encrypted.user_version = {}; DETACH DATABASE encrypted;",
+            user_version
+        ))
+        .map_err(|e| format!("Error cerrando la copia cifrada: {}", e))?;
+        drop(conn);
+
+        let check = Connection::open(&tmp)
+            .map_err(|e| format!("Error abriendo la copia cifrada: {}", e))?;
+        use_key(&check, key)?;
+        let integrity: String = check
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(|e| format!("La copia cifrada no se puede leer: {}", e))?;
+        if integrity != "ok" {
+            return Err(format!("La copia cifrada esta danada: {}", integrity));
+        }
+        Ok(())
+    })();
+
+    let result = result.and_then(|()| database::replace_with(db, &tmp));
+    let _ = fs::remove_file(&tmp);
+    result
+}
+
+/// Directorio de snapshots de la base de datos `db`
+pub(crate) fn snapshots_dir(db: &Path) -> Option<PathBuf> {
+    db.parent()
+        .map(|parent| parent.join(cleanup::SNAPSHOTS_DIR))
+}
+
+/// Deja los snapshots de `dir` cifrados con `new_key`: cifra los que están en
+/// claro y cambia la clave de los que abre `old_key`. Los que ya están cifrados
+/// con otra clave no se tocan. Devuelve error con los que no se pudieron
+/// convertir (los demás quedan convertidos).
+pub(crate) fn reencrypt_snapshots(
+    dir: &Path,
+    old_key: Option<&str>,
+    new_key: &str,
+) -> Result<(), String> {
+    let mut failed = Vec::new();
+    for snapshot in cleanup::list_snapshot_files(dir) {
+        let path = &snapshot.path;
+        let result = if is_plaintext(path) {
+            encrypt_file(path, new_key)
+        } else {
+            match old_key {
+                Some(old) if old != new_key && key_opens(path, old) => {
+                    rekey_file(path, old, new_key)
+                }
+                _ => Ok(()),
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!(target: "db_encryption", "Snapshot {}: {}", path.display(), e);
+            failed.push(
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "No se pudieron cifrar los snapshots: {}",
+            failed.join(", ")
+        ))
+    }
+}
+
+/// Estado del cifrado de la base de datos
+#[tauri::command]
+pub fn get_database_encryption() -> DatabaseEncryption {
+    let db = database::database_path();
+    let exists = db.as_deref().is_some_and(Path::exists);
+    DatabaseEncryption {
+        encrypted: exists && db.as_deref().is_some_and(|db| !is_plaintext(db)),
+        exists,
+    }
+}
+
+/// Cifra la base de datos existente y sus snapshots (si no, seguirían en claro
+/// con el contenido de los manuscritos), pausando el backend mientras dura
+#[tauri::command]
+pub async fn encrypt_database(
+    app: AppHandle,
+    server_state: State<'_, BackendServer>,
+) -> Result<DatabaseEncryption, String> {
+    let db = database::database_path().ok_or("No se pudo determinar el directorio home")?;
+    if !db.exists() {
+        return Err(format!("Base de datos no encontrada: {}", db.display()));
+    }
+    if !is_plaintext(&db) {
+        return Err("La base de datos ya esta cifrada".into());
+    }
+
+    // La clave se guarda antes de cifrar: un fichero cifrado sin su clave se perdería
+    let key = match stored_key() {
+        Some(key) => key,
+        None => generate_key()?,
+    };
+    store_key(&key)?;
+
+    let was_running = crate::pause_backend(&app, &server_state);
+    let db_clone = db.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        encrypt_file(&db_clone, &key)?;
+        match snapshots_dir(&db_clone) {
+            Some(dir) => reencrypt_snapshots(&dir, None, &key),
+            None => Ok(()),
+        }
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de cifrado: {}", e))
+    .and_then(|r| r);
+    // El backend se relanza con `NA_DB_KEY` (o sin ella si el cifrado falló)
+    crate::resume_backend(&app, server_state, was_running).await;
+
+    result?;
+    tracing::info!(target: "db_encryption", "Base de datos cifrada: {}", db.display());
+    Ok(get_database_encryption())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_copy_replaces_the_plaintext_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("narrative_assistant.db");
+        {
+            let conn = Connection::open(&db).unwrap();
+            conn.execute_batch(
+                "PRAGMA journal_mode = WAL; PRAGMA user_version = 34;
+                 CREATE TABLE projects (id INTEGER PRIMARY KEY, name TEXT);
+                 INSERT INTO projects (name) VALUES ('Encargo confidencial');",
+            )
+            .unwrap();
+        }
+        assert!(is_plaintext(&db));
+
+        let key = generate_key().unwrap();
+        assert_eq!(key.len(), 64);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+        encrypt_file(&db, &key).unwrap();
+        assert!(!is_plaintext(&db));
+        assert!(!fs::read(&db)
+            .unwrap()
+            .windows(20)
+            .any(|w| w == b"Encargo confidencial"));
+        assert!(encrypt_file(&db, &key).is_err());
+
+        let conn = Connection::open(&db).unwrap();
+        use_key(&conn, &key).unwrap();
+        let name: String = conn
+            .query_row("SELECT name FROM projects", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(name, "Encargo confidencial");
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 34);
+
+        // Sin la clave (o con otra) no se puede leer
+        let wrong = Connection::open(&db).unwrap();
+        use_key(&wrong, &generate_key().unwrap()).unwrap();
+        assert!(wrong
+            .query_row("SELECT count(*) FROM projects", [], |row| row
+                .get::<_, i64>(0))
+            .is_err());
+    }
+
+    #[test]
+    fn snapshots_follow_the_database_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = dir.path().join(cleanup::SNAPSHOTS_DIR);
+        fs::create_dir_all(&snapshots).unwrap();
+        let plain = snapshots.join("narrative_assistant-1.db");
+        let old = snapshots.join("narrative_assistant-2.db");
+        for path in [&plain, &old] {
+            Connection::open(path)
+                .unwrap()
+                .execute_batch("CREATE TABLE projects (id INTEGER PRIMARY KEY);")
+                .unwrap();
+        }
+        let old_key = generate_key().unwrap();
+        encrypt_file(&old, &old_key).unwrap();
+
+        let new_key = generate_key().unwrap();
+        reencrypt_snapshots(&snapshots, Some(&old_key), &new_key).unwrap();
+        assert!(!is_plaintext(&plain));
+        assert!(key_opens(&plain, &new_key));
+        assert!(key_opens(&old, &new_key));
+        assert!(!key_opens(&old, &old_key));
+    }
+}
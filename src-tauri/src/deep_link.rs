@@ -0,0 +1,198 @@
+// Enlaces narrassist:// de Narrative Assistant
+//
+// Los informes HTML exportados enlazan de vuelta a la aplicación con URLs
+// `narrassist://project/<id>` o `narrassist://project/<id>/chapter/<n>`.
+// El sistema abre la app (o, gracias a tauri-plugin-single-instance, reenvía
+// el enlace a la instancia que ya está abierta) y el frontend navega al
+// proyecto y capítulo indicados al recibir el evento `deep-link`.
+//
+// Si el enlace llega antes de que el frontend esté montado (arranque en frío)
+// queda pendiente hasta que lo recoja `take_pending_deep_link`.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::splash;
+use crate::tray;
+use crate::windows::{self, MAIN_WINDOW};
+
+/// Esquema registrado en tauri.conf.json (plugins.deep-link)
+pub const SCHEME: &str = "narrassist";
+
+/// Destino de un enlace narrassist://
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct DeepLinkTarget {
+    pub project_id: i64,
+    /// Número de capítulo (1-based), si el enlace apunta a uno
+    pub chapter: Option<u32>,
+    pub url: String,
+}
+
+/// Interpreta `narrassist://project/<id>[/chapter/<n>]`
+pub fn parse(url: &Url) -> Result<DeepLinkTarget, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Esquema no soportado: {}", url.scheme()));
+    }
+    if url.host_str() != Some("project") {
+        return Err(format!("Enlace no reconocido: {}", url));
+    }
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|s| s.filter(|seg| !seg.is_empty()).collect())
+        .unwrap_or_default();
+
+    let project_id = match segments.first().and_then(|id| id.parse::<i64>().ok()) {
+        Some(id) if id > 0 => id,
+        _ => return Err(format!("Id de proyecto no valido en {}", url)),
+    };
+    let chapter = match &segments[1..] {
+        [] => None,
+        ["chapter", n] => match n.parse::<u32>() {
+            Ok(n) if n > 0 => Some(n),
+            _ => return Err(format!("Capitulo no valido en {}", url)),
+        },
+        _ => return Err(format!("Enlace no reconocido: {}", url)),
+    };
+
+    Ok(DeepLinkTarget {
+        project_id,
+        chapter,
+        url: url.to_string(),
+    })
+}
+
+/// Si los argumentos de una segunda instancia traen un enlace narrassist://
+pub fn is_deep_link_argv(argv: &[String]) -> bool {
+    let prefix = format!("{}://", SCHEME);
+    argv.iter().skip(1).any(|arg| arg.starts_with(&prefix))
+}
+
+/// Enlace recibido que el frontend de la ventana principal aún no ha recogido
+pub struct DeepLinkState(Mutex<Option<DeepLinkTarget>>);
+
+impl DeepLinkState {
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.0.lock().unwrap().is_some()
+    }
+}
+
+/// Envía el enlace a la ventana del proyecto si está abierta, o a la principal
+fn dispatch(app: &AppHandle, target: DeepLinkTarget) {
+    tracing::info!(target: "deep_link", "Abriendo {}", target.url);
+
+    let project_label = windows::project_window_label(target.project_id);
+    if let Some(window) = app.get_webview_window(&project_label) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        let _ = app.emit_to(
+            EventTarget::webview_window(&project_label),
+            "deep-link",
+            target,
+        );
+        return;
+    }
+
+    *app.state::<DeepLinkState>().0.lock().unwrap() = Some(target.clone());
+    // Con la ventana de carga aún visible, la principal se mostrará al terminar el arranque
+    if splash::is_revealed() {
+        tray::show_main_window(app);
+    }
+    let _ = app.emit_to(
+        EventTarget::webview_window(MAIN_WINDOW),
+        "deep-link",
+        target,
+    );
+}
+
+/// Abre un proyecto como si llegara `narrassist://project/<id>` (menú de recientes)
+pub(crate) fn open_project(app: &AppHandle, project_id: i64) {
+    dispatch(
+        app,
+        DeepLinkTarget {
+            project_id,
+            chapter: None,
+            url: format!("{}://project/{}", SCHEME, project_id),
+        },
+    );
+}
+
+/// Procesa los enlaces que el sistema entrega a la aplicación
+pub fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    for url in urls {
+        match parse(url) {
+            Ok(target) => dispatch(app, target),
+            Err(e) => tracing::warn!(target: "deep_link", "{}", e),
+        }
+    }
+}
+
+/// Registra el esquema y procesa el enlace con el que se lanzó la app
+pub fn setup(app: &AppHandle) {
+    // En Linux (y en Windows en desarrollo) el esquema se registra en tiempo de ejecución;
+    // en los instaladores lo declaran NSIS y el Info.plist
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!(target: "deep_link", "No se pudo registrar {}://: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_urls(&handle, &event.urls());
+    });
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => handle_urls(app, &urls),
+        Ok(None) => {}
+        Err(e) => tracing::warn!(target: "deep_link", "No se pudo leer el enlace inicial: {}", e),
+    }
+}
+
+/// Enlace pendiente para la ventana principal (se consume al leerlo)
+#[tauri::command]
+pub fn take_pending_deep_link(state: tauri::State<'_, DeepLinkState>) -> Option<DeepLinkTarget> {
+    state.0.lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(url: &str) -> Result<DeepLinkTarget, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_project_and_chapter_links() {
+        let link = target("narrassist://project/12/chapter/3").unwrap();
+        assert_eq!(link.project_id, 12);
+        assert_eq!(link.chapter, Some(3));
+
+        let link = target("narrassist://project/7/").unwrap();
+        assert_eq!(link.project_id, 7);
+        assert_eq!(link.chapter, None);
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert!(target("https://project/12").is_err());
+        assert!(target("narrassist://settings/12").is_err());
+        assert!(target("narrassist://project/abc").is_err());
+        assert!(target("narrassist://project/0").is_err());
+        assert!(target("narrassist://project/12/chapter/0").is_err());
+        assert!(target("narrassist://project/12/scene/2").is_err());
+    }
+
+    #[test]
+    fn detects_links_in_second_instance_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(is_deep_link_argv(&args(&["app", "narrassist://project/1"])));
+        assert!(!is_deep_link_argv(&args(&["app"])));
+        assert!(!is_deep_link_argv(&args(&["narrassist://project/1"])));
+    }
+}
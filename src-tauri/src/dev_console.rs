@@ -0,0 +1,97 @@
+// Consola de desarrollador de Narrative Assistant
+//
+// En las versiones publicadas no hay forma de ver la consola del webview ni
+// los logs del shell sin instalar una compilación de depuración. El atajo
+// oculto CmdOrCtrl+Alt+Shift+D (lo captura el frontend, no aparece en la
+// lista de atajos) llama a `toggle_developer_console`, que abre:
+//   - Las DevTools del webview de la ventana que lo pide
+//   - Una ventana (frontend/public/dev-console.html) que muestra el log del
+//     shell en vivo: las últimas líneas y cada nueva como `dev-console-log`
+//
+// La primera vez activa la preferencia oculta `developer_tools`, con la que
+// la consola aparece también en la paleta de comandos.
+
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::logging::LogState;
+use crate::settings::SettingsStore;
+
+/// Label de la ventana de logs
+pub const DEV_CONSOLE_WINDOW: &str = "dev-console";
+
+/// Página de la ventana de logs (frontend/public)
+const DEV_CONSOLE_URL: &str = "dev-console.html";
+
+/// Evento con cada línea nueva del log
+const LOG_LINE_EVENT: &str = "dev-console-log";
+
+/// Si `label` es la ventana de logs (no guarda geometría ni recibe eventos de menú)
+pub fn is_dev_console(label: &str) -> bool {
+    label == DEV_CONSOLE_WINDOW
+}
+
+/// Abre la ventana de logs y empieza a reenviarle las líneas nuevas
+fn open_log_window(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(DEV_CONSOLE_WINDOW) {
+        let _ = window.unminimize();
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        DEV_CONSOLE_WINDOW,
+        WebviewUrl::App(DEV_CONSOLE_URL.into()),
+    )
+    .title("Consola de desarrollador")
+    .inner_size(960.0, 560.0)
+    .min_inner_size(480.0, 280.0)
+    .focused(true)
+    .build()
+    .map_err(|e| format!("No se pudo abrir la consola de desarrollador: {}", e))?;
+
+    // El hilo termina con la primera línea que llega con la ventana ya cerrada
+    let receiver = app.state::<LogState>().subscribe();
+    let app = app.clone();
+    std::thread::Builder::new()
+        .name("dev-console".into())
+        .spawn(move || {
+            for line in receiver {
+                let Some(window) = app.get_webview_window(DEV_CONSOLE_WINDOW) else {
+                    break;
+                };
+                if window.emit(LOG_LINE_EVENT, line).is_err() {
+                    break;
+                }
+            }
+        })
+        .map_err(|e| format!("No se pudo iniciar la consola de desarrollador: {}", e))?;
+    Ok(())
+}
+
+/// Abre las DevTools de `target` y la ventana de logs, o cierra ambas si ya
+/// estaba abierta. Devuelve si la consola queda abierta.
+pub(crate) fn toggle(app: &AppHandle, target: &WebviewWindow) -> Result<bool, String> {
+    if let Some(window) = app.get_webview_window(DEV_CONSOLE_WINDOW) {
+        target.close_devtools();
+        let _ = window.close();
+        return Ok(false);
+    }
+
+    let store = app.state::<SettingsStore>();
+    if !store.get().developer_tools {
+        let updated = store.update(serde_json::json!({ "developer_tools": true }))?;
+        let _ = app.emit("shell-settings-changed", &updated);
+        tracing::info!(target: "dev_console", "Herramientas de desarrollador activadas");
+    }
+
+    target.open_devtools();
+    open_log_window(app)?;
+    tracing::info!(target: "dev_console", "Consola de desarrollador abierta para {}", target.label());
+    Ok(true)
+}
+
+/// Abre o cierra la consola de desarrollador para la ventana que lo pide
+#[tauri::command]
+pub fn toggle_developer_console(app: AppHandle, window: WebviewWindow) -> Result<bool, String> {
+    toggle(&app, &window)
+}
@@ -0,0 +1,449 @@
+// Cola de descargas de modelos
+//
+// `download_model` no descarga directamente: añade el modelo a la cola y
+// espera a que termine. La cola descarga los modelos de uno en uno con
+// downloads.rs y se guarda en download_queue.json (directorio de datos de la
+// app), así que lo que quedó pendiente al cerrar la app continúa al abrirla;
+// los ficheros a medias (.part) se reanudan donde se quedaron.
+//
+// `pause_download` detiene la descarga en curso conservando los .part y pasa
+// a la siguiente; `resume_download` la devuelve a la cola y `cancel_download`
+// la quita y borra lo descargado. Cada cambio se emite como
+// `download-queue-progress` con el progreso conjunto, para el panel de
+// descargas. Las reparaciones de `repair_models` no pasan por la cola.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{broadcast, Notify};
+
+use crate::downloads::{self, DownloadProgress, DownloadsState};
+use crate::models::{ModelManifest, ModelSpec};
+use crate::{cleanup, settings};
+
+/// Nombre del fichero de la cola
+const QUEUE_FILE_NAME: &str = "download_queue.json";
+
+/// Estado de un modelo en la cola
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStatus {
+    Queued,
+    Downloading,
+    Paused,
+    Failed,
+}
+
+/// Modelo en la cola
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QueuedDownload {
+    pub id: String,
+    pub display_name: String,
+    pub status: QueueStatus,
+    #[serde(default)]
+    pub bytes_downloaded: u64,
+    #[serde(default)]
+    pub bytes_total: u64,
+    #[serde(default)]
+    pub speed_bps: f64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Cola con el progreso conjunto (payload de `download-queue-progress`)
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DownloadQueueStatus {
+    pub downloads: Vec<QueuedDownload>,
+    pub bytes_downloaded: u64,
+    pub bytes_total: u64,
+    pub percent: f64,
+    pub speed_bps: f64,
+}
+
+impl DownloadQueueStatus {
+    fn new(downloads: Vec<QueuedDownload>) -> Self {
+        // Los fallidos no cuentan: no avanzan hasta que se reanuden
+        let pending = downloads.iter().filter(|d| d.status != QueueStatus::Failed);
+        let (bytes_downloaded, bytes_total) = pending.fold((0, 0), |(done, total), d| {
+            (done + d.bytes_downloaded, total + d.bytes_total)
+        });
+        Self {
+            bytes_downloaded,
+            bytes_total,
+            percent: if bytes_total > 0 {
+                bytes_downloaded as f64 * 100.0 / bytes_total as f64
+            } else {
+                0.0
+            },
+            speed_bps: downloads
+                .iter()
+                .filter(|d| d.status == QueueStatus::Downloading)
+                .map(|d| d.speed_bps)
+                .sum(),
+            downloads,
+        }
+    }
+}
+
+/// Cola en memoria, registrada con `.manage()`
+pub struct DownloadQueue {
+    path: PathBuf,
+    entries: Mutex<Vec<QueuedDownload>>,
+    /// Descarga en curso y la señal para detenerla
+    current: Mutex<Option<(String, Arc<Notify>)>>,
+    /// Si hay una tarea procesando la cola
+    worker: AtomicBool,
+    /// Resultado de cada descarga, para quien la espera en `download_model`
+    finished: broadcast::Sender<(String, Result<String, String>)>,
+}
+
+impl DownloadQueue {
+    /// Carga download_queue.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(QUEUE_FILE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let mut entries: Vec<QueuedDownload> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        // La descarga que estaba en curso al cerrar vuelve a la cola
+        for entry in &mut entries {
+            if entry.status == QueueStatus::Downloading {
+                entry.status = QueueStatus::Queued;
+            }
+            entry.speed_bps = 0.0;
+        }
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            current: Mutex::new(None),
+            worker: AtomicBool::new(false),
+            finished: broadcast::channel(16).0,
+        }
+    }
+
+    fn status(&self) -> DownloadQueueStatus {
+        DownloadQueueStatus::new(self.entries.lock().unwrap().clone())
+    }
+
+    /// Guarda la cola y emite `download-queue-progress`
+    fn changed(&self, app: &AppHandle) {
+        let status = self.status();
+        match serde_json::to_vec_pretty(&status.downloads) {
+            Ok(json) => {
+                if let Err(e) = settings::write_atomic(&self.path, &json) {
+                    tracing::warn!(target: "downloads", "Error guardando {}: {}", self.path.display(), e);
+                }
+            }
+            Err(e) => tracing::warn!(target: "downloads", "{}", e),
+        }
+        let _ = app.emit("download-queue-progress", status);
+    }
+
+    /// Actualiza el progreso del modelo que se está descargando (lo llama
+    /// `downloads::Progress` con cada evento `model-download-progress`)
+    pub(crate) fn report(&self, app: &AppHandle, progress: &DownloadProgress) {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            let Some(entry) = entries
+                .iter_mut()
+                .find(|e| e.id == progress.model_type && e.status == QueueStatus::Downloading)
+            else {
+                return;
+            };
+            if progress.bytes_total > 0 {
+                entry.bytes_downloaded = progress.bytes_downloaded;
+                entry.bytes_total = progress.bytes_total;
+            }
+            entry.speed_bps = progress.speed_bps;
+        }
+        // El progreso no se guarda: al reanudar lo recalculan los .part
+        let _ = app.emit("download-queue-progress", self.status());
+    }
+
+    /// Pone `spec` en la cola (o reanuda su entrada) y lanza la tarea si hace falta
+    fn enqueue(&self, app: &AppHandle, spec: &ModelSpec) {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            match entries.iter_mut().find(|e| e.id == spec.id) {
+                Some(entry) => {
+                    if matches!(entry.status, QueueStatus::Paused | QueueStatus::Failed) {
+                        entry.status = QueueStatus::Queued;
+                        entry.error = None;
+                    }
+                }
+                None => entries.push(QueuedDownload {
+                    id: spec.id.clone(),
+                    display_name: spec.display_name.clone(),
+                    status: QueueStatus::Queued,
+                    bytes_downloaded: 0,
+                    bytes_total: spec.size_mb * 1024 * 1024,
+                    speed_bps: 0.0,
+                    error: None,
+                }),
+            }
+        }
+        self.changed(app);
+        self.start(app);
+    }
+
+    fn start(&self, app: &AppHandle) {
+        if !self.worker.swap(true, Ordering::SeqCst) {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move { run(app).await });
+        }
+    }
+
+    /// Detiene la descarga de `id` si es la que está en curso
+    fn stop_current(&self, id: &str) {
+        if let Some((current, stop)) = self.current.lock().unwrap().as_ref() {
+            if current == id {
+                stop.notify_one();
+            }
+        }
+    }
+
+    /// Siguiente modelo en cola, marcado como en curso. Sin ninguno, la tarea
+    /// termina (dentro del mismo lock, para que `enqueue` lance otra).
+    fn next(&self) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.iter_mut().find(|e| e.status == QueueStatus::Queued) {
+            Some(entry) => {
+                entry.status = QueueStatus::Downloading;
+                Some(entry.id.clone())
+            }
+            None => {
+                self.worker.store(false, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+
+    /// Registra el final de la descarga de `id`
+    fn finish(&self, id: &str, result: Result<String, String>) {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            match &result {
+                Ok(_) => entries.retain(|e| e.id != id),
+                Err(error) => {
+                    if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+                        entry.status = QueueStatus::Failed;
+                        entry.speed_bps = 0.0;
+                        entry.error = Some(error.clone());
+                    }
+                }
+            }
+        }
+        let _ = self.finished.send((id.to_string(), result));
+    }
+}
+
+/// Borra lo descargado de un modelo cancelado
+fn remove_partial(app: &AppHandle, id: &str) {
+    let Some(spec) = ModelManifest::bundled().get(id).cloned() else {
+        return;
+    };
+    if let Some(root) = downloads::models_root(app) {
+        let _ = fs::remove_dir_all(downloads::staging_dir(&root, &spec));
+    }
+}
+
+/// Descarga los modelos en cola de uno en uno
+async fn run(app: AppHandle) {
+    let queue = app.state::<DownloadQueue>();
+    let downloads_state = app.state::<DownloadsState>();
+    while let Some(id) = queue.next() {
+        queue.changed(&app);
+        let Some(spec) = ModelManifest::bundled().get(&id).cloned() else {
+            queue.finish(&id, Err(format!("Modelo desconocido: {}", id)));
+            queue.changed(&app);
+            continue;
+        };
+
+        let stop = Arc::new(Notify::new());
+        *queue.current.lock().unwrap() = Some((id.clone(), stop.clone()));
+        let result = tokio::select! {
+            result = downloads::download(&app, &downloads_state, &spec, None) => Some(result),
+            _ = stop.notified() => None,
+        };
+        *queue.current.lock().unwrap() = None;
+
+        match result {
+            Some(result) => {
+                queue.finish(&id, result.map(|dir| dir.to_string_lossy().into_owned()));
+            }
+            // Pausada: los .part se conservan. Cancelada: ya no está en la cola.
+            None => {
+                let cancelled = !queue.entries.lock().unwrap().iter().any(|e| e.id == id);
+                tracing::info!(
+                    target: "downloads",
+                    "Descarga de {} {}",
+                    id,
+                    if cancelled { "cancelada" } else { "en pausa" }
+                );
+                if cancelled {
+                    remove_partial(&app, &id);
+                }
+            }
+        }
+        queue.changed(&app);
+    }
+}
+
+/// Reanuda al arrancar lo que quedó en cola al cerrar la app
+pub fn restore(app: &AppHandle) {
+    let queue = app.state::<DownloadQueue>();
+    let pending = queue
+        .entries
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|e| e.status == QueueStatus::Queued);
+    if pending {
+        tracing::info!(target: "downloads", "Reanudando la cola de descargas");
+        queue.start(app);
+    }
+}
+
+/// Añade `spec` a la cola y espera a que se descargue
+pub(crate) async fn download(app: &AppHandle, spec: &ModelSpec) -> Result<String, String> {
+    let queue = app.state::<DownloadQueue>();
+    // Suscribirse antes de encolar para no perder el resultado
+    let mut finished = queue.finished.subscribe();
+    queue.enqueue(app, spec);
+    loop {
+        match finished.recv().await {
+            Ok((id, result)) if id == spec.id => return result,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => {
+                return Err("La cola de descargas se ha cerrado".into())
+            }
+        }
+    }
+}
+
+/// Modelos en la cola de descargas con el progreso conjunto
+#[tauri::command]
+pub fn list_downloads(queue: State<'_, DownloadQueue>) -> DownloadQueueStatus {
+    queue.status()
+}
+
+/// Detiene la descarga de un modelo conservando lo descargado
+#[tauri::command]
+pub fn pause_download(
+    app: AppHandle,
+    queue: State<'_, DownloadQueue>,
+    id: String,
+) -> Result<DownloadQueueStatus, String> {
+    {
+        let mut entries = queue.entries.lock().unwrap();
+        let entry = entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| format!("{} no esta en la cola de descargas", id))?;
+        if matches!(entry.status, QueueStatus::Queued | QueueStatus::Downloading) {
+            entry.status = QueueStatus::Paused;
+            entry.speed_bps = 0.0;
+        }
+    }
+    queue.stop_current(&id);
+    queue.changed(&app);
+    Ok(queue.status())
+}
+
+/// Vuelve a poner en cola una descarga en pausa o fallida
+#[tauri::command]
+pub fn resume_download(
+    app: AppHandle,
+    queue: State<'_, DownloadQueue>,
+    id: String,
+) -> Result<DownloadQueueStatus, String> {
+    let spec = ModelManifest::bundled()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| format!("Modelo desconocido: {}", id))?;
+    queue.enqueue(&app, &spec);
+    Ok(queue.status())
+}
+
+/// Quita un modelo de la cola y borra lo que se había descargado
+#[tauri::command]
+pub fn cancel_download(
+    app: AppHandle,
+    queue: State<'_, DownloadQueue>,
+    id: String,
+) -> Result<DownloadQueueStatus, String> {
+    let active = {
+        let mut entries = queue.entries.lock().unwrap();
+        let index = entries
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or_else(|| format!("{} no esta en la cola de descargas", id))?;
+        entries.remove(index).status == QueueStatus::Downloading
+    };
+    // La que está en curso la borra la tarea cuando se haya detenido
+    if active {
+        queue.stop_current(&id);
+    } else {
+        remove_partial(&app, &id);
+    }
+    let _ = queue
+        .finished
+        .send((id.clone(), Err("Descarga cancelada".into())));
+    queue.changed(&app);
+    Ok(queue.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, status: QueueStatus, downloaded: u64, total: u64) -> QueuedDownload {
+        QueuedDownload {
+            id: id.into(),
+            display_name: id.into(),
+            status,
+            bytes_downloaded: downloaded,
+            bytes_total: total,
+            speed_bps: 100.0,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn interrupted_download_is_queued_again_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(QUEUE_FILE_NAME);
+        let saved = vec![
+            entry("spacy", QueueStatus::Downloading, 10, 100),
+            entry("embeddings", QueueStatus::Paused, 0, 50),
+        ];
+        fs::write(&path, serde_json::to_vec(&saved).unwrap()).unwrap();
+
+        let queue = DownloadQueue::load(path);
+        let status = queue.status();
+        assert_eq!(status.downloads[0].status, QueueStatus::Queued);
+        assert_eq!(status.downloads[0].speed_bps, 0.0);
+        assert_eq!(status.downloads[1].status, QueueStatus::Paused);
+        assert_eq!(queue.next().as_deref(), Some("spacy"));
+        assert_eq!(queue.next(), None);
+        assert!(!queue.worker.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn aggregate_progress_skips_failed_downloads() {
+        let status = DownloadQueueStatus::new(vec![
+            entry("spacy", QueueStatus::Downloading, 50, 100),
+            entry("embeddings", QueueStatus::Queued, 0, 100),
+            entry("transformer_ner", QueueStatus::Failed, 10, 100),
+        ]);
+        assert_eq!((status.bytes_downloaded, status.bytes_total), (50, 200));
+        assert_eq!(status.percent, 25.0);
+        assert_eq!(status.speed_bps, 100.0);
+    }
+}
@@ -0,0 +1,841 @@
+// Descarga de los modelos NLP desde el shell
+//
+// La primera ejecución descargaba los modelos desde el backend (spacy.cli,
+// huggingface_hub.snapshot_download): sin progreso fiable, sin reanudar tras un
+// corte y con el backend bloqueado mientras tanto. `download_model` los descarga
+// desde Rust a ~/.narrative_assistant/models (o NA_MODELS_DIR), en los mismos
+// directorios que busca core/model_manager.py:
+//   - spaCy: el .whl de la release de explosion/spacy-models, verificado con el
+//     SHA-256 del manifiesto; se extrae el subdirectorio versionado del paquete
+//   - HuggingFace: los ficheros del commit pinneado (API /tree), sin los pesos
+//     de otros frameworks; los de LFS se verifican con su SHA-256 y el resto
+//     con su tamaño
+//
+// Cada fichero se descarga a `<nombre>.part` en .downloads/<id>-<revisión>; si
+// la descarga se corta, la siguiente continúa con una petición Range. El modelo
+// solo se mueve a su directorio final cuando está completo y verificado. El
+// progreso se emite como `model-download-progress` con la forma de
+// /api/models/download/progress. Al terminar se guardan las sumas de lo
+// instalado para `verify_models` (integrity.rs).
+//
+// `download_model` pasa por la cola de descargas (download_queue.rs), que las
+// hace de una en una, las reanuda al reabrir la app y permite pausarlas.
+//
+// `download_limit_kbps` (preferencias) limita la velocidad de todas las
+// descargas del shell (`RateLimit`) para no saturar redes compartidas. Los
+// modelos de Ollama los descarga el propio servicio de Ollama y no pasan por aquí.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::download_queue::{self, DownloadQueue};
+use crate::models::{ModelKind, ModelManifest, ModelSpec};
+use crate::settings::{Settings, SettingsStore};
+use crate::{integrity, proxy};
+
+const HF_BASE_URL: &str = "https://huggingface.co";
+pub(crate) const SPACY_RELEASES_URL: &str =
+    "https://github.com/explosion/spacy-models/releases/download";
+
+/// Descargas a medias dentro del directorio de modelos
+pub(crate) const DOWNLOADS_DIR: &str = ".downloads";
+const PART_SUFFIX: &str = ".part";
+
+/// Marcador de versión que escribe y lee core/model_manager.py
+pub(crate) const VERSION_MARKER: &str = ".version";
+
+/// Tiempo máximo de las consultas a la API de HuggingFace
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Intervalo mínimo entre eventos de progreso
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Ficheros de otros frameworks que no usa el backend (los mismos
+/// `ignore_patterns` que core/model_manager.py)
+const IGNORED_EXTENSIONS: &[&str] = &["onnx", "h5", "ot", "msgpack"];
+const IGNORED_PREFIXES: &[&str] = &["tf_", "flax_"];
+const IGNORED_DIRS: &[&str] = &["onnx", "openvino"];
+
+/// Modelos descargándose, registrado con `.manage()`
+pub struct DownloadsState(Mutex<HashSet<String>>);
+
+/// Marca en `DownloadsState` mientras se mueve el directorio de modelos o se
+/// importa un paquete offline
+const EXCLUSIVE: &str = "*";
+
+impl DownloadsState {
+    pub fn new() -> Self {
+        Self(Mutex::new(HashSet::new()))
+    }
+
+    /// Bloquea las descargas mientras se reorganiza el directorio de modelos.
+    /// Falla si hay alguna en curso.
+    pub(crate) fn begin_exclusive(&self) -> bool {
+        let mut active = self.0.lock().unwrap();
+        active.is_empty() && active.insert(EXCLUSIVE.into())
+    }
+
+    pub(crate) fn end_exclusive(&self) {
+        self.0.lock().unwrap().remove(EXCLUSIVE);
+    }
+}
+
+/// Directorio de modelos por defecto (NA_MODELS_DIR o ~/.narrative_assistant/models)
+pub(crate) fn default_models_root() -> Option<PathBuf> {
+    match std::env::var_os("NA_MODELS_DIR") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => dirs::home_dir().map(|home| home.join(".narrative_assistant").join("models")),
+    }
+}
+
+/// Directorio de modelos con las preferencias `settings`
+pub(crate) fn models_root_for(settings: &Settings) -> Option<PathBuf> {
+    match &settings.models_dir {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => default_models_root(),
+    }
+}
+
+/// Directorio de modelos del backend (el de preferencias si se ha movido)
+pub(crate) fn models_root(app: &AppHandle) -> Option<PathBuf> {
+    models_root_for(&app.state::<SettingsStore>().get())
+}
+
+/// Progreso de un modelo (payload de `model-download-progress`, con los campos
+/// de DownloadProgress en core/model_manager.py)
+#[derive(Serialize, Clone, Debug)]
+pub struct DownloadProgress {
+    pub model_type: String,
+    /// "connecting", "downloading", "installing", "completed" o "error"
+    pub phase: String,
+    pub bytes_downloaded: u64,
+    pub bytes_total: u64,
+    pub percent: f64,
+    pub speed_bps: f64,
+    pub speed_mbps: f64,
+    pub eta_seconds: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Fichero remoto de un modelo
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RemoteFile {
+    /// Ruta relativa dentro del modelo
+    pub path: PathBuf,
+    pub url: String,
+    pub size: Option<u64>,
+    pub sha256: Option<String>,
+}
+
+/// Entrada de GET /api/models/<repo>/tree/<revisión>
+#[derive(Deserialize, Debug)]
+struct TreeEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    path: String,
+    #[serde(default)]
+    size: u64,
+    lfs: Option<LfsInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LfsInfo {
+    /// SHA-256 del contenido
+    oid: String,
+    size: u64,
+}
+
+/// Ruta relativa sin `..` ni componentes absolutos
+pub(crate) fn safe_relative(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+fn is_ignored(path: &Path) -> bool {
+    let in_ignored_dir = path
+        .components()
+        .next()
+        .and_then(|c| c.as_os_str().to_str())
+        .is_some_and(|first| IGNORED_DIRS.contains(&first) && path.components().count() > 1);
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    in_ignored_dir
+        || IGNORED_EXTENSIONS.contains(&extension)
+        || IGNORED_PREFIXES.iter().any(|p| file_name.starts_with(p))
+}
+
+/// Ficheros a descargar de un repo HF en `revision`
+fn hf_files(repo: &str, revision: &str, entries: Vec<TreeEntry>) -> Vec<RemoteFile> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.kind == "file")
+        .filter_map(|entry| {
+            let path = safe_relative(&entry.path)?;
+            if is_ignored(&path) {
+                return None;
+            }
+            let (size, sha256) = match entry.lfs {
+                Some(lfs) => (lfs.size, Some(lfs.oid.to_ascii_lowercase())),
+                None => (entry.size, None),
+            };
+            Some(RemoteFile {
+                url: format!(
+                    "{}/{}/resolve/{}/{}",
+                    HF_BASE_URL, repo, revision, entry.path
+                ),
+                path,
+                size: Some(size),
+                sha256,
+            })
+        })
+        .collect()
+}
+
+/// .whl de un paquete spaCy en una versión
+fn spacy_wheel(name: &str, version: &str, sha256: Option<&str>) -> RemoteFile {
+    let package = format!("{}-{}", name, version);
+    let file = format!("{}-py3-none-any.whl", package);
+    RemoteFile {
+        url: format!("{}/{}/{}", SPACY_RELEASES_URL, package, file),
+        path: PathBuf::from(file),
+        size: None,
+        sha256: sha256.map(str::to_ascii_lowercase),
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Fecha UTC en ISO 8601 ("2026-01-31T12:00:00Z", como el marcador del backend)
+fn iso_utc(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Algoritmo de días a fecha civil (proleptic gregoriano)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Cada cuánto se relee el límite de preferencias durante una descarga
+const RATE_LIMIT_RELOAD: Duration = Duration::from_secs(2);
+
+/// Ventana de la medición: tras una pausa no se permite una ráfaga de más de 1 s
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Espera necesaria para que `bytes` en `elapsed` no superen `bytes_per_sec`
+fn rate_limit_delay(bytes_per_sec: u64, bytes: u64, elapsed: Duration) -> Duration {
+    Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64).saturating_sub(elapsed)
+}
+
+/// Limita la velocidad de una descarga a `download_limit_kbps`. El límite se
+/// relee cada pocos segundos: cambiarlo afecta también a las descargas en curso.
+pub(crate) struct RateLimit<'a> {
+    app: &'a AppHandle,
+    bytes_per_sec: Option<u64>,
+    checked: Instant,
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+impl<'a> RateLimit<'a> {
+    pub fn new(app: &'a AppHandle) -> Self {
+        Self {
+            app,
+            bytes_per_sec: Self::configured(app),
+            checked: Instant::now(),
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+
+    fn configured(app: &AppHandle) -> Option<u64> {
+        app.state::<SettingsStore>()
+            .get()
+            .download_limit_kbps
+            .map(|kbps| u64::from(kbps) * 1024)
+    }
+
+    /// Espera lo necesario tras recibir `bytes`
+    pub async fn consume(&mut self, bytes: usize) {
+        if self.checked.elapsed() >= RATE_LIMIT_RELOAD {
+            self.bytes_per_sec = Self::configured(self.app);
+            self.checked = Instant::now();
+        }
+        let Some(bytes_per_sec) = self.bytes_per_sec else {
+            return;
+        };
+        self.window_bytes += bytes as u64;
+        let delay = rate_limit_delay(
+            bytes_per_sec,
+            self.window_bytes,
+            self.window_start.elapsed(),
+        );
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        if self.window_start.elapsed() >= RATE_LIMIT_WINDOW {
+            self.window_start = Instant::now();
+            self.window_bytes = 0;
+        }
+    }
+}
+
+/// Acumula el progreso de todos los ficheros de un modelo
+pub(crate) struct Progress<'a> {
+    app: &'a AppHandle,
+    model_id: &'a str,
+    total: u64,
+    /// Bytes de los ficheros ya terminados
+    finished: u64,
+    /// Bytes descargados en esta sesión (para la velocidad)
+    fetched: u64,
+    started: Instant,
+    last_emit: Option<Instant>,
+    rate: RateLimit<'a>,
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(app: &'a AppHandle, model_id: &'a str, total: u64) -> Self {
+        Self {
+            app,
+            model_id,
+            total,
+            finished: 0,
+            fetched: 0,
+            started: Instant::now(),
+            last_emit: None,
+            rate: RateLimit::new(app),
+        }
+    }
+
+    fn snapshot(&self, phase: &str, current: u64, error: Option<String>) -> DownloadProgress {
+        let downloaded = self.finished + current;
+        let total = self.total.max(downloaded);
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let speed_bps = if elapsed > 0.0 {
+            self.fetched as f64 / elapsed
+        } else {
+            0.0
+        };
+        DownloadProgress {
+            model_type: self.model_id.to_string(),
+            phase: phase.to_string(),
+            bytes_downloaded: downloaded,
+            bytes_total: total,
+            percent: if total > 0 {
+                downloaded as f64 * 100.0 / total as f64
+            } else {
+                0.0
+            },
+            speed_bps,
+            speed_mbps: speed_bps / (1024.0 * 1024.0),
+            eta_seconds: (speed_bps > 0.0).then(|| (total - downloaded) as f64 / speed_bps),
+            error,
+        }
+    }
+
+    /// Emite el progreso (las fases distintas de "downloading" siempre)
+    pub fn emit(&mut self, phase: &str, current: u64) {
+        let throttled = phase == "downloading"
+            && self
+                .last_emit
+                .is_some_and(|at| at.elapsed() < PROGRESS_EVENT_INTERVAL);
+        if !throttled {
+            self.last_emit = Some(Instant::now());
+            let snapshot = self.snapshot(phase, current, None);
+            if let Some(queue) = self.app.try_state::<DownloadQueue>() {
+                queue.report(self.app, &snapshot);
+            }
+            let _ = self.app.emit("model-download-progress", snapshot);
+        }
+    }
+}
+
+/// Descarga `file` en `dest`, reanudando `<dest>.part` si existe, y comprueba
+/// su tamaño y SHA-256
+pub(crate) async fn download_file(
+    client: &reqwest::Client,
+    file: &RemoteFile,
+    dest: &Path,
+    progress: &mut Progress<'_>,
+) -> Result<u64, String> {
+    if let Ok(meta) = fs::metadata(dest) {
+        if file.size.is_none_or(|size| size == meta.len()) {
+            return Ok(meta.len());
+        }
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando {}: {}", parent.display(), e))?;
+    }
+    let part = PathBuf::from(format!("{}{}", dest.display(), PART_SUFFIX));
+    let mut offset = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    if file.size.is_some_and(|size| offset > size) {
+        offset = 0;
+    }
+
+    let mut request = client.get(&file.url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Error descargando {}: {}", file.path.display(), e))?;
+    let status = response.status();
+    let complete = status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && offset > 0;
+    if !complete {
+        response = response
+            .error_for_status()
+            .map_err(|e| format!("Error descargando {}: {}", file.path.display(), e))?;
+        if status != reqwest::StatusCode::PARTIAL_CONTENT {
+            // El servidor no admite Range: se empieza de cero
+            offset = 0;
+        }
+    }
+    if offset > 0 {
+        tracing::info!(target: "downloads", "Reanudando {} desde {} bytes", file.path.display(), offset);
+    }
+
+    let mut hasher = Sha256::new();
+    let mut out = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(&part)
+        .map_err(|e| format!("Error abriendo {}: {}", part.display(), e))?;
+    if offset > 0 {
+        let mut existing = fs::File::open(&part)
+            .map_err(|e| format!("Error leyendo {}: {}", part.display(), e))?;
+        std::io::copy(&mut existing, &mut hasher).map_err(|e| e.to_string())?;
+    }
+
+    let mut written = offset;
+    progress.emit("downloading", written);
+    if !complete {
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Error descargando {}: {}", file.path.display(), e))?
+        {
+            out.write_all(&chunk)
+                .map_err(|e| format!("Error escribiendo {}: {}", part.display(), e))?;
+            hasher.update(&chunk);
+            written += chunk.len() as u64;
+            progress.fetched += chunk.len() as u64;
+            progress.emit("downloading", written);
+            progress.rate.consume(chunk.len()).await;
+        }
+    }
+    out.sync_all().map_err(|e| e.to_string())?;
+    drop(out);
+
+    let size_ok = file.size.is_none_or(|size| size == written);
+    let hash_ok = file
+        .sha256
+        .as_ref()
+        .is_none_or(|expected| *expected == to_hex(&hasher.finalize()));
+    if !size_ok || !hash_ok {
+        let _ = fs::remove_file(&part);
+        return Err(format!(
+            "{} se descargo danado: vuelva a intentarlo",
+            file.path.display()
+        ));
+    }
+    fs::rename(&part, dest).map_err(|e| format!("Error moviendo {}: {}", dest.display(), e))?;
+    Ok(written)
+}
+
+/// Descarga todos los ficheros en `dir`
+async fn download_all(
+    client: &reqwest::Client,
+    files: &[RemoteFile],
+    dir: &Path,
+    progress: &mut Progress<'_>,
+) -> Result<(), String> {
+    for file in files {
+        let written = download_file(client, file, &dir.join(&file.path), progress).await?;
+        progress.finished += written;
+    }
+    Ok(())
+}
+
+/// Escribe el marcador .version en el formato de core/model_manager.py
+pub(crate) fn write_version_marker(dir: &Path, spec: &ModelSpec) -> Result<(), String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (pinned_version, hf_revision) = match spec.kind {
+        ModelKind::Spacy => (spec.revision.clone(), None),
+        _ => (None, spec.revision.clone()),
+    };
+    let marker = serde_json::json!({
+        "model_name": spec.local_name.as_deref().unwrap_or(&spec.name),
+        "model_type": spec.subdirectory,
+        "pinned_version": pinned_version,
+        "hf_revision": hf_revision,
+        "actual_version": spec.revision,
+        "expected_sha256": spec.sha256,
+        "installed_at": iso_utc(now),
+    });
+    let json = serde_json::to_vec_pretty(&marker).map_err(|e| e.to_string())?;
+    fs::write(dir.join(VERSION_MARKER), json).map_err(|e| e.to_string())
+}
+
+/// Sustituye el modelo instalado por `staged`
+pub(crate) fn install(staged: &Path, target: &Path) -> Result<(), String> {
+    if target.exists() {
+        fs::remove_dir_all(target)
+            .map_err(|e| format!("Error borrando {}: {}", target.display(), e))?;
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando {}: {}", parent.display(), e))?;
+    }
+    fs::rename(staged, target)
+        .map_err(|e| format!("Error instalando en {}: {}", target.display(), e))
+}
+
+/// Extrae `<paquete>/<paquete>-<versión>/` de un .whl de spaCy en `dest`
+fn extract_spacy_wheel(wheel: &Path, package: &str, dest: &Path) -> Result<usize, String> {
+    let file = fs::File::open(wheel).map_err(|e| e.to_string())?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Paquete spaCy no valido: {}", e))?;
+    let base = format!("{}/", package);
+    // El paquete guarda el modelo en un subdirectorio versionado (es_core_news_lg-3.7.0/)
+    let prefix = archive
+        .file_names()
+        .find_map(|name| {
+            let rest = name.strip_prefix(&base)?;
+            let (dir, tail) = rest.split_once('/')?;
+            (tail == "config.cfg").then(|| format!("{}{}/", base, dir))
+        })
+        .unwrap_or(base);
+
+    let _ = fs::remove_dir_all(dest);
+    let mut extracted = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let Some(relative) = entry.name().strip_prefix(&prefix).and_then(safe_relative) else {
+            continue;
+        };
+        let target = dest.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = fs::File::create(&target).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        extracted += 1;
+    }
+    if extracted == 0 {
+        return Err(format!("El paquete {} no contiene el modelo", package));
+    }
+    Ok(extracted)
+}
+
+async fn download_spacy(
+    app: &AppHandle,
+    spec: &ModelSpec,
+    staging: &Path,
+    target: &Path,
+) -> Result<(), String> {
+    let version = spec
+        .revision
+        .as_deref()
+        .ok_or("El manifiesto no fija la version de spaCy")?;
+    let wheel = spacy_wheel(&spec.name, version, spec.sha256.as_deref());
+    let client = proxy::http_client(app).await?;
+    let mut progress = Progress::new(app, &spec.id, spec.size_mb * 1024 * 1024);
+    progress.emit("connecting", 0);
+    download_all(
+        &client,
+        std::slice::from_ref(&wheel),
+        staging,
+        &mut progress,
+    )
+    .await?;
+
+    progress.emit("installing", 0);
+    let wheel_path = staging.join(&wheel.path);
+    let extracted = staging.join("model");
+    let package = spec.name.clone();
+    let dest = extracted.clone();
+    let count = tauri::async_runtime::spawn_blocking(move || {
+        extract_spacy_wheel(&wheel_path, &package, &dest)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    write_version_marker(&extracted, spec)?;
+    install(&extracted, target)?;
+    tracing::info!(target: "downloads", "{} instalado en {} ({} ficheros)", spec.name, target.display(), count);
+    Ok(())
+}
+
+async fn download_huggingface(
+    app: &AppHandle,
+    spec: &ModelSpec,
+    staging: &Path,
+    target: &Path,
+) -> Result<(), String> {
+    let revision = spec.revision.as_deref().unwrap_or("main");
+    let client = proxy::http_client(app).await?;
+    let mut progress = Progress::new(app, &spec.id, spec.size_mb * 1024 * 1024);
+    progress.emit("connecting", 0);
+    let entries: Vec<TreeEntry> = client
+        .get(format!(
+            "{}/api/models/{}/tree/{}?recursive=true",
+            HF_BASE_URL, spec.name, revision
+        ))
+        .timeout(API_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Error consultando {}: {}", spec.name, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta no valida de HuggingFace: {}", e))?;
+    let files = hf_files(&spec.name, revision, entries);
+    if files.is_empty() {
+        return Err(format!("{} no tiene ficheros en {}", spec.name, revision));
+    }
+    progress.total = files.iter().filter_map(|f| f.size).sum();
+
+    let snapshot = staging.join("model");
+    download_all(&client, &files, &snapshot, &mut progress).await?;
+    progress.emit("installing", 0);
+    write_version_marker(&snapshot, spec)?;
+    install(&snapshot, target)?;
+    tracing::info!(target: "downloads", "{} instalado en {} ({} ficheros)", spec.name, target.display(), files.len());
+    Ok(())
+}
+
+pub(crate) fn staging_dir(root: &Path, spec: &ModelSpec) -> PathBuf {
+    root.join(DOWNLOADS_DIR).join(format!(
+        "{}-{}",
+        spec.id,
+        spec.revision.as_deref().unwrap_or("main")
+    ))
+}
+
+/// Prepara la reparación de un modelo HF: lleva el modelo instalado a
+/// `snapshot` sin los ficheros dañados, de modo que solo se descarguen esos
+fn reuse_installed(target: &Path, snapshot: &Path, broken: &[String]) -> Result<(), String> {
+    if !target.is_dir() {
+        return Ok(());
+    }
+    let _ = fs::remove_dir_all(snapshot);
+    if let Some(parent) = snapshot.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando {}: {}", parent.display(), e))?;
+    }
+    fs::rename(target, snapshot).map_err(|e| {
+        format!(
+            "Error preparando la reparacion de {}: {}",
+            target.display(),
+            e
+        )
+    })?;
+    for relative in broken {
+        if let Some(relative) = safe_relative(relative) {
+            let _ = fs::remove_file(snapshot.join(relative));
+        }
+    }
+    Ok(())
+}
+
+/// Quita el modelo de `DownloadsState` también si la descarga se interrumpe
+/// (`pause_download` descarta la descarga a medias)
+struct ActiveDownload<'a> {
+    state: &'a DownloadsState,
+    id: &'a str,
+}
+
+impl Drop for ActiveDownload<'_> {
+    fn drop(&mut self) {
+        self.state.0.lock().unwrap().remove(self.id);
+    }
+}
+
+/// Descarga e instala `spec`. Con `broken`, repara un modelo instalado: en los
+/// de HF solo se vuelven a descargar esos ficheros; en spaCy, el paquete entero.
+pub(crate) async fn download(
+    app: &AppHandle,
+    state: &DownloadsState,
+    spec: &ModelSpec,
+    broken: Option<&[String]>,
+) -> Result<PathBuf, String> {
+    let root = models_root(app).ok_or("No se pudo determinar el directorio home")?;
+    let target = spec
+        .install_dir(&root)
+        .ok_or_else(|| format!("{} se descarga con Ollama", spec.display_name))?;
+    {
+        let mut active = state.0.lock().unwrap();
+        if active.contains(EXCLUSIVE) {
+            return Err("Hay otra operacion en curso en el directorio de modelos".into());
+        }
+        if !active.insert(spec.id.clone()) {
+            return Err(format!("Ya se esta descargando {}", spec.display_name));
+        }
+    }
+    let active = ActiveDownload {
+        state,
+        id: &spec.id,
+    };
+
+    let staging = staging_dir(&root, spec);
+    tracing::info!(target: "downloads", "Descargando {} en {}", spec.name, target.display());
+    let result = match (spec.kind, broken) {
+        (ModelKind::Huggingface, Some(broken)) => {
+            match reuse_installed(&target, &staging.join("model"), broken) {
+                Ok(()) => download_huggingface(app, spec, &staging, &target).await,
+                Err(e) => Err(e),
+            }
+        }
+        (ModelKind::Huggingface, None) => download_huggingface(app, spec, &staging, &target).await,
+        (ModelKind::Spacy, _) => download_spacy(app, spec, &staging, &target).await,
+        (ModelKind::Ollama, _) => Err(format!("{} se descarga con Ollama", spec.display_name)),
+    };
+    drop(active);
+
+    let mut progress = Progress::new(app, &spec.id, 0);
+    match &result {
+        Ok(()) => {
+            let _ = fs::remove_dir_all(&staging);
+            // Sumas de lo instalado, para verify_models
+            let dir = target.clone();
+            match tauri::async_runtime::spawn_blocking(move || integrity::record(&dir)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::warn!(target: "downloads", "{}", e),
+                Err(e) => tracing::warn!(target: "downloads", "{}", e),
+            }
+            progress.emit("completed", 0);
+        }
+        Err(e) => {
+            // Los .part se conservan para reanudar
+            tracing::warn!(target: "downloads", "{}", e);
+            let _ = app.emit(
+                "model-download-progress",
+                progress.snapshot("error", 0, Some(e.clone())),
+            );
+        }
+    }
+    result.map(|()| target)
+}
+
+/// Descarga un modelo NLP del manifiesto (id: spacy, embeddings,
+/// transformer_ner...) y devuelve el directorio donde queda instalado. Pasa
+/// por la cola de descargas (download_queue.rs); una descarga interrumpida
+/// continúa donde se quedó.
+#[tauri::command]
+pub async fn download_model(app: AppHandle, id: String) -> Result<String, String> {
+    let spec = ModelManifest::bundled()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| format!("Modelo desconocido: {}", id))?;
+    download_queue::download(&app, &spec).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hf_tree_keeps_safe_backend_files_with_lfs_hashes() {
+        let entries: Vec<TreeEntry> = serde_json::from_str(
+            r#"[
+                {"type": "directory", "path": "1_Pooling", "size": 0},
+                {"type": "file", "path": "config.json", "size": 645},
+                {"type": "file", "path": "model.safetensors", "size": 135,
+                 "lfs": {"oid": "ABCDEF", "size": 470000000}},
+                {"type": "file", "path": "tf_model.h5", "size": 10},
+                {"type": "file", "path": "onnx/model.onnx", "size": 10},
+                {"type": "file", "path": "../fuera.txt", "size": 10}
+            ]"#,
+        )
+        .unwrap();
+        let files = hf_files("org/repo", "abc123", entries);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, PathBuf::from("config.json"));
+        assert_eq!(files[0].sha256, None);
+        assert_eq!(
+            files[1].url,
+            "https://huggingface.co/org/repo/resolve/abc123/model.safetensors"
+        );
+        assert_eq!(files[1].size, Some(470000000));
+        assert_eq!(files[1].sha256.as_deref(), Some("abcdef"));
+
+        let wheel = spacy_wheel("es_core_news_lg", "3.7.0", None);
+        assert!(wheel
+            .url
+            .ends_with("/es_core_news_lg-3.7.0/es_core_news_lg-3.7.0-py3-none-any.whl"));
+        assert_eq!(iso_utc(1_767_225_600), "2026-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn rate_limit_waits_until_the_cap_is_respected() {
+        let bytes_per_sec = 512 * 1024;
+        // 1 MB a 512 KB/s = 2 s; si ya ha pasado 1,5 s, faltan 0,5 s
+        assert_eq!(
+            rate_limit_delay(bytes_per_sec, 1024 * 1024, Duration::from_millis(1500)),
+            Duration::from_millis(500)
+        );
+        assert!(rate_limit_delay(bytes_per_sec, 1024, Duration::from_secs(1)).is_zero());
+    }
+
+    #[test]
+    fn spacy_wheel_is_extracted_from_versioned_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let wheel = dir.path().join("es_core_news_lg-3.7.0-py3-none-any.whl");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&wheel).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in [
+            "es_core_news_lg/__init__.py",
+            "es_core_news_lg/es_core_news_lg-3.7.0/config.cfg",
+            "es_core_news_lg/es_core_news_lg-3.7.0/ner/model",
+            "es_core_news_lg-3.7.0.dist-info/METADATA",
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let dest = dir.path().join("model");
+        assert_eq!(
+            extract_spacy_wheel(&wheel, "es_core_news_lg", &dest).unwrap(),
+            2
+        );
+        assert!(dest.join("config.cfg").is_file());
+        assert!(dest.join("ner/model").is_file());
+        assert!(!dest.join("__init__.py").exists());
+    }
+}
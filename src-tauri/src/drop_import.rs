@@ -0,0 +1,180 @@
+// Importación arrastrando archivos a la ventana en Narrative Assistant
+//
+// Tauri intercepta los archivos soltados sobre el WebView
+// (`WindowEvent::DragDrop`) con sus rutas reales, que el frontend no vería
+// con el drag and drop de HTML. Por cada archivo soltado:
+//
+// - Manuscrito (extensiones de `file_dialogs::MANUSCRIPT_EXTENSIONS`, hasta
+//   `MAX_IMPORT_BYTES`): se copia a `<datos>/documents`, el mismo directorio
+//   donde el backend guarda los manuscritos subidos, y se emite
+//   `import-file` a la ventana con la ruta copiada. El original puede estar
+//   en una memoria USB o en una carpeta sincronizada que desaparezca. Los
+//   `.txt` y `.md` que no están en UTF-8 se copian transcodificados
+//   (text_encoding.rs).
+// - Proyecto `.nra`: se abre como desde el sistema (file_association.rs).
+// - Cualquier otro: `import-file-rejected` con el motivo para mostrarlo.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager, Window};
+
+use crate::file_association;
+use crate::file_dialogs::MANUSCRIPT_EXTENSIONS;
+use crate::settings::{Settings, SettingsStore};
+use crate::text_encoding;
+
+/// Límite de tamaño (el mismo que aplica el backend a las subidas)
+const MAX_IMPORT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Manuscrito copiado listo para importar (payload de `import-file`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct StagedImport {
+    /// Copia en el directorio de documentos
+    pub path: String,
+    /// Ruta del archivo soltado
+    pub original_path: String,
+    /// Nombre del archivo soltado
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+/// Archivo rechazado (payload de `import-file-rejected`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct RejectedImport {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Directorio de manuscritos del backend (`config.data_dir / "documents"`)
+pub(crate) fn documents_dir(settings: &Settings) -> Option<PathBuf> {
+    let data_dir = match &settings.data_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()?.join(".narrative_assistant"),
+    };
+    Some(data_dir.join("documents"))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Valida el manuscrito y lo copia a `documents` con un prefijo único
+fn stage(path: &Path, documents: &Path) -> Result<StagedImport, String> {
+    let name = file_name(path);
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !MANUSCRIPT_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Formato no admitido. Use {}",
+            MANUSCRIPT_EXTENSIONS.join(", ")
+        ));
+    }
+    let meta = fs::metadata(path).map_err(|e| format!("No se pudo leer el archivo: {}", e))?;
+    if !meta.is_file() {
+        return Err("Solo se pueden importar archivos, no carpetas".into());
+    }
+    if meta.len() > MAX_IMPORT_BYTES {
+        return Err(format!(
+            "El archivo supera el limite de {} MB",
+            MAX_IMPORT_BYTES / (1024 * 1024)
+        ));
+    }
+    if meta.len() == 0 {
+        return Err("El archivo esta vacio".into());
+    }
+
+    fs::create_dir_all(documents)
+        .map_err(|e| format!("No se pudo crear {}: {}", documents.display(), e))?;
+    let staged = documents.join(format!("{}_{}", uuid::Uuid::new_v4().simple(), name));
+    let transcoded = if extension == "txt" || extension == "md" {
+        let bytes = fs::read(path).map_err(|e| format!("No se pudo leer el archivo: {}", e))?;
+        text_encoding::transcode(&bytes)
+    } else {
+        None
+    };
+    match transcoded {
+        Some((text, _)) => fs::write(&staged, text),
+        None => fs::copy(path, &staged).map(|_| ()),
+    }
+    .map_err(|e| format!("No se pudo copiar el archivo: {}", e))?;
+    Ok(StagedImport {
+        path: staged.to_string_lossy().into_owned(),
+        original_path: path.to_string_lossy().into_owned(),
+        name,
+        size_bytes: meta.len(),
+    })
+}
+
+/// Archivos soltados sobre `window` (desde `on_window_event`)
+pub fn on_drop(window: &Window, paths: &[PathBuf]) {
+    let (projects, manuscripts): (Vec<PathBuf>, Vec<PathBuf>) = paths
+        .iter()
+        .cloned()
+        .partition(|p| file_association::is_project_file(p));
+    file_association::handle_paths(window.app_handle(), &projects);
+    if manuscripts.is_empty() {
+        return;
+    }
+
+    let window = window.clone();
+    let documents = documents_dir(&window.state::<SettingsStore>().get());
+    tauri::async_runtime::spawn_blocking(move || {
+        for path in manuscripts {
+            let result = documents
+                .as_deref()
+                .ok_or_else(|| "No se encontro el directorio de datos".to_string())
+                .and_then(|documents| stage(&path, documents));
+            match result {
+                Ok(staged) => {
+                    tracing::info!(target: "drop_import", "{} copiado a {}", staged.original_path, staged.path);
+                    let _ = window.emit_to(window.label(), "import-file", staged);
+                }
+                Err(reason) => {
+                    tracing::info!(target: "drop_import", "{} rechazado: {}", path.display(), reason);
+                    let rejected = RejectedImport {
+                        name: file_name(&path),
+                        reason,
+                    };
+                    let _ = window.emit_to(window.label(), "import-file-rejected", rejected);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manuscripts_are_validated_and_copied_to_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let documents = dir.path().join("documents");
+        let novel = dir.path().join("Novela.DOCX");
+        fs::write(&novel, b"PK...").unwrap();
+
+        let staged = stage(&novel, &documents).unwrap();
+        assert_eq!(staged.name, "Novela.DOCX");
+        assert_eq!(staged.size_bytes, 5);
+        let copy = Path::new(&staged.path);
+        assert_eq!(copy.parent(), Some(documents.as_path()));
+        assert!(copy.to_string_lossy().ends_with("_Novela.DOCX"));
+        assert_eq!(fs::read(copy).unwrap(), b"PK...");
+
+        let image = dir.path().join("portada.png");
+        fs::write(&image, b"png").unwrap();
+        assert!(stage(&image, &documents)
+            .unwrap_err()
+            .starts_with("Formato no admitido"));
+        let empty = dir.path().join("vacio.txt");
+        fs::write(&empty, b"").unwrap();
+        assert!(stage(&empty, &documents).is_err());
+        assert!(stage(&dir.path().join("no-existe.md"), &documents).is_err());
+    }
+}
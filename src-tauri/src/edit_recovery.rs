@@ -0,0 +1,258 @@
+// Recuperación de textos sin guardar en Narrative Assistant
+//
+// Si el WebView se cuelga o el shell se cierra de golpe, se pierde lo que el
+// usuario estaba escribiendo en un editor (notas de una escena, definición de
+// un término...). Cada pocos segundos el frontend guarda con
+// `save_edit_buffer` el contenido de los editores abiertos en
+// `<datos de la app>/recovery/`, y lo borra con `clear_edit_buffer` al
+// guardarlo o descartarlo.
+//
+// Cada carga de página tiene su propio identificador de sesión. Al arrancar,
+// `list_recovered_edits` devuelve los textos de sesiones que ya no están vivas:
+// las de un WebView que se reinició tras colgarse o las de un cierre
+// inesperado de la app. Los de otras ventanas abiertas no se tocan. Al cerrar
+// una ventana con normalidad sus textos se descartan; pasados
+// `MAX_AGE_SECS` también.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+
+use crate::clock::now_secs;
+use crate::{cleanup, downloads, settings};
+
+/// Carpeta de textos dentro del directorio de datos de la app
+const RECOVERY_DIR_NAME: &str = "recovery";
+
+/// Antigüedad a partir de la cual un texto se descarta (7 días)
+const MAX_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Texto de un editor sin guardar
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecoveredEdit {
+    /// Editor dentro de la página, p. ej. "scene-notes:12:3"
+    pub id: String,
+    /// Carga de página que lo escribió
+    pub session: String,
+    pub window: String,
+    pub project_id: Option<i64>,
+    /// Descripción para el usuario ("Notas de la escena 3")
+    pub label: String,
+    pub content: String,
+    /// Segundos desde epoch
+    pub updated_at: u64,
+}
+
+/// Identifica un texto guardado
+#[derive(Deserialize, Clone, Debug)]
+pub struct EditKey {
+    pub session: String,
+    pub id: String,
+}
+
+/// Textos guardados y sesión viva de cada ventana, registrado con `.manage()`
+pub struct EditRecovery {
+    dir: PathBuf,
+    live: Mutex<HashMap<String, String>>,
+}
+
+impl EditRecovery {
+    /// Usa `recovery/` en el directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::new(cleanup::app_data_dir().join(RECOVERY_DIR_NAME))
+    }
+
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            live: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Archivo de un texto: hash de sesión e id, que pueden tener cualquier carácter
+    fn path(&self, session: &str, id: &str) -> PathBuf {
+        let digest = Sha256::new()
+            .chain_update(session)
+            .chain_update([0])
+            .chain_update(id)
+            .finalize();
+        let name = downloads::to_hex(&digest[..12]);
+        self.dir.join(format!("{}.json", name))
+    }
+
+    fn register(&self, window: &str, session: &str) {
+        self.live
+            .lock()
+            .unwrap()
+            .insert(window.to_string(), session.to_string());
+    }
+
+    fn save(&self, edit: RecoveredEdit) -> Result<(), String> {
+        self.register(&edit.window, &edit.session);
+        let path = self.path(&edit.session, &edit.id);
+        if edit.content.trim().is_empty() {
+            let _ = fs::remove_file(path);
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("No se pudo crear {}: {}", self.dir.display(), e))?;
+        let json = serde_json::to_vec(&edit).map_err(|e| e.to_string())?;
+        settings::write_atomic(&path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", path.display(), e))
+    }
+
+    fn clear(&self, session: &str, id: &str) {
+        let _ = fs::remove_file(self.path(session, id));
+    }
+
+    fn stored(&self) -> Vec<(PathBuf, RecoveredEdit)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let edit = fs::read(&path)
+                    .ok()
+                    .and_then(|raw| serde_json::from_slice(&raw).ok())?;
+                Some((path, edit))
+            })
+            .collect()
+    }
+
+    /// Textos de sesiones que ya no están vivas, el más reciente primero
+    fn recoverable(&self, window: &str, session: &str) -> Vec<RecoveredEdit> {
+        self.register(window, session);
+        let live: Vec<String> = self.live.lock().unwrap().values().cloned().collect();
+        let cutoff = now_secs().saturating_sub(MAX_AGE_SECS);
+        let mut edits: Vec<RecoveredEdit> = self
+            .stored()
+            .into_iter()
+            .filter_map(|(path, edit)| {
+                if edit.updated_at < cutoff {
+                    let _ = fs::remove_file(path);
+                    return None;
+                }
+                (!live.contains(&edit.session)).then_some(edit)
+            })
+            .collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.updated_at));
+        edits
+    }
+
+    /// La ventana se ha cerrado con normalidad: sus textos ya no hacen falta
+    fn end_window(&self, window: &str) {
+        let Some(session) = self.live.lock().unwrap().remove(window) else {
+            return;
+        };
+        for (path, edit) in self.stored() {
+            if edit.session == session {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Descarta los textos de una ventana cerrada (desde `WindowEvent::Destroyed`)
+pub fn release_window(app: &AppHandle, window: &str) {
+    app.state::<EditRecovery>().end_window(window);
+}
+
+/// Guarda el contenido de un editor sin guardar; vacío lo borra
+#[tauri::command]
+pub fn save_edit_buffer(
+    recovery: State<'_, EditRecovery>,
+    window: WebviewWindow,
+    session: String,
+    id: String,
+    label: String,
+    project_id: Option<i64>,
+    content: String,
+) -> Result<(), String> {
+    recovery.save(RecoveredEdit {
+        id,
+        session,
+        window: window.label().to_string(),
+        project_id,
+        label,
+        content,
+        updated_at: now_secs(),
+    })
+}
+
+/// Borra el texto de un editor ya guardado o descartado
+#[tauri::command]
+pub fn clear_edit_buffer(recovery: State<'_, EditRecovery>, session: String, id: String) {
+    recovery.clear(&session, &id);
+}
+
+/// Textos sin guardar de sesiones anteriores (tras un cierre inesperado)
+#[tauri::command]
+pub fn list_recovered_edits(
+    recovery: State<'_, EditRecovery>,
+    window: WebviewWindow,
+    session: String,
+) -> Vec<RecoveredEdit> {
+    recovery.recoverable(window.label(), &session)
+}
+
+/// Descarta textos recuperados
+#[tauri::command]
+pub fn discard_recovered_edits(recovery: State<'_, EditRecovery>, keys: Vec<EditKey>) {
+    for key in keys {
+        recovery.clear(&key.session, &key.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(window: &str, session: &str, id: &str, content: &str) -> RecoveredEdit {
+        RecoveredEdit {
+            id: id.into(),
+            session: session.into(),
+            window: window.into(),
+            project_id: Some(1),
+            label: "Notas".into(),
+            content: content.into(),
+            updated_at: now_secs(),
+        }
+    }
+
+    #[test]
+    fn only_edits_of_dead_sessions_are_recovered() {
+        let dir = tempfile::tempdir().unwrap();
+        let recovery = EditRecovery::new(dir.path().join(RECOVERY_DIR_NAME));
+        recovery
+            .save(edit("main", "s1", "scene:1", "Ana duda"))
+            .unwrap();
+        recovery.save(edit("main", "s1", "scene:2", "   ")).unwrap();
+        recovery
+            .save(edit("project-2", "p1", "term:7", "Vórtice"))
+            .unwrap();
+
+        // El WebView principal se reinicia tras colgarse: nueva sesión s2
+        let recovered = recovery.recoverable("main", "s2");
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].content, "Ana duda");
+
+        // Tras recuperarlo se descarta; la ventana 2 se cierra con normalidad
+        recovery.clear("s1", "scene:1");
+        recovery.end_window("project-2");
+        assert!(recovery.recoverable("main", "s2").is_empty());
+        assert!(recovery.stored().is_empty());
+
+        // Cierre inesperado de la app: nada está vivo en el siguiente arranque
+        recovery
+            .save(edit("main", "s2", "scene:1", "Luis"))
+            .unwrap();
+        let restarted = EditRecovery::new(dir.path().join(RECOVERY_DIR_NAME));
+        assert_eq!(restarted.recoverable("main", "s3")[0].content, "Luis");
+    }
+}
@@ -0,0 +1,223 @@
+// Carpetas de destino de las exportaciones en Narrative Assistant
+//
+// Los comandos de exportación (PDF, Word, glosario, imágenes, archivos de
+// proyecto, informes) escribían en cualquier ruta absoluta que les pasara el
+// frontend: el diálogo de guardar se abría en el webview, así que el shell no
+// podía distinguir una ruta elegida por el usuario de una inventada por un
+// frontend con un fallo (o comprometido) que sobrescribiera, p. ej., un
+// fichero de configuración del sistema.
+//
+// Ahora el diálogo lo abre el shell (`pick_export_path`) y concede permiso de
+// escritura sobre la carpeta elegida. Las concesiones se guardan en
+// export_grants.json (directorio de datos de la app; las `MAX_GRANTS` más
+// recientes) para no volver a pedirlas en cada arranque. Antes de escribir,
+// cada comando de exportación pasa la ruta por `ExportGrants::authorize`: tiene
+// que quedar dentro de una carpeta concedida (o de una subcarpeta) y no ser un
+// enlace simbólico. backend_proxy.rs aplica la misma comprobación a la ruta de
+// POST /api/projects/<id>/save-file, que escribe el backend.
+
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, State, WebviewWindow};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{cleanup, settings};
+
+/// Nombre del fichero de concesiones
+const GRANTS_FILE_NAME: &str = "export_grants.json";
+
+/// Carpetas concedidas que se conservan
+const MAX_GRANTS: usize = 50;
+
+/// Filtro del diálogo de guardar (mismo formato que `save()` de plugin-dialog)
+#[derive(Deserialize, Clone, Debug)]
+pub struct DialogFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+/// Carpetas en las que se puede exportar, registrado con `.manage()`
+pub struct ExportGrants {
+    path: PathBuf,
+    /// Rutas canónicas, la más reciente primero
+    dirs: Mutex<Vec<PathBuf>>,
+}
+
+impl ExportGrants {
+    /// Carga export_grants.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(GRANTS_FILE_NAME))
+    }
+
+    pub(crate) fn load(path: PathBuf) -> Self {
+        let dirs = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Vec<PathBuf>>(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            dirs: Mutex::new(dirs),
+        }
+    }
+
+    /// Concede la carpeta `dir` (el usuario la eligió en el diálogo)
+    pub(crate) fn grant(&self, dir: &Path) -> Result<(), String> {
+        let dir = fs::canonicalize(dir)
+            .map_err(|e| format!("No se pudo resolver {}: {}", dir.display(), e))?;
+        let dirs = {
+            let mut dirs = self.dirs.lock().unwrap();
+            dirs.retain(|granted| *granted != dir);
+            dirs.insert(0, dir);
+            dirs.truncate(MAX_GRANTS);
+            dirs.clone()
+        };
+        let json = serde_json::to_vec_pretty(&dirs).map_err(|e| e.to_string())?;
+        settings::write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))
+    }
+
+    /// Carpeta concedida más reciente (carpeta inicial del diálogo)
+    fn latest(&self) -> Option<PathBuf> {
+        self.dirs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|dir| dir.is_dir())
+            .cloned()
+    }
+
+    /// Comprueba que se puede escribir `path`: absoluta, dentro de una carpeta
+    /// concedida y sin ser un enlace simbólico
+    pub fn authorize(&self, path: &Path) -> Result<(), String> {
+        if !path.is_absolute() {
+            return Err(format!(
+                "La ruta de destino debe ser absoluta: {}",
+                path.display()
+            ));
+        }
+        let parent = path
+            .parent()
+            .and_then(|parent| fs::canonicalize(parent).ok())
+            .ok_or_else(|| format!("No existe la carpeta de destino de {}", path.display()))?;
+        if fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            return Err(format!(
+                "No se exporta a traves de enlaces simbolicos: {}",
+                path.display()
+            ));
+        }
+        if self
+            .dirs
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|dir| parent.starts_with(dir))
+        {
+            return Ok(());
+        }
+        tracing::warn!(
+            target: "export_scope",
+            "Exportacion rechazada fuera de las carpetas elegidas: {}",
+            path.display()
+        );
+        Err(format!(
+            "La carpeta {} no se ha elegido como destino de exportacion",
+            parent.display()
+        ))
+    }
+}
+
+/// Diálogo de guardar del shell: devuelve la ruta elegida (`None` si se
+/// cancela) y concede su carpeta a las exportaciones
+#[tauri::command]
+pub async fn pick_export_path(
+    app: AppHandle,
+    window: WebviewWindow,
+    grants: State<'_, ExportGrants>,
+    title: Option<String>,
+    default_path: Option<String>,
+    filters: Option<Vec<DialogFilter>>,
+) -> Result<Option<String>, String> {
+    let mut dialog = app.dialog().file().set_parent(&window);
+    if let Some(title) = title {
+        dialog = dialog.set_title(title);
+    }
+    for filter in filters.iter().flatten() {
+        let extensions: Vec<&str> = filter.extensions.iter().map(String::as_str).collect();
+        dialog = dialog.add_filter(&filter.name, &extensions);
+    }
+    let default_path = default_path.map(PathBuf::from);
+    let default_dir = default_path
+        .as_deref()
+        .and_then(Path::parent)
+        .filter(|dir| dir.is_absolute())
+        .map(Path::to_path_buf)
+        .or_else(|| grants.latest())
+        .or_else(dirs::document_dir);
+    if let Some(dir) = default_dir {
+        dialog = dialog.set_directory(dir);
+    }
+    if let Some(name) = default_path.as_deref().and_then(Path::file_name) {
+        dialog = dialog.set_file_name(name.to_string_lossy());
+    }
+
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file())
+        .await
+        .map_err(|e| format!("Error en el dialogo de archivos: {}", e))?;
+    let Some(path) = picked.and_then(|path| path.into_path().ok()) else {
+        return Ok(None);
+    };
+    if let Some(dir) = path.parent() {
+        grants.grant(dir)?;
+    }
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_granted_folders_can_be_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let exports = dir.path().join("exports");
+        let other = dir.path().join("other");
+        fs::create_dir_all(exports.join("capitulos")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let grants = ExportGrants::load(dir.path().join(GRANTS_FILE_NAME));
+
+        assert!(grants.authorize(&exports.join("informe.pdf")).is_err());
+        grants.grant(&exports).unwrap();
+        assert!(grants.authorize(&exports.join("informe.pdf")).is_ok());
+        assert!(grants
+            .authorize(&exports.join("capitulos").join("glosario.csv"))
+            .is_ok());
+        assert!(grants.authorize(&other.join("informe.pdf")).is_err());
+        // `..` no saca la ruta de la carpeta concedida sin que se note
+        assert!(grants
+            .authorize(&exports.join("..").join("other").join("informe.pdf"))
+            .is_err());
+        assert!(grants.authorize(Path::new("informe.pdf")).is_err());
+
+        // Las concesiones sobreviven a un reinicio
+        let reloaded = ExportGrants::load(dir.path().join(GRANTS_FILE_NAME));
+        assert!(reloaded.authorize(&exports.join("informe.pdf")).is_ok());
+        assert_eq!(reloaded.latest(), Some(fs::canonicalize(&exports).unwrap()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_in_granted_folders_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("fuera.txt");
+        fs::write(&target, b"").unwrap();
+        let exports = dir.path().join("exports");
+        fs::create_dir_all(&exports).unwrap();
+        std::os::unix::fs::symlink(&target, exports.join("informe.pdf")).unwrap();
+        let grants = ExportGrants::load(dir.path().join(GRANTS_FILE_NAME));
+        grants.grant(&exports).unwrap();
+
+        assert!(grants.authorize(&exports.join("informe.pdf")).is_err());
+    }
+}
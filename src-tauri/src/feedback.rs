@@ -0,0 +1,290 @@
+// Informe para soporte: un ZIP con todo lo necesario para diagnosticar un problema
+//
+// `create_feedback_bundle` reúne en un único fichero, listo para adjuntar a un
+// correo o una incidencia:
+//   - description.txt: lo que cuenta el usuario
+//   - diagnostics.json: versión, sesión de log, hardware, preferencias (sin
+//     datos identificativos), estado del backend, complementos y fallos
+//   - logs/: el final de shell.log y de los logs del backend y del frontend, y
+//     el registro de operaciones destructivas (audit.rs)
+//   - screenshot.png: la vista actual, si se pide
+//
+// El ZIP se guarda en Descargas (o en el home) y se muestra seleccionado en el
+// explorador de archivos. Nada se envía: el usuario decide a quién mandarlo.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use zip::write::SimpleFileOptions;
+
+use crate::logging::{self, LogState};
+use crate::settings::{Settings, SettingsStore};
+use crate::{audit, capture, crash, hardware, plugins};
+
+/// Bytes que se incluyen del final de cada log
+const MAX_LOG_TAIL_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Ficheros de log del shell que se incluyen (el actual, la última rotación y el registro de operaciones)
+const SHELL_LOG_FILES: [&str; 3] = ["shell.log", "shell.log.1", audit::AUDIT_FILE_NAME];
+
+/// Logs que escribe el backend Python en su propio directorio
+const BACKEND_LOG_FILES: [&str; 2] = ["backend-debug.log", "frontend.log"];
+
+/// Directorio de logs del backend (mismo criterio que api-server/main.py)
+fn backend_log_dir() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    Some(if cfg!(target_os = "windows") {
+        home.join("AppData")
+            .join("Local")
+            .join("Narrative Assistant")
+    } else if cfg!(target_os = "macos") {
+        home.join("Library")
+            .join("Logs")
+            .join("Narrative Assistant")
+    } else {
+        home.join(".local")
+            .join("share")
+            .join("narrative-assistant")
+    })
+}
+
+/// Últimos `max` bytes de `path` (un log largo solo interesa por el final)
+fn read_tail(path: &Path, max: u64) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max))).ok()?;
+    let mut bytes = Vec::new();
+    file.take(max).read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+/// Preferencias sin el id de telemetría ni el usuario del proxy
+fn redacted_settings(settings: &Settings) -> serde_json::Value {
+    let mut value = serde_json::to_value(settings).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("telemetry_id");
+        if let Some(proxy) = object.get_mut("proxy").and_then(|p| p.as_object_mut()) {
+            proxy.remove("username");
+        }
+    }
+    value
+}
+
+#[derive(Serialize)]
+struct Diagnostics {
+    app_version: &'static str,
+    /// Id de sesión del log: localiza este arranque en shell.log
+    log_session: String,
+    /// Segundos UNIX
+    created_at: u64,
+    hardware: Option<hardware::HardwareInfo>,
+    settings: serde_json::Value,
+    /// Respuesta de /api/health, o `None` si el backend no responde
+    backend_health: Option<serde_json::Value>,
+    plugins: plugins::PluginList,
+    crash_reports: usize,
+}
+
+async fn backend_health() -> Option<serde_json::Value> {
+    crate::backend_auth::backend_client()
+        .get(crate::backend_auth::backend_url(crate::BACKEND_HEALTH_PATH))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()
+}
+
+async fn collect_diagnostics(app: &AppHandle, created_at: u64) -> Diagnostics {
+    Diagnostics {
+        app_version: env!("CARGO_PKG_VERSION"),
+        log_session: app.state::<LogState>().session_id().to_string(),
+        created_at,
+        hardware: hardware::get_hardware_info(app.state()).await.ok(),
+        settings: redacted_settings(&app.state::<SettingsStore>().get()),
+        backend_health: backend_health().await,
+        plugins: plugins::list_plugins(app.state()),
+        crash_reports: crash::report_occurrences().len(),
+    }
+}
+
+/// Escribe el ZIP con las entradas (ruta dentro del ZIP, contenido)
+fn write_bundle(path: &Path, entries: &[(String, Vec<u8>)]) -> Result<(), String> {
+    let error = |e: zip::result::ZipError| format!("Error creando el informe: {}", e);
+    let file = File::create(path).map_err(|e| format!("Error creando el informe: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, contents) in entries {
+        zip.start_file(name.as_str(), options).map_err(error)?;
+        zip.write_all(contents)
+            .map_err(|e| format!("Error creando el informe: {}", e))?;
+    }
+    zip.finish().map_err(error)?;
+    Ok(())
+}
+
+/// Muestra `path` seleccionado en el explorador de archivos del sistema
+pub(crate) fn reveal_in_file_manager(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = {
+        use std::os::windows::process::CommandExt;
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path.display()))
+            .spawn()
+            .map(|_| ())
+    };
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .map(|_| ());
+    #[cfg(target_os = "linux")]
+    let result = {
+        // FileManager1 (Nautilus, Dolphin, Nemo...) selecciona el fichero; si no
+        // está disponible, se abre su carpeta
+        let shown = tauri::Url::from_file_path(path).ok().is_some_and(|uri| {
+            zbus::blocking::Connection::session()
+                .and_then(|connection| {
+                    connection.call_method(
+                        Some("org.freedesktop.FileManager1"),
+                        "/org/freedesktop/FileManager1",
+                        Some("org.freedesktop.FileManager1"),
+                        "ShowItems",
+                        &(vec![uri.as_str()], ""),
+                    )
+                })
+                .is_ok()
+        });
+        if shown {
+            Ok(())
+        } else {
+            std::process::Command::new("xdg-open")
+                .arg(path.parent().unwrap_or(path))
+                .spawn()
+                .map(|_| ())
+        }
+    };
+    result.map_err(|e| format!("No se pudo abrir el explorador de archivos: {}", e))
+}
+
+/// Crea el informe para soporte y lo muestra en el explorador. Devuelve la ruta del ZIP.
+#[tauri::command]
+pub async fn create_feedback_bundle(
+    app: AppHandle,
+    window: WebviewWindow,
+    description: String,
+    include_screenshot: bool,
+) -> Result<String, String> {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut entries = vec![(
+        "description.txt".to_string(),
+        description.trim().as_bytes().to_vec(),
+    )];
+
+    // La captura primero: el resto no cambia la vista
+    if include_screenshot {
+        match capture::snapshot_png(&window).await {
+            Ok(png) => entries.push(("screenshot.png".into(), png)),
+            Err(e) => tracing::warn!(target: "feedback", "Informe sin captura: {}", e),
+        }
+    }
+
+    let diagnostics = collect_diagnostics(&app, created_at).await;
+    entries.push((
+        "diagnostics.json".into(),
+        serde_json::to_vec_pretty(&diagnostics).map_err(|e| e.to_string())?,
+    ));
+
+    let log_files = logging::log_dir()
+        .into_iter()
+        .flat_map(|dir| SHELL_LOG_FILES.map(|name| dir.join(name)))
+        .chain(
+            backend_log_dir()
+                .into_iter()
+                .flat_map(|dir| BACKEND_LOG_FILES.map(|name| dir.join(name))),
+        );
+    for path in log_files {
+        if let (Some(name), Some(tail)) = (path.file_name(), read_tail(&path, MAX_LOG_TAIL_BYTES)) {
+            entries.push((format!("logs/{}", name.to_string_lossy()), tail));
+        }
+    }
+
+    let dir = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .ok_or("No se pudo determinar el directorio de descargas")?;
+    let path = dir.join(format!("narrative-assistant-informe-{}.zip", created_at));
+    let bundle_path = path.clone();
+    tauri::async_runtime::spawn_blocking(move || write_bundle(&bundle_path, &entries))
+        .await
+        .map_err(|e| format!("Error creando el informe: {}", e))??;
+    tracing::info!(target: "feedback", "Informe para soporte creado en {}", path.display());
+
+    if let Err(e) = reveal_in_file_manager(&path) {
+        tracing::warn!(target: "feedback", "{}", e);
+    }
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy;
+
+    #[test]
+    fn bundle_contains_entries_and_log_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("shell.log");
+        std::fs::write(&log, b"antiguo\nreciente\n").unwrap();
+        let tail = read_tail(&log, 9).unwrap();
+        assert_eq!(tail, b"reciente\n");
+        assert!(read_tail(&dir.path().join("no-existe.log"), 9).is_none());
+
+        let path = dir.path().join("informe.zip");
+        write_bundle(
+            &path,
+            &[
+                ("description.txt".into(), b"No abre el proyecto".to_vec()),
+                ("logs/shell.log".into(), tail),
+            ],
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut contents = String::new();
+        archive
+            .by_name("logs/shell.log")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "reciente\n");
+        assert_eq!(archive.len(), 2);
+    }
+
+    #[test]
+    fn settings_are_redacted() {
+        let settings = Settings {
+            telemetry_id: Some("install-id".into()),
+            proxy: proxy::ProxySettings {
+                username: Some("ana".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let value = redacted_settings(&settings);
+        assert!(value.get("telemetry_id").is_none());
+        assert!(value["proxy"].get("username").is_none());
+        assert_eq!(value["language"], "es");
+    }
+}
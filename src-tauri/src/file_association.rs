@@ -0,0 +1,182 @@
+// Asociación de archivos de Narrative Assistant
+//
+// Los instaladores registran la app para los proyectos `.nra`, los archivos
+// de proyecto `.nazip` (project_archive.rs) y como
+// "Abrir con" para manuscritos `.docx` (bundle.fileAssociations en
+// tauri.conf.json). El archivo llega de dos formas:
+//
+// - Windows/Linux: como argumento del proceso. Si la app ya estaba abierta,
+//   tauri-plugin-single-instance reenvía los argumentos a la instancia activa.
+// - macOS: como evento `RunEvent::Opened` (Apple Events `open-file`).
+//
+// En ambos casos se emite `open-file` a la ventana principal, que abre el
+// proyecto o crea uno nuevo con el manuscrito. Como los deep links, los
+// archivos recibidos antes de que el frontend esté montado quedan pendientes
+// hasta que los recoja `take_pending_open_files`.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Url};
+
+use crate::splash;
+use crate::tray;
+use crate::windows::MAIN_WINDOW;
+
+/// Tipo de archivo asociado
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenFileKind {
+    /// Proyecto guardado (.nra) o archivo de proyecto (.nazip)
+    Project,
+    /// Manuscrito para crear un proyecto nuevo (.docx)
+    Manuscript,
+}
+
+/// Archivo recibido del sistema
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct OpenFileRequest {
+    pub path: String,
+    pub kind: OpenFileKind,
+}
+
+/// Tipo según la extensión (las mismas que bundle.fileAssociations)
+fn classify(path: &Path) -> Option<OpenFileKind> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "nra" | "nazip" => Some(OpenFileKind::Project),
+        "docx" => Some(OpenFileKind::Manuscript),
+        _ => None,
+    }
+}
+
+/// Si `path` es un proyecto guardado (.nra o .nazip)
+pub fn is_project_file(path: &Path) -> bool {
+    classify(path) == Some(OpenFileKind::Project)
+}
+
+/// Archivos asociados entre los argumentos del proceso (sin el ejecutable ni las opciones).
+/// Las rutas relativas se resuelven contra `cwd`, el directorio de la instancia que las recibió.
+fn requests_from_args(argv: &[String], cwd: &Path) -> Vec<OpenFileRequest> {
+    argv.iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-') && !arg.contains("://"))
+        .filter_map(|arg| {
+            let path = cwd.join(arg);
+            let kind = classify(&path)?;
+            Some(OpenFileRequest {
+                path: path.to_string_lossy().into_owned(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Archivos recibidos que el frontend aún no ha recogido
+pub struct PendingOpenFiles(Mutex<Vec<OpenFileRequest>>);
+
+impl PendingOpenFiles {
+    pub fn new() -> Self {
+        Self(Mutex::new(Vec::new()))
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.0.lock().unwrap().is_empty()
+    }
+}
+
+fn dispatch(app: &AppHandle, requests: Vec<OpenFileRequest>) {
+    if requests.is_empty() {
+        return;
+    }
+    for request in &requests {
+        tracing::info!(target: "file_association", "Abriendo {} ({:?})", request.path, request.kind);
+    }
+    app.state::<PendingOpenFiles>()
+        .0
+        .lock()
+        .unwrap()
+        .extend(requests.iter().cloned());
+    // Con la ventana de carga aún visible, la principal se mostrará al terminar el arranque
+    if splash::is_revealed() {
+        tray::show_main_window(app);
+    }
+    let _ = app.emit_to(
+        EventTarget::webview_window(MAIN_WINDOW),
+        "open-file",
+        requests,
+    );
+}
+
+/// Procesa los argumentos de esta instancia o los reenviados por una segunda.
+/// Devuelve si había algún archivo asociado.
+pub fn handle_args(app: &AppHandle, argv: &[String], cwd: &Path) -> bool {
+    let requests = requests_from_args(argv, cwd);
+    let found = !requests.is_empty();
+    dispatch(app, requests);
+    found
+}
+
+/// Procesa rutas absolutas (archivos soltados sobre la ventana, drop_import.rs)
+pub fn handle_paths(app: &AppHandle, paths: &[PathBuf]) {
+    let requests = paths
+        .iter()
+        .filter_map(|path| {
+            Some(OpenFileRequest {
+                kind: classify(path)?,
+                path: path.to_string_lossy().into_owned(),
+            })
+        })
+        .collect();
+    dispatch(app, requests);
+}
+
+/// Procesa los archivos de `RunEvent::Opened` (macOS)
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    let paths: Vec<PathBuf> = urls
+        .iter()
+        .filter_map(|url| url.to_file_path().ok())
+        .collect();
+    handle_paths(app, &paths);
+}
+
+/// Archivos pendientes para la ventana principal (se consumen al leerlos)
+#[tauri::command]
+pub fn take_pending_open_files(state: tauri::State<'_, PendingOpenFiles>) -> Vec<OpenFileRequest> {
+    std::mem::take(&mut *state.0.lock().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_keep_only_associated_files() {
+        let cwd = Path::new("/home/ana/libros");
+        let argv: Vec<String> = [
+            "narrative-assistant",
+            "--flag",
+            "novela.DOCX",
+            "/tmp/proyecto.nra",
+            "notas.txt",
+            "/tmp/compartido.nazip",
+            "narrassist://project/1",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let requests = requests_from_args(&argv, cwd);
+
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].kind, OpenFileKind::Manuscript);
+        assert_eq!(
+            Path::new(&requests[0].path),
+            cwd.join("novela.DOCX").as_path()
+        );
+        assert_eq!(requests[1].kind, OpenFileKind::Project);
+        assert_eq!(requests[1].path, "/tmp/proyecto.nra");
+        assert_eq!(requests[2].kind, OpenFileKind::Project);
+    }
+}
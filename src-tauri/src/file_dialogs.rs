@@ -0,0 +1,197 @@
+// Diálogos nativos para abrir manuscritos y proyectos en Narrative Assistant
+//
+// El `<input type="file">` del WebView solo entrega el contenido y el nombre
+// del archivo: el backend recibe una copia subida y pierde la ruta original,
+// así que no puede volver a leer el manuscrito al reanalizar. Estos comandos
+// abren el diálogo del sistema con los filtros adecuados y devuelven rutas
+// absolutas, que el frontend pasa al backend como `file_path`.
+//
+// Cada acción recuerda la carpeta del último archivo elegido
+// (`settings.dialog_dirs`); la primera vez se abre en Documentos.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State, WebviewWindow};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::settings::{Settings, SettingsStore};
+
+/// Acción de un diálogo
+struct PickAction {
+    /// Clave en `settings.dialog_dirs`
+    key: &'static str,
+    title: &'static str,
+    filter_name: &'static str,
+    extensions: &'static [&'static str],
+    /// Filtros adicionales (nombre, extensiones)
+    extra_filters: &'static [(&'static str, &'static [&'static str])],
+}
+
+/// Formatos de manuscrito que importa el backend (parsers/base.py)
+pub(crate) const MANUSCRIPT_EXTENSIONS: &[&str] =
+    &["docx", "doc", "odt", "epub", "pdf", "txt", "md"];
+
+const MANUSCRIPT: PickAction = PickAction {
+    key: "manuscript",
+    title: "Seleccionar manuscrito",
+    filter_name: "Manuscritos",
+    extensions: MANUSCRIPT_EXTENSIONS,
+    // Los proyectos de Scrivener se aplanan antes de importarlos (scrivener.rs)
+    extra_filters: &[("Proyectos de Scrivener", &["scriv", "scrivx"])],
+};
+
+/// Proyecto guardado o archivo de proyecto (file_association.rs)
+const PROJECT: PickAction = PickAction {
+    key: "project",
+    title: "Abrir proyecto",
+    filter_name: "Proyecto Narrative Assistant",
+    extensions: &["nra", "nazip"],
+    extra_filters: &[],
+};
+
+/// Carpeta inicial: la última usada en la acción si sigue existiendo; si no, Documentos
+fn initial_dir(settings: &Settings, action: &str) -> Option<PathBuf> {
+    settings
+        .dialog_dirs
+        .get(action)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .or_else(dirs::document_dir)
+}
+
+/// Carpeta que se recuerda tras elegir `paths`
+fn remembered_dir(paths: &[PathBuf]) -> Option<&Path> {
+    paths.first()?.parent()
+}
+
+/// Abre el diálogo y recuerda la carpeta elegida. Vacío si se cancela.
+async fn pick(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    store: &SettingsStore,
+    action: &PickAction,
+    multiple: bool,
+) -> Result<Vec<String>, String> {
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_title(action.title)
+        .add_filter(action.filter_name, action.extensions)
+        .set_parent(window);
+    for (name, extensions) in action.extra_filters {
+        dialog = dialog.add_filter(*name, extensions);
+    }
+    if let Some(dir) = initial_dir(&store.get(), action.key) {
+        dialog = dialog.set_directory(dir);
+    }
+
+    let picked = tauri::async_runtime::spawn_blocking(move || {
+        if multiple {
+            dialog.blocking_pick_files()
+        } else {
+            dialog.blocking_pick_file().map(|path| vec![path])
+        }
+    })
+    .await
+    .map_err(|e| format!("Error en el dialogo de archivos: {}", e))?;
+
+    let paths: Vec<PathBuf> = picked
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| path.into_path().ok())
+        .collect();
+    if let Some(dir) = remembered_dir(&paths) {
+        store.update(serde_json::json!({
+            "dialog_dirs": { action.key: dir.to_string_lossy() }
+        }))?;
+    }
+    Ok(paths
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Elige uno o varios manuscritos (`multiple`). Devuelve rutas absolutas; vacío si se cancela.
+#[tauri::command]
+pub async fn pick_manuscript(
+    app: AppHandle,
+    window: WebviewWindow,
+    store: State<'_, SettingsStore>,
+    multiple: Option<bool>,
+) -> Result<Vec<String>, String> {
+    pick(
+        &app,
+        &window,
+        &store,
+        &MANUSCRIPT,
+        multiple.unwrap_or(false),
+    )
+    .await
+}
+
+/// Elige uno o varios proyectos `.nra`/`.nazip` (`multiple`). Vacío si se cancela.
+#[tauri::command]
+pub async fn pick_project(
+    app: AppHandle,
+    window: WebviewWindow,
+    store: State<'_, SettingsStore>,
+    multiple: Option<bool>,
+) -> Result<Vec<String>, String> {
+    pick(&app, &window, &store, &PROJECT, multiple.unwrap_or(false)).await
+}
+
+/// Elige una carpeta de capítulos (folder_import.rs). `None` si se cancela.
+#[tauri::command]
+pub async fn pick_chapter_folder(
+    app: AppHandle,
+    window: WebviewWindow,
+    store: State<'_, SettingsStore>,
+) -> Result<Option<String>, String> {
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_title("Seleccionar carpeta de capítulos")
+        .set_parent(&window);
+    if let Some(dir) = initial_dir(&store.get(), MANUSCRIPT.key) {
+        dialog = dialog.set_directory(dir);
+    }
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_folder())
+        .await
+        .map_err(|e| format!("Error en el dialogo de archivos: {}", e))?;
+    let Some(folder) = picked.and_then(|path| path.into_path().ok()) else {
+        return Ok(None);
+    };
+    if let Some(dir) = remembered_dir(std::slice::from_ref(&folder)) {
+        store.update(serde_json::json!({
+            "dialog_dirs": { MANUSCRIPT.key: dir.to_string_lossy() }
+        }))?;
+    }
+    Ok(Some(folder.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_folder_is_remembered_per_action_while_it_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings
+            .dialog_dirs
+            .insert(MANUSCRIPT.key.into(), dir.path().to_string_lossy().into());
+        settings
+            .dialog_dirs
+            .insert(PROJECT.key.into(), "/no/existe".into());
+
+        assert_eq!(
+            initial_dir(&settings, MANUSCRIPT.key),
+            Some(dir.path().to_path_buf())
+        );
+        // Carpeta borrada: se vuelve a Documentos
+        assert_eq!(initial_dir(&settings, PROJECT.key), dirs::document_dir());
+
+        let picked = [dir.path().join("novela.docx"), PathBuf::from("/otra/b.md")];
+        assert_eq!(remembered_dir(&picked), Some(dir.path()));
+        assert_eq!(remembered_dir(&[]), None);
+    }
+}
@@ -0,0 +1,476 @@
+// Importación de una carpeta de capítulos en Narrative Assistant
+//
+// Muchos autores guardan un archivo por capítulo ("01 - La llegada.docx",
+// "02 - El faro.docx"...). El backend importa un único documento por
+// proyecto, así que el shell junta la carpeta en un `.md` con un `# Título`
+// por archivo, en `<datos>/documents`, y el proyecto se crea desde él con un
+// solo trabajo de importación.
+//
+// - `plan_folder_import` recorre la carpeta (sin subcarpetas) y devuelve el
+//   plan para que el usuario lo confirme: archivos en orden, título y palabras
+//   de cada uno, y los que se omiten.
+// - `import_folder` rehace el plan con el mismo orden y escribe el `.md`.
+//
+// Orden (`FolderOrdering`): por defecto natural, con los números del nombre
+// como números ("Capítulo 2" antes de "Capítulo 10") y prólogo y epílogo en
+// su sitio; o alfabético, o por fecha de modificación.
+//
+// Título: el primer párrafo si ya es un título ("Capítulo 3", `# El faro`);
+// si no, el nombre del archivo sin la numeración ("01 - La llegada" → "La
+// llegada"). Se admiten TXT, MD (en cualquier codificación, text_encoding.rs)
+// y DOCX (manuscript_preview.rs).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+
+use crate::chapter_split::{self, Splitter};
+use crate::drop_import;
+use crate::manuscript_preview;
+use crate::settings::SettingsStore;
+
+/// Formatos de capítulo que se pueden juntar
+const CHAPTER_EXTENSIONS: &[&str] = &["docx", "txt", "md"];
+
+/// Nombres que van antes de los capítulos numerados
+const FRONT_MATTER: &[&str] = &[
+    "prologo",
+    "prólogo",
+    "prologue",
+    "prefacio",
+    "introduccion",
+    "introducción",
+];
+
+/// Nombres que van después
+const BACK_MATTER: &[&str] = &[
+    "epilogo",
+    "epílogo",
+    "epilogue",
+    "agradecimientos",
+    "apendice",
+    "apéndice",
+];
+
+/// Criterio de orden de los archivos
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderOrdering {
+    #[default]
+    Natural,
+    Name,
+    Modified,
+}
+
+/// Capítulo del plan
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlannedChapter {
+    pub path: String,
+    pub file_name: String,
+    pub title: String,
+    pub word_count: usize,
+}
+
+/// Plan de importación de una carpeta (respuesta de `plan_folder_import`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct FolderImportPlan {
+    pub folder: String,
+    /// Nombre de la carpeta, como nombre de proyecto sugerido
+    pub title: String,
+    pub chapters: Vec<PlannedChapter>,
+    /// Archivos omitidos, con el motivo
+    pub skipped: Vec<SkippedFile>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub file_name: String,
+    pub reason: String,
+}
+
+/// Manuscrito juntado (respuesta de `import_folder`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct StagedFolder {
+    pub path: String,
+    pub chapter_count: usize,
+    pub word_count: usize,
+}
+
+/// Trozo de un nombre para el orden natural: los números se comparan como números
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Chunk {
+    Number(u64),
+    Text(String),
+}
+
+fn natural_key(name: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut rest = name;
+    while let Some(c) = rest.chars().next() {
+        let is_digit = c.is_ascii_digit();
+        let end = rest
+            .find(|ch: char| ch.is_ascii_digit() != is_digit)
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(match chunk.parse() {
+            Ok(n) if is_digit => Chunk::Number(n),
+            _ => Chunk::Text(chunk.to_lowercase()),
+        });
+        rest = tail;
+    }
+    chunks
+}
+
+/// 0: prólogo y similares, 1: capítulos, 2: epílogo y similares
+fn matter_rank(stem: &str) -> u8 {
+    let first = stem
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|word| !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or("")
+        .to_lowercase();
+    if FRONT_MATTER.contains(&first.as_str()) {
+        0
+    } else if BACK_MATTER.contains(&first.as_str()) {
+        2
+    } else {
+        1
+    }
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Ordena los archivos según `ordering`
+fn sort_files(files: &mut [PathBuf], ordering: FolderOrdering) {
+    match ordering {
+        FolderOrdering::Natural => files.sort_by_cached_key(|path| {
+            let stem = stem(path);
+            (matter_rank(&stem), natural_key(&stem))
+        }),
+        FolderOrdering::Name => files.sort_by_cached_key(|path| stem(path).to_lowercase()),
+        FolderOrdering::Modified => files.sort_by_cached_key(|path| {
+            fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        }),
+    }
+}
+
+/// Título a partir del nombre: sin la numeración inicial ni guiones bajos
+fn title_from_stem(stem: &str) -> String {
+    let cleaned = stem.replace('_', " ");
+    let digits = cleaned
+        .trim_start()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .count();
+    let rest = cleaned.trim_start()[digits..]
+        .trim_start_matches(|c: char| c.is_whitespace() || "-.)–—".contains(c))
+        .trim();
+    if !rest.is_empty() && digits > 0 {
+        return capitalize(rest);
+    }
+    if rest.is_empty() && digits > 0 {
+        let number: u64 = cleaned.trim()[..digits].parse().unwrap_or(0);
+        return format!("Capítulo {}", number);
+    }
+    capitalize(cleaned.trim())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Capítulo leído: título propio (si el archivo empieza con uno) y cuerpo en Markdown
+struct ChapterText {
+    title: Option<String>,
+    body: String,
+}
+
+/// Párrafos de un `.txt`: bloques entre líneas en blanco o, si no las hay, líneas
+fn text_paragraphs(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    if lines.iter().any(|line| line.is_empty()) {
+        lines
+            .split(|line| line.is_empty())
+            .filter(|block| !block.is_empty())
+            .map(|block| block.join(" "))
+            .collect()
+    } else {
+        lines.into_iter().map(str::to_string).collect()
+    }
+}
+
+fn read_chapter(path: &Path, splitter: &Splitter) -> Result<ChapterText, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if extension == "md" {
+        let text = chapter_split::read_text(path)?;
+        let mut lines = text
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .peekable();
+        let title = lines
+            .peek()
+            .and_then(|line| chapter_split::markdown_heading(line.trim()))
+            .map(|(_, title)| title.to_string());
+        if title.is_some() {
+            lines.next();
+        }
+        let body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+        return Ok(ChapterText { title, body });
+    }
+    let mut paragraphs = if extension == "docx" {
+        manuscript_preview::docx_paragraphs(path)?
+    } else {
+        text_paragraphs(&chapter_split::read_text(path)?)
+    };
+    let title = match paragraphs.first() {
+        Some(first) if splitter.is_heading(first) => Some(paragraphs.remove(0)),
+        _ => None,
+    };
+    Ok(ChapterText {
+        title,
+        body: paragraphs.join("\n\n"),
+    })
+}
+
+/// Archivos de capítulo de `folder` ordenados y los omitidos
+fn scan(
+    folder: &Path,
+    ordering: FolderOrdering,
+) -> Result<(Vec<PathBuf>, Vec<SkippedFile>), String> {
+    let entries = fs::read_dir(folder)
+        .map_err(|e| format!("No se pudo leer la carpeta {}: {}", folder.display(), e))?;
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let name = file_name(&path);
+        // Ocultos y bloqueos de Word (`~$capitulo.docx`)
+        if name.starts_with('.') || name.starts_with("~$") || path.is_dir() {
+            continue;
+        }
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        if CHAPTER_EXTENSIONS.contains(&extension.as_str()) {
+            files.push(path);
+        } else {
+            skipped.push(SkippedFile {
+                file_name: name,
+                reason: format!(
+                    "Formato no admitido (use {})",
+                    CHAPTER_EXTENSIONS.join(", ")
+                ),
+            });
+        }
+    }
+    sort_files(&mut files, ordering);
+    skipped.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok((files, skipped))
+}
+
+/// Lee los capítulos en orden; los ilegibles o vacíos pasan a `skipped`
+fn read_folder(
+    folder: &Path,
+    ordering: FolderOrdering,
+    splitter: &Splitter,
+) -> Result<(FolderImportPlan, Vec<ChapterText>), String> {
+    let (files, mut skipped) = scan(folder, ordering)?;
+    let mut chapters = Vec::new();
+    let mut texts = Vec::new();
+    for path in files {
+        let name = file_name(&path);
+        match read_chapter(&path, splitter) {
+            Ok(text) if text.body.split_whitespace().next().is_none() => {
+                skipped.push(SkippedFile {
+                    file_name: name,
+                    reason: "No tiene texto".into(),
+                })
+            }
+            Ok(text) => {
+                chapters.push(PlannedChapter {
+                    path: path.to_string_lossy().into_owned(),
+                    title: text
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| title_from_stem(&stem(&path))),
+                    word_count: text.body.split_whitespace().count(),
+                    file_name: name,
+                });
+                texts.push(text);
+            }
+            Err(reason) => skipped.push(SkippedFile {
+                file_name: name,
+                reason,
+            }),
+        }
+    }
+    if chapters.is_empty() {
+        return Err("La carpeta no tiene capitulos que importar (TXT, MD o DOCX)".into());
+    }
+    let plan = FolderImportPlan {
+        folder: folder.to_string_lossy().into_owned(),
+        title: file_name(folder),
+        chapters,
+        skipped,
+    };
+    Ok((plan, texts))
+}
+
+/// Markdown del manuscrito completo
+fn join_chapters(plan: &FolderImportPlan, texts: &[ChapterText]) -> String {
+    let markdown = plan
+        .chapters
+        .iter()
+        .zip(texts)
+        .map(|(chapter, text)| format!("# {}\n\n{}", chapter.title, text.body))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    markdown + "\n"
+}
+
+/// Archivos de capítulo de `path` en orden, para confirmarlos antes de importar
+#[tauri::command]
+pub async fn plan_folder_import(
+    store: State<'_, SettingsStore>,
+    path: String,
+    ordering: Option<FolderOrdering>,
+) -> Result<FolderImportPlan, String> {
+    let splitter = Splitter::new(&store.get().chapter_split)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        read_folder(Path::new(&path), ordering.unwrap_or_default(), &splitter).map(|(plan, _)| plan)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de importacion: {}", e))?
+}
+
+/// Junta los capítulos de `path` en un `.md` del directorio de documentos
+#[tauri::command]
+pub async fn import_folder(
+    store: State<'_, SettingsStore>,
+    path: String,
+    ordering: Option<FolderOrdering>,
+) -> Result<StagedFolder, String> {
+    let settings = store.get();
+    let splitter = Splitter::new(&settings.chapter_split)?;
+    let documents = drop_import::documents_dir(&settings)
+        .ok_or_else(|| "No se encontro el directorio de datos".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let (plan, texts) = read_folder(Path::new(&path), ordering.unwrap_or_default(), &splitter)?;
+        let markdown = join_chapters(&plan, &texts);
+        fs::create_dir_all(&documents)
+            .map_err(|e| format!("No se pudo crear {}: {}", documents.display(), e))?;
+        let target = documents.join(format!(
+            "{}_{}.md",
+            uuid::Uuid::new_v4().simple(),
+            plan.title
+        ));
+        fs::write(&target, &markdown)
+            .map_err(|e| format!("No se pudo guardar el manuscrito: {}", e))?;
+        tracing::info!(
+            target: "folder_import",
+            "{}: {} capitulos ({} omitidos) en {}",
+            path,
+            plan.chapters.len(),
+            plan.skipped.len(),
+            target.display()
+        );
+        Ok(StagedFolder {
+            path: target.to_string_lossy().into_owned(),
+            chapter_count: plan.chapters.len(),
+            word_count: plan.chapters.iter().map(|c| c.word_count).sum(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de importacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chapter_split::ChapterSplitSettings;
+
+    #[test]
+    fn files_are_ordered_naturally_with_front_and_back_matter() {
+        let mut files: Vec<PathBuf> = [
+            "Capítulo 10.txt",
+            "Epílogo.md",
+            "Capítulo 2.txt",
+            "Prólogo.docx",
+            "Capítulo 1.txt",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        sort_files(&mut files, FolderOrdering::Natural);
+        let names: Vec<String> = files.iter().map(|p| file_name(p)).collect();
+        assert_eq!(
+            names,
+            [
+                "Prólogo.docx",
+                "Capítulo 1.txt",
+                "Capítulo 2.txt",
+                "Capítulo 10.txt",
+                "Epílogo.md"
+            ]
+        );
+        assert_eq!(title_from_stem("01 - la llegada"), "La llegada");
+        assert_eq!(title_from_stem("07"), "Capítulo 7");
+        assert_eq!(title_from_stem("el_faro"), "El faro");
+    }
+
+    #[test]
+    fn folder_is_joined_into_one_markdown_manuscript() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("Novela");
+        fs::create_dir(&folder).unwrap();
+        fs::write(
+            folder.join("02_el_faro.md"),
+            "# El faro\n\nLuis la esperaba.\n",
+        )
+        .unwrap();
+        fs::write(
+            folder.join("01 - la llegada.txt"),
+            "Ana llego.\n\nLlovia.\n",
+        )
+        .unwrap();
+        fs::write(folder.join("10.txt"), "Capítulo 10\nFin.\n").unwrap();
+        fs::write(folder.join("vacio.txt"), "  \n").unwrap();
+        fs::write(folder.join("portada.png"), b"png").unwrap();
+        fs::write(folder.join(".DS_Store"), b"").unwrap();
+
+        let splitter = Splitter::new(&ChapterSplitSettings::default()).unwrap();
+        let (plan, texts) = read_folder(&folder, FolderOrdering::Natural, &splitter).unwrap();
+        let titles: Vec<&str> = plan.chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["La llegada", "El faro", "Capítulo 10"]);
+        assert_eq!(plan.title, "Novela");
+        let skipped: Vec<&str> = plan.skipped.iter().map(|s| s.file_name.as_str()).collect();
+        assert_eq!(skipped, ["portada.png", "vacio.txt"]);
+        assert_eq!(
+            join_chapters(&plan, &texts),
+            "# La llegada\n\nAna llego.\n\nLlovia.\n\n# El faro\n\nLuis la esperaba.\n\n\
+             # Capítulo 10\n\nFin.\n"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+// Exportación del glosario a CSV y JSON en Narrative Assistant
+//
+// Los traductores cargan la terminología del autor en su herramienta de
+// traducción asistida (Trados MultiTerm, memoQ, OmegaT...), que importa
+// listas de términos en CSV o JSON. `export_glossary` lee el glosario del
+// proyecto (`project_glossary`, persistence/glossary.py) directamente de la
+// base de datos y lo escribe:
+//
+// - CSV: RFC 4180 (comillas dobles cuando hace falta, `""` dentro del valor,
+//   saltos de línea CRLF), UTF-8 con BOM para que Excel y las herramientas de
+//   Windows no lean las tildes como Windows-1252. Variantes y términos
+//   relacionados en una celda, separados por `; `.
+// - JSON: objeto con el proyecto, el idioma y la lista de términos.
+//
+// Se exportan todos los términos, en orden alfabético.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+use crate::export_scope::ExportGrants;
+use crate::{database, project_list, settings};
+
+/// Columnas del CSV
+const CSV_HEADER: &[&str] = &[
+    "termino",
+    "definicion",
+    "variantes",
+    "categoria",
+    "subcategoria",
+    "notas",
+    "relacionados",
+    "ejemplo",
+    "tecnico",
+    "inventado",
+    "nombre_propio",
+    "apariciones",
+    "primer_capitulo",
+];
+
+/// Separador de listas dentro de una celda
+const LIST_SEPARATOR: &str = "; ";
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GlossaryFormat {
+    Csv,
+    Json,
+}
+
+impl GlossaryFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Término del glosario
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct GlossaryTerm {
+    pub term: String,
+    pub definition: String,
+    pub variants: Vec<String>,
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub context_notes: String,
+    pub related_terms: Vec<String>,
+    pub usage_example: String,
+    pub is_technical: bool,
+    pub is_invented: bool,
+    pub is_proper_noun: bool,
+    pub usage_count: i64,
+    pub first_chapter: Option<i64>,
+}
+
+/// Documento JSON exportado
+#[derive(Serialize)]
+struct GlossaryDocument<'a> {
+    project_id: i64,
+    project_name: &'a str,
+    language: &'static str,
+    /// Segundos desde epoch
+    exported_at: u64,
+    terms: &'a [GlossaryTerm],
+}
+
+/// Resultado de `export_glossary`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct GlossaryExport {
+    pub path: String,
+    pub term_count: usize,
+}
+
+/// Lista JSON de la base de datos; una lista mal formada cuenta como vacía
+fn json_list(raw: Option<String>) -> Vec<String> {
+    raw.and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Nombre del proyecto y términos de su glosario
+fn read_glossary(
+    conn: &Connection,
+    project_id: i64,
+) -> Result<(String, Vec<GlossaryTerm>), String> {
+    let name: String = conn
+        .query_row(
+            "SELECT name FROM projects WHERE id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("No se encontro el proyecto {}: {}", project_id, e))?;
+    // La tabla la crea el backend la primera vez que se usa el glosario
+    if !project_list::has_table(conn, "project_glossary")? {
+        return Ok((name, Vec::new()));
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT term, definition, variants_json, category, subcategory, context_notes,
+                    related_terms_json, usage_example, is_technical, is_invented,
+                    is_proper_noun, usage_count, first_chapter
+             FROM project_glossary WHERE project_id = ?1
+             ORDER BY term COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Error leyendo el glosario: {}", e))?;
+    let terms = stmt
+        .query_map([project_id], |row| {
+            Ok(GlossaryTerm {
+                term: row.get(0)?,
+                definition: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                variants: json_list(row.get(2)?),
+                category: row
+                    .get::<_, Option<String>>(3)?
+                    .unwrap_or_else(|| "general".into()),
+                subcategory: row.get(4)?,
+                context_notes: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                related_terms: json_list(row.get(6)?),
+                usage_example: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                is_technical: row.get::<_, Option<bool>>(8)?.unwrap_or(false),
+                is_invented: row.get::<_, Option<bool>>(9)?.unwrap_or(false),
+                is_proper_noun: row.get::<_, Option<bool>>(10)?.unwrap_or(false),
+                usage_count: row.get::<_, Option<i64>>(11)?.unwrap_or(0),
+                first_chapter: row.get(12)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Error leyendo el glosario: {}", e))?;
+    Ok((name, terms))
+}
+
+/// Celda CSV con comillas si contiene separadores, comillas o saltos de línea
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) || value.starts_with(' ') || value.ends_with(' ') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let row: Vec<String> = fields.iter().map(|f| csv_field(f.as_ref())).collect();
+    row.join(",") + "\r\n"
+}
+
+fn yes_no(flag: bool) -> String {
+    if flag { "si" } else { "no" }.to_string()
+}
+
+fn to_csv(terms: &[GlossaryTerm]) -> Vec<u8> {
+    let mut csv = String::from("\u{FEFF}");
+    csv.push_str(&csv_row(CSV_HEADER));
+    for term in terms {
+        csv.push_str(&csv_row(&[
+            term.term.clone(),
+            term.definition.clone(),
+            term.variants.join(LIST_SEPARATOR),
+            term.category.clone(),
+            term.subcategory.clone().unwrap_or_default(),
+            term.context_notes.clone(),
+            term.related_terms.join(LIST_SEPARATOR),
+            term.usage_example.clone(),
+            yes_no(term.is_technical),
+            yes_no(term.is_invented),
+            yes_no(term.is_proper_noun),
+            term.usage_count.to_string(),
+            term.first_chapter
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+        ]));
+    }
+    csv.into_bytes()
+}
+
+fn to_json(project_id: i64, project_name: &str, terms: &[GlossaryTerm]) -> Result<Vec<u8>, String> {
+    let document = GlossaryDocument {
+        project_id,
+        project_name,
+        language: "es",
+        exported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        terms,
+    };
+    serde_json::to_vec_pretty(&document).map_err(|e| e.to_string())
+}
+
+/// Ruta absoluta con la extensión del formato, en una carpeta existente
+fn resolve_output_path(output_path: &str, format: GlossaryFormat) -> Result<PathBuf, String> {
+    let mut path = PathBuf::from(output_path.trim());
+    if !path.is_absolute() {
+        return Err("La ruta del archivo debe ser absoluta".into());
+    }
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(format.extension()))
+    {
+        path.set_extension(format.extension());
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(format!(
+            "No existe la carpeta de destino de {}",
+            path.display()
+        )),
+    }
+}
+
+fn export(
+    db: &Path,
+    project_id: i64,
+    path: &Path,
+    format: GlossaryFormat,
+) -> Result<usize, String> {
+    let conn = database::open_read_only(db)?;
+    let (name, terms) = read_glossary(&conn, project_id)?;
+    let bytes = match format {
+        GlossaryFormat::Csv => to_csv(&terms),
+        GlossaryFormat::Json => to_json(project_id, &name, &terms)?,
+    };
+    settings::write_atomic(path, &bytes)
+        .map_err(|e| format!("No se pudo guardar {}: {}", path.display(), e))?;
+    Ok(terms.len())
+}
+
+/// Escribe el glosario del proyecto en `path` como CSV o JSON
+#[tauri::command]
+pub async fn export_glossary(
+    grants: State<'_, ExportGrants>,
+    project_id: i64,
+    path: String,
+    format: GlossaryFormat,
+) -> Result<GlossaryExport, String> {
+    let output = resolve_output_path(&path, format)?;
+    grants.authorize(&output)?;
+    let db =
+        database::database_path().ok_or_else(|| "No se encontro la base de datos".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let term_count = export(&db, project_id, &output, format)?;
+        tracing::info!(
+            target: "glossary_export",
+            "{} terminos del proyecto {} exportados a {}",
+            term_count,
+            project_id,
+            output.display()
+        );
+        Ok(GlossaryExport {
+            path: output.to_string_lossy().into_owned(),
+            term_count,
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de exportacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glossary_is_written_as_quoted_csv_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("narrative.db");
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE projects (id INTEGER PRIMARY KEY, name TEXT);
+               INSERT INTO projects VALUES (1, 'El faro');
+               CREATE TABLE project_glossary (
+                   id INTEGER PRIMARY KEY, project_id INTEGER, term TEXT, definition TEXT,
+                   variants_json TEXT DEFAULT '[]', category TEXT DEFAULT 'general',
+                   subcategory TEXT, context_notes TEXT DEFAULT '',
+                   related_terms_json TEXT DEFAULT '[]', usage_example TEXT DEFAULT '',
+                   is_technical INTEGER DEFAULT 0, is_invented INTEGER DEFAULT 0,
+                   is_proper_noun INTEGER DEFAULT 0, usage_count INTEGER DEFAULT 0,
+                   first_chapter INTEGER);
+               INSERT INTO project_glossary (project_id, term, definition, variants_json,
+                   category, is_invented, usage_count, first_chapter)
+                   VALUES (1, 'vórtice', 'Puerta entre mundos, "abierta" de noche', '["vórtices","el Vórtice"]',
+                   'concepto', 1, 12, 3);
+               INSERT INTO project_glossary (project_id, term, definition, context_notes)
+                   VALUES (1, 'Árbol', 'Ciudad', 'Línea 1
+Línea 2');
+               INSERT INTO project_glossary (project_id, term, definition)
+                   VALUES (2, 'otro', 'De otro proyecto');"#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let csv_path = dir.path().join("glosario.csv");
+        assert_eq!(export(&db, 1, &csv_path, GlossaryFormat::Csv).unwrap(), 2);
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert!(lines[0].starts_with("\u{FEFF}termino,definicion,variantes,"));
+        assert_eq!(
+            &lines[1..],
+            [
+                // NOCASE solo pliega ASCII: "Á" va detrás de "v", como en la API
+                "vórtice,\"Puerta entre mundos, \"\"abierta\"\" de noche\",vórtices; el Vórtice,\
+                 concepto,,,,,no,si,no,12,3",
+                "Árbol,Ciudad,,general,,\"Línea 1\nLínea 2\",,,no,no,no,0,",
+                "",
+            ]
+        );
+
+        let json_path = dir.path().join("glosario.json");
+        export(&db, 1, &json_path, GlossaryFormat::Json).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["project_name"], "El faro");
+        assert_eq!(json["terms"][0]["variants"][1], "el Vórtice");
+        assert_eq!(json["terms"][1]["first_chapter"], serde_json::Value::Null);
+
+        assert_eq!(
+            resolve_output_path(
+                dir.path().join("glosario").to_str().unwrap(),
+                GlossaryFormat::Json
+            )
+            .unwrap(),
+            dir.path().join("glosario.json")
+        );
+    }
+}
@@ -0,0 +1,373 @@
+// Capacidad del hardware de Narrative Assistant
+//
+// `get_hardware_info` describe CPU, RAM, disco libre y GPU para que el
+// asistente de primer arranque recomiende modelos que el equipo pueda
+// mover, antes de que el backend (y torch) estén cargados.
+//
+// La GPU se detecta con las herramientas del sistema: `nvidia-smi` en
+// cualquier plataforma y, si no hay NVIDIA, `system_profiler` (macOS),
+// `Win32_VideoController` (Windows) o `lspci` + sysfs (Linux).
+//
+// El presupuesto para modelos LLM sigue a `detect_capacity` del backend
+// (core/device.py): la VRAM con CUDA, el 75 % de la memoria unificada con
+// Apple Metal y el 50 % de la RAM solo con CPU.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::settings::SettingsStore;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Presupuesto mínimo (GB) de cada nivel de calidad (LEVEL_MIN_BUDGET_GB en llm/config.py)
+const LEVEL_MIN_BUDGET_GB: &[(&str, f64)] = &[("experta", 20.0), ("completa", 12.0)];
+
+/// GPU detectada
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GpuInfo {
+    pub name: String,
+    /// Memoria dedicada (None si no se conoce o es compartida)
+    pub vram_mb: Option<u64>,
+    /// Memoria unificada con la CPU (Apple Silicon)
+    pub unified_memory: bool,
+    /// Usable con CUDA (NVIDIA con driver)
+    pub cuda: bool,
+}
+
+/// Respuesta de `get_hardware_info`
+#[derive(Serialize, Clone, Debug)]
+pub struct HardwareInfo {
+    pub os: String,
+    pub arch: String,
+    pub cpu_brand: String,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub total_ram_mb: u64,
+    pub available_ram_mb: u64,
+    /// Disco libre donde se guardan los datos y los modelos
+    pub free_disk_mb: Option<u64>,
+    pub data_dir: String,
+    pub gpus: Vec<GpuInfo>,
+    /// Memoria que pueden ocupar los modelos LLM
+    pub llm_budget_gb: f64,
+    /// Nivel de calidad recomendado: "rapida" | "completa" | "experta"
+    pub recommended_level: &'static str,
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `nvidia-smi --query-gpu=name,memory.total --format=csv,noheader,nounits`
+fn parse_nvidia_smi(output: &str) -> Vec<GpuInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, memory) = line.rsplit_once(',')?;
+            Some(GpuInfo {
+                name: name.trim().to_string(),
+                vram_mb: memory.trim().parse().ok(),
+                unified_memory: false,
+                cuda: true,
+            })
+        })
+        .filter(|gpu| !gpu.name.is_empty())
+        .collect()
+}
+
+/// Tamaños como los de system_profiler ("1536 MB", "8 GB")
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_size_mb(text: &str) -> Option<u64> {
+    let mut parts = text.split_whitespace();
+    let value: u64 = parts.next()?.parse().ok()?;
+    match parts.next()?.to_ascii_uppercase().as_str() {
+        "MB" => Some(value),
+        "GB" => Some(value * 1024),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_gpus() -> Vec<GpuInfo> {
+    let Some(json) = run("system_profiler", &["SPDisplaysDataType", "-json"]) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&json) else {
+        return Vec::new();
+    };
+    value["SPDisplaysDataType"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|gpu| {
+            let name = gpu["sppci_model"].as_str()?.to_string();
+            let unified = name.starts_with("Apple");
+            let vram_mb = gpu["spdisplays_vram"]
+                .as_str()
+                .and_then(parse_size_mb)
+                .filter(|_| !unified);
+            Some(GpuInfo {
+                name,
+                vram_mb,
+                unified_memory: unified,
+                cuda: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_gpus() -> Vec<GpuInfo> {
+    let script = "Get-CimInstance Win32_VideoController | Select-Object Name,AdapterRAM | ConvertTo-Json -Compress";
+    let Some(json) = run(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+    ) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json.trim()) else {
+        return Vec::new();
+    };
+    // Un solo adaptador llega como objeto, varios como lista
+    let adapters = match value {
+        serde_json::Value::Array(list) => list,
+        other => vec![other],
+    };
+    adapters
+        .iter()
+        .filter_map(|gpu| {
+            Some(GpuInfo {
+                name: gpu["Name"].as_str()?.trim().to_string(),
+                // AdapterRAM es de 32 bits: las GPU de más de 4 GB aparecen como 4 GB
+                vram_mb: gpu["AdapterRAM"].as_u64().map(|bytes| bytes / MIB),
+                unified_memory: false,
+                cuda: false,
+            })
+        })
+        .collect()
+}
+
+/// `lspci -mm`: `00:02.0 "VGA compatible controller" "Intel Corporation" "Iris Xe" ...`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_lspci(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (slot, rest) = line.split_once(' ')?;
+            let fields: Vec<&str> = rest.split('"').filter(|f| !f.trim().is_empty()).collect();
+            let class = fields.first()?;
+            if !(class.contains("VGA")
+                || class.contains("3D controller")
+                || class.contains("Display"))
+            {
+                return None;
+            }
+            let vendor = fields.get(1)?.replace(" Corporation", "");
+            let device = fields.get(2)?;
+            Some((slot.to_string(), format!("{} {}", vendor, device)))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_gpus() -> Vec<GpuInfo> {
+    let Some(output) = run("lspci", &["-mm"]) else {
+        return Vec::new();
+    };
+    parse_lspci(&output)
+        .into_iter()
+        .map(|(slot, name)| {
+            // amdgpu publica la VRAM en sysfs
+            let vram_mb = std::fs::read_to_string(format!(
+                "/sys/bus/pci/devices/0000:{}/mem_info_vram_total",
+                slot
+            ))
+            .ok()
+            .and_then(|bytes| bytes.trim().parse::<u64>().ok())
+            .map(|bytes| bytes / MIB);
+            GpuInfo {
+                name,
+                vram_mb,
+                unified_memory: false,
+                cuda: false,
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn platform_gpus() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+fn detect_gpus() -> Vec<GpuInfo> {
+    let nvidia = run(
+        "nvidia-smi",
+        &[
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ],
+    )
+    .map(|output| parse_nvidia_smi(&output))
+    .unwrap_or_default();
+    if nvidia.is_empty() {
+        platform_gpus()
+    } else {
+        nvidia
+    }
+}
+
+/// Presupuesto LLM (GB) como `detect_capacity` del backend
+fn llm_budget_gb(total_ram_mb: u64, gpus: &[GpuInfo]) -> f64 {
+    let ram_gb = total_ram_mb as f64 / 1024.0;
+    let cuda_vram = gpus
+        .iter()
+        .filter(|gpu| gpu.cuda)
+        .filter_map(|gpu| gpu.vram_mb)
+        .max();
+    if let Some(vram_mb) = cuda_vram {
+        vram_mb as f64 / 1024.0
+    } else if gpus.iter().any(|gpu| gpu.unified_memory) {
+        ram_gb * 0.75
+    } else {
+        ram_gb * 0.5
+    }
+}
+
+fn recommended_level(budget_gb: f64) -> &'static str {
+    LEVEL_MIN_BUDGET_GB
+        .iter()
+        .find(|(_, min)| budget_gb >= *min)
+        .map(|(level, _)| *level)
+        .unwrap_or("rapida")
+}
+
+/// Espacio libre del disco que contiene `path` (el punto de montaje más largo que lo contiene)
+pub(crate) fn free_disk_mb(path: &Path) -> Option<u64> {
+    let path = path
+        .ancestors()
+        .find_map(|p| p.canonicalize().ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space() / MIB)
+}
+
+fn collect(data_dir: PathBuf) -> HardwareInfo {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    system.refresh_cpu_list(sysinfo::CpuRefreshKind::nothing());
+    let total_ram_mb = system.total_memory() / MIB;
+    let gpus = detect_gpus();
+    let budget = llm_budget_gb(total_ram_mb, &gpus);
+
+    HardwareInfo {
+        os: sysinfo::System::long_os_version().unwrap_or_else(|| std::env::consts::OS.into()),
+        arch: std::env::consts::ARCH.into(),
+        cpu_brand: system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .unwrap_or_default(),
+        physical_cores: sysinfo::System::physical_core_count(),
+        logical_cores: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        total_ram_mb,
+        available_ram_mb: system.available_memory() / MIB,
+        free_disk_mb: free_disk_mb(&data_dir),
+        data_dir: data_dir.to_string_lossy().into_owned(),
+        gpus,
+        llm_budget_gb: (budget * 10.0).round() / 10.0,
+        recommended_level: recommended_level(budget),
+    }
+}
+
+/// Detecta el hardware sin bloquear el hilo de IPC
+pub(crate) async fn detect(store: &SettingsStore) -> Result<HardwareInfo, String> {
+    // Los modelos se guardan en el directorio de datos del backend
+    let data_dir = match store.get().data_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()
+            .ok_or("No se pudo determinar el directorio home")?
+            .join(".narrative_assistant"),
+    };
+    tauri::async_runtime::spawn_blocking(move || collect(data_dir))
+        .await
+        .map_err(|e| format!("Error detectando el hardware: {}", e))
+}
+
+/// Capacidades del equipo para el asistente de primer arranque
+#[tauri::command]
+pub async fn get_hardware_info(
+    store: tauri::State<'_, SettingsStore>,
+) -> Result<HardwareInfo, String> {
+    detect(&store).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpu_tool_output_is_parsed() {
+        let nvidia = parse_nvidia_smi("NVIDIA GeForce RTX 3060, 12288\nNVIDIA RTX A2000, Xyz\n");
+        assert_eq!(nvidia.len(), 2);
+        assert_eq!(nvidia[0].name, "NVIDIA GeForce RTX 3060");
+        assert_eq!(nvidia[0].vram_mb, Some(12288));
+        assert_eq!(nvidia[1].vram_mb, None);
+
+        let lspci = parse_lspci(concat!(
+            "00:02.0 \"VGA compatible controller\" \"Intel Corporation\" \"Alder Lake-P GT2 [Iris Xe Graphics]\" -r0c \"Lenovo\" \"Device 3b20\"\n",
+            "00:14.0 \"USB controller\" \"Intel Corporation\" \"Alder Lake PCH USB 3.2\" -r01 \"Lenovo\" \"Device 3b20\"\n",
+        ));
+        assert_eq!(
+            lspci,
+            vec![(
+                "00:02.0".to_string(),
+                "Intel Alder Lake-P GT2 [Iris Xe Graphics]".to_string()
+            )]
+        );
+        assert_eq!(parse_size_mb("1536 MB"), Some(1536));
+        assert_eq!(parse_size_mb("8 GB"), Some(8192));
+    }
+
+    #[test]
+    fn budget_follows_backend_capacity_rules() {
+        let gpu = |vram_mb, unified_memory, cuda| GpuInfo {
+            name: "GPU".into(),
+            vram_mb,
+            unified_memory,
+            cuda,
+        };
+        // CUDA: manda la VRAM
+        let budget = llm_budget_gb(32 * 1024, &[gpu(Some(12 * 1024), false, true)]);
+        assert_eq!(budget, 12.0);
+        assert_eq!(recommended_level(budget), "completa");
+        // Apple Silicon: 75 % de la memoria unificada
+        let budget = llm_budget_gb(32 * 1024, &[gpu(None, true, false)]);
+        assert_eq!(budget, 24.0);
+        assert_eq!(recommended_level(budget), "experta");
+        // Solo CPU (la VRAM de una GPU no CUDA no cuenta)
+        let budget = llm_budget_gb(16 * 1024, &[gpu(Some(4096), false, false)]);
+        assert_eq!(budget, 8.0);
+        assert_eq!(recommended_level(budget), "rapida");
+    }
+}
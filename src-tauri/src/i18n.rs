@@ -0,0 +1,434 @@
+// Textos del shell en el idioma de la interfaz
+//
+// Los textos fijos (menú, paleta de comandos) son un `Text` con su versión en
+// cada idioma. Los mensajes de eventos y errores se identifican con una clave
+// ("backend.restarting") y parámetros, y su texto está en `MESSAGES` con
+// marcadores `{nombre}`.
+//
+// Los eventos llevan `message_key` y `message_params` para que el frontend los
+// traduzca con su propio catálogo, y `message` ya resuelto en el idioma de
+// `Settings::language` para mostrarlo tal cual si no tiene traducción. Los
+// errores de los comandos se devuelven resueltos (siguen siendo `String`).
+
+use std::fmt::Display;
+
+use tauri::{AppHandle, Manager};
+
+use crate::settings::SettingsStore;
+
+/// Cadena traducida (español por defecto)
+#[derive(Clone, Copy, Debug)]
+pub struct Text {
+    pub es: &'static str,
+    pub en: &'static str,
+}
+
+impl Text {
+    /// Texto para el idioma de la interfaz (`Settings::language`)
+    pub fn get(&self, language: &str) -> &'static str {
+        if language.starts_with("en") {
+            self.en
+        } else {
+            self.es
+        }
+    }
+}
+
+pub const fn text(es: &'static str, en: &'static str) -> Text {
+    Text { es, en }
+}
+
+/// Catálogo de mensajes: clave -> texto con marcadores `{nombre}`
+const MESSAGES: &[(&str, Text)] = &[
+    // Backend (main.rs)
+    (
+        "backend.starting",
+        text("Iniciando servidor...", "Starting server..."),
+    ),
+    (
+        "backend.loading_modules",
+        text(
+            "Servidor iniciado, cargando módulos...",
+            "Server started, loading modules...",
+        ),
+    ),
+    (
+        "backend.started",
+        text(
+            "Servidor iniciado correctamente",
+            "Server started successfully",
+        ),
+    ),
+    (
+        "backend.start_error",
+        text(
+            "Error iniciando servidor: {error}",
+            "Error starting the server: {error}",
+        ),
+    ),
+    (
+        "backend.locked",
+        text(
+            "Base de datos bloqueada: introduce la contraseña para continuar",
+            "Database locked: enter the password to continue",
+        ),
+    ),
+    (
+        "backend.maintenance",
+        text(
+            "Mantenimiento de datos en curso...",
+            "Data maintenance in progress...",
+        ),
+    ),
+    (
+        "backend.restarting",
+        text(
+            "El servidor se detuvo, reiniciando...",
+            "The server stopped, restarting...",
+        ),
+    ),
+    (
+        "backend.restarted",
+        text(
+            "Servidor reiniciado correctamente",
+            "Server restarted successfully",
+        ),
+    ),
+    (
+        "backend.restart_error",
+        text(
+            "Error reiniciando servidor: {error}",
+            "Error restarting the server: {error}",
+        ),
+    ),
+    (
+        "backend.restart_failed",
+        text(
+            "El servidor se detuvo y no pudo reiniciarse. Reinicia la aplicación.",
+            "The server stopped and could not be restarted. Restart the application.",
+        ),
+    ),
+    // Gestión de datos (cleanup.rs)
+    (
+        "cleanup.category.webview_cache",
+        text("Caché del WebView", "WebView cache"),
+    ),
+    (
+        "cleanup.category.webview_cache.description",
+        text(
+            "Cache de paginas y shaders (se regenera automaticamente)",
+            "Page and shader cache (rebuilt automatically)",
+        ),
+    ),
+    (
+        "cleanup.category.app_settings",
+        text("Configuración", "Settings"),
+    ),
+    (
+        "cleanup.category.app_settings.description",
+        text(
+            "Preferencias, estado de ventanas y ajustes guardados",
+            "Preferences, window state and saved settings",
+        ),
+    ),
+    (
+        "cleanup.category.user_data",
+        text("Proyectos y base de datos", "Projects and database"),
+    ),
+    (
+        "cleanup.category.user_data.description",
+        text(
+            "Proyectos, anotaciones, historial de cambios",
+            "Projects, annotations, change history",
+        ),
+    ),
+    (
+        "cleanup.category.snapshots",
+        text("Copias de seguridad automaticas", "Automatic backups"),
+    ),
+    (
+        "cleanup.category.snapshots.description",
+        text(
+            "Instantaneas periodicas de la base de datos de proyectos",
+            "Periodic snapshots of the projects database",
+        ),
+    ),
+    ("cleanup.category.models", text("Modelos NLP", "NLP models")),
+    (
+        "cleanup.category.models.description",
+        text(
+            "spaCy, sentence-transformers (se pueden volver a descargar)",
+            "spaCy, sentence-transformers (can be downloaded again)",
+        ),
+    ),
+    (
+        "cleanup.category.ollama",
+        text("Ollama (compartido)", "Ollama (shared)"),
+    ),
+    (
+        "cleanup.category.ollama.description",
+        text(
+            "Modelos LLM - compartido con otras aplicaciones",
+            "LLM models - shared with other applications",
+        ),
+    ),
+    (
+        "cleanup.category.huggingface",
+        text("HuggingFace (compartido)", "HuggingFace (shared)"),
+    ),
+    (
+        "cleanup.category.huggingface.description",
+        text(
+            "Cache de modelos - compartido con otras aplicaciones",
+            "Model cache - shared with other applications",
+        ),
+    ),
+    (
+        "cleanup.secure_wipe_notice",
+        text(
+            "Sobrescribe el contenido de los ficheros antes de borrarlos. \
+             En discos SSD, sistemas de ficheros con copy-on-write (APFS, Btrfs) o carpetas \
+             sincronizadas (OneDrive, iCloud, Time Machine) pueden quedar copias que no se \
+             sobrescriben: para una garantía completa use el cifrado de disco del sistema.",
+            "Overwrites file contents before deleting them. \
+             On SSDs, copy-on-write file systems (APFS, Btrfs) or synced folders \
+             (OneDrive, iCloud, Time Machine) copies may remain that are not overwritten: \
+             for full assurance use the system's disk encryption.",
+        ),
+    ),
+    (
+        "cleanup.stopping_backend",
+        text("Deteniendo el servidor...", "Stopping the server..."),
+    ),
+    (
+        "cleanup.deleting",
+        text("Eliminando datos...", "Deleting data..."),
+    ),
+    (
+        "cleanup.wiping",
+        text(
+            "Sobrescribiendo y eliminando datos...",
+            "Overwriting and deleting data...",
+        ),
+    ),
+    (
+        "cleanup.restarting_backend",
+        text("Reiniciando el servidor...", "Restarting the server..."),
+    ),
+    (
+        "cleanup.deleted.webview_cache",
+        text("Caché del WebView eliminada", "WebView cache deleted"),
+    ),
+    (
+        "cleanup.deleted.app_settings",
+        text("Configuración eliminada", "Settings deleted"),
+    ),
+    (
+        "cleanup.deleted.user_data",
+        text(
+            "Proyectos y base de datos eliminados",
+            "Projects and database deleted",
+        ),
+    ),
+    (
+        "cleanup.wiped.user_data",
+        text(
+            "Proyectos y base de datos sobrescritos y eliminados",
+            "Projects and database overwritten and deleted",
+        ),
+    ),
+    (
+        "cleanup.deleted.snapshots",
+        text("Copias de seguridad eliminadas", "Backups deleted"),
+    ),
+    (
+        "cleanup.deleted.models",
+        text("Modelos NLP eliminados", "NLP models deleted"),
+    ),
+    (
+        "cleanup.error.no_home",
+        text(
+            "No se pudo determinar el directorio home",
+            "Could not determine the home directory",
+        ),
+    ),
+    (
+        "cleanup.error.shared",
+        text(
+            "Los directorios compartidos no se pueden eliminar automaticamente. \
+             Eliminelos manualmente si no los utiliza con otras aplicaciones.",
+            "Shared directories cannot be deleted automatically. \
+             Delete them manually if no other application uses them.",
+        ),
+    ),
+    (
+        "cleanup.error.secure_wipe_unsupported",
+        text(
+            "Esta categoría no admite borrado seguro",
+            "This category does not support secure deletion",
+        ),
+    ),
+    (
+        "cleanup.error.webview_cache",
+        text(
+            "No se pudo vaciar parte de la caché (puede estar en uso): {details}",
+            "Part of the cache could not be cleared (it may be in use): {details}",
+        ),
+    ),
+    (
+        "cleanup.error.user_data",
+        text(
+            "No se pudieron eliminar algunos datos: {details}",
+            "Some data could not be deleted: {details}",
+        ),
+    ),
+    (
+        "cleanup.error.delete",
+        text(
+            "Error eliminando {path}: {error}",
+            "Error deleting {path}: {error}",
+        ),
+    ),
+    (
+        "cleanup.error.delete_task",
+        text(
+            "Error en la tarea de eliminacion: {error}",
+            "Error in the deletion task: {error}",
+        ),
+    ),
+    (
+        "cleanup.error.confirmation_required",
+        text(
+            "Esta eliminacion requiere confirmacion previa",
+            "This deletion requires confirmation first",
+        ),
+    ),
+    (
+        "cleanup.error.confirmation_expired",
+        text(
+            "La confirmacion ha caducado, vuelva a intentarlo",
+            "The confirmation has expired, please try again",
+        ),
+    ),
+    (
+        "cleanup.error.confirmation_invalid",
+        text(
+            "Token de confirmacion invalido",
+            "Invalid confirmation token",
+        ),
+    ),
+];
+
+/// Texto de `key` en el catálogo
+fn lookup(key: &str) -> Option<Text> {
+    MESSAGES
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, text)| *text)
+}
+
+/// Mensaje localizable: clave del catálogo y valores de sus marcadores
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub key: &'static str,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            params: Vec::new(),
+        }
+    }
+
+    /// Añade el valor del marcador `{name}`
+    pub fn with(mut self, name: &'static str, value: impl Display) -> Self {
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// Texto en `language` con los marcadores sustituidos (la clave si no está en el catálogo)
+    pub fn render(&self, language: &str) -> String {
+        let Some(text) = lookup(self.key) else {
+            return self.key.to_string();
+        };
+        self.params
+            .iter()
+            .fold(text.get(language).to_string(), |message, (name, value)| {
+                message.replace(&format!("{{{}}}", name), value)
+            })
+    }
+
+    /// Campos `message`, `message_key` y `message_params` de un evento
+    pub fn to_json(&self, language: &str) -> serde_json::Map<String, serde_json::Value> {
+        let params: serde_json::Map<String, serde_json::Value> = self
+            .params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone().into()))
+            .collect();
+        let mut fields = serde_json::Map::new();
+        fields.insert("message".into(), self.render(language).into());
+        fields.insert("message_key".into(), self.key.into());
+        fields.insert("message_params".into(), params.into());
+        fields
+    }
+}
+
+/// Idioma de la interfaz
+pub fn language(app: &AppHandle) -> String {
+    app.try_state::<SettingsStore>()
+        .map(|store| store.get().language)
+        .unwrap_or_else(|| "es".into())
+}
+
+/// Payload del estado del backend: `{ status, message, message_key, message_params }`
+pub fn status_payload(app: &AppHandle, status: &str, message: Message) -> serde_json::Value {
+    let mut payload = message.to_json(&language(app));
+    payload.insert("status".into(), status.into());
+    payload.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn messages_render_params_in_each_language() {
+        let message = Message::new("backend.restart_error").with("error", "puerto ocupado");
+        assert_eq!(
+            message.render("es"),
+            "Error reiniciando servidor: puerto ocupado"
+        );
+        assert_eq!(
+            message.render("en-GB"),
+            "Error restarting the server: puerto ocupado"
+        );
+        assert_eq!(Message::new("no.existe").render("es"), "no.existe");
+
+        let json = message.to_json("es");
+        assert_eq!(json["message_key"], "backend.restart_error");
+        assert_eq!(json["message_params"]["error"], "puerto ocupado");
+    }
+
+    #[test]
+    fn catalog_keys_are_unique_and_placeholders_match() {
+        let mut seen = HashSet::new();
+        for (key, text) in MESSAGES {
+            assert!(seen.insert(*key), "Clave duplicada: '{}'", key);
+            let placeholders = |s: &str| {
+                s.split('{')
+                    .skip(1)
+                    .filter_map(|rest| rest.split_once('}').map(|(name, _)| name.to_string()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                placeholders(text.es),
+                placeholders(text.en),
+                "Marcadores distintos en '{}'",
+                key
+            );
+        }
+    }
+}
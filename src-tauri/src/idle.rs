@@ -0,0 +1,220 @@
+// Detección de inactividad del usuario en Narrative Assistant
+//
+// Un monitor consulta cada pocos segundos el tiempo sin entrada de teclado o
+// ratón en todo el sistema (no solo en la app) y emite `user-idle` al pasar
+// de `IDLE_THRESHOLD` sin actividad y `user-active` al volver. El trabajo
+// pesado en segundo plano (precarga de modelos, limpiezas de caché,
+// reinicios preventivos del backend) espera a la inactividad con
+// `wait_until_idle` o escucha los eventos en el frontend.
+//
+// Fuentes del tiempo de inactividad:
+// - Windows: GetLastInputInfo
+// - macOS: CGEventSourceSecondsSinceLastEventType
+// - Linux: IdleMonitor de GNOME (Mutter) u org.freedesktop.ScreenSaver (KDE
+//   y otros) por D-Bus. Sin ninguno de los dos, el usuario se considera
+//   siempre activo y no se emite nada.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Tiempo sin entrada para considerar al usuario inactivo
+const IDLE_THRESHOLD: Duration = Duration::from_secs(3 * 60);
+
+/// Intervalo de consulta
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Estado de actividad (payload de `user-idle` y `user-active`)
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdleStatus {
+    pub idle: bool,
+    /// Segundos sin entrada en la última consulta
+    pub idle_secs: u64,
+}
+
+/// Último estado, registrado con `.manage()`
+pub struct IdleState(Mutex<IdleStatus>);
+
+impl IdleState {
+    pub fn new() -> Self {
+        Self(Mutex::new(IdleStatus {
+            idle: false,
+            idle_secs: 0,
+        }))
+    }
+
+    /// Registra una consulta. Devuelve el nuevo estado si el usuario ha pasado
+    /// a inactivo o ha vuelto.
+    fn record(&self, idle_time: Duration) -> Option<IdleStatus> {
+        let mut status = self.0.lock().unwrap();
+        let was_idle = status.idle;
+        // Inactivo al superar el umbral; activo en cuanto el contador se reinicia
+        // (hubo entrada desde la última consulta)
+        let idle = if was_idle {
+            idle_time.as_secs() >= status.idle_secs
+        } else {
+            idle_time >= IDLE_THRESHOLD
+        };
+        *status = IdleStatus {
+            idle,
+            idle_secs: idle_time.as_secs(),
+        };
+        (idle != was_idle).then_some(*status)
+    }
+}
+
+#[cfg(windows)]
+fn system_idle_time() -> Option<Duration> {
+    use ::windows::Win32::System::SystemInformation::GetTickCount;
+    use ::windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    // SAFETY: `info` es un LASTINPUTINFO válido con cbSize inicializado
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    // SAFETY: GetTickCount no tiene precondiciones
+    let now = unsafe { GetTickCount() };
+    // Ambos contadores dan la vuelta a los 49 días
+    Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+}
+
+#[cfg(target_os = "macos")]
+fn system_idle_time() -> Option<Duration> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const ANY_INPUT_EVENT: u32 = u32::MAX;
+
+    // SAFETY: función sin estado de CoreGraphics, con constantes documentadas
+    let secs =
+        unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+#[cfg(target_os = "linux")]
+fn system_idle_time() -> Option<Duration> {
+    use std::sync::OnceLock;
+    use zbus::blocking::Connection;
+
+    static SESSION: OnceLock<Option<Connection>> = OnceLock::new();
+    let connection = SESSION
+        .get_or_init(|| Connection::session().ok())
+        .as_ref()?;
+
+    // GNOME: milisegundos
+    let gnome = connection
+        .call_method(
+            Some("org.gnome.Mutter.IdleMonitor"),
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            Some("org.gnome.Mutter.IdleMonitor"),
+            "GetIdletime",
+            &(),
+        )
+        .and_then(|reply| reply.body().deserialize::<u64>());
+    if let Ok(millis) = gnome {
+        return Some(Duration::from_millis(millis));
+    }
+    // KDE y otros: segundos
+    connection
+        .call_method(
+            Some("org.freedesktop.ScreenSaver"),
+            "/org/freedesktop/ScreenSaver",
+            Some("org.freedesktop.ScreenSaver"),
+            "GetSessionIdleTime",
+            &(),
+        )
+        .and_then(|reply| reply.body().deserialize::<u32>())
+        .ok()
+        .map(|secs| Duration::from_secs(secs as u64))
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn system_idle_time() -> Option<Duration> {
+    None
+}
+
+/// Si el usuario lleva más de `IDLE_THRESHOLD` sin usar el equipo
+pub fn is_idle(app: &AppHandle) -> bool {
+    app.state::<IdleState>().0.lock().unwrap().idle
+}
+
+/// Tiempo sin entrada de la última consulta (cero si no se puede medir)
+pub fn idle_time(app: &AppHandle) -> Duration {
+    Duration::from_secs(app.state::<IdleState>().0.lock().unwrap().idle_secs)
+}
+
+/// Espera a que el usuario esté inactivo (o a que pase `max_wait`, para no aplazar el trabajo indefinidamente)
+pub async fn wait_until_idle(app: &AppHandle, max_wait: Duration) {
+    let deadline = tokio::time::Instant::now() + max_wait;
+    while !is_idle(app) && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Monitor en segundo plano
+pub async fn monitor(app: AppHandle) {
+    let mut unavailable_logged = false;
+    loop {
+        let idle_time = tauri::async_runtime::spawn_blocking(system_idle_time)
+            .await
+            .ok()
+            .flatten();
+        match idle_time {
+            Some(idle_time) => {
+                if let Some(status) = app.state::<IdleState>().record(idle_time) {
+                    let event = if status.idle {
+                        "user-idle"
+                    } else {
+                        "user-active"
+                    };
+                    tracing::debug!(target: "idle", "{} ({} s sin actividad)", event, status.idle_secs);
+                    let _ = app.emit(event, status);
+                }
+            }
+            None if !unavailable_logged => {
+                unavailable_logged = true;
+                tracing::info!(
+                    target: "idle",
+                    "Tiempo de inactividad no disponible en este sistema; se asume usuario activo"
+                );
+            }
+            None => {}
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Último estado de actividad del usuario
+#[tauri::command]
+pub fn get_idle_state(state: State<'_, IdleState>) -> IdleStatus {
+    *state.0.lock().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_after_threshold_and_active_on_new_input() {
+        let state = IdleState::new();
+        let secs = Duration::from_secs;
+
+        assert_eq!(state.record(secs(60)), None);
+        let idle = state.record(IDLE_THRESHOLD).unwrap();
+        assert!(idle.idle);
+        assert_eq!(idle.idle_secs, IDLE_THRESHOLD.as_secs());
+        // Sigue inactivo mientras no haya entrada nueva
+        assert_eq!(state.record(IDLE_THRESHOLD + secs(120)), None);
+        // Una tecla: vuelve a activo aunque no haya pasado el umbral
+        let active = state.record(secs(1)).unwrap();
+        assert!(!active.idle);
+        assert_eq!(state.record(secs(30)), None);
+    }
+}
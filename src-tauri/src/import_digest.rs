@@ -0,0 +1,222 @@
+// Detección de importaciones duplicadas en Narrative Assistant
+//
+// Muchos usuarios acaban con varios proyectos del mismo manuscrito por
+// importarlo de nuevo sin darse cuenta. Al crear un proyecto desde un archivo
+// local el frontend registra con `record_import` el SHA-256 del archivo y el
+// proyecto creado (import_digests.json, en el directorio de datos de la app).
+// Antes de la siguiente importación pregunta con `check_duplicate_import`: si
+// el archivo es idéntico byte a byte a uno ya importado, ofrece crear una
+// versión nueva en ese proyecto u omitir la importación y abrirlo.
+//
+// Los registros de proyectos que ya no están en la base de datos se descartan
+// al consultarlos.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::clock::now_secs;
+use crate::{cleanup, database, integrity, settings};
+
+/// Nombre del fichero de huellas
+const DIGESTS_FILE_NAME: &str = "import_digests.json";
+
+/// Archivo importado en un proyecto
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ImportRecord {
+    pub sha256: String,
+    pub project_id: i64,
+    pub file_name: String,
+    pub size_bytes: u64,
+    /// Segundos desde epoch
+    pub imported_at: u64,
+}
+
+/// Importación previa del mismo archivo (respuesta de `check_duplicate_import`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateImport {
+    pub project_id: i64,
+    pub project_name: String,
+    pub file_name: String,
+    pub imported_at: u64,
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Huellas en memoria, registrado con `.manage()`
+pub struct ImportDigests {
+    path: PathBuf,
+    records: Mutex<Vec<ImportRecord>>,
+}
+
+impl ImportDigests {
+    /// Carga import_digests.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(DIGESTS_FILE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let records = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            records: Mutex::new(records),
+        }
+    }
+
+    fn save(&self, records: &[ImportRecord]) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(records).map_err(|e| e.to_string())?;
+        settings::write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))
+    }
+
+    fn record(&self, record: ImportRecord) -> Result<(), String> {
+        let mut records = self.records.lock().unwrap();
+        records.retain(|r| !(r.sha256 == record.sha256 && r.project_id == record.project_id));
+        records.push(record);
+        self.save(&records)
+    }
+
+    /// Importación más reciente de `sha256` en un proyecto de `existing`; los
+    /// registros de proyectos borrados se eliminan
+    fn find(
+        &self,
+        sha256: &str,
+        existing: &dyn Fn(i64) -> Option<String>,
+    ) -> Result<Option<DuplicateImport>, String> {
+        let mut records = self.records.lock().unwrap();
+        let mut names = Vec::new();
+        let mut gone = HashSet::new();
+        for record in records.iter() {
+            if let Some(name) = existing(record.project_id) {
+                names.push((record.clone(), name));
+            } else {
+                gone.insert(record.project_id);
+            }
+        }
+        if !gone.is_empty() {
+            records.retain(|r| !gone.contains(&r.project_id));
+            self.save(&records)?;
+        }
+        Ok(names
+            .into_iter()
+            .filter(|(record, _)| record.sha256 == sha256)
+            .max_by_key(|(record, _)| record.imported_at)
+            .map(|(record, project_name)| DuplicateImport {
+                project_id: record.project_id,
+                project_name,
+                file_name: record.file_name,
+                imported_at: record.imported_at,
+            }))
+    }
+}
+
+/// Nombre del proyecto `project_id` si sigue en la base de datos
+fn project_name(db: &Path, project_id: i64) -> Result<Option<String>, String> {
+    let conn = database::open_read_only(db)?;
+    match conn.query_row(
+        "SELECT name FROM projects WHERE id = ?1",
+        [project_id],
+        |row| row.get(0),
+    ) {
+        Ok(name) => Ok(Some(name)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Error leyendo el proyecto {}: {}", project_id, e)),
+    }
+}
+
+fn hash(path: &Path) -> Result<(u64, String), String> {
+    integrity::hash_file(path).map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))
+}
+
+/// Proyecto en el que ya se importó un archivo idéntico a `path`, si lo hay
+#[tauri::command]
+pub async fn check_duplicate_import(
+    digests: State<'_, ImportDigests>,
+    path: String,
+) -> Result<Option<DuplicateImport>, String> {
+    let Some(db) = database::database_path().filter(|db| db.exists()) else {
+        return Ok(None);
+    };
+    let path = PathBuf::from(path);
+    let (_, sha256) = tauri::async_runtime::spawn_blocking(move || hash(&path))
+        .await
+        .map_err(|e| format!("Error en la tarea de huella: {}", e))??;
+    // Si no se puede leer la base de datos no se descarta nada
+    let existing = |project_id| match project_name(&db, project_id) {
+        Ok(name) => name,
+        Err(e) => {
+            tracing::warn!(target: "import_digest", "{}", e);
+            Some(String::new())
+        }
+    };
+    digests.find(&sha256, &existing)
+}
+
+/// Registra que `path` se ha importado en `project_id`
+#[tauri::command]
+pub async fn record_import(
+    digests: State<'_, ImportDigests>,
+    path: String,
+    project_id: i64,
+) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    let name = file_name(&path);
+    let (size_bytes, sha256) = tauri::async_runtime::spawn_blocking(move || hash(&path))
+        .await
+        .map_err(|e| format!("Error en la tarea de huella: {}", e))??;
+    digests.record(ImportRecord {
+        sha256,
+        project_id,
+        file_name: name,
+        size_bytes,
+        imported_at: now_secs(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_files_match_the_latest_live_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let digests = ImportDigests::load(dir.path().join(DIGESTS_FILE_NAME));
+        let novel = dir.path().join("novela.docx");
+        fs::write(&novel, b"capitulo uno").unwrap();
+        let (size_bytes, sha256) = hash(&novel).unwrap();
+
+        for (project_id, imported_at) in [(1, 100), (2, 200), (3, 300)] {
+            digests
+                .record(ImportRecord {
+                    sha256: sha256.clone(),
+                    project_id,
+                    file_name: "novela.docx".into(),
+                    size_bytes,
+                    imported_at,
+                })
+                .unwrap();
+        }
+        // El proyecto 3 se ha borrado
+        let existing = |id: i64| (id != 3).then(|| format!("Proyecto {}", id));
+        let duplicate = digests.find(&sha256, &existing).unwrap().unwrap();
+        assert_eq!(duplicate.project_id, 2);
+        assert_eq!(duplicate.project_name, "Proyecto 2");
+
+        let reloaded = ImportDigests::load(dir.path().join(DIGESTS_FILE_NAME));
+        assert_eq!(reloaded.records.lock().unwrap().len(), 2);
+
+        fs::write(&novel, b"capitulo uno, revisado").unwrap();
+        let (_, changed) = hash(&novel).unwrap();
+        assert_eq!(reloaded.find(&changed, &existing).unwrap(), None);
+    }
+}
@@ -0,0 +1,403 @@
+// Integridad de los modelos NLP instalados
+//
+// Una descarga cortada o un disco con errores deja modelos a medias que el
+// backend carga sin quejarse y que luego fallan en mitad del análisis.
+// `verify_models` comprueba cada fichero de los modelos spaCy y HF contra sus
+// sumas SHA-256 esperadas, que salen de:
+//   - resources/model_files.json, embebido y firmado en la release (minisign,
+//     con la misma clave que las actualizaciones; la firma llega en
+//     NA_MODEL_FILES_SIGNATURE). Si hay clave pero la firma no es válida, el
+//     manifiesto se ignora
+//   - .files.json, que downloads.rs escribe en cada modelo al instalarlo, para
+//     los modelos que el manifiesto no cubre
+// Sin ninguno de los dos, el modelo queda "sin verificar". Un manifiesto vacío
+// o con firma no válida no es "todo correcto": `verify_models` y
+// `repair_models` fallan con un error en lugar de dar los modelos por intactos.
+//
+// `repair_models` vuelve a descargar los modelos dañados (y los requeridos que
+// faltan); en los de HF, solo los ficheros que fallan.
+//
+// El manifiesto se genera con scripts/generate_model_files_manifest.py a partir
+// de una instalación verificada.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, State};
+
+use crate::downloads::{self, DownloadsState};
+use crate::models::{ModelManifest, ModelSpec};
+use crate::updater;
+
+/// Manifiesto de ficheros embebido en tiempo de compilación
+const FILES_MANIFEST_JSON: &str = include_str!("../resources/model_files.json");
+
+/// Firma minisign del manifiesto (base64, formato de `cargo tauri signer sign`)
+const FILES_MANIFEST_SIGNATURE: Option<&str> = option_env!("NA_MODEL_FILES_SIGNATURE");
+
+/// Sumas de lo instalado que escribe downloads.rs
+const FILES_RECORD: &str = ".files.json";
+
+/// Fichero esperado de un modelo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FileEntry {
+    /// Ruta relativa con `/`
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Deserialize, Default)]
+struct FilesManifest {
+    #[serde(default)]
+    models: HashMap<String, Vec<FileEntry>>,
+}
+
+/// Estado de un modelo
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    /// Todos los ficheros coinciden
+    Ok,
+    /// No está instalado
+    Missing,
+    /// Faltan ficheros o alguno no coincide
+    Damaged,
+    /// Instalado, pero sin sumas con las que comparar
+    Unverified,
+}
+
+/// Resultado de la verificación de un modelo
+#[derive(Serialize, Clone, Debug)]
+pub struct ModelIntegrity {
+    pub id: String,
+    pub display_name: String,
+    pub required: bool,
+    pub path: String,
+    pub status: IntegrityStatus,
+    /// "signed_manifest" o "install_record"
+    pub source: Option<&'static str>,
+    pub missing_files: Vec<String>,
+    pub corrupted_files: Vec<String>,
+}
+
+/// Lee el manifiesto `json` comprobando su firma si hay clave. Falla si la
+/// firma no es válida o si no trae sumas de ningún modelo.
+fn parse_manifest(
+    json: &str,
+    pubkey: Option<&str>,
+    signature: Option<&str>,
+) -> Result<HashMap<String, Vec<FileEntry>>, String> {
+    if let Some(pubkey) = pubkey {
+        updater::verify_signature(pubkey, signature.unwrap_or(""), json.as_bytes()).map_err(
+            |e| {
+                format!(
+                    "El manifiesto de ficheros de modelos no tiene firma valida: {}",
+                    e
+                )
+            },
+        )?;
+    }
+    let models = serde_json::from_str::<FilesManifest>(json)
+        .map_err(|e| format!("El manifiesto de ficheros de modelos no es valido: {}", e))?
+        .models;
+    if models.values().all(Vec::is_empty) {
+        return Err(
+            "El manifiesto de ficheros de modelos esta vacio: esta version no puede verificar los modelos"
+                .into(),
+        );
+    }
+    Ok(models)
+}
+
+/// Ficheros del manifiesto firmado embebido
+pub(crate) fn signed_manifest() -> Result<&'static HashMap<String, Vec<FileEntry>>, String> {
+    static MANIFEST: OnceLock<Result<HashMap<String, Vec<FileEntry>>, String>> = OnceLock::new();
+    MANIFEST
+        .get_or_init(|| {
+            parse_manifest(
+                FILES_MANIFEST_JSON,
+                updater::configured_pubkey(),
+                FILES_MANIFEST_SIGNATURE,
+            )
+            .inspect_err(|e| tracing::error!(target: "integrity", "{}", e))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+pub(crate) fn hash_file(path: &Path) -> std::io::Result<(u64, String)> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)?;
+    Ok((size, downloads::to_hex(&hasher.finalize())))
+}
+
+/// Ficheros de `dir` (rutas relativas con `/`), sin los marcadores
+pub(crate) fn list_files(dir: &Path) -> Vec<String> {
+    fn walk(base: &Path, dir: &Path, out: &mut Vec<String>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(base, &path, out);
+            } else if let Ok(relative) = path.strip_prefix(base) {
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if relative != FILES_RECORD && relative != downloads::VERSION_MARKER {
+                    out.push(relative);
+                }
+            }
+        }
+    }
+    let mut files = Vec::new();
+    walk(dir, dir, &mut files);
+    files.sort();
+    files
+}
+
+/// Guarda en `.files.json` las sumas de todos los ficheros de un modelo recién instalado
+pub(crate) fn record(dir: &Path) -> Result<(), String> {
+    let entries = list_files(dir)
+        .into_iter()
+        .map(|path| {
+            let (size, sha256) = hash_file(&dir.join(&path))
+                .map_err(|e| format!("Error leyendo {}: {}", path, e))?;
+            Ok(FileEntry { path, size, sha256 })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let json = serde_json::to_vec_pretty(&entries).map_err(|e| e.to_string())?;
+    fs::write(dir.join(FILES_RECORD), json)
+        .map_err(|e| format!("Error guardando {}: {}", FILES_RECORD, e))
+}
+
+/// Compara `dir` con `expected`: (ficheros que faltan, ficheros dañados)
+fn check(dir: &Path, expected: &[FileEntry]) -> (Vec<String>, Vec<String>) {
+    let mut missing = Vec::new();
+    let mut corrupted = Vec::new();
+    for entry in expected {
+        let path = dir.join(&entry.path);
+        if !path.is_file() {
+            missing.push(entry.path.clone());
+            continue;
+        }
+        // Primero el tamaño: evita leer un fichero grande que ya se sabe dañado
+        let size_ok = fs::metadata(&path).is_ok_and(|m| m.len() == entry.size);
+        let hash_ok = size_ok
+            && hash_file(&path).is_ok_and(|(_, sha256)| sha256.eq_ignore_ascii_case(&entry.sha256));
+        if !hash_ok {
+            corrupted.push(entry.path.clone());
+        }
+    }
+    (missing, corrupted)
+}
+
+fn verify_model(
+    spec: &ModelSpec,
+    dir: PathBuf,
+    signed: &HashMap<String, Vec<FileEntry>>,
+) -> ModelIntegrity {
+    let mut result = ModelIntegrity {
+        id: spec.id.clone(),
+        display_name: spec.display_name.clone(),
+        required: spec.required,
+        path: dir.to_string_lossy().into_owned(),
+        status: IntegrityStatus::Missing,
+        source: None,
+        missing_files: Vec::new(),
+        corrupted_files: Vec::new(),
+    };
+    if !dir.is_dir() {
+        return result;
+    }
+    let recorded = fs::read_to_string(dir.join(FILES_RECORD))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Vec<FileEntry>>(&raw).ok());
+    let expected = match signed.get(&spec.id).filter(|files| !files.is_empty()) {
+        Some(files) => Some((files.clone(), "signed_manifest")),
+        None => recorded.map(|files| (files, "install_record")),
+    };
+    let Some((expected, source)) = expected else {
+        result.status = IntegrityStatus::Unverified;
+        return result;
+    };
+    let (missing, corrupted) = check(&dir, &expected);
+    result.status = if missing.is_empty() && corrupted.is_empty() {
+        IntegrityStatus::Ok
+    } else {
+        IntegrityStatus::Damaged
+    };
+    result.source = Some(source);
+    result.missing_files = missing;
+    result.corrupted_files = corrupted;
+    result
+}
+
+/// Verifica todos los modelos spaCy y HF del manifiesto
+fn verify_all(root: &Path, signed: &HashMap<String, Vec<FileEntry>>) -> Vec<ModelIntegrity> {
+    ModelManifest::bundled()
+        .models
+        .iter()
+        .filter_map(|spec| Some(verify_model(spec, spec.install_dir(root)?, signed)))
+        .collect()
+}
+
+async fn verify_in_background(app: &AppHandle) -> Result<Vec<ModelIntegrity>, String> {
+    let signed = signed_manifest()?;
+    let root = downloads::models_root(app).ok_or("No se pudo determinar el directorio home")?;
+    tauri::async_runtime::spawn_blocking(move || verify_all(&root, signed))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Comprueba los ficheros de los modelos NLP instalados
+#[tauri::command]
+pub async fn verify_models(app: AppHandle) -> Result<Vec<ModelIntegrity>, String> {
+    let results = verify_in_background(&app).await?;
+    for result in results
+        .iter()
+        .filter(|r| r.status == IntegrityStatus::Damaged)
+    {
+        tracing::warn!(
+            target: "integrity",
+            "{} dañado: {} ausentes, {} corruptos",
+            result.id,
+            result.missing_files.len(),
+            result.corrupted_files.len()
+        );
+    }
+    Ok(results)
+}
+
+/// Vuelve a descargar los modelos dañados y los requeridos que faltan (o solo
+/// `ids`, si se indican) y devuelve la verificación tras la reparación
+#[tauri::command]
+pub async fn repair_models(
+    app: AppHandle,
+    state: State<'_, DownloadsState>,
+    ids: Option<Vec<String>>,
+) -> Result<Vec<ModelIntegrity>, String> {
+    let manifest = ModelManifest::bundled();
+    let broken: Vec<ModelIntegrity> = verify_in_background(&app)
+        .await?
+        .into_iter()
+        .filter(|r| ids.as_ref().is_none_or(|ids| ids.contains(&r.id)))
+        .filter(|r| {
+            r.status == IntegrityStatus::Damaged
+                || (r.status == IntegrityStatus::Missing && r.required)
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    for model in &broken {
+        let Some(spec) = manifest.get(&model.id) else {
+            continue;
+        };
+        let files: Vec<String> = model
+            .missing_files
+            .iter()
+            .chain(&model.corrupted_files)
+            .cloned()
+            .collect();
+        tracing::info!(target: "integrity", "Reparando {} ({} ficheros)", model.id, files.len());
+        let repair = (model.status == IntegrityStatus::Damaged).then_some(files.as_slice());
+        if let Err(e) = downloads::download(&app, &state, spec, repair).await {
+            errors.push(format!("{}: {}", spec.display_name, e));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    verify_in_background(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_sums_detect_missing_and_corrupted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = dir.path().join("embeddings/modelo");
+        fs::create_dir_all(model.join("1_Pooling")).unwrap();
+        fs::write(model.join("config.json"), "{}").unwrap();
+        fs::write(model.join("1_Pooling/config.json"), "{\"pool\": 1}").unwrap();
+        fs::write(model.join("model.safetensors"), "pesos").unwrap();
+        fs::write(model.join(downloads::VERSION_MARKER), "{}").unwrap();
+        record(&model).unwrap();
+
+        let spec: ModelSpec = serde_json::from_value(serde_json::json!({
+            "id": "embeddings", "kind": "huggingface", "name": "org/modelo",
+            "display_name": "Embeddings", "purpose": "embeddings",
+            "subdirectory": "embeddings",
+            "local_name": "modelo", "size_mb": 1, "required": true
+        }))
+        .unwrap();
+        let signed = HashMap::new();
+        let ok = verify_model(&spec, model.clone(), &signed);
+        assert_eq!(ok.status, IntegrityStatus::Ok);
+        assert_eq!(ok.source, Some("install_record"));
+
+        // Mismo tamaño, contenido distinto: solo lo detecta la suma
+        fs::write(model.join("model.safetensors"), "PESOS").unwrap();
+        fs::remove_file(model.join("1_Pooling/config.json")).unwrap();
+        let damaged = verify_model(&spec, model.clone(), &signed);
+        assert_eq!(damaged.status, IntegrityStatus::Damaged);
+        assert_eq!(damaged.missing_files, vec!["1_Pooling/config.json"]);
+        assert_eq!(damaged.corrupted_files, vec!["model.safetensors"]);
+
+        fs::remove_file(model.join(FILES_RECORD)).unwrap();
+        assert_eq!(
+            verify_model(&spec, model, &signed).status,
+            IntegrityStatus::Unverified
+        );
+    }
+
+    #[test]
+    fn signed_manifest_takes_precedence_over_install_record() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config.cfg"), "[nlp]").unwrap();
+        record(dir.path()).unwrap();
+        let spec: ModelSpec = serde_json::from_value(serde_json::json!({
+            "id": "spacy", "kind": "spacy", "name": "es_core_news_lg",
+            "display_name": "spaCy", "purpose": "grammar", "subdirectory": "spacy",
+            "size_mb": 1, "required": true
+        }))
+        .unwrap();
+        let signed = HashMap::from([(
+            "spacy".to_string(),
+            vec![FileEntry {
+                path: "config.cfg".into(),
+                size: 5,
+                sha256: "0".repeat(64),
+            }],
+        )]);
+        let result = verify_model(&spec, dir.path().to_path_buf(), &signed);
+        assert_eq!(result.source, Some("signed_manifest"));
+        assert_eq!(result.corrupted_files, vec!["config.cfg"]);
+        assert!(FILES_MANIFEST_JSON.contains("\"models\""));
+    }
+
+    #[test]
+    fn empty_or_unsigned_manifests_are_errors() {
+        assert!(parse_manifest(r#"{"version": 1, "models": {}}"#, None, None).is_err());
+        assert!(parse_manifest(r#"{"version": 1, "models": {"spacy": []}}"#, None, None).is_err());
+        let manifest = r#"{"version": 1, "models": {"spacy": [
+            {"path": "config.cfg", "size": 5, "sha256": "00"}
+        ]}}"#;
+        assert_eq!(
+            parse_manifest(manifest, None, None).unwrap()["spacy"].len(),
+            1
+        );
+        let key = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, "x");
+        assert!(parse_manifest(manifest, Some(&key), None).is_err());
+    }
+}
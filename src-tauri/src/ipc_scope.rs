@@ -0,0 +1,163 @@
+// Comandos permitidos por ventana en Narrative Assistant
+//
+// Todas las ventanas podían invocar cualquier comando del shell: la ventana de
+// carga o la de notas rápidas podían borrar datos (`delete_data_category`) o
+// detener el backend igual que la principal. Cada ventana nueva ampliaba la
+// superficie expuesta por IPC.
+//
+// Ahora el manejador de comandos pasa antes por `authorize`, según el label de
+// la ventana que llama:
+//
+// - `main`: todos los comandos.
+// - `project-<id>`: todos salvo los destructivos (`DESTRUCTIVE`), que se
+//   hacen desde la ventana principal. Esto incluye lo destructivo que llega
+//   por comandos genéricos: `backend_request` comprueba `DELETE_PROJECT` y
+//   `invoke_command`, `RESTART_BACKEND`.
+// - Ventanas auxiliares (`splash`, `quick-note`, `dev-console`, `unlock`): solo los que
+//   usa su página en frontend/public.
+// - Cualquier otra (impresión, guía): ninguno.
+//
+// Los comandos de los plugins de Tauri (`plugin:...`) no pasan por aquí; se
+// limitan con capabilities/main.json y capabilities/auxiliary.json.
+
+use crate::dev_console::DEV_CONSOLE_WINDOW;
+use crate::project_unlock::UNLOCK_WINDOW;
+use crate::quick_note::QUICK_NOTE_WINDOW;
+use crate::splash::SPLASH_WINDOW;
+use crate::windows::{self, MAIN_WINDOW};
+
+/// Eliminar un proyecto por `backend_request` (DELETE /api/projects/<id>)
+pub const DELETE_PROJECT: &str = "backend_request:delete_project";
+
+/// Reiniciar el backend por `invoke_command`
+pub const RESTART_BACKEND: &str = "invoke_command:restart_backend";
+
+/// Comandos que borran datos, detienen servicios o sustituyen componentes
+const DESTRUCTIVE: &[&str] = &[
+    DELETE_PROJECT,
+    RESTART_BACKEND,
+    "stop_backend_server",
+    "delete_data_category",
+    "apply_snapshot_retention",
+    "restore_snapshot",
+    "encrypt_database",
+    "delete_secret",
+    "delete_ollama_model",
+    "dedup_models",
+    "set_models_location",
+    "clear_telemetry",
+    "install_update",
+];
+
+/// La ventana de carga solo escucha eventos
+const SPLASH_COMMANDS: &[&str] = &[];
+
+/// quick-note.js
+const QUICK_NOTE_COMMANDS: &[&str] = &["get_quick_note_context", "save_quick_note"];
+
+/// dev-console.js
+const DEV_CONSOLE_COMMANDS: &[&str] = &["get_recent_logs"];
+
+/// unlock.js
+const UNLOCK_COMMANDS: &[&str] = &["get_unlock_state", "unlock_project", "set_project_password"];
+
+/// Comandos que puede invocar una ventana
+#[derive(Debug, PartialEq, Eq)]
+enum WindowScope {
+    /// Todos
+    Full,
+    /// Todos salvo `DESTRUCTIVE`
+    NonDestructive,
+    /// Solo los de la lista
+    Only(&'static [&'static str]),
+}
+
+fn scope_for(label: &str) -> WindowScope {
+    match label {
+        MAIN_WINDOW => WindowScope::Full,
+        SPLASH_WINDOW => WindowScope::Only(SPLASH_COMMANDS),
+        QUICK_NOTE_WINDOW => WindowScope::Only(QUICK_NOTE_COMMANDS),
+        DEV_CONSOLE_WINDOW => WindowScope::Only(DEV_CONSOLE_COMMANDS),
+        UNLOCK_WINDOW => WindowScope::Only(UNLOCK_COMMANDS),
+        _ if windows::project_id_from_label(label).is_some() => WindowScope::NonDestructive,
+        _ => WindowScope::Only(&[]),
+    }
+}
+
+/// Comprueba si la ventana `label` puede invocar `command`
+pub fn authorize(label: &str, command: &str) -> Result<(), String> {
+    let allowed = match scope_for(label) {
+        WindowScope::Full => true,
+        WindowScope::NonDestructive => !DESTRUCTIVE.contains(&command),
+        WindowScope::Only(commands) => commands.contains(&command),
+    };
+    if allowed {
+        return Ok(());
+    }
+    tracing::warn!(
+        target: "ipc_scope",
+        "Comando {} rechazado desde la ventana {}",
+        command,
+        label
+    );
+    Err(format!(
+        "El comando {} no esta permitido en la ventana {}",
+        command, label
+    ))
+}
+
+/// Envuelve el manejador de `generate_handler!` con `authorize`
+pub fn scoped<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let label = invoke.message.webview_ref().label().to_string();
+        match authorize(&label, invoke.message.command()) {
+            Ok(()) => handler(invoke),
+            Err(e) => {
+                invoke.resolver.reject(e);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destructive_commands_are_limited_to_the_main_window() {
+        for command in ["delete_data_category", "stop_backend_server"] {
+            assert!(authorize("main", command).is_ok());
+            assert!(authorize("project-7", command).is_err());
+            assert!(authorize("quick-note", command).is_err());
+            assert!(authorize("splash", command).is_err());
+        }
+        assert!(authorize("project-7", "open_project_window").is_ok());
+        // Un label que solo empieza por "project-" no es una ventana de proyecto
+        assert!(authorize("project-x", "open_project_window").is_err());
+    }
+
+    #[test]
+    fn destructive_operations_behind_generic_commands_are_main_only() {
+        for operation in [DELETE_PROJECT, RESTART_BACKEND] {
+            assert!(authorize("main", operation).is_ok());
+            assert!(authorize("project-7", operation).is_err());
+            assert!(authorize("quick-note", operation).is_err());
+        }
+        // Los comandos genéricos en sí siguen permitidos en las ventanas de proyecto
+        assert!(authorize("project-7", "backend_request").is_ok());
+        assert!(authorize("project-7", "invoke_command").is_ok());
+    }
+
+    #[test]
+    fn auxiliary_windows_only_get_their_commands() {
+        assert!(authorize("quick-note", "save_quick_note").is_ok());
+        assert!(authorize("quick-note", "get_settings").is_err());
+        assert!(authorize("dev-console", "get_recent_logs").is_ok());
+        assert!(authorize("dev-console", "set_log_level").is_err());
+        assert!(authorize("print-1234", "print_report").is_err());
+        assert!(authorize("user-guide", "get_recent_logs").is_err());
+    }
+}
@@ -0,0 +1,406 @@
+// Logging estructurado del shell de Narrative Assistant
+//
+// Basado en `tracing`. Cada evento se escribe en:
+//   - La consola (solo útil en desarrollo; en release no hay consola en Windows)
+//   - ~/.narrative_assistant/logs/shell.log, rotado por tamaño (shell.log.1, .2...)
+//   - Un buffer en memoria con las últimas líneas, consultable con `get_recent_logs`
+//   - La consola de desarrollador (dev_console.rs), mientras está abierta
+//
+// El nivel se puede cambiar en caliente con `set_log_level`. El nivel inicial
+// se lee de NA_SHELL_LOG_LEVEL (por defecto "info").
+//
+// Los errores del webview llegan con `log_frontend_error` y se escriben con el
+// target "frontend" en el mismo log. Cada arranque tiene un id de sesión que va
+// en esas líneas y en la de inicio, para relacionarlas con el resto del log
+// aunque varios arranques compartan fichero.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use tauri::{State, WebviewWindow};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{reload, Registry};
+
+/// Nombre del fichero de log del shell
+const LOG_FILE_NAME: &str = "shell.log";
+
+/// Tamaño a partir del cual se rota shell.log
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Ficheros rotados que se conservan (shell.log.1 .. shell.log.N)
+const MAX_ROTATED_FILES: u32 = 3;
+
+/// Líneas que se guardan en memoria para `get_recent_logs`
+const RECENT_CAPACITY: usize = 500;
+
+/// Variable de entorno con el nivel inicial
+const LOG_LEVEL_ENV: &str = "NA_SHELL_LOG_LEVEL";
+
+/// Longitud máxima (en caracteres) del mensaje y de la traza de un error del webview
+const MAX_FRONTEND_MESSAGE_CHARS: usize = 2_000;
+const MAX_FRONTEND_STACK_CHARS: usize = 8_000;
+
+/// Directorio de logs (~/.narrative_assistant/logs)
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".narrative_assistant").join("logs"))
+}
+
+/// Fichero de log con rotación por tamaño
+struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf) -> Self {
+        let file = path
+            .parent()
+            .and_then(|dir| fs::create_dir_all(dir).ok())
+            .and_then(|_| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .ok()
+            });
+        let written = file
+            .as_ref()
+            .and_then(|f| f.metadata().ok())
+            .map(|m| m.len())
+            .unwrap_or(0);
+        Self {
+            path,
+            file,
+            written,
+        }
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    /// shell.log -> shell.log.1 -> shell.log.2 ... (el más antiguo se descarta)
+    fn rotate(&mut self) {
+        self.file = None;
+        let _ = fs::remove_file(self.rotated_path(MAX_ROTATED_FILES));
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let _ = fs::rename(self.rotated_path(index), self.rotated_path(index + 1));
+        }
+        let _ = fs::rename(&self.path, self.rotated_path(1));
+        *self = Self::open(self.path.clone());
+    }
+
+    fn write(&mut self, buf: &[u8]) {
+        if self.written > 0 && self.written + buf.len() as u64 > MAX_LOG_BYTES {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(buf).is_ok() {
+                self.written += buf.len() as u64;
+            }
+        }
+    }
+}
+
+/// Destino compartido: fichero rotado + últimas líneas en memoria + suscriptor en vivo
+struct LogSink {
+    file: Option<Mutex<RotatingFile>>,
+    recent: Mutex<VecDeque<String>>,
+    live: Mutex<Option<Sender<String>>>,
+}
+
+impl LogSink {
+    fn new(log_file: Option<PathBuf>) -> Self {
+        Self {
+            file: log_file.map(|path| Mutex::new(RotatingFile::open(path))),
+            recent: Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)),
+            live: Mutex::new(None),
+        }
+    }
+
+    fn record(&self, buf: &[u8]) {
+        if let Some(file) = &self.file {
+            file.lock().unwrap().write(buf);
+        }
+        let text = String::from_utf8_lossy(buf);
+        {
+            let mut recent = self.recent.lock().unwrap();
+            for line in text.lines() {
+                if recent.len() == RECENT_CAPACITY {
+                    recent.pop_front();
+                }
+                recent.push_back(line.to_string());
+            }
+        }
+        let mut live = self.live.lock().unwrap();
+        if let Some(sender) = live.as_ref() {
+            // Sin receptor (consola cerrada) se deja de enviar
+            if text
+                .lines()
+                .any(|line| sender.send(line.to_string()).is_err())
+            {
+                *live = None;
+            }
+        }
+    }
+
+    /// Nuevo suscriptor en vivo; sustituye al anterior
+    fn subscribe(&self) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        *self.live.lock().unwrap() = Some(sender);
+        receiver
+    }
+
+    fn recent(&self, limit: usize) -> Vec<String> {
+        let recent = self.recent.lock().unwrap();
+        recent
+            .iter()
+            .skip(recent.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Writer que `tracing_subscriber::fmt` usa para cada evento (una línea por escritura)
+#[derive(Clone)]
+struct SinkWriter(Arc<LogSink>);
+
+impl Write for SinkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.record(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for SinkWriter {
+    type Writer = SinkWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Estado del logging, registrado con `.manage()`
+pub struct LogState {
+    level: reload::Handle<LevelFilter, Registry>,
+    sink: Arc<LogSink>,
+    session_id: String,
+}
+
+impl LogState {
+    /// Id de este arranque, el mismo que llevan sus líneas de "frontend"
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Recibe cada línea nueva del log (un solo suscriptor a la vez)
+    pub fn subscribe(&self) -> Receiver<String> {
+        self.sink.subscribe()
+    }
+
+    /// Acceso a las últimas líneas desde fuera del estado de Tauri (p.ej. el panic hook)
+    pub fn recent_logs(&self) -> RecentLogs {
+        RecentLogs(Arc::clone(&self.sink))
+    }
+}
+
+/// Últimas líneas del log en memoria
+#[derive(Clone)]
+pub struct RecentLogs(Arc<LogSink>);
+
+impl RecentLogs {
+    /// Como `get_recent_logs`, pero sin bloquear: si el buffer está ocupado o
+    /// envenenado (pánico a mitad de un log) devuelve una lista vacía
+    pub fn lines(&self, limit: usize) -> Vec<String> {
+        match self.0.recent.try_lock() {
+            Ok(recent) => recent
+                .iter()
+                .skip(recent.len().saturating_sub(limit))
+                .cloned()
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Convierte "trace" | "debug" | "info" | "warn" | "error" | "off" en un filtro
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Nivel de log no valido: {}", level))
+}
+
+/// Instala el subscriber global. Llamar una sola vez, al principio de `main`.
+pub fn init() -> LogState {
+    let initial = std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .and_then(|l| parse_level(&l).ok())
+        .unwrap_or(LevelFilter::INFO);
+    let (filter, level) = reload::Layer::new(initial);
+    let sink = Arc::new(LogSink::new(log_dir().map(|d| d.join(LOG_FILE_NAME))));
+
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(io::stderr))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(SinkWriter(Arc::clone(&sink))),
+        )
+        .try_init();
+    if let Err(e) = result {
+        eprintln!("No se pudo inicializar el logging: {}", e);
+    }
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    tracing::info!(
+        target: "logging",
+        session = %session_id,
+        "Narrative Assistant {} iniciado",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    LogState {
+        level,
+        sink,
+        session_id,
+    }
+}
+
+/// Recorta `text` a `max` caracteres, marcando el corte
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}… [recortado]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Cambia el nivel de log en caliente
+#[tauri::command]
+pub fn set_log_level(state: State<'_, LogState>, level: String) -> Result<String, String> {
+    let filter = parse_level(&level)?;
+    state
+        .level
+        .modify(|current| *current = filter)
+        .map_err(|e| format!("Error cambiando el nivel de log: {}", e))?;
+    tracing::info!(target: "logging", "Nivel de log cambiado a {}", filter);
+    Ok(filter.to_string())
+}
+
+/// Últimas líneas del log del shell (por defecto 200, máximo el buffer en memoria)
+#[tauri::command]
+pub fn get_recent_logs(state: State<'_, LogState>, limit: Option<usize>) -> Vec<String> {
+    state.sink.recent(limit.unwrap_or(200))
+}
+
+/// Escribe en el log del shell un error (o aviso) del webview
+///
+/// `level` es "error" | "warn" | "info"; cualquier otro valor se trata como error.
+#[tauri::command]
+pub fn log_frontend_error(
+    window: WebviewWindow,
+    state: State<'_, LogState>,
+    level: String,
+    message: String,
+    stack: Option<String>,
+) {
+    let message = truncate_chars(message.trim(), MAX_FRONTEND_MESSAGE_CHARS);
+    let stack = stack
+        .map(|s| truncate_chars(s.trim(), MAX_FRONTEND_STACK_CHARS))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_default();
+    let session = state.session_id.as_str();
+    let label = window.label();
+
+    match level.trim().to_ascii_lowercase().as_str() {
+        "info" => {
+            tracing::info!(target: "frontend", session, window = label, stack, "{}", message)
+        }
+        "warn" | "warning" => {
+            tracing::warn!(target: "frontend", session, window = label, stack, "{}", message)
+        }
+        _ => tracing::error!(target: "frontend", session, window = label, stack, "{}", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotating_file_rotates_by_size_and_keeps_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOG_FILE_NAME);
+        let mut file = RotatingFile::open(path.clone());
+        let chunk = vec![b'x'; (MAX_LOG_BYTES / 2 + 1) as usize];
+
+        for _ in 0..(2 * (MAX_ROTATED_FILES + 2)) {
+            file.write(&chunk);
+        }
+
+        assert!(path.exists());
+        for index in 1..=MAX_ROTATED_FILES {
+            assert!(file.rotated_path(index).exists());
+        }
+        assert!(!file.rotated_path(MAX_ROTATED_FILES + 1).exists());
+        assert!(fs::metadata(&path).unwrap().len() <= MAX_LOG_BYTES);
+    }
+
+    #[test]
+    fn sink_keeps_last_lines() {
+        let sink = LogSink::new(None);
+        for i in 0..(RECENT_CAPACITY + 10) {
+            sink.record(format!("line {}\n", i).as_bytes());
+        }
+
+        let recent = sink.recent(3);
+        assert_eq!(
+            recent,
+            vec![
+                format!("line {}", RECENT_CAPACITY + 7),
+                format!("line {}", RECENT_CAPACITY + 8),
+                format!("line {}", RECENT_CAPACITY + 9),
+            ]
+        );
+        assert_eq!(sink.recent(usize::MAX).len(), RECENT_CAPACITY);
+    }
+
+    #[test]
+    fn subscriber_receives_new_lines_until_dropped() {
+        let sink = LogSink::new(None);
+        sink.record(b"antes\n");
+        let receiver = sink.subscribe();
+        sink.record(b"uno\ndos\n");
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["uno", "dos"]);
+
+        drop(receiver);
+        sink.record(b"tres\n");
+        assert!(sink.live.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_level_accepts_known_levels() {
+        assert_eq!(parse_level("debug").unwrap(), LevelFilter::DEBUG);
+        assert_eq!(parse_level(" WARN ").unwrap(), LevelFilter::WARN);
+        assert!(parse_level("verbose").is_err());
+    }
+
+    #[test]
+    fn truncate_chars_respects_char_boundaries() {
+        assert_eq!(truncate_chars("corto", 10), "corto");
+        assert_eq!(truncate_chars("áéíóú", 2), "áé… [recortado]");
+    }
+}
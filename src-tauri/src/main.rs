@@ -1,9 +1,91 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod accessibility;
+mod annotated_docx;
+mod audit;
+mod autostart;
+mod backend_auth;
+mod backend_proxy;
+mod badge;
+mod capture;
+mod chapter_split;
 mod cleanup;
+mod clipboard;
+mod clock;
+mod commands;
+mod crash;
+mod database;
+mod db_encryption;
+mod deep_link;
+mod dev_console;
+mod download_queue;
+mod downloads;
+mod drop_import;
+mod edit_recovery;
+mod export_scope;
+mod feedback;
+mod file_association;
+mod file_dialogs;
+mod folder_import;
+mod glossary_export;
+mod hardware;
+mod i18n;
+mod idle;
+mod import_digest;
+mod integrity;
+mod ipc_scope;
+mod logging;
+mod manuscript_preview;
+mod manuscript_watch;
 mod menu;
-
+mod model_bundle;
+mod model_catalog;
+mod model_dedup;
+mod models;
+mod models_location;
+mod network;
+mod ollama;
+mod ollama_install;
+mod onboarding;
+mod pdf_text;
+mod plugins;
+mod power;
+mod preflight;
+mod print;
+mod progress;
+mod project_archive;
+mod project_list;
+mod project_lock;
+mod project_templates;
+mod project_unlock;
+mod proxy;
+mod quick_note;
+mod quick_stats;
+mod recent_projects;
+mod remote_llm;
+mod report_pdf;
+mod scrivener;
+mod secrets;
+mod session;
+mod settings;
+mod snapshots;
+mod spacy_install;
+mod splash;
+mod status_channel;
+mod telemetry;
+mod text_encoding;
+mod theme;
+mod tray;
+mod updater;
+mod user_guide;
+mod visualization_export;
+mod warmup;
+mod window_state;
+mod windows;
+
+use i18n::Message;
+use std::collections::BTreeSet;
 #[cfg(not(debug_assertions))]
 use std::io::{BufRead, BufReader};
 #[cfg(test)]
@@ -17,16 +99,23 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 #[cfg(test)]
 use std::thread;
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Manager, State};
 
 const BACKEND_WARMING_MSG: &str = "Backend warming up (modules loading)";
-const BACKEND_HEALTH_URL: &str = "http://127.0.0.1:8008/api/health";
+const BACKEND_HEALTH_PATH: &str = "/api/health";
+
+/// Máximo que se aplaza la retención de snapshots esperando a que el usuario esté inactivo
+const RETENTION_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(30 * 60);
 
 /// Estado compartido del servidor backend
 struct BackendServer {
     child: Arc<Mutex<Option<Child>>>,
     /// Flag para evitar reinicio durante el cierre de la app
     shutting_down: Arc<AtomicBool>,
+    /// Flag para que el watchdog no reinicie el backend durante un mantenimiento
+    paused: Arc<AtomicBool>,
+    /// Ventanas abiertas que comparten el backend (labels)
+    windows: Arc<Mutex<BTreeSet<String>>>,
 }
 
 impl BackendServer {
@@ -34,8 +123,24 @@ impl BackendServer {
         Self {
             child: Arc::new(Mutex::new(None)),
             shutting_down: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
+            windows: Arc::new(Mutex::new(BTreeSet::from([
+                windows::MAIN_WINDOW.to_string()
+            ]))),
         }
     }
+
+    /// Registra una ventana que usa el backend
+    fn attach_window(&self, label: &str) {
+        self.windows.lock().unwrap().insert(label.to_string());
+    }
+
+    /// Libera una ventana. Devuelve `true` si era la última y hay que parar el backend.
+    fn release_window(&self, label: &str) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        windows.remove(label);
+        windows.is_empty()
+    }
 }
 
 fn is_backend_ready_body(body: &serde_json::Value) -> bool {
@@ -46,11 +151,11 @@ fn is_backend_ready_body(body: &serde_json::Value) -> bool {
 
 /// Liveness check: el proceso backend responde HTTP 200 (puede no tener módulos cargados).
 async fn poll_health_alive() -> bool {
-    poll_health_alive_url(BACKEND_HEALTH_URL).await
+    poll_health_alive_url(&backend_auth::backend_url(BACKEND_HEALTH_PATH)).await
 }
 
 async fn poll_health_alive_url(url: &str) -> bool {
-    let client = reqwest::Client::new();
+    let client = backend_auth::backend_client();
     match client
         .get(url)
         .timeout(std::time::Duration::from_secs(2))
@@ -65,11 +170,11 @@ async fn poll_health_alive_url(url: &str) -> bool {
 /// Readiness check: el backend responde Y tiene los módulos cargados (`backend_loaded: true`).
 /// Usa esto para decidir cuándo emitir "running" al frontend.
 async fn poll_health_ready() -> bool {
-    poll_health_ready_url(BACKEND_HEALTH_URL).await
+    poll_health_ready_url(&backend_auth::backend_url(BACKEND_HEALTH_PATH)).await
 }
 
 async fn poll_health_ready_url(url: &str) -> bool {
-    let client = reqwest::Client::new();
+    let client = backend_auth::backend_client();
     match client
         .get(url)
         .timeout(std::time::Duration::from_secs(2))
@@ -109,7 +214,7 @@ where
 async fn wait_for_alive(max_attempts: u32, delay_ms: u64) -> bool {
     for attempt in 1..=max_attempts {
         if wait_for_health(1, delay_ms, poll_health_alive).await {
-            println!("[Health] Backend alive after {} attempts", attempt);
+            tracing::info!(target: "health", "Backend alive after {} attempts", attempt);
             return true;
         }
     }
@@ -121,7 +226,7 @@ async fn wait_for_alive(max_attempts: u32, delay_ms: u64) -> bool {
 async fn wait_for_ready(max_attempts: u32, delay_ms: u64) -> bool {
     for attempt in 1..=max_attempts {
         if wait_for_health(1, delay_ms, poll_health_ready).await {
-            println!("[Health] Backend ready after {} attempts", attempt);
+            tracing::info!(target: "health", "Backend ready after {} attempts", attempt);
             return true;
         }
     }
@@ -135,6 +240,11 @@ async fn start_backend_server(
     _app: AppHandle,
     server_state: State<'_, BackendServer>,
 ) -> Result<String, String> {
+    // Sin la contraseña el backend no podría abrir la base de datos
+    if project_unlock::is_locked() {
+        return Err("La base de datos esta bloqueada".to_string());
+    }
+
     // Verificar handle existente y limpiar stale handles si el proceso ya terminó.
     {
         let mut child_lock = server_state.child.lock().unwrap();
@@ -144,15 +254,17 @@ async fn start_backend_server(
                     return Ok("Backend server already running".to_string());
                 }
                 Ok(Some(status)) => {
-                    eprintln!(
-                        "[Setup] Found stale backend child handle (exited with status: {:?}), cleaning up",
+                    tracing::warn!(
+                        target: "setup",
+                        "Found stale backend child handle (exited with status: {:?}), cleaning up",
                         status.code()
                     );
                     *child_lock = None;
                 }
                 Err(e) => {
-                    eprintln!(
-                        "[Setup] Failed to query backend child status ({}), cleaning up handle",
+                    tracing::error!(
+                        target: "setup",
+                        "Failed to query backend child status ({}), cleaning up handle",
                         e
                     );
                     *child_lock = None;
@@ -161,17 +273,28 @@ async fn start_backend_server(
         }
     }
 
-    // Verificar si el servidor ya esta corriendo externamente
+    // Verificar si el servidor ya esta corriendo externamente. En release
+    // tiene que pasar las mismas comprobaciones que un backend recién lanzado:
+    // si no, cualquier proceso en 127.0.0.1:8008 recibiría los tokens del shell
     if poll_health_alive().await {
-        println!("[Setup] Backend server already running externally");
+        #[cfg(not(debug_assertions))]
+        if let Err(e) = backend_auth::verify_backend().await {
+            tracing::error!(target: "setup", "External backend rejected: {}", e);
+            return Err(format!(
+                "Hay otro proceso escuchando en el puerto del backend: {}",
+                e
+            ));
+        }
+        tracing::info!(target: "setup", "Backend server already running externally");
         return Ok("Backend server already running externally".to_string());
     }
 
     // En modo desarrollo, indicar que se debe iniciar manualmente
     #[cfg(debug_assertions)]
     {
-        println!(
-            "[Setup] Development mode: start backend manually with 'python api-server/main.py'"
+        tracing::info!(
+            target: "setup",
+            "Development mode: start backend manually with 'python api-server/main.py'"
         );
         Ok("Development mode: start backend manually".to_string())
     }
@@ -179,6 +302,7 @@ async fn start_backend_server(
     // En modo release, usar el sidecar
     #[cfg(not(debug_assertions))]
     {
+        splash::emit_progress(&_app, splash::BootPhase::Spawning);
         let mut child = spawn_embedded_backend(&_app)?;
 
         if let Some(stdout) = child.stdout.take() {
@@ -190,38 +314,44 @@ async fn start_backend_server(
         }
 
         // HI-12: Emit "starting" so frontend knows we're polling
-        let _ = _app.emit(
-            "backend-status",
-            serde_json::json!({
-                "status": "starting",
-                "message": "Iniciando servidor..."
-            }),
-        );
+        status_channel::backend_status(&_app, "starting", Message::new("backend.starting"));
+
+        splash::emit_progress(&_app, splash::BootPhase::Connecting);
 
         // HI-12: Two-phase health check — liveness then readiness.
         // Phase 1: Wait for the process to respond at all (liveness).
         // 30 attempts × 500ms = 15s max.
         if !wait_for_alive(30, 500).await {
-            eprintln!("[Setup] Backend process did not respond after 15s — killing");
+            tracing::warn!(target: "setup", "Backend process did not respond after 15s — killing");
             // Process never came alive — kill it to avoid stale handle
             let _ = child.kill();
             let _ = child.wait();
             return Err("Backend did not respond after 15s of polling".to_string());
         }
 
+        // Sin aislamiento (escucha en la red o no exige el token) no se usa
+        if let Err(e) = backend_auth::verify_backend().await {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
         // Process is alive — persist the handle so watchdog can manage it
         {
             let mut child_lock = server_state.child.lock().unwrap();
             *child_lock = Some(child);
         }
 
+        splash::emit_progress(&_app, splash::BootPhase::LoadingModules);
+
         // Phase 2: Wait for backend_loaded == true (readiness).
         // 60 attempts × 500ms = 30s extra for module loading.
         if !wait_for_ready(60, 500).await {
             // Process is alive but modules not loaded yet.
             // Return "warming" — NOT Err — so watchdog can still start.
-            println!(
-                "[Setup] Backend alive but modules not loaded after 30s — entering warming mode"
+            tracing::info!(
+                target: "setup",
+                "Backend alive but modules not loaded after 30s — entering warming mode"
             );
             return Ok(BACKEND_WARMING_MSG.to_string());
         }
@@ -246,6 +376,54 @@ async fn stop_backend_server(server_state: State<'_, BackendServer>) -> Result<S
     }
 }
 
+/// Pausa el backend gestionado para operaciones de mantenimiento que necesitan
+/// acceso exclusivo a sus ficheros (VACUUM, borrado de datos...).
+/// Devuelve true si había un proceso gestionado que hay que reanudar después.
+fn pause_backend(app: &AppHandle, server_state: &BackendServer) -> bool {
+    server_state.paused.store(true, Ordering::SeqCst);
+
+    let mut child_lock = server_state.child.lock().unwrap();
+    match child_lock.take() {
+        Some(mut child) => {
+            tracing::info!(target: "maintenance", "Pausing backend");
+            status_channel::backend_status(app, "restarting", Message::new("backend.maintenance"));
+            let _ = child.kill();
+            let _ = child.wait();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Reanuda el backend tras `pause_backend`. Solo lo relanza si estaba corriendo.
+async fn resume_backend(
+    app: &AppHandle,
+    server_state: State<'_, BackendServer>,
+    was_running: bool,
+) {
+    server_state.paused.store(false, Ordering::SeqCst);
+    if !was_running {
+        return;
+    }
+
+    tracing::info!(target: "maintenance", "Resuming backend");
+    match start_backend_server(app.clone(), server_state).await {
+        Ok(_) => status_channel::backend_status(app, "running", Message::new("backend.restarted")),
+        Err(e) => status_channel::backend_status(
+            app,
+            "error",
+            Message::new("backend.restart_error").with("error", e),
+        ),
+    }
+}
+
+/// Reinicia el backend gestionado (paleta de comandos)
+pub(crate) async fn restart_backend(app: &AppHandle) {
+    let server_state = app.state::<BackendServer>();
+    pause_backend(app, &server_state);
+    resume_backend(app, server_state, true).await;
+}
+
 /// Verifica si el servidor backend está corriendo (readiness para frontend)
 #[tauri::command]
 async fn check_backend_health() -> Result<bool, String> {
@@ -256,25 +434,40 @@ async fn check_backend_health() -> Result<bool, String> {
 /// Se ejecuta en un loop cada 15s en release builds.
 #[cfg(not(debug_assertions))]
 async fn backend_watchdog(app_handle: AppHandle) {
-    // Esperar a que el backend arranque inicialmente (45s para permitir carga completa)
-    tokio::time::sleep(tokio::time::Duration::from_secs(45)).await;
+    // La política se relee en cada iteración: los cambios en preferencias aplican sin reiniciar
+    let current_policy = || app_handle.state::<settings::SettingsStore>().get().watchdog;
+
+    // Esperar a que el backend arranque inicialmente (45s por defecto para permitir carga completa)
+    tokio::time::sleep(tokio::time::Duration::from_secs(
+        current_policy().initial_delay_secs,
+    ))
+    .await;
 
     let mut consecutive_failures: u32 = 0;
-    const MAX_FAILURES_BEFORE_RESTART: u32 = 3;
-    const MAX_RESTARTS: u32 = 3;
     let mut restart_count: u32 = 0;
 
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(15)).await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(
+            current_policy().interval_secs,
+        ))
+        .await;
 
         let server_state = app_handle.state::<BackendServer>();
+        let policy = current_policy();
 
         // No reiniciar si la app se está cerrando
         if server_state.shutting_down.load(Ordering::Relaxed) {
-            println!("[Watchdog] App shutting down, stopping watchdog");
+            tracing::info!(target: "watchdog", "App shutting down, stopping watchdog");
             break;
         }
 
+        // No contar fallos mientras el backend está pausado por mantenimiento
+        // o el usuario ha desactivado el watchdog
+        if server_state.paused.load(Ordering::SeqCst) || !policy.enabled {
+            consecutive_failures = 0;
+            continue;
+        }
+
         // HI-12: Use liveness (not readiness) for crash detection.
         // A backend that's alive but still loading modules should NOT trigger restart.
         if poll_health_alive().await {
@@ -283,44 +476,43 @@ async fn backend_watchdog(app_handle: AppHandle) {
         }
 
         consecutive_failures += 1;
-        eprintln!(
-            "[Watchdog] Health check failed ({}/{})",
-            consecutive_failures, MAX_FAILURES_BEFORE_RESTART
+        tracing::warn!(
+            target: "watchdog",
+            "Health check failed ({}/{})",
+            consecutive_failures, policy.max_failures
         );
 
-        if consecutive_failures < MAX_FAILURES_BEFORE_RESTART {
+        if consecutive_failures < policy.max_failures {
             continue;
         }
 
         // Backend is down - attempt restart
-        if restart_count >= MAX_RESTARTS {
-            eprintln!(
-                "[Watchdog] Max restarts ({}) reached, giving up",
-                MAX_RESTARTS
+        if restart_count >= policy.max_restarts {
+            tracing::error!(
+                target: "watchdog",
+                "Max restarts ({}) reached, giving up",
+                policy.max_restarts
             );
-            let _ = app_handle.emit(
-                "backend-status",
-                serde_json::json!({
-                    "status": "error",
-                    "message": "El servidor se detuvo y no pudo reiniciarse. Reinicia la aplicación."
-                }),
+            status_channel::backend_status(
+                &app_handle,
+                "error",
+                Message::new("backend.restart_failed"),
             );
             break;
         }
 
-        println!(
-            "[Watchdog] Attempting backend restart ({}/{})",
+        tracing::info!(
+            target: "watchdog",
+            "Attempting backend restart ({}/{})",
             restart_count + 1,
-            MAX_RESTARTS
+            policy.max_restarts
         );
 
         // Notify frontend
-        let _ = app_handle.emit(
-            "backend-status",
-            serde_json::json!({
-                "status": "restarting",
-                "message": "El servidor se detuvo, reiniciando..."
-            }),
+        status_channel::backend_status(
+            &app_handle,
+            "restarting",
+            Message::new("backend.restarting"),
         );
 
         // Kill old process if still hanging
@@ -347,52 +539,274 @@ async fn backend_watchdog(app_handle: AppHandle) {
                     *child_lock = Some(child);
                 }
 
+                // Como en start_backend_server: primero que escuche, luego el aislamiento.
+                // Si falla, el intento cuenta como reinicio y se reintenta en la siguiente
+                // iteración (hasta `max_restarts`).
+                let probe = if wait_for_alive(30, 500).await {
+                    backend_auth::verify_backend().await
+                } else {
+                    Err("Backend did not respond after 15s of polling".to_string())
+                };
+                if let Err(e) = probe {
+                    tracing::error!(target: "watchdog", "Restarted backend rejected: {}", e);
+                    if let Some(mut child) = server_state.child.lock().unwrap().take() {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    restart_count += 1;
+                    status_channel::backend_status(
+                        &app_handle,
+                        "error",
+                        Message::new("backend.restart_error").with("error", e),
+                    );
+                    continue;
+                }
+
                 // Wait for readiness after restart
                 if wait_for_ready(30, 500).await {
-                    println!("[Watchdog] Backend restarted successfully");
+                    tracing::info!(target: "watchdog", "Backend restarted successfully");
                     restart_count += 1;
                     consecutive_failures = 0;
 
-                    let _ = app_handle.emit(
-                        "backend-status",
-                        serde_json::json!({
-                            "status": "running",
-                            "message": "Servidor reiniciado correctamente"
-                        }),
+                    status_channel::backend_status(
+                        &app_handle,
+                        "running",
+                        Message::new("backend.restarted"),
                     );
                 } else {
-                    eprintln!("[Watchdog] Backend failed to respond after restart");
+                    tracing::error!(target: "watchdog", "Backend failed to respond after restart");
                     restart_count += 1;
                 }
             }
             Err(e) => {
-                eprintln!("[Watchdog] Failed to spawn backend: {}", e);
+                tracing::error!(target: "watchdog", "Failed to spawn backend: {}", e);
                 restart_count += 1;
 
-                let _ = app_handle.emit(
-                    "backend-status",
-                    serde_json::json!({
-                        "status": "error",
-                        "message": format!("Error reiniciando servidor: {}", e)
-                    }),
+                status_channel::backend_status(
+                    &app_handle,
+                    "error",
+                    Message::new("backend.restart_error").with("error", e),
                 );
             }
         }
     }
 }
 
+/// Detiene el backend al salir de la app, avisando antes al watchdog
+pub(crate) fn shutdown_backend(app: &AppHandle) {
+    // Señalar al watchdog que pare antes de matar el backend
+    let server_state = app.state::<BackendServer>();
+    server_state.shutting_down.store(true, Ordering::Relaxed);
+
+    tauri::async_runtime::block_on(async {
+        let _ = stop_backend_server(server_state).await;
+    });
+}
+
 fn main() {
-    tauri::Builder::default()
+    let log_state = logging::init();
+    crash::install(&log_state);
+    let recent_logs = log_state.recent_logs();
+
+    let result = tauri::Builder::default()
+        // Debe registrarse la primera: una segunda instancia (p. ej. al abrir un
+        // enlace narrassist://) reenvía sus argumentos a esta y termina
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            // Los archivos asociados abren en la principal; los enlaces los enfoca
+            // deep_link::handle_urls en su ventana de destino
+            let opened_file =
+                file_association::handle_args(app, &argv, std::path::Path::new(&cwd));
+            if !opened_file && !deep_link::is_deep_link_argv(&argv) {
+                tray::show_main_window(app);
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![autostart::AUTOSTART_ARG]),
+        ))
+        // Guía de usuario sin conexión (guide://)
+        .register_uri_scheme_protocol(user_guide::GUIDE_SCHEME, |ctx, request| {
+            user_guide::handle_request(ctx.app_handle(), &request)
+        })
+        .manage(log_state)
+        .manage(status_channel::StatusChannel::new())
+        .manage(settings::SettingsStore::load_default())
+        .manage(window_state::WindowStateStore::load_default())
+        .manage(session::SessionStore::load_default())
+        .manage(recent_projects::RecentProjectsStore::load_default())
+        .manage(export_scope::ExportGrants::load_default())
+        .manage(import_digest::ImportDigests::load_default())
+        .manage(edit_recovery::EditRecovery::load_default())
+        .manage(onboarding::OnboardingStore::load_default())
+        .manage(plugins::PluginRegistry::discover_default())
         .manage(BackendServer::new())
-        .invoke_handler(tauri::generate_handler![
+        .manage(cleanup::DeletionGuard::new())
+        .manage(windows::ActiveWindow::new())
+        .manage(accessibility::AccessibilityState::new())
+        .manage(ollama::OllamaState::new())
+        .manage(downloads::DownloadsState::new())
+        .manage(download_queue::DownloadQueue::load_default())
+        .manage(deep_link::DeepLinkState::new())
+        .manage(file_association::PendingOpenFiles::new())
+        .manage(quick_note::QuickNoteState::new())
+        .manage(manuscript_watch::ManuscriptWatch::new())
+        .manage(project_lock::ProjectLocks::new())
+        .manage(project_unlock::UnlockState::new())
+        .manage(clipboard::ClipboardState::new())
+        .manage(theme::ThemeState::new())
+        .manage(proxy::HttpClients::new())
+        .manage(network::NetworkState::new())
+        .manage(idle::IdleState::new())
+        .manage(telemetry::TelemetryState::load_default())
+        .manage(updater::UpdaterState::load(env!("CARGO_PKG_VERSION")))
+        .invoke_handler(ipc_scope::scoped(tauri::generate_handler![
             start_backend_server,
             stop_backend_server,
             check_backend_health,
             cleanup::get_data_categories,
-            cleanup::delete_data_category
-        ])
+            cleanup::preview_delete,
+            cleanup::delete_data_category,
+            cleanup::export_storage_report,
+            cleanup::apply_snapshot_retention,
+            snapshots::list_snapshots,
+            snapshots::create_snapshot,
+            snapshots::restore_snapshot,
+            database::compact_database,
+            database::check_database,
+            db_encryption::get_database_encryption,
+            db_encryption::encrypt_database,
+            project_unlock::get_unlock_state,
+            project_unlock::open_unlock_window,
+            project_unlock::unlock_project,
+            project_unlock::lock_project,
+            project_unlock::set_project_password,
+            logging::set_log_level,
+            logging::get_recent_logs,
+            audit::get_audit_log,
+            audit::record_settings_reset,
+            logging::log_frontend_error,
+            feedback::create_feedback_bundle,
+            user_guide::open_user_guide,
+            onboarding::get_onboarding_state,
+            onboarding::advance_onboarding,
+            accessibility::get_accessibility_preferences,
+            accessibility::set_ui_scale,
+            ollama::ollama_status,
+            ollama::start_ollama,
+            ollama::stop_ollama,
+            ollama::pull_ollama_model,
+            ollama::cancel_ollama_pull,
+            ollama::delete_ollama_model,
+            ollama_install::install_ollama,
+            downloads::download_model,
+            download_queue::list_downloads,
+            download_queue::pause_download,
+            download_queue::resume_download,
+            download_queue::cancel_download,
+            integrity::verify_models,
+            integrity::repair_models,
+            spacy_install::install_spacy_model,
+            preflight::check_models,
+            models_location::set_models_location,
+            model_bundle::import_model_bundle,
+            model_catalog::get_model_catalog,
+            model_catalog::recommend_models,
+            model_dedup::find_duplicate_models,
+            model_dedup::dedup_models,
+            dev_console::toggle_developer_console,
+            settings::get_settings,
+            settings::update_settings,
+            crash::list_crash_reports,
+            crash::get_crash_report,
+            crash::export_crash_report,
+            crash::dismiss_crash_report,
+            telemetry::record_telemetry_event,
+            telemetry::get_telemetry_queue,
+            telemetry::clear_telemetry,
+            telemetry::upload_telemetry,
+            updater::check_for_updates,
+            updater::download_update,
+            updater::install_update,
+            windows::open_project_window,
+            deep_link::take_pending_deep_link,
+            file_association::take_pending_open_files,
+            quick_note::set_current_project,
+            quick_note::set_quick_note_shortcut,
+            quick_note::get_quick_note_context,
+            quick_note::save_quick_note,
+            secrets::store_secret,
+            secrets::get_secret,
+            secrets::delete_secret,
+            clipboard::copy_to_clipboard,
+            print::print_report,
+            capture::capture_view,
+            theme::get_system_theme,
+            theme::set_window_theme,
+            proxy::set_proxy_settings,
+            file_dialogs::pick_manuscript,
+            file_dialogs::pick_project,
+            file_dialogs::pick_chapter_folder,
+            export_scope::pick_export_path,
+            folder_import::plan_folder_import,
+            folder_import::import_folder,
+            manuscript_preview::preview_manuscript,
+            pdf_text::extract_pdf_text,
+            scrivener::import_scrivener,
+            report_pdf::export_report_pdf,
+            annotated_docx::export_annotated_docx,
+            glossary_export::export_glossary,
+            visualization_export::export_visualization,
+            backend_auth::get_backend_session_token,
+            backend_proxy::backend_request,
+            status_channel::status_replay,
+            status_channel::watch_analysis,
+            status_channel::unwatch_analysis,
+            project_archive::export_project_archive,
+            project_archive::import_project_archive,
+            manuscript_watch::refresh_manuscript_watch,
+            chapter_split::split_chapters,
+            text_encoding::normalize_text_import,
+            remote_llm::get_remote_llm,
+            remote_llm::set_remote_llm,
+            network::check_connectivity,
+            network::get_network_status,
+            hardware::get_hardware_info,
+            idle::get_idle_state,
+            badge::set_badge_count,
+            progress::set_progress,
+            autostart::set_autostart,
+            session::save_session,
+            import_digest::check_duplicate_import,
+            import_digest::record_import,
+            project_templates::list_project_templates,
+            project_templates::create_project_from_template,
+            edit_recovery::save_edit_buffer,
+            edit_recovery::clear_edit_buffer,
+            edit_recovery::list_recovered_edits,
+            edit_recovery::discard_recovered_edits,
+            project_list::list_projects_fast,
+            quick_stats::quick_stats,
+            project_lock::acquire_project_lock,
+            project_lock::release_project_lock,
+            recent_projects::list_recent_projects,
+            recent_projects::record_recent_project,
+            recent_projects::pin_project,
+            recent_projects::remove_recent,
+            session::take_pending_session_restore,
+            commands::list_commands,
+            commands::invoke_command,
+            plugins::list_plugins,
+            plugins::invoke_plugin_command
+        ]))
         .setup(|app| {
+            // Icono de bandeja (estado del backend y modo segundo plano)
+            if let Err(e) = tray::create_tray(app.handle()) {
+                tracing::warn!(target: "setup", "No se pudo crear el icono de bandeja: {}", e);
+            }
+
             // Configurar menu nativo
             let menu = menu::create_menu(app.handle())?;
             app.set_menu(menu)?;
@@ -401,58 +815,156 @@ fn main() {
             // En Tauri 2.0, on_menu_event debe llamarse en App, no en Builder
             app.on_menu_event(|app_handle, event| {
                 let id = event.id();
-                println!("[Menu] on_menu_event fired, id={:?}", id);
+                tracing::debug!(target: "menu", "on_menu_event fired, id={:?}", id);
                 menu::handle_menu_event(app_handle, id.as_ref());
             });
 
-            // Forzar la ventana a primer plano (fix para cuando se lanza desde el instalador NSIS)
-            // Cuando NSIS lanza la app después de la instalación, puede hacerlo en un contexto
-            // diferente que causa que la ventana aparezca minimizada o detrás de otras ventanas
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-                // En Windows, también intentar traer al frente
-                #[cfg(target_os = "windows")]
-                {
-                    let _ = window.set_always_on_top(true);
-                    let _ = window.set_always_on_top(false);
-                }
+            // Enlaces narrassist:// desde los informes exportados
+            deep_link::setup(app.handle());
+
+            // Proyecto o manuscrito abierto desde el explorador de archivos (Windows/Linux)
+            let args: Vec<String> = std::env::args().collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            file_association::handle_args(app.handle(), &args, &cwd);
+
+            // Descargas de modelos que quedaron pendientes al cerrar
+            download_queue::restore(app.handle());
+
+            // Atajo global de notas rápidas
+            quick_note::setup(app.handle());
+
+            // Tema del sistema y tema elegido para los marcos de ventana
+            theme::setup(app.handle());
+
+            // Restaurar tamaño y posición de la sesión anterior. La ventana principal
+            // arranca oculta y se muestra cuando el backend está listo (ver splash.rs).
+            if let Some(window) = app.get_webview_window(windows::MAIN_WINDOW) {
+                window_state::restore(app.handle(), &window);
+            }
+            // Arranque al iniciar sesion: sin ventanas, solo la bandeja (ver autostart.rs)
+            autostart::setup(app.handle());
+            if autostart::start_hidden(app.handle()) {
+                tracing::info!(target: "setup", "Arranque al iniciar sesion: la app queda en la bandeja");
+                splash::stay_in_tray();
+            } else if let Err(e) = splash::create(app.handle()) {
+                tracing::warn!(target: "setup", "No se pudo crear la ventana de carga: {}", e);
+                splash::reveal_main(app.handle());
+            } else {
+                // Nunca dejar al usuario solo con la ventana de carga
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(splash::SPLASH_MAX_WAIT).await;
+                    splash::reveal_main(&app_handle);
+                });
+            }
+
+            // Migrar ~/.narrative_assistant al layout actual antes de que el backend lo abra
+            match cleanup::run_layout_migrations() {
+                Ok(Some(report)) if !report.applied.is_empty() => tracing::info!(
+                    target: "setup",
+                    "Layout de datos migrado v{} -> v{}: {}",
+                    report.from_version,
+                    report.to_version,
+                    report.applied.join(", ")
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::error!(target: "setup", "Error migrando layout de datos: {}", e),
             }
 
+            // Aplicar la retención de copias automáticas cuando el usuario no esté escribiendo
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                idle::wait_until_idle(&app_handle, RETENTION_MAX_DELAY).await;
+                let store = app_handle.state::<settings::SettingsStore>();
+                match cleanup::apply_snapshot_retention(store, None).await {
+                    Ok(report) if !report.deleted.is_empty() => tracing::info!(
+                        target: "setup",
+                        "Retencion de snapshots: {} eliminados, {} conservados",
+                        report.deleted.len(),
+                        report.kept
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!(target: "setup", "Error aplicando retencion de snapshots: {}", e),
+                }
+            });
+
+            // Copias automaticas de la base de datos con un proyecto abierto
+            tauri::async_runtime::spawn(snapshots::scheduler(app.handle().clone()));
+
+            // Cambios externos en los manuscritos importados (manuscript-changed)
+            tauri::async_runtime::spawn(manuscript_watch::monitor(app.handle().clone()));
+            tauri::async_runtime::spawn(project_lock::monitor(app.handle().clone()));
+
+            // Tamaño de texto y movimiento del sistema (accessibility-changed)
+            tauri::async_runtime::spawn(accessibility::monitor(app.handle().clone()));
+
+            // Monitor de inactividad (user-idle / user-active)
+            tauri::async_runtime::spawn(idle::monitor(app.handle().clone()));
+
+            // Precarga del LLM y los embeddings con el usuario inactivo y enchufado
+            tauri::async_runtime::spawn(warmup::scheduler(app.handle().clone()));
+
+            // Monitor de conectividad (network-status)
+            tauri::async_runtime::spawn(network::monitor(app.handle().clone()));
+
+            // Comprobacion semanal de actualizaciones (si esta activada)
+            tauri::async_runtime::spawn(updater::background_checks(app.handle().clone()));
+
+            // Envio de telemetria (solo con consentimiento)
+            tauri::async_runtime::spawn(telemetry::background_uploads(app.handle().clone()));
+
+            // Bloqueo por inactividad de la base de datos protegida con contraseña
+            tauri::async_runtime::spawn(project_unlock::auto_lock(app.handle().clone()));
+
             // Iniciar el backend automaticamente al arrancar la app
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 // Esperar un poco para que la ventana este lista
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
+                // Con la base de datos protegida, pedir antes la contraseña
+                if project_unlock::is_locked() {
+                    splash::reveal_main(&app_handle);
+                    project_unlock::wait_until_unlocked(&app_handle).await;
+                }
+
                 // Obtener el estado del servidor
                 let server_state = app_handle.state::<BackendServer>();
 
                 // Intentar iniciar el servidor
                 match start_backend_server(app_handle.clone(), server_state).await {
                     Ok(msg) => {
-                        println!("[Setup] {}", msg);
+                        tracing::info!(target: "setup", "{}", msg);
 
                         // HI-12: Distinguish "fully ready" from "warming up"
                         let is_warming = msg == BACKEND_WARMING_MSG;
                         if is_warming {
+                            // Mantener la ventana de carga hasta que pase el health check
+                            let splash_handle = app_handle.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let deadline = tokio::time::Instant::now() + splash::SPLASH_MAX_WAIT;
+                                while tokio::time::Instant::now() < deadline {
+                                    if poll_health_ready().await {
+                                        preflight::run(&splash_handle);
+                                        splash::emit_progress(&splash_handle, splash::BootPhase::Ready);
+                                        session::offer_restore(&splash_handle);
+                                        ollama::check_version(&splash_handle).await;
+                                        break;
+                                    }
+                                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                                }
+                                splash::reveal_main(&splash_handle);
+                            });
                             // Process alive but modules still loading — emit "starting"
-                            let _ = app_handle.emit(
-                                "backend-status",
-                                serde_json::json!({
-                                    "status": "starting",
-                                    "message": "Servidor iniciado, cargando módulos..."
-                                }),
-                            );
+                            status_channel::backend_status(&app_handle, "starting", Message::new("backend.loading_modules"));
                         } else {
                             // Fully ready
-                            let _ = app_handle.emit(
-                                "backend-status",
-                                serde_json::json!({
-                                    "status": "running",
-                                    "message": msg
-                                }),
-                            );
+                            preflight::run(&app_handle);
+                            splash::emit_progress(&app_handle, splash::BootPhase::Ready);
+                            splash::reveal_main(&app_handle);
+                            status_channel::backend_status(&app_handle, "running", Message::new("backend.started"));
+                            session::offer_restore(&app_handle);
+                            ollama::check_version(&app_handle).await;
                         }
 
                         // HI-12: Always start watchdog when process is alive (Ok branch).
@@ -465,35 +977,81 @@ fn main() {
                         }
                     }
                     Err(e) => {
-                        eprintln!("[Setup Error] Failed to start backend: {}", e);
+                        tracing::error!(target: "setup", "Failed to start backend: {}", e);
+                        // Mostrar la interfaz para que el usuario vea el error
+                        splash::reveal_main(&app_handle);
                         // Emitir evento de error al frontend
-                        let _ = app_handle.emit(
-                            "backend-status",
-                            serde_json::json!({
-                                "status": "error",
-                                "message": format!("Error iniciando servidor: {}", e)
-                            }),
-                        );
+                        status_channel::backend_status(&app_handle, "error", Message::new("backend.start_error").with("error", e));
                     }
                 }
             });
 
             Ok(())
         })
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                // Señalar al watchdog que pare antes de matar el backend
-                let server_state = window.state::<BackendServer>();
-                server_state.shutting_down.store(true, Ordering::Relaxed);
-
-                // Detener el backend al cerrar la ventana
-                tauri::async_runtime::block_on(async {
-                    let _ = stop_backend_server(server_state).await;
-                });
+        .on_window_event(|window, event| match event {
+            // Las ventanas de carga, de notas, de logs, de desbloqueo, de la guía y de impresión no guardan geometría ni reciben el foco de los menús
+            _ if splash::is_splash(window.label())
+                || quick_note::is_quick_note(window.label())
+                || dev_console::is_dev_console(window.label())
+                || project_unlock::is_unlock_window(window.label())
+                || user_guide::is_user_guide(window.label())
+                || print::is_print_window(window.label()) => {}
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                window_state::track(window);
+            }
+            tauri::WindowEvent::Focused(true) => {
+                windows::set_active(window.app_handle(), window.label());
             }
+            tauri::WindowEvent::ThemeChanged(theme) => {
+                theme::on_theme_changed(window.app_handle(), *theme);
+            }
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                drop_import::on_drop(window, paths);
+            }
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                window_state::save_now(window);
+                // Modo segundo plano: ocultar en la bandeja sin parar el backend
+                let settings = window.state::<settings::SettingsStore>().get();
+                if window.label() == windows::MAIN_WINDOW
+                    && settings.minimize_to_tray
+                    && tray::is_available(window.app_handle())
+                {
+                    api.prevent_close();
+                    let _ = window.hide();
+                    return;
+                }
+                // El backend es compartido: solo se para al cerrar la última ventana
+                if window
+                    .state::<BackendServer>()
+                    .release_window(window.label())
+                {
+                    shutdown_backend(window.app_handle());
+                    updater::install_staged_on_exit(window.app_handle());
+                }
+            }
+            tauri::WindowEvent::Destroyed => {
+                project_lock::release_window(window.app_handle(), window.label());
+                edit_recovery::release_window(window.app_handle(), window.label());
+            }
+            _ => {}
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!());
+    let app = match result {
+        Ok(app) => app,
+        Err(e) => {
+            tracing::error!(target: "setup", "error while running tauri application: {}", e);
+            crash::record_runtime_error(&recent_logs, &e);
+            std::process::exit(1);
+        }
+    };
+
+    app.run(|_app_handle, _event| {
+        // macOS entrega los archivos asociados como Apple Event, no como argumentos
+        #[cfg(target_os = "macos")]
+        if let tauri::RunEvent::Opened { urls } = _event {
+            file_association::handle_urls(_app_handle, &urls);
+        }
+    });
 }
 
 #[cfg(not(debug_assertions))]
@@ -580,6 +1138,12 @@ fn spawn_embedded_backend(app: &AppHandle) -> Result<Child, String> {
         }
     }
 
+    // Modelos de spaCy instalados por el usuario si el Python embebido no es escribible
+    if let Some(user_site) = spacy_install::user_site_packages().filter(|dir| dir.is_dir()) {
+        python_path_env.push_str(path_separator);
+        python_path_env.push_str(&user_site.display().to_string());
+    }
+
     if let Ok(existing) = std::env::var("PYTHONPATH") {
         if !existing.is_empty() {
             python_path_env.push_str(path_separator);
@@ -604,10 +1168,40 @@ fn spawn_embedded_backend(app: &AppHandle) -> Result<Child, String> {
         .env("PYTHONPATH", python_path_env)
         .env("PYTHONHOME", &python_home)
         .env("NA_EMBEDDED", "1")
+        .env(backend_auth::TOKEN_ENV, backend_auth::session_token())
+        .env(backend_auth::SHELL_TOKEN_ENV, backend_auth::shell_token())
         .env("NA_RESOURCE_DIR", &resource_dir)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
+    // Directorio de datos elegido en preferencias
+    if let Some(data_dir) = app.state::<settings::SettingsStore>().get().data_dir {
+        command.env("NA_DATA_DIR", data_dir);
+    }
+    // Directorio de modelos (movido con set_models_location)
+    if let Some(models_dir) = app.state::<settings::SettingsStore>().get().models_dir {
+        command.env("NA_MODELS_DIR", models_dir);
+    }
+
+    // Claves de API del almacén del sistema (nunca en ficheros ni argumentos)
+    command.envs(secrets::backend_env());
+
+    // Clave de la base de datos cifrada (db_encryption.rs)
+    command.envs(db_encryption::backend_env());
+
+    // Analizador remoto compatible con OpenAI en lugar de Ollama
+    command.envs(remote_llm::backend_env(
+        &app.state::<settings::SettingsStore>().get().remote_llm,
+    ));
+
+    // Proxy de las preferencias para las descargas de modelos
+    proxy::apply_backend_env(app, &mut command);
+
+    // Puntos de entrada de los complementos instalados
+    if let Some(plugins) = app.state::<plugins::PluginRegistry>().backend_env() {
+        command.env("NA_PLUGINS", plugins);
+    }
+
     // En macOS, Python.framework necesita DYLD_FRAMEWORK_PATH para encontrar la libreria
     #[cfg(target_os = "macos")]
     {
@@ -631,7 +1225,7 @@ fn spawn_embedded_backend(app: &AppHandle) -> Result<Child, String> {
                 .join("3.12")
                 .join("Python");
             if let Err(e) = symlink(&relative_target, &python_symlink) {
-                eprintln!("[TAURI] Failed to create Python symlink: {}", e);
+                tracing::error!(target: "setup", "Failed to create Python symlink: {}", e);
             }
         }
     }
@@ -658,15 +1252,10 @@ where
         let buf_reader = BufReader::new(reader);
         for line in buf_reader.lines() {
             match line {
-                Ok(content) => {
-                    if label == "stderr" {
-                        eprintln!("[Backend {}] {}", label, content);
-                    } else {
-                        println!("[Backend {}] {}", label, content);
-                    }
-                }
+                // El backend escribe su logging en stderr: no implica error
+                Ok(content) => tracing::info!(target: "backend", stream = label, "{}", content),
                 Err(err) => {
-                    eprintln!("[Backend {}] Error leyendo salida: {}", label, err);
+                    tracing::error!(target: "backend", stream = label, "Error leyendo salida: {}", err);
                     break;
                 }
             }
@@ -801,4 +1390,14 @@ mod tests {
 
         assert!(!ready);
     }
+
+    #[test]
+    fn backend_stops_only_when_last_window_is_released() {
+        let server = BackendServer::new();
+        server.attach_window("project-7");
+
+        assert!(!server.release_window(windows::MAIN_WINDOW));
+        assert!(!server.release_window("project-unknown"));
+        assert!(server.release_window("project-7"));
+    }
 }
@@ -0,0 +1,619 @@
+// Vista previa rápida de manuscritos en Narrative Assistant
+//
+// Importar un manuscrito en el backend (python-docx/ebooklib, detección de
+// estructura) tarda varios segundos en novelas largas. Para que el diálogo de
+// nuevo proyecto muestre palabras y capítulos nada más elegir el archivo, aquí
+// se leen los XML del paquete en streaming (zip + quick-xml).
+//
+// DOCX (`word/document.xml`):
+// - Texto plano de cada párrafo (`w:t`, `w:tab`, `w:br`); no se mira el
+//   formato ni las revisiones borradas (`w:delText`).
+// - Capítulos: párrafos de nivel 1, por estilo (`word/styles.xml`,
+//   "heading 1" o `w:outlineLvl` 0, sea cual sea el id localizado del estilo).
+//
+// EPUB (`META-INF/container.xml` → OPF → documentos del `spine` en orden):
+// - Texto de los bloques XHTML (`p`, `div`, `h1`…), sin `head`/`script`/`style`.
+// - Capítulos: los `h1`; un documento del spine sin ninguno aporta su primer
+//   título de cualquier nivel (muchos ebooks autoeditados usan `h2`).
+//
+// En ambos, también los párrafos "Capítulo 3", "Chapter 3", "Parte 2", como
+// `CHAPTER_PATTERNS` de parsers/docx_parser.py.
+//
+// TXT y MD: capítulos de chapter_split.rs, con los patrones configurados.
+//
+// Es una estimación: la estructura definitiva la decide el backend al importar.
+
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek};
+use std::path::Path;
+use std::time::Instant;
+use tauri::State;
+
+use crate::chapter_split::{self, Splitter};
+use crate::settings::SettingsStore;
+
+/// Resumen del manuscrito para el diálogo de importación
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManuscriptPreview {
+    pub word_count: usize,
+    /// Caracteres con espacios, sin saltos de párrafo
+    pub char_count: usize,
+    /// Párrafos con texto
+    pub paragraph_count: usize,
+    /// Títulos de capítulo detectados, en orden
+    pub chapters: Vec<String>,
+    /// Texto de cada párrafo, solo si se pide (`docx_paragraphs`)
+    #[serde(skip)]
+    pub paragraphs: Option<Vec<String>>,
+}
+
+impl ManuscriptPreview {
+    /// Cuenta un párrafo; `heading`: título de nivel 1
+    fn push_paragraph(&mut self, text: &str, heading: bool) -> bool {
+        let words = text.split_whitespace().count();
+        if words == 0 {
+            return false;
+        }
+        self.word_count += words;
+        self.char_count += text.trim().chars().count();
+        self.paragraph_count += 1;
+        if let Some(paragraphs) = &mut self.paragraphs {
+            paragraphs.push(text.trim().to_string());
+        }
+        let chapter = heading || is_chapter_title(text);
+        if chapter {
+            self.chapters
+                .push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+        chapter
+    }
+}
+
+/// Prefijos de título de capítulo seguidos de un número
+const CHAPTER_PREFIXES: &[&str] = &["capítulo", "capitulo", "chapter", "parte"];
+
+fn is_chapter_title(text: &str) -> bool {
+    let lower = text.trim().to_lowercase();
+    CHAPTER_PREFIXES.iter().any(|prefix| {
+        lower.strip_prefix(prefix).is_some_and(|rest| {
+            rest.starts_with(char::is_whitespace)
+                && rest.trim_start().starts_with(|c: char| c.is_ascii_digit())
+        })
+    })
+}
+
+/// Valor de `w:val` (o del atributo `local` con cualquier prefijo)
+pub(crate) fn attribute(e: &BytesStart, local: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == local)
+        .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+}
+
+/// Referencia `&...;` en el texto. Las entidades HTML con nombre (`&nbsp;`)
+/// no existen en XML y cuentan como espacio.
+pub(crate) fn push_reference(text: &mut String, e: &BytesRef) {
+    if let Ok(Some(c)) = e.resolve_char_ref() {
+        text.push(c);
+    } else if let Some(entity) = e
+        .decode()
+        .ok()
+        .and_then(|name| quick_xml::escape::resolve_predefined_entity(&name))
+    {
+        text.push_str(entity);
+    } else {
+        text.push(' ');
+    }
+}
+
+/// Nivel de esquema 1-based a partir de `w:outlineLvl` (0 = nivel 1)
+fn outline_level(e: &BytesStart) -> Option<u8> {
+    attribute(e, b"val")?.parse::<u8>().ok().map(|l| l + 1)
+}
+
+/// Nivel de título de cada estilo de párrafo (id → nivel)
+fn heading_styles<R: BufRead>(xml: R) -> Result<HashMap<String, u8>, String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut levels = HashMap::new();
+    let mut current: Option<(String, Option<u8>)> = None;
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("styles.xml no valido: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"style" => current = attribute(&e, b"styleId").map(|id| (id, None)),
+                b"name" => {
+                    if let Some((_, level)) = current.as_mut() {
+                        let name = attribute(&e, b"val").unwrap_or_default().to_lowercase();
+                        if let Some(n) = name.strip_prefix("heading ") {
+                            *level = level.or(n.trim().parse().ok());
+                        }
+                    }
+                }
+                b"outlineLvl" => {
+                    if let Some((_, level)) = current.as_mut() {
+                        *level = outline_level(&e).or(*level);
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) if e.local_name().as_ref() == b"style" => {
+                if let Some((id, Some(level))) = current.take() {
+                    levels.insert(id, level);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(levels)
+}
+
+/// Recorre `document.xml` contando palabras y recogiendo capítulos
+fn scan_document<R: BufRead>(
+    xml: R,
+    styles: &HashMap<String, u8>,
+    mut preview: ManuscriptPreview,
+) -> Result<ManuscriptPreview, String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut text = String::new();
+    let mut level: Option<u8> = None;
+    let mut in_text = false;
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("document.xml no valido: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"p" => {
+                    text.clear();
+                    level = None;
+                }
+                b"pStyle" => {
+                    level = level
+                        .or_else(|| attribute(&e, b"val").and_then(|id| styles.get(&id).copied()))
+                }
+                // El nivel del propio párrafo manda sobre el del estilo
+                b"outlineLvl" => level = outline_level(&e).or(level),
+                b"t" => in_text = true,
+                b"tab" | b"br" | b"cr" => text.push(' '),
+                _ => {}
+            },
+            Event::Text(e) if in_text => {
+                text.push_str(&e.decode().map_err(|e| e.to_string())?);
+            }
+            Event::GeneralRef(e) if in_text => push_reference(&mut text, &e),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => {
+                    preview.push_paragraph(&text, level == Some(1));
+                    text.clear();
+                    level = None;
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(preview)
+}
+
+fn preview_docx<R: Read + Seek>(reader: R) -> Result<ManuscriptPreview, String> {
+    read_docx(reader, ManuscriptPreview::default())
+}
+
+fn read_docx<R: Read + Seek>(
+    reader: R,
+    preview: ManuscriptPreview,
+) -> Result<ManuscriptPreview, String> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| format!("DOCX no valido: {}", e))?;
+    // styles.xml es opcional: sin él solo se detectan capítulos por texto
+    let styles = match archive.by_name("word/styles.xml") {
+        Ok(entry) => heading_styles(BufReader::new(entry))?,
+        Err(_) => HashMap::new(),
+    };
+    let document = archive
+        .by_name("word/document.xml")
+        .map_err(|_| "DOCX no valido: falta word/document.xml".to_string())?;
+    scan_document(BufReader::new(document), &styles, preview)
+}
+
+/// Párrafos con texto de un `.docx` (importación por carpetas, folder_import.rs)
+pub(crate) fn docx_paragraphs(path: &Path) -> Result<Vec<String>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("No se pudo abrir {}: {}", path.display(), e))?;
+    let with_text = ManuscriptPreview {
+        paragraphs: Some(Vec::new()),
+        ..Default::default()
+    };
+    Ok(read_docx(file, with_text)?.paragraphs.unwrap_or_default())
+}
+
+/// Decodifica `%XX` de un `href` del OPF ("Cap%C3%ADtulo%201.xhtml")
+fn percent_decode(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Ruta dentro del zip de `href` relativo al directorio del OPF
+fn resolve_href(base_dir: &str, href: &str) -> String {
+    let href = percent_decode(href.split('#').next().unwrap_or_default());
+    let mut parts: Vec<&str> = base_dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Ruta del OPF según `META-INF/container.xml`
+fn opf_path<R: BufRead>(xml: R) -> Result<String, String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("container.xml no valido: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"rootfile" => {
+                if let Some(path) = attribute(&e, b"full-path") {
+                    return Ok(path);
+                }
+            }
+            Event::Eof => return Err("EPUB no valido: container.xml sin rootfile".into()),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Documentos del `spine` en orden de lectura, como rutas dentro del zip
+fn spine_documents<R: BufRead>(xml: R, base_dir: &str) -> Result<Vec<String>, String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut manifest = HashMap::new();
+    let mut spine = Vec::new();
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("OPF no valido: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"item" => {
+                    if let (Some(id), Some(href)) = (attribute(&e, b"id"), attribute(&e, b"href")) {
+                        manifest.insert(id, href);
+                    }
+                }
+                b"itemref" => spine.extend(attribute(&e, b"idref")),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(spine
+        .iter()
+        .filter_map(|id| manifest.get(id))
+        .map(|href| resolve_href(base_dir, href))
+        .collect())
+}
+
+/// Elementos XHTML que delimitan un párrafo
+fn is_block(name: &[u8]) -> bool {
+    matches!(
+        name,
+        b"p" | b"div"
+            | b"li"
+            | b"blockquote"
+            | b"td"
+            | b"section"
+            | b"h1"
+            | b"h2"
+            | b"h3"
+            | b"h4"
+            | b"h5"
+            | b"h6"
+    )
+}
+
+/// Nivel de `h1`…`h6`
+fn heading_level(name: &[u8]) -> Option<u8> {
+    match name {
+        [b'h', level @ b'1'..=b'6'] => Some(level - b'0'),
+        _ => None,
+    }
+}
+
+/// Añade un documento XHTML del spine a `preview`
+fn scan_xhtml<R: BufRead>(xml: R, preview: &mut ManuscriptPreview) -> Result<(), String> {
+    let mut reader = Reader::from_reader(xml);
+    // Muchos EPUB autoeditados no son XHTML estricto
+    reader.config_mut().check_end_names = false;
+    let mut buf = Vec::new();
+    let mut text = String::new();
+    let mut level: Option<u8> = None;
+    let mut skip_depth = 0_usize;
+    let mut has_chapter = false;
+    let mut first_heading: Option<String> = None;
+    let mut flush = |text: &mut String, level: Option<u8>, preview: &mut ManuscriptPreview| {
+        if level.is_some() && first_heading.is_none() && !text.trim().is_empty() {
+            first_heading = Some(text.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+        has_chapter |= preview.push_paragraph(text, level == Some(1));
+        text.clear();
+    };
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("XHTML no valido: {}", e))?
+        {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"head" | b"script" | b"style" => skip_depth += 1,
+                name if is_block(name) => {
+                    flush(&mut text, level, preview);
+                    level = heading_level(name).or(level);
+                }
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"br" => text.push(' '),
+            Event::Text(e) if skip_depth == 0 => {
+                text.push_str(&e.decode().map_err(|e| e.to_string())?);
+            }
+            Event::GeneralRef(e) if skip_depth == 0 => push_reference(&mut text, &e),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"head" | b"script" | b"style" => skip_depth = skip_depth.saturating_sub(1),
+                name if is_block(name) => {
+                    flush(&mut text, level, preview);
+                    if heading_level(name).is_some() {
+                        level = None;
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    flush(&mut text, level, preview);
+    if !has_chapter {
+        preview.chapters.extend(first_heading);
+    }
+    Ok(())
+}
+
+fn preview_epub<R: Read + Seek>(reader: R) -> Result<ManuscriptPreview, String> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| format!("EPUB no valido: {}", e))?;
+    let opf = {
+        let container = archive
+            .by_name("META-INF/container.xml")
+            .map_err(|_| "EPUB no valido: falta META-INF/container.xml".to_string())?;
+        opf_path(BufReader::new(container))?
+    };
+    let base_dir = opf.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    let documents = {
+        let entry = archive
+            .by_name(&opf)
+            .map_err(|_| format!("EPUB no valido: falta {}", opf))?;
+        spine_documents(BufReader::new(entry), base_dir)?
+    };
+
+    let mut preview = ManuscriptPreview::default();
+    for document in documents {
+        // Un documento del spine que falta no invalida la vista previa
+        if let Ok(entry) = archive.by_name(&document) {
+            scan_xhtml(BufReader::new(entry), &mut preview)?;
+        }
+    }
+    Ok(preview)
+}
+
+fn preview_text(text: &str, splitter: &Splitter) -> ManuscriptPreview {
+    let chapters = chapter_split::split(text, splitter);
+    let lines: Vec<&str> = text.lines().collect();
+    ManuscriptPreview {
+        word_count: chapters.iter().map(|c| c.word_count).sum(),
+        char_count: lines.iter().map(|line| line.trim().chars().count()).sum(),
+        // Bloques separados por líneas en blanco
+        paragraph_count: lines
+            .split(|line| line.trim().is_empty())
+            .filter(|block| !block.is_empty())
+            .count(),
+        chapters: chapters.into_iter().filter_map(|c| c.title).collect(),
+        paragraphs: None,
+    }
+}
+
+/// Formatos que se pueden leer sin el backend
+fn supported_extension(path: &str) -> Result<String, String> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !matches!(extension.as_str(), "docx" | "epub" | "txt" | "md") {
+        return Err("La vista previa solo admite DOCX, EPUB, TXT y MD".into());
+    }
+    Ok(extension)
+}
+
+/// Vista previa de un archivo (bloqueante; también la usa quick_stats.rs)
+pub(crate) fn preview_path(path: &str, splitter: &Splitter) -> Result<ManuscriptPreview, String> {
+    let extension = supported_extension(path)?;
+    let open = || File::open(path).map_err(|e| format!("No se pudo abrir {}: {}", path, e));
+    match extension.as_str() {
+        "docx" => preview_docx(open()?),
+        "epub" => preview_epub(open()?),
+        _ => Ok(preview_text(
+            &chapter_split::read_text(Path::new(path))?,
+            splitter,
+        )),
+    }
+}
+
+/// Palabras y capítulos de un `.docx`, `.epub`, `.txt` o `.md` sin pasar por el backend
+#[tauri::command]
+pub async fn preview_manuscript(
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<ManuscriptPreview, String> {
+    supported_extension(&path)?;
+    let splitter = Splitter::new(&store.get().chapter_split)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let started = Instant::now();
+        let preview = preview_path(&path, &splitter)?;
+        tracing::info!(
+            target: "manuscript_preview",
+            "{}: {} palabras, {} capitulos en {} ms",
+            path,
+            preview.word_count,
+            preview.chapters.len(),
+            started.elapsed().as_millis()
+        );
+        Ok(preview)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de vista previa: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    const STYLES: &str = r#"<w:styles xmlns:w="w">
+        <w:style w:type="paragraph" w:styleId="Ttulo1"><w:name w:val="heading 1"/></w:style>
+        <w:style w:type="paragraph" w:styleId="Ttulo2"><w:name w:val="heading 2"/></w:style>
+        <w:style w:type="paragraph" w:styleId="Parte"><w:name w:val="Parte"/>
+            <w:pPr><w:outlineLvl w:val="0"/></w:pPr></w:style>
+        <w:style w:type="paragraph" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+    </w:styles>"#;
+
+    const DOCUMENT: &str = r#"<w:document xmlns:w="w"><w:body>
+        <w:p><w:pPr><w:pStyle w:val="Ttulo1"/></w:pPr><w:r><w:t>El  faro</w:t></w:r></w:p>
+        <w:p><w:r><w:t xml:space="preserve">Ana y Luis </w:t></w:r><w:r><w:t>llegaron&#44;</w:t><w:tab/><w:t>tarde &amp; cansados.</w:t></w:r></w:p>
+        <w:p><w:pPr><w:pStyle w:val="Ttulo2"/></w:pPr><w:r><w:t>Escena</w:t></w:r></w:p>
+        <w:p><w:r><w:t>Capítulo 2</w:t></w:r></w:p>
+        <w:p><w:pPr><w:pStyle w:val="Parte"/></w:pPr><w:r><w:t>Segunda parte</w:t></w:r></w:p>
+        <w:p><w:r><w:delText>borrado</w:delText><w:t>Fin</w:t></w:r></w:p>
+        <w:p/>
+    </w:body></w:document>"#;
+
+    fn package(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let mut cursor = writer.finish().unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+
+    #[test]
+    fn counts_words_and_detects_chapters_by_style_and_text() {
+        let archive = package(&[("word/styles.xml", STYLES), ("word/document.xml", DOCUMENT)]);
+        let preview = preview_docx(archive).unwrap();
+        assert_eq!(preview.chapters, ["El faro", "Capítulo 2", "Segunda parte"]);
+        // 2 + 7 + 1 + 2 + 2 + 1; el texto borrado no cuenta
+        assert_eq!(preview.word_count, 15);
+        assert_eq!(preview.paragraph_count, 6);
+
+        // Sin styles.xml solo quedan los capítulos por texto
+        let archive = package(&[("word/document.xml", DOCUMENT)]);
+        assert_eq!(preview_docx(archive).unwrap().chapters, ["Capítulo 2"]);
+        assert!(preview_docx(package(&[("otro.xml", "")])).is_err());
+    }
+
+    const CONTAINER: &str = r#"<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+        <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+    </container>"#;
+
+    const OPF: &str = r#"<package xmlns="http://www.idpf.org/2007/opf"><manifest>
+        <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml"/>
+        <item id="c2" href="Texto/Cap%C3%ADtulo%202.xhtml#inicio" media-type="application/xhtml+xml"/>
+        <item id="c1" href="./Texto/cap1.xhtml" media-type="application/xhtml+xml"/>
+        <item id="cover" href="../cover.xhtml" media-type="application/xhtml+xml"/>
+    </manifest><spine><itemref idref="cover"/><itemref idref="c1"/><itemref idref="c2"/></spine></package>"#;
+
+    const CHAPTER_1: &str = r#"<html xmlns="http://www.w3.org/1999/xhtml">
+        <head><title>Ignorado</title><style>p { margin: 0 }</style></head>
+        <body><h1>Uno: <em>El faro</em></h1><p>Ana&nbsp;llegó<br/>tarde.</p><div><p>Luis &amp; ella.</p></div></body></html>"#;
+
+    const CHAPTER_2: &str =
+        r#"<html><body><h2 class="titulo">La tormenta</h2><p>Llovía.</body></html>"#;
+
+    #[test]
+    fn epub_follows_the_spine_and_strips_xhtml() {
+        let archive = package(&[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", OPF),
+            (
+                "cover.xhtml",
+                "<html><body><img src=\"c.jpg\"/></body></html>",
+            ),
+            ("OEBPS/Texto/cap1.xhtml", CHAPTER_1),
+            ("OEBPS/Texto/Capítulo 2.xhtml", CHAPTER_2),
+        ]);
+        let preview = preview_epub(archive).unwrap();
+        // El h2 cuenta como capítulo porque su documento no tiene h1
+        assert_eq!(preview.chapters, ["Uno: El faro", "La tormenta"]);
+        // 3 + 3 + 3 + 2 + 1 (el nav y el head quedan fuera)
+        assert_eq!(preview.word_count, 12);
+        assert_eq!(preview.paragraph_count, 5);
+        assert!(preview_epub(package(&[("mimetype", "application/epub+zip")])).is_err());
+    }
+
+    #[test]
+    fn plain_text_uses_the_chapter_splitter() {
+        let splitter = Splitter::new(&Default::default()).unwrap();
+        let preview = preview_text("# Uno\n\nAna llegó.\nTarde.\n\n# Dos\n\nFin.\n", &splitter);
+        assert_eq!(preview.chapters, ["Uno", "Dos"]);
+        assert_eq!(preview.word_count, 4);
+        assert_eq!(preview.paragraph_count, 4);
+    }
+
+    #[test]
+    fn chapter_titles_need_a_number() {
+        assert!(is_chapter_title("CAPÍTULO 12: La huida"));
+        assert!(is_chapter_title("Chapter 1"));
+        assert!(!is_chapter_title("Capítulo final"));
+        assert!(!is_chapter_title("Partes 3"));
+    }
+}
@@ -0,0 +1,250 @@
+// Vigilancia de los manuscritos importados en Narrative Assistant
+//
+// El análisis trabaja sobre el texto importado: si el autor sigue editando el
+// manuscrito en Word, las alertas se quedan desfasadas sin que nadie lo note.
+// Este monitor vigila con notify el `document_path` de cada proyecto (leído
+// de la base de datos) y emite `manuscript-changed` (`{ project_id, path }`)
+// a la ventana principal cuando cambia, para ofrecer reimportarlo.
+//
+// - Se vigila la carpeta, no el archivo: Word y LibreOffice guardan en un
+//   temporal y lo renombran sobre el original, lo que rompe un watch directo.
+// - Un guardado produce ráfagas de eventos: se espera `DEBOUNCE` sin eventos
+//   y solo se avisa si cambian el tamaño o la fecha de modificación.
+// - La lista de proyectos se lee al arrancar y al llamar a
+//   `refresh_manuscript_watch` (al abrir, crear o importar un proyecto).
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::mpsc;
+
+use crate::database;
+use crate::windows::MAIN_WINDOW;
+
+/// Silencio tras el último evento antes de comprobar los archivos
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Payload de `manuscript-changed`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ManuscriptChanged {
+    pub project_id: i64,
+    pub path: String,
+}
+
+/// Estado del archivo la última vez que se miró
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Fingerprint {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+fn fingerprint(path: &Path) -> Option<Fingerprint> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(Fingerprint {
+        size: metadata.len(),
+        modified: metadata.modified().ok(),
+    })
+}
+
+struct WatchedFile {
+    project_id: i64,
+    /// `None` si no existía (se avisa cuando aparezca)
+    fingerprint: Option<Fingerprint>,
+}
+
+#[derive(Default)]
+struct Watching {
+    watcher: Option<RecommendedWatcher>,
+    dirs: HashSet<PathBuf>,
+    files: HashMap<PathBuf, WatchedFile>,
+}
+
+/// Archivos vigilados, registrado con `.manage()`
+pub struct ManuscriptWatch(Mutex<Watching>);
+
+impl ManuscriptWatch {
+    pub fn new() -> Self {
+        Self(Mutex::new(Watching::default()))
+    }
+}
+
+/// Sustituye los archivos vigilados; los que ya estaban conservan su estado
+fn replace_files(files: &mut HashMap<PathBuf, WatchedFile>, projects: Vec<(i64, PathBuf)>) {
+    let mut next = HashMap::new();
+    for (project_id, path) in projects {
+        let fingerprint = match files.remove(&path) {
+            Some(known) => known.fingerprint,
+            None => fingerprint(&path),
+        };
+        next.insert(
+            path,
+            WatchedFile {
+                project_id,
+                fingerprint,
+            },
+        );
+    }
+    *files = next;
+}
+
+/// Manuscritos vigilados entre `paths` que han cambiado desde la última vez
+fn changed_files(
+    files: &mut HashMap<PathBuf, WatchedFile>,
+    paths: &HashSet<PathBuf>,
+) -> Vec<ManuscriptChanged> {
+    let mut changed = Vec::new();
+    for path in paths {
+        let Some(watched) = files.get_mut(path) else {
+            continue;
+        };
+        let current = fingerprint(path);
+        // Borrado (o a medio renombrar): se avisará cuando vuelva
+        if current.is_some() && current != watched.fingerprint {
+            changed.push(ManuscriptChanged {
+                project_id: watched.project_id,
+                path: path.to_string_lossy().into_owned(),
+            });
+        }
+        watched.fingerprint = current;
+    }
+    changed
+}
+
+/// Manuscritos de los proyectos en la base de datos
+fn project_documents() -> Result<Vec<(i64, PathBuf)>, String> {
+    let db = database::database_path().ok_or("No se encontro la base de datos")?;
+    if !db.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = database::open_read_only(&db)?;
+    let mut statement = conn
+        .prepare("SELECT id, document_path FROM projects WHERE document_path IS NOT NULL")
+        .map_err(|e| format!("Error leyendo los proyectos: {}", e))?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| format!("Error leyendo los proyectos: {}", e))?;
+    Ok(rows
+        .filter_map(Result::ok)
+        .filter(|(_, path)| !path.trim().is_empty())
+        .map(|(id, path)| (id, PathBuf::from(path)))
+        .collect())
+}
+
+/// Vuelve a leer los proyectos y ajusta las carpetas vigiladas
+fn refresh(state: &ManuscriptWatch) -> Result<usize, String> {
+    let documents = project_documents()?;
+    let mut watching = state.0.lock().unwrap();
+    let watching = &mut *watching;
+    replace_files(&mut watching.files, documents);
+
+    let wanted: HashSet<PathBuf> = watching
+        .files
+        .keys()
+        .filter_map(|path| path.parent().map(Path::to_path_buf))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    if let Some(watcher) = watching.watcher.as_mut() {
+        for dir in watching.dirs.difference(&wanted) {
+            let _ = watcher.unwatch(dir);
+        }
+        for dir in wanted.difference(&watching.dirs) {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                tracing::warn!(target: "manuscript_watch", "No se puede vigilar {}: {}", dir.display(), e);
+            }
+        }
+    }
+    watching.dirs = wanted;
+    Ok(watching.files.len())
+}
+
+/// Monitor en segundo plano (se lanza en el setup)
+pub async fn monitor(app: AppHandle) {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<PathBuf>();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            let _ = sender.send(path);
+        }
+    });
+    let state = app.state::<ManuscriptWatch>();
+    match watcher {
+        Ok(watcher) => state.0.lock().unwrap().watcher = Some(watcher),
+        Err(e) => {
+            tracing::warn!(target: "manuscript_watch", "Vigilancia de manuscritos no disponible: {}", e);
+            return;
+        }
+    }
+    match refresh(&state) {
+        Ok(count) => tracing::info!(target: "manuscript_watch", "Vigilando {} manuscritos", count),
+        Err(e) => tracing::warn!(target: "manuscript_watch", "{}", e),
+    }
+
+    while let Some(first) = receiver.recv().await {
+        let mut paths = HashSet::from([first]);
+        while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, receiver.recv()).await {
+            paths.insert(path);
+        }
+        let changed = changed_files(&mut state.0.lock().unwrap().files, &paths);
+        for change in changed {
+            tracing::info!(
+                target: "manuscript_watch",
+                "Manuscrito del proyecto {} modificado: {}",
+                change.project_id,
+                change.path
+            );
+            let _ = app.emit_to(MAIN_WINDOW, "manuscript-changed", change);
+        }
+    }
+}
+
+/// Relee los proyectos (tras abrir, crear o importar uno). Devuelve los manuscritos vigilados.
+#[tauri::command]
+pub async fn refresh_manuscript_watch(state: State<'_, ManuscriptWatch>) -> Result<usize, String> {
+    refresh(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_real_changes_to_watched_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let novel = dir.path().join("novela.docx");
+        let other = dir.path().join("~$novela.docx");
+        std::fs::write(&novel, b"v1").unwrap();
+
+        let mut files = HashMap::new();
+        replace_files(&mut files, vec![(7, novel.clone())]);
+        let events = HashSet::from([novel.clone(), other.clone()]);
+        assert!(changed_files(&mut files, &events).is_empty());
+
+        // Guardado desde Word: temporal renombrado sobre el original
+        std::fs::write(&other, b"bloqueo").unwrap();
+        std::fs::write(dir.path().join("tmp"), b"v2 con cambios").unwrap();
+        std::fs::rename(dir.path().join("tmp"), &novel).unwrap();
+        assert_eq!(
+            changed_files(&mut files, &events),
+            [ManuscriptChanged {
+                project_id: 7,
+                path: novel.to_string_lossy().into_owned(),
+            }]
+        );
+        // El resto de la ráfaga ya no avisa
+        assert!(changed_files(&mut files, &events).is_empty());
+
+        // Al releer los proyectos se conserva lo ya visto
+        std::fs::write(&novel, b"v3, todavia mas larga").unwrap();
+        replace_files(&mut files, vec![(7, novel.clone())]);
+        assert_eq!(changed_files(&mut files, &events).len(), 1);
+    }
+}
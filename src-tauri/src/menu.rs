@@ -1,15 +1,24 @@
 // Menu nativo para Narrative Assistant
 // Proporciona acceso rapido a las funciones principales de la aplicacion
 //
+// Nombres y atajos salen del registro de comandos (commands.rs), que
+// comparte con la paleta de comandos del frontend
+//
 // Atajos de pestañas: Ctrl+1..8 (patron estandar VS Code/Chrome)
 // Orden: Texto(1) Entidades(2) Relaciones(3) Revision(4) Cronologia(5)
 //        Escritura(6) Glosario(7) Resumen(8)
 
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
-    AppHandle, Emitter, Manager, Wry,
+    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    AppHandle, Emitter, EventTarget, Manager, Wry,
 };
 
+use crate::commands::{self, Group};
+use crate::i18n::Text;
+use crate::plugins::{self, PluginRegistry};
+use crate::recent_projects;
+use crate::settings::SettingsStore;
+
 // ---------------------------------------------------------------------------
 // Menu item IDs — el frontend escucha estos strings via "menu-event"
 // ---------------------------------------------------------------------------
@@ -41,6 +50,7 @@ pub mod view_menu {
     pub const TOGGLE_SIDEBAR: &str = "toggle_sidebar";
     pub const TOGGLE_HISTORY: &str = "toggle_history";
     pub const TOGGLE_THEME: &str = "toggle_theme";
+    pub const COMMAND_PALETTE: &str = "command_palette";
 }
 
 /// IDs del menu Analisis
@@ -54,13 +64,14 @@ pub mod help_menu {
     pub const KEYBOARD_SHORTCUTS: &str = "keyboard_shortcuts";
     pub const USER_GUIDE: &str = "user_guide";
     pub const MANAGE_DATA: &str = "manage_data";
+    pub const REPORT_PROBLEM: &str = "report_problem";
     pub const CHECK_UPDATES: &str = "check_updates";
     pub const ABOUT: &str = "about";
 }
 
 /// Todos los IDs de menu personalizados (no incluye predefinidos como Undo/Copy)
 #[cfg(test)]
-const ALL_MENU_IDS: &[&str] = &[
+pub(crate) const ALL_MENU_IDS: &[&str] = &[
     file_menu::NEW_PROJECT,
     file_menu::OPEN_PROJECT,
     file_menu::SAVE_PROJECT,
@@ -82,318 +93,185 @@ const ALL_MENU_IDS: &[&str] = &[
     view_menu::TOGGLE_SIDEBAR,
     view_menu::TOGGLE_HISTORY,
     view_menu::TOGGLE_THEME,
+    view_menu::COMMAND_PALETTE,
     analysis_menu::RUN,
     help_menu::TUTORIAL,
     help_menu::KEYBOARD_SHORTCUTS,
     help_menu::USER_GUIDE,
     help_menu::MANAGE_DATA,
+    help_menu::REPORT_PROBLEM,
     help_menu::CHECK_UPDATES,
     help_menu::ABOUT,
 ];
 
+/// Elemento del menu con el nombre y el atajo del registro de comandos
+fn item(app: &AppHandle, language: &str, id: &str) -> Result<MenuItem<Wry>, tauri::Error> {
+    match commands::find(id) {
+        Some(command) => MenuItem::with_id(
+            app,
+            command.id,
+            command.label.get(language),
+            true,
+            command.shortcut,
+        ),
+        None => {
+            tracing::warn!(target: "menu", "'{}' no esta en el registro de comandos", id);
+            MenuItem::with_id(app, id, id, true, None::<&str>)
+        }
+    }
+}
+
 /// Crea el menu principal de la aplicacion
 pub fn create_menu(app: &AppHandle) -> Result<Menu<Wry>, tauri::Error> {
-    // Menu Archivo
-    let new_project = MenuItem::with_id(
-        app,
-        file_menu::NEW_PROJECT,
-        "Nuevo proyecto...",
-        true,
-        Some("CmdOrCtrl+N"),
-    )?;
-    let open_project = MenuItem::with_id(
-        app,
-        file_menu::OPEN_PROJECT,
-        "Abrir proyecto...",
-        true,
-        Some("CmdOrCtrl+O"),
-    )?;
-    let save_project = MenuItem::with_id(
-        app,
-        file_menu::SAVE_PROJECT,
-        "Guardar proyecto...",
-        true,
-        Some("CmdOrCtrl+S"),
-    )?;
-    let open_file = MenuItem::with_id(
-        app,
-        file_menu::OPEN_FILE,
-        "Abrir archivo .nra...",
-        true,
-        Some("CmdOrCtrl+Shift+O"),
-    )?;
-    let close_project = MenuItem::with_id(
-        app,
-        file_menu::CLOSE_PROJECT,
-        "Cerrar proyecto",
-        true,
-        Some("CmdOrCtrl+W"),
-    )?;
-    let separator1 = PredefinedMenuItem::separator(app)?;
-    let import = MenuItem::with_id(
-        app,
-        file_menu::IMPORT,
-        "Importar manuscrito...",
-        true,
-        Some("CmdOrCtrl+I"),
-    )?;
-    let update_manuscript = MenuItem::with_id(
-        app,
-        file_menu::UPDATE_MANUSCRIPT,
-        "Actualizar manuscrito...",
-        true,
-        None::<&str>,
-    )?;
-    let export = MenuItem::with_id(
-        app,
-        file_menu::EXPORT,
-        "Exportar informe...",
-        true,
-        Some("CmdOrCtrl+E"),
-    )?;
-    let separator2 = PredefinedMenuItem::separator(app)?;
-    let settings = MenuItem::with_id(
-        app,
-        file_menu::SETTINGS,
-        "Configuración...",
-        true,
-        Some("CmdOrCtrl+,"),
-    )?;
-    let separator3 = PredefinedMenuItem::separator(app)?;
-    let quit = PredefinedMenuItem::quit(app, Some("Salir"))?;
+    let language = app.state::<SettingsStore>().get().language;
+    let language = language.as_str();
+    let item = |id: &str| item(app, language, id);
+    let label = |text: Text| Some(text.get(language));
+    let submenu_title = |group: Group| group.label().get(language);
 
+    // Menu Archivo
     let file_submenu = Submenu::with_items(
         app,
-        "Archivo",
+        submenu_title(Group::File),
         true,
         &[
-            &new_project,
-            &open_project,
-            &save_project,
-            &open_file,
-            &close_project,
-            &separator1,
-            &import,
-            &update_manuscript,
-            &export,
-            &separator2,
-            &settings,
-            &separator3,
-            &quit,
+            &item(file_menu::NEW_PROJECT)?,
+            &item(file_menu::OPEN_PROJECT)?,
+            &recent_submenu(app, language)?,
+            &item(file_menu::SAVE_PROJECT)?,
+            &item(file_menu::OPEN_FILE)?,
+            &item(file_menu::CLOSE_PROJECT)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(file_menu::IMPORT)?,
+            &item(file_menu::UPDATE_MANUSCRIPT)?,
+            &item(file_menu::EXPORT)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(file_menu::SETTINGS)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::quit(
+                app,
+                label(Text {
+                    es: "Salir",
+                    en: "Quit",
+                }),
+            )?,
         ],
     )?;
 
     // Menu Edicion (predefinidos del sistema — sin conflictos)
-    let undo = PredefinedMenuItem::undo(app, Some("Deshacer"))?;
-    let redo = PredefinedMenuItem::redo(app, Some("Rehacer"))?;
-    let separator4 = PredefinedMenuItem::separator(app)?;
-    let cut = PredefinedMenuItem::cut(app, Some("Cortar"))?;
-    let copy = PredefinedMenuItem::copy(app, Some("Copiar"))?;
-    let paste = PredefinedMenuItem::paste(app, Some("Pegar"))?;
-    let select_all = PredefinedMenuItem::select_all(app, Some("Seleccionar todo"))?;
-    let separator4b = PredefinedMenuItem::separator(app)?;
-    let find = MenuItem::with_id(app, "find", "Buscar", true, Some("CmdOrCtrl+F"))?;
-
     let edit_submenu = Submenu::with_items(
         app,
-        "Edición",
+        submenu_title(Group::Edit),
         true,
         &[
-            &undo,
-            &redo,
-            &separator4,
-            &cut,
-            &copy,
-            &paste,
-            &select_all,
-            &separator4b,
-            &find,
+            &PredefinedMenuItem::undo(
+                app,
+                label(Text {
+                    es: "Deshacer",
+                    en: "Undo",
+                }),
+            )?,
+            &PredefinedMenuItem::redo(
+                app,
+                label(Text {
+                    es: "Rehacer",
+                    en: "Redo",
+                }),
+            )?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::cut(
+                app,
+                label(Text {
+                    es: "Cortar",
+                    en: "Cut",
+                }),
+            )?,
+            &PredefinedMenuItem::copy(
+                app,
+                label(Text {
+                    es: "Copiar",
+                    en: "Copy",
+                }),
+            )?,
+            &PredefinedMenuItem::paste(
+                app,
+                label(Text {
+                    es: "Pegar",
+                    en: "Paste",
+                }),
+            )?,
+            &PredefinedMenuItem::select_all(
+                app,
+                label(Text {
+                    es: "Seleccionar todo",
+                    en: "Select all",
+                }),
+            )?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(commands::FIND)?,
         ],
     )?;
 
     // Menu Ver — pestañas Ctrl+1..8 en orden visual
-    let view_chapters =
-        MenuItem::with_id(app, view_menu::CHAPTERS, "Texto", true, Some("CmdOrCtrl+1"))?;
-    let view_entities = MenuItem::with_id(
-        app,
-        view_menu::ENTITIES,
-        "Entidades",
-        true,
-        Some("CmdOrCtrl+2"),
-    )?;
-    let view_relationships = MenuItem::with_id(
-        app,
-        view_menu::RELATIONSHIPS,
-        "Relaciones",
-        true,
-        Some("CmdOrCtrl+3"),
-    )?;
-    let view_alerts = MenuItem::with_id(
-        app,
-        view_menu::ALERTS,
-        "Revision",
-        true,
-        Some("CmdOrCtrl+4"),
-    )?;
-    let view_timeline = MenuItem::with_id(
-        app,
-        view_menu::TIMELINE,
-        "Cronologia",
-        true,
-        Some("CmdOrCtrl+5"),
-    )?;
-    let view_style = MenuItem::with_id(
-        app,
-        view_menu::STYLE,
-        "Escritura",
-        true,
-        Some("CmdOrCtrl+6"),
-    )?;
-    let view_glossary = MenuItem::with_id(
-        app,
-        view_menu::GLOSSARY,
-        "Glosario",
-        true,
-        Some("CmdOrCtrl+7"),
-    )?;
-    let view_summary = MenuItem::with_id(
-        app,
-        view_menu::SUMMARY,
-        "Resumen",
-        true,
-        Some("CmdOrCtrl+8"),
-    )?;
-    let separator5 = PredefinedMenuItem::separator(app)?;
-    let toggle_sidebar = MenuItem::with_id(
-        app,
-        view_menu::TOGGLE_SIDEBAR,
-        "Mostrar/ocultar sidebar",
-        true,
-        Some("CmdOrCtrl+B"),
-    )?;
-    let toggle_inspector = MenuItem::with_id(
-        app,
-        view_menu::TOGGLE_INSPECTOR,
-        "Mostrar/ocultar inspector",
-        true,
-        Some("CmdOrCtrl+Shift+I"),
-    )?;
-    let toggle_history = MenuItem::with_id(
-        app,
-        view_menu::TOGGLE_HISTORY,
-        "Mostrar/ocultar historial",
-        true,
-        Some("CmdOrCtrl+Shift+H"),
-    )?;
-    let separator6 = PredefinedMenuItem::separator(app)?;
-    let toggle_theme = MenuItem::with_id(
-        app,
-        view_menu::TOGGLE_THEME,
-        "Cambiar tema",
-        true,
-        Some("CmdOrCtrl+Shift+D"),
-    )?;
-    let separator6b = PredefinedMenuItem::separator(app)?;
-    let fullscreen = PredefinedMenuItem::fullscreen(app, Some("Pantalla completa"))?;
-
     let view_submenu = Submenu::with_items(
         app,
-        "Ver",
+        submenu_title(Group::View),
         true,
         &[
-            &view_chapters,
-            &view_entities,
-            &view_relationships,
-            &view_alerts,
-            &view_timeline,
-            &view_style,
-            &view_glossary,
-            &view_summary,
-            &separator5,
-            &toggle_sidebar,
-            &toggle_inspector,
-            &toggle_history,
-            &separator6,
-            &toggle_theme,
-            &separator6b,
-            &fullscreen,
+            &item(view_menu::CHAPTERS)?,
+            &item(view_menu::ENTITIES)?,
+            &item(view_menu::RELATIONSHIPS)?,
+            &item(view_menu::ALERTS)?,
+            &item(view_menu::TIMELINE)?,
+            &item(view_menu::STYLE)?,
+            &item(view_menu::GLOSSARY)?,
+            &item(view_menu::SUMMARY)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(view_menu::TOGGLE_SIDEBAR)?,
+            &item(view_menu::TOGGLE_INSPECTOR)?,
+            &item(view_menu::TOGGLE_HISTORY)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(view_menu::TOGGLE_THEME)?,
+            &item(view_menu::COMMAND_PALETTE)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::fullscreen(
+                app,
+                label(Text {
+                    es: "Pantalla completa",
+                    en: "Full screen",
+                }),
+            )?,
         ],
     )?;
 
-    // Menu Analisis (sin atajo global — evita conflicto con Ctrl+R del navegador)
-    let run_analysis = MenuItem::with_id(
+    // Menu Analisis
+    let analysis_submenu = Submenu::with_items(
         app,
-        analysis_menu::RUN,
-        "Ejecutar análisis",
+        submenu_title(Group::Analysis),
         true,
-        None::<&str>,
+        &[&item(analysis_menu::RUN)?],
     )?;
-    let analysis_submenu = Submenu::with_items(app, "Análisis", true, &[&run_analysis])?;
 
     // Menu Ayuda
-    let tutorial = MenuItem::with_id(
-        app,
-        help_menu::TUTORIAL,
-        "Tutorial de bienvenida",
-        true,
-        None::<&str>,
-    )?;
-    let keyboard_shortcuts = MenuItem::with_id(
-        app,
-        help_menu::KEYBOARD_SHORTCUTS,
-        "Atajos de teclado",
-        true,
-        Some("CmdOrCtrl+/"),
-    )?;
-    let user_guide = MenuItem::with_id(
-        app,
-        help_menu::USER_GUIDE,
-        "Guia de usuario",
-        true,
-        Some("F1"),
-    )?;
-    let separator8 = PredefinedMenuItem::separator(app)?;
-    let manage_data = MenuItem::with_id(
-        app,
-        help_menu::MANAGE_DATA,
-        "Gestionar datos...",
-        true,
-        None::<&str>,
-    )?;
-    let separator8b = PredefinedMenuItem::separator(app)?;
-    let check_updates = MenuItem::with_id(
-        app,
-        help_menu::CHECK_UPDATES,
-        "Buscar actualizaciones...",
-        true,
-        None::<&str>,
-    )?;
-    let about = MenuItem::with_id(
-        app,
-        help_menu::ABOUT,
-        "Acerca de Narrative Assistant",
-        true,
-        None::<&str>,
-    )?;
-
     let help_submenu = Submenu::with_items(
         app,
-        "Ayuda",
+        submenu_title(Group::Help),
         true,
         &[
-            &tutorial,
-            &keyboard_shortcuts,
-            &user_guide,
-            &separator8,
-            &manage_data,
-            &separator8b,
-            &check_updates,
-            &about,
+            &item(help_menu::TUTORIAL)?,
+            &item(help_menu::KEYBOARD_SHORTCUTS)?,
+            &item(help_menu::USER_GUIDE)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(help_menu::MANAGE_DATA)?,
+            &item(help_menu::REPORT_PROBLEM)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(help_menu::CHECK_UPDATES)?,
+            &item(help_menu::ABOUT)?,
         ],
     )?;
 
-    // Construir menu completo
-    Menu::with_items(
+    // Construir menu completo (Complementos solo si alguno declara elementos)
+    let menu = Menu::with_items(
         app,
         &[
             &file_submenu,
@@ -402,33 +280,104 @@ pub fn create_menu(app: &AppHandle) -> Result<Menu<Wry>, tauri::Error> {
             &analysis_submenu,
             &help_submenu,
         ],
-    )
+    )?;
+    if let Some(plugins_submenu) = plugins_submenu(app, language)? {
+        menu.insert(&plugins_submenu, 4)?;
+    }
+    Ok(menu)
+}
+
+/// Submenu Abrir reciente (recent_projects.rs); desactivado si no hay recientes
+fn recent_submenu(app: &AppHandle, language: &str) -> Result<Submenu<Wry>, tauri::Error> {
+    let items = recent_projects::for_menu(app)
+        .iter()
+        .map(|recent| {
+            let label = if recent.pinned {
+                format!("★ {}", recent.title)
+            } else {
+                recent.title.clone()
+            };
+            MenuItem::with_id(
+                app,
+                recent_projects::menu_id(recent.project_id),
+                label,
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let items: Vec<&dyn IsMenuItem<Wry>> =
+        items.iter().map(|i| i as &dyn IsMenuItem<Wry>).collect();
+    let title = Text {
+        es: "Abrir reciente",
+        en: "Open recent",
+    };
+    Submenu::with_items(app, title.get(language), !items.is_empty(), &items)
+}
+
+/// Submenu con los elementos declarados por los complementos instalados
+fn plugins_submenu(app: &AppHandle, language: &str) -> Result<Option<Submenu<Wry>>, tauri::Error> {
+    let registry = app.state::<PluginRegistry>();
+    let items = registry
+        .plugins()
+        .iter()
+        .flat_map(|plugin| {
+            plugin.manifest.menu.iter().map(|item| {
+                MenuItem::with_id(
+                    app,
+                    plugins::menu_id(&plugin.manifest.id, &item.command),
+                    &item.label,
+                    true,
+                    None::<&str>,
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if items.is_empty() {
+        return Ok(None);
+    }
+    let items: Vec<&dyn IsMenuItem<Wry>> =
+        items.iter().map(|i| i as &dyn IsMenuItem<Wry>).collect();
+    Submenu::with_items(app, Group::Plugins.label().get(language), true, &items).map(Some)
 }
 
 /// Maneja los eventos del menu
 pub fn handle_menu_event(app: &AppHandle, event_id: &str) {
-    println!(
-        "[Menu] Event received: '{}' (len={})",
+    tracing::debug!(
+        target: "menu",
+        "Event received: '{}' (len={})",
         event_id,
         event_id.len()
     );
 
-    // Intentar emitir al frontend via la ventana principal
-    match app.get_webview_window("main") {
-        Some(window) => match window.emit("menu-event", event_id) {
-            Ok(_) => println!("[Menu] Emitted to window 'main' OK"),
-            Err(e) => {
-                println!("[Menu] emit to window failed: {e}, trying app.emit()");
-                if let Err(e2) = app.emit("menu-event", event_id) {
-                    println!("[Menu] app.emit() also failed: {e2}");
-                }
-            }
-        },
-        None => {
+    // Elementos de complementos: los ejecuta la ventana activa con `plugin-command`
+    if plugins::dispatch_menu_item(app, event_id) {
+        return;
+    }
+
+    // Recientes: se abren como un enlace narrassist://project/<id>
+    if let Some(project_id) = recent_projects::project_from_menu_id(event_id) {
+        crate::deep_link::open_project(app, project_id);
+        return;
+    }
+
+    // La guía se abre en su propia ventana (user_guide.rs)
+    if event_id == help_menu::USER_GUIDE {
+        if let Err(e) = crate::user_guide::open(app, None) {
+            tracing::warn!(target: "menu", "{}", e);
+        }
+        return;
+    }
+
+    // Emitir solo a la ventana activa (principal o de proyecto)
+    let label = crate::windows::active_window_label(app);
+    match app.emit_to(EventTarget::webview_window(&label), "menu-event", event_id) {
+        Ok(_) => tracing::debug!(target: "menu", "Emitted to window '{}' OK", label),
+        Err(e) => {
             // Fallback: emitir a todas las ventanas via AppHandle
-            println!("[Menu] Window 'main' not found, using app.emit()");
-            if let Err(e) = app.emit("menu-event", event_id) {
-                println!("[Menu] app.emit() failed: {e}");
+            tracing::warn!(target: "menu", "emit to window '{label}' failed: {e}, trying app.emit()");
+            if let Err(e2) = app.emit("menu-event", event_id) {
+                tracing::error!(target: "menu", "app.emit() also failed: {e2}");
             }
         }
     }
@@ -470,10 +419,10 @@ mod tests {
     /// (para detectar si se anade un item sin actualizar ALL_MENU_IDS)
     #[test]
     fn menu_ids_count_matches_expected() {
-        // 9 archivo + 12 ver + 1 analisis + 6 ayuda = 28
+        // 9 archivo + 13 ver + 1 analisis + 7 ayuda = 30
         assert_eq!(
             ALL_MENU_IDS.len(),
-            28,
+            30,
             "Se cambio el numero de items de menu. Actualizar ALL_MENU_IDS y este test."
         );
     }
@@ -506,11 +455,13 @@ mod tests {
             "toggle_sidebar",
             "toggle_history",
             "toggle_theme",
+            "command_palette",
             "run_analysis",
             "tutorial",
             "keyboard_shortcuts",
             "user_guide",
             "manage_data",
+            "report_problem",
             "check_updates",
             "about",
         ];
@@ -552,6 +503,7 @@ mod tests {
         assert_eq!(view_menu::TOGGLE_SIDEBAR, "toggle_sidebar");
         assert_eq!(view_menu::TOGGLE_HISTORY, "toggle_history");
         assert_eq!(view_menu::TOGGLE_THEME, "toggle_theme");
+        assert_eq!(view_menu::COMMAND_PALETTE, "command_palette");
     }
 
     #[test]
@@ -565,6 +517,7 @@ mod tests {
         assert_eq!(help_menu::KEYBOARD_SHORTCUTS, "keyboard_shortcuts");
         assert_eq!(help_menu::USER_GUIDE, "user_guide");
         assert_eq!(help_menu::MANAGE_DATA, "manage_data");
+        assert_eq!(help_menu::REPORT_PROBLEM, "report_problem");
         assert_eq!(help_menu::CHECK_UPDATES, "check_updates");
         assert_eq!(help_menu::ABOUT, "about");
     }
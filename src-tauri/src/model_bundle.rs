@@ -0,0 +1,401 @@
+// Importación de modelos desde un paquete offline
+//
+// En equipos sin acceso a internet (redes aisladas de editoriales, despachos)
+// la app no puede descargar nunca nada. `import_model_bundle` instala los
+// modelos NLP desde un .zip preparado en otro equipo con
+// scripts/build_model_bundle.py:
+//
+//   bundle.json          {"format": 1, "models": [{"id", "revision", "files": [...]}]}
+//   models/<id>/...      los ficheros del directorio instalado del modelo
+//
+// Cada modelo del paquete tiene que estar en el manifiesto de esta versión de
+// la app y con la misma revisión pinneada. Los ficheros se extraen a
+// .downloads/ comprobando tamaño y SHA-256 contra el manifiesto firmado que
+// trae la app (integrity.rs), no contra bundle.json: un paquete manipulado
+// pasaría su propia comprobación. Un modelo sin sumas firmadas no se importa.
+// Solo si todos los modelos del paquete son correctos se instalan (con su
+// .version y .files.json, como una descarga normal). El progreso se emite
+// como `model-bundle-progress`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::downloads::{self, DownloadsState};
+use crate::integrity::{self, FileEntry};
+use crate::models::{ModelKind, ModelManifest, ModelSpec};
+
+/// Versión del formato de bundle.json que entiende esta app
+const BUNDLE_FORMAT: u32 = 1;
+const BUNDLE_MANIFEST: &str = "bundle.json";
+const MODELS_PREFIX: &str = "models/";
+
+/// Intervalo mínimo entre eventos de progreso
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Deserialize, Debug)]
+struct BundleManifest {
+    format: u32,
+    models: Vec<BundleModel>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BundleModel {
+    id: String,
+    revision: Option<String>,
+    files: Vec<FileEntry>,
+}
+
+/// Progreso de la importación (payload de `model-bundle-progress`)
+#[derive(Serialize, Clone, Debug)]
+pub struct BundleProgress {
+    /// "verifying", "installing" o "completed"
+    pub phase: &'static str,
+    pub model: Option<String>,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Comprueba que un modelo del paquete es el que espera esta versión de la app
+fn expected_spec<'a>(
+    manifest: &'a ModelManifest,
+    model: &BundleModel,
+) -> Result<&'a ModelSpec, String> {
+    let spec = manifest
+        .get(&model.id)
+        .ok_or_else(|| format!("El paquete incluye un modelo desconocido: {}", model.id))?;
+    if spec.kind == ModelKind::Ollama {
+        return Err(format!("{} se instala con Ollama", spec.display_name));
+    }
+    if model.revision != spec.revision {
+        return Err(format!(
+            "El paquete trae {} en la revision {}; esta version de la app necesita {}",
+            spec.display_name,
+            model.revision.as_deref().unwrap_or("(ninguna)"),
+            spec.revision.as_deref().unwrap_or("(ninguna)")
+        ));
+    }
+    if model.files.is_empty() {
+        return Err(format!(
+            "El paquete no contiene ficheros de {}",
+            spec.display_name
+        ));
+    }
+    Ok(spec)
+}
+
+/// Sumas firmadas de `model`: sin ellas el paquete no se puede verificar
+fn signed_files<'a>(
+    signed: &'a HashMap<String, Vec<FileEntry>>,
+    model: &BundleModel,
+) -> Result<&'a [FileEntry], String> {
+    signed
+        .get(&model.id)
+        .filter(|files| !files.is_empty())
+        .map(Vec::as_slice)
+        .ok_or_else(|| {
+            format!(
+                "Esta version de la app no tiene sumas firmadas de {}: no se puede importar",
+                model.id
+            )
+        })
+}
+
+/// Extrae los ficheros `files` de `model` a `dest` comprobando su tamaño y SHA-256
+fn extract_model(
+    archive: &mut zip::ZipArchive<fs::File>,
+    model: &BundleModel,
+    files: &[FileEntry],
+    dest: &Path,
+    on_bytes: &mut dyn FnMut(u64),
+) -> Result<(), String> {
+    let _ = fs::remove_dir_all(dest);
+    let mut buffer = vec![0_u8; 1024 * 1024];
+    for file in files {
+        let relative = downloads::safe_relative(&file.path)
+            .ok_or_else(|| format!("Ruta no valida en el paquete: {}", file.path))?;
+        let name = format!("{}{}/{}", MODELS_PREFIX, model.id, file.path);
+        let mut entry = archive
+            .by_name(&name)
+            .map_err(|_| format!("Falta {} en el paquete", name))?;
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = fs::File::create(&target)
+            .map_err(|e| format!("Error creando {}: {}", target.display(), e))?;
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        loop {
+            let n = entry
+                .read(&mut buffer)
+                .map_err(|e| format!("Error leyendo {}: {}", name, e))?;
+            if n == 0 {
+                break;
+            }
+            out.write_all(&buffer[..n])
+                .map_err(|e| format!("Error escribiendo {}: {}", target.display(), e))?;
+            hasher.update(&buffer[..n]);
+            size += n as u64;
+            on_bytes(n as u64);
+        }
+        let sha256 = downloads::to_hex(&hasher.finalize());
+        if size != file.size || !sha256.eq_ignore_ascii_case(&file.sha256) {
+            return Err(format!("{} esta dañado en el paquete", name));
+        }
+    }
+    Ok(())
+}
+
+/// Importa el paquete `bundle` en `root` y devuelve los ids instalados
+fn import(
+    bundle: &Path,
+    root: &Path,
+    manifest: &ModelManifest,
+    signed: &HashMap<String, Vec<FileEntry>>,
+    on_progress: &mut dyn FnMut(BundleProgress),
+) -> Result<Vec<String>, String> {
+    let file = fs::File::open(bundle)
+        .map_err(|e| format!("No se pudo abrir {}: {}", bundle.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Paquete de modelos no valido: {}", e))?;
+    let contents: BundleManifest = {
+        let mut entry = archive
+            .by_name(BUNDLE_MANIFEST)
+            .map_err(|_| format!("El paquete no contiene {}", BUNDLE_MANIFEST))?;
+        let mut raw = String::new();
+        entry.read_to_string(&mut raw).map_err(|e| e.to_string())?;
+        serde_json::from_str(&raw).map_err(|e| format!("{} no valido: {}", BUNDLE_MANIFEST, e))?
+    };
+    if contents.format != BUNDLE_FORMAT {
+        return Err(format!(
+            "Formato de paquete {} no soportado (esta version lee el {})",
+            contents.format, BUNDLE_FORMAT
+        ));
+    }
+    let specs = contents
+        .models
+        .iter()
+        .map(|model| expected_spec(manifest, model))
+        .collect::<Result<Vec<_>, String>>()?;
+    let expected = contents
+        .models
+        .iter()
+        .map(|model| signed_files(signed, model))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Primero se extrae y verifica todo: un paquete dañado no toca lo instalado
+    let total: u64 = expected
+        .iter()
+        .flat_map(|files| *files)
+        .map(|f| f.size)
+        .sum();
+    let mut processed = 0;
+    let staging = root.join(downloads::DOWNLOADS_DIR).join("bundle");
+    let mut staged: HashMap<&str, PathBuf> = HashMap::new();
+    for (model, files) in contents.models.iter().zip(&expected) {
+        let dest = staging.join(&model.id);
+        let result = extract_model(&mut archive, model, files, &dest, &mut |bytes| {
+            processed += bytes;
+            on_progress(BundleProgress {
+                phase: "verifying",
+                model: Some(model.id.clone()),
+                processed_bytes: processed,
+                total_bytes: total,
+            });
+        });
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+        staged.insert(model.id.as_str(), dest);
+    }
+
+    let mut installed = Vec::new();
+    for spec in specs {
+        on_progress(BundleProgress {
+            phase: "installing",
+            model: Some(spec.id.clone()),
+            processed_bytes: processed,
+            total_bytes: total,
+        });
+        let dir = &staged[spec.id.as_str()];
+        let target = spec
+            .install_dir(root)
+            .ok_or_else(|| format!("{} se instala con Ollama", spec.display_name))?;
+        downloads::write_version_marker(dir, spec)?;
+        downloads::install(dir, &target)?;
+        integrity::record(&target)?;
+        tracing::info!(target: "downloads", "{} importado en {}", spec.name, target.display());
+        installed.push(spec.id.clone());
+    }
+    let _ = fs::remove_dir_all(&staging);
+    Ok(installed)
+}
+
+/// Instala los modelos de un paquete offline (.zip de build_model_bundle.py)
+/// y devuelve los ids instalados
+#[tauri::command]
+pub async fn import_model_bundle(
+    app: AppHandle,
+    state: State<'_, DownloadsState>,
+    zip_path: String,
+) -> Result<Vec<String>, String> {
+    let root = downloads::models_root(&app).ok_or("No se pudo determinar el directorio home")?;
+    let signed = integrity::signed_manifest()?;
+    if !state.begin_exclusive() {
+        return Err("Hay descargas de modelos en curso".into());
+    }
+    let progress_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut last_emit: Option<Instant> = None;
+        import(
+            Path::new(&zip_path),
+            &root,
+            &ModelManifest::bundled(),
+            signed,
+            &mut |progress| {
+                let throttled = progress.phase == "verifying"
+                    && last_emit.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL);
+                if !throttled {
+                    last_emit = Some(Instant::now());
+                    let _ = progress_app.emit("model-bundle-progress", progress);
+                }
+            },
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    state.end_exclusive();
+
+    match &result {
+        Ok(installed) => {
+            let _ = app.emit(
+                "model-bundle-progress",
+                BundleProgress {
+                    phase: "completed",
+                    model: None,
+                    processed_bytes: 0,
+                    total_bytes: 0,
+                },
+            );
+            tracing::info!(target: "downloads", "Paquete importado: {}", installed.join(", "));
+        }
+        Err(e) => tracing::warn!(target: "downloads", "Importacion fallida: {}", e),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILES: [(&str, &[u8]); 2] = [
+        ("config.json", b"{}"),
+        ("1_Pooling/config.json", b"{\"pool\": 1}"),
+    ];
+
+    fn entries(files: &[(&str, &[u8])]) -> Vec<FileEntry> {
+        files
+            .iter()
+            .map(|(name, data)| FileEntry {
+                path: name.to_string(),
+                size: data.len() as u64,
+                sha256: downloads::to_hex(&Sha256::digest(data)),
+            })
+            .collect()
+    }
+
+    /// Sumas firmadas de `embeddings` (las de la app, no las del paquete)
+    fn signed() -> HashMap<String, Vec<FileEntry>> {
+        HashMap::from([("embeddings".to_string(), entries(&FILES))])
+    }
+
+    /// Paquete con `embeddings` en la revisión `revision`; `tamper` altera el contenido
+    fn build_bundle(path: &Path, revision: Option<&str>, tamper: bool) {
+        build_bundle_with(path, revision, &FILES, tamper);
+    }
+
+    /// Paquete con los ficheros `files` y sus sumas en bundle.json
+    fn build_bundle_with(
+        path: &Path,
+        revision: Option<&str>,
+        files: &[(&str, &[u8])],
+        tamper: bool,
+    ) {
+        let entries = entries(files);
+        let manifest = serde_json::json!({
+            "format": 1,
+            "models": [{ "id": "embeddings", "revision": revision, "files": entries }],
+        });
+
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file(BUNDLE_MANIFEST, options).unwrap();
+        writer.write_all(manifest.to_string().as_bytes()).unwrap();
+        for (name, data) in files.iter().copied() {
+            writer
+                .start_file(format!("models/embeddings/{}", name), options)
+                .unwrap();
+            writer
+                .write_all(if tamper { b"otro" } else { data })
+                .unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn bundle_is_verified_and_installed() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = ModelManifest::bundled();
+        let spec = manifest.get("embeddings").unwrap();
+        let bundle = dir.path().join("modelos.zip");
+        build_bundle(&bundle, spec.revision.as_deref(), false);
+
+        let root = dir.path().join("models");
+        let installed = import(&bundle, &root, &manifest, &signed(), &mut |_| {}).unwrap();
+        assert_eq!(installed, ["embeddings"]);
+        let target = spec.install_dir(&root).unwrap();
+        assert!(target.join("1_Pooling/config.json").is_file());
+        assert!(target.join(downloads::VERSION_MARKER).is_file());
+        assert!(!root.join(downloads::DOWNLOADS_DIR).join("bundle").exists());
+    }
+
+    #[test]
+    fn damaged_or_mismatched_bundle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = ModelManifest::bundled();
+        let spec = manifest.get("embeddings").unwrap();
+        let root = dir.path().join("models");
+
+        let bundle = dir.path().join("dañado.zip");
+        build_bundle(&bundle, spec.revision.as_deref(), true);
+        assert!(import(&bundle, &root, &manifest, &signed(), &mut |_| {}).is_err());
+
+        let bundle = dir.path().join("otra-revision.zip");
+        build_bundle(&bundle, Some("0000000"), false);
+        assert!(import(&bundle, &root, &manifest, &signed(), &mut |_| {}).is_err());
+
+        // Manipulado pero coherente con su propio bundle.json: lo detectan las sumas firmadas
+        let bundle = dir.path().join("manipulado.zip");
+        build_bundle_with(
+            &bundle,
+            spec.revision.as_deref(),
+            &[("config.json", b"{\"x\": 1}"), FILES[1]],
+            false,
+        );
+        assert!(import(&bundle, &root, &manifest, &signed(), &mut |_| {}).is_err());
+
+        // Sin sumas firmadas del modelo no se importa
+        let bundle = dir.path().join("sin-firma.zip");
+        build_bundle(&bundle, spec.revision.as_deref(), false);
+        assert!(import(&bundle, &root, &manifest, &HashMap::new(), &mut |_| {}).is_err());
+        assert!(!spec.install_dir(&root).unwrap().exists());
+    }
+}
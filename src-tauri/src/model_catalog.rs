@@ -0,0 +1,383 @@
+// Catálogo de modelos: disponibles frente a instalados
+//
+// La pantalla Preferencias > Modelos necesita, para cada modelo del manifiesto,
+// su descripción y lo que supone en disco: cuánto descarga si no está y cuánto
+// ocupa (y libera al borrarlo) si está. `get_model_catalog` junta:
+//   - el manifiesto embebido (nombres, tamaños, idiomas, para qué sirve)
+//   - los modelos NLP en disco, con las mismas comprobaciones que preflight.rs
+//     (incompleto, otra revisión) y su tamaño real
+//   - los modelos de Ollama: /api/tags si el servicio responde, o los
+//     manifiestos de ~/.ollama si no
+// Los modelos empaquetados con la app se marcan como no borrables.
+//
+// `recommend_models` cruza el catálogo con el hardware (hardware.rs) para el
+// asistente de primer arranque: los modelos NLP requeridos y el LLM más grande
+// cuyo `min_ram_gb` cabe en el presupuesto de memoria del equipo.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::cleanup::dir_size;
+use crate::downloads;
+use crate::hardware;
+use crate::models::{self, ModelKind, ModelManifest, ModelPurpose, ModelSpec};
+use crate::ollama::OLLAMA_API_URL;
+use crate::preflight::{self, MissingReason};
+use crate::proxy;
+use crate::settings::SettingsStore;
+
+/// Estado de un modelo del catálogo
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogStatus {
+    Installed,
+    NotInstalled,
+    /// En disco pero le faltan ficheros
+    Incomplete,
+    /// Instalado con otra revisión que la del manifiesto
+    Outdated,
+}
+
+/// Modelo del catálogo
+#[derive(Serialize, Clone, Debug)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub kind: ModelKind,
+    pub name: String,
+    pub display_name: String,
+    pub description: String,
+    pub purpose: ModelPurpose,
+    pub languages: Vec<String>,
+    pub required: bool,
+    /// Tamaño aproximado de descarga
+    pub download_mb: u64,
+    pub status: CatalogStatus,
+    /// Lo que ocupa en disco (0 si no está)
+    pub installed_bytes: u64,
+    pub path: Option<String>,
+    /// Si se puede borrar desde la app (los empaquetados con la app, no)
+    pub removable: bool,
+    /// Tags instalados en Ollama (`qwen3:latest`...)
+    pub ollama_tags: Vec<String>,
+}
+
+/// Catálogo completo (respuesta de `get_model_catalog`)
+#[derive(Serialize, Clone, Debug)]
+pub struct ModelCatalog {
+    pub models: Vec<CatalogEntry>,
+    /// Directorio de modelos NLP del usuario
+    pub models_dir: String,
+    /// Disco libre donde se instalan los modelos NLP
+    pub free_disk_mb: Option<u64>,
+    pub ollama_running: bool,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagModel>,
+}
+
+#[derive(Deserialize)]
+struct TagModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+}
+
+/// Modelos instalados en Ollama (`nombre:tag`, bytes); `None` si no responde
+async fn ollama_tags() -> Option<Vec<(String, u64)>> {
+    let response: TagsResponse = proxy::local_client()
+        .get(format!("{}/tags", OLLAMA_API_URL))
+        .timeout(std::time::Duration::from_secs(2))
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    Some(
+        response
+            .models
+            .into_iter()
+            .map(|m| (m.name, m.size))
+            .collect(),
+    )
+}
+
+fn ollama_entry(spec: &ModelSpec, ollama: &[(String, u64)]) -> (CatalogStatus, u64, Vec<String>) {
+    let tags: Vec<&(String, u64)> = ollama
+        .iter()
+        .filter(|(tag, _)| {
+            let base = tag.split(':').next().unwrap_or(tag);
+            base.eq_ignore_ascii_case(&spec.name)
+        })
+        .collect();
+    let status = if tags.is_empty() {
+        CatalogStatus::NotInstalled
+    } else {
+        CatalogStatus::Installed
+    };
+    let bytes = tags.iter().map(|(_, size)| size).sum();
+    (
+        status,
+        bytes,
+        tags.into_iter().map(|(tag, _)| tag.clone()).collect(),
+    )
+}
+
+fn build(
+    manifest: &ModelManifest,
+    roots: &[PathBuf],
+    user_root: &Path,
+    ollama: &[(String, u64)],
+) -> Vec<CatalogEntry> {
+    manifest
+        .models
+        .iter()
+        .map(|spec| {
+            let (status, path, installed_bytes, ollama_tags) = match spec.kind {
+                ModelKind::Ollama => {
+                    let (status, bytes, tags) = ollama_entry(spec, ollama);
+                    (status, None, bytes, tags)
+                }
+                _ => {
+                    let (status, path) = match preflight::locate(spec, roots) {
+                        Ok(dir) => (CatalogStatus::Installed, Some(dir)),
+                        Err(reason) => {
+                            let status = match reason {
+                                MissingReason::Missing => CatalogStatus::NotInstalled,
+                                MissingReason::Incomplete => CatalogStatus::Incomplete,
+                                MissingReason::Outdated => CatalogStatus::Outdated,
+                            };
+                            // Lo que haya a medias también ocupa
+                            (
+                                status,
+                                spec.install_dir(user_root).filter(|dir| dir.is_dir()),
+                            )
+                        }
+                    };
+                    let bytes = path.as_deref().map(dir_size).unwrap_or(0);
+                    (status, path, bytes, vec![])
+                }
+            };
+            CatalogEntry {
+                id: spec.id.clone(),
+                kind: spec.kind,
+                name: spec.name.clone(),
+                display_name: spec.display_name.clone(),
+                description: spec.description.clone(),
+                purpose: spec.purpose,
+                languages: spec.languages.clone(),
+                required: spec.required,
+                download_mb: spec.size_mb,
+                status,
+                installed_bytes,
+                removable: match &path {
+                    Some(dir) => dir.starts_with(user_root),
+                    None => status == CatalogStatus::Installed,
+                },
+                path: path.map(|dir| dir.to_string_lossy().into_owned()),
+                ollama_tags,
+            }
+        })
+        .collect()
+}
+
+async fn catalog(app: &AppHandle) -> Result<ModelCatalog, String> {
+    let user_root =
+        downloads::models_root(app).ok_or("No se pudo determinar el directorio home")?;
+    let roots = preflight::model_roots(app);
+    let tags = ollama_tags().await;
+    let ollama_running = tags.is_some();
+    tauri::async_runtime::spawn_blocking(move || {
+        let manifest = ModelManifest::bundled();
+        // Sin servicio, lo que haya en ~/.ollama
+        let ollama = tags.unwrap_or_else(|| match dirs::home_dir() {
+            Some(home) => models::ollama_usage(&home.join(".ollama"), &manifest)
+                .into_iter()
+                .map(|usage| (usage.name, usage.size_bytes))
+                .collect(),
+            None => vec![],
+        });
+        ModelCatalog {
+            models: build(&manifest, &roots, &user_root, &ollama),
+            models_dir: user_root.to_string_lossy().into_owned(),
+            free_disk_mb: hardware::free_disk_mb(&user_root),
+            ollama_running,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Modelos del manifiesto con su estado en disco y en Ollama
+#[tauri::command]
+pub async fn get_model_catalog(app: AppHandle) -> Result<ModelCatalog, String> {
+    catalog(&app).await
+}
+
+/// Modelo recomendado para el equipo
+#[derive(Serialize, Clone, Debug)]
+pub struct RecommendedModel {
+    pub id: String,
+    pub display_name: String,
+    pub size_mb: u64,
+    pub installed: bool,
+    /// Por qué se recomienda
+    pub reason: String,
+}
+
+/// Respuesta de `recommend_models`
+#[derive(Serialize, Clone, Debug)]
+pub struct ModelRecommendation {
+    pub models: Vec<RecommendedModel>,
+    pub llm_budget_gb: f64,
+    /// Nivel de calidad recomendado (el de `get_hardware_info`)
+    pub recommended_level: &'static str,
+    /// Lo que falta por descargar de lo recomendado
+    pub download_mb: u64,
+    pub fits_on_disk: bool,
+    /// Si no cabe ningún LLM del catálogo, por qué
+    pub llm_note: Option<String>,
+}
+
+/// Modelos recomendados con `llm_budget_gb` de memoria para LLM: los NLP
+/// requeridos y el LLM más grande que cabe (sin los sustituidos)
+fn recommend(manifest: &ModelManifest, llm_budget_gb: f64) -> Vec<(&ModelSpec, String)> {
+    let nlp = manifest
+        .models
+        .iter()
+        .filter(|spec| spec.required)
+        .map(|spec| (spec, "Necesario para el analisis".to_string()));
+    let llm = manifest
+        .models
+        .iter()
+        .filter(|spec| spec.purpose == ModelPurpose::Llm && !spec.legacy)
+        .filter(|spec| spec.min_ram_gb.is_some_and(|min| min <= llm_budget_gb))
+        .max_by_key(|spec| spec.size_mb)
+        .map(|spec| {
+            let reason = format!(
+                "El modelo de lenguaje mas completo que cabe en {:.1} GB de memoria",
+                llm_budget_gb
+            );
+            (spec, reason)
+        });
+    nlp.chain(llm).collect()
+}
+
+/// Modelos que el equipo puede mover con holgura, para el asistente de primer arranque
+#[tauri::command]
+pub async fn recommend_models(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+) -> Result<ModelRecommendation, String> {
+    let hardware = hardware::detect(&store).await?;
+    let catalog = catalog(&app).await?;
+    let manifest = ModelManifest::bundled();
+
+    let models: Vec<RecommendedModel> = recommend(&manifest, hardware.llm_budget_gb)
+        .into_iter()
+        .map(|(spec, reason)| RecommendedModel {
+            id: spec.id.clone(),
+            display_name: spec.display_name.clone(),
+            size_mb: spec.size_mb,
+            installed: catalog
+                .models
+                .iter()
+                .any(|entry| entry.id == spec.id && entry.status == CatalogStatus::Installed),
+            reason,
+        })
+        .collect();
+    let download_mb = models
+        .iter()
+        .filter(|m| !m.installed)
+        .map(|m| m.size_mb)
+        .sum();
+    let llm_note = models
+        .iter()
+        .all(|m| manifest.get(&m.id).is_none_or(|spec| spec.purpose != ModelPurpose::Llm))
+        .then(|| {
+            format!(
+                "Ningun modelo de lenguaje cabe en {:.1} GB de memoria: el analisis funcionara sin LLM",
+                hardware.llm_budget_gb
+            )
+        });
+    Ok(ModelRecommendation {
+        fits_on_disk: catalog.free_disk_mb.is_none_or(|free| free >= download_mb),
+        models,
+        llm_budget_gb: hardware.llm_budget_gb,
+        recommended_level: hardware.recommended_level,
+        download_mb,
+        llm_note,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn recommendation_picks_the_largest_llm_that_fits() {
+        let manifest = ModelManifest::bundled();
+        let ids = |budget: f64| -> Vec<String> {
+            recommend(&manifest, budget)
+                .into_iter()
+                .map(|(spec, _)| spec.id.clone())
+                .collect()
+        };
+        // 16 GB de RAM sin GPU: 8 GB para el LLM, un 7-8B cuantizado
+        assert_eq!(
+            ids(8.0),
+            ["spacy", "embeddings", "transformer_ner", "ollama_hermes3"]
+        );
+        // GPU de 12 GB
+        assert_eq!(ids(12.0).last().unwrap(), "ollama_qwen3");
+        // Equipo de 4 GB: solo los modelos NLP
+        assert_eq!(ids(2.0).len(), 3);
+    }
+
+    #[test]
+    fn catalog_merges_disk_and_ollama_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundled = dir.path().join("resources/models");
+        let user = dir.path().join("models");
+        let spacy = bundled.join("spacy/es_core_news_lg");
+        fs::create_dir_all(&spacy).unwrap();
+        fs::write(spacy.join("config.cfg"), "[nlp]").unwrap();
+        let embeddings = user.join("embeddings/paraphrase-multilingual-MiniLM-L12-v2");
+        fs::create_dir_all(&embeddings).unwrap();
+        fs::write(embeddings.join("config.json"), "{}").unwrap();
+
+        let ollama = vec![
+            ("qwen3:latest".to_string(), 100),
+            ("qwen3:14b".to_string(), 50),
+            ("codellama:7b".to_string(), 10),
+        ];
+        let catalog = build(
+            &ModelManifest::bundled(),
+            &[bundled, user.clone()],
+            &user,
+            &ollama,
+        );
+        let entry = |id: &str| catalog.iter().find(|e| e.id == id).unwrap();
+
+        assert_eq!(entry("spacy").status, CatalogStatus::Installed);
+        assert_eq!(entry("spacy").installed_bytes, 5);
+        assert!(!entry("spacy").removable);
+        assert_eq!(entry("embeddings").status, CatalogStatus::Incomplete);
+        assert!(entry("embeddings").removable);
+        assert_eq!(entry("transformer_ner").status, CatalogStatus::NotInstalled);
+        assert_eq!(entry("transformer_ner").installed_bytes, 0);
+
+        let qwen = entry("ollama_qwen3");
+        assert_eq!(qwen.status, CatalogStatus::Installed);
+        assert_eq!(qwen.installed_bytes, 150);
+        assert_eq!(qwen.ollama_tags, ["qwen3:latest", "qwen3:14b"]);
+        assert_eq!(entry("ollama_mistral").status, CatalogStatus::NotInstalled);
+    }
+}
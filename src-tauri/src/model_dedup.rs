@@ -0,0 +1,314 @@
+// Modelos duplicados entre el directorio de modelos y la cache de HuggingFace
+//
+// Si el usuario tiene sentence-transformers o transformers instalados, la misma
+// revisión de un modelo suele estar dos veces en disco: en el directorio de
+// modelos de la app y en ~/.cache/huggingface/hub/models--org--name/snapshots/<rev>.
+// `find_duplicate_models` busca los ficheros idénticos (mismo tamaño y ruta
+// relativa que no sean ya el mismo fichero) y Gestionar datos muestra su tamaño
+// como espacio recuperable en la categoría de modelos.
+//
+// `dedup_models` sustituye la copia de la app por un hardlink al blob de la
+// cache tras comprobar que las sumas SHA-256 coinciden. Se usa hardlink (no
+// symlink ni junction) porque los datos siguen siendo de los dos: si el usuario
+// borra la cache de HuggingFace, la app conserva sus modelos. Los hardlinks solo
+// funcionan dentro del mismo volumen; si los dos directorios están en discos
+// distintos el fichero se deja como está. Se detiene el backend mientras tanto
+// (en Windows no se puede sustituir un fichero abierto).
+
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use crate::downloads::{self, DownloadsState};
+use crate::integrity;
+use crate::models::{ModelKind, ModelManifest, ModelSpec};
+use crate::BackendServer;
+
+/// Modelo con ficheros repetidos en la cache de HuggingFace
+#[derive(Serialize, Clone, Debug)]
+pub struct DuplicateModel {
+    pub id: String,
+    pub display_name: String,
+    /// Directorio del modelo en el directorio de modelos de la app
+    pub app_path: String,
+    /// Snapshot de la misma revisión en la cache de HuggingFace
+    pub hf_path: String,
+    /// Ficheros repetidos (rutas relativas)
+    pub files: Vec<String>,
+    /// Espacio que se recupera enlazando los ficheros repetidos
+    pub reclaimable_bytes: u64,
+}
+
+/// Resultado de `dedup_models`
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DedupReport {
+    pub linked_files: usize,
+    pub reclaimed_bytes: u64,
+    /// Ficheros que se han dejado como estaban, con el motivo
+    pub skipped: Vec<String>,
+}
+
+/// Si dos rutas son el mismo fichero en disco (hardlinks)
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(windows)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use ::windows::Win32::Foundation::HANDLE;
+    use ::windows::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+    use std::os::windows::io::AsRawHandle;
+
+    fn file_id(path: &Path) -> Option<(u32, u32, u32)> {
+        let file = fs::File::open(path).ok()?;
+        let mut info = BY_HANDLE_FILE_INFORMATION::default();
+        unsafe { GetFileInformationByHandle(HANDLE(file.as_raw_handle()), &mut info) }.ok()?;
+        Some((
+            info.dwVolumeSerialNumber,
+            info.nFileIndexHigh,
+            info.nFileIndexLow,
+        ))
+    }
+    matches!((file_id(a), file_id(b)), (Some(a), Some(b)) if a == b)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// Snapshot de la revisión del modelo en la cache (`None` si no está)
+fn hf_snapshot(spec: &ModelSpec, hf_root: &Path) -> Option<PathBuf> {
+    let repo = hf_root
+        .join("hub")
+        .join(format!("models--{}", spec.name.replace('/', "--")));
+    // Sin revisión pinneada, la que la cache tiene como main
+    let revision = match &spec.revision {
+        Some(revision) => revision.clone(),
+        None => fs::read_to_string(repo.join("refs").join("main"))
+            .ok()?
+            .trim()
+            .to_string(),
+    };
+    let snapshot = repo.join("snapshots").join(revision);
+    snapshot.is_dir().then_some(snapshot)
+}
+
+/// Ficheros de `app_dir` repetidos en `snapshot`: (ruta relativa, tamaño)
+fn duplicated_files(app_dir: &Path, snapshot: &Path) -> Vec<(String, u64)> {
+    integrity::list_files(app_dir)
+        .into_iter()
+        .filter_map(|relative| {
+            let ours = app_dir.join(&relative);
+            // Los ficheros de snapshots/ son symlinks a blobs/: metadata los sigue
+            let theirs = snapshot.join(&relative);
+            let size = fs::metadata(&ours).ok()?.len();
+            let matches =
+                fs::metadata(&theirs).is_ok_and(|meta| meta.is_file() && meta.len() == size);
+            (matches && size > 0 && !same_file(&ours, &theirs)).then_some((relative, size))
+        })
+        .collect()
+}
+
+/// Modelos de HuggingFace del manifiesto con ficheros repetidos en la cache
+pub(crate) fn find_duplicates(
+    manifest: &ModelManifest,
+    models_root: &Path,
+    hf_root: &Path,
+) -> Vec<DuplicateModel> {
+    manifest
+        .models
+        .iter()
+        .filter(|spec| spec.kind == ModelKind::Huggingface)
+        .filter_map(|spec| {
+            let app_dir = spec.install_dir(models_root).filter(|dir| dir.is_dir())?;
+            let snapshot = hf_snapshot(spec, hf_root)?;
+            let files = duplicated_files(&app_dir, &snapshot);
+            (!files.is_empty()).then(|| DuplicateModel {
+                id: spec.id.clone(),
+                display_name: spec.display_name.clone(),
+                app_path: app_dir.to_string_lossy().into(),
+                hf_path: snapshot.to_string_lossy().into(),
+                reclaimable_bytes: files.iter().map(|(_, size)| size).sum(),
+                files: files.into_iter().map(|(path, _)| path).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Sustituye `ours` por un hardlink a `theirs` si tienen el mismo contenido
+fn link_file(ours: &Path, theirs: &Path) -> Result<u64, String> {
+    let blob = fs::canonicalize(theirs).map_err(|e| e.to_string())?;
+    let (size, ours_sum) = integrity::hash_file(ours).map_err(|e| e.to_string())?;
+    let (_, theirs_sum) = integrity::hash_file(&blob).map_err(|e| e.to_string())?;
+    if ours_sum != theirs_sum {
+        return Err("el contenido no coincide".into());
+    }
+
+    // Enlace con otro nombre y rename encima: el original no desaparece si algo falla
+    let mut temporary = ours.as_os_str().to_owned();
+    temporary.push(".dedup");
+    let temporary = PathBuf::from(temporary);
+    let _ = fs::remove_file(&temporary);
+    fs::hard_link(&blob, &temporary).map_err(|e| match e.kind() {
+        io::ErrorKind::CrossesDevices => "la cache esta en otro disco".to_string(),
+        _ => e.to_string(),
+    })?;
+    fs::rename(&temporary, ours).map_err(|e| {
+        let _ = fs::remove_file(&temporary);
+        e.to_string()
+    })?;
+    Ok(size)
+}
+
+/// Enlaza los ficheros repetidos de `duplicates`
+fn dedup(duplicates: &[DuplicateModel]) -> DedupReport {
+    let mut report = DedupReport::default();
+    for model in duplicates {
+        let (app_dir, snapshot) = (Path::new(&model.app_path), Path::new(&model.hf_path));
+        for relative in &model.files {
+            match link_file(&app_dir.join(relative), &snapshot.join(relative)) {
+                Ok(size) => {
+                    report.linked_files += 1;
+                    report.reclaimed_bytes += size;
+                }
+                Err(e) => report
+                    .skipped
+                    .push(format!("{}/{}: {}", model.id, relative, e)),
+            }
+        }
+    }
+    report
+}
+
+fn hf_root() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".cache").join("huggingface"))
+}
+
+/// Modelos de la app que también están en la cache de HuggingFace
+#[tauri::command]
+pub async fn find_duplicate_models(app: AppHandle) -> Result<Vec<DuplicateModel>, String> {
+    let models_root =
+        downloads::models_root(&app).ok_or("No se pudo determinar el directorio home")?;
+    let hf_root = hf_root().ok_or("No se pudo determinar el directorio home")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        find_duplicates(&ModelManifest::bundled(), &models_root, &hf_root)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Sustituye las copias repetidas de la app por hardlinks a la cache de HuggingFace
+#[tauri::command]
+pub async fn dedup_models(
+    app: AppHandle,
+    server_state: State<'_, BackendServer>,
+) -> Result<DedupReport, String> {
+    let duplicates = find_duplicate_models(app.clone()).await?;
+    if duplicates.is_empty() {
+        return Ok(DedupReport::default());
+    }
+    let downloads_state = app.state::<DownloadsState>();
+    if !downloads_state.begin_exclusive() {
+        return Err("Hay descargas de modelos en curso".into());
+    }
+
+    let was_running = crate::pause_backend(&app, &server_state);
+    let result = tauri::async_runtime::spawn_blocking(move || dedup(&duplicates))
+        .await
+        .map_err(|e| e.to_string());
+    downloads_state.end_exclusive();
+    crate::resume_backend(&app, server_state, was_running).await;
+
+    let report = result?;
+    tracing::info!(
+        target: "models",
+        "Deduplicados {} ficheros de modelos ({} bytes recuperados, {} omitidos)",
+        report.linked_files,
+        report.reclaimed_bytes,
+        report.skipped.len()
+    );
+    for skipped in &report.skipped {
+        tracing::warn!(target: "models", "No se pudo deduplicar {}", skipped);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "sentence-transformers/paraphrase-multilingual-MiniLM-L12-v2";
+
+    fn write(path: &Path, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    /// Directorio de modelos y cache de HuggingFace con el modelo de embeddings
+    fn setup(dir: &Path, weights_in_cache: &[u8]) -> (PathBuf, PathBuf, ModelManifest) {
+        let manifest = ModelManifest::bundled();
+        let spec = manifest.find(ModelKind::Huggingface, MODEL).unwrap();
+        let models_root = dir.join("models");
+        let app_dir = spec.install_dir(&models_root).unwrap();
+        write(&app_dir.join("config.json"), b"{\"a\": 1}");
+        write(&app_dir.join("model.safetensors"), &[7; 4096]);
+        write(&app_dir.join(downloads::VERSION_MARKER), b"{}");
+
+        let hf_root = dir.join("huggingface");
+        let repo = hf_root
+            .join("hub")
+            .join(format!("models--{}", MODEL.replace('/', "--")));
+        let revision = spec.revision.clone().unwrap_or_else(|| "main".into());
+        write(&repo.join("refs/main"), revision.as_bytes());
+        write(&repo.join("blobs/abc"), weights_in_cache);
+        let snapshot = repo.join("snapshots").join(&revision);
+        write(&snapshot.join("config.json"), b"{\"a\": 1}");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(repo.join("blobs/abc"), snapshot.join("model.safetensors"))
+            .unwrap();
+        #[cfg(not(unix))]
+        write(&snapshot.join("model.safetensors"), weights_in_cache);
+        (models_root, hf_root, manifest)
+    }
+
+    #[test]
+    fn finds_and_links_files_repeated_in_the_hf_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let (models_root, hf_root, manifest) = setup(dir.path(), &[7; 4096]);
+
+        let duplicates = find_duplicates(&manifest, &models_root, &hf_root);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].files, ["config.json", "model.safetensors"]);
+        assert_eq!(duplicates[0].reclaimable_bytes, 8 + 4096);
+
+        let report = dedup(&duplicates);
+        assert_eq!(report.linked_files, 2);
+        assert!(report.skipped.is_empty());
+        let weights = Path::new(&duplicates[0].app_path).join("model.safetensors");
+        assert_eq!(fs::read(&weights).unwrap(), [7; 4096]);
+        assert!(find_duplicates(&manifest, &models_root, &hf_root).is_empty());
+    }
+
+    #[test]
+    fn different_contents_of_the_same_size_are_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let (models_root, hf_root, manifest) = setup(dir.path(), &[8; 4096]);
+
+        let duplicates = find_duplicates(&manifest, &models_root, &hf_root);
+        let report = dedup(&duplicates);
+        assert_eq!(report.linked_files, 1);
+        assert_eq!(report.skipped.len(), 1);
+        let weights = Path::new(&duplicates[0].app_path).join("model.safetensors");
+        assert_eq!(fs::read(&weights).unwrap(), [7; 4096]);
+    }
+}
@@ -0,0 +1,431 @@
+// Manifiesto de modelos de Narrative Assistant
+//
+// El manifiesto (resources/model_manifest.json) enumera los modelos NLP y LLM
+// que usa la aplicación. Se embebe en el binario y sirve para:
+//   - Identificar qué entradas de los directorios compartidos (~/.ollama,
+//     cache de HuggingFace) pertenecen a Narrative Assistant
+//   - Descargar los modelos spaCy y HF desde el shell (downloads.rs)
+//   - El catálogo de la pantalla de modelos (model_catalog.rs)
+//
+// Debe mantenerse sincronizado con KNOWN_MODELS (core/model_manager.py) y
+// AVAILABLE_MODELS (llm/ollama_manager.py) del backend.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cleanup::dir_size;
+
+/// Manifiesto embebido en tiempo de compilación
+const MANIFEST_JSON: &str = include_str!("../resources/model_manifest.json");
+
+/// Registro por defecto de Ollama (los modelos oficiales viven en `library/`)
+const OLLAMA_DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+const OLLAMA_DEFAULT_NAMESPACE: &str = "library";
+
+/// Prefijo de los repos de modelos en la cache de HuggingFace (`models--org--name`)
+const HF_MODEL_DIR_PREFIX: &str = "models--";
+
+/// Origen de un modelo
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelKind {
+    /// Paquete spaCy instalado en models/spacy
+    Spacy,
+    /// Repo de HuggingFace (sentence-transformers, transformers)
+    Huggingface,
+    /// Modelo LLM servido por Ollama
+    Ollama,
+}
+
+/// Para qué usa el análisis un modelo
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelPurpose {
+    /// Análisis gramatical (spaCy)
+    Grammar,
+    /// Similitud semántica
+    Embeddings,
+    /// Reconocimiento de entidades (personajes, lugares)
+    Ner,
+    /// Modelo de lenguaje para el análisis narrativo
+    Llm,
+}
+
+/// Entrada del manifiesto
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelSpec {
+    /// Identificador estable (spacy, embeddings, ollama_llama3_2...)
+    pub id: String,
+    pub kind: ModelKind,
+    /// Nombre en su origen: paquete spaCy, repo HF (`org/name`) o modelo Ollama
+    pub name: String,
+    /// Nombre para mostrar en la UI
+    pub display_name: String,
+    /// Descripción breve para la pantalla de modelos
+    #[serde(default)]
+    pub description: String,
+    pub purpose: ModelPurpose,
+    /// Idiomas que cubre (códigos ISO 639-1; "multi" = multilingüe)
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Subdirectorio dentro de ~/.narrative_assistant/models (spaCy y HF)
+    #[serde(default)]
+    pub subdirectory: Option<String>,
+    /// Directorio dentro de `subdirectory` si no es `name` (el backend guarda
+    /// los embeddings sin la organización del repo)
+    #[serde(default)]
+    pub local_name: Option<String>,
+    /// Versión (spaCy) o commit pinneado (HF)
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// SHA-256 del paquete descargado (el .whl de spaCy)
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Tamaño aproximado de descarga
+    pub size_mb: u64,
+    /// Memoria que necesita un LLM (`min_ram_gb` de llm/ollama_manager.py)
+    #[serde(default)]
+    pub min_ram_gb: Option<f64>,
+    /// Si el análisis no funciona sin este modelo
+    pub required: bool,
+    /// Sustituido por otro: se puede instalar pero no se recomienda
+    #[serde(default)]
+    pub legacy: bool,
+    /// Id del modelo al que sustituye si ese no está disponible
+    #[serde(default)]
+    pub fallback_for: Option<String>,
+}
+
+impl ModelSpec {
+    /// Directorio del modelo instalado (el mismo que busca core/model_manager.py);
+    /// `None` para los modelos de Ollama
+    pub fn install_dir(&self, models_root: &Path) -> Option<PathBuf> {
+        let subdirectory = self.subdirectory.as_ref()?;
+        let name = self.local_name.as_deref().unwrap_or(&self.name);
+        Some(models_root.join(subdirectory).join(name))
+    }
+}
+
+/// Manifiesto de modelos
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelManifest {
+    pub version: u32,
+    pub models: Vec<ModelSpec>,
+}
+
+impl ModelManifest {
+    /// Manifiesto embebido en el binario
+    pub fn bundled() -> Self {
+        serde_json::from_str(MANIFEST_JSON).expect("resources/model_manifest.json invalido")
+    }
+
+    /// Busca un modelo por su id del manifiesto
+    pub fn get(&self, id: &str) -> Option<&ModelSpec> {
+        self.models.iter().find(|m| m.id == id)
+    }
+
+    /// Busca un modelo de un origen concreto por su nombre en ese origen
+    pub fn find(&self, kind: ModelKind, name: &str) -> Option<&ModelSpec> {
+        self.models
+            .iter()
+            .find(|m| m.kind == kind && m.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Modelo encontrado en disco dentro de una categoría de datos
+#[derive(Serialize, Clone, Debug)]
+pub struct ModelUsage {
+    /// Nombre en su origen (`llama3.2:latest`, `org/name`)
+    pub name: String,
+    /// Ruta absoluta en disco
+    pub path: String,
+    /// Tamaño en bytes
+    pub size_bytes: u64,
+    /// Si el modelo está en el manifiesto de Narrative Assistant
+    pub used_by_app: bool,
+    /// Id del manifiesto si `used_by_app`
+    pub model_id: Option<String>,
+}
+
+/// Nombre de un modelo Ollama a partir de la ruta relativa de su manifiesto
+/// (`registry/namespace/model/tag`). Devuelve `(nombre_base, nombre:tag)`.
+fn ollama_name_from_components(components: &[String]) -> Option<(String, String)> {
+    let [registry, namespace, model, tag] = components else {
+        return None;
+    };
+    let base = if registry == OLLAMA_DEFAULT_REGISTRY && namespace == OLLAMA_DEFAULT_NAMESPACE {
+        model.clone()
+    } else if registry == OLLAMA_DEFAULT_REGISTRY {
+        format!("{}/{}", namespace, model)
+    } else {
+        format!("{}/{}/{}", registry, namespace, model)
+    };
+    let full = format!("{}:{}", base, tag);
+    Some((base, full))
+}
+
+/// Suma de los tamaños de capas declarados en un manifiesto de Ollama
+fn ollama_manifest_size(manifest_file: &Path) -> u64 {
+    let Ok(raw) = fs::read_to_string(manifest_file) else {
+        return 0;
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&raw) else {
+        return 0;
+    };
+    let layer_size = |v: &serde_json::Value| v.get("size").and_then(|s| s.as_u64()).unwrap_or(0);
+    let config = json.get("config").map(layer_size).unwrap_or(0);
+    let layers: u64 = json
+        .get("layers")
+        .and_then(|l| l.as_array())
+        .map(|layers| layers.iter().map(layer_size).sum())
+        .unwrap_or(0);
+    config + layers
+}
+
+/// Recorre `manifests/` recogiendo los ficheros a profundidad 4 (registry/namespace/model/tag)
+fn collect_ollama_manifests(
+    dir: &Path,
+    components: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, PathBuf)>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        components.push(entry.file_name().to_string_lossy().into());
+        if meta.is_dir() && components.len() < 4 {
+            collect_ollama_manifests(&entry.path(), components, out);
+        } else if meta.is_file() && components.len() == 4 {
+            out.push((components.clone(), entry.path()));
+        }
+        components.pop();
+    }
+}
+
+/// Modelos instalados en Ollama (`<ollama_root>/models/manifests`) marcados según el manifiesto
+pub fn ollama_usage(ollama_root: &Path, manifest: &ModelManifest) -> Vec<ModelUsage> {
+    let mut found = Vec::new();
+    collect_ollama_manifests(
+        &ollama_root.join("models").join("manifests"),
+        &mut Vec::new(),
+        &mut found,
+    );
+
+    let mut usage: Vec<ModelUsage> = found
+        .into_iter()
+        .filter_map(|(components, path)| {
+            let (base, full) = ollama_name_from_components(&components)?;
+            let spec = manifest.find(ModelKind::Ollama, &base);
+            Some(ModelUsage {
+                name: full,
+                size_bytes: ollama_manifest_size(&path),
+                path: path.to_string_lossy().into(),
+                used_by_app: spec.is_some(),
+                model_id: spec.map(|s| s.id.clone()),
+            })
+        })
+        .collect();
+    usage.sort_by(|a, b| a.name.cmp(&b.name));
+    usage
+}
+
+/// Repos de la cache de HuggingFace (`<hf_root>/hub/models--org--name`) marcados según el manifiesto
+pub fn huggingface_usage(hf_root: &Path, manifest: &ModelManifest) -> Vec<ModelUsage> {
+    let Ok(entries) = fs::read_dir(hf_root.join("hub")) else {
+        return vec![];
+    };
+
+    let mut usage: Vec<ModelUsage> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let dir_name = entry.file_name().to_string_lossy().to_string();
+            let repo = dir_name
+                .strip_prefix(HF_MODEL_DIR_PREFIX)?
+                .replacen("--", "/", 1);
+            let path = entry.path();
+            // Los snapshots son symlinks a blobs/: medir solo blobs evita contar dos veces
+            let blobs = path.join("blobs");
+            let size_bytes = if blobs.is_dir() {
+                dir_size(&blobs)
+            } else {
+                dir_size(&path)
+            };
+            let spec = manifest.find(ModelKind::Huggingface, &repo);
+            Some(ModelUsage {
+                name: repo,
+                path: path.to_string_lossy().into(),
+                size_bytes,
+                used_by_app: spec.is_some(),
+                model_id: spec.map(|s| s.id.clone()),
+            })
+        })
+        .collect();
+    usage.sort_by(|a, b| a.name.cmp(&b.name));
+    usage
+}
+
+/// Modelos de Narrative Assistant instalados, de mayor a menor: los spaCy y HF
+/// del directorio de modelos y los de Ollama del manifiesto (que viven en
+/// `<ollama_root>`, fuera del directorio de modelos)
+pub fn app_model_usage(
+    models_root: &Path,
+    ollama_root: &Path,
+    manifest: &ModelManifest,
+) -> Vec<ModelUsage> {
+    let mut usage: Vec<ModelUsage> = manifest
+        .models
+        .iter()
+        .filter_map(|spec| {
+            let dir = spec.install_dir(models_root).filter(|d| d.is_dir())?;
+            Some(ModelUsage {
+                name: spec.name.clone(),
+                size_bytes: dir_size(&dir),
+                path: dir.to_string_lossy().into(),
+                used_by_app: true,
+                model_id: Some(spec.id.clone()),
+            })
+        })
+        .chain(
+            ollama_usage(ollama_root, manifest)
+                .into_iter()
+                .filter(|u| u.used_by_app),
+        )
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.size_bytes));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn bundled_manifest_parses_and_ids_are_unique() {
+        let manifest = ModelManifest::bundled();
+        let mut ids = std::collections::HashSet::new();
+        for model in &manifest.models {
+            assert!(ids.insert(&model.id), "id duplicado: {}", model.id);
+        }
+        assert!(manifest.find(ModelKind::Spacy, "es_core_news_lg").is_some());
+        assert!(manifest.find(ModelKind::Ollama, "llama3.2").is_some());
+
+        let root = Path::new("/m");
+        let embeddings = manifest.get("embeddings").unwrap();
+        assert_eq!(
+            embeddings.install_dir(root),
+            Some(root.join("embeddings/paraphrase-multilingual-MiniLM-L12-v2"))
+        );
+        assert_eq!(
+            manifest.get("transformer_ner").unwrap().install_dir(root),
+            Some(root.join("transformer_ner/mrm8488/bert-spanish-cased-finetuned-ner"))
+        );
+        assert_eq!(
+            manifest.get("ollama_qwen3").unwrap().install_dir(root),
+            None
+        );
+    }
+
+    #[test]
+    fn ollama_usage_marks_app_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifests = dir.path().join("models/manifests/registry.ollama.ai");
+        write(
+            &manifests.join("library/llama3.2/latest"),
+            r#"{"config":{"size":100},"layers":[{"size":2000},{"size":30}]}"#,
+        );
+        write(
+            &manifests.join("someone/codellama/7b"),
+            r#"{"config":{"size":1},"layers":[{"size":9}]}"#,
+        );
+
+        let usage = ollama_usage(dir.path(), &ModelManifest::bundled());
+
+        assert_eq!(usage.len(), 2);
+        let llama = usage.iter().find(|u| u.name == "llama3.2:latest").unwrap();
+        assert!(llama.used_by_app);
+        assert_eq!(llama.model_id.as_deref(), Some("ollama_llama3_2"));
+        assert_eq!(llama.size_bytes, 2130);
+        let other = usage
+            .iter()
+            .find(|u| u.name == "someone/codellama:7b")
+            .unwrap();
+        assert!(!other.used_by_app);
+        assert_eq!(other.size_bytes, 10);
+    }
+
+    #[test]
+    fn huggingface_usage_marks_app_repos() {
+        let dir = tempfile::tempdir().unwrap();
+        let hub = dir.path().join("hub");
+        write(
+            &hub.join(
+                "models--sentence-transformers--paraphrase-multilingual-MiniLM-L12-v2/blobs/abc",
+            ),
+            "0123456789",
+        );
+        write(&hub.join("models--bert-base-uncased/blobs/def"), "01234");
+        write(&hub.join("version.txt"), "1");
+
+        let usage = huggingface_usage(dir.path(), &ModelManifest::bundled());
+
+        assert_eq!(usage.len(), 2);
+        assert!(!usage[0].used_by_app);
+        assert_eq!(usage[0].name, "bert-base-uncased");
+        assert!(usage[1].used_by_app);
+        assert_eq!(usage[1].model_id.as_deref(), Some("embeddings"));
+        assert_eq!(usage[1].size_bytes, 10);
+    }
+
+    #[test]
+    fn app_model_usage_lists_installed_models_largest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_root = dir.path().join("models");
+        write(
+            &models_root.join("embeddings/paraphrase-multilingual-MiniLM-L12-v2/model.bin"),
+            "0123456789",
+        );
+        write(&models_root.join("spacy/es_core_news_lg/meta.json"), "{}");
+        let manifests = dir
+            .path()
+            .join("ollama/models/manifests/registry.ollama.ai");
+        write(
+            &manifests.join("library/llama3.2/latest"),
+            r#"{"config":{"size":100},"layers":[{"size":2000}]}"#,
+        );
+        write(
+            &manifests.join("library/codellama/7b"),
+            r#"{"config":{"size":1},"layers":[{"size":9}]}"#,
+        );
+
+        let usage = app_model_usage(
+            &models_root,
+            &dir.path().join("ollama"),
+            &ModelManifest::bundled(),
+        );
+
+        let ids: Vec<_> = usage.iter().map(|u| u.model_id.as_deref()).collect();
+        assert_eq!(
+            ids,
+            [Some("ollama_llama3_2"), Some("embeddings"), Some("spacy")]
+        );
+        assert_eq!(usage[1].size_bytes, 10);
+    }
+
+    #[test]
+    fn usage_is_empty_for_missing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = ModelManifest::bundled();
+
+        assert!(ollama_usage(&dir.path().join("nope"), &manifest).is_empty());
+        assert!(huggingface_usage(&dir.path().join("nope"), &manifest).is_empty());
+    }
+}
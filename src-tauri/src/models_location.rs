@@ -0,0 +1,312 @@
+// Cambio de ubicación del directorio de modelos
+//
+// Los modelos NLP ocupan varios GB y en portátiles con un SSD de sistema pequeño
+// conviene llevarlos a otro disco. `set_models_location` los mueve y guarda la
+// nueva ruta en preferencias (`models_dir`), que el backend recibe como
+// NA_MODELS_DIR y que usan downloads.rs, integrity.rs y cleanup.rs.
+//
+// Se detiene el backend mientras tanto (tiene los modelos abiertos y en Windows
+// no se pueden mover) y se bloquean las descargas. Dentro del mismo disco basta
+// un rename; entre discos se copia fichero a fichero emitiendo
+// `models-move-progress` y el original solo se borra cuando la copia ha
+// terminado. Si algo falla, los modelos siguen donde estaban.
+
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::downloads::{self, DownloadsState};
+use crate::hardware;
+use crate::settings::SettingsStore;
+use crate::BackendServer;
+
+/// Intervalo mínimo entre eventos de progreso de la copia
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Progreso del traslado (payload de `models-move-progress`)
+#[derive(Serialize, Clone, Debug)]
+pub struct MoveProgress {
+    /// "stopping_backend", "moving", "restarting_backend", "completed" o "error"
+    pub phase: &'static str,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub error: Option<String>,
+}
+
+fn emit_phase(app: &AppHandle, phase: &'static str, error: Option<String>) {
+    let _ = app.emit(
+        "models-move-progress",
+        MoveProgress {
+            phase,
+            copied_bytes: 0,
+            total_bytes: 0,
+            error,
+        },
+    );
+}
+
+/// Ficheros de `dir` (rutas relativas) con su tamaño
+fn list_files(dir: &Path, prefix: &Path, files: &mut Vec<(PathBuf, u64)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let relative = prefix.join(entry.file_name());
+        // Los enlaces simbólicos se siguen: el destino no tiene por qué poder crearlos
+        let meta = fs::metadata(entry.path())?;
+        if meta.is_dir() {
+            list_files(&entry.path(), &relative, files)?;
+        } else {
+            files.push((relative, meta.len()));
+        }
+    }
+    Ok(())
+}
+
+/// Copia `source` en `target` llamando a `on_progress(copiados, total)`
+fn copy_tree(
+    source: &Path,
+    target: &Path,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> io::Result<()> {
+    let mut files = Vec::new();
+    list_files(source, Path::new(""), &mut files)?;
+    let total: u64 = files.iter().map(|(_, size)| size).sum();
+    let mut copied = 0;
+    let mut buffer = vec![0_u8; 1024 * 1024];
+
+    fs::create_dir_all(target)?;
+    for (relative, _) in &files {
+        let destination = target.join(relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut input = fs::File::open(source.join(relative))?;
+        let mut output = fs::File::create(&destination)?;
+        loop {
+            let n = input.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            output.write_all(&buffer[..n])?;
+            copied += n as u64;
+            on_progress(copied, total);
+        }
+        output.sync_all()?;
+    }
+    on_progress(copied, total);
+    Ok(())
+}
+
+/// Comprueba que `target` sirve como nuevo directorio de modelos
+fn check_target(source: &Path, target: &Path) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err(format!(
+            "El directorio de modelos debe ser una ruta absoluta: {}",
+            target.display()
+        ));
+    }
+    if target.starts_with(source) {
+        return Err("El nuevo directorio no puede estar dentro del actual".into());
+    }
+    if target.is_file() {
+        return Err(format!("{} es un fichero", target.display()));
+    }
+    let occupied = fs::read_dir(target).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied {
+        return Err(format!(
+            "{} no esta vacio: elige un directorio nuevo o vacio",
+            target.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Mueve `source` a `target` (que no existe o está vacío)
+fn move_tree(
+    source: &Path,
+    target: &Path,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<(), String> {
+    let io_error = |e: io::Error| format!("Error moviendo los modelos: {}", e);
+    if !source.exists() {
+        return fs::create_dir_all(target).map_err(io_error);
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    // rename no sustituye un directorio vacío en Windows
+    let _ = fs::remove_dir(target);
+    if fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+
+    // Otro disco: copiar y borrar el original
+    let mut files = Vec::new();
+    list_files(source, Path::new(""), &mut files).map_err(io_error)?;
+    let needed_mb = files.iter().map(|(_, size)| size).sum::<u64>() / (1024 * 1024);
+    if let Some(free_mb) = hardware::free_disk_mb(target) {
+        if free_mb < needed_mb {
+            return Err(format!(
+                "No hay espacio suficiente en {}: se necesitan {} MB y hay {} MB libres",
+                target.display(),
+                needed_mb,
+                free_mb
+            ));
+        }
+    }
+    if let Err(e) = copy_tree(source, target, on_progress) {
+        let _ = fs::remove_dir_all(target);
+        return Err(io_error(e));
+    }
+    if let Err(e) = fs::remove_dir_all(source) {
+        // La copia es completa: el original solo ocupa espacio
+        tracing::warn!(
+            target: "models",
+            "No se pudo borrar {} tras copiarlo: {}",
+            source.display(),
+            e
+        );
+    }
+    Ok(())
+}
+
+/// Mueve los modelos instalados a `path` (`None` = ubicación por defecto) y
+/// devuelve la nueva ruta. Detiene el backend durante el traslado.
+#[tauri::command]
+pub async fn set_models_location(
+    app: AppHandle,
+    server_state: State<'_, BackendServer>,
+    downloads_state: State<'_, DownloadsState>,
+    store: State<'_, SettingsStore>,
+    path: Option<String>,
+) -> Result<String, String> {
+    let no_home = "No se pudo determinar el directorio home";
+    let source = downloads::models_root_for(&store.get()).ok_or(no_home)?;
+    let default = downloads::default_models_root().ok_or(no_home)?;
+    let target = path.map(PathBuf::from).unwrap_or_else(|| default.clone());
+    let models_dir = (target != default).then(|| target.to_string_lossy().into_owned());
+
+    if target != source {
+        check_target(&source, &target)?;
+        if !downloads_state.begin_exclusive() {
+            return Err("Hay descargas de modelos en curso".into());
+        }
+
+        emit_phase(&app, "stopping_backend", None);
+        let was_running = crate::pause_backend(&app, &server_state);
+
+        emit_phase(&app, "moving", None);
+        tracing::info!(
+            target: "models",
+            "Moviendo modelos de {} a {}",
+            source.display(),
+            target.display()
+        );
+        let (from, to, progress_app) = (source.clone(), target.clone(), app.clone());
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let mut last = Instant::now();
+            move_tree(&from, &to, &mut |copied, total| {
+                if last.elapsed() >= PROGRESS_INTERVAL || copied == total {
+                    last = Instant::now();
+                    let _ = progress_app.emit(
+                        "models-move-progress",
+                        MoveProgress {
+                            phase: "moving",
+                            copied_bytes: copied,
+                            total_bytes: total,
+                            error: None,
+                        },
+                    );
+                }
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+        // La ruta se guarda antes de relanzar el backend, que la recibe al arrancar
+        let result =
+            result.and_then(|()| store.update(serde_json::json!({ "models_dir": models_dir })));
+        downloads_state.end_exclusive();
+
+        if was_running {
+            emit_phase(&app, "restarting_backend", None);
+        }
+        crate::resume_backend(&app, server_state, was_running).await;
+
+        match result {
+            Ok(updated) => {
+                let _ = app.emit("shell-settings-changed", &updated);
+            }
+            Err(e) => {
+                tracing::warn!(target: "models", "No se pudieron mover los modelos: {}", e);
+                emit_phase(&app, "error", Some(e.clone()));
+                return Err(e);
+            }
+        }
+    } else if store.get().models_dir != models_dir {
+        // Misma ruta escrita de otra forma (p.ej. la de por defecto explícita)
+        let updated = store.update(serde_json::json!({ "models_dir": models_dir }))?;
+        let _ = app.emit("shell-settings-changed", &updated);
+    }
+
+    emit_phase(&app, "completed", None);
+    Ok(target.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn copy_tree_copies_nested_files_with_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("models");
+        write(&source.join("spacy/es_core_news_lg/config.cfg"), b"[nlp]");
+        write(&source.join("embeddings/model.safetensors"), &[7; 3000]);
+
+        let target = dir.path().join("otro disco/models");
+        let mut reported = (0, 0);
+        copy_tree(&source, &target, &mut |copied, total| {
+            reported = (copied, total)
+        })
+        .unwrap();
+
+        assert_eq!(reported, (3005, 3005));
+        assert_eq!(
+            fs::read(target.join("spacy/es_core_news_lg/config.cfg")).unwrap(),
+            b"[nlp]"
+        );
+        assert_eq!(
+            fs::metadata(target.join("embeddings/model.safetensors"))
+                .unwrap()
+                .len(),
+            3000
+        );
+    }
+
+    #[test]
+    fn target_must_be_empty_and_outside_the_current_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("models");
+        write(&source.join("spacy/config.cfg"), b"");
+        write(&dir.path().join("ocupado/fichero"), b"");
+
+        assert!(check_target(&source, &source.join("nuevo")).is_err());
+        assert!(check_target(&source, &dir.path().join("ocupado")).is_err());
+        assert!(check_target(&source, Path::new("relativo")).is_err());
+
+        let target = dir.path().join("vacio");
+        fs::create_dir(&target).unwrap();
+        check_target(&source, &target).unwrap();
+        move_tree(&source, &target, &mut |_, _| {}).unwrap();
+        assert!(!source.exists());
+        assert!(target.join("spacy/config.cfg").is_file());
+    }
+}
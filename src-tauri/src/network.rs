@@ -0,0 +1,132 @@
+// Conectividad de red de Narrative Assistant
+//
+// Un monitor en segundo plano comprueba si hay salida a internet (con el
+// proxy configurado, ver proxy.rs) y emite `network-status` cuando cambia.
+// Las funciones que dependen de la red (descarga de modelos, actualizaciones,
+// LLM remotos) lo consultan para avisar en lugar de agotar tiempos de espera
+// una y otra vez.
+//
+// La sonda es una petición HEAD al mismo servidor que las actualizaciones
+// (api.github.com); cualquier respuesta HTTP cuenta como conexión. Sin
+// conexión se reintenta cada 30 s; con conexión, cada 5 min.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::clock::now_secs;
+use crate::proxy;
+
+/// Servidor de la sonda (el mismo que ya se contacta para las actualizaciones)
+const PROBE_URL: &str = "https://api.github.com/";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const ONLINE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Estado de la red (payload de `network-status`)
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkStatus {
+    pub online: bool,
+    /// Última comprobación (segundos UNIX)
+    pub checked_at: u64,
+}
+
+/// Último estado conocido, registrado con `.manage()`
+pub struct NetworkState(Mutex<Option<NetworkStatus>>);
+
+impl NetworkState {
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Guarda el resultado de una sonda. Devuelve si cambió la conectividad.
+    fn record(&self, status: NetworkStatus) -> bool {
+        let mut current = self.0.lock().unwrap();
+        let changed = current.is_none_or(|previous| previous.online != status.online);
+        *current = Some(status);
+        changed
+    }
+}
+
+/// Si la última comprobación fue sin conexión (sin comprobar aún se asume que hay red)
+pub fn is_offline(app: &AppHandle) -> bool {
+    app.state::<NetworkState>()
+        .0
+        .lock()
+        .unwrap()
+        .is_some_and(|status| !status.online)
+}
+
+async fn probe(app: &AppHandle) -> bool {
+    let Ok(client) = proxy::http_client(app).await else {
+        return false;
+    };
+    client
+        .head(PROBE_URL)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .is_ok()
+}
+
+/// Comprueba la conexión y emite `network-status` si ha cambiado
+async fn check(app: &AppHandle) -> NetworkStatus {
+    let status = NetworkStatus {
+        online: probe(app).await,
+        checked_at: now_secs(),
+    };
+    if app.state::<NetworkState>().record(status) {
+        tracing::info!(
+            target: "network",
+            "{}",
+            if status.online { "Conexion a internet disponible" } else { "Sin conexion a internet" }
+        );
+        let _ = app.emit("network-status", status);
+    }
+    status
+}
+
+/// Monitor en segundo plano
+pub async fn monitor(app: AppHandle) {
+    loop {
+        let status = check(&app).await;
+        let interval = if status.online {
+            ONLINE_INTERVAL
+        } else {
+            OFFLINE_INTERVAL
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Comprueba la conexión ahora (p. ej. antes de descargar un modelo)
+#[tauri::command]
+pub async fn check_connectivity(app: AppHandle) -> Result<NetworkStatus, String> {
+    Ok(check(&app).await)
+}
+
+/// Último estado conocido sin hacer peticiones (`None` si aún no se ha comprobado)
+#[tauri::command]
+pub fn get_network_status(state: State<'_, NetworkState>) -> Option<NetworkStatus> {
+    *state.0.lock().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_connectivity_transitions_are_reported() {
+        let state = NetworkState::new();
+        let status = |online, checked_at| NetworkStatus { online, checked_at };
+
+        assert!(state.record(status(true, 1)));
+        assert!(!state.record(status(true, 2)));
+        assert!(state.record(status(false, 3)));
+        assert!(!state.record(status(false, 4)));
+        assert!(state.record(status(true, 5)));
+        assert_eq!(*state.0.lock().unwrap(), Some(status(true, 5)));
+    }
+}
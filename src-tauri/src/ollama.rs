@@ -0,0 +1,707 @@
+// Servicio local de Ollama
+//
+// Las funciones con LLM dependen de que Ollama esté instalado y sirviendo en
+// 127.0.0.1:11434. Sin él, el backend solo puede devolver errores de conexión
+// poco claros. El shell lo comprueba y lo gestiona:
+//   - `ollama_status`: si está instalado (PATH o rutas habituales, las mismas
+//     que llm/ollama_manager.py), si responde y qué versión tiene
+//   - `start_ollama`: lanza `ollama serve` y espera a que responda
+//   - `stop_ollama`: detiene el proceso que lanzó `start_ollama`. Un Ollama
+//     iniciado por el sistema o por el usuario no se toca (puede estar sirviendo
+//     a otras aplicaciones)
+//   - `pull_ollama_model`: descarga un modelo con POST /api/pull, que responde
+//     una línea JSON por avance de cada capa. Se emiten como
+//     `ollama-pull-progress` (con el total de todas las capas) y al terminar
+//     `ollama-pull-finished`. `cancel_ollama_pull` corta la conexión, con lo que
+//     Ollama detiene la descarga (y la reanuda donde iba si se vuelve a pedir)
+//   - `delete_ollama_model`: borra un modelo con DELETE /api/delete. Solo los
+//     LLM del manifiesto: ~/.ollama se comparte con otras aplicaciones y
+//     cleanup.rs nunca lo toca en disco
+//   - `check_version`: al arrancar, si Ollama está instalado (el usuario usa
+//     las funciones con LLM), compara su versión con `MIN_OLLAMA_VERSION`.
+//     Un Ollama antiguo no conoce los modelos del manifiesto ni algunas rutas
+//     de la API y responde 404 o 412 a mitad de una generación; en su lugar se
+//     emite `ollama-version-warning` para ofrecer la actualización
+//     (`install_ollama` con `update`)
+//
+// La instalación guiada está en ollama_install.rs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::models::{ModelKind, ModelManifest};
+use crate::proxy;
+
+/// API local de Ollama
+pub(crate) const OLLAMA_API_URL: &str = "http://127.0.0.1:11434/api";
+
+/// Tiempo máximo de espera a que `ollama serve` responda
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Versión mínima de Ollama: la primera que sirve qwen3, el LLM por defecto
+/// del manifiesto (y acepta `model` en /api/pull y /api/delete)
+pub(crate) const MIN_OLLAMA_VERSION: &str = "0.6.6";
+
+/// Intervalo mínimo entre eventos de progreso de una misma capa
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Estado de Ollama en el shell, registrado con `.manage()`
+pub struct OllamaState {
+    /// Proceso lanzado por `start_ollama`
+    child: Mutex<Option<Child>>,
+    /// Descargas en curso: modelo -> petición de cancelación
+    pulls: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl OllamaState {
+    pub fn new() -> Self {
+        Self {
+            child: Mutex::new(None),
+            pulls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Detiene el proceso lanzado por `start_ollama`, si lo hay
+    pub(crate) fn stop_managed(&self) {
+        if let Some(mut child) = self.child.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    /// Si el proceso lanzado por el shell sigue vivo
+    fn is_managed(&self) -> bool {
+        let mut child = self.child.lock().unwrap();
+        match child.as_mut().map(|c| c.try_wait()) {
+            Some(Ok(None)) => true,
+            Some(_) => {
+                *child = None;
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// Estado de Ollama (respuesta de los comandos y payload de `ollama-status`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct OllamaStatus {
+    pub installed: bool,
+    pub running: bool,
+    pub version: Option<String>,
+    /// Ejecutable encontrado
+    pub executable: Option<String>,
+    /// Lo lanzó el shell (y por tanto `stop_ollama` puede detenerlo)
+    pub managed: bool,
+    /// Versión anterior a `MIN_OLLAMA_VERSION`
+    pub outdated: bool,
+}
+
+/// Aviso de Ollama antiguo (payload de `ollama-version-warning`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct OllamaVersionWarning {
+    pub version: String,
+    pub min_version: &'static str,
+    /// Acción que ofrece la UI: siempre "update_ollama"
+    pub action: &'static str,
+    /// Si `install_ollama` puede actualizarlo (instalador oficial de Windows y macOS)
+    pub can_update: bool,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+fn executable_name() -> &'static str {
+    if cfg!(windows) {
+        "ollama.exe"
+    } else {
+        "ollama"
+    }
+}
+
+/// Rutas habituales de instalación fuera del PATH
+fn common_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if cfg!(windows) {
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            paths.push(
+                PathBuf::from(local)
+                    .join("Programs")
+                    .join("Ollama")
+                    .join("ollama.exe"),
+            );
+        }
+        for var in ["PROGRAMFILES", "PROGRAMFILES(X86)"] {
+            if let Some(base) = std::env::var_os(var) {
+                paths.push(PathBuf::from(base).join("Ollama").join("ollama.exe"));
+            }
+        }
+    } else if cfg!(target_os = "macos") {
+        paths.push("/usr/local/bin/ollama".into());
+        paths.push("/opt/homebrew/bin/ollama".into());
+        paths.push("/Applications/Ollama.app/Contents/Resources/ollama".into());
+        if let Some(home) = dirs::home_dir() {
+            paths.push(home.join(".ollama").join("ollama"));
+        }
+    } else {
+        paths.push("/usr/local/bin/ollama".into());
+        paths.push("/usr/bin/ollama".into());
+    }
+    paths
+}
+
+/// Primer `name` que exista en `dirs`, o en `fallbacks`
+fn find_in(
+    dirs: impl IntoIterator<Item = PathBuf>,
+    name: &str,
+    fallbacks: &[PathBuf],
+) -> Option<PathBuf> {
+    dirs.into_iter()
+        .map(|dir| dir.join(name))
+        .chain(fallbacks.iter().cloned())
+        .find(|path| path.is_file())
+}
+
+/// Ejecutable de Ollama
+pub(crate) fn find_executable() -> Option<PathBuf> {
+    let path_dirs = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    find_in(path_dirs, executable_name(), &common_paths())
+}
+
+fn hidden_command(program: &Path) -> Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    let mut command = Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// Versión en la salida de `ollama --version` ("ollama version is 0.5.7";
+/// sin servidor, "client version is 0.5.7" tras un aviso)
+fn parse_version_output(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, rest) = line.split_once("version")?;
+        let version = rest.trim().trim_start_matches("is").trim();
+        (!version.is_empty()).then(|| version.to_string())
+    })
+}
+
+/// (mayor, menor, parche) de "0.6.6", "v0.5.7" o "0.7.0-rc1"
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+', ' ']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// Si `version` es anterior a la mínima (una versión ilegible no se da por antigua)
+pub(crate) fn is_outdated(version: &str) -> bool {
+    match (parse_version(version), parse_version(MIN_OLLAMA_VERSION)) {
+        (Some(version), Some(minimum)) => version < minimum,
+        _ => false,
+    }
+}
+
+/// Versión del servidor si responde (`None` si no está sirviendo)
+async fn server_version() -> Option<String> {
+    let response = proxy::local_client()
+        .get(format!("{}/version", OLLAMA_API_URL))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response
+        .json::<VersionResponse>()
+        .await
+        .ok()
+        .map(|v| v.version)
+}
+
+pub(crate) async fn status(state: &OllamaState) -> OllamaStatus {
+    let executable = find_executable();
+    let server = server_version().await;
+    let running = server.is_some();
+    let version = match (server, &executable) {
+        (Some(version), _) => Some(version),
+        (None, Some(exe)) => {
+            let exe = exe.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let output = hidden_command(&exe).arg("--version").output().ok()?;
+                parse_version_output(&String::from_utf8_lossy(&output.stdout))
+            })
+            .await
+            .ok()
+            .flatten()
+        }
+        (None, None) => None,
+    };
+    OllamaStatus {
+        // Un servidor que responde cuenta como instalado aunque no se encuentre el ejecutable
+        installed: executable.is_some() || running,
+        running,
+        executable: executable.map(|p| p.to_string_lossy().into_owned()),
+        managed: state.is_managed(),
+        outdated: version.as_deref().is_some_and(is_outdated),
+        version,
+    }
+}
+
+/// Comprueba al arrancar la versión de Ollama y emite `ollama-version-warning`
+/// si es demasiado antigua. Sin Ollama instalado no hay nada que avisar.
+pub async fn check_version(app: &AppHandle) {
+    let state = app.state::<OllamaState>();
+    let status = status(&state).await;
+    let Some(version) = status.version.filter(|_| status.outdated) else {
+        return;
+    };
+    tracing::warn!(
+        target: "ollama",
+        "Ollama {} es anterior a la version minima {}",
+        version,
+        MIN_OLLAMA_VERSION
+    );
+    let _ = app.emit(
+        "ollama-version-warning",
+        OllamaVersionWarning {
+            version,
+            min_version: MIN_OLLAMA_VERSION,
+            action: "update_ollama",
+            can_update: crate::ollama_install::has_installer(),
+        },
+    );
+}
+
+/// Si Ollama responde en 127.0.0.1:11434
+pub(crate) async fn is_running() -> bool {
+    server_version().await.is_some()
+}
+
+/// Instalado, en marcha y versión de Ollama
+#[tauri::command]
+pub async fn ollama_status(state: State<'_, OllamaState>) -> Result<OllamaStatus, String> {
+    Ok(status(&state).await)
+}
+
+/// Inicia `ollama serve` si no está en marcha y espera a que responda
+#[tauri::command]
+pub async fn start_ollama(
+    app: AppHandle,
+    state: State<'_, OllamaState>,
+) -> Result<OllamaStatus, String> {
+    if is_running().await {
+        return Ok(status(&state).await);
+    }
+    let exe = find_executable().ok_or("Ollama no esta instalado")?;
+
+    let mut command = hidden_command(&exe);
+    command
+        .arg("serve")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Su propio grupo de procesos: no recibe las señales dirigidas a la app
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let child = command
+        .spawn()
+        .map_err(|e| format!("No se pudo iniciar Ollama: {}", e))?;
+    tracing::info!(target: "ollama", "Iniciando {} serve (pid {})", exe.display(), child.id());
+    *state.child.lock().unwrap() = Some(child);
+
+    let deadline = tokio::time::Instant::now() + START_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        if is_running().await {
+            let status = status(&state).await;
+            let _ = app.emit("ollama-status", &status);
+            return Ok(status);
+        }
+        if !state.is_managed() {
+            return Err("Ollama se cerro al iniciarse".into());
+        }
+    }
+    Err(format!(
+        "Ollama no respondio tras {} s",
+        START_TIMEOUT.as_secs()
+    ))
+}
+
+/// Detiene el Ollama lanzado por `start_ollama`
+#[tauri::command]
+pub async fn stop_ollama(
+    app: AppHandle,
+    state: State<'_, OllamaState>,
+) -> Result<OllamaStatus, String> {
+    let child = state.child.lock().unwrap().take();
+    let Some(mut child) = child else {
+        return Err(
+            "Ollama no lo inicio Narrative Assistant: detengalo desde su aplicacion".into(),
+        );
+    };
+    let _ = child.kill();
+    let _ = child.wait();
+    tracing::info!(target: "ollama", "Ollama detenido");
+    let status = status(&state).await;
+    let _ = app.emit("ollama-status", &status);
+    Ok(status)
+}
+
+/// Línea de la respuesta de /api/pull
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+struct PullLine {
+    status: String,
+    digest: Option<String>,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+/// Progreso de una descarga (payload de `ollama-pull-progress`)
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PullProgress {
+    pub model: String,
+    /// Fase según Ollama: "pulling manifest", "pulling <digest>", "verifying sha256 digest"...
+    pub status: String,
+    /// Capa que se está descargando
+    pub digest: Option<String>,
+    pub layer_completed: u64,
+    pub layer_total: u64,
+    /// Bytes de todas las capas conocidas hasta ahora
+    pub completed: u64,
+    pub total: u64,
+    /// 0-100, `None` mientras no se conoce ninguna capa
+    pub percent: Option<f64>,
+}
+
+/// Parte la respuesta en líneas JSON y acumula el progreso de cada capa
+#[derive(Default)]
+struct PullTracker {
+    pending: Vec<u8>,
+    /// Capa -> (descargado, total)
+    layers: BTreeMap<String, (u64, u64)>,
+}
+
+impl PullTracker {
+    /// Líneas completas recibidas con `chunk` (las mal formadas se ignoran)
+    fn feed(&mut self, chunk: &[u8]) -> Vec<PullLine> {
+        self.pending.extend_from_slice(chunk);
+        let Some(end) = self.pending.iter().rposition(|b| *b == b'\n') else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = self.pending.drain(..=end).collect();
+        complete
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .filter_map(|line| serde_json::from_slice(line).ok())
+            .collect()
+    }
+
+    fn progress(&mut self, model: &str, line: &PullLine) -> PullProgress {
+        let (layer_completed, layer_total) = match (&line.digest, line.total) {
+            (Some(digest), Some(total)) => {
+                let layer = (line.completed.unwrap_or(0).min(total), total);
+                self.layers.insert(digest.clone(), layer);
+                layer
+            }
+            _ => (0, 0),
+        };
+        let (completed, total) = self
+            .layers
+            .values()
+            .fold((0, 0), |(c, t), (lc, lt)| (c + lc, t + lt));
+        PullProgress {
+            model: model.to_string(),
+            status: line.status.clone(),
+            digest: line.digest.clone(),
+            layer_completed,
+            layer_total,
+            completed,
+            total,
+            percent: (total > 0).then(|| completed as f64 * 100.0 / total as f64),
+        }
+    }
+}
+
+/// Nombre de modelo de Ollama ("llama3.2", "qwen2.5:7b", "usuario/modelo:tag")
+fn valid_model_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 200
+        && !name.starts_with(['-', '.', '/'])
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._:/-".contains(c))
+}
+
+async fn pull(app: &AppHandle, name: &str, cancel: &AtomicBool) -> Result<(), String> {
+    let mut response = proxy::local_client()
+        .post(format!("{}/pull", OLLAMA_API_URL))
+        .json(&serde_json::json!({ "model": name, "stream": true }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Error descargando {}: {}", name, e))?;
+
+    let mut tracker = PullTracker::default();
+    let mut last_emit: Option<(String, Instant)> = None;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Error descargando {}: {}", name, e))?
+    {
+        if cancel.load(Ordering::SeqCst) {
+            // Al soltar la respuesta se cierra la conexión y Ollama para
+            return Err("Descarga cancelada".into());
+        }
+        for line in tracker.feed(&chunk) {
+            if let Some(error) = line.error {
+                return Err(format!("Ollama no pudo descargar {}: {}", name, error));
+            }
+            let progress = tracker.progress(name, &line);
+            // Cada cambio de fase se emite; el avance de una capa, como mucho cada 250 ms
+            let throttled = last_emit.as_ref().is_some_and(|(status, at)| {
+                *status == progress.status && at.elapsed() < PROGRESS_EVENT_INTERVAL
+            });
+            if !throttled {
+                last_emit = Some((progress.status.clone(), Instant::now()));
+                let _ = app.emit("ollama-pull-progress", &progress);
+            }
+            if line.status == "success" {
+                return Ok(());
+            }
+        }
+    }
+    Err(format!("La descarga de {} termino sin confirmar", name))
+}
+
+/// Descarga un modelo en Ollama emitiendo `ollama-pull-progress`. Termina al
+/// completarse, fallar o cancelarse (`ollama-pull-finished` en los tres casos).
+#[tauri::command]
+pub async fn pull_ollama_model(
+    app: AppHandle,
+    state: State<'_, OllamaState>,
+    name: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if !valid_model_name(&name) {
+        return Err(format!("Nombre de modelo no valido: {}", name));
+    }
+    if !is_running().await {
+        return Err("Ollama no esta en marcha".into());
+    }
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut pulls = state.pulls.lock().unwrap();
+        if pulls.contains_key(&name) {
+            return Err(format!("Ya hay una descarga en curso de {}", name));
+        }
+        pulls.insert(name.clone(), Arc::clone(&cancel));
+    }
+
+    tracing::info!(target: "ollama", "Descargando el modelo {}", name);
+    let result = pull(&app, &name, &cancel).await;
+    state.pulls.lock().unwrap().remove(&name);
+
+    let cancelled = cancel.load(Ordering::SeqCst);
+    match &result {
+        Ok(()) => tracing::info!(target: "ollama", "Modelo {} descargado", name),
+        Err(_) if cancelled => tracing::info!(target: "ollama", "Descarga de {} cancelada", name),
+        Err(e) => tracing::warn!(target: "ollama", "{}", e),
+    }
+    let _ = app.emit(
+        "ollama-pull-finished",
+        serde_json::json!({
+            "model": name,
+            "success": result.is_ok(),
+            "cancelled": cancelled,
+            "error": result.as_ref().err(),
+        }),
+    );
+    result
+}
+
+/// Cancela la descarga en curso de `name`. Devuelve si había una.
+#[tauri::command]
+pub fn cancel_ollama_pull(state: State<'_, OllamaState>, name: String) -> bool {
+    match state.pulls.lock().unwrap().get(name.trim()) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Si `name` (`modelo` o `modelo:tag`) es un LLM del manifiesto
+fn is_app_model(manifest: &ModelManifest, name: &str) -> bool {
+    let base = name.split(':').next().unwrap_or(name);
+    manifest.find(ModelKind::Ollama, base).is_some()
+}
+
+/// Borra un modelo de Ollama instalado por la app para liberar espacio
+#[tauri::command]
+pub async fn delete_ollama_model(
+    state: State<'_, OllamaState>,
+    name: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if !valid_model_name(&name) {
+        return Err(format!("Nombre de modelo no valido: {}", name));
+    }
+    if !is_app_model(&ModelManifest::bundled(), &name) {
+        return Err(format!(
+            "{} no es un modelo de Narrative Assistant: borralo desde Ollama",
+            name
+        ));
+    }
+    if state.pulls.lock().unwrap().contains_key(&name) {
+        return Err(format!("Hay una descarga en curso de {}", name));
+    }
+    if !is_running().await {
+        return Err("Ollama no esta en marcha".into());
+    }
+
+    let response = proxy::local_client()
+        .delete(format!("{}/delete", OLLAMA_API_URL))
+        .json(&serde_json::json!({ "model": name }))
+        .send()
+        .await
+        .map_err(|e| format!("Error conectando con Ollama: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("{} no esta instalado en Ollama", name));
+    }
+    response
+        .error_for_status()
+        .map_err(|e| format!("Error borrando {}: {}", name, e))?;
+    tracing::info!(target: "ollama", "Modelo {} borrado", name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_manifest_models_can_be_deleted() {
+        let manifest = ModelManifest::bundled();
+        assert!(is_app_model(&manifest, "qwen3"));
+        assert!(is_app_model(&manifest, "llama3.2:latest"));
+        assert!(!is_app_model(&manifest, "codellama:7b"));
+        assert!(!is_app_model(&manifest, "someone/qwen3:latest"));
+    }
+
+    #[test]
+    fn versions_older_than_the_minimum_are_outdated() {
+        assert_eq!(parse_version("v0.5.7"), Some((0, 5, 7)));
+        assert_eq!(parse_version("0.7.0-rc1"), Some((0, 7, 0)));
+        assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+        assert!(is_outdated("0.5.7"));
+        assert!(is_outdated("0.6.5"));
+        assert!(!is_outdated("0.6.6"));
+        assert!(!is_outdated("0.12.3"));
+        assert!(!is_outdated("desconocida"));
+    }
+
+    #[test]
+    fn version_is_read_from_cli_output() {
+        assert_eq!(
+            parse_version_output("ollama version is 0.5.7\n").as_deref(),
+            Some("0.5.7")
+        );
+        assert_eq!(
+            parse_version_output(
+                "Warning: could not connect to a running Ollama instance\n\
+                 Warning: client version is 0.6.2\n"
+            )
+            .as_deref(),
+            Some("0.6.2")
+        );
+        assert_eq!(parse_version_output(""), None);
+    }
+
+    #[test]
+    fn pull_lines_are_split_across_chunks_and_layers_summed() {
+        let mut tracker = PullTracker::default();
+        assert!(
+            tracker
+                .feed(b"{\"status\":\"pulling manifest\"}\n{\"status\":\"pul")
+                .len()
+                == 1
+        );
+        let lines = tracker.feed(
+            b"ling aa\",\"digest\":\"sha256:aa\",\"total\":300,\"completed\":100}\n\
+              {\"status\":\"pulling bb\",\"digest\":\"sha256:bb\",\"total\":100,\"completed\":100}\n",
+        );
+        assert_eq!(lines.len(), 2);
+        tracker.progress("llama3.2", &lines[0]);
+        let progress = tracker.progress("llama3.2", &lines[1]);
+        assert_eq!((progress.completed, progress.total), (200, 400));
+        assert_eq!(progress.percent, Some(50.0));
+        assert_eq!(progress.layer_completed, 100);
+
+        let error = tracker.feed(b"{\"error\":\"pull model manifest: file does not exist\"}\n");
+        assert!(error[0].error.is_some());
+
+        assert!(valid_model_name("qwen2.5:7b-instruct"));
+        assert!(valid_model_name("usuario/modelo:latest"));
+        assert!(!valid_model_name("--insecure"));
+        assert!(!valid_model_name("modelo; rm -rf"));
+    }
+
+    #[test]
+    fn executable_is_searched_in_path_then_fallbacks() {
+        let dir = tempfile::tempdir().unwrap();
+        let fallback = dir.path().join("fallback").join("ollama");
+        assert_eq!(
+            find_in(
+                [dir.path().to_path_buf()],
+                "ollama",
+                std::slice::from_ref(&fallback)
+            ),
+            None
+        );
+
+        std::fs::create_dir_all(fallback.parent().unwrap()).unwrap();
+        std::fs::write(&fallback, b"").unwrap();
+        assert_eq!(
+            find_in(
+                [dir.path().to_path_buf()],
+                "ollama",
+                std::slice::from_ref(&fallback)
+            ),
+            Some(fallback)
+        );
+
+        let in_path = dir.path().join("ollama");
+        std::fs::write(&in_path, b"").unwrap();
+        assert_eq!(
+            find_in([dir.path().to_path_buf()], "ollama", &[]),
+            Some(in_path)
+        );
+    }
+}
@@ -0,0 +1,307 @@
+// Instalación guiada de Ollama
+//
+// Instalar Ollama a mano son varios pasos fuera de la app (web, descarga,
+// instalador, volver y pulsar "Iniciar"). `install_ollama` los hace de una vez:
+//   - Windows y macOS: descarga el instalador oficial de la última release de
+//     GitHub (OllamaSetup.exe / Ollama-darwin.zip), comprueba su SHA-256 con el
+//     sha256sum.txt publicado en la misma release, lo lanza (en macOS copia
+//     Ollama.app a Aplicaciones y la abre) y espera a que el servicio responda
+//   - Linux y otros: no hay instalador gráfico; devuelve los comandos (brew si
+//     está disponible, si no el script oficial) para que el frontend los muestre
+//
+// Con `update`, lo mismo sobre un Ollama ya instalado: el instalador oficial
+// sustituye la versión anterior (lo ofrece `ollama-version-warning`, ver
+// ollama.rs). Se espera a que responda la versión nueva, no la antigua.
+//
+// El progreso se emite como `ollama-install-progress` con la fase
+// ("checksum", "download", "launch", "waiting") y los bytes descargados.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::downloads::{to_hex, RateLimit};
+use crate::ollama::{self, OllamaState, OllamaStatus};
+use crate::proxy;
+
+/// Releases de Ollama (el instalador y las sumas se piden a la última)
+const RELEASE_DOWNLOAD_URL: &str = "https://github.com/ollama/ollama/releases/latest/download";
+
+/// Sumas SHA-256 de todos los ficheros de la release
+const CHECKSUMS_FILE: &str = "sha256sum.txt";
+
+/// Tiempo máximo de espera a que el servicio responda tras lanzar el
+/// instalador (el usuario tiene que completarlo)
+const SERVICE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Tiempo máximo de la descarga de las sumas (la del instalador no tiene límite)
+const CHECKSUMS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Evita dos instalaciones a la vez
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+/// Resultado de `install_ollama`
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum InstallOutcome {
+    /// Ollama instalado y respondiendo
+    Installed { status: OllamaStatus },
+    /// Sin instalador para esta plataforma: comandos para una terminal
+    Manual { commands: Vec<String>, url: String },
+}
+
+/// Instalador oficial de esta plataforma
+fn installer_asset() -> Option<&'static str> {
+    if cfg!(target_os = "windows") {
+        Some("OllamaSetup.exe")
+    } else if cfg!(target_os = "macos") {
+        Some("Ollama-darwin.zip")
+    } else {
+        None
+    }
+}
+
+/// Si `install_ollama` puede instalar o actualizar Ollama sin la terminal
+pub(crate) fn has_installer() -> bool {
+    installer_asset().is_some()
+}
+
+/// Si Homebrew está en el PATH
+fn has_brew() -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join("brew").is_file()))
+}
+
+/// Comandos de instalación cuando no hay instalador gráfico
+fn manual_commands(has_brew: bool) -> Vec<String> {
+    let mut commands = Vec::new();
+    if has_brew {
+        commands.push("brew install ollama".to_string());
+    }
+    commands.push("curl -fsSL https://ollama.com/install.sh | sh".to_string());
+    commands
+}
+
+/// Suma de `asset` en un sha256sum.txt ("<hex>  ./OllamaSetup.exe")
+fn expected_checksum(checksums: &str, asset: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim().trim_start_matches('*').trim_start_matches("./");
+        (name == asset && hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| hash.to_ascii_lowercase())
+    })
+}
+
+fn emit_progress(app: &AppHandle, phase: &str, downloaded: u64, total: Option<u64>) {
+    let _ = app.emit(
+        "ollama-install-progress",
+        serde_json::json!({ "phase": phase, "downloaded": downloaded, "total": total }),
+    );
+}
+
+/// Descarga `url` en `path` y devuelve su SHA-256 en hexadecimal
+async fn download_hashed(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+) -> Result<String, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Error descargando Ollama: {}", e))?;
+    let total = response.content_length();
+    let mut file =
+        fs::File::create(path).map_err(|e| format!("Error creando {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut rate = RateLimit::new(app);
+    let mut downloaded: u64 = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Error descargando Ollama: {}", e))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        emit_progress(app, "download", downloaded, total);
+        rate.consume(chunk.len()).await;
+    }
+    file.sync_all().map_err(|e| e.to_string())?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Lanza el instalador descargado
+#[cfg(target_os = "macos")]
+fn launch(installer: &Path) -> Result<(), String> {
+    use std::process::Command;
+
+    // Ollama.app va a /Applications; sin permisos, a ~/Applications
+    let mut destinations = vec![std::path::PathBuf::from("/Applications")];
+    if let Some(home) = dirs::home_dir() {
+        destinations.push(home.join("Applications"));
+    }
+    // Al actualizar, cerrar la versión en marcha antes de sustituirla
+    let _ = Command::new("osascript")
+        .args(["-e", "quit app \"Ollama\""])
+        .status();
+    for destination in destinations {
+        let _ = fs::create_dir_all(&destination);
+        let extracted = Command::new("ditto")
+            .arg("-x")
+            .arg("-k")
+            .arg(installer)
+            .arg(&destination)
+            .status()
+            .is_ok_and(|status| status.success());
+        if extracted {
+            let app = destination.join("Ollama.app");
+            tracing::info!(target: "ollama", "Ollama copiado en {}", app.display());
+            return Command::new("open")
+                .arg(&app)
+                .spawn()
+                .map(|_| ())
+                .map_err(|e| format!("No se pudo abrir Ollama: {}", e));
+        }
+    }
+    Err("No se pudo copiar Ollama a Aplicaciones".into())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn launch(installer: &Path) -> Result<(), String> {
+    std::process::Command::new(installer)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("No se pudo lanzar el instalador de Ollama: {}", e))
+}
+
+/// Descarga, verifica y lanza el instalador; espera a que Ollama responda
+async fn install(
+    app: &AppHandle,
+    state: &OllamaState,
+    asset: &str,
+) -> Result<InstallOutcome, String> {
+    let client = proxy::http_client(app).await?;
+
+    emit_progress(app, "checksum", 0, None);
+    let checksums = client
+        .get(format!("{}/{}", RELEASE_DOWNLOAD_URL, CHECKSUMS_FILE))
+        .timeout(CHECKSUMS_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Error descargando las sumas de Ollama: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Error descargando las sumas de Ollama: {}", e))?;
+    let expected = expected_checksum(&checksums, asset)
+        .ok_or_else(|| format!("La release de Ollama no publica la suma de {}", asset))?;
+
+    let dir = std::env::temp_dir().join("narrative-assistant-ollama");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Error creando {}: {}", dir.display(), e))?;
+    let installer = dir.join(asset);
+    let url = format!("{}/{}", RELEASE_DOWNLOAD_URL, asset);
+    let actual = download_hashed(app, &client, &url, &installer).await?;
+    if actual != expected {
+        let _ = fs::remove_dir_all(&dir);
+        tracing::warn!(target: "ollama", "Suma de {} incorrecta: {} (esperada {})", asset, actual, expected);
+        return Err("El instalador de Ollama descargado esta danado: vuelva a intentarlo".into());
+    }
+
+    emit_progress(app, "launch", 0, None);
+    tracing::info!(target: "ollama", "Lanzando el instalador de Ollama {}", installer.display());
+    launch(&installer)?;
+
+    emit_progress(app, "waiting", 0, None);
+    let deadline = tokio::time::Instant::now() + SERVICE_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        if !ollama::is_running().await {
+            continue;
+        }
+        // Al actualizar, el Ollama antiguo puede seguir respondiendo un rato
+        let status = ollama::status(state).await;
+        if !status.outdated {
+            tracing::info!(target: "ollama", "Ollama instalado ({:?})", status.version);
+            let _ = app.emit("ollama-status", &status);
+            return Ok(InstallOutcome::Installed { status });
+        }
+    }
+    Err(format!(
+        "Ollama no respondio tras {} minutos: termine el instalador y pulse \"Iniciar\"",
+        SERVICE_TIMEOUT.as_secs() / 60
+    ))
+}
+
+/// Instala Ollama con el instalador oficial, o devuelve los comandos para
+/// instalarlo si la plataforma no tiene instalador. Si ya está instalado no
+/// hace nada, salvo con `update` (instala la última versión encima).
+#[tauri::command]
+pub async fn install_ollama(
+    app: AppHandle,
+    state: State<'_, OllamaState>,
+    update: Option<bool>,
+) -> Result<InstallOutcome, String> {
+    let status = ollama::status(&state).await;
+    let update = update.unwrap_or(false) && status.installed;
+    if status.installed && !update {
+        return Ok(InstallOutcome::Installed { status });
+    }
+    let Some(asset) = installer_asset() else {
+        return Ok(InstallOutcome::Manual {
+            commands: manual_commands(has_brew()),
+            url: "https://ollama.com/download".into(),
+        });
+    };
+
+    if INSTALLING.swap(true, Ordering::SeqCst) {
+        return Err("Ya se esta instalando Ollama".into());
+    }
+    if update {
+        tracing::info!(target: "ollama", "Actualizando Ollama {:?}", status.version);
+        // El que lanzó el shell tiene el ejecutable abierto
+        state.stop_managed();
+    }
+    let result = install(&app, &state, asset).await;
+    INSTALLING.store(false, Ordering::SeqCst);
+    if let Err(e) = &result {
+        tracing::warn!(target: "ollama", "{}", e);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_is_read_for_the_requested_asset() {
+        let checksums = format!(
+            "{}  ./ollama-linux-amd64.tgz\n{}  ./OllamaSetup.exe\nno-es-una-suma  ./Ollama-darwin.zip\n",
+            "a".repeat(64),
+            "B".repeat(64)
+        );
+        assert_eq!(
+            expected_checksum(&checksums, "OllamaSetup.exe"),
+            Some("b".repeat(64))
+        );
+        assert_eq!(expected_checksum(&checksums, "Ollama-darwin.zip"), None);
+        assert_eq!(expected_checksum(&checksums, "Ollama.dmg"), None);
+
+        // SHA-256 de la cadena vacía
+        assert_eq!(
+            to_hex(&Sha256::digest(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(manual_commands(true)[0], "brew install ollama");
+    }
+}
@@ -0,0 +1,200 @@
+// Progreso del primer uso de Narrative Assistant
+//
+// La primera vez hay que descargar los modelos, crear el primer proyecto
+// (importar un manuscrito) y, si el usuario quiere, seguir el tutorial. El
+// frontend guardaba esto en localStorage, que se pierde al vaciar la caché del
+// webview: el tutorial volvía a salir y los avisos de descarga no sabían si ya
+// se habían hecho. Ahora el progreso vive en onboarding.json, en el directorio
+// de datos de la app.
+//
+// Cada hito se marca con `advance_onboarding` (una sola vez: se guarda cuándo
+// ocurrió) y `get_onboarding_state` devuelve los hitos y la etapa actual, el
+// primero pendiente en orden: modelos -> importación -> tutorial -> completo.
+// Los cambios se emiten como `onboarding-changed` a todas las ventanas.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::{cleanup, settings};
+
+/// Nombre del fichero de progreso
+const ONBOARDING_FILE_NAME: &str = "onboarding.json";
+
+/// Etapa del primer uso (el primer hito pendiente)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    DownloadModels,
+    ImportManuscript,
+    Tutorial,
+    Complete,
+}
+
+/// Hito que avanza el primer uso
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Milestone {
+    ModelsDownloaded,
+    FirstImport,
+    TutorialCompleted,
+}
+
+/// Progreso guardado: cuándo se alcanzó cada hito (segundos UNIX)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+struct Progress {
+    models_downloaded_at: Option<u64>,
+    first_import_at: Option<u64>,
+    tutorial_completed_at: Option<u64>,
+}
+
+impl Progress {
+    fn slot(&mut self, milestone: Milestone) -> &mut Option<u64> {
+        match milestone {
+            Milestone::ModelsDownloaded => &mut self.models_downloaded_at,
+            Milestone::FirstImport => &mut self.first_import_at,
+            Milestone::TutorialCompleted => &mut self.tutorial_completed_at,
+        }
+    }
+
+    fn state(&self) -> OnboardingState {
+        let models_downloaded = self.models_downloaded_at.is_some();
+        let first_import = self.first_import_at.is_some();
+        let tutorial_completed = self.tutorial_completed_at.is_some();
+        let stage = if !models_downloaded {
+            Stage::DownloadModels
+        } else if !first_import {
+            Stage::ImportManuscript
+        } else if !tutorial_completed {
+            Stage::Tutorial
+        } else {
+            Stage::Complete
+        };
+        OnboardingState {
+            stage,
+            models_downloaded,
+            first_import,
+            tutorial_completed,
+        }
+    }
+}
+
+/// Estado del primer uso (respuesta de los comandos y payload de `onboarding-changed`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct OnboardingState {
+    pub stage: Stage,
+    pub models_downloaded: bool,
+    pub first_import: bool,
+    pub tutorial_completed: bool,
+}
+
+/// Progreso en memoria, registrado con `.manage()`
+pub struct OnboardingStore {
+    path: PathBuf,
+    progress: Mutex<Progress>,
+}
+
+impl OnboardingStore {
+    /// Carga onboarding.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(ONBOARDING_FILE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let progress = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Progress>(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            progress: Mutex::new(progress),
+        }
+    }
+
+    fn state(&self) -> OnboardingState {
+        self.progress.lock().unwrap().state()
+    }
+
+    /// Marca `milestone` en `now`. Devuelve el estado y si ha cambiado
+    /// (un hito ya alcanzado conserva su fecha).
+    fn advance(&self, milestone: Milestone, now: u64) -> Result<(OnboardingState, bool), String> {
+        let mut progress = self.progress.lock().unwrap();
+        let slot = progress.slot(milestone);
+        if slot.is_some() {
+            return Ok((progress.state(), false));
+        }
+        *slot = Some(now);
+        let json = serde_json::to_vec_pretty(&*progress).map_err(|e| e.to_string())?;
+        settings::write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))?;
+        Ok((progress.state(), true))
+    }
+}
+
+/// Hitos alcanzados y etapa actual del primer uso
+#[tauri::command]
+pub fn get_onboarding_state(store: State<'_, OnboardingStore>) -> OnboardingState {
+    store.state()
+}
+
+/// Marca un hito del primer uso (no hace nada si ya estaba marcado)
+#[tauri::command]
+pub fn advance_onboarding(
+    app: AppHandle,
+    store: State<'_, OnboardingStore>,
+    milestone: Milestone,
+) -> Result<OnboardingState, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (state, changed) = store.advance(milestone, now)?;
+    if changed {
+        tracing::info!(target: "onboarding", "Hito {:?}: etapa {:?}", milestone, state.stage);
+        let _ = app.emit("onboarding-changed", &state);
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_is_first_pending_milestone_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = OnboardingStore::load(dir.path().join(ONBOARDING_FILE_NAME));
+        assert_eq!(store.state().stage, Stage::DownloadModels);
+
+        // El tutorial puede hacerse antes de tener modelos
+        let (state, changed) = store.advance(Milestone::TutorialCompleted, 10).unwrap();
+        assert!(changed && state.tutorial_completed);
+        assert_eq!(state.stage, Stage::DownloadModels);
+
+        store.advance(Milestone::ModelsDownloaded, 20).unwrap();
+        assert_eq!(store.state().stage, Stage::ImportManuscript);
+        let (state, _) = store.advance(Milestone::FirstImport, 30).unwrap();
+        assert_eq!(state.stage, Stage::Complete);
+    }
+
+    #[test]
+    fn progress_survives_reload_and_keeps_first_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ONBOARDING_FILE_NAME);
+        let store = OnboardingStore::load(path.clone());
+        store.advance(Milestone::ModelsDownloaded, 100).unwrap();
+        let (_, changed) = store.advance(Milestone::ModelsDownloaded, 200).unwrap();
+        assert!(!changed);
+
+        let reloaded = OnboardingStore::load(path);
+        assert_eq!(reloaded.state().stage, Stage::ImportManuscript);
+        assert_eq!(
+            reloaded.progress.lock().unwrap().models_downloaded_at,
+            Some(100)
+        );
+    }
+}
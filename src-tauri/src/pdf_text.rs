@@ -0,0 +1,249 @@
+// Extracción de texto de PDF en Narrative Assistant
+//
+// Muchos correctores solo reciben el manuscrito en PDF. El backend lo importa
+// con pdfplumber, que no siempre está instalado en la app empaquetada y
+// tarda en libros largos. `extract_pdf_text` extrae el texto en el shell
+// (pdf-extract) página a página, emitiendo `pdf-extract-progress`
+// (`{ page, total }`) a la ventana, y lo guarda como `.txt` en
+// `<datos>/documents` para importarlo como manuscrito.
+//
+// Un PDF no guarda párrafos sino líneas posicionadas: se reconstruyen con
+// heurísticas (`Reflow`) y el resultado siempre va con `warning` para que la
+// UI avise. Las páginas sin texto (escaneadas) se devuelven en `empty_pages`.
+//
+// pdf-extract entra en pánico con algunos PDF mal formados: cada página se
+// procesa con `crash::catch_quietly` y una página rota solo se salta.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, State, WebviewWindow};
+
+use crate::crash;
+use crate::drop_import;
+use crate::settings::SettingsStore;
+
+/// Aviso que acompaña siempre al texto extraído
+const LAYOUT_WARNING: &str = "El texto se ha reconstruido a partir de la maquetacion del PDF: \
+     puede haber parrafos cortados, guiones de final de linea o cabeceras mezcladas con el texto. \
+     Revise el manuscrito antes de fiarse de las alertas de formato.";
+
+/// Si una línea más corta que esta fracción de la más larga cierra párrafo
+const SHORT_LINE_RATIO: f64 = 0.8;
+
+/// Resultado de `extract_pdf_text`
+#[derive(Serialize, Clone, Debug)]
+pub struct PdfExtraction {
+    /// `.txt` con el texto, en el directorio de documentos
+    pub path: String,
+    pub page_count: u32,
+    /// Páginas (1-based) sin texto: escaneadas, solo imagen o ilegibles
+    pub empty_pages: Vec<u32>,
+    pub word_count: usize,
+    pub warning: String,
+}
+
+/// Progreso (payload de `pdf-extract-progress`)
+#[derive(Serialize, Clone, Debug)]
+struct ExtractProgress {
+    page: u32,
+    total: u32,
+}
+
+fn ends_sentence(line: &str) -> bool {
+    line.ends_with(['.', '!', '?', ':', '»', '"', '”', '…'])
+}
+
+/// Reconstruye párrafos a partir de las líneas de cada página
+#[derive(Default)]
+struct Reflow {
+    paragraphs: Vec<String>,
+    /// Párrafo abierto (puede continuar en la página siguiente)
+    current: String,
+}
+
+impl Reflow {
+    fn close(&mut self) {
+        if !self.current.is_empty() {
+            self.paragraphs.push(std::mem::take(&mut self.current));
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.current.is_empty() {
+            self.current.push_str(line);
+        } else if self.current.ends_with('-') && line.starts_with(|c: char| c.is_lowercase()) {
+            // "pala-" + "bra": guion de final de línea
+            self.current.pop();
+            self.current.push_str(line);
+        } else {
+            self.current.push(' ');
+            self.current.push_str(line);
+        }
+    }
+
+    /// Añade el texto de una página. Las líneas en blanco separan párrafos, y
+    /// también una línea corta que acaba en punto (la última de un párrafo con
+    /// sangría). Los números de página sueltos se descartan.
+    fn push_page(&mut self, text: &str) {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.chars().all(|c| c.is_ascii_digit()) || line.is_empty())
+            .collect();
+        let longest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        for line in lines {
+            if line.is_empty() {
+                self.close();
+                continue;
+            }
+            self.push_line(line);
+            let short = (line.chars().count() as f64) < longest as f64 * SHORT_LINE_RATIO;
+            if short && ends_sentence(line) {
+                self.close();
+            }
+        }
+        // Un párrafo que no acaba en punto sigue en la página siguiente
+        if ends_sentence(&self.current) {
+            self.close();
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.close();
+        self.paragraphs
+    }
+}
+
+/// Texto de cada página (`None` si la página entró en pánico o falló)
+fn extract_pages(
+    path: &Path,
+    mut on_page: impl FnMut(u32, u32),
+) -> Result<Vec<Option<String>>, String> {
+    let mut doc = crash::catch_quietly(|| pdf_extract::Document::load(path))?
+        .map_err(|e| format!("No se pudo abrir el PDF: {}", e))?;
+    if doc.is_encrypted() {
+        // Muchos PDF solo llevan contraseña de propietario (impedir copiar)
+        doc.decrypt("")
+            .map_err(|_| "El PDF esta protegido con contraseña".to_string())?;
+    }
+    let pages: Vec<u32> = doc.get_pages().keys().copied().collect();
+    let total = pages.len() as u32;
+    let mut texts = Vec::with_capacity(pages.len());
+    for (index, page) in pages.into_iter().enumerate() {
+        let text = crash::catch_quietly(|| {
+            let mut text = String::new();
+            let mut output = pdf_extract::PlainTextOutput::new(&mut text);
+            pdf_extract::output_doc_page(&doc, &mut output, page).map(|_| text)
+        });
+        texts.push(match text {
+            Ok(Ok(text)) => Some(text),
+            Ok(Err(e)) => {
+                tracing::warn!(target: "pdf_text", "Pagina {} no legible: {}", page, e);
+                None
+            }
+            Err(panic) => {
+                tracing::warn!(target: "pdf_text", "Pagina {} rompe el extractor: {}", page, panic);
+                None
+            }
+        });
+        on_page(index as u32 + 1, total);
+    }
+    Ok(texts)
+}
+
+/// Extrae el texto de un PDF y lo guarda como `.txt` importable
+#[tauri::command]
+pub async fn extract_pdf_text(
+    window: WebviewWindow,
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<PdfExtraction, String> {
+    let documents = drop_import::documents_dir(&store.get())
+        .ok_or_else(|| "No se encontro el directorio de datos".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = PathBuf::from(&path);
+        let pages = extract_pages(&source, |page, total| {
+            let _ = window.emit_to(
+                window.label(),
+                "pdf-extract-progress",
+                ExtractProgress { page, total },
+            );
+        })?;
+
+        let mut reflow = Reflow::default();
+        let mut empty_pages = Vec::new();
+        for (index, text) in pages.iter().enumerate() {
+            match text.as_deref().filter(|t| !t.trim().is_empty()) {
+                Some(text) => reflow.push_page(text),
+                None => empty_pages.push(index as u32 + 1),
+            }
+        }
+        let paragraphs = reflow.finish();
+        if paragraphs.is_empty() {
+            return Err("El PDF no contiene texto extraible (probablemente escaneado)".into());
+        }
+
+        let stem = source
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "manuscrito".into());
+        fs::create_dir_all(&documents)
+            .map_err(|e| format!("No se pudo crear {}: {}", documents.display(), e))?;
+        let target = documents.join(format!("{}_{}.txt", uuid::Uuid::new_v4().simple(), stem));
+        let text = paragraphs.join("\n\n");
+        fs::write(&target, &text).map_err(|e| format!("No se pudo guardar el texto: {}", e))?;
+
+        tracing::info!(
+            target: "pdf_text",
+            "{}: {} paginas ({} sin texto) extraidas a {}",
+            path,
+            pages.len(),
+            empty_pages.len(),
+            target.display()
+        );
+        Ok(PdfExtraction {
+            path: target.to_string_lossy().into_owned(),
+            page_count: pages.len() as u32,
+            empty_pages,
+            word_count: text.split_whitespace().count(),
+            warning: LAYOUT_WARNING.into(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de extraccion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_are_reflowed_into_paragraphs() {
+        let mut reflow = Reflow::default();
+        reflow.push_page(
+            "Ana llegó al faro cuando ya ano-\n\
+             checía y el viento soplaba fuerte.\n\
+             Nadie la esperaba.\n\
+             Luis había salido a buscarla por\n\
+             12",
+        );
+        reflow.push_page("el camino de la costa.\n\n— ¿Dónde estabas?\n");
+        assert_eq!(
+            reflow.finish(),
+            [
+                "Ana llegó al faro cuando ya anochecía y el viento soplaba fuerte. Nadie la esperaba.",
+                "Luis había salido a buscarla por el camino de la costa.",
+                "— ¿Dónde estabas?",
+            ]
+        );
+    }
+
+    #[test]
+    fn files_that_are_not_pdf_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let fake = dir.path().join("novela.pdf");
+        fs::write(&fake, b"no es un pdf").unwrap();
+        assert!(extract_pages(&fake, |_, _| {}).is_err());
+    }
+}
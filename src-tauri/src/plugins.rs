@@ -0,0 +1,461 @@
+// Complementos de terceros para Narrative Assistant
+//
+// Cada complemento vive en una carpeta de `~/.narrative_assistant/plugins/`
+// con un `plugin.json`:
+//
+//   {
+//     "id": "ritmo-dialogos",
+//     "name": "Ritmo de los diálogos",
+//     "version": "1.0.0",
+//     "entry_point": "ritmo:register",
+//     "commands": [{ "id": "informe", "method": "POST", "path": "informe" }],
+//     "menu": [{ "label": "Informe de ritmo", "command": "informe" }]
+//   }
+//
+// Al arrancar se leen los manifiestos (los no válidos se registran y se
+// ignoran). Los puntos de entrada Python pasan al backend en `NA_PLUGINS`
+// (JSON con id, carpeta y `modulo:funcion`); el backend importa cada uno y le
+// da un router bajo `/api/plugins/<id>/` para sus endpoints.
+//
+// Los comandos declarados son proxies a esos endpoints: `invoke_plugin_command`
+// solo puede llamar a rutas del propio complemento en el backend local. Los
+// elementos de menú aparecen en el submenú Complementos y en la paleta de
+// comandos; al pulsarlos se emite `plugin-command` a la ventana activa, que
+// lo ejecuta con el proyecto abierto.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, State};
+
+use crate::backend_auth;
+
+/// Nombre del manifiesto dentro de la carpeta de cada complemento
+const MANIFEST_FILE_NAME: &str = "plugin.json";
+
+/// Endpoints de los complementos en el backend
+const BACKEND_PLUGINS_PATH: &str = "/api/plugins";
+
+/// Prefijo de los ids de menú de los complementos (`plugin:<id>:<comando>`)
+pub const MENU_ID_PREFIX: &str = "plugin:";
+
+/// Un análisis puede tardar: margen amplio antes de dar el comando por perdido
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Carpeta de complementos
+pub fn plugins_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".narrative_assistant").join("plugins"))
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    #[default]
+    Post,
+}
+
+/// Comando del complemento: proxy a `/api/plugins/<id>/<path>`
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PluginCommand {
+    pub id: String,
+    #[serde(default)]
+    pub method: HttpMethod,
+    pub path: String,
+}
+
+/// Elemento del submenú Complementos
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PluginMenuItem {
+    pub label: String,
+    /// Id de uno de los `commands` del manifiesto
+    pub command: String,
+}
+
+/// Contenido de plugin.json
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Función Python que registra los endpoints (`modulo:funcion`)
+    #[serde(default)]
+    pub entry_point: Option<String>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub menu: Vec<PluginMenuItem>,
+}
+
+fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 64
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+fn is_python_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl PluginManifest {
+    /// Comprueba que el manifiesto solo declara lo que el shell sabe ejecutar de forma segura
+    fn validate(&self) -> Result<(), String> {
+        if !is_slug(&self.id) {
+            return Err(format!(
+                "Id no valido '{}' (minusculas, digitos, - y _)",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() || self.version.trim().is_empty() {
+            return Err("Faltan el nombre o la version".into());
+        }
+        if let Some(entry_point) = &self.entry_point {
+            let valid = entry_point
+                .split_once(':')
+                .is_some_and(|(module, function)| {
+                    module.split('.').all(is_python_identifier) && is_python_identifier(function)
+                });
+            if !valid {
+                return Err(format!(
+                    "Punto de entrada no valido '{}' (modulo:funcion)",
+                    entry_point
+                ));
+            }
+        }
+        for (i, command) in self.commands.iter().enumerate() {
+            if !is_slug(&command.id) {
+                return Err(format!("Id de comando no valido '{}'", command.id));
+            }
+            if self.commands[..i].iter().any(|c| c.id == command.id) {
+                return Err(format!("Comando duplicado '{}'", command.id));
+            }
+            let path_ok = !command.path.is_empty()
+                && command
+                    .path
+                    .split('/')
+                    .all(|segment| is_slug(&segment.to_ascii_lowercase()));
+            if !path_ok {
+                return Err(format!(
+                    "Ruta no valida '{}' en el comando '{}'",
+                    command.path, command.id
+                ));
+            }
+        }
+        for item in &self.menu {
+            if item.label.trim().is_empty() {
+                return Err("Elemento de menu sin nombre".into());
+            }
+            if !self.commands.iter().any(|c| c.id == item.command) {
+                return Err(format!(
+                    "El menu usa el comando '{}', que no esta declarado",
+                    item.command
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn command(&self, id: &str) -> Option<&PluginCommand> {
+        self.commands.iter().find(|command| command.id == id)
+    }
+}
+
+/// Complemento cargado
+#[derive(Serialize, Clone, Debug)]
+pub struct Plugin {
+    #[serde(flatten)]
+    pub manifest: PluginManifest,
+    pub dir: PathBuf,
+}
+
+/// Manifiesto que no se pudo cargar
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginError {
+    pub dir: PathBuf,
+    pub error: String,
+}
+
+/// Resultado de `list_plugins`
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PluginList {
+    pub plugins: Vec<Plugin>,
+    pub errors: Vec<PluginError>,
+}
+
+/// Complementos descubiertos al arrancar, registrados con `.manage()`
+pub struct PluginRegistry(PluginList);
+
+impl PluginRegistry {
+    /// Lee los manifiestos de `plugins_dir()`
+    pub fn discover_default() -> Self {
+        let registry = match plugins_dir() {
+            Some(dir) => Self::discover(&dir),
+            None => Self(PluginList::default()),
+        };
+        for plugin in &registry.0.plugins {
+            tracing::info!(
+                target: "plugins",
+                "Complemento {} {} ({})",
+                plugin.manifest.id,
+                plugin.manifest.version,
+                plugin.dir.display()
+            );
+        }
+        for error in &registry.0.errors {
+            tracing::warn!(target: "plugins", "{}: {}", error.dir.display(), error.error);
+        }
+        registry
+    }
+
+    fn discover(root: &Path) -> Self {
+        let mut list = PluginList::default();
+        let Ok(entries) = fs::read_dir(root) else {
+            return Self(list);
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.join(MANIFEST_FILE_NAME).is_file())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            let manifest = fs::read_to_string(dir.join(MANIFEST_FILE_NAME))
+                .map_err(|e| format!("No se pudo leer {}: {}", MANIFEST_FILE_NAME, e))
+                .and_then(|raw| {
+                    serde_json::from_str::<PluginManifest>(&raw)
+                        .map_err(|e| format!("{} no valido: {}", MANIFEST_FILE_NAME, e))
+                })
+                .and_then(|manifest| manifest.validate().map(|_| manifest))
+                .and_then(|manifest| {
+                    if list.plugins.iter().any(|p| p.manifest.id == manifest.id) {
+                        Err(format!("Id '{}' repetido", manifest.id))
+                    } else {
+                        Ok(manifest)
+                    }
+                });
+            match manifest {
+                Ok(manifest) => list.plugins.push(Plugin { manifest, dir }),
+                Err(error) => list.errors.push(PluginError { dir, error }),
+            }
+        }
+        Self(list)
+    }
+
+    pub fn plugins(&self) -> &[Plugin] {
+        &self.0.plugins
+    }
+
+    fn find(&self, id: &str) -> Option<&Plugin> {
+        self.0
+            .plugins
+            .iter()
+            .find(|plugin| plugin.manifest.id == id)
+    }
+
+    /// Valor de `NA_PLUGINS` para el backend (None si no hay puntos de entrada)
+    #[cfg_attr(debug_assertions, allow(dead_code))] // En desarrollo el backend se lanza a mano
+    pub fn backend_env(&self) -> Option<String> {
+        let entries: Vec<serde_json::Value> = self
+            .0
+            .plugins
+            .iter()
+            .filter_map(|plugin| {
+                let entry_point = plugin.manifest.entry_point.as_ref()?;
+                Some(serde_json::json!({
+                    "id": plugin.manifest.id,
+                    "dir": plugin.dir,
+                    "entry_point": entry_point,
+                }))
+            })
+            .collect();
+        (!entries.is_empty()).then(|| serde_json::Value::from(entries).to_string())
+    }
+}
+
+/// Id de menú (y de la paleta) de un elemento de complemento
+pub fn menu_id(plugin_id: &str, command_id: &str) -> String {
+    format!("{}{}:{}", MENU_ID_PREFIX, plugin_id, command_id)
+}
+
+fn parse_menu_id(id: &str) -> Option<(&str, &str)> {
+    id.strip_prefix(MENU_ID_PREFIX)?.split_once(':')
+}
+
+/// Payload de `plugin-command`
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginCommandRequest {
+    pub plugin_id: String,
+    pub command_id: String,
+    pub label: String,
+}
+
+/// Pide a la ventana activa que ejecute el elemento de menú `id` de un
+/// complemento. Devuelve false si el id no es de ningún complemento.
+pub fn dispatch_menu_item(app: &AppHandle, id: &str) -> bool {
+    let Some((plugin_id, command_id)) = parse_menu_id(id) else {
+        return false;
+    };
+    let registry = app.state::<PluginRegistry>();
+    let Some(item) = registry.find(plugin_id).and_then(|plugin| {
+        plugin
+            .manifest
+            .menu
+            .iter()
+            .find(|item| item.command == command_id)
+    }) else {
+        return false;
+    };
+    let label = crate::windows::active_window_label(app);
+    let _ = app.emit_to(
+        EventTarget::webview_window(&label),
+        "plugin-command",
+        PluginCommandRequest {
+            plugin_id: plugin_id.to_string(),
+            command_id: command_id.to_string(),
+            label: item.label.clone(),
+        },
+    );
+    true
+}
+
+/// Complementos cargados y manifiestos descartados
+#[tauri::command]
+pub fn list_plugins(registry: State<'_, PluginRegistry>) -> PluginList {
+    registry.0.clone()
+}
+
+/// Ejecuta un comando de complemento en el backend y devuelve su `data`
+#[tauri::command]
+pub async fn invoke_plugin_command(
+    registry: State<'_, PluginRegistry>,
+    plugin_id: String,
+    command_id: String,
+    payload: Option<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let plugin = registry
+        .find(&plugin_id)
+        .ok_or_else(|| format!("Complemento desconocido: {}", plugin_id))?;
+    let command = plugin
+        .manifest
+        .command(&command_id)
+        .ok_or_else(|| format!("Comando desconocido: {}/{}", plugin_id, command_id))?;
+    let url = backend_auth::backend_url(&format!(
+        "{}/{}/{}",
+        BACKEND_PLUGINS_PATH, plugin_id, command.path
+    ));
+    tracing::info!(target: "plugins", "{:?} {}", command.method, url);
+
+    let client = backend_auth::backend_client();
+    let request = match command.method {
+        HttpMethod::Get => client.get(&url),
+        HttpMethod::Post => client
+            .post(&url)
+            .json(&payload.unwrap_or(serde_json::Value::Null)),
+    };
+    let body: serde_json::Value = request
+        .timeout(COMMAND_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("No se pudo contactar con el servidor: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta no valida del complemento: {}", e))?;
+
+    // Respuesta con el formato ApiResponse del backend, o JSON libre
+    match body["success"].as_bool() {
+        Some(true) => Ok(body["data"].clone()),
+        Some(false) => Err(body["error"]
+            .as_str()
+            .unwrap_or("El complemento devolvio un error")
+            .to_string()),
+        None => match body["detail"].as_str() {
+            Some(detail) => Err(detail.to_string()),
+            None => Ok(body),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plugin(root: &Path, dir: &str, manifest: &str) {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join(MANIFEST_FILE_NAME), manifest).unwrap();
+    }
+
+    #[test]
+    fn discovers_valid_manifests_and_reports_the_rest() {
+        let root = std::env::temp_dir().join(format!("na-plugins-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        write_plugin(
+            &root,
+            "a-ritmo",
+            r#"{
+                "id": "ritmo", "name": "Ritmo", "version": "1.0.0",
+                "entry_point": "ritmo.api:register",
+                "commands": [{ "id": "informe", "path": "informe" }],
+                "menu": [{ "label": "Informe de ritmo", "command": "informe" }]
+            }"#,
+        );
+        write_plugin(
+            &root,
+            "b-duplicado",
+            r#"{ "id": "ritmo", "name": "Otro", "version": "0.1" }"#,
+        );
+        write_plugin(&root, "c-roto", "{ no es json");
+        // Carpeta sin manifiesto: se ignora sin error
+        fs::create_dir_all(root.join("d-vacia")).unwrap();
+
+        let registry = PluginRegistry::discover(&root);
+        assert_eq!(registry.plugins().len(), 1);
+        assert_eq!(registry.0.errors.len(), 2);
+        let plugin = &registry.plugins()[0];
+        assert_eq!(plugin.manifest.commands[0].method, HttpMethod::Post);
+
+        let env: serde_json::Value =
+            serde_json::from_str(&registry.backend_env().unwrap()).unwrap();
+        assert_eq!(env[0]["id"], "ritmo");
+        assert_eq!(env[0]["entry_point"], "ritmo.api:register");
+        assert_eq!(
+            parse_menu_id(&menu_id("ritmo", "informe")),
+            Some(("ritmo", "informe"))
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn manifests_cannot_escape_their_namespace() {
+        let manifest = |commands: &str, extra: &str| {
+            serde_json::from_str::<PluginManifest>(&format!(
+                r#"{{ "id": "p", "name": "P", "version": "1", "commands": {} {} }}"#,
+                commands, extra
+            ))
+            .unwrap()
+            .validate()
+        };
+        assert!(manifest(r#"[{ "id": "a", "path": "run/all" }]"#, "").is_ok());
+        assert!(manifest(r#"[{ "id": "a", "path": "../projects" }]"#, "").is_err());
+        assert!(manifest(r#"[{ "id": "a", "path": "/api/projects" }]"#, "").is_err());
+        assert!(manifest(
+            r#"[{ "id": "a", "path": "x" }, { "id": "a", "path": "y" }]"#,
+            ""
+        )
+        .is_err());
+        assert!(manifest(
+            "[]",
+            r#", "menu": [{ "label": "Fantasma", "command": "nada" }]"#
+        )
+        .is_err());
+        assert!(manifest("[]", r#", "entry_point": "os; rm:run""#).is_err());
+        assert!(manifest("[]", r#", "entry_point": "sin_funcion""#).is_err());
+    }
+}
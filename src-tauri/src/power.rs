@@ -0,0 +1,123 @@
+// Estado de la alimentación del equipo en Narrative Assistant
+//
+// El trabajo en segundo plano que gasta batería (precarga de modelos) solo se
+// hace enchufado a la corriente. `on_ac_power` devuelve `None` si el sistema
+// no lo permite saber; quien lo use debe tratarlo como "no enchufado".
+//
+// Fuentes:
+// - Windows: GetSystemPowerStatus (ACLineStatus)
+// - macOS: salida de `pmset -g batt`
+// - Linux: /sys/class/power_supply. Un equipo sin batería (sobremesa) se
+//   considera enchufado.
+
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+#[cfg(windows)]
+pub fn on_ac_power() -> Option<bool> {
+    use ::windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // SAFETY: `status` es un SYSTEM_POWER_STATUS válido que la función rellena
+    unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+    // 0 = batería, 1 = corriente, 255 = desconocido
+    match status.ACLineStatus {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn on_ac_power() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Primera línea de `pmset -g batt`: "Now drawing from 'AC Power'"
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    let line = output.lines().next()?;
+    if line.contains("'AC Power'") {
+        Some(true)
+    } else if line.contains("'Battery Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn on_ac_power() -> Option<bool> {
+    from_power_supply(Path::new("/sys/class/power_supply"))
+}
+
+/// Lee las fuentes de `dir`: enchufado si alguna "Mains" está online o si no
+/// hay batería; sin leer `dir`, desconocido.
+#[cfg(any(target_os = "linux", test))]
+fn from_power_supply(dir: &std::path::Path) -> Option<bool> {
+    let read = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut has_battery = false;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let supply = entry.path();
+        match read(supply.join("type")).as_str() {
+            "Mains" | "USB" if read(supply.join("online")) == "1" => return Some(true),
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    Some(!has_battery)
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn on_ac_power() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn pmset_output_tells_ac_from_battery() {
+        assert_eq!(
+            parse_pmset("Now drawing from 'AC Power'\n -InternalBattery-0 (id=1)\t100%; charged;"),
+            Some(true)
+        );
+        assert_eq!(
+            parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t80%;"),
+            Some(false)
+        );
+        assert_eq!(parse_pmset(""), None);
+    }
+
+    #[test]
+    fn power_supply_without_battery_counts_as_ac() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(from_power_supply(dir.path()), Some(true));
+
+        let battery = dir.path().join("BAT0");
+        fs::create_dir(&battery).unwrap();
+        fs::write(battery.join("type"), "Battery\n").unwrap();
+        let mains = dir.path().join("AC");
+        fs::create_dir(&mains).unwrap();
+        fs::write(mains.join("type"), "Mains\n").unwrap();
+        fs::write(mains.join("online"), "0\n").unwrap();
+        assert_eq!(from_power_supply(dir.path()), Some(false));
+
+        fs::write(mains.join("online"), "1\n").unwrap();
+        assert_eq!(from_power_supply(dir.path()), Some(true));
+        assert_eq!(from_power_supply(&dir.path().join("missing")), None);
+    }
+}
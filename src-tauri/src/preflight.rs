@@ -0,0 +1,231 @@
+// Comprobación de modelos al arrancar
+//
+// El backend arranca aunque falten modelos y solo falla al cargarlos, con un
+// traceback de Python en mitad del primer análisis. Antes de dar el backend por
+// listo, el shell comprueba los modelos NLP requeridos del manifiesto:
+//   - que su directorio exista (en los modelos empaquetados con la app o en el
+//     directorio de modelos) con los ficheros mínimos que exige
+//     core/model_manager.py (`_verify_model_structure`)
+//   - que el marcador .version no sea de otra revisión pinneada que la del
+//     manifiesto (una actualización de la app ha cambiado de modelo)
+// Un modelo con `fallback_for` cubre al que sustituye. Si falta algo se emite
+// `models-missing` con la lista exacta; `check_models` devuelve lo mismo.
+//
+// No calcula sumas (eso es `verify_models`, integrity.rs): tiene que ser
+// instantáneo para no retrasar el arranque.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::downloads;
+use crate::models::{ModelKind, ModelManifest, ModelSpec};
+
+/// Por qué hay que descargar un modelo
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingReason {
+    /// No está instalado
+    Missing,
+    /// Faltan ficheros imprescindibles
+    Incomplete,
+    /// Instalado con otra revisión que la del manifiesto
+    Outdated,
+}
+
+/// Modelo requerido que falta
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MissingModel {
+    pub id: String,
+    pub display_name: String,
+    pub size_mb: u64,
+    pub reason: MissingReason,
+}
+
+/// Resultado de la comprobación (payload de `models-missing`)
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ModelsCheck {
+    pub ready: bool,
+    pub missing: Vec<MissingModel>,
+    /// Tamaño aproximado de lo que hay que descargar
+    pub download_mb: u64,
+}
+
+/// Ficheros mínimos del modelo (los de `_verify_model_structure`)
+fn structure_ok(spec: &ModelSpec, dir: &Path) -> bool {
+    match spec.kind {
+        ModelKind::Spacy => {
+            // config.cfg en la raíz o en el subdirectorio versionado
+            dir.join("config.cfg").is_file()
+                || fs::read_dir(dir).is_ok_and(|entries| {
+                    entries
+                        .flatten()
+                        .any(|entry| entry.path().join("config.cfg").is_file())
+                })
+        }
+        ModelKind::Huggingface => {
+            let weights =
+                dir.join("pytorch_model.bin").is_file() || dir.join("model.safetensors").is_file();
+            let tokenizer = spec.subdirectory.as_deref() != Some("transformer_ner")
+                || dir.join("tokenizer_config.json").is_file()
+                || dir.join("tokenizer.json").is_file();
+            dir.join("config.json").is_file() && weights && tokenizer
+        }
+        ModelKind::Ollama => true,
+    }
+}
+
+/// Si el modelo se instaló fijado a otra revisión que la del manifiesto
+fn outdated(spec: &ModelSpec, dir: &Path) -> bool {
+    let Some(expected) = spec.revision.as_deref() else {
+        return false;
+    };
+    let key = match spec.kind {
+        ModelKind::Spacy => "pinned_version",
+        _ => "hf_revision",
+    };
+    fs::read_to_string(dir.join(downloads::VERSION_MARKER))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|marker| marker.get(key)?.as_str().map(str::to_string))
+        .is_some_and(|pinned| pinned != expected)
+}
+
+/// Directorio donde está instalado un modelo, o por qué no está disponible
+pub(crate) fn locate(spec: &ModelSpec, roots: &[PathBuf]) -> Result<PathBuf, MissingReason> {
+    let mut reason = MissingReason::Missing;
+    for dir in roots.iter().filter_map(|root| spec.install_dir(root)) {
+        if !dir.is_dir() {
+            continue;
+        }
+        if !structure_ok(spec, &dir) {
+            reason = MissingReason::Incomplete;
+        } else if outdated(spec, &dir) {
+            reason = MissingReason::Outdated;
+        } else {
+            return Ok(dir);
+        }
+    }
+    Err(reason)
+}
+
+/// Estado de un modelo en los directorios de modelos (`None` = disponible)
+fn status(spec: &ModelSpec, roots: &[PathBuf]) -> Option<MissingReason> {
+    locate(spec, roots).err()
+}
+
+fn check(manifest: &ModelManifest, roots: &[PathBuf]) -> ModelsCheck {
+    let missing: Vec<MissingModel> = manifest
+        .models
+        .iter()
+        .filter(|spec| spec.required && spec.install_dir(Path::new("")).is_some())
+        .filter_map(|spec| {
+            let reason = status(spec, roots)?;
+            let covered = manifest
+                .models
+                .iter()
+                .filter(|alt| alt.fallback_for.as_deref() == Some(spec.id.as_str()))
+                .any(|alt| status(alt, roots).is_none());
+            (!covered).then(|| MissingModel {
+                id: spec.id.clone(),
+                display_name: spec.display_name.clone(),
+                size_mb: spec.size_mb,
+                reason,
+            })
+        })
+        .collect();
+    ModelsCheck {
+        ready: missing.is_empty(),
+        download_mb: missing.iter().map(|m| m.size_mb).sum(),
+        missing,
+    }
+}
+
+/// Directorios donde el backend busca modelos: los empaquetados y el del usuario
+pub(crate) fn model_roots(app: &AppHandle) -> Vec<PathBuf> {
+    let bundled = app
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join("models"))
+        .filter(|dir| dir.is_dir());
+    bundled
+        .into_iter()
+        .chain(downloads::models_root(app))
+        .collect()
+}
+
+/// Comprueba los modelos y emite `models-missing` si falta alguno
+pub fn run(app: &AppHandle) -> ModelsCheck {
+    let result = check(&ModelManifest::bundled(), &model_roots(app));
+    if !result.ready {
+        let ids: Vec<&str> = result.missing.iter().map(|m| m.id.as_str()).collect();
+        tracing::warn!(
+            target: "preflight",
+            "Faltan modelos: {} (~{} MB)",
+            ids.join(", "),
+            result.download_mb
+        );
+        let _ = app.emit("models-missing", &result);
+    }
+    result
+}
+
+/// Modelos requeridos que faltan o no coinciden con el manifiesto
+#[tauri::command]
+pub fn check_models(app: AppHandle) -> ModelsCheck {
+    check(&ModelManifest::bundled(), &model_roots(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_incomplete_and_outdated_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let manifest = ModelManifest::bundled();
+
+        let result = check(&manifest, std::slice::from_ref(&root));
+        let ids: Vec<&str> = result.missing.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["spacy", "embeddings", "transformer_ner"]);
+        assert_eq!(result.download_mb, 540 + 470 + 440);
+
+        // spaCy con su subdirectorio versionado pero de otra versión pinneada
+        write(
+            &root.join("spacy/es_core_news_lg/es_core_news_lg-3.6.0/config.cfg"),
+            "",
+        );
+        write(
+            &root.join("spacy/es_core_news_lg/.version"),
+            r#"{"pinned_version": "3.6.0"}"#,
+        );
+        // Embeddings sin pesos
+        write(
+            &root.join("embeddings/paraphrase-multilingual-MiniLM-L12-v2/config.json"),
+            "{}",
+        );
+        let result = check(&manifest, std::slice::from_ref(&root));
+        assert_eq!(result.missing[0].reason, MissingReason::Outdated);
+        assert_eq!(result.missing[1].reason, MissingReason::Incomplete);
+    }
+
+    #[test]
+    fn fallback_model_covers_the_one_it_replaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let fallback = root.join("transformer_ner/Davlan/xlm-roberta-base-ner-hrl");
+        for file in ["config.json", "model.safetensors", "tokenizer.json"] {
+            write(&fallback.join(file), "{}");
+        }
+        let result = check(&ModelManifest::bundled(), &[root]);
+        assert!(result.missing.iter().all(|m| m.id != "transformer_ner"));
+    }
+}
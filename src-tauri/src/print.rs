@@ -0,0 +1,265 @@
+// Impresión de informes a PDF de Narrative Assistant
+//
+// `print_report` genera el PDF con el motor del webview, sin pasar por la
+// exportación del backend Python:
+//
+// - Con `html`, el informe se carga en una ventana oculta y temporal
+//   (`print-<uuid>`) que se destruye al terminar.
+// - Sin `html`, se imprime la vista actual de la ventana que lo pide, con
+//   sus estilos `@media print`.
+//
+// Cada plataforma usa la API nativa de su webview: PrintOperation con la
+// impresora "Print to File" en WebKitGTK, `PrintToPdf` en WebView2 y
+// `createPDF` en WKWebView (en macOS el resultado es una única página
+// continua, sin paginar).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use tauri::webview::{PageLoadEvent, PlatformWebview};
+use tauri::{AppHandle, State, Url, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tokio::sync::oneshot;
+
+use crate::export_scope::ExportGrants;
+
+/// Prefijo de las ventanas ocultas de impresión
+const PRINT_WINDOW_PREFIX: &str = "print-";
+
+/// Tamaño máximo del HTML (WebView2 no navega a URLs data: de más de 2 MB)
+const MAX_HTML_BYTES: usize = 1_500_000;
+
+/// Tiempo máximo para cargar el informe y para generar el PDF
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const PRINT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Si `label` es una ventana de impresión (no guarda geometría ni recibe el foco)
+pub fn is_print_window(label: &str) -> bool {
+    label.starts_with(PRINT_WINDOW_PREFIX)
+}
+
+/// Ruta final del PDF: absoluta, con extensión `.pdf` y en un directorio existente
+pub(crate) fn resolve_output_path(output_path: &str) -> Result<PathBuf, String> {
+    let mut path = PathBuf::from(output_path.trim());
+    if !path.is_absolute() {
+        return Err("La ruta del PDF debe ser absoluta".into());
+    }
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+    {
+        path.set_extension("pdf");
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(format!(
+            "No existe la carpeta de destino de {}",
+            path.display()
+        )),
+    }
+}
+
+/// URL data: con el documento completo
+fn html_data_url(html: &str) -> Result<Url, String> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(html);
+    Url::parse(&format!("data:text/html;charset=utf-8;base64,{}", encoded))
+        .map_err(|e| format!("HTML no valido: {}", e))
+}
+
+type PrintResult = Result<(), String>;
+
+/// Aviso de fin de una operación asíncrona del webview, compartido entre sus
+/// señales y callbacks (también lo usa capture.rs)
+pub(crate) struct Completion<T>(Arc<Mutex<Option<oneshot::Sender<T>>>>);
+
+impl<T> Clone for Completion<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Completion<T> {
+    pub(crate) fn new() -> (Self, oneshot::Receiver<T>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Arc::new(Mutex::new(Some(tx)))), rx)
+    }
+
+    /// Solo cuenta el primer resultado (p. ej. GTK emite `failed` y después `finished`)
+    pub(crate) fn finish(&self, result: T) {
+        if let Some(tx) = self.0.lock().unwrap().take() {
+            let _ = tx.send(result);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn render_pdf(webview: PlatformWebview, path: &Path, done: Completion<PrintResult>) {
+    use webkit2gtk::{PrintOperation, PrintOperationExt};
+
+    let uri = match Url::from_file_path(path) {
+        Ok(uri) => uri,
+        Err(()) => return done.finish(Err("Ruta del PDF no valida".into())),
+    };
+    let settings = gtk::PrintSettings::new();
+    settings.set_printer("Print to File");
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(uri.as_str()));
+    let page_setup = gtk::PageSetup::new();
+    page_setup.set_paper_size(&gtk::PaperSize::new(Some(gtk::PAPER_NAME_A4)));
+
+    let operation = PrintOperation::new(&webview.inner());
+    operation.set_print_settings(&settings);
+    operation.set_page_setup(&page_setup);
+    let failed = done.clone();
+    operation.connect_failed(move |_, e| {
+        failed.finish(Err(format!("Error generando el PDF: {}", e)));
+    });
+    operation.connect_finished(move |_| done.finish(Ok(())));
+    operation.print();
+}
+
+#[cfg(windows)]
+fn render_pdf(webview: PlatformWebview, path: &Path, done: Completion<PrintResult>) {
+    use ::windows::core::{Interface, HSTRING};
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2PrintSettings, ICoreWebView2_7,
+    };
+    use webview2_com::PrintToPdfCompletedHandler;
+
+    let path = HSTRING::from(path);
+    let completed = done.clone();
+    let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, success| {
+        completed.finish(match result {
+            Ok(()) if success => Ok(()),
+            Ok(()) => Err("WebView2 no pudo generar el PDF".into()),
+            Err(e) => Err(format!("Error generando el PDF: {}", e)),
+        });
+        Ok(())
+    }));
+    // SAFETY: llamadas COM en el hilo del webview (with_webview se ejecuta en el hilo principal)
+    let started = unsafe {
+        webview
+            .controller()
+            .CoreWebView2()
+            .and_then(|core| core.cast::<ICoreWebView2_7>())
+            .and_then(|core| core.PrintToPdf(&path, None::<&ICoreWebView2PrintSettings>, &handler))
+    };
+    if let Err(e) = started {
+        done.finish(Err(format!(
+            "Esta version de WebView2 no permite imprimir a PDF: {}",
+            e
+        )));
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn render_pdf(webview: PlatformWebview, path: &Path, done: Completion<PrintResult>) {
+    use block2::RcBlock;
+    use objc2_foundation::{NSData, NSError};
+    use objc2_web_kit::WKWebView;
+
+    let path = path.to_path_buf();
+    let handler = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+        // SAFETY: WebKit pasa punteros válidos (o nulos) durante la llamada al bloque
+        let result = match unsafe { (data.as_ref(), error.as_ref()) } {
+            (Some(data), _) => std::fs::write(&path, data.to_vec())
+                .map_err(|e| format!("Error guardando el PDF: {}", e)),
+            (None, Some(error)) => Err(format!(
+                "Error generando el PDF: {}",
+                error.localizedDescription()
+            )),
+            (None, None) => Err("WebKit no pudo generar el PDF".into()),
+        };
+        done.finish(result);
+    });
+    // SAFETY: `inner()` es el WKWebView de la ventana y with_webview corre en el hilo principal
+    unsafe {
+        let webview: &WKWebView = &*webview.inner().cast();
+        webview.createPDFWithConfiguration_completionHandler(None, &handler);
+    }
+}
+
+/// Genera el PDF del webview de `window` y espera a que termine
+async fn print_window(window: &WebviewWindow, path: PathBuf) -> Result<(), String> {
+    let (done, rx) = Completion::new();
+    window
+        .with_webview(move |webview| render_pdf(webview, &path, done))
+        .map_err(|e| format!("No se pudo acceder al visor web: {}", e))?;
+    tokio::time::timeout(PRINT_TIMEOUT, rx)
+        .await
+        .map_err(|_| "Tiempo de espera agotado generando el PDF".to_string())?
+        .map_err(|_| "La impresion se interrumpio".to_string())?
+}
+
+/// Carga el informe en una ventana oculta y la imprime
+async fn print_html(app: &AppHandle, html: &str, path: PathBuf) -> Result<(), String> {
+    if html.len() > MAX_HTML_BYTES {
+        return Err("El informe es demasiado grande para imprimirlo; exportelo a PDF desde el menu Exportar".into());
+    }
+    let (loaded_tx, loaded_rx) = oneshot::channel();
+    let loaded_tx = Mutex::new(Some(loaded_tx));
+    let label = format!("{}{}", PRINT_WINDOW_PREFIX, uuid::Uuid::new_v4().simple());
+    let window = WebviewWindowBuilder::new(app, &label, WebviewUrl::External(html_data_url(html)?))
+        .title("Imprimir informe")
+        .inner_size(794.0, 1123.0) // A4 a 96 ppp
+        .visible(false)
+        .skip_taskbar(true)
+        .on_page_load(move |_, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                if let Some(tx) = loaded_tx.lock().unwrap().take() {
+                    let _ = tx.send(());
+                }
+            }
+        })
+        .build()
+        .map_err(|e| format!("No se pudo preparar la impresion: {}", e))?;
+
+    let result = match tokio::time::timeout(LOAD_TIMEOUT, loaded_rx).await {
+        Ok(Ok(())) => print_window(&window, path).await,
+        _ => Err("Tiempo de espera agotado cargando el informe".into()),
+    };
+    // destroy() no emite CloseRequested: la ventana no cuenta para el backend compartido
+    let _ = window.destroy();
+    result
+}
+
+/// Imprime a PDF el HTML indicado o, sin `html`, la vista actual de la ventana.
+/// Devuelve la ruta del PDF generado.
+#[tauri::command]
+pub async fn print_report(
+    app: AppHandle,
+    window: WebviewWindow,
+    grants: State<'_, ExportGrants>,
+    html: Option<String>,
+    output_path: String,
+) -> Result<String, String> {
+    let path = resolve_output_path(&output_path)?;
+    grants.authorize(&path)?;
+    match html.as_deref().filter(|html| !html.trim().is_empty()) {
+        Some(html) => print_html(&app, html, path.clone()).await?,
+        None => print_window(&window, path.clone()).await?,
+    }
+    tracing::info!(target: "print", "Informe impreso en {}", path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_path_is_absolute_pdf_in_existing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("informe");
+
+        let path = resolve_output_path(base.to_str().unwrap()).unwrap();
+        assert_eq!(path, dir.path().join("informe.pdf"));
+        let upper = dir.path().join("Revision.PDF");
+        assert_eq!(resolve_output_path(upper.to_str().unwrap()).unwrap(), upper);
+
+        assert!(resolve_output_path("informe.pdf").is_err());
+        let missing = dir.path().join("no-existe").join("informe.pdf");
+        assert!(resolve_output_path(missing.to_str().unwrap()).is_err());
+    }
+}
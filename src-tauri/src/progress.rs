@@ -0,0 +1,100 @@
+// Progreso del análisis en la barra de tareas y el Dock
+//
+// `set_progress` refleja el avance del análisis en curso en el icono de la
+// app (barra de progreso de la barra de tareas en Windows, del Dock en macOS
+// y de los lanzadores compatibles con Unity en Linux), para seguir los
+// análisis largos sin tener la ventana delante. El frontend lo llama con
+// cada sondeo de progreso del backend (ver useAnalysisPolling.ts).
+//
+// En macOS y Linux el progreso es de toda la app y los estados en pausa y
+// error se muestran como normales.
+
+use serde::Deserialize;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+use crate::windows::MAIN_WINDOW;
+
+/// Estado del indicador
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressState {
+    /// Sin indicador
+    None,
+    Normal,
+    /// En cola o sin porcentaje conocido
+    Indeterminate,
+    Paused,
+    Error,
+}
+
+fn progress_bar(percent: Option<f64>, state: ProgressState) -> ProgressBarState {
+    let status = match state {
+        ProgressState::None => ProgressBarStatus::None,
+        ProgressState::Normal => ProgressBarStatus::Normal,
+        ProgressState::Indeterminate => ProgressBarStatus::Indeterminate,
+        ProgressState::Paused => ProgressBarStatus::Paused,
+        ProgressState::Error => ProgressBarStatus::Error,
+    };
+    let progress = match state {
+        ProgressState::None | ProgressState::Indeterminate => None,
+        _ => percent
+            .filter(|p| p.is_finite())
+            .map(|p| p.round().clamp(0.0, 100.0) as u64),
+    };
+    ProgressBarState {
+        status: Some(status),
+        progress,
+    }
+}
+
+/// Muestra el progreso (`percent` de 0 a 100) en el icono de la app
+#[tauri::command]
+pub fn set_progress(
+    app: AppHandle,
+    percent: Option<f64>,
+    state: ProgressState,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW)
+        .ok_or("Ventana principal no disponible")?;
+    window
+        .set_progress_bar(progress_bar(percent, state))
+        .map_err(|e| format!("No se pudo actualizar el progreso: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_is_clamped_and_dropped_without_a_value_to_show() {
+        let bar = progress_bar(Some(42.6), ProgressState::Normal);
+        assert!(matches!(bar.status, Some(ProgressBarStatus::Normal)));
+        assert_eq!(bar.progress, Some(43));
+        assert_eq!(
+            progress_bar(Some(140.0), ProgressState::Error).progress,
+            Some(100)
+        );
+        assert_eq!(
+            progress_bar(Some(-3.0), ProgressState::Paused).progress,
+            Some(0)
+        );
+        assert_eq!(
+            progress_bar(Some(f64::NAN), ProgressState::Normal).progress,
+            None
+        );
+        assert_eq!(
+            progress_bar(Some(50.0), ProgressState::Indeterminate).progress,
+            None
+        );
+
+        let hidden = progress_bar(Some(50.0), ProgressState::None);
+        assert!(matches!(hidden.status, Some(ProgressBarStatus::None)));
+        assert_eq!(hidden.progress, None);
+        assert_eq!(
+            serde_json::from_str::<ProgressState>(r#""indeterminate""#).unwrap(),
+            ProgressState::Indeterminate
+        );
+    }
+}
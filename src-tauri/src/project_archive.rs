@@ -0,0 +1,552 @@
+// Archivo de proyecto portátil (.nazip) de Narrative Assistant
+//
+// Un `.nra` (persistence/project_file.py) lleva las filas del proyecto en la
+// base de datos, pero no el manuscrito: al abrirlo en otra máquina el
+// proyecto queda sin documento (`document_path` a NULL) y no se puede
+// reanalizar. El `.nazip` es un zip con todo lo necesario para que dos
+// correctores se pasen un proyecto completo:
+//
+//   manifest.json        versión del formato, app, proyecto y documento (sha256)
+//   project.nra          filas del proyecto, exportadas por el backend
+//   documents/<nombre>   el manuscrito original, si sigue en disco
+//
+// `export_project_archive` pide el `.nra` al backend (`save-file`) en un
+// directorio temporal y empaqueta. `import_project_archive` copia el
+// manuscrito a `<datos>/documents`, importa el `.nra` (`open-file`, que
+// renumera los ids) y apunta el proyecto nuevo a su copia del manuscrito.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::State;
+use zip::write::SimpleFileOptions;
+
+use crate::backend_auth;
+use crate::database;
+use crate::drop_import;
+use crate::export_scope::ExportGrants;
+use crate::settings::SettingsStore;
+
+/// Versión del formato (incrementar si cambia la estructura del zip)
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROJECT_ENTRY: &str = "project.nra";
+const DOCUMENTS_PREFIX: &str = "documents/";
+
+/// Tamaño máximo de cada entrada al extraer (el backend admite `.nra` de hasta 500 MB)
+const MAX_ENTRY_BYTES: u64 = 1024 * 1024 * 1024;
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024;
+
+/// Exportar o importar un `.nra` grande puede tardar
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct ArchivedDocument {
+    /// Nombre del archivo dentro de `documents/`
+    name: String,
+    sha256: String,
+    size_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct Manifest {
+    format_version: u32,
+    app_version: String,
+    /// Segundos UNIX
+    exported_at: u64,
+    project_name: String,
+    document: Option<ArchivedDocument>,
+}
+
+/// Resultado de `export_project_archive`
+#[derive(Serialize, Clone, Debug)]
+pub struct ArchiveExport {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Si el manuscrito va en el archivo (no, si ya no estaba en disco)
+    pub includes_document: bool,
+}
+
+/// Resultado de `import_project_archive`
+#[derive(Serialize, Clone, Debug)]
+pub struct ArchiveImport {
+    pub project_id: i64,
+    pub project_name: Option<String>,
+    /// Copia local del manuscrito
+    pub document_path: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Directorio temporal que se borra al salir
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Result<Self, String> {
+        let dir = std::env::temp_dir().join(format!("narrassist-archive-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("No se pudo crear el directorio temporal: {}", e))?;
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Llama al backend y devuelve `data` (o el error que explique)
+async fn backend(request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+    let body: serde_json::Value = request
+        .timeout(BACKEND_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("No se pudo contactar con el servidor: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta no valida del servidor: {}", e))?;
+    if body["success"].as_bool() != Some(true) {
+        let error = body["error"]
+            .as_str()
+            .or(body["detail"].as_str())
+            .unwrap_or("Error del servidor");
+        return Err(error.to_string());
+    }
+    Ok(body["data"].clone())
+}
+
+/// Nombre sin directorios ni caracteres problemáticos (viene del zip)
+fn safe_file_name(name: &str) -> Option<String> {
+    let name = Path::new(name).file_name()?.to_string_lossy();
+    let clean: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || r#"<>:"/\|?*"#.contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let clean = clean.trim().trim_start_matches('.');
+    (!clean.is_empty()).then(|| clean.to_string())
+}
+
+/// Ruta final: absoluta, con extensión `.nazip` y en un directorio existente
+fn resolve_output_path(output_path: &str) -> Result<PathBuf, String> {
+    let mut path = PathBuf::from(output_path.trim());
+    if !path.is_absolute() {
+        return Err("La ruta del archivo debe ser absoluta".into());
+    }
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("nazip"))
+    {
+        path.set_extension("nazip");
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(format!(
+            "No existe la carpeta de destino de {}",
+            path.display()
+        )),
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<(String, u64), String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?;
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
+
+/// Escribe el zip: manifiesto, `.nra` y manuscrito
+fn write_archive<W: Write + Seek>(
+    writer: W,
+    manifest: &Manifest,
+    nra: &Path,
+    document: Option<&Path>,
+) -> Result<(), String> {
+    let error = |e: zip::result::ZipError| format!("Error creando el archivo: {}", e);
+    let io_error = |e: io::Error| format!("Error creando el archivo: {}", e);
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    zip.start_file(MANIFEST_ENTRY, options).map_err(error)?;
+    let json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(io_error)?;
+
+    zip.start_file(PROJECT_ENTRY, options).map_err(error)?;
+    io::copy(&mut File::open(nra).map_err(io_error)?, &mut zip).map_err(io_error)?;
+
+    if let (Some(path), Some(archived)) = (document, &manifest.document) {
+        zip.start_file(format!("{}{}", DOCUMENTS_PREFIX, archived.name), options)
+            .map_err(error)?;
+        io::copy(&mut File::open(path).map_err(io_error)?, &mut zip).map_err(io_error)?;
+    }
+    zip.finish().map_err(error)?;
+    Ok(())
+}
+
+/// Copia una entrada a `target` sin pasar de `MAX_ENTRY_BYTES`
+fn extract_entry<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+    target: &Path,
+) -> Result<(String, u64), String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("Falta {} en el archivo de proyecto", name))?;
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(format!("{} es demasiado grande", name));
+    }
+    let mut file = File::create(target)
+        .map_err(|e| format!("No se pudo crear {}: {}", target.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut limited = (&mut entry).take(MAX_ENTRY_BYTES + 1);
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let read = limited
+            .read(&mut buffer)
+            .map_err(|e| format!("Archivo de proyecto danado ({}): {}", name, e))?;
+        if read == 0 {
+            break;
+        }
+        size += read as u64;
+        if size > MAX_ENTRY_BYTES {
+            return Err(format!("{} es demasiado grande", name));
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])
+            .map_err(|e| format!("No se pudo escribir {}: {}", target.display(), e))?;
+    }
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
+
+fn read_manifest<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Result<Manifest, String> {
+    let entry = archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| "No es un archivo de proyecto de Narrative Assistant".to_string())?;
+    let manifest: Manifest = serde_json::from_reader(entry.take(MAX_MANIFEST_BYTES))
+        .map_err(|e| format!("Manifiesto no valido: {}", e))?;
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "El archivo se creo con una version mas reciente de la aplicacion ({}). Actualice para abrirlo.",
+            manifest.app_version
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Paquete extraído, antes de importarlo
+struct Extracted {
+    manifest: Manifest,
+    nra: PathBuf,
+    document: Option<PathBuf>,
+}
+
+/// Extrae el `.nra` a `work_dir` y el manuscrito a `documents` (verificado)
+fn extract_archive<R: Read + Seek>(
+    reader: R,
+    work_dir: &Path,
+    documents: &Path,
+) -> Result<Extracted, String> {
+    let mut archive = zip::ZipArchive::new(reader)
+        .map_err(|e| format!("Archivo de proyecto no valido: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    let nra = work_dir.join(PROJECT_ENTRY);
+    extract_entry(&mut archive, PROJECT_ENTRY, &nra)?;
+
+    let document = match &manifest.document {
+        Some(archived) => {
+            let name = safe_file_name(&archived.name)
+                .ok_or_else(|| "Nombre de documento no valido en el archivo".to_string())?;
+            fs::create_dir_all(documents)
+                .map_err(|e| format!("No se pudo crear {}: {}", documents.display(), e))?;
+            let target = documents.join(format!("{}_{}", uuid::Uuid::new_v4().simple(), name));
+            let entry = format!("{}{}", DOCUMENTS_PREFIX, archived.name);
+            let checked = extract_entry(&mut archive, &entry, &target).and_then(|(sha, _)| {
+                if sha == archived.sha256 {
+                    Ok(())
+                } else {
+                    Err(format!("El manuscrito del archivo esta danado ({})", name))
+                }
+            });
+            if let Err(e) = checked {
+                let _ = fs::remove_file(&target);
+                return Err(e);
+            }
+            Some(target)
+        }
+        None => None,
+    };
+    Ok(Extracted {
+        manifest,
+        nra,
+        document,
+    })
+}
+
+/// Empaqueta el proyecto `project_id` en `path` (.nazip)
+#[tauri::command]
+pub async fn export_project_archive(
+    grants: State<'_, ExportGrants>,
+    project_id: i64,
+    path: String,
+) -> Result<ArchiveExport, String> {
+    let output = resolve_output_path(&path)?;
+    grants.authorize(&output)?;
+    let client = backend_auth::backend_client();
+    let project = backend(client.get(backend_auth::backend_url(&format!(
+        "/api/projects/{}",
+        project_id
+    ))))
+    .await?;
+    let project_name = project["name"].as_str().unwrap_or_default().to_string();
+    let document = project["document_path"]
+        .as_str()
+        .map(PathBuf::from)
+        .filter(|p| p.is_file());
+
+    let work_dir = TempDir::new()?;
+    let nra = work_dir.0.join(PROJECT_ENTRY);
+    // El destino es un directorio temporal del shell: no hace falta concederlo
+    backend(backend_auth::with_shell_token(
+        client
+            .post(backend_auth::backend_url(&format!(
+                "/api/projects/{}/save-file",
+                project_id
+            )))
+            .json(&serde_json::json!({ "file_path": nra.to_string_lossy() })),
+    ))
+    .await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let archived = match &document {
+            Some(path) => {
+                let (sha256, size_bytes) = sha256_file(path)?;
+                let name = path
+                    .file_name()
+                    .and_then(|n| safe_file_name(&n.to_string_lossy()))
+                    .unwrap_or_else(|| "manuscrito".into());
+                Some(ArchivedDocument {
+                    name,
+                    sha256,
+                    size_bytes,
+                })
+            }
+            None => None,
+        };
+        let manifest = Manifest {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").into(),
+            exported_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            project_name,
+            document: archived,
+        };
+
+        // A un `.partial` y después se renombra: nunca queda un archivo a medias
+        let partial = output.with_extension("nazip.partial");
+        let file = File::create(&partial)
+            .map_err(|e| format!("No se pudo crear {}: {}", output.display(), e))?;
+        let written = write_archive(file, &manifest, &nra, document.as_deref())
+            .and_then(|_| fs::rename(&partial, &output).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        drop(work_dir);
+
+        let size_bytes = fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+        tracing::info!(
+            target: "project_archive",
+            "Proyecto {} exportado a {} ({} bytes)",
+            project_id,
+            output.display(),
+            size_bytes
+        );
+        Ok(ArchiveExport {
+            path: output.to_string_lossy().into_owned(),
+            size_bytes,
+            includes_document: manifest.document.is_some(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de exportacion: {}", e))?
+}
+
+/// Importa un `.nazip` como proyecto nuevo
+#[tauri::command]
+pub async fn import_project_archive(
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<ArchiveImport, String> {
+    let documents = drop_import::documents_dir(&store.get())
+        .ok_or_else(|| "No se encontro el directorio de datos".to_string())?;
+    let work_dir = TempDir::new()?;
+    let work = work_dir.0.clone();
+    let extracted = tauri::async_runtime::spawn_blocking(move || {
+        let file = File::open(&path).map_err(|e| format!("No se pudo abrir {}: {}", path, e))?;
+        extract_archive(file, &work, &documents)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de importacion: {}", e))??;
+
+    let imported = backend(
+        backend_auth::backend_client()
+            .post(backend_auth::backend_url("/api/projects/open-file"))
+            .json(&serde_json::json!({ "file_path": extracted.nra.to_string_lossy() })),
+    )
+    .await;
+    let data = match imported {
+        Ok(data) => data,
+        Err(e) => {
+            if let Some(document) = &extracted.document {
+                let _ = fs::remove_file(document);
+            }
+            return Err(e);
+        }
+    };
+    let project_id = data["project_id"]
+        .as_i64()
+        .ok_or_else(|| "Respuesta no valida del servidor".to_string())?;
+    let mut warnings: Vec<String> = data["warnings"]
+        .as_array()
+        .map(|w| {
+            w.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // El importador del `.nra` deja el proyecto sin documento
+    let document_path = extracted.document.map(|p| p.to_string_lossy().into_owned());
+    if let Some(document) = &document_path {
+        let (id, linked_path) = (project_id, document.clone());
+        let linked = tauri::async_runtime::spawn_blocking(move || {
+            let db = database::database_path()
+                .ok_or_else(|| "No se encontro la base de datos".to_string())?;
+            let conn = database::open_existing(&db)?;
+            conn.execute(
+                "UPDATE projects SET document_path = ?1 WHERE id = ?2",
+                rusqlite::params![linked_path, id],
+            )
+            .map_err(|e| format!("No se pudo enlazar el manuscrito: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Error en la tarea de importacion: {}", e))?;
+        if let Err(e) = linked {
+            tracing::warn!(target: "project_archive", "{}", e);
+            warnings.push(format!(
+                "El manuscrito se copio a {} pero no se pudo enlazar",
+                document
+            ));
+        }
+    } else {
+        warnings.push("El archivo no incluye el manuscrito: no se podra reanalizar".into());
+    }
+
+    tracing::info!(
+        target: "project_archive",
+        "Archivo de proyecto '{}' importado como proyecto {}",
+        extracted.manifest.project_name,
+        project_id
+    );
+    Ok(ArchiveImport {
+        project_id,
+        project_name: data["project_name"]
+            .as_str()
+            .map(String::from)
+            .or(Some(extracted.manifest.project_name)),
+        document_path,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn archive(dir: &Path, sha256: Option<&str>) -> Cursor<Vec<u8>> {
+        let nra = dir.join("p.nra");
+        let document = dir.join("novela.docx");
+        fs::write(&nra, b"SQLite format 3\0filas").unwrap();
+        fs::write(&document, b"PK manuscrito").unwrap();
+        let (real, size_bytes) = sha256_file(&document).unwrap();
+        let manifest = Manifest {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            app_version: "0.11.13".into(),
+            exported_at: 0,
+            project_name: "El faro".into(),
+            document: Some(ArchivedDocument {
+                name: "novela.docx".into(),
+                sha256: sha256.map(String::from).unwrap_or(real),
+                size_bytes,
+            }),
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        write_archive(&mut buffer, &manifest, &nra, Some(&document)).unwrap();
+        buffer.set_position(0);
+        buffer
+    }
+
+    #[test]
+    fn archives_round_trip_and_reject_tampered_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = dir.path().join("work");
+        let documents = dir.path().join("documents");
+        fs::create_dir_all(&work).unwrap();
+
+        let extracted = extract_archive(archive(dir.path(), None), &work, &documents).unwrap();
+        assert_eq!(extracted.manifest.project_name, "El faro");
+        assert_eq!(fs::read(&extracted.nra).unwrap(), b"SQLite format 3\0filas");
+        let document = extracted.document.unwrap();
+        assert!(document.starts_with(&documents));
+        assert!(document.to_string_lossy().ends_with("_novela.docx"));
+        assert_eq!(fs::read(&document).unwrap(), b"PK manuscrito");
+
+        let tampered = archive(dir.path(), Some("0000"));
+        assert!(extract_archive(tampered, &work, &documents).is_err());
+        // El manuscrito a medias no se queda en documentos
+        assert_eq!(fs::read_dir(&documents).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn names_from_the_archive_cannot_escape_the_documents_dir() {
+        assert_eq!(safe_file_name("../../.bashrc").as_deref(), Some("bashrc"));
+        assert_eq!(
+            safe_file_name("novela: v2.docx").as_deref(),
+            Some("novela_ v2.docx")
+        );
+        assert_eq!(safe_file_name(".."), None);
+
+        let newer = Manifest {
+            format_version: ARCHIVE_FORMAT_VERSION + 1,
+            app_version: "9.0.0".into(),
+            exported_at: 0,
+            project_name: String::new(),
+            document: None,
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&serde_json::to_vec(&newer).unwrap()).unwrap();
+        zip.finish().unwrap();
+        let error = read_manifest(&mut zip::ZipArchive::new(buffer).unwrap()).unwrap_err();
+        assert!(error.contains("9.0.0"));
+    }
+}
@@ -0,0 +1,163 @@
+// Lista rápida de proyectos de Narrative Assistant
+//
+// Al arrancar, el backend Python tarda varios segundos en estar listo y la
+// pantalla de inicio se quedaba en esqueletos de carga. `list_projects_fast`
+// lee los proyectos directamente de la base de datos (solo lectura, sin
+// esperar al backend) para pintar el selector al instante; el frontend lo
+// sustituye por la lista completa de `/api/projects` cuando llega.
+//
+// La base de datos es del backend: aquí solo se lee, y se tolera un esquema
+// antiguo (sin `analysis_runs`) o una base de datos que aún no existe.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::database;
+
+/// Proyecto tal como lo necesita la pantalla de inicio
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ProjectSummary {
+    pub id: i64,
+    pub name: String,
+    pub document_path: Option<String>,
+    pub document_format: String,
+    pub created_at: Option<String>,
+    pub last_modified: Option<String>,
+    pub last_opened: Option<String>,
+    pub analysis_status: String,
+    pub word_count: i64,
+    pub chapter_count: i64,
+    /// Fin del último análisis completado
+    pub last_analysis_at: Option<String>,
+}
+
+pub(crate) fn has_table(conn: &Connection, table: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|found| found.is_some())
+    .map_err(|e| format!("Error leyendo el esquema: {}", e))
+}
+
+/// Proyectos ordenados como `/api/projects`: último abierto primero
+pub(crate) fn list_projects(db: &Path) -> Result<Vec<ProjectSummary>, String> {
+    let conn = database::open_read_only(db)?;
+    if !has_table(&conn, "projects")? {
+        return Ok(Vec::new());
+    }
+    let last_analysis = if has_table(&conn, "analysis_runs")? {
+        "(SELECT MAX(r.completed_at) FROM analysis_runs r \
+          WHERE r.project_id = p.id AND r.status = 'completed')"
+    } else {
+        "NULL"
+    };
+    // Fechas de SQLite ('2025-01-31 10:00:00') en ISO, como las da la API
+    let sql = format!(
+        "SELECT p.id, p.name, p.document_path, p.document_format,
+                strftime('%Y-%m-%dT%H:%M:%S', p.created_at),
+                strftime('%Y-%m-%dT%H:%M:%S', p.updated_at),
+                strftime('%Y-%m-%dT%H:%M:%S', p.last_opened_at),
+                COALESCE(p.analysis_status, 'pending'),
+                COALESCE(p.word_count, 0),
+                COALESCE(NULLIF((SELECT COUNT(*) FROM chapters c WHERE c.project_id = p.id), 0),
+                         p.chapter_count, 0),
+                strftime('%Y-%m-%dT%H:%M:%S', {})
+         FROM projects p
+         ORDER BY p.last_opened_at IS NULL, p.last_opened_at DESC, p.created_at DESC",
+        last_analysis
+    );
+    let mut statement = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Error leyendo los proyectos: {}", e))?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok(ProjectSummary {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                document_path: row.get(2)?,
+                document_format: row.get(3)?,
+                created_at: row.get(4)?,
+                last_modified: row.get(5)?,
+                last_opened: row.get(6)?,
+                analysis_status: row.get(7)?,
+                word_count: row.get(8)?,
+                chapter_count: row.get(9)?,
+                last_analysis_at: row.get(10)?,
+            })
+        })
+        .map_err(|e| format!("Error leyendo los proyectos: {}", e))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| format!("Error leyendo los proyectos: {}", e))
+}
+
+/// Proyectos leídos de la base de datos sin pasar por el backend
+#[tauri::command]
+pub async fn list_projects_fast() -> Result<Vec<ProjectSummary>, String> {
+    let db = database::database_path().ok_or("No se pudo determinar el directorio home")?;
+    if !db.exists() {
+        return Ok(Vec::new());
+    }
+    tauri::async_runtime::spawn_blocking(move || list_projects(&db))
+        .await
+        .map_err(|e| format!("Error en la tarea de lectura de proyectos: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_projects_with_chapters_and_last_completed_analysis() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("narrative_assistant.db");
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE projects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, document_path TEXT,
+                document_format TEXT NOT NULL, word_count INTEGER, chapter_count INTEGER,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                last_opened_at TEXT, analysis_status TEXT DEFAULT 'pending');
+             CREATE TABLE chapters (id INTEGER PRIMARY KEY, project_id INTEGER NOT NULL);
+             INSERT INTO projects (name, document_format, word_count, chapter_count, created_at, last_opened_at)
+                VALUES ('El faro', 'docx', 81000, 3, '2025-01-02 09:00:00', '2025-03-01 18:30:15');
+             INSERT INTO projects (name, document_format, created_at)
+                VALUES ('Borrador', 'txt', '2025-02-01 09:00:00');
+             INSERT INTO chapters (project_id) VALUES (1), (1);",
+        )
+        .unwrap();
+
+        // Sin analysis_runs (esquema antiguo)
+        let projects = list_projects(&db).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].name, "El faro");
+        assert_eq!(projects[0].chapter_count, 2);
+        assert_eq!(
+            projects[0].last_opened.as_deref(),
+            Some("2025-03-01T18:30:15")
+        );
+        assert_eq!(projects[0].last_analysis_at, None);
+        // Sin capítulos guardados se usa el recuento del proyecto
+        assert_eq!(projects[1].chapter_count, 0);
+        assert_eq!(projects[1].word_count, 0);
+
+        conn.execute_batch(
+            "CREATE TABLE analysis_runs (id INTEGER PRIMARY KEY, project_id INTEGER NOT NULL,
+                completed_at TEXT, status TEXT);
+             INSERT INTO analysis_runs (project_id, completed_at, status) VALUES
+                (1, '2025-02-10 12:00:00', 'completed'),
+                (1, '2025-02-20 12:00:00', 'failed'),
+                (1, NULL, 'running');",
+        )
+        .unwrap();
+        let projects = list_projects(&db).unwrap();
+        assert_eq!(
+            projects[0].last_analysis_at.as_deref(),
+            Some("2025-02-10T12:00:00")
+        );
+    }
+}
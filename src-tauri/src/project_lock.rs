@@ -0,0 +1,381 @@
+// Bloqueo de proyectos abiertos en Narrative Assistant
+//
+// Con la base de datos en una carpeta sincronizada (Dropbox, OneDrive...) dos
+// equipos pueden abrir el mismo proyecto a la vez y pisarse las revisiones.
+// Al abrir un proyecto la ventana pide `acquire_project_lock`, que deja junto
+// a la base de datos `locks/project-<id>.lock` con el PID, el equipo y la
+// instancia que lo tiene. Si ya hay un bloqueo vivo de otra instancia se
+// devuelve quién lo tiene y el frontend ofrece abrirlo en solo lectura o
+// quitarle el bloqueo (`force`).
+//
+// - Un bloqueo está vivo si se ha renovado en los últimos `STALE_AFTER`
+//   segundos (el monitor lo renueva cada `HEARTBEAT`) y, en el mismo equipo,
+//   su proceso sigue en marcha. Así un cierre inesperado no deja el proyecto
+//   bloqueado para siempre.
+// - Varias ventanas de la misma instancia comparten el bloqueo; se borra al
+//   cerrar la última (`release_project_lock` o al destruirse la ventana).
+// - Si al renovar el bloqueo resulta que otra instancia lo ha forzado, se
+//   emite `project-lock-lost` a las ventanas del proyecto.
+//
+// En una carpeta sincronizada no hay creación atómica entre equipos: esto
+// avisa de la situación habitual, no garantiza exclusión estricta.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, State, WebviewWindow};
+
+use crate::clock::now_secs;
+use crate::{database, settings};
+
+/// Subcarpeta de los bloqueos, junto a la base de datos
+const LOCK_DIR: &str = "locks";
+
+/// Cada cuánto se renuevan los bloqueos propios
+const HEARTBEAT: Duration = Duration::from_secs(60);
+
+/// Un bloqueo sin renovar durante este tiempo (s) se considera abandonado
+const STALE_AFTER: u64 = 5 * 60;
+
+/// Contenido de un fichero de bloqueo
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub hostname: String,
+    /// Identificador de la instancia de la app que lo creó
+    pub instance: String,
+    /// Segundos desde epoch
+    pub opened_at: u64,
+    pub heartbeat_at: u64,
+}
+
+/// Resultado de `acquire_project_lock`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProjectLockStatus {
+    pub acquired: bool,
+    /// Quien tiene el bloqueo si no se ha conseguido
+    pub holder: Option<LockInfo>,
+}
+
+/// Payload de `project-lock-lost`
+#[derive(Serialize, Clone, Debug)]
+struct LockLost {
+    project_id: i64,
+    holder: LockInfo,
+}
+
+fn process_alive(pid: u32) -> bool {
+    let mut system = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true) > 0
+}
+
+/// Si `lock` impide abrir el proyecto a otra instancia en `hostname`
+fn is_live(lock: &LockInfo, hostname: &str, now: u64, alive: impl Fn(u32) -> bool) -> bool {
+    let fresh = now.saturating_sub(lock.heartbeat_at) < STALE_AFTER;
+    fresh && (lock.hostname != hostname || alive(lock.pid))
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_lock(path: &Path, lock: &LockInfo) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(lock).map_err(|e| e.to_string())?;
+    settings::write_atomic(path, &json)
+        .map_err(|e| format!("Error guardando {}: {}", path.display(), e))
+}
+
+/// Bloqueos de esta instancia, registrado con `.manage()`
+pub struct ProjectLocks {
+    instance: String,
+    hostname: String,
+    /// Carpeta de bloqueos (`None` si no se encuentra la base de datos)
+    dir: Option<PathBuf>,
+    /// Ventanas que tienen abierto cada proyecto bloqueado
+    held: Mutex<HashMap<i64, HashSet<String>>>,
+}
+
+impl ProjectLocks {
+    pub fn new() -> Self {
+        let dir =
+            database::database_path().and_then(|db| db.parent().map(|dir| dir.join(LOCK_DIR)));
+        Self::with_dir(dir)
+    }
+
+    fn with_dir(dir: Option<PathBuf>) -> Self {
+        Self {
+            instance: uuid::Uuid::new_v4().to_string(),
+            hostname: sysinfo::System::host_name().unwrap_or_else(|| "desconocido".into()),
+            dir,
+            held: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_path(&self, project_id: i64) -> Result<PathBuf, String> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("project-{}.lock", project_id)))
+            .ok_or_else(|| "No se encontro la carpeta de la base de datos".to_string())
+    }
+
+    fn own_lock(&self, opened_at: u64) -> LockInfo {
+        LockInfo {
+            pid: std::process::id(),
+            hostname: self.hostname.clone(),
+            instance: self.instance.clone(),
+            opened_at,
+            heartbeat_at: now_secs(),
+        }
+    }
+
+    fn acquire(
+        &self,
+        project_id: i64,
+        window: &str,
+        force: bool,
+        alive: impl Fn(u32) -> bool,
+    ) -> Result<ProjectLockStatus, String> {
+        let path = self.lock_path(project_id)?;
+        let mut held = self.held.lock().unwrap();
+        let existing = read_lock(&path);
+        if let Some(lock) = existing.as_ref() {
+            if lock.instance != self.instance
+                && !force
+                && is_live(lock, &self.hostname, now_secs(), alive)
+            {
+                return Ok(ProjectLockStatus {
+                    acquired: false,
+                    holder: Some(lock.clone()),
+                });
+            }
+        }
+        let opened_at = existing
+            .filter(|lock| lock.instance == self.instance)
+            .map(|lock| lock.opened_at)
+            .unwrap_or_else(now_secs);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("No se pudo crear {}: {}", dir.display(), e))?;
+        }
+        write_lock(&path, &self.own_lock(opened_at))?;
+        held.entry(project_id)
+            .or_default()
+            .insert(window.to_string());
+        Ok(ProjectLockStatus {
+            acquired: true,
+            holder: None,
+        })
+    }
+
+    /// Suelta el proyecto en `window`; borra el fichero si era la última ventana
+    fn release(&self, project_id: i64, window: &str) {
+        let mut held = self.held.lock().unwrap();
+        let Some(windows) = held.get_mut(&project_id) else {
+            return;
+        };
+        windows.remove(window);
+        if !windows.is_empty() {
+            return;
+        }
+        held.remove(&project_id);
+        if let Ok(path) = self.lock_path(project_id) {
+            if read_lock(&path).is_some_and(|lock| lock.instance == self.instance) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    /// Proyectos que tenía abiertos `window`
+    fn projects_of(&self, window: &str) -> Vec<i64> {
+        self.held
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, windows)| windows.contains(window))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Renueva los bloqueos propios; devuelve los que ha forzado otra instancia
+    fn heartbeat(&self) -> Vec<(i64, HashSet<String>, LockInfo)> {
+        let mut held = self.held.lock().unwrap();
+        let mut lost = Vec::new();
+        held.retain(|project_id, windows| {
+            let Ok(path) = self.lock_path(*project_id) else {
+                return true;
+            };
+            match read_lock(&path) {
+                Some(lock) if lock.instance != self.instance => {
+                    lost.push((*project_id, windows.clone(), lock));
+                    false
+                }
+                existing => {
+                    let opened_at = existing.map(|lock| lock.opened_at).unwrap_or_else(now_secs);
+                    if let Err(e) = write_lock(&path, &self.own_lock(opened_at)) {
+                        tracing::warn!(target: "project_lock", "{}", e);
+                    }
+                    true
+                }
+            }
+        });
+        lost
+    }
+}
+
+/// Renueva los bloqueos en segundo plano (se lanza en el setup)
+pub async fn monitor(app: AppHandle) {
+    let mut interval = tokio::time::interval(HEARTBEAT);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let locks = app.state::<ProjectLocks>();
+        for (project_id, windows, holder) in locks.heartbeat() {
+            tracing::warn!(
+                target: "project_lock",
+                "El proyecto {} lo ha abierto {} (PID {})",
+                project_id,
+                holder.hostname,
+                holder.pid
+            );
+            for window in windows {
+                let _ = app.emit_to(
+                    EventTarget::webview_window(&window),
+                    "project-lock-lost",
+                    LockLost {
+                        project_id,
+                        holder: holder.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Suelta los proyectos de una ventana que se ha cerrado
+pub fn release_window(app: &AppHandle, label: &str) {
+    let locks = app.state::<ProjectLocks>();
+    for project_id in locks.projects_of(label) {
+        locks.release(project_id, label);
+    }
+}
+
+/// Bloquea el proyecto para esta ventana. Con `force` se quita el bloqueo a
+/// otra instancia (el usuario ha decidido abrirlo igualmente).
+#[tauri::command]
+pub fn acquire_project_lock(
+    window: WebviewWindow,
+    locks: State<'_, ProjectLocks>,
+    project_id: i64,
+    force: Option<bool>,
+) -> Result<ProjectLockStatus, String> {
+    let status = locks.acquire(
+        project_id,
+        window.label(),
+        force.unwrap_or(false),
+        process_alive,
+    )?;
+    match &status.holder {
+        Some(holder) => tracing::info!(
+            target: "project_lock",
+            "Proyecto {} bloqueado por {} (PID {})",
+            project_id,
+            holder.hostname,
+            holder.pid
+        ),
+        None if force == Some(true) => tracing::warn!(
+            target: "project_lock",
+            "Bloqueo del proyecto {} forzado",
+            project_id
+        ),
+        None => {}
+    }
+    Ok(status)
+}
+
+/// La ventana deja el proyecto (al cerrarlo o cambiar a otro)
+#[tauri::command]
+pub fn release_project_lock(
+    window: WebviewWindow,
+    locks: State<'_, ProjectLocks>,
+    project_id: i64,
+) {
+    locks.release(project_id, window.label());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(hostname: &str, heartbeat_at: u64) -> LockInfo {
+        LockInfo {
+            pid: 4242,
+            hostname: hostname.into(),
+            instance: "otra".into(),
+            opened_at: 0,
+            heartbeat_at,
+        }
+    }
+
+    #[test]
+    fn live_locks_need_a_recent_heartbeat_and_a_running_process_here() {
+        let now = 10_000;
+        assert!(is_live(
+            &lock("portatil", now - 30),
+            "sobremesa",
+            now,
+            |_| false
+        ));
+        assert!(!is_live(
+            &lock("portatil", now - STALE_AFTER),
+            "sobremesa",
+            now,
+            |_| true
+        ));
+        // En el mismo equipo manda el proceso
+        assert!(is_live(
+            &lock("sobremesa", now - 30),
+            "sobremesa",
+            now,
+            |_| true
+        ));
+        assert!(!is_live(
+            &lock("sobremesa", now - 30),
+            "sobremesa",
+            now,
+            |_| false
+        ));
+    }
+
+    #[test]
+    fn another_instance_is_refused_until_forced_and_windows_share_the_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let mine = ProjectLocks::with_dir(Some(dir.path().to_path_buf()));
+        let theirs = ProjectLocks::with_dir(Some(dir.path().to_path_buf()));
+        let alive = |_| true;
+
+        assert!(mine.acquire(3, "main", false, alive).unwrap().acquired);
+        assert!(mine.acquire(3, "project-3", false, alive).unwrap().acquired);
+        let refused = theirs.acquire(3, "main", false, alive).unwrap();
+        assert!(!refused.acquired);
+        assert_eq!(refused.holder.unwrap().instance, mine.instance);
+
+        // Cerrar una de las dos ventanas no suelta el bloqueo
+        mine.release(3, "main");
+        assert!(!theirs.acquire(3, "main", false, alive).unwrap().acquired);
+        mine.release(3, "project-3");
+        assert!(!dir.path().join("project-3.lock").exists());
+
+        // Forzado: la instancia anterior lo detecta al renovar
+        assert!(mine.acquire(3, "main", false, alive).unwrap().acquired);
+        assert!(theirs.acquire(3, "main", true, alive).unwrap().acquired);
+        let lost = mine.heartbeat();
+        assert_eq!(lost.len(), 1);
+        assert_eq!(lost[0].2.instance, theirs.instance);
+        assert!(mine.projects_of("main").is_empty());
+    }
+}
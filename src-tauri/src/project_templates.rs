@@ -0,0 +1,308 @@
+// Plantillas de proyecto nuevo en Narrative Assistant
+//
+// "Nuevo proyecto" exigía un manuscrito ya escrito. Las plantillas
+// (`templates/*.json`, instaladas como recurso) preparan el punto de partida
+// de quien empieza de cero: novela, relato corto y saga multi-libro.
+// `create_project_from_template` crea en Documentos/Narrative Assistant una
+// carpeta con el nombre del proyecto, sus subcarpetas y fichas de trabajo, y
+// un manuscrito Markdown con los títulos de capítulo de la plantilla. El
+// frontend crea el proyecto en el backend desde ese manuscrito y le aplica el
+// tipo de documento de la plantilla (y, en la saga, una colección).
+//
+// Las rutas de carpetas y fichas de una plantilla son relativas y no pueden
+// salir de la carpeta del proyecto.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Directorio de plantillas dentro de los recursos (tauri.conf.json)
+const TEMPLATES_RESOURCE_DIR: &str = "templates";
+
+/// Carpeta de proyectos dentro de Documentos
+const PROJECTS_DIR_NAME: &str = "Narrative Assistant";
+
+/// Ficha que la plantilla crea en la carpeta del proyecto
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SeedFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// Plantilla de proyecto
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProjectTemplate {
+    pub id: String,
+    /// Posición en la lista
+    #[serde(default)]
+    pub order: u32,
+    pub name: String,
+    pub description: String,
+    /// Tipo y subtipo de documento del backend (feature_profile/models.py)
+    pub document_type: String,
+    pub document_subtype: Option<String>,
+    /// Si el proyecto se agrupa en una colección con el nombre del proyecto
+    #[serde(default)]
+    pub collection: bool,
+    #[serde(default)]
+    pub folders: Vec<String>,
+    /// Títulos de capítulo del manuscrito inicial
+    pub chapters: Vec<String>,
+    #[serde(default)]
+    pub files: Vec<SeedFile>,
+}
+
+/// Carpeta preparada (respuesta de `create_project_from_template`)
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct TemplateProject {
+    pub template_id: String,
+    pub folder: String,
+    /// Manuscrito inicial, desde el que se crea el proyecto
+    pub manuscript_path: String,
+    pub description: String,
+    pub document_type: String,
+    pub document_subtype: Option<String>,
+    /// Nombre de la colección que hay que crear, si la plantilla la pide
+    pub collection_name: Option<String>,
+}
+
+/// Directorio con las plantillas (el del repositorio en desarrollo)
+fn templates_dir<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    let installed = app
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join(TEMPLATES_RESOURCE_DIR))
+        .filter(|dir| dir.is_dir());
+    if installed.is_some() || !cfg!(debug_assertions) {
+        return installed;
+    }
+    Some(Path::new(env!("CARGO_MANIFEST_DIR")).join(TEMPLATES_RESOURCE_DIR))
+        .filter(|dir| dir.is_dir())
+}
+
+/// Plantillas válidas de `dir`, en orden; las mal formadas se ignoran
+fn load_templates(dir: &Path) -> Vec<ProjectTemplate> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<ProjectTemplate> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    tracing::warn!(target: "project_templates", "{}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    templates.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.id.cmp(&b.id)));
+    templates
+}
+
+/// Ruta relativa de la plantilla dentro de `root`, sin `..` ni rutas absolutas
+fn inside(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(format!(
+            "Ruta de plantilla no valida: {}",
+            relative.display()
+        ));
+    }
+    Ok(root.join(relative))
+}
+
+/// Nombre de carpeta y archivo para `name` (sin separadores ni caracteres reservados en Windows)
+fn safe_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || r#"<>:"/\|?*"#.contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').trim();
+    if cleaned.is_empty() {
+        "Proyecto".into()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// `parent/name`, o `name (2)`, `name (3)`... si ya existe
+fn unique_dir(parent: &Path, name: &str) -> PathBuf {
+    let first = parent.join(name);
+    if !first.exists() {
+        return first;
+    }
+    (2..)
+        .map(|n| parent.join(format!("{} ({})", name, n)))
+        .find(|dir| !dir.exists())
+        .expect("siempre hay un nombre libre")
+}
+
+/// Manuscrito inicial: un `#` por capítulo, sin texto
+fn manuscript(template: &ProjectTemplate) -> String {
+    let chapters: Vec<String> = template
+        .chapters
+        .iter()
+        .map(|title| format!("# {}\n", title))
+        .collect();
+    chapters.join("\n")
+}
+
+/// Crea la carpeta del proyecto en `parent` según la plantilla
+fn scaffold(
+    template: &ProjectTemplate,
+    name: &str,
+    parent: &Path,
+) -> Result<TemplateProject, String> {
+    let file_name = safe_name(name);
+    let root = unique_dir(parent, &file_name);
+    let create = |dir: &Path| {
+        fs::create_dir_all(dir).map_err(|e| format!("No se pudo crear {}: {}", dir.display(), e))
+    };
+    create(&root)?;
+    for folder in &template.folders {
+        create(&inside(&root, folder)?)?;
+    }
+    for seed in &template.files {
+        let path = inside(&root, &seed.path)?;
+        if let Some(dir) = path.parent() {
+            create(dir)?;
+        }
+        fs::write(&path, &seed.content)
+            .map_err(|e| format!("No se pudo crear {}: {}", path.display(), e))?;
+    }
+    let manuscript_path = root.join(format!("{}.md", file_name));
+    fs::write(&manuscript_path, manuscript(template))
+        .map_err(|e| format!("No se pudo crear el manuscrito: {}", e))?;
+
+    Ok(TemplateProject {
+        template_id: template.id.clone(),
+        folder: root.to_string_lossy().into_owned(),
+        manuscript_path: manuscript_path.to_string_lossy().into_owned(),
+        description: template.description.clone(),
+        document_type: template.document_type.clone(),
+        document_subtype: template.document_subtype.clone(),
+        collection_name: template.collection.then(|| name.trim().to_string()),
+    })
+}
+
+/// Plantillas de proyecto disponibles
+#[tauri::command]
+pub fn list_project_templates(app: AppHandle) -> Vec<ProjectTemplate> {
+    templates_dir(&app)
+        .map(|dir| load_templates(&dir))
+        .unwrap_or_default()
+}
+
+/// Prepara la carpeta y el manuscrito inicial de un proyecto con la plantilla `id`
+#[tauri::command]
+pub async fn create_project_from_template(
+    app: AppHandle,
+    id: String,
+    name: String,
+) -> Result<TemplateProject, String> {
+    if name.trim().is_empty() {
+        return Err("El nombre del proyecto es obligatorio".into());
+    }
+    let template = list_project_templates(app)
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("No existe la plantilla {}", id))?;
+    let parent = dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| "No se encontro la carpeta de Documentos".to_string())?
+        .join(PROJECTS_DIR_NAME);
+    tauri::async_runtime::spawn_blocking(move || {
+        let project = scaffold(&template, &name, &parent)?;
+        tracing::info!(
+            target: "project_templates",
+            "Proyecto '{}' preparado con la plantilla {} en {}",
+            name,
+            template.id,
+            project.folder
+        );
+        Ok(project)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de plantilla: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_templates_are_valid() {
+        let templates = load_templates(&Path::new(env!("CARGO_MANIFEST_DIR")).join("templates"));
+        let ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["novela", "relato-corto", "saga"]);
+        for template in &templates {
+            assert!(!template.chapters.is_empty(), "{}", template.id);
+            let root = Path::new("/proyecto");
+            for path in template
+                .folders
+                .iter()
+                .chain(template.files.iter().map(|f| &f.path))
+            {
+                assert!(inside(root, path).is_ok(), "{}: {}", template.id, path);
+            }
+        }
+    }
+
+    #[test]
+    fn template_scaffolds_a_unique_folder_with_manuscript() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = ProjectTemplate {
+            id: "saga".into(),
+            order: 0,
+            name: "Saga".into(),
+            description: "Saga".into(),
+            document_type: "FIC".into(),
+            document_subtype: Some("FIC_GEN".into()),
+            collection: true,
+            folders: vec!["Biblia/Mundo".into()],
+            chapters: vec!["Prólogo".into(), "Capítulo 1".into()],
+            files: vec![SeedFile {
+                path: "Biblia/Reglas.md".into(),
+                content: "# Reglas\n".into(),
+            }],
+        };
+        let first = scaffold(&template, "El faro: libro 1", dir.path()).unwrap();
+        let root = dir.path().join("El faro_ libro 1");
+        assert_eq!(first.folder, root.to_string_lossy());
+        assert!(root.join("Biblia").join("Mundo").is_dir());
+        assert_eq!(
+            fs::read_to_string(root.join("Biblia").join("Reglas.md")).unwrap(),
+            "# Reglas\n"
+        );
+        let manuscript = fs::read_to_string(&first.manuscript_path).unwrap();
+        assert_eq!(manuscript, "# Prólogo\n\n# Capítulo 1\n");
+        assert_eq!(first.collection_name.as_deref(), Some("El faro: libro 1"));
+
+        let second = scaffold(&template, "El faro: libro 1", dir.path()).unwrap();
+        assert!(second.folder.ends_with("El faro_ libro 1 (2)"));
+
+        let escaping = ProjectTemplate {
+            folders: vec!["../fuera".into()],
+            ..template
+        };
+        assert!(scaffold(&escaping, "Otro", dir.path()).is_err());
+    }
+}
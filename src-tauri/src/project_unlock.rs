@@ -0,0 +1,460 @@
+// Desbloqueo con contraseña de los proyectos cifrados en Narrative Assistant
+//
+// Con la base de datos cifrada (db_encryption.rs) la clave vive en el almacén
+// del sistema: cualquiera con la sesión del usuario abierta puede leer los
+// proyectos. Para encargos confidenciales la clave puede salir de una
+// contraseña:
+//
+// - `set_project_password` deriva la clave con Argon2id (sal y parámetros en
+//   db_password.json, junto a la base de datos), vuelve a cifrar la base de
+//   datos y sus snapshots con ella y borra la clave del almacén del sistema.
+// - Al arrancar, si la base de datos está bloqueada, el backend no se lanza
+//   hasta `unlock_project`: se publica el estado `locked` y se abre la
+//   ventana de desbloqueo.
+// - `lock_project` (o `auto_lock`, tras `auto_lock_minutes` sin usar el
+//   equipo) detiene el backend y olvida la clave.
+//
+// El cifrado es de toda la base de datos, así que desbloquear "el proyecto"
+// desbloquea todos. La contraseña se escribe en una ventana propia
+// (frontend/public/unlock.html) que se destruye al terminar: nunca pasa por
+// el heap de la ventana principal ni por sus stores, y la clave derivada no
+// sale del shell.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+use crate::i18n::Message;
+use crate::settings::{self, SettingsStore};
+use crate::{database, db_encryption, downloads, idle, status_channel, BackendServer};
+
+/// Label de la ventana de desbloqueo
+pub const UNLOCK_WINDOW: &str = "unlock";
+
+/// Página de la ventana (frontend/public)
+const UNLOCK_URL: &str = "unlock.html";
+
+/// Sal y parámetros de la derivación, junto a la base de datos
+const PASSWORD_FILE: &str = "db_password.json";
+
+/// Longitud mínima de la contraseña
+const MIN_PASSWORD_LEN: usize = 8;
+
+/// Espera tras una contraseña incorrecta (frena los intentos por fuerza bruta)
+const WRONG_PASSWORD_DELAY: Duration = Duration::from_secs(1);
+
+/// Intervalo con el que `auto_lock` comprueba la inactividad
+const AUTO_LOCK_POLL: Duration = Duration::from_secs(30);
+
+/// Parámetros de Argon2id guardados con la sal (para poder endurecerlos sin romper contraseñas antiguas)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct PasswordConfig {
+    /// Sal en hexadecimal
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl PasswordConfig {
+    /// Sal nueva con los parámetros recomendados por OWASP para Argon2id
+    fn generate() -> Self {
+        let mut salt = [0u8; 16];
+        getrandom::getrandom(&mut salt).expect("el sistema no ofrece numeros aleatorios");
+        Self {
+            salt: downloads::to_hex(&salt),
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Lo que pide la ventana de desbloqueo
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnlockMode {
+    /// Introducir la contraseña para desbloquear
+    Unlock,
+    /// Elegir la contraseña con la que proteger la base de datos
+    Set,
+}
+
+/// Estado de `get_unlock_state`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnlockStatus {
+    /// Si la base de datos está protegida con contraseña
+    pub protected: bool,
+    pub locked: bool,
+    pub mode: UnlockMode,
+    pub min_password_len: usize,
+}
+
+/// Registrado con `.manage()`
+pub struct UnlockState {
+    mode: Mutex<UnlockMode>,
+    /// Si el backend estaba corriendo al bloquear (se relanza al desbloquear)
+    resume_backend: AtomicBool,
+    unlocked: tokio::sync::Notify,
+}
+
+impl UnlockState {
+    pub fn new() -> Self {
+        Self {
+            mode: Mutex::new(UnlockMode::Unlock),
+            resume_backend: AtomicBool::new(false),
+            unlocked: tokio::sync::Notify::new(),
+        }
+    }
+}
+
+/// Si `label` es la ventana de desbloqueo (no guarda geometría ni recibe eventos de menú)
+pub fn is_unlock_window(label: &str) -> bool {
+    label == UNLOCK_WINDOW
+}
+
+fn config_path() -> Option<PathBuf> {
+    Some(database::database_path()?.parent()?.join(PASSWORD_FILE))
+}
+
+fn read_config(path: &Path) -> Result<PasswordConfig, String> {
+    let json =
+        fs::read_to_string(path).map_err(|e| format!("Error leyendo {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("{} no es valido: {}", path.display(), e))
+}
+
+/// Si la clave de la base de datos sale de una contraseña
+pub fn is_password_protected() -> bool {
+    config_path().is_some_and(|path| path.exists())
+}
+
+/// Si hace falta la contraseña para usar la base de datos
+pub fn is_locked() -> bool {
+    is_password_protected() && db_encryption::stored_key().is_none()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err("Sal no valida".into());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "Sal no valida".to_string()))
+        .collect()
+}
+
+/// Clave de SQLCipher (32 bytes en hexadecimal) derivada de la contraseña
+fn derive_key(password: &str, config: &PasswordConfig) -> Result<String, String> {
+    let params = Params::new(
+        config.memory_kib,
+        config.iterations,
+        config.parallelism,
+        Some(32),
+    )
+    .map_err(|e| format!("Parametros de derivacion no validos: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), &decode_hex(&config.salt)?, &mut key)
+        .map_err(|e| format!("Error derivando la clave: {}", e))?;
+    Ok(downloads::to_hex(&key))
+}
+
+async fn derive_key_blocking(password: String, config: PasswordConfig) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || derive_key(&password, &config))
+        .await
+        .map_err(|e| format!("Error en la tarea de derivacion: {}", e))?
+}
+
+/// Abre (o enfoca) la ventana de desbloqueo en el modo indicado
+fn open_window(app: &AppHandle, mode: UnlockMode) {
+    *app.state::<UnlockState>().mode.lock().unwrap() = mode;
+    if let Some(window) = app.get_webview_window(UNLOCK_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let title = match mode {
+        UnlockMode::Unlock => "Desbloquear proyectos",
+        UnlockMode::Set => "Proteger con contraseña",
+    };
+    let result = WebviewWindowBuilder::new(app, UNLOCK_WINDOW, WebviewUrl::App(UNLOCK_URL.into()))
+        .title(title)
+        .inner_size(380.0, 260.0)
+        .resizable(false)
+        .always_on_top(true)
+        .center()
+        .focused(true)
+        .build();
+    if let Err(e) = result {
+        tracing::warn!(target: "project_unlock", "No se pudo abrir la ventana de desbloqueo: {}", e);
+    }
+}
+
+fn close_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(UNLOCK_WINDOW) {
+        let _ = window.close();
+    }
+}
+
+/// Avisa de que la base de datos está bloqueada y pide la contraseña
+fn prompt_unlock(app: &AppHandle) {
+    status_channel::backend_status(app, "locked", Message::new("backend.locked"));
+    open_window(app, UnlockMode::Unlock);
+}
+
+/// Espera a que se desbloquee la base de datos (arranque: antes de lanzar el backend)
+pub async fn wait_until_unlocked(app: &AppHandle) {
+    let state = app.state::<UnlockState>();
+    let mut prompted = false;
+    loop {
+        let unlocked = state.unlocked.notified();
+        if !is_locked() {
+            return;
+        }
+        if !prompted {
+            tracing::info!(target: "project_unlock", "Base de datos bloqueada: esperando la contrasena");
+            prompt_unlock(app);
+            prompted = true;
+        }
+        unlocked.await;
+    }
+}
+
+/// Detiene el backend y olvida la clave
+async fn lock(app: &AppHandle) {
+    let server_state = app.state::<BackendServer>();
+    let was_running = crate::pause_backend(app, &server_state);
+    app.state::<UnlockState>()
+        .resume_backend
+        .fetch_or(was_running, Ordering::SeqCst);
+    db_encryption::set_session_key(None);
+    tracing::info!(target: "project_unlock", "Base de datos bloqueada");
+    prompt_unlock(app);
+}
+
+/// Bloquea la base de datos tras `auto_lock_minutes` sin usar el equipo
+pub async fn auto_lock(app: AppHandle) {
+    loop {
+        tokio::time::sleep(AUTO_LOCK_POLL).await;
+        let minutes = app.state::<SettingsStore>().get().auto_lock_minutes;
+        if minutes == 0 || !is_password_protected() || is_locked() {
+            continue;
+        }
+        if idle::idle_time(&app) >= Duration::from_secs(minutes as u64 * 60) {
+            tracing::info!(target: "project_unlock", "{} min de inactividad: bloqueando", minutes);
+            lock(&app).await;
+        }
+    }
+}
+
+/// Estado del bloqueo (ventana de desbloqueo y preferencias)
+#[tauri::command]
+pub fn get_unlock_state(state: State<'_, UnlockState>) -> UnlockStatus {
+    UnlockStatus {
+        protected: is_password_protected(),
+        locked: is_locked(),
+        mode: *state.mode.lock().unwrap(),
+        min_password_len: MIN_PASSWORD_LEN,
+    }
+}
+
+/// Abre la ventana para desbloquear o, si aún no hay contraseña, para elegirla
+#[tauri::command]
+pub fn open_unlock_window(app: AppHandle) -> Result<(), String> {
+    let db = database::database_path().ok_or("No se pudo determinar el directorio home")?;
+    if is_password_protected() {
+        if !is_locked() {
+            return Err("Los proyectos ya estan desbloqueados".into());
+        }
+        open_window(&app, UnlockMode::Unlock);
+    } else {
+        if !db.exists() {
+            return Err(format!("Base de datos no encontrada: {}", db.display()));
+        }
+        open_window(&app, UnlockMode::Set);
+    }
+    Ok(())
+}
+
+/// Desbloquea la base de datos con la contraseña y relanza el backend
+#[tauri::command]
+pub async fn unlock_project(
+    app: AppHandle,
+    state: State<'_, UnlockState>,
+    server_state: State<'_, BackendServer>,
+    password: String,
+) -> Result<(), String> {
+    if !is_locked() {
+        close_window(&app);
+        return Ok(());
+    }
+    let db = database::database_path().ok_or("No se pudo determinar el directorio home")?;
+    let config = read_config(&config_path().ok_or("No se pudo determinar el directorio home")?)?;
+    let key = derive_key_blocking(password, config).await?;
+    let db_clone = db.clone();
+    let key_clone = key.clone();
+    let opens = tauri::async_runtime::spawn_blocking(move || {
+        db_encryption::key_opens(&db_clone, &key_clone)
+    })
+    .await
+    .unwrap_or(false);
+    if !opens {
+        tokio::time::sleep(WRONG_PASSWORD_DELAY).await;
+        return Err("Contraseña incorrecta".into());
+    }
+
+    db_encryption::set_session_key(Some(key));
+    tracing::info!(target: "project_unlock", "Base de datos desbloqueada");
+    close_window(&app);
+    state.unlocked.notify_waiters();
+    let was_running = state.resume_backend.swap(false, Ordering::SeqCst);
+    crate::resume_backend(&app, server_state, was_running).await;
+    Ok(())
+}
+
+/// Bloquea la base de datos ahora
+#[tauri::command]
+pub async fn lock_project(app: AppHandle) -> Result<(), String> {
+    if !is_password_protected() {
+        return Err("Los proyectos no estan protegidos con contraseña".into());
+    }
+    if !is_locked() {
+        lock(&app).await;
+    }
+    Ok(())
+}
+
+/// Protege la base de datos con una contraseña (desde la ventana en modo `Set`).
+/// Una base de datos en claro se cifra; una cifrada con la clave del almacén
+/// del sistema cambia de clave.
+#[tauri::command]
+pub async fn set_project_password(
+    app: AppHandle,
+    state: State<'_, UnlockState>,
+    server_state: State<'_, BackendServer>,
+    password: String,
+) -> Result<(), String> {
+    if *state.mode.lock().unwrap() != UnlockMode::Set {
+        return Err("La ventana no esta en modo de elegir contraseña".into());
+    }
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!(
+            "La contraseña debe tener al menos {} caracteres",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    if is_password_protected() {
+        return Err("Los proyectos ya estan protegidos con contraseña".into());
+    }
+    let db = database::database_path().ok_or("No se pudo determinar el directorio home")?;
+    if !db.exists() {
+        return Err(format!("Base de datos no encontrada: {}", db.display()));
+    }
+    let old_key = if db_encryption::is_plaintext(&db) {
+        None
+    } else {
+        Some(db_encryption::stored_key().ok_or("Falta la clave actual de la base de datos")?)
+    };
+    let config = PasswordConfig::generate();
+    let key = derive_key_blocking(password, config.clone()).await?;
+    let path = config_path().ok_or("No se pudo determinar el directorio home")?;
+
+    let was_running = crate::pause_backend(&app, &server_state);
+    let new_key = key.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let json = serde_json::to_vec_pretty(&config).map_err(|e| e.to_string())?;
+        match &old_key {
+            Some(old_key) => db_encryption::rekey_file(&db, old_key, &new_key)?,
+            None => db_encryption::encrypt_file(&db, &new_key)?,
+        }
+        // Los snapshots con la clave anterior dejarían de poder restaurarse
+        // al borrarla del almacén del sistema
+        if let Some(dir) = db_encryption::snapshots_dir(&db) {
+            if let Err(e) = db_encryption::reencrypt_snapshots(&dir, old_key.as_deref(), &new_key) {
+                tracing::warn!(target: "project_unlock", "{}", e);
+            }
+        }
+        // db_password.json solo aparece si la base de datos ya usa la clave
+        // derivada (de forma atómica: a medio escribir no se podría derivar)
+        settings::write_atomic(&path, &json).map_err(|e| {
+            // Sin sal la clave derivada se perdería: queda en el almacén del sistema
+            if let Err(store) = db_encryption::store_key(&new_key) {
+                tracing::error!(target: "project_unlock", "{}", store);
+            }
+            format!("Error guardando {}: {}", path.display(), e)
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de cifrado: {}", e))
+    .and_then(|r| r);
+
+    if result.is_ok() {
+        db_encryption::set_session_key(Some(key));
+        if let Err(e) = db_encryption::delete_keychain_key() {
+            tracing::warn!(target: "project_unlock", "{}", e);
+        }
+        tracing::info!(target: "project_unlock", "Base de datos protegida con contraseña");
+        close_window(&app);
+    }
+    crate::resume_backend(&app, server_state, was_running).await;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn test_config(salt: &str) -> PasswordConfig {
+        // Parámetros mínimos: las pruebas no miden la resistencia
+        PasswordConfig {
+            salt: salt.into(),
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        }
+    }
+
+    #[test]
+    fn key_depends_on_password_and_salt() {
+        let config = test_config("00112233445566778899aabbccddeeff");
+        let key = derive_key("una contraseña larga", &config).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, derive_key("una contraseña larga", &config).unwrap());
+        assert_ne!(key, derive_key("otra contraseña", &config).unwrap());
+        assert_ne!(
+            key,
+            derive_key(
+                "una contraseña larga",
+                &test_config("ffeeddccbbaa99887766554433221100")
+            )
+            .unwrap()
+        );
+        assert!(derive_key("x", &test_config("abc")).is_err());
+        assert_eq!(PasswordConfig::generate().salt.len(), 32);
+    }
+
+    #[test]
+    fn password_key_replaces_the_previous_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("narrative_assistant.db");
+        Connection::open(&db)
+            .unwrap()
+            .execute_batch("PRAGMA journal_mode = WAL; CREATE TABLE projects (name TEXT);")
+            .unwrap();
+        let config = test_config("00112233445566778899aabbccddeeff");
+        let first = derive_key("primera contraseña", &config).unwrap();
+        let second = derive_key("segunda contraseña", &config).unwrap();
+
+        db_encryption::encrypt_file(&db, &first).unwrap();
+        assert!(db_encryption::key_opens(&db, &first));
+        db_encryption::rekey_file(&db, &first, &second).unwrap();
+        assert!(db_encryption::key_opens(&db, &second));
+        assert!(!db_encryption::key_opens(&db, &first));
+    }
+}
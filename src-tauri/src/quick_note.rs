@@ -0,0 +1,274 @@
+// Notas rápidas de Narrative Assistant
+//
+// Un atajo global (por defecto CmdOrCtrl+Alt+N, configurable en
+// `settings.shortcuts["global.quick_note"]`) abre una ventana pequeña siempre
+// encima (frontend/public/quick-note.html) para apuntar notas de continuidad
+// mientras se lee en otra aplicación. La nota se guarda en el proyecto
+// actual a través del backend (`POST /api/projects/<id>/notes`).
+//
+// El proyecto actual es el de la ventana de proyecto activa o, en la ventana
+// principal, el que el frontend notifica con `set_current_project`. Se fija
+// al abrir la ventana de la nota para que cambiar de ventana no lo altere.
+
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::backend_auth;
+use crate::settings::{Settings, SettingsStore};
+use crate::windows::{self, MAIN_WINDOW};
+
+/// Label de la ventana de notas
+pub const QUICK_NOTE_WINDOW: &str = "quick-note";
+
+/// Página de la ventana de notas (frontend/public)
+const QUICK_NOTE_URL: &str = "quick-note.html";
+
+/// Acción del atajo en `Settings::shortcuts`
+pub const QUICK_NOTE_ACTION: &str = "global.quick_note";
+
+/// Atajo por defecto (sin conflicto con los del menú nativo)
+pub const DEFAULT_QUICK_NOTE_SHORTCUT: &str = "CmdOrCtrl+Alt+N";
+
+/// Longitud máxima de una nota (la misma que valida el backend)
+const MAX_NOTE_CHARS: usize = 4000;
+
+/// Estado de las notas rápidas, registrado con `.manage()`
+pub struct QuickNoteState {
+    /// Proyecto abierto en la ventana principal
+    main_project: Mutex<Option<i64>>,
+    /// Proyecto en el que se guardará la nota de la ventana abierta
+    note_project: Mutex<Option<i64>>,
+    /// Atajo registrado actualmente
+    shortcut: Mutex<Option<Shortcut>>,
+}
+
+impl QuickNoteState {
+    pub fn new() -> Self {
+        Self {
+            main_project: Mutex::new(None),
+            note_project: Mutex::new(None),
+            shortcut: Mutex::new(None),
+        }
+    }
+
+    /// Proyecto abierto en la ventana principal
+    pub(crate) fn main_project(&self) -> Option<i64> {
+        *self.main_project.lock().unwrap()
+    }
+}
+
+/// Proyecto y nombre que muestra la ventana de notas
+#[derive(Serialize, Clone, Debug)]
+pub struct QuickNoteContext {
+    pub project_id: Option<i64>,
+    pub project_name: Option<String>,
+    pub shortcut: String,
+}
+
+/// Si `label` es la ventana de notas (no guarda geometría ni recibe eventos de menú)
+pub fn is_quick_note(label: &str) -> bool {
+    label == QUICK_NOTE_WINDOW
+}
+
+/// Atajo configurado para la nota rápida
+pub(crate) fn shortcut_for(settings: &Settings) -> &str {
+    settings
+        .shortcuts
+        .get(QUICK_NOTE_ACTION)
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_QUICK_NOTE_SHORTCUT)
+}
+
+fn parse_shortcut(accelerator: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(accelerator).map_err(|e| format!("Atajo no valido '{}': {}", accelerator, e))
+}
+
+/// Proyecto de la ventana activa: su id si es una ventana de proyecto, o el de la principal
+fn current_project(app: &AppHandle) -> Option<i64> {
+    let main_project = app.state::<QuickNoteState>().main_project();
+    windows::project_id_from_label(&windows::active_window_label(app)).or(main_project)
+}
+
+/// Abre la ventana de notas para el proyecto actual, o la cierra si ya tiene el foco
+pub(crate) fn toggle_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(QUICK_NOTE_WINDOW) {
+        if window.is_focused().unwrap_or(false) {
+            let _ = window.close();
+        } else {
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+
+    *app.state::<QuickNoteState>().note_project.lock().unwrap() = current_project(app);
+    let result = WebviewWindowBuilder::new(
+        app,
+        QUICK_NOTE_WINDOW,
+        WebviewUrl::App(QUICK_NOTE_URL.into()),
+    )
+    .title("Nota rápida")
+    .inner_size(380.0, 260.0)
+    .min_inner_size(300.0, 200.0)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build();
+    if let Err(e) = result {
+        tracing::warn!(target: "quick_note", "No se pudo abrir la ventana de notas: {}", e);
+    }
+}
+
+/// Registra el atajo global configurado, sustituyendo al anterior
+pub fn apply_shortcut(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut = parse_shortcut(accelerator)?;
+    let state = app.state::<QuickNoteState>();
+    let mut current = state.shortcut.lock().unwrap();
+    if *current == Some(shortcut) {
+        return Ok(());
+    }
+    if let Some(previous) = current.take() {
+        let _ = app.global_shortcut().unregister(previous);
+    }
+    app.global_shortcut()
+        .on_shortcut(shortcut, |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                toggle_window(app);
+            }
+        })
+        .map_err(|e| format!("No se pudo registrar el atajo {}: {}", accelerator, e))?;
+    *current = Some(shortcut);
+    Ok(())
+}
+
+/// Registra el atajo de las preferencias al arrancar
+pub fn setup(app: &AppHandle) {
+    let settings = app.state::<SettingsStore>().get();
+    let accelerator = shortcut_for(&settings);
+    match apply_shortcut(app, accelerator) {
+        Ok(()) => tracing::info!(target: "quick_note", "Atajo de nota rapida: {}", accelerator),
+        Err(e) => tracing::warn!(target: "quick_note", "{}", e),
+    }
+}
+
+/// La ventana principal notifica el proyecto que muestra (`None` fuera de un proyecto)
+#[tauri::command]
+pub fn set_current_project(
+    window: WebviewWindow,
+    state: State<'_, QuickNoteState>,
+    project_id: Option<i64>,
+) {
+    if window.label() == MAIN_WINDOW {
+        *state.main_project.lock().unwrap() = project_id;
+    }
+}
+
+/// Cambia el atajo global de la nota rápida y lo guarda en las preferencias
+#[tauri::command]
+pub async fn set_quick_note_shortcut(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    accelerator: String,
+) -> Result<(), String> {
+    let accelerator = accelerator.trim().to_string();
+    apply_shortcut(&app, &accelerator)?;
+    let updated =
+        store.update(serde_json::json!({ "shortcuts": { QUICK_NOTE_ACTION: accelerator } }))?;
+    let _ = app.emit("shell-settings-changed", &updated);
+    Ok(())
+}
+
+/// Proyecto en el que se guardará la nota de la ventana abierta
+#[tauri::command]
+pub async fn get_quick_note_context(
+    state: State<'_, QuickNoteState>,
+    store: State<'_, SettingsStore>,
+) -> Result<QuickNoteContext, String> {
+    let project_id = *state.note_project.lock().unwrap();
+    let shortcut = shortcut_for(&store.get()).to_string();
+    let project_name = match project_id {
+        Some(id) => fetch_project_name(id).await,
+        None => None,
+    };
+    Ok(QuickNoteContext {
+        project_id,
+        project_name,
+        shortcut,
+    })
+}
+
+async fn fetch_project_name(project_id: i64) -> Option<String> {
+    let body: serde_json::Value = backend_auth::backend_client()
+        .get(backend_auth::backend_url(&format!(
+            "/api/projects/{}",
+            project_id
+        )))
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    body["data"]["name"].as_str().map(String::from)
+}
+
+/// Guarda la nota en el proyecto a través del backend
+#[tauri::command]
+pub async fn save_quick_note(state: State<'_, QuickNoteState>, text: String) -> Result<(), String> {
+    let project_id =
+        (*state.note_project.lock().unwrap()).ok_or("Abra un proyecto para guardar notas")?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("La nota esta vacia".into());
+    }
+    if text.chars().count() > MAX_NOTE_CHARS {
+        return Err(format!("La nota supera los {} caracteres", MAX_NOTE_CHARS));
+    }
+
+    let response = backend_auth::backend_client()
+        .post(backend_auth::backend_url(&format!(
+            "/api/projects/{}/notes",
+            project_id
+        )))
+        .json(&serde_json::json!({ "text": text, "source": "quick_note" }))
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("No se pudo contactar con el servidor: {}", e))?;
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta no valida del servidor: {}", e))?;
+    if body["success"].as_bool() != Some(true) {
+        let error = body["error"]
+            .as_str()
+            .or(body["detail"].as_str())
+            .unwrap_or("Error guardando la nota");
+        return Err(error.to_string());
+    }
+    tracing::info!(target: "quick_note", "Nota guardada en el proyecto {}", project_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcut_defaults_and_can_be_overridden() {
+        let mut settings = Settings::default();
+        assert_eq!(shortcut_for(&settings), DEFAULT_QUICK_NOTE_SHORTCUT);
+        assert!(parse_shortcut(DEFAULT_QUICK_NOTE_SHORTCUT).is_ok());
+
+        settings
+            .shortcuts
+            .insert(QUICK_NOTE_ACTION.into(), "CmdOrCtrl+Shift+J".into());
+        assert_eq!(shortcut_for(&settings), "CmdOrCtrl+Shift+J");
+        assert!(parse_shortcut("CmdOrCtrl+Nada").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+// Estadísticas instantáneas de un manuscrito en Narrative Assistant
+//
+// El diálogo de importación y la barra de estado muestran palabras,
+// caracteres, páginas y tiempo de lectura sin esperar al backend.
+// `quick_stats` los calcula sobre un texto o un archivo: TXT y MD se leen
+// enteros; DOCX y EPUB, con la lectura en streaming de la vista previa
+// (manuscript_preview.rs).
+//
+// - Páginas: página estándar de 1.800 caracteres con espacios (30 líneas de
+//   60), la que usan editoriales y correctores en España.
+// - Lectura: 200 palabras por minuto, la media en español que usa el backend
+//   para el tiempo de lectura de cada capítulo (persistence/chapter.py).
+
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+use tauri::State;
+
+use crate::chapter_split::{self, Splitter};
+use crate::manuscript_preview;
+use crate::settings::SettingsStore;
+
+/// Caracteres con espacios por página estándar
+const CHARS_PER_PAGE: usize = 1800;
+
+/// Palabras por minuto de lectura
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Cifras rápidas de un manuscrito
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct QuickStats {
+    pub words: usize,
+    /// Caracteres con espacios
+    pub characters: usize,
+    pub characters_no_spaces: usize,
+    /// Páginas estándar (al menos 1 si hay texto)
+    pub pages: usize,
+    /// Minutos de lectura (al menos 1 si hay texto)
+    pub reading_minutes: usize,
+}
+
+impl QuickStats {
+    fn new(words: usize, characters: usize, characters_no_spaces: usize) -> Self {
+        let at_least_one = |n: usize| if words > 0 { n.max(1) } else { 0 };
+        Self {
+            words,
+            characters,
+            characters_no_spaces,
+            pages: at_least_one(characters.div_ceil(CHARS_PER_PAGE)),
+            reading_minutes: at_least_one((words + WORDS_PER_MINUTE / 2) / WORDS_PER_MINUTE),
+        }
+    }
+}
+
+/// Cifras de un texto (los saltos de línea no cuentan como caracteres)
+fn text_stats(text: &str) -> QuickStats {
+    let characters = text.chars().filter(|c| *c != '\n' && *c != '\r').count();
+    let characters_no_spaces = text.chars().filter(|c| !c.is_whitespace()).count();
+    QuickStats::new(
+        text.split_whitespace().count(),
+        characters,
+        characters_no_spaces,
+    )
+}
+
+/// Cifras de un archivo `.docx`, `.epub`, `.txt` o `.md`
+fn file_stats(path: &str, splitter: &Splitter) -> Result<QuickStats, String> {
+    let lower = path.to_ascii_lowercase();
+    if lower.ends_with(".txt") || lower.ends_with(".md") {
+        return chapter_split::read_text(Path::new(path)).map(|text| text_stats(&text));
+    }
+    let preview = manuscript_preview::preview_path(path, splitter)?;
+    // La vista previa de DOCX y EPUB no guarda el texto: los espacios se
+    // estiman con las palabras (uno entre cada dos de un mismo párrafo)
+    let spaces = preview.word_count.saturating_sub(preview.paragraph_count);
+    Ok(QuickStats::new(
+        preview.word_count,
+        preview.char_count,
+        preview.char_count.saturating_sub(spaces),
+    ))
+}
+
+/// Palabras, caracteres, páginas y tiempo de lectura de `path` o de `text`
+#[tauri::command]
+pub async fn quick_stats(
+    store: State<'_, SettingsStore>,
+    path: Option<String>,
+    text: Option<String>,
+) -> Result<QuickStats, String> {
+    match (path, text) {
+        (None, Some(text)) => Ok(text_stats(&text)),
+        (Some(path), None) => {
+            let splitter = Splitter::new(&store.get().chapter_split)?;
+            tauri::async_runtime::spawn_blocking(move || {
+                let started = Instant::now();
+                let stats = file_stats(&path, &splitter)?;
+                tracing::debug!(
+                    target: "quick_stats",
+                    "{}: {} palabras en {} ms",
+                    path,
+                    stats.words,
+                    started.elapsed().as_millis()
+                );
+                Ok(stats)
+            })
+            .await
+            .map_err(|e| format!("Error en la tarea de estadisticas: {}", e))?
+        }
+        _ => Err("Indica una ruta o un texto, no ambos".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chapter_split::ChapterSplitSettings;
+
+    #[test]
+    fn text_and_file_stats_agree() {
+        let text = "Ana llegó al faro.\nLuis la esperaba  en la puerta.\n";
+        let stats = text_stats(text);
+        assert_eq!(stats.words, 10);
+        assert_eq!(stats.characters, 49);
+        assert_eq!(stats.characters_no_spaces, 40);
+        assert_eq!(stats.pages, 1);
+        assert_eq!(stats.reading_minutes, 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faro.txt");
+        std::fs::write(&path, text.replace("  ", " ")).unwrap();
+        let splitter = Splitter::new(&ChapterSplitSettings::default()).unwrap();
+        let from_file = file_stats(path.to_str().unwrap(), &splitter).unwrap();
+        assert_eq!(from_file.words, 10);
+        assert_eq!(from_file.characters, 48);
+        assert_eq!(from_file.characters_no_spaces, 40);
+
+        let empty = text_stats("  \n");
+        assert_eq!((empty.pages, empty.reading_minutes), (0, 0));
+        // 90.000 palabras de novela: 7 horas y media
+        assert_eq!(
+            QuickStats::new(90_000, 540_000, 450_000).reading_minutes,
+            450
+        );
+        assert_eq!(QuickStats::new(90_000, 540_000, 450_000).pages, 300);
+    }
+}
@@ -0,0 +1,280 @@
+// Proyectos recientes de Narrative Assistant
+//
+// recent_projects.json (en el directorio de datos de la app) guarda los
+// proyectos abiertos con su título, el manuscrito, la última apertura y si
+// están fijados. Es la única fuente para las listas de recientes: el submenú
+// Archivo > Abrir reciente y la pantalla de inicio del frontend leen de aquí,
+// en vez de deducirlos cada uno por su lado de la fecha de modificación.
+//
+// - El frontend registra cada apertura con `record_recent_project`.
+// - Los fijados no caducan; del resto se guardan los `MAX_RECENT` últimos.
+// - Cada cambio reconstruye el menú y emite `recent-projects-changed` con la
+//   lista nueva, para que las ventanas abiertas la repinten.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::clock::now_secs;
+use crate::{cleanup, menu, settings};
+
+/// Nombre del fichero de recientes
+const RECENT_FILE_NAME: &str = "recent_projects.json";
+
+/// Recientes sin fijar que se conservan
+const MAX_RECENT: usize = 10;
+
+/// Prefijo de los elementos del submenú Abrir reciente (`recent_project_<id>`)
+const MENU_ID_PREFIX: &str = "recent_project_";
+
+/// Proyecto abierto recientemente
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecentProject {
+    pub project_id: i64,
+    pub title: String,
+    /// Manuscrito del proyecto, si lo tiene
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Última apertura (segundos desde epoch)
+    pub last_opened: u64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Fijados primero; dentro de cada grupo, el más reciente primero. Los sin
+/// fijar que sobran se descartan.
+fn normalize(entries: &mut Vec<RecentProject>) {
+    entries.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(b.last_opened.cmp(&a.last_opened))
+    });
+    let mut unpinned = 0;
+    entries.retain(|entry| {
+        if entry.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_RECENT
+    });
+}
+
+/// Recientes en memoria, registrado con `.manage()`
+pub struct RecentProjectsStore {
+    path: PathBuf,
+    entries: Mutex<Vec<RecentProject>>,
+}
+
+impl RecentProjectsStore {
+    /// Carga recent_projects.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(RECENT_FILE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let mut entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Vec<RecentProject>>(&raw).ok())
+            .unwrap_or_default();
+        entries.retain(|entry| entry.project_id > 0);
+        normalize(&mut entries);
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub fn list(&self) -> Vec<RecentProject> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Aplica `change` y guarda; devuelve la lista resultante
+    fn modify(
+        &self,
+        change: impl FnOnce(&mut Vec<RecentProject>),
+    ) -> Result<Vec<RecentProject>, String> {
+        let entries = {
+            let mut entries = self.entries.lock().unwrap();
+            change(&mut entries);
+            normalize(&mut entries);
+            entries.clone()
+        };
+        let json = serde_json::to_vec_pretty(&entries).map_err(|e| e.to_string())?;
+        settings::write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))?;
+        Ok(entries)
+    }
+
+    fn record(
+        &self,
+        project_id: i64,
+        title: String,
+        path: Option<String>,
+    ) -> Result<Vec<RecentProject>, String> {
+        self.modify(|entries| {
+            let pinned = entries
+                .iter()
+                .any(|entry| entry.project_id == project_id && entry.pinned);
+            entries.retain(|entry| entry.project_id != project_id);
+            // Al principio: con la misma marca de tiempo, el último manda
+            entries.insert(
+                0,
+                RecentProject {
+                    project_id,
+                    title,
+                    path,
+                    last_opened: now_secs(),
+                    pinned,
+                },
+            );
+        })
+    }
+
+    fn pin(&self, project_id: i64, pinned: bool) -> Result<Vec<RecentProject>, String> {
+        if !self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|entry| entry.project_id == project_id)
+        {
+            return Err(format!("El proyecto {} no esta en recientes", project_id));
+        }
+        self.modify(|entries| {
+            for entry in entries.iter_mut().filter(|e| e.project_id == project_id) {
+                entry.pinned = pinned;
+            }
+        })
+    }
+
+    fn remove(&self, project_id: i64) -> Result<Vec<RecentProject>, String> {
+        self.modify(|entries| entries.retain(|entry| entry.project_id != project_id))
+    }
+}
+
+/// ID del elemento de menú de un reciente
+pub(crate) fn menu_id(project_id: i64) -> String {
+    format!("{}{}", MENU_ID_PREFIX, project_id)
+}
+
+/// Proyecto de un elemento del submenú Abrir reciente
+pub(crate) fn project_from_menu_id(id: &str) -> Option<i64> {
+    id.strip_prefix(MENU_ID_PREFIX)?
+        .parse()
+        .ok()
+        .filter(|id| *id > 0)
+}
+
+/// Reconstruye el menú y avisa a las ventanas de la lista nueva
+fn publish(app: &AppHandle, entries: &[RecentProject]) {
+    match menu::create_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = app.set_menu(menu) {
+                tracing::warn!(target: "recent_projects", "No se pudo actualizar el menu: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!(target: "recent_projects", "No se pudo crear el menu: {}", e),
+    }
+    let _ = app.emit("recent-projects-changed", entries);
+}
+
+/// Proyectos recientes, los fijados primero
+#[tauri::command]
+pub fn list_recent_projects(store: State<'_, RecentProjectsStore>) -> Vec<RecentProject> {
+    store.list()
+}
+
+/// Registra la apertura de un proyecto
+#[tauri::command]
+pub fn record_recent_project(
+    app: AppHandle,
+    store: State<'_, RecentProjectsStore>,
+    project_id: i64,
+    title: String,
+    path: Option<String>,
+) -> Result<Vec<RecentProject>, String> {
+    if project_id <= 0 {
+        return Err(format!("Proyecto no valido: {}", project_id));
+    }
+    let path = path.filter(|path| !path.trim().is_empty());
+    let entries = store.record(project_id, title.trim().to_string(), path)?;
+    publish(&app, &entries);
+    Ok(entries)
+}
+
+/// Fija (o suelta) un proyecto en recientes
+#[tauri::command]
+pub fn pin_project(
+    app: AppHandle,
+    store: State<'_, RecentProjectsStore>,
+    project_id: i64,
+    pinned: bool,
+) -> Result<Vec<RecentProject>, String> {
+    let entries = store.pin(project_id, pinned)?;
+    publish(&app, &entries);
+    Ok(entries)
+}
+
+/// Quita un proyecto de recientes (p. ej. al borrarlo)
+#[tauri::command]
+pub fn remove_recent(
+    app: AppHandle,
+    store: State<'_, RecentProjectsStore>,
+    project_id: i64,
+) -> Result<Vec<RecentProject>, String> {
+    let entries = store.remove(project_id)?;
+    publish(&app, &entries);
+    Ok(entries)
+}
+
+/// Recientes para el submenú (la app puede no tener el estado en los tests)
+pub(crate) fn for_menu(app: &AppHandle) -> Vec<RecentProject> {
+    app.try_state::<RecentProjectsStore>()
+        .map(|store| store.list())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recents_keep_pins_first_and_survive_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RECENT_FILE_NAME);
+        let store = RecentProjectsStore::load(path.clone());
+        assert!(store.list().is_empty());
+
+        for id in 1..=(MAX_RECENT as i64 + 2) {
+            store.record(id, format!("Novela {}", id), None).unwrap();
+        }
+        store.pin(1, true).unwrap_err();
+        store.pin(5, true).unwrap();
+        // Reabrir un fijado no lo suelta
+        store
+            .record(5, "El faro".into(), Some("/libros/faro.docx".into()))
+            .unwrap();
+        store.remove(12).unwrap();
+
+        let reloaded = RecentProjectsStore::load(path);
+        let list = reloaded.list();
+        assert_eq!(list[0].project_id, 5);
+        assert!(list[0].pinned);
+        assert_eq!(list[0].title, "El faro");
+        assert_eq!(list[0].path.as_deref(), Some("/libros/faro.docx"));
+        assert!(list.iter().all(|entry| entry.project_id != 12));
+        // 12 abiertos, 1 fijado, 1 quitado y los 2 más antiguos descartados
+        assert_eq!(list.len(), MAX_RECENT - 1);
+        assert!(list.iter().all(|entry| entry.project_id > 2));
+    }
+
+    #[test]
+    fn menu_ids_roundtrip() {
+        assert_eq!(project_from_menu_id(&menu_id(42)), Some(42));
+        assert_eq!(project_from_menu_id("recent_project_0"), None);
+        assert_eq!(project_from_menu_id("recent_project_x"), None);
+        assert_eq!(project_from_menu_id("open_project"), None);
+    }
+}
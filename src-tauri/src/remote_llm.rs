@@ -0,0 +1,167 @@
+// Analizador remoto (LLM compatible con la API de OpenAI) en Narrative Assistant
+//
+// Sin GPU local, Ollama es demasiado lento para las sugerencias de estilo.
+// `settings.remote_llm` apunta a un servidor compatible con OpenAI
+// (`POST <base_url>/chat/completions`): un proveedor alojado, vLLM o
+// LM Studio en otra máquina. La clave de API va al almacén del sistema
+// (secrets.rs, `remote_llm_api_key`) y nunca a settings.json.
+//
+// Al lanzar el backend se inyectan:
+// - NA_LLM_BACKEND=openai
+// - NA_OPENAI_BASE_URL, NA_OPENAI_MODEL
+// - NA_OPENAI_API_KEY (desde el almacén, con el resto de secretos)
+//
+// El backend aplica el cambio al reiniciarse.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::secrets;
+use crate::settings::SettingsStore;
+
+/// Secreto con la clave de API del servidor remoto
+pub(crate) const API_KEY_SECRET: &str = "remote_llm_api_key";
+
+/// Preferencias del analizador remoto (`settings.remote_llm`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct RemoteLlmSettings {
+    /// Usar el servidor remoto en lugar de Ollama
+    pub enabled: bool,
+    /// URL base de la API ("https://api.openai.com/v1")
+    pub base_url: Option<String>,
+    /// Modelo ("gpt-4o-mini")
+    pub model: Option<String>,
+}
+
+impl RemoteLlmSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(url) = &self.base_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(format!(
+                    "La URL del analizador remoto debe ser http(s): {}",
+                    url
+                ));
+            }
+        }
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.base_url.as_deref().is_none_or(str::is_empty) {
+            return Err("Falta la URL del analizador remoto".into());
+        }
+        if self.model.as_deref().is_none_or(|m| m.trim().is_empty()) {
+            return Err("Falta el modelo del analizador remoto".into());
+        }
+        Ok(())
+    }
+}
+
+/// Variables de entorno del backend (sin la clave, que va con los secretos)
+#[cfg_attr(debug_assertions, allow(dead_code))] // En desarrollo el backend se lanza a mano
+pub fn backend_env(remote: &RemoteLlmSettings) -> Vec<(&'static str, String)> {
+    match (remote.enabled, &remote.base_url, &remote.model) {
+        (true, Some(url), Some(model)) => vec![
+            ("NA_LLM_BACKEND", "openai".into()),
+            ("NA_OPENAI_BASE_URL", url.trim_end_matches('/').to_string()),
+            ("NA_OPENAI_MODEL", model.trim().to_string()),
+        ],
+        _ => vec![],
+    }
+}
+
+/// Configuración del analizador remoto para la UI
+#[derive(Serialize, Clone, Debug)]
+pub struct RemoteLlmStatus {
+    #[serde(flatten)]
+    pub settings: RemoteLlmSettings,
+    /// Si hay una clave guardada (la clave no sale del almacén)
+    pub has_api_key: bool,
+}
+
+/// Configuración actual y si hay clave guardada
+#[tauri::command]
+pub async fn get_remote_llm(store: State<'_, SettingsStore>) -> Result<RemoteLlmStatus, String> {
+    let settings = store.get().remote_llm;
+    let has_api_key = tauri::async_runtime::spawn_blocking(|| secrets::read(API_KEY_SECRET))
+        .await
+        .map_err(|e| format!("Error en la tarea del almacen de claves: {}", e))??
+        .is_some();
+    Ok(RemoteLlmStatus {
+        settings,
+        has_api_key,
+    })
+}
+
+/// Guarda la configuración del analizador remoto. `api_key`: `Some("")` la
+/// borra, `None` la conserva. El backend aplica el cambio al reiniciarse.
+#[tauri::command]
+pub async fn set_remote_llm(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    remote_llm: RemoteLlmSettings,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    remote_llm.validate()?;
+    if let Some(api_key) = api_key {
+        tauri::async_runtime::spawn_blocking(move || {
+            if api_key.is_empty() {
+                secrets::delete(API_KEY_SECRET)
+            } else {
+                secrets::store(API_KEY_SECRET, &api_key)
+            }
+        })
+        .await
+        .map_err(|e| format!("Error en la tarea del almacen de claves: {}", e))??;
+    }
+    let updated = store.update(serde_json::json!({ "remote_llm": remote_llm }))?;
+    tracing::info!(
+        target: "remote_llm",
+        "Analizador remoto {} ({})",
+        if updated.remote_llm.enabled { "activado" } else { "desactivado" },
+        updated.remote_llm.base_url.as_deref().unwrap_or("-")
+    );
+    let _ = app.emit("shell-settings-changed", &updated);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(enabled: bool, url: Option<&str>, model: Option<&str>) -> RemoteLlmSettings {
+        RemoteLlmSettings {
+            enabled,
+            base_url: url.map(String::from),
+            model: model.map(String::from),
+        }
+    }
+
+    #[test]
+    fn enabled_endpoint_needs_url_and_model() {
+        assert!(RemoteLlmSettings::default().validate().is_ok());
+        // Desactivado se puede guardar a medias
+        assert!(remote(false, Some("https://h/v1"), None).validate().is_ok());
+        assert!(remote(false, Some("ftp://h"), None).validate().is_err());
+        assert!(remote(true, None, Some("m")).validate().is_err());
+        assert!(remote(true, Some("https://h/v1"), Some(" "))
+            .validate()
+            .is_err());
+        assert!(remote(true, Some("https://h/v1"), Some("m"))
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn env_is_only_set_when_enabled() {
+        assert!(backend_env(&remote(false, Some("https://h/v1"), Some("m"))).is_empty());
+        assert_eq!(
+            backend_env(&remote(true, Some("https://h/v1/"), Some("gpt-4o-mini"))),
+            [
+                ("NA_LLM_BACKEND", "openai".to_string()),
+                ("NA_OPENAI_BASE_URL", "https://h/v1".to_string()),
+                ("NA_OPENAI_MODEL", "gpt-4o-mini".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,727 @@
+// Exportación del informe de análisis a PDF en Narrative Assistant
+//
+// `export_report_pdf` compone el informe (resumen, personajes, observaciones
+// y cronología) y lo escribe como PDF paginado con printpdf, sin el backend
+// Python ni el motor del webview (print.rs): funciona aunque el backend esté
+// ocupado analizando o caído. El frontend pasa los datos del informe JSON del
+// backend (`/export/report`, más los eventos de `/timeline`), o la última
+// copia que guardó si ahora no responde.
+//
+// - Portada con el título, la fecha y el índice con el número de página de
+//   cada sección; las secciones también van como marcadores del PDF.
+// - Cada sección empieza en página nueva, con cabecera (proyecto y sección)
+//   y pie "Página N de M".
+// - Fuente DejaVu Serif incrustada (fonts/, licencia en LICENSE-DejaVu.txt),
+//   con los acentos, «», ¿¡ y rayas. Las líneas se cortan midiendo el ancho
+//   real de cada glifo.
+//
+// La composición (`Composer`) genera primero las páginas como operaciones de
+// dibujo y después se escriben, para conocer el total de páginas y las del
+// índice antes de dibujar.
+
+use printpdf::{Color, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point, Rgb};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::{BufWriter, Cursor};
+use tauri::State;
+
+use crate::export_scope::ExportGrants;
+use crate::print;
+
+const FONT_REGULAR: &[u8] = include_bytes!("../fonts/DejaVuSerif.ttf");
+const FONT_BOLD: &[u8] = include_bytes!("../fonts/DejaVuSerif-Bold.ttf");
+
+/// A4 y márgenes (mm, con el origen arriba a la izquierda)
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN_X: f32 = 20.0;
+const CONTENT_TOP: f32 = 30.0;
+const CONTENT_BOTTOM: f32 = 277.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN_X;
+
+/// Puntos tipográficos a mm
+const PT: f32 = 25.4 / 72.0;
+/// Interlineado respecto al cuerpo
+const LEADING: f32 = 1.35;
+
+const BODY_SIZE: f32 = 10.0;
+const SMALL_SIZE: f32 = 8.5;
+const HEADING_SIZE: f32 = 17.0;
+
+/// Informe del backend (`/api/projects/{id}/export/report?format=json`)
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct AnalysisReport {
+    pub project_name: String,
+    pub statistics: ReportStatistics,
+    pub alerts_by_category: BTreeMap<String, u64>,
+    pub alerts_by_severity: BTreeMap<String, u64>,
+    pub top_alerts: Vec<ReportAlert>,
+    pub main_characters: Vec<ReportCharacter>,
+    /// Eventos de `/api/projects/{id}/timeline`
+    pub timeline: Vec<TimelineEvent>,
+    /// Fecha del informe en la zona horaria del usuario (la pone el frontend)
+    pub generated_at: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ReportStatistics {
+    pub word_count: u64,
+    pub chapter_count: u64,
+    pub character_count: u64,
+    pub location_count: u64,
+    pub total_entities: u64,
+    pub total_alerts: u64,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ReportAlert {
+    pub category: String,
+    pub description: String,
+    pub severity: String,
+    pub chapter: Option<u32>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ReportCharacter {
+    pub name: String,
+    pub importance: String,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TimelineEvent {
+    pub chapter: Option<u32>,
+    pub description: String,
+    pub story_date: Option<String>,
+}
+
+pub(crate) fn severity_label(severity: &str) -> &str {
+    match severity {
+        "critical" => "Crítica",
+        "error" => "Error",
+        "warning" => "Aviso",
+        "info" => "Información",
+        other => other,
+    }
+}
+
+fn importance_label(importance: &str) -> &str {
+    match importance {
+        "main" => "Principal",
+        "secondary" => "Secundario",
+        other => other,
+    }
+}
+
+/// "name_consistency" -> "Name consistency"
+fn category_label(category: &str) -> String {
+    let text = category.replace('_', " ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+/// 12345 -> "12.345"
+fn thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push('.');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Anchos de glifo de las fuentes incrustadas
+struct Metrics {
+    regular: ttf_parser::Face<'static>,
+    bold: ttf_parser::Face<'static>,
+}
+
+impl Metrics {
+    fn new() -> Result<Self, String> {
+        let parse =
+            |data| ttf_parser::Face::parse(data, 0).map_err(|e| format!("Fuente no valida: {}", e));
+        Ok(Self {
+            regular: parse(FONT_REGULAR)?,
+            bold: parse(FONT_BOLD)?,
+        })
+    }
+
+    /// Ancho en mm de `text` a `size` puntos
+    fn width(&self, text: &str, size: f32, bold: bool) -> f32 {
+        let face = if bold { &self.bold } else { &self.regular };
+        let units: u32 = text
+            .chars()
+            .map(|c| {
+                face.glyph_index(c)
+                    .and_then(|g| face.glyph_hor_advance(g))
+                    .unwrap_or(face.units_per_em() / 2) as u32
+            })
+            .sum();
+        units as f32 / face.units_per_em() as f32 * size * PT
+    }
+}
+
+/// Corta `text` en líneas de como mucho `max_width` (las palabras más largas
+/// que la línea se parten por caracteres)
+fn wrap(text: &str, max_width: f32, width: impl Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if width(&candidate) <= max_width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            line.push(c);
+            if width(&line) > max_width && line.chars().count() > 1 {
+                line.pop();
+                lines.push(std::mem::replace(&mut line, c.to_string()));
+            }
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Recorta `text` con "…" para que quepa en `max_width`
+fn truncate(text: &str, max_width: f32, width: impl Fn(&str) -> f32) -> String {
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+    let mut out: String = text.to_string();
+    while !out.is_empty() && width(&format!("{}…", out)) > max_width {
+        out.pop();
+    }
+    format!("{}…", out.trim_end())
+}
+
+/// Operación de dibujo (y = línea base en mm desde arriba)
+#[derive(Debug, Clone, PartialEq)]
+enum Op {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        bold: bool,
+        text: String,
+    },
+    Rule {
+        y: f32,
+    },
+}
+
+#[derive(Default)]
+struct Page {
+    /// Sección para la cabecera (`None` en la portada)
+    section: Option<String>,
+    ops: Vec<Op>,
+}
+
+/// Composición del informe en páginas
+struct Composer<'a> {
+    metrics: &'a Metrics,
+    pages: Vec<Page>,
+    /// Línea base de la última línea escrita
+    y: f32,
+    /// Secciones y su página (0-based) para el índice
+    toc: Vec<(String, usize)>,
+}
+
+impl<'a> Composer<'a> {
+    /// Empieza con la portada vacía: se rellena con `cover` al final
+    fn new(metrics: &'a Metrics) -> Self {
+        Self {
+            metrics,
+            pages: vec![Page::default()],
+            y: CONTENT_TOP,
+            toc: Vec::new(),
+        }
+    }
+
+    fn page(&mut self) -> &mut Page {
+        self.pages.last_mut().expect("siempre hay una pagina")
+    }
+
+    fn new_page(&mut self) {
+        let section = self.page().section.clone();
+        self.pages.push(Page {
+            section,
+            ops: Vec::new(),
+        });
+        self.y = CONTENT_TOP;
+    }
+
+    /// Baja una línea de `size` puntos, pasando de página si no cabe
+    fn advance(&mut self, size: f32) -> f32 {
+        let height = size * PT * LEADING;
+        if self.y + height > CONTENT_BOTTOM {
+            self.new_page();
+        }
+        self.y += height;
+        self.y
+    }
+
+    fn gap(&mut self, mm: f32) {
+        self.y += mm;
+    }
+
+    fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: String) {
+        self.page().ops.push(Op::Text {
+            x,
+            y,
+            size,
+            bold,
+            text,
+        });
+    }
+
+    /// Línea bajo la última línea escrita
+    fn rule(&mut self) {
+        let y = self.y + 1.5;
+        self.page().ops.push(Op::Rule { y });
+        self.gap(2.0);
+    }
+
+    fn section(&mut self, title: &str) {
+        self.new_page();
+        self.page().section = Some(title.to_string());
+        self.toc.push((title.to_string(), self.pages.len() - 1));
+        let y = self.advance(HEADING_SIZE);
+        self.text(MARGIN_X, y, HEADING_SIZE, true, title.to_string());
+        self.gap(4.0);
+    }
+
+    fn subheading(&mut self, title: &str) {
+        // Sin título huérfano al pie de la página
+        if self.y + 3.0 * BODY_SIZE * PT * LEADING > CONTENT_BOTTOM {
+            self.new_page();
+        }
+        self.gap(3.0);
+        let y = self.advance(BODY_SIZE + 2.0);
+        self.text(MARGIN_X, y, BODY_SIZE + 2.0, true, title.to_string());
+        self.gap(1.0);
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, bold: bool, indent: f32) {
+        let metrics = self.metrics;
+        for line in wrap(text, CONTENT_WIDTH - indent, |s| {
+            metrics.width(s, size, bold)
+        }) {
+            let y = self.advance(size);
+            self.text(MARGIN_X + indent, y, size, bold, line);
+        }
+    }
+
+    /// Fila de una línea con columnas `(texto, x relativa, ancho)`
+    fn row(&mut self, cells: &[(&str, f32, f32)], bold: bool) {
+        let y = self.advance(BODY_SIZE);
+        let metrics = self.metrics;
+        for (text, x, width) in cells {
+            let text = truncate(text, *width, |s| metrics.width(s, BODY_SIZE, bold));
+            self.text(MARGIN_X + x, y, BODY_SIZE, bold, text);
+        }
+    }
+
+    /// Par etiqueta: valor alineado
+    fn stat(&mut self, label: &str, value: String) {
+        self.row(&[(label, 4.0, 70.0), (&value, 80.0, 90.0)], false);
+    }
+
+    /// Portada con el índice, cuando ya se conocen las páginas
+    fn cover(&mut self, report: &AnalysisReport) {
+        let metrics = self.metrics;
+        let mut ops = vec![
+            Op::Text {
+                x: MARGIN_X,
+                y: 90.0,
+                size: 26.0,
+                bold: true,
+                text: "Informe de análisis".into(),
+            },
+            Op::Rule { y: 96.0 },
+        ];
+        let mut y = 108.0;
+        for line in wrap(&report.project_name, CONTENT_WIDTH, |s| {
+            metrics.width(s, 16.0, false)
+        }) {
+            ops.push(Op::Text {
+                x: MARGIN_X,
+                y,
+                size: 16.0,
+                bold: false,
+                text: line,
+            });
+            y += 16.0 * PT * LEADING;
+        }
+        if let Some(date) = &report.generated_at {
+            ops.push(Op::Text {
+                x: MARGIN_X,
+                y: y + 2.0,
+                size: BODY_SIZE,
+                bold: false,
+                text: date.clone(),
+            });
+        }
+
+        let mut y = 160.0;
+        ops.push(Op::Text {
+            x: MARGIN_X,
+            y,
+            size: BODY_SIZE + 2.0,
+            bold: true,
+            text: "Índice".into(),
+        });
+        for (title, page) in &self.toc {
+            y += BODY_SIZE * PT * LEADING * 1.4;
+            let number = (page + 1).to_string();
+            let number_width = metrics.width(&number, BODY_SIZE, false);
+            // Puntos guía entre el título y el número
+            let title_width = metrics.width(title, BODY_SIZE, false);
+            let dot = metrics.width(".", BODY_SIZE, false);
+            let dots = ((CONTENT_WIDTH - title_width - number_width - 4.0) / dot).max(0.0) as usize;
+            ops.push(Op::Text {
+                x: MARGIN_X,
+                y,
+                size: BODY_SIZE,
+                bold: false,
+                text: format!("{} {}", title, ".".repeat(dots)),
+            });
+            ops.push(Op::Text {
+                x: MARGIN_X + CONTENT_WIDTH - number_width,
+                y,
+                size: BODY_SIZE,
+                bold: false,
+                text: number,
+            });
+        }
+        self.pages[0].ops = ops;
+    }
+}
+
+/// Compone el informe completo
+fn compose(report: &AnalysisReport, metrics: &Metrics) -> (Vec<Page>, Vec<(String, usize)>) {
+    let mut c = Composer::new(metrics);
+    let stats = &report.statistics;
+
+    c.section("Resumen");
+    c.stat("Palabras", thousands(stats.word_count));
+    c.stat("Capítulos", thousands(stats.chapter_count));
+    c.stat("Personajes", thousands(stats.character_count));
+    c.stat("Localizaciones", thousands(stats.location_count));
+    c.stat("Entidades", thousands(stats.total_entities));
+    c.stat("Alertas abiertas", thousands(stats.total_alerts));
+    if report.alerts_by_severity.values().any(|&n| n > 0) {
+        c.subheading("Alertas por severidad");
+        for severity in ["critical", "error", "warning", "info"] {
+            if let Some(&n) = report.alerts_by_severity.get(severity).filter(|&&n| n > 0) {
+                c.stat(severity_label(severity), thousands(n));
+            }
+        }
+    }
+    if !report.alerts_by_category.is_empty() {
+        c.subheading("Alertas por categoría");
+        let mut categories: Vec<_> = report.alerts_by_category.iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (category, &n) in categories {
+            c.stat(&category_label(category), thousands(n));
+        }
+    }
+
+    c.section("Personajes principales");
+    if report.main_characters.is_empty() {
+        c.paragraph(
+            "No se han detectado personajes principales.",
+            BODY_SIZE,
+            false,
+            0.0,
+        );
+    } else {
+        c.row(
+            &[("Nombre", 0.0, 110.0), ("Importancia", 120.0, 50.0)],
+            true,
+        );
+        c.rule();
+        for character in &report.main_characters {
+            c.row(
+                &[
+                    (&character.name, 0.0, 110.0),
+                    (importance_label(&character.importance), 120.0, 50.0),
+                ],
+                false,
+            );
+            if !character.aliases.is_empty() {
+                c.paragraph(
+                    &format!("También: {}", character.aliases.join(", ")),
+                    SMALL_SIZE,
+                    false,
+                    4.0,
+                );
+            }
+        }
+    }
+
+    c.section("Observaciones principales");
+    if report.top_alerts.is_empty() {
+        c.paragraph("No hay alertas abiertas.", BODY_SIZE, false, 0.0);
+    }
+    for alert in &report.top_alerts {
+        let chapter = alert
+            .chapter
+            .map(|n| format!(" · cap. {}", n))
+            .unwrap_or_default();
+        c.gap(1.5);
+        c.paragraph(
+            &format!(
+                "{} · {}{}",
+                severity_label(&alert.severity),
+                category_label(&alert.category),
+                chapter
+            ),
+            BODY_SIZE,
+            true,
+            0.0,
+        );
+        c.paragraph(&alert.description, BODY_SIZE, false, 4.0);
+    }
+
+    c.section("Cronología");
+    if report.timeline.is_empty() {
+        c.paragraph(
+            "No hay línea temporal: el análisis aún no la ha construido.",
+            BODY_SIZE,
+            false,
+            0.0,
+        );
+    }
+    for event in &report.timeline {
+        let when = match (&event.story_date, event.chapter) {
+            (Some(date), Some(n)) => format!("{} · cap. {}", date, n),
+            (Some(date), None) => date.clone(),
+            (None, Some(n)) => format!("Cap. {}", n),
+            (None, None) => "Sin fecha".into(),
+        };
+        c.gap(1.0);
+        c.paragraph(&when, SMALL_SIZE, true, 0.0);
+        c.paragraph(&event.description, BODY_SIZE, false, 4.0);
+    }
+
+    c.cover(report);
+    (c.pages, c.toc)
+}
+
+fn draw(layer: &PdfLayerReference, op: &Op, regular: &IndirectFontRef, bold: &IndirectFontRef) {
+    match op {
+        Op::Text {
+            x,
+            y,
+            size,
+            bold: is_bold,
+            text,
+        } => {
+            let font = if *is_bold { bold } else { regular };
+            layer.use_text(text.as_str(), *size, Mm(*x), Mm(PAGE_HEIGHT - y), font);
+        }
+        Op::Rule { y } => {
+            let y = Mm(PAGE_HEIGHT - y);
+            layer.add_line(Line {
+                points: vec![
+                    (Point::new(Mm(MARGIN_X), y), false),
+                    (Point::new(Mm(PAGE_WIDTH - MARGIN_X), y), false),
+                ],
+                is_closed: false,
+            });
+        }
+    }
+}
+
+/// PDF del informe en memoria
+fn render(report: &AnalysisReport) -> Result<Vec<u8>, String> {
+    let metrics = Metrics::new()?;
+    let (pages, toc) = compose(report, &metrics);
+    let title = format!("Informe de análisis: {}", report.project_name);
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Informe");
+    let pdf_error = |e: printpdf::Error| format!("Error generando el PDF: {}", e);
+    let regular = doc
+        .add_external_font(Cursor::new(FONT_REGULAR))
+        .map_err(pdf_error)?;
+    let bold = doc
+        .add_external_font(Cursor::new(FONT_BOLD))
+        .map_err(pdf_error)?;
+    let gray = Color::Rgb(Rgb::new(0.45, 0.45, 0.45, None));
+    let black = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
+
+    let total = pages.len();
+    let mut indices = vec![(first_page, first_layer)];
+    indices.extend((1..total).map(|_| doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Informe")));
+    for (number, (page, (page_index, layer_index))) in pages.iter().zip(&indices).enumerate() {
+        let layer = doc.get_page(*page_index).get_layer(*layer_index);
+        layer.set_outline_thickness(0.5);
+        if let Some(section) = &page.section {
+            // Cabecera: proyecto a la izquierda, sección a la derecha
+            layer.set_fill_color(gray.clone());
+            let header = truncate(&report.project_name, CONTENT_WIDTH * 0.6, |s| {
+                metrics.width(s, SMALL_SIZE, false)
+            });
+            draw(
+                &layer,
+                &Op::Text {
+                    x: MARGIN_X,
+                    y: 15.0,
+                    size: SMALL_SIZE,
+                    bold: false,
+                    text: header,
+                },
+                &regular,
+                &bold,
+            );
+            let section_x = PAGE_WIDTH - MARGIN_X - metrics.width(section, SMALL_SIZE, false);
+            draw(
+                &layer,
+                &Op::Text {
+                    x: section_x,
+                    y: 15.0,
+                    size: SMALL_SIZE,
+                    bold: false,
+                    text: section.clone(),
+                },
+                &regular,
+                &bold,
+            );
+            draw(&layer, &Op::Rule { y: 17.5 }, &regular, &bold);
+            let footer = format!("Página {} de {}", number + 1, total);
+            let footer_x = (PAGE_WIDTH - metrics.width(&footer, SMALL_SIZE, false)) / 2.0;
+            draw(
+                &layer,
+                &Op::Text {
+                    x: footer_x,
+                    y: 287.0,
+                    size: SMALL_SIZE,
+                    bold: false,
+                    text: footer,
+                },
+                &regular,
+                &bold,
+            );
+            layer.set_fill_color(black.clone());
+        }
+        for op in &page.ops {
+            draw(&layer, op, &regular, &bold);
+        }
+    }
+    for (title, page) in toc {
+        doc.add_bookmark(title, indices[page].0);
+    }
+    let mut bytes = BufWriter::new(Vec::new());
+    doc.save(&mut bytes).map_err(pdf_error)?;
+    bytes
+        .into_inner()
+        .map_err(|e| format!("Error generando el PDF: {}", e))
+}
+
+/// Escribe el informe en `output_path` y devuelve la ruta final
+#[tauri::command]
+pub async fn export_report_pdf(
+    grants: State<'_, ExportGrants>,
+    report: AnalysisReport,
+    output_path: String,
+) -> Result<String, String> {
+    let path = print::resolve_output_path(&output_path)?;
+    grants.authorize(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let bytes = render(&report)?;
+        std::fs::write(&path, bytes)
+            .map_err(|e| format!("No se pudo guardar {}: {}", path.display(), e))?;
+        tracing::info!(target: "report_pdf", "Informe exportado a {}", path.display());
+        Ok(path.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de exportacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_wrap_by_measured_width() {
+        // Un "carácter" = 1 mm
+        let width = |s: &str| s.chars().count() as f32;
+        assert_eq!(
+            wrap("Ana llegó al   faro de noche", 10.0, width),
+            ["Ana llegó", "al faro de", "noche"]
+        );
+        assert_eq!(
+            wrap("supercalifragilístico", 8.0, width),
+            ["supercal", "ifragilí", "stico"]
+        );
+        assert_eq!(truncate("Personaje secundario", 10.0, width), "Personaje…");
+        assert_eq!(thousands(1234567), "1.234.567");
+        assert_eq!(category_label("name_consistency"), "Name consistency");
+    }
+
+    #[test]
+    fn sections_start_on_new_pages_listed_in_the_index() {
+        let report: AnalysisReport = serde_json::from_value(serde_json::json!({
+            "project_name": "El faro",
+            "statistics": { "word_count": 80000, "chapter_count": 12 },
+            "alerts_by_severity": { "critical": 1, "warning": 0 },
+            "top_alerts": (0..120).map(|i| serde_json::json!({
+                "category": "timeline", "severity": "critical", "chapter": 3,
+                "description": format!("Ana tiene {} años en el capítulo 3 y {} en el 5.", i, i + 2)
+            })).collect::<Vec<_>>(),
+            "timeline": [{ "chapter": 1, "description": "Ana llega al faro", "story_date": "1923-05-01" }],
+        }))
+        .unwrap();
+        let metrics = Metrics::new().unwrap();
+        let (pages, toc) = compose(&report, &metrics);
+
+        let sections: Vec<&str> = toc.iter().map(|(title, _)| title.as_str()).collect();
+        assert_eq!(
+            sections,
+            [
+                "Resumen",
+                "Personajes principales",
+                "Observaciones principales",
+                "Cronología"
+            ]
+        );
+        // 120 observaciones no caben en una página: la cronología va después
+        assert!(toc[3].1 > toc[2].1 + 1);
+        assert_eq!(toc[3].1, pages.len() - 1);
+        assert!(pages[0].section.is_none());
+        assert_eq!(
+            pages[toc[2].1 + 1].section.as_deref(),
+            Some("Observaciones principales")
+        );
+        for op in pages.iter().flat_map(|p| &p.ops) {
+            if let Op::Text { y, .. } = op {
+                assert!(*y <= CONTENT_BOTTOM);
+            }
+        }
+
+        let pdf = render(&report).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+    }
+}
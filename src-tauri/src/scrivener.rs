@@ -0,0 +1,549 @@
+// Importación de proyectos de Scrivener en Narrative Assistant
+//
+// Buena parte de los autores escribe en Scrivener, que no guarda un
+// manuscrito sino un paquete `.scriv`: el binder (`<Nombre>.scrivx`, XML) con
+// el árbol de carpetas y documentos, y un RTF por documento
+// (`Files/Data/<UUID>/content.rtf` en Scrivener 3, `Files/Docs/<ID>.rtf` en
+// Scrivener 2). `import_scrivener` aplana la carpeta Manuscrito (Draft) en un
+// `.md` con un `# Título` por capítulo, en `<datos>/documents`, que se importa
+// como cualquier borrador Markdown (chapter_split.rs).
+//
+// - Capítulos: una carpeta cuyos hijos son todos carpetas es una parte y se
+//   recorre; cualquier otra carpeta es un capítulo con el texto de todos sus
+//   documentos, separados como escenas (`* * *`). Un documento suelto al
+//   nivel de los capítulos es un capítulo por sí mismo.
+// - Se respeta "Incluir en compilación"; los excluidos y los documentos sin
+//   RTF se devuelven en `skipped`.
+// - El RTF se reduce a texto (`rtf_to_text`): párrafos, tabuladores,
+//   caracteres Unicode y de la página de códigos; se descartan tablas de
+//   fuentes, estilos, imágenes, notas y comentarios.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::drop_import;
+use crate::manuscript_preview::{attribute, push_reference};
+use crate::settings::SettingsStore;
+
+/// Separador de escenas dentro de un capítulo
+const SCENE_SEPARATOR: &str = "* * *";
+
+/// Destinos RTF cuyo contenido no es texto del manuscrito
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "header",
+    "footer",
+    "footnote",
+    "annotation",
+    "listtable",
+    "listoverridetable",
+    "fldinst",
+    "object",
+    "themedata",
+    "latentstyles",
+    "datastore",
+];
+
+/// Resultado de `import_scrivener`
+#[derive(Serialize, Clone, Debug)]
+pub struct ScrivenerImport {
+    /// `.md` aplanado, en el directorio de documentos
+    pub path: String,
+    /// Nombre del proyecto de Scrivener
+    pub title: String,
+    pub chapter_count: usize,
+    pub word_count: usize,
+    /// Documentos no incluidos (fuera de compilación o sin texto)
+    pub skipped: Vec<String>,
+}
+
+/// Elemento del binder
+#[derive(Debug, Default)]
+struct BinderItem {
+    uuid: Option<String>,
+    id: Option<String>,
+    kind: String,
+    title: String,
+    include: bool,
+    children: Vec<BinderItem>,
+}
+
+impl BinderItem {
+    fn is_folder(&self) -> bool {
+        self.kind.ends_with("Folder")
+    }
+}
+
+/// Lee el binder y devuelve los elementos de primer nivel
+fn parse_binder(xml: &str) -> Result<Vec<BinderItem>, String> {
+    let mut reader = Reader::from_str(xml);
+    // Pila de elementos abiertos (raíz ficticia) y de nombres de etiqueta
+    let mut items = vec![BinderItem::default()];
+    let mut tags: Vec<Vec<u8>> = Vec::new();
+    let mut text = String::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Binder de Scrivener no valido: {}", e))?;
+        match event {
+            Event::Start(e) => {
+                let name = e.local_name().as_ref().to_vec();
+                if name == b"BinderItem" {
+                    items.push(BinderItem {
+                        uuid: attribute(&e, b"UUID"),
+                        id: attribute(&e, b"ID"),
+                        kind: attribute(&e, b"Type").unwrap_or_default(),
+                        include: true,
+                        ..Default::default()
+                    });
+                }
+                tags.push(name);
+                text.clear();
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"BinderItem" => {
+                let item = BinderItem {
+                    uuid: attribute(&e, b"UUID"),
+                    id: attribute(&e, b"ID"),
+                    kind: attribute(&e, b"Type").unwrap_or_default(),
+                    include: true,
+                    ..Default::default()
+                };
+                items.last_mut().expect("raiz").children.push(item);
+            }
+            Event::Text(e) => text.push_str(&e.decode().map_err(|e| e.to_string())?),
+            Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e)),
+            Event::GeneralRef(e) => push_reference(&mut text, &e),
+            Event::End(_) => {
+                let name = tags.pop().unwrap_or_default();
+                let parent = tags.last().map(Vec::as_slice);
+                match name.as_slice() {
+                    b"BinderItem" if items.len() > 1 => {
+                        let item = items.pop().expect("elemento abierto");
+                        items.last_mut().expect("raiz").children.push(item);
+                    }
+                    b"Title" if parent == Some(b"BinderItem") => {
+                        items.last_mut().expect("raiz").title = text.trim().to_string();
+                    }
+                    b"IncludeInCompile" => {
+                        items.last_mut().expect("raiz").include = text.trim() == "Yes";
+                    }
+                    _ => {}
+                }
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(items.swap_remove(0).children)
+}
+
+/// Estado de un grupo `{...}` del RTF
+#[derive(Clone, Copy)]
+struct Group {
+    skip: bool,
+    /// Caracteres de sustitución tras cada `\uN` (`\ucN`)
+    unicode_skip: usize,
+}
+
+/// Texto de un documento RTF, un párrafo por línea
+pub(crate) fn rtf_to_text(rtf: &str) -> String {
+    let bytes = rtf.as_bytes();
+    let mut out = String::new();
+    let mut stack = vec![Group {
+        skip: false,
+        unicode_skip: 1,
+    }];
+    // Caracteres de sustitución que quedan por saltar tras un `\uN`
+    let mut pending_skip = 0usize;
+    // Al abrir grupo: el primer control decide si es un destino a saltar
+    let mut group_start = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let state = *stack.last().expect("grupo raiz");
+        match bytes[i] {
+            b'{' => {
+                stack.push(state);
+                group_start = true;
+                i += 1;
+                continue;
+            }
+            b'}' => {
+                if stack.len() > 1 {
+                    stack.pop();
+                }
+                pending_skip = 0;
+                i += 1;
+            }
+            b'\\' => {
+                let start = i + 1;
+                let Some(&next) = bytes.get(start) else { break };
+                if next.is_ascii_alphabetic() {
+                    let mut end = start;
+                    while end < bytes.len() && bytes[end].is_ascii_alphabetic() {
+                        end += 1;
+                    }
+                    let word = &rtf[start..end];
+                    let mut param_end = end;
+                    if bytes.get(param_end) == Some(&b'-') {
+                        param_end += 1;
+                    }
+                    while param_end < bytes.len() && bytes[param_end].is_ascii_digit() {
+                        param_end += 1;
+                    }
+                    let param: Option<i32> = rtf[end..param_end].parse().ok();
+                    i = param_end;
+                    if bytes.get(i) == Some(&b' ') {
+                        i += 1;
+                    }
+                    if group_start && SKIPPED_DESTINATIONS.contains(&word) {
+                        stack.last_mut().expect("grupo").skip = true;
+                    }
+                    group_start = false;
+                    if state.skip {
+                        continue;
+                    }
+                    match word {
+                        "par" | "line" | "sect" | "page" => out.push('\n'),
+                        "tab" => out.push('\t'),
+                        "emdash" => out.push('—'),
+                        "endash" => out.push('–'),
+                        "lquote" => out.push('‘'),
+                        "rquote" => out.push('’'),
+                        "ldblquote" => out.push('“'),
+                        "rdblquote" => out.push('”'),
+                        "bullet" => out.push('•'),
+                        "uc" => {
+                            stack.last_mut().expect("grupo").unicode_skip =
+                                param.unwrap_or(1).max(0) as usize
+                        }
+                        "u" => {
+                            let code = param.unwrap_or(0);
+                            let code = if code < 0 { code + 65536 } else { code };
+                            if let Some(c) = char::from_u32(code as u32) {
+                                out.push(c);
+                            }
+                            pending_skip = state.unicode_skip;
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                let was_group_start = group_start;
+                group_start = false;
+                match next {
+                    // `{\*\destino ...}`: destino opcional que no se entiende
+                    b'*' if was_group_start => {
+                        stack.last_mut().expect("grupo").skip = true;
+                        i = start + 1;
+                        continue;
+                    }
+                    b'\'' => {
+                        let hex = rtf.get(start + 1..start + 3).unwrap_or("");
+                        i = start + 3;
+                        if state.skip {
+                            continue;
+                        }
+                        if pending_skip > 0 {
+                            pending_skip -= 1;
+                            continue;
+                        }
+                        if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                            let bytes = [byte];
+                            let (decoded, _, _) = encoding_rs::WINDOWS_1252.decode(&bytes);
+                            out.push_str(&decoded);
+                        }
+                        continue;
+                    }
+                    _ if state.skip => {}
+                    b'~' => out.push(' '),
+                    b'_' => out.push('-'),
+                    b'\\' | b'{' | b'}' if pending_skip > 0 => pending_skip -= 1,
+                    b'\\' | b'{' | b'}' => out.push(next as char),
+                    // `\` + salto de línea equivale a `\par`
+                    b'\n' | b'\r' => out.push('\n'),
+                    _ => {}
+                }
+                i = start + 1;
+                continue;
+            }
+            b'\r' | b'\n' => i += 1,
+            _ => {
+                // Carácter literal (UTF-8 en el `&str`, aunque el RTF sea ASCII)
+                let c = rtf[i..].chars().next().expect("caracter");
+                i += c.len_utf8();
+                if state.skip {
+                    continue;
+                }
+                if pending_skip > 0 {
+                    pending_skip -= 1;
+                    continue;
+                }
+                out.push(c);
+            }
+        }
+        group_start = false;
+    }
+    out.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Carpeta del paquete y ruta del `.scrivx`, a partir del `.scriv` o del `.scrivx`
+fn locate(path: &Path) -> Result<(PathBuf, PathBuf), String> {
+    if path.is_dir() {
+        let scrivx = fs::read_dir(path)
+            .map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|p| {
+                p.extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("scrivx"))
+            })
+            .ok_or_else(|| format!("{} no contiene un archivo .scrivx", path.display()))?;
+        return Ok((path.to_path_buf(), scrivx));
+    }
+    let is_scrivx = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("scrivx"));
+    match path.parent() {
+        Some(root) if is_scrivx => Ok((root.to_path_buf(), path.to_path_buf())),
+        _ => Err("Elige la carpeta .scriv o el archivo .scrivx del proyecto".into()),
+    }
+}
+
+/// Texto de un documento del binder, si tiene RTF
+fn document_text(root: &Path, item: &BinderItem) -> Option<String> {
+    let candidates = [
+        item.uuid.as_ref().map(|uuid| {
+            root.join("Files")
+                .join("Data")
+                .join(uuid)
+                .join("content.rtf")
+        }),
+        item.id
+            .as_ref()
+            .map(|id| root.join("Files").join("Docs").join(format!("{}.rtf", id))),
+    ];
+    let path = candidates.into_iter().flatten().find(|p| p.is_file())?;
+    let bytes = fs::read(path).ok()?;
+    // El RTF es ASCII; los bytes sueltos se leen como Windows-1252
+    let rtf = match String::from_utf8(bytes) {
+        Ok(rtf) => rtf,
+        Err(e) => encoding_rs::WINDOWS_1252
+            .decode(e.as_bytes())
+            .0
+            .into_owned(),
+    };
+    Some(rtf_to_text(&rtf)).filter(|text| !text.is_empty())
+}
+
+struct Flattener<'a> {
+    root: &'a Path,
+    chapters: Vec<(String, Vec<String>)>,
+    skipped: Vec<String>,
+}
+
+impl Flattener<'_> {
+    /// Textos (escenas) de `item` y sus descendientes, en orden
+    fn scenes(&mut self, item: &BinderItem, scenes: &mut Vec<String>) {
+        if !item.include {
+            self.skipped.push(item.title.clone());
+            return;
+        }
+        if !item.is_folder() {
+            match document_text(self.root, item) {
+                Some(text) => scenes.push(text),
+                None if item.children.is_empty() => self.skipped.push(item.title.clone()),
+                None => {}
+            }
+        }
+        for child in &item.children {
+            self.scenes(child, scenes);
+        }
+    }
+
+    /// Recorre un nivel de capítulos (la carpeta Manuscrito o una parte)
+    fn level(&mut self, items: &[BinderItem]) {
+        for item in items {
+            if !item.include {
+                self.skipped.push(item.title.clone());
+                continue;
+            }
+            let is_part = item.is_folder()
+                && !item.children.is_empty()
+                && item.children.iter().all(BinderItem::is_folder);
+            if is_part {
+                self.level(&item.children);
+                continue;
+            }
+            let mut scenes = Vec::new();
+            self.scenes(item, &mut scenes);
+            if !scenes.is_empty() {
+                self.chapters.push((item.title.clone(), scenes));
+            }
+        }
+    }
+}
+
+/// Markdown del manuscrito y documentos omitidos
+fn flatten(root: &Path, binder: &[BinderItem]) -> Result<(String, usize, Vec<String>), String> {
+    let draft = binder
+        .iter()
+        .find(|item| item.kind == "DraftFolder")
+        .ok_or("El proyecto no tiene carpeta Manuscrito (Draft)")?;
+    let mut flattener = Flattener {
+        root,
+        chapters: Vec::new(),
+        skipped: Vec::new(),
+    };
+    flattener.level(&draft.children);
+    if flattener.chapters.is_empty() {
+        return Err("La carpeta Manuscrito no tiene texto que importar".into());
+    }
+    let separator = format!("\n\n{}\n\n", SCENE_SEPARATOR);
+    let markdown = flattener
+        .chapters
+        .iter()
+        .map(|(title, scenes)| {
+            let body = scenes
+                .iter()
+                .map(|scene| scene.replace('\n', "\n\n"))
+                .collect::<Vec<_>>()
+                .join(&separator);
+            let title = if title.is_empty() {
+                "Sin titulo"
+            } else {
+                title
+            };
+            format!("# {}\n\n{}", title, body)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok((markdown + "\n", flattener.chapters.len(), flattener.skipped))
+}
+
+/// Aplana la carpeta Manuscrito de un proyecto de Scrivener en un `.md` para importarlo
+#[tauri::command]
+pub async fn import_scrivener(
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<ScrivenerImport, String> {
+    let documents = drop_import::documents_dir(&store.get())
+        .ok_or_else(|| "No se encontro el directorio de datos".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let (root, scrivx) = locate(Path::new(&path))?;
+        let xml = fs::read_to_string(&scrivx)
+            .map_err(|e| format!("No se pudo leer {}: {}", scrivx.display(), e))?;
+        let binder = parse_binder(&xml)?;
+        let (markdown, chapter_count, skipped) = flatten(&root, &binder)?;
+
+        let title = scrivx
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "manuscrito".into());
+        fs::create_dir_all(&documents)
+            .map_err(|e| format!("No se pudo crear {}: {}", documents.display(), e))?;
+        let target = documents.join(format!("{}_{}.md", uuid::Uuid::new_v4().simple(), title));
+        fs::write(&target, &markdown)
+            .map_err(|e| format!("No se pudo guardar el manuscrito: {}", e))?;
+
+        tracing::info!(
+            target: "scrivener",
+            "{}: {} capitulos ({} documentos omitidos) en {}",
+            path,
+            chapter_count,
+            skipped.len(),
+            target.display()
+        );
+        Ok(ScrivenerImport {
+            path: target.to_string_lossy().into_owned(),
+            title,
+            chapter_count,
+            word_count: markdown.split_whitespace().count(),
+            skipped,
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de importacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rtf_is_reduced_to_paragraphs() {
+        let rtf = r"{\rtf1\ansi\ansicpg1252\uc1{\fonttbl{\f0 Palatino;}}{\colortbl;\red0\green0\blue0;}
+{\*\expandedcolortbl;;}\pard\f0 \'bfD\'f3nde est\'e1s?\par
+Ana dijo \'97no\'97 y sali\u243 ? al faro.{\footnote nota}\line
+\tab Fin \{1\}\par}";
+        assert_eq!(
+            rtf_to_text(rtf),
+            "¿Dónde estás?\nAna dijo —no— y salió al faro.\nFin {1}"
+        );
+    }
+
+    #[test]
+    fn draft_folder_is_flattened_into_chapters() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("Novela.scriv");
+        let doc = |uuid: &str, text: &str| {
+            let folder = root.join("Files").join("Data").join(uuid);
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(
+                folder.join("content.rtf"),
+                format!(r"{{\rtf1\ansi {}\par}}", text),
+            )
+            .unwrap();
+        };
+        doc("A1", "Ana llego al faro.");
+        doc("A2", "Luis la esperaba.");
+        doc("B1", "Llovia.");
+        doc("C1", "Notas de investigacion");
+        let binder = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ScrivenerProject><Binder>
+  <BinderItem UUID="D" Type="DraftFolder"><Title>Manuscrito</Title><Children>
+    <BinderItem UUID="P1" Type="Folder"><Title>Primera parte</Title><Children>
+      <BinderItem UUID="CH1" Type="Folder"><Title>Capítulo 1 &amp; llegada</Title><Children>
+        <BinderItem UUID="A1" Type="Text"><Title>Escena 1</Title></BinderItem>
+        <BinderItem UUID="A2" Type="Text"><Title>Escena 2</Title></BinderItem>
+      </Children></BinderItem>
+    </Children></BinderItem>
+    <BinderItem UUID="B1" Type="Text"><Title>Tormenta</Title>
+      <MetaData><IncludeInCompile>Yes</IncludeInCompile></MetaData></BinderItem>
+    <BinderItem UUID="X" Type="Text"><Title>Descartes</Title>
+      <MetaData><IncludeInCompile>No</IncludeInCompile></MetaData></BinderItem>
+    <BinderItem UUID="EMPTY" Type="Text"><Title>Vacio</Title></BinderItem>
+  </Children></BinderItem>
+  <BinderItem UUID="R" Type="ResearchFolder"><Title>Investigacion</Title><Children>
+    <BinderItem UUID="C1" Type="Text"><Title>Notas</Title></BinderItem>
+  </Children></BinderItem>
+</Binder></ScrivenerProject>"#;
+        fs::write(root.join("Novela.scrivx"), binder).unwrap();
+
+        let (found_root, scrivx) = locate(&root).unwrap();
+        assert_eq!(found_root, root);
+        assert_eq!(locate(&scrivx).unwrap().0, root);
+
+        let binder = parse_binder(&fs::read_to_string(scrivx).unwrap()).unwrap();
+        let (markdown, chapters, skipped) = flatten(&root, &binder).unwrap();
+        assert_eq!(chapters, 2);
+        assert_eq!(
+            markdown,
+            "# Capítulo 1 & llegada\n\nAna llego al faro.\n\n* * *\n\nLuis la esperaba.\n\n\
+             # Tormenta\n\nLlovia.\n"
+        );
+        assert_eq!(skipped, ["Descartes", "Vacio"]);
+    }
+}
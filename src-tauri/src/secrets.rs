@@ -0,0 +1,146 @@
+// Secretos de Narrative Assistant (claves de API de los proveedores LLM)
+//
+// Las claves se guardan en el almacén del sistema (Llavero en macOS,
+// Administrador de credenciales en Windows, Secret Service en Linux), nunca
+// en settings.json. Al lanzar el backend se inyectan como variables de
+// entorno (`OPENAI_API_KEY`, `ANTHROPIC_API_KEY`, `NA_OPENAI_API_KEY` del
+// analizador remoto de remote_llm.rs), de modo que tampoco
+// aparecen en la línea de comandos ni en los ficheros del directorio de datos.
+
+use keyring::Entry;
+
+use crate::remote_llm;
+
+/// Servicio bajo el que se guardan las entradas del almacén del sistema
+pub(crate) const KEYRING_SERVICE: &str = "com.tfm.narrative-assistant";
+
+/// Longitud máxima de un secreto
+const MAX_SECRET_LEN: usize = 4096;
+
+/// Secretos admitidos: nombre -> variable de entorno del backend
+const KNOWN_SECRETS: &[(&str, &str)] = &[
+    ("openai_api_key", "OPENAI_API_KEY"),
+    ("anthropic_api_key", "ANTHROPIC_API_KEY"),
+    (remote_llm::API_KEY_SECRET, "NA_OPENAI_API_KEY"),
+];
+
+/// Contraseña del proxy manual (la usa el shell, no se pasa tal cual al backend)
+pub(crate) const PROXY_PASSWORD: &str = "proxy_password";
+
+/// Evita que el frontend use el almacén del sistema para cosas arbitrarias
+fn validate_name(name: &str) -> Result<(), String> {
+    if name == PROXY_PASSWORD || KNOWN_SECRETS.iter().any(|(known, _)| *known == name) {
+        Ok(())
+    } else {
+        Err(format!("Secreto desconocido: {}", name))
+    }
+}
+
+fn entry(name: &str) -> Result<Entry, String> {
+    validate_name(name)?;
+    Entry::new(KEYRING_SERVICE, name)
+        .map_err(|e| format!("No se pudo acceder al almacen de claves: {}", e))
+}
+
+pub(crate) fn read(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!(
+            "Error leyendo {} del almacen de claves: {}",
+            name, e
+        )),
+    }
+}
+
+/// Variables de entorno con los secretos guardados, para lanzar el backend.
+/// Los errores del almacén se registran y el backend arranca sin esa clave.
+#[cfg_attr(debug_assertions, allow(dead_code))] // En desarrollo el backend se lanza a mano
+pub fn backend_env() -> Vec<(&'static str, String)> {
+    KNOWN_SECRETS
+        .iter()
+        .filter_map(|(name, var)| match read(name) {
+            Ok(secret) => secret.map(|s| (*var, s)),
+            Err(e) => {
+                tracing::warn!(target: "secrets", "{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn store(name: &str, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("El secreto no puede estar vacio".into());
+    }
+    if value.len() > MAX_SECRET_LEN {
+        return Err("El secreto es demasiado largo".into());
+    }
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| format!("Error guardando {} en el almacen de claves: {}", name, e))?;
+    tracing::info!(target: "secrets", "Secreto {} guardado (se aplica al reiniciar el backend)", name);
+    Ok(())
+}
+
+pub(crate) fn delete(name: &str) -> Result<(), String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => {
+            tracing::info!(target: "secrets", "Secreto {} borrado", name);
+            Ok(())
+        }
+        Err(e) => Err(format!(
+            "Error borrando {} del almacen de claves: {}",
+            name, e
+        )),
+    }
+}
+
+/// El almacén del sistema puede bloquear (p. ej. pidiendo desbloquear el llavero)
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Error en la tarea del almacen de claves: {}", e))?
+}
+
+/// Guarda un secreto en el almacén del sistema
+#[tauri::command]
+pub async fn store_secret(name: String, value: String) -> Result<(), String> {
+    run_blocking(move || store(&name, &value)).await
+}
+
+/// Lee un secreto (`None` si no está guardado)
+#[tauri::command]
+pub async fn get_secret(name: String) -> Result<Option<String>, String> {
+    run_blocking(move || read(&name)).await
+}
+
+/// Borra un secreto. No es un error si no existía.
+#[tauri::command]
+pub async fn delete_secret(name: String) -> Result<(), String> {
+    run_blocking(move || delete(&name)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_known_secrets_are_accepted() {
+        assert!(validate_name("openai_api_key").is_ok());
+        assert!(validate_name("anthropic_api_key").is_ok());
+        assert!(validate_name("remote_llm_api_key").is_ok());
+        assert!(validate_name(PROXY_PASSWORD).is_ok());
+        assert!(validate_name("license_key").is_err());
+        assert!(validate_name("").is_err());
+
+        let vars: Vec<&str> = KNOWN_SECRETS.iter().map(|(_, var)| *var).collect();
+        assert_eq!(
+            vars,
+            ["OPENAI_API_KEY", "ANTHROPIC_API_KEY", "NA_OPENAI_API_KEY"]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+// Restauración de la sesión de trabajo de Narrative Assistant
+//
+// La ventana principal guarda con `save_session` el proyecto abierto, la
+// pestaña activa y la posición de lectura del texto (carácter en la parte
+// superior de la vista) en session.json, dentro del directorio de datos de
+// la app. Al arrancar, en cuanto el backend pasa el health check, se emite
+// `restore-session` para que el frontend vuelva exactamente a ese punto.
+//
+// Si la app se abrió con un enlace narrassist:// o un archivo asociado, manda
+// eso y no se restaura nada. Como el evento puede llegar antes de que el
+// frontend esté montado, la sesión queda pendiente hasta que la recoja
+// `take_pending_session_restore`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, State, Window};
+
+use crate::deep_link::DeepLinkState;
+use crate::file_association::PendingOpenFiles;
+use crate::windows::MAIN_WINDOW;
+use crate::{cleanup, settings};
+
+/// Nombre del fichero de sesión
+const SESSION_FILE_NAME: &str = "session.json";
+
+/// Punto en el que el usuario dejó el trabajo (payload de `restore-session`)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Session {
+    /// Proyecto abierto en la ventana principal (None: lista de proyectos)
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    /// Pestaña activa del proyecto ('text', 'alerts', ...)
+    #[serde(default)]
+    pub tab: Option<String>,
+    /// Carácter (global en el documento) visible en la parte superior del texto
+    #[serde(default)]
+    pub scroll_position: Option<u64>,
+}
+
+impl Session {
+    /// Descarta los valores sin sentido; sin proyecto no queda nada que restaurar
+    fn sanitized(self) -> Self {
+        let Some(project_id) = self.project_id.filter(|id| *id > 0) else {
+            return Self::default();
+        };
+        let tab = self.tab.filter(|tab| {
+            !tab.is_empty()
+                && tab.len() <= 32
+                && tab
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+        Self {
+            project_id: Some(project_id),
+            tab,
+            scroll_position: self.scroll_position.filter(|pos| *pos > 0),
+        }
+    }
+}
+
+/// Sesión en memoria, registrada con `.manage()`
+pub struct SessionStore {
+    path: PathBuf,
+    session: Mutex<Session>,
+    /// Sesión emitida al arrancar que el frontend aún no ha recogido
+    pending: Mutex<Option<Session>>,
+    /// La restauración se ofrece una sola vez por arranque
+    offered: AtomicBool,
+}
+
+impl SessionStore {
+    /// Carga session.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(SESSION_FILE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let session = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Session>(&raw).ok())
+            .map(Session::sanitized)
+            .unwrap_or_default();
+        Self {
+            path,
+            session: Mutex::new(session),
+            pending: Mutex::new(None),
+            offered: AtomicBool::new(false),
+        }
+    }
+
+    fn get(&self) -> Session {
+        self.session.lock().unwrap().clone()
+    }
+
+    fn save(&self, session: Session) -> Result<(), String> {
+        let session = session.sanitized();
+        {
+            let mut current = self.session.lock().unwrap();
+            if *current == session {
+                return Ok(());
+            }
+            *current = session.clone();
+        }
+        let json = serde_json::to_vec_pretty(&session).map_err(|e| e.to_string())?;
+        settings::write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))
+    }
+}
+
+/// Ofrece al frontend la sesión anterior. Se llama cuando el backend pasa el
+/// health check; solo tiene efecto la primera vez.
+pub fn offer_restore(app: &AppHandle) {
+    let store = app.state::<SessionStore>();
+    if store.offered.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if app.state::<DeepLinkState>().has_pending() || app.state::<PendingOpenFiles>().has_pending() {
+        tracing::info!(target: "session", "Arranque con enlace o archivo: no se restaura la sesion");
+        return;
+    }
+    let session = store.get();
+    let Some(project_id) = session.project_id else {
+        return;
+    };
+    tracing::info!(
+        target: "session",
+        "Restaurando sesion: proyecto {} ({})",
+        project_id,
+        session.tab.as_deref().unwrap_or("text")
+    );
+    *store.pending.lock().unwrap() = Some(session.clone());
+    let _ = app.emit_to(
+        EventTarget::webview_window(MAIN_WINDOW),
+        "restore-session",
+        session,
+    );
+}
+
+/// Guarda el punto de trabajo actual. Solo cuenta la ventana principal: las
+/// ventanas de proyecto adicionales no cambian la sesión que se restaura.
+#[tauri::command]
+pub fn save_session(
+    window: Window,
+    store: State<'_, SessionStore>,
+    session: Session,
+) -> Result<(), String> {
+    if window.label() != MAIN_WINDOW {
+        return Ok(());
+    }
+    store.save(session)
+}
+
+/// Sesión pendiente de restaurar (se consume al leerla)
+#[tauri::command]
+pub fn take_pending_session_restore(store: State<'_, SessionStore>) -> Option<Session> {
+    store.pending.lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_roundtrips_through_disk_and_drops_invalid_values() {
+        let dir = std::env::temp_dir().join(format!("na-session-{}", std::process::id()));
+        let path = dir.join(SESSION_FILE_NAME);
+        let store = SessionStore::load(path.clone());
+        assert_eq!(store.get(), Session::default());
+
+        store
+            .save(Session {
+                project_id: Some(12),
+                tab: Some("alerts".into()),
+                scroll_position: Some(48_210),
+            })
+            .unwrap();
+        let reloaded = SessionStore::load(path.clone());
+        assert_eq!(reloaded.get().project_id, Some(12));
+        assert_eq!(reloaded.get().tab.as_deref(), Some("alerts"));
+        assert_eq!(reloaded.get().scroll_position, Some(48_210));
+
+        // Pestaña no válida y posición 0 se descartan; sin proyecto se vacía todo
+        let odd = Session {
+            project_id: Some(3),
+            tab: Some("../text".into()),
+            scroll_position: Some(0),
+        }
+        .sanitized();
+        assert_eq!(odd.tab, None);
+        assert_eq!(odd.scroll_position, None);
+        let closed = Session {
+            project_id: None,
+            tab: Some("text".into()),
+            scroll_position: Some(10),
+        };
+        assert_eq!(closed.sanitized(), Session::default());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
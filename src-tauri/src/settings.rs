@@ -0,0 +1,375 @@
+// Preferencias del shell de Narrative Assistant
+//
+// Se guardan en settings.json dentro del directorio de datos de la app (la
+// categoría "Configuración" de Gestionar datos). Las escrituras son atómicas
+// (fichero temporal + rename) y cada cambio se notifica al frontend con el
+// evento `shell-settings-changed` (distinto del evento DOM `settings-changed`
+// de las preferencias del frontend, que viven en localStorage).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::chapter_split::ChapterSplitSettings;
+use crate::cleanup::{self, RetentionPolicy};
+use crate::proxy::ProxySettings;
+use crate::remote_llm::RemoteLlmSettings;
+
+/// Nombre del fichero de preferencias
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Versión del esquema de settings.json
+const SETTINGS_VERSION: u32 = 1;
+
+/// Puerto por defecto del backend
+pub const DEFAULT_BACKEND_PORT: u16 = 8008;
+
+/// Tema de la interfaz
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+/// Política del watchdog que reinicia el backend (solo en release)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct WatchdogPolicy {
+    pub enabled: bool,
+    /// Espera inicial antes del primer chequeo (carga de modelos)
+    pub initial_delay_secs: u64,
+    /// Intervalo entre chequeos de salud
+    pub interval_secs: u64,
+    /// Fallos consecutivos antes de reiniciar
+    pub max_failures: u32,
+    /// Reinicios antes de rendirse
+    pub max_restarts: u32,
+}
+
+impl Default for WatchdogPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_delay_secs: 45,
+            interval_secs: 15,
+            max_failures: 3,
+            max_restarts: 3,
+        }
+    }
+}
+
+/// Preferencias del shell. Los campos ausentes en el fichero toman su valor por defecto.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// Puerto del backend. Reservado: el servidor Python y el frontend aún usan 8008 fijo.
+    pub backend_port: u16,
+    pub watchdog: WatchdogPolicy,
+    /// Idioma de la interfaz (código BCP 47: "es", "en"...)
+    pub language: String,
+    pub theme: Theme,
+    /// Directorio de datos del backend (NA_DATA_DIR). `None` = ubicación por defecto.
+    /// Se aplica al reiniciar el backend.
+    pub data_dir: Option<String>,
+    /// Directorio de modelos NLP (NA_MODELS_DIR). `None` = ~/.narrative_assistant/models.
+    /// Se cambia con `set_models_location`, que mueve los modelos instalados.
+    pub models_dir: Option<String>,
+    /// Atajos personalizados: acción -> acelerador ("file.open" -> "CmdOrCtrl+O")
+    pub shortcuts: BTreeMap<String, String>,
+    /// Minutos entre copias automáticas con un proyecto abierto (0 = desactivadas)
+    pub snapshot_interval_minutes: u32,
+    /// Retención de las copias automáticas
+    pub snapshot_retention: RetentionPolicy,
+    /// Cerrar la ventana la oculta en la bandeja y el backend sigue trabajando
+    pub minimize_to_tray: bool,
+    /// Abrir la app al iniciar sesión (se sincroniza con el registro del sistema)
+    pub launch_at_login: bool,
+    /// Al abrirse con el inicio de sesión, quedarse en la bandeja sin mostrar la ventana
+    pub start_minimized: bool,
+    /// Buscar actualizaciones automáticamente una vez a la semana
+    pub check_updates_weekly: bool,
+    /// Última comprobación de actualizaciones (segundos UNIX)
+    pub last_update_check: Option<u64>,
+    /// Consentimiento para enviar estadísticas de uso anónimas
+    pub telemetry_enabled: bool,
+    /// Id aleatorio de la instalación para la telemetría (se genera al primer envío)
+    pub telemetry_id: Option<String>,
+    /// Proxy para el tráfico a internet del shell y del backend
+    pub proxy: ProxySettings,
+    /// Consola de desarrollador en la paleta de comandos (oculta: se activa con su atajo)
+    pub developer_tools: bool,
+    /// Zoom de las ventanas. `None` = el tamaño de texto del sistema (accessibility.rs).
+    pub ui_scale: Option<f64>,
+    /// Índice de paquetes (PEP 503) para los modelos spaCy; `None` = PyPI.
+    /// Para mirrors locales o corporativos (spacy_install.rs).
+    pub package_index_url: Option<String>,
+    /// Límite de velocidad de las descargas del shell en KB/s; `None` = sin límite
+    pub download_limit_kbps: Option<u32>,
+    /// Minutos de inactividad, enchufado a la corriente, tras los que se precargan
+    /// el LLM y los embeddings (0 = sin precarga, warmup.rs)
+    pub warmup_idle_minutes: u32,
+    /// Servidor LLM compatible con OpenAI en lugar de Ollama (remote_llm.rs)
+    pub remote_llm: RemoteLlmSettings,
+    /// Última carpeta de cada diálogo de apertura: acción -> carpeta (file_dialogs.rs)
+    pub dialog_dirs: BTreeMap<String, String>,
+    /// Patrones extra de capítulo y escena para `.txt`/`.md` (chapter_split.rs)
+    pub chapter_split: ChapterSplitSettings,
+    /// Minutos sin usar el equipo tras los que se bloquea la base de datos
+    /// protegida con contraseña (0 = nunca, project_unlock.rs)
+    pub auto_lock_minutes: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            backend_port: DEFAULT_BACKEND_PORT,
+            watchdog: WatchdogPolicy::default(),
+            language: "es".into(),
+            theme: Theme::default(),
+            data_dir: None,
+            models_dir: None,
+            shortcuts: BTreeMap::new(),
+            snapshot_interval_minutes: 30,
+            snapshot_retention: RetentionPolicy::default(),
+            minimize_to_tray: false,
+            launch_at_login: false,
+            start_minimized: true,
+            check_updates_weekly: false,
+            last_update_check: None,
+            telemetry_enabled: false,
+            telemetry_id: None,
+            proxy: ProxySettings::default(),
+            developer_tools: false,
+            ui_scale: None,
+            package_index_url: None,
+            download_limit_kbps: None,
+            warmup_idle_minutes: 10,
+            remote_llm: RemoteLlmSettings::default(),
+            dialog_dirs: BTreeMap::new(),
+            chapter_split: ChapterSplitSettings::default(),
+            auto_lock_minutes: 15,
+        }
+    }
+}
+
+impl Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.backend_port < 1024 {
+            return Err("El puerto del backend debe ser 1024 o superior".into());
+        }
+        if self.watchdog.interval_secs == 0 || self.watchdog.max_failures == 0 {
+            return Err("El intervalo y los fallos del watchdog deben ser mayores que 0".into());
+        }
+        if self.language.trim().is_empty() {
+            return Err("El idioma no puede estar vacio".into());
+        }
+        if let Some(dir) = &self.data_dir {
+            if !Path::new(dir).is_absolute() {
+                return Err(format!(
+                    "El directorio de datos debe ser una ruta absoluta: {}",
+                    dir
+                ));
+            }
+        }
+        if let Some(dir) = &self.models_dir {
+            if !Path::new(dir).is_absolute() {
+                return Err(format!(
+                    "El directorio de modelos debe ser una ruta absoluta: {}",
+                    dir
+                ));
+            }
+        }
+        if let Some((action, _)) = self.shortcuts.iter().find(|(_, accel)| accel.is_empty()) {
+            return Err(format!("Atajo vacio para la accion {}", action));
+        }
+        if let Some(url) = &self.package_index_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(format!(
+                    "El indice de paquetes debe ser una URL http(s): {}",
+                    url
+                ));
+            }
+        }
+        if self.download_limit_kbps == Some(0) {
+            return Err("El limite de descarga debe ser mayor que 0 (o ninguno)".into());
+        }
+        self.remote_llm.validate()?;
+        self.chapter_split.validate()?;
+        self.proxy.validate()
+    }
+}
+
+/// Mezcla `patch` sobre `base` (los objetos se fusionan recursivamente, el resto se reemplaza)
+fn merge_json(base: &mut serde_json::Value, patch: serde_json::Value) {
+    match (base, patch) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// Escribe `contents` en `path` sin dejar nunca un fichero a medias
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+/// Preferencias cargadas en memoria, registradas con `.manage()`
+pub struct SettingsStore {
+    path: PathBuf,
+    current: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Carga settings.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(SETTINGS_FILE_NAME))
+    }
+
+    /// Carga las preferencias de `path`. Un fichero corrupto se aparta como
+    /// `.bak` y se usan los valores por defecto.
+    fn load(path: PathBuf) -> Self {
+        let settings = match fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str::<Settings>(&raw) {
+                Ok(settings) => match settings.validate() {
+                    Ok(()) => settings,
+                    Err(e) => {
+                        tracing::warn!(target: "settings", "{}: {}", path.display(), e);
+                        Settings::default()
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!(
+                        target: "settings",
+                        "{} invalido ({}), usando valores por defecto",
+                        path.display(),
+                        e
+                    );
+                    let _ = fs::rename(&path, path.with_extension("json.bak"));
+                    Settings::default()
+                }
+            },
+            Err(_) => Settings::default(),
+        };
+        Self {
+            path,
+            current: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Aplica un cambio parcial, lo valida y lo persiste
+    pub(crate) fn update(&self, patch: serde_json::Value) -> Result<Settings, String> {
+        let mut current = self.current.lock().unwrap();
+        let mut merged = serde_json::to_value(&*current).map_err(|e| e.to_string())?;
+        merge_json(&mut merged, patch);
+        let updated: Settings = serde_json::from_value(merged)
+            .map_err(|e| format!("Preferencias no validas: {}", e))?;
+        updated.validate()?;
+
+        let json = serde_json::to_vec_pretty(&updated).map_err(|e| e.to_string())?;
+        write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))?;
+        *current = updated.clone();
+        Ok(updated)
+    }
+}
+
+/// Devuelve las preferencias actuales
+#[tauri::command]
+pub fn get_settings(store: State<'_, SettingsStore>) -> Settings {
+    store.get()
+}
+
+/// Actualiza las preferencias con un objeto parcial (p.ej. `{ "theme": "dark" }`)
+/// y emite `shell-settings-changed` con el resultado
+#[tauri::command]
+pub fn update_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    patch: serde_json::Value,
+) -> Result<Settings, String> {
+    let updated = store.update(patch)?;
+    let _ = app.emit("shell-settings-changed", &updated);
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_take_defaults() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"theme":"dark","watchdog":{"enabled":false}}"#).unwrap();
+
+        assert_eq!(settings.theme, Theme::Dark);
+        assert!(!settings.watchdog.enabled);
+        assert_eq!(settings.watchdog.interval_secs, 15);
+        assert_eq!(settings.backend_port, DEFAULT_BACKEND_PORT);
+    }
+
+    #[test]
+    fn update_merges_validates_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE_NAME);
+        let store = SettingsStore::load(path.clone());
+
+        let updated = store
+            .update(serde_json::json!({
+                "language": "en",
+                "watchdog": { "max_restarts": 5 },
+                "shortcuts": { "file.open": "CmdOrCtrl+O" }
+            }))
+            .unwrap();
+        assert_eq!(updated.language, "en");
+        assert_eq!(updated.watchdog.max_restarts, 5);
+        assert_eq!(updated.watchdog.max_failures, 3);
+
+        assert!(store
+            .update(serde_json::json!({ "data_dir": "relativo" }))
+            .is_err());
+        assert!(store
+            .update(serde_json::json!({ "theme": "neon" }))
+            .is_err());
+        assert_eq!(store.get(), updated);
+
+        assert!(!path.with_extension("json.tmp").exists());
+        assert_eq!(SettingsStore::load(path).get(), updated);
+    }
+
+    #[test]
+    fn corrupt_file_is_set_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE_NAME);
+        fs::write(&path, "{ no es json").unwrap();
+
+        let store = SettingsStore::load(path.clone());
+
+        assert_eq!(store.get(), Settings::default());
+        assert!(path.with_extension("json.bak").exists());
+    }
+}
@@ -0,0 +1,309 @@
+// Copias automáticas de la base de datos de proyectos
+//
+// Meses de anotaciones viven en un único fichero SQLite: un corte de luz o un
+// disco lleno en mal momento pueden dejarlo inservible. Mientras hay un
+// proyecto abierto, `scheduler` copia la base de datos cada
+// `Settings::snapshot_interval_minutes` en ~/.narrative_assistant/snapshots
+// (`narrative_assistant-<segundos UNIX>.db`) y aplica la retención de
+// `Settings::snapshot_retention` (cleanup.rs).
+//
+// La copia se hace con `database::copy_to` (checkpoint del WAL + VACUUM INTO),
+// así que el backend no se detiene. Solo se copia si la base de datos ha
+// cambiado desde la última copia.
+//
+// `restore_snapshot` detiene el backend, guarda una copia del estado actual
+// (por si la restauración no era lo que se quería), sustituye la base de datos
+// y vuelve a arrancarlo.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::audit::{self, AuditEntry};
+use crate::cleanup::{self, SNAPSHOTS_DIR};
+use crate::clock::now_secs;
+use crate::database;
+use crate::quick_note::QuickNoteState;
+use crate::settings::SettingsStore;
+use crate::{windows, BackendServer};
+
+/// Cada cuánto comprueba el programador si toca hacer una copia
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Prefijo de los ficheros de snapshot (el nombre de la base de datos)
+const SNAPSHOT_PREFIX: &str = "narrative_assistant-";
+
+/// Una sola copia o restauración a la vez (programador y comandos)
+static SNAPSHOT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Snapshot disponible para restaurar
+#[derive(Serialize, Clone, Debug)]
+pub struct SnapshotInfo {
+    /// Nombre del fichero (lo que recibe `restore_snapshot`)
+    pub name: String,
+    pub path: String,
+    /// Segundos UNIX
+    pub created: u64,
+    pub size_bytes: u64,
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Base de datos y directorio de snapshots
+fn paths() -> Result<(PathBuf, PathBuf), String> {
+    let db = database::database_path().ok_or("No se pudo determinar el directorio home")?;
+    let dir = db
+        .parent()
+        .map(|parent| parent.join(SNAPSHOTS_DIR))
+        .ok_or("No se pudo determinar el directorio de snapshots")?;
+    Ok((db, dir))
+}
+
+/// Copia `db` en `dir` con la fecha actual en el nombre (y `suffix` si se indica)
+fn create_in(db: &Path, dir: &Path, suffix: &str) -> Result<SnapshotInfo, String> {
+    if !db.exists() {
+        return Err(format!("Base de datos no encontrada: {}", db.display()));
+    }
+    let _guard = SNAPSHOT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    std::fs::create_dir_all(dir).map_err(|e| format!("Error creando {}: {}", dir.display(), e))?;
+
+    let created = now_secs();
+    let mut name = format!("{}{}{}.db", SNAPSHOT_PREFIX, created, suffix);
+    // Dos copias en el mismo segundo (una manual justo tras la automática)
+    let mut n = 1;
+    while dir.join(&name).exists() {
+        name = format!("{}{}{}-{}.db", SNAPSHOT_PREFIX, created, suffix, n);
+        n += 1;
+    }
+    let path = dir.join(&name);
+    let size_bytes = database::copy_to(db, &path)?;
+    Ok(SnapshotInfo {
+        name,
+        path: path.to_string_lossy().into_owned(),
+        created,
+        size_bytes,
+    })
+}
+
+fn list_in(dir: &Path) -> Vec<SnapshotInfo> {
+    cleanup::list_snapshot_files(dir)
+        .into_iter()
+        .filter_map(|snapshot| {
+            Some(SnapshotInfo {
+                name: snapshot.path.file_name()?.to_string_lossy().into_owned(),
+                path: snapshot.path.to_string_lossy().into_owned(),
+                created: snapshot.created,
+                size_bytes: snapshot.size_bytes,
+            })
+        })
+        .collect()
+}
+
+/// Ruta del snapshot `name`: solo un nombre de fichero de `dir`, nunca una ruta
+fn resolve_snapshot(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let valid = !name.is_empty()
+        && Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name)
+        && name.ends_with(".db");
+    if !valid {
+        return Err(format!("Nombre de snapshot no valido: {}", name));
+    }
+    let path = dir.join(name);
+    if !path.is_file() {
+        return Err(format!("Snapshot no encontrado: {}", name));
+    }
+    Ok(path)
+}
+
+/// Si toca copia: pasó el intervalo desde la última y la base de datos cambió después
+fn is_due(db: &Path, dir: &Path, interval_secs: u64, now: u64) -> bool {
+    let Some(changed) = [db.to_path_buf(), db.with_extension("db-wal")]
+        .iter()
+        .filter_map(|path| modified_secs(path))
+        .max()
+    else {
+        return false;
+    };
+    match cleanup::list_snapshot_files(dir).first() {
+        Some(latest) => {
+            now.saturating_sub(latest.created) >= interval_secs && changed > latest.created
+        }
+        None => true,
+    }
+}
+
+/// Si hay algún proyecto abierto (en la ventana principal o en su propia ventana)
+fn project_open(app: &AppHandle) -> bool {
+    app.state::<QuickNoteState>().main_project().is_some()
+        || app
+            .webview_windows()
+            .keys()
+            .any(|label| windows::project_id_from_label(label).is_some())
+}
+
+/// Hace copias periódicas mientras hay un proyecto abierto
+pub async fn scheduler(app: AppHandle) {
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let settings = app.state::<SettingsStore>().get();
+        if settings.snapshot_interval_minutes == 0
+            || !project_open(&app)
+            // Mantenimiento o restauración en curso
+            || app.state::<BackendServer>().paused.load(Ordering::SeqCst)
+        {
+            continue;
+        }
+        let Ok((db, dir)) = paths() else {
+            continue;
+        };
+        let interval_secs = u64::from(settings.snapshot_interval_minutes) * 60;
+        let policy = settings.snapshot_retention;
+
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            if !is_due(&db, &dir, interval_secs, now_secs()) {
+                return Ok(None);
+            }
+            let snapshot = create_in(&db, &dir, "")?;
+            let report = cleanup::apply_retention(&dir, policy);
+            Ok::<_, String>(Some((snapshot, report)))
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+
+        match result {
+            Ok(Some((snapshot, report))) => tracing::info!(
+                target: "snapshots",
+                "Copia automatica {} ({} bytes, {} antiguas eliminadas)",
+                snapshot.name,
+                snapshot.size_bytes,
+                report.deleted.len()
+            ),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(target: "snapshots", "No se pudo hacer la copia automatica: {}", e)
+            }
+        }
+    }
+}
+
+/// Snapshots disponibles, el más reciente primero
+#[tauri::command]
+pub async fn list_snapshots() -> Result<Vec<SnapshotInfo>, String> {
+    let (_, dir) = paths()?;
+    tauri::async_runtime::spawn_blocking(move || list_in(&dir))
+        .await
+        .map_err(|e| format!("Error listando snapshots: {}", e))
+}
+
+/// Copia de la base de datos ahora mismo
+#[tauri::command]
+pub async fn create_snapshot() -> Result<SnapshotInfo, String> {
+    let (db, dir) = paths()?;
+    let snapshot = tauri::async_runtime::spawn_blocking(move || create_in(&db, &dir, ""))
+        .await
+        .map_err(|e| format!("Error creando el snapshot: {}", e))??;
+    tracing::info!(target: "snapshots", "Copia manual {}", snapshot.name);
+    Ok(snapshot)
+}
+
+/// Sustituye la base de datos por el snapshot `name`, pausando el backend.
+/// Devuelve la copia del estado anterior que se guarda antes de restaurar.
+#[tauri::command]
+pub async fn restore_snapshot(
+    app: AppHandle,
+    server_state: State<'_, BackendServer>,
+    name: String,
+) -> Result<Option<SnapshotInfo>, String> {
+    let (db, dir) = paths()?;
+    let source = resolve_snapshot(&dir, &name)?;
+
+    let check_source = source.clone();
+    let report =
+        tauri::async_runtime::spawn_blocking(move || database::check_integrity(&check_source))
+            .await
+            .map_err(|e| format!("Error en la tarea de verificacion: {}", e))??;
+    if !report.ok {
+        return Err(format!(
+            "El snapshot {} esta danado y no se puede restaurar",
+            name
+        ));
+    }
+
+    let audit = AuditEntry::new("restore_snapshot", name.as_str())
+        .bytes(std::fs::metadata(&db).map_or(0, |m| m.len()));
+    let was_running = crate::pause_backend(&app, &server_state);
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let backup = if db.exists() {
+            Some(create_in(&db, &dir, "-antes-de-restaurar")?)
+        } else {
+            None
+        };
+        let _guard = SNAPSHOT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        database::replace_with(&db, &source)?;
+        Ok::<_, String>(backup)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de restauracion: {}", e))
+    .and_then(|r| r);
+
+    // Reanudar siempre, incluso si la restauración falló
+    crate::resume_backend(&app, server_state, was_running).await;
+
+    audit::record(audit.outcome(&result));
+    let backup = result?;
+    tracing::info!(
+        target: "snapshots",
+        "Snapshot {} restaurado (estado anterior en {})",
+        name,
+        backup.as_ref().map_or("-", |b| b.name.as_str())
+    );
+    let _ = app.emit("snapshot-restored", &name);
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_names_cannot_escape_the_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("narrative_assistant-1.db"), b"").unwrap();
+
+        assert!(resolve_snapshot(dir.path(), "narrative_assistant-1.db").is_ok());
+        assert!(resolve_snapshot(dir.path(), "narrative_assistant-2.db").is_err());
+        assert!(resolve_snapshot(dir.path(), "../narrative_assistant.db").is_err());
+        assert!(resolve_snapshot(dir.path(), "/etc/passwd").is_err());
+        assert!(resolve_snapshot(dir.path(), "").is_err());
+    }
+
+    #[test]
+    fn snapshots_are_due_only_after_interval_and_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join(database::DB_FILE_NAME);
+        let snapshots = dir.path().join(SNAPSHOTS_DIR);
+        rusqlite::Connection::open(&db)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (body TEXT);")
+            .unwrap();
+        let now = now_secs();
+        assert!(is_due(&db, &snapshots, 1800, now));
+
+        let snapshot = create_in(&db, &snapshots, "").unwrap();
+        let second = create_in(&db, &snapshots, "").unwrap();
+        assert_ne!(snapshot.name, second.name);
+        assert_eq!(list_in(&snapshots).len(), 2);
+
+        // Recién copiada: ni ha pasado el intervalo ni hay cambios posteriores
+        assert!(!is_due(&db, &snapshots, 1800, now));
+        assert!(!is_due(&db, &snapshots, 1800, now + 3600));
+    }
+}
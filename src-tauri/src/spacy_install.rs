@@ -0,0 +1,465 @@
+// Instalación de modelos de idioma de spaCy
+//
+// El backend trae el modelo español (downloads.rs lo instala en el directorio
+// de modelos); para analizar otros idiomas hace falta el paquete del modelo
+// (en_core_web_md, fr_core_news_lg...) instalado en el Python embebido.
+// `install_spacy_model(lang, size)`:
+//   - busca el .whl en un índice de paquetes PEP 503/691: PyPI por defecto o
+//     `Settings::package_index_url` (mirrors de China, Artifactory/Nexus
+//     corporativos). Sin mirror propio, si PyPI no lo tiene se usa la release
+//     de explosion/spacy-models en GitHub
+//   - elige la versión más alta de la serie de spaCy del backend (la del modelo
+//     del manifiesto, p.ej. 3.7.x) y la verifica con el SHA-256 del índice
+//   - lo extrae en el site-packages del Python embebido o, si la instalación no
+//     es escribible (Archivos de programa), en ~/.narrative_assistant/python-packages,
+//     que va en el PYTHONPATH del backend
+//   - reinicia el backend para que cargue el paquete nuevo
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::downloads::{self, Progress, RemoteFile};
+use crate::models::{ModelKind, ModelManifest};
+use crate::proxy;
+use crate::settings::SettingsStore;
+
+const PYPI_SIMPLE_URL: &str = "https://pypi.org/simple";
+
+/// Paquetes instalados por el usuario cuando el Python embebido no es escribible
+const USER_PACKAGES_DIR: &str = "python-packages";
+
+/// Tiempo máximo de las consultas al índice (la descarga no tiene límite)
+const INDEX_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tamaños de modelo que publica spaCy
+const MODEL_SIZES: &[&str] = &["sm", "md", "lg", "trf"];
+
+/// Resultado de `install_spacy_model`
+#[derive(Serialize, Clone, Debug)]
+pub struct SpacyInstall {
+    pub package: String,
+    pub version: String,
+    /// site-packages donde ha quedado instalado
+    pub location: String,
+    /// Índice o release de donde se ha descargado
+    pub source: String,
+}
+
+/// Directorio de paquetes del usuario (se añade al PYTHONPATH si existe)
+pub(crate) fn user_site_packages() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".narrative_assistant").join(USER_PACKAGES_DIR))
+}
+
+/// Paquete de spaCy para un idioma y tamaño ("en", "md" -> en_core_web_md)
+fn package_name(lang: &str, size: &str) -> Result<String, String> {
+    let lang = lang.trim().to_ascii_lowercase();
+    if lang.len() != 2 || !lang.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(format!("Codigo de idioma no valido: {}", lang));
+    }
+    let size = size.trim().to_ascii_lowercase();
+    if !MODEL_SIZES.contains(&size.as_str()) {
+        return Err(format!(
+            "Tamano de modelo no valido: {} (use {})",
+            size,
+            MODEL_SIZES.join(", ")
+        ));
+    }
+    Ok(match lang.as_str() {
+        // Multilingüe: solo entidades
+        "xx" => format!("xx_ent_wiki_{}", size),
+        // Inglés y chino se entrenan con texto web; el resto, con noticias
+        "en" | "zh" => format!("{}_core_web_{}", lang, size),
+        _ => format!("{}_core_news_{}", lang, size),
+    })
+}
+
+/// Nombre normalizado de un proyecto en el índice (PEP 503)
+fn normalize_project(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// Serie de spaCy del backend ("3.7"), la del modelo del manifiesto
+fn spacy_series() -> String {
+    ModelManifest::bundled()
+        .models
+        .iter()
+        .find(|m| m.kind == ModelKind::Spacy)
+        .and_then(|m| m.revision.as_deref())
+        .map(|version| version.splitn(3, '.').take(2).collect::<Vec<_>>().join("."))
+        .unwrap_or_else(|| "3.7".into())
+}
+
+/// Fichero publicado en el índice
+#[derive(Debug, Clone, PartialEq)]
+struct IndexFile {
+    filename: String,
+    url: String,
+    sha256: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IndexJson {
+    files: Vec<IndexJsonFile>,
+}
+
+#[derive(Deserialize)]
+struct IndexJsonFile {
+    filename: String,
+    url: String,
+    #[serde(default)]
+    hashes: std::collections::HashMap<String, String>,
+}
+
+/// Respuesta JSON del índice (PEP 691)
+fn parse_index_json(body: &str, base: &reqwest::Url) -> Vec<IndexFile> {
+    let Ok(index) = serde_json::from_str::<IndexJson>(body) else {
+        return Vec::new();
+    };
+    index
+        .files
+        .into_iter()
+        .filter_map(|file| {
+            Some(IndexFile {
+                url: base.join(&file.url).ok()?.to_string(),
+                sha256: file.hashes.get("sha256").map(|h| h.to_ascii_lowercase()),
+                filename: file.filename,
+            })
+        })
+        .collect()
+}
+
+/// Respuesta HTML del índice (PEP 503: un `<a href="url#sha256=...">fichero</a>` por fichero)
+fn parse_index_html(body: &str, base: &reqwest::Url) -> Vec<IndexFile> {
+    body.split("<a ")
+        .skip(1)
+        .filter_map(|anchor| {
+            let href_start = anchor.find("href=\"")? + 6;
+            let href_len = anchor[href_start..].find('"')?;
+            let href = anchor[href_start..href_start + href_len].replace("&amp;", "&");
+            let text_start = anchor.find('>')? + 1;
+            let text_len = anchor[text_start..].find("</a>")?;
+            let filename = anchor[text_start..text_start + text_len].trim().to_string();
+            let mut url = base.join(&href).ok()?;
+            let sha256 = url
+                .fragment()
+                .and_then(|f| f.strip_prefix("sha256="))
+                .map(str::to_ascii_lowercase);
+            url.set_fragment(None);
+            Some(IndexFile {
+                filename,
+                url: url.to_string(),
+                sha256,
+            })
+        })
+        .collect()
+}
+
+/// Versión de un .whl universal del paquete (`paquete-3.7.1-py3-none-any.whl`)
+fn wheel_version(filename: &str, package: &str) -> Option<String> {
+    let version = filename
+        .strip_prefix(package)?
+        .strip_prefix('-')?
+        .strip_suffix("-py3-none-any.whl")?;
+    version
+        .split('.')
+        .all(|part| part.parse::<u64>().is_ok())
+        .then(|| version.to_string())
+}
+
+/// El .whl más reciente de la serie de spaCy
+fn select_wheel(files: &[IndexFile], package: &str, series: &str) -> Option<(IndexFile, String)> {
+    let numeric =
+        |version: &str| -> Vec<u64> { version.split('.').filter_map(|p| p.parse().ok()).collect() };
+    let prefix = format!("{}.", series);
+    files
+        .iter()
+        .filter_map(|file| {
+            // Los nombres de fichero usan `_` aunque el proyecto se normalice con `-`
+            let version = wheel_version(&file.filename, package)?;
+            version
+                .starts_with(&prefix)
+                .then(|| (file.clone(), version))
+        })
+        .max_by_key(|(_, version)| numeric(version))
+}
+
+/// Busca el paquete en el índice; `Ok(None)` si no lo tiene
+async fn find_in_index(
+    client: &reqwest::Client,
+    index: &str,
+    package: &str,
+    series: &str,
+) -> Result<Option<(RemoteFile, String)>, String> {
+    let base = reqwest::Url::parse(&format!(
+        "{}/{}/",
+        index.trim_end_matches('/'),
+        normalize_project(package)
+    ))
+    .map_err(|e| format!("Indice de paquetes no valido: {}", e))?;
+    let response = client
+        .get(base.clone())
+        .header(
+            reqwest::header::ACCEPT,
+            "application/vnd.pypi.simple.v1+json, text/html;q=0.1",
+        )
+        .timeout(INDEX_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Error consultando {}: {}", index, e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response = response
+        .error_for_status()
+        .map_err(|e| format!("Error consultando {}: {}", index, e))?;
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Error consultando {}: {}", index, e))?;
+    let files = if is_json {
+        parse_index_json(&body, &base)
+    } else {
+        parse_index_html(&body, &base)
+    };
+    Ok(
+        select_wheel(&files, package, series).map(|(file, version)| {
+            (
+                RemoteFile {
+                    path: PathBuf::from(&file.filename),
+                    url: file.url,
+                    size: None,
+                    sha256: file.sha256,
+                },
+                version,
+            )
+        }),
+    )
+}
+
+/// .whl de la release de GitHub (solo el modelo del manifiesto tiene suma conocida)
+fn github_wheel(package: &str, series: &str) -> (RemoteFile, String) {
+    let manifest = ModelManifest::bundled();
+    let pinned = manifest
+        .find(ModelKind::Spacy, package)
+        .filter(|m| m.revision.as_deref().is_some_and(|v| v.starts_with(series)));
+    let version = pinned
+        .and_then(|m| m.revision.clone())
+        .unwrap_or_else(|| format!("{}.0", series));
+    let file = format!("{}-{}-py3-none-any.whl", package, version);
+    let wheel = RemoteFile {
+        url: format!(
+            "{}/{}-{}/{}",
+            downloads::SPACY_RELEASES_URL,
+            package,
+            version,
+            file
+        ),
+        path: PathBuf::from(file),
+        size: None,
+        sha256: pinned.and_then(|m| m.sha256.clone()),
+    };
+    (wheel, version)
+}
+
+/// site-packages del Python embebido (solo en la app instalada)
+fn embedded_site_packages(app: &AppHandle) -> Option<PathBuf> {
+    let python_dir = app
+        .path()
+        .resource_dir()
+        .ok()?
+        .join("binaries")
+        .join("python-embed");
+    [
+        python_dir.join("Lib").join("site-packages"),
+        python_dir
+            .join("Python.framework/Versions/3.12/lib/python3.12")
+            .join("site-packages"),
+        python_dir.join("lib/python3.12").join("site-packages"),
+    ]
+    .into_iter()
+    .find(|dir| dir.is_dir())
+}
+
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(".na-write-test");
+    let writable = fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    writable
+}
+
+/// Dónde instalar: el Python embebido si es escribible, si no el directorio del usuario
+fn target_site_packages(app: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(site) = embedded_site_packages(app).filter(|dir| is_writable(dir)) {
+        return Ok(site);
+    }
+    let user = user_site_packages().ok_or("No se pudo determinar el directorio home")?;
+    fs::create_dir_all(&user).map_err(|e| format!("Error creando {}: {}", user.display(), e))?;
+    Ok(user)
+}
+
+/// Extrae un .whl universal en `site` (sustituye otra versión del paquete)
+fn install_wheel(wheel: &Path, site: &Path, package: &str) -> Result<usize, String> {
+    let file = fs::File::open(wheel).map_err(|e| e.to_string())?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Paquete {} no valido: {}", package, e))?;
+
+    let _ = fs::remove_dir_all(site.join(package));
+    if let Ok(entries) = fs::read_dir(site) {
+        let dist_info = format!("{}-", package);
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(&dist_info) && name.ends_with(".dist-info") {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    let mut extracted = 0;
+    let mut dist_info_dir = None;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let Some(relative) = downloads::safe_relative(entry.name()) else {
+            continue;
+        };
+        if let Some(first) = relative.components().next() {
+            let first = first.as_os_str().to_string_lossy();
+            if first.ends_with(".dist-info") {
+                dist_info_dir = Some(site.join(first.as_ref()));
+            }
+        }
+        let target = site.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = fs::File::create(&target).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        extracted += 1;
+    }
+    // Como pip: quién lo instaló
+    if let Some(dir) = dist_info_dir {
+        let _ = fs::write(dir.join("INSTALLER"), "narrative-assistant\n");
+    }
+    Ok(extracted)
+}
+
+/// Descarga e instala el modelo de spaCy de `lang` y `size` (sm, md, lg, trf)
+/// y reinicia el backend
+#[tauri::command]
+pub async fn install_spacy_model(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    lang: String,
+    size: String,
+) -> Result<SpacyInstall, String> {
+    let package = package_name(&lang, &size)?;
+    let series = spacy_series();
+    let mirror = store.get().package_index_url;
+    let index = mirror.clone().unwrap_or_else(|| PYPI_SIMPLE_URL.into());
+    let client = proxy::http_client(&app).await?;
+
+    let found = find_in_index(&client, &index, &package, &series).await;
+    let (wheel, version, source) = match (found, mirror.is_some()) {
+        (Ok(Some((wheel, version))), _) => (wheel, version, index),
+        (Ok(None), true) => {
+            return Err(format!(
+                "{} no tiene {} para spaCy {}",
+                index, package, series
+            ))
+        }
+        (Err(e), true) => return Err(e),
+        (result, false) => {
+            if let Err(e) = result {
+                tracing::warn!(target: "downloads", "{}", e);
+            }
+            let (wheel, version) = github_wheel(&package, &series);
+            (wheel, version, "github.com/explosion/spacy-models".into())
+        }
+    };
+    if wheel.sha256.is_none() {
+        tracing::warn!(target: "downloads", "{} {} sin suma publicada: se instala sin verificar", package, version);
+    }
+
+    let root = downloads::models_root(&app).ok_or("No se pudo determinar el directorio home")?;
+    let staging = root.join(".downloads").join(&package);
+    let wheel_path = staging.join(&wheel.path);
+    tracing::info!(target: "downloads", "Descargando {} {} de {}", package, version, source);
+    let mut progress = Progress::new(&app, &package, 0);
+    progress.emit("connecting", 0);
+    downloads::download_file(&client, &wheel, &wheel_path, &mut progress).await?;
+
+    progress.emit("installing", 0);
+    let site = target_site_packages(&app)?;
+    let (wheel_copy, site_copy, package_copy) = (wheel_path.clone(), site.clone(), package.clone());
+    let count = tauri::async_runtime::spawn_blocking(move || {
+        install_wheel(&wheel_copy, &site_copy, &package_copy)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let _ = fs::remove_dir_all(&staging);
+    progress.emit("completed", 0);
+    tracing::info!(target: "downloads", "{} {} instalado en {} ({} ficheros)", package, version, site.display(), count);
+
+    crate::restart_backend(&app).await;
+    Ok(SpacyInstall {
+        package,
+        version,
+        location: site.to_string_lossy().into_owned(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_names_follow_spacy_conventions() {
+        assert_eq!(package_name("EN", "md").unwrap(), "en_core_web_md");
+        assert_eq!(package_name("fr", "lg").unwrap(), "fr_core_news_lg");
+        assert_eq!(package_name("xx", "sm").unwrap(), "xx_ent_wiki_sm");
+        assert!(package_name("../es", "sm").is_err());
+        assert!(package_name("es", "xl").is_err());
+        assert_eq!(normalize_project("fr_core_news_lg"), "fr-core-news-lg");
+        assert_eq!(spacy_series(), "3.7");
+    }
+
+    #[test]
+    fn index_pages_select_latest_wheel_of_the_series() {
+        let base = reqwest::Url::parse("https://mirror.example/simple/fr-core-news-lg/").unwrap();
+        let html = r#"<html><body>
+            <a href="../../packages/fr_core_news_lg-3.6.0-py3-none-any.whl#sha256=AA">fr_core_news_lg-3.6.0-py3-none-any.whl</a>
+            <a href="../../packages/fr_core_news_lg-3.7.0-py3-none-any.whl#sha256=BB">fr_core_news_lg-3.7.0-py3-none-any.whl</a>
+            <a href="../../packages/fr_core_news_lg-3.7.10-py3-none-any.whl#sha256=CC">fr_core_news_lg-3.7.10-py3-none-any.whl</a>
+            <a href="../../packages/fr_core_news_lg-3.7.1.tar.gz">fr_core_news_lg-3.7.1.tar.gz</a>
+        </body></html>"#;
+        let files = parse_index_html(html, &base);
+        assert_eq!(files.len(), 4);
+        let (file, version) = select_wheel(&files, "fr_core_news_lg", "3.7").unwrap();
+        assert_eq!(version, "3.7.10");
+        assert_eq!(
+            file.url,
+            "https://mirror.example/packages/fr_core_news_lg-3.7.10-py3-none-any.whl"
+        );
+        assert_eq!(file.sha256.as_deref(), Some("cc"));
+
+        let json = r#"{"files": [{"filename": "fr_core_news_lg-3.7.0-py3-none-any.whl",
+            "url": "https://files.example/fr_core_news_lg-3.7.0-py3-none-any.whl",
+            "hashes": {"sha256": "DD"}}]}"#;
+        let (file, _) =
+            select_wheel(&parse_index_json(json, &base), "fr_core_news_lg", "3.7").unwrap();
+        assert_eq!(file.sha256.as_deref(), Some("dd"));
+        assert!(select_wheel(&files, "fr_core_news_lg", "3.8").is_none());
+    }
+}
@@ -0,0 +1,157 @@
+// Ventana de carga de Narrative Assistant
+//
+// Mientras arranca el backend se muestra una ventana pequeña sin marco
+// (frontend/public/splash.html) con las fases del arranque, que llegan por el
+// canal de estado (tema `boot` de status_channel.rs). La ventana principal se crea oculta
+// (tauri.conf.json) y solo se muestra cuando el backend responde al health
+// check, o cuando falla, para que el usuario vea el error en la interfaz.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::status_channel::{self, StatusTopic};
+use crate::windows::MAIN_WINDOW;
+
+/// Label de la ventana de carga
+pub const SPLASH_WINDOW: &str = "splash";
+
+/// Página de la ventana de carga (frontend/public)
+const SPLASH_URL: &str = "splash.html";
+
+/// Tiempo máximo con la ventana de carga: pasado este plazo se muestra la principal
+/// aunque el backend no haya terminado de cargar
+pub const SPLASH_MAX_WAIT: Duration = Duration::from_secs(180);
+
+/// Si la ventana principal ya se ha mostrado
+static REVEALED: AtomicBool = AtomicBool::new(false);
+
+/// Fase del arranque del backend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(debug_assertions, allow(dead_code))] // En desarrollo el backend se lanza a mano
+pub enum BootPhase {
+    /// Lanzando el proceso del servidor
+    Spawning,
+    /// Esperando a que el proceso responda
+    Connecting,
+    /// Servidor activo, cargando modelos y módulos NLP
+    LoadingModules,
+    Ready,
+}
+
+impl BootPhase {
+    fn as_str(self) -> &'static str {
+        match self {
+            BootPhase::Spawning => "spawning",
+            BootPhase::Connecting => "connecting",
+            BootPhase::LoadingModules => "loading_modules",
+            BootPhase::Ready => "ready",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            BootPhase::Spawning => "Iniciando servidor...",
+            BootPhase::Connecting => "Conectando con el servidor...",
+            BootPhase::LoadingModules => "Cargando modelos de lenguaje...",
+            BootPhase::Ready => "Listo",
+        }
+    }
+
+    /// Progreso aproximado (0-1) para la barra de la ventana de carga
+    fn progress(self) -> f64 {
+        match self {
+            BootPhase::Spawning => 0.1,
+            BootPhase::Connecting => 0.3,
+            BootPhase::LoadingModules => 0.6,
+            BootPhase::Ready => 1.0,
+        }
+    }
+}
+
+/// Si `label` es la ventana de carga (no guarda geometría ni recibe eventos de menú)
+pub fn is_splash(label: &str) -> bool {
+    label == SPLASH_WINDOW
+}
+
+/// Crea la ventana de carga centrada en pantalla
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    WebviewWindowBuilder::new(app, SPLASH_WINDOW, WebviewUrl::App(SPLASH_URL.into()))
+        .title("Narrative Assistant")
+        .inner_size(420.0, 200.0)
+        .resizable(false)
+        .decorations(false)
+        .center()
+        .focused(true)
+        .build()?;
+    Ok(())
+}
+
+/// Notifica una fase del arranque a la ventana de carga (y a quien escuche)
+pub fn emit_progress(app: &AppHandle, phase: BootPhase) {
+    status_channel::publish(
+        app,
+        StatusTopic::Boot,
+        None,
+        "progress",
+        serde_json::json!({
+            "phase": phase.as_str(),
+            "message": phase.message(),
+            "progress": phase.progress(),
+        }),
+    );
+}
+
+/// Si la ventana principal ya se ha mostrado tras el arranque
+pub fn is_revealed() -> bool {
+    REVEALED.load(Ordering::SeqCst)
+}
+
+/// Arranque en la bandeja (ver autostart.rs): la principal no se muestra sola,
+/// solo al abrirla desde la bandeja, un enlace o un archivo
+pub fn stay_in_tray() {
+    REVEALED.store(true, Ordering::SeqCst);
+}
+
+/// Cierra la ventana de carga y muestra la principal (solo la primera vez)
+pub fn reveal_main(app: &AppHandle) {
+    if REVEALED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(splash) = app.get_webview_window(SPLASH_WINDOW) {
+        let _ = splash.close();
+    }
+    // Forzar la ventana a primer plano (fix para cuando se lanza desde el instalador NSIS)
+    // Cuando NSIS lanza la app después de la instalación, puede hacerlo en un contexto
+    // diferente que causa que la ventana aparezca minimizada o detrás de otras ventanas
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        // En Windows, también intentar traer al frente
+        #[cfg(target_os = "windows")]
+        {
+            let _ = window.set_always_on_top(true);
+            let _ = window.set_always_on_top(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boot_phases_progress_forward() {
+        let phases = [
+            BootPhase::Spawning,
+            BootPhase::Connecting,
+            BootPhase::LoadingModules,
+            BootPhase::Ready,
+        ];
+        for pair in phases.windows(2) {
+            assert!(pair[0].progress() < pair[1].progress());
+            assert_ne!(pair[0].as_str(), pair[1].as_str());
+        }
+        assert_eq!(BootPhase::Ready.progress(), 1.0);
+    }
+}
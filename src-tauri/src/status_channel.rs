@@ -0,0 +1,327 @@
+// Canal de estado entre shell, backend y frontend en Narrative Assistant
+//
+// El estado llegaba por tres vías sin relación entre sí: eventos sueltos
+// `backend-status` y `backend-progress` del arranque y del watchdog, y
+// sondeos periódicos del frontend al progreso del análisis. Un evento emitido
+// antes de que la página escuchara (o mientras el WebView se recargaba) se
+// perdía sin rastro.
+//
+// Ahora todo pasa por `publish`: cada evento lleva un número de secuencia, se
+// guarda en un búfer con los últimos `BUFFER_LEN` y se emite como
+// `status-event`. El frontend (services/statusChannel.ts) detecta huecos en la
+// secuencia y pide lo perdido con `status_replay`; al arrancar, el historial le
+// da el último estado del backend.
+//
+// Temas:
+// - `boot`: fases del arranque (ventana de carga).
+// - `backend`: estado del backend y del watchdog (`i18n::status_payload`).
+// - `analysis`: progreso de un análisis. `watch_analysis` abre el stream SSE
+//   del backend (/api/projects/{id}/analysis/stream) y reenvía sus eventos,
+//   reconectando si se corta antes de que el análisis termine.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::backend_auth;
+use crate::i18n::{self, Message};
+
+/// Evento de Tauri con el que se emite cada `StatusEvent`
+pub const STATUS_EVENT: &str = "status-event";
+
+/// Eventos que se guardan para `status_replay`
+const BUFFER_LEN: usize = 256;
+
+/// Reconexiones seguidas al stream de un análisis antes de rendirse
+const MAX_RECONNECTS: u32 = 5;
+
+/// Espera antes de la primera reconexión; se dobla en cada intento
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Origen de un evento de estado
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusTopic {
+    Boot,
+    Backend,
+    Analysis,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StatusEvent {
+    pub seq: u64,
+    pub topic: StatusTopic,
+    pub project_id: Option<i64>,
+    /// Tipo dentro del tema: "status", "progress", "complete", "error"...
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// Milisegundos desde epoch
+    pub at: u64,
+}
+
+/// Respuesta de `status_replay`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StatusReplay {
+    pub events: Vec<StatusEvent>,
+    /// Si se han perdido eventos que ya no están en el búfer
+    pub gap: bool,
+}
+
+/// Búfer de eventos y streams de análisis abiertos, registrado con `.manage()`
+pub struct StatusChannel {
+    seq: AtomicU64,
+    recent: Mutex<VecDeque<StatusEvent>>,
+    relays: Mutex<HashMap<i64, tauri::async_runtime::JoinHandle<()>>>,
+}
+
+impl StatusChannel {
+    pub fn new() -> Self {
+        Self {
+            seq: AtomicU64::new(0),
+            recent: Mutex::new(VecDeque::with_capacity(BUFFER_LEN)),
+            relays: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn record(
+        &self,
+        topic: StatusTopic,
+        project_id: Option<i64>,
+        kind: &str,
+        payload: serde_json::Value,
+    ) -> StatusEvent {
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut recent = self.recent.lock().unwrap();
+        // La secuencia se asigna con el búfer bloqueado para que quede ordenado
+        let event = StatusEvent {
+            seq: self.seq.fetch_add(1, Ordering::SeqCst) + 1,
+            topic,
+            project_id,
+            kind: kind.to_string(),
+            payload,
+            at,
+        };
+        if recent.len() == BUFFER_LEN {
+            recent.pop_front();
+        }
+        recent.push_back(event.clone());
+        event
+    }
+
+    fn replay(&self, after: u64) -> StatusReplay {
+        let recent = self.recent.lock().unwrap();
+        let oldest = recent.front().map(|e| e.seq);
+        StatusReplay {
+            events: recent.iter().filter(|e| e.seq > after).cloned().collect(),
+            gap: oldest.is_some_and(|oldest| oldest > after + 1),
+        }
+    }
+}
+
+/// Guarda y emite un evento de estado
+pub fn publish(
+    app: &AppHandle,
+    topic: StatusTopic,
+    project_id: Option<i64>,
+    kind: &str,
+    payload: serde_json::Value,
+) {
+    let event = app
+        .state::<StatusChannel>()
+        .record(topic, project_id, kind, payload);
+    let _ = app.emit(STATUS_EVENT, event);
+}
+
+/// Estado del backend (arranque, watchdog, mantenimiento)
+pub fn backend_status(app: &AppHandle, status: &str, message: Message) {
+    publish(
+        app,
+        StatusTopic::Backend,
+        None,
+        "status",
+        i18n::status_payload(app, status, message),
+    );
+}
+
+/// Eventos de un stream SSE (`event:` y `data:`), troceado como llegue
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    /// Añade bytes y devuelve los eventos completos: (tipo, datos)
+    fn feed(&mut self, chunk: &[u8]) -> Vec<(String, String)> {
+        self.buffer
+            .extend(chunk.iter().copied().filter(|b| *b != b'\r'));
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&block);
+            let mut kind = "message".to_string();
+            let mut data = Vec::new();
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    kind = value.trim().to_string();
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data.push(value.strip_prefix(' ').unwrap_or(value));
+                }
+            }
+            if !data.is_empty() {
+                events.push((kind, data.join("\n")));
+            }
+        }
+        events
+    }
+}
+
+/// Reenvía el stream del análisis de `project_id` hasta que termina; devuelve si terminó
+async fn relay_stream(app: &AppHandle, project_id: i64) -> Result<bool, String> {
+    let mut response = backend_auth::backend_client()
+        .get(backend_auth::backend_url(&format!(
+            "/api/projects/{}/analysis/stream",
+            project_id
+        )))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let mut parser = SseParser::default();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        for (kind, data) in parser.feed(&chunk) {
+            if kind == "keepalive" {
+                continue;
+            }
+            let payload = serde_json::from_str(&data).unwrap_or(serde_json::Value::String(data));
+            publish(app, StatusTopic::Analysis, Some(project_id), &kind, payload);
+            if kind == "complete" || kind == "error" {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+async fn relay_analysis(app: AppHandle, project_id: i64) {
+    let mut failures = 0;
+    loop {
+        let error = match relay_stream(&app, project_id).await {
+            Ok(true) => break,
+            // El backend cerró el stream sin terminar (p. ej. se reinició)
+            Ok(false) => "stream cerrado".to_string(),
+            Err(e) => e,
+        };
+        failures += 1;
+        tracing::warn!(
+            target: "status_channel",
+            "Stream del analisis {} cortado ({}/{}): {}",
+            project_id,
+            failures,
+            MAX_RECONNECTS,
+            error
+        );
+        if failures >= MAX_RECONNECTS {
+            publish(
+                &app,
+                StatusTopic::Analysis,
+                Some(project_id),
+                "disconnected",
+                serde_json::Value::Null,
+            );
+            break;
+        }
+        tokio::time::sleep(RECONNECT_DELAY * 2u32.pow(failures - 1)).await;
+    }
+    app.state::<StatusChannel>()
+        .relays
+        .lock()
+        .unwrap()
+        .remove(&project_id);
+}
+
+/// Eventos emitidos después de `after` (0: todo el búfer)
+#[tauri::command]
+pub fn status_replay(channel: State<'_, StatusChannel>, after: u64) -> StatusReplay {
+    channel.replay(after)
+}
+
+/// Empieza a reenviar el progreso del análisis de `project_id` (si no se estaba reenviando ya)
+#[tauri::command]
+pub fn watch_analysis(app: AppHandle, channel: State<'_, StatusChannel>, project_id: i64) {
+    let mut relays = channel.relays.lock().unwrap();
+    if relays.get(&project_id).is_some() {
+        return;
+    }
+    let handle = tauri::async_runtime::spawn(relay_analysis(app.clone(), project_id));
+    relays.insert(project_id, handle);
+}
+
+/// Deja de reenviar el progreso del análisis de `project_id`
+#[tauri::command]
+pub fn unwatch_analysis(channel: State<'_, StatusChannel>, project_id: i64) {
+    if let Some(handle) = channel.relays.lock().unwrap().remove(&project_id) {
+        handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_returns_missed_events_and_reports_gaps() {
+        let channel = StatusChannel::new();
+        for n in 0..BUFFER_LEN + 10 {
+            channel.record(
+                StatusTopic::Analysis,
+                Some(1),
+                "progress",
+                serde_json::json!({ "progress": n }),
+            );
+        }
+        let last = (BUFFER_LEN + 10) as u64;
+
+        let recent = channel.replay(last - 2);
+        assert_eq!(
+            recent.events.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            [last - 1, last]
+        );
+        assert!(!recent.gap);
+
+        // Los 10 primeros ya no están en el búfer
+        let all = channel.replay(0);
+        assert!(all.gap);
+        assert_eq!(all.events.len(), BUFFER_LEN);
+        assert_eq!(all.events[0].seq, 11);
+        assert!(channel.replay(last).events.is_empty());
+    }
+
+    #[test]
+    fn sse_events_are_parsed_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser
+            .feed(b"event: progress\r\ndata: {\"progress\": 0.")
+            .is_empty());
+        let events = parser.feed(
+            "5, \"phase\": \"Análisis\"}\r\n\r\nevent: keepalive\ndata: {}\n\ndata: sin tipo\n\nevent: vacío\n\n"
+                .as_bytes(),
+        );
+        assert_eq!(
+            events,
+            [
+                (
+                    "progress".to_string(),
+                    "{\"progress\": 0.5, \"phase\": \"Análisis\"}".to_string()
+                ),
+                ("keepalive".to_string(), "{}".to_string()),
+                ("message".to_string(), "sin tipo".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,391 @@
+// Telemetría anónima y opcional de Narrative Assistant
+//
+// Solo se guardan agregados: cuántas veces se usa cada función, la duración de
+// los análisis (en segundos) y el número de cierres inesperados. Nunca rutas,
+// textos ni nombres de proyecto. La cola vive en telemetry.json (directorio de
+// datos de la app) y solo se envía si el usuario ha dado su consentimiento
+// (`telemetry_enabled` en preferencias) y la build tiene un endpoint
+// configurado (NA_TELEMETRY_URL).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::clock::now_secs;
+use crate::settings::{self, SettingsStore};
+use crate::{cleanup, crash, network, proxy};
+
+/// Nombre del fichero de la cola
+const TELEMETRY_FILE_NAME: &str = "telemetry.json";
+
+/// Endpoint de envío, fijado en compilación. Sin él la cola nunca sale del equipo.
+const TELEMETRY_URL: Option<&str> = option_env!("NA_TELEMETRY_URL");
+
+/// Duraciones de análisis que se guardan como máximo entre envíos
+const MAX_DURATIONS: usize = 500;
+
+/// Longitud máxima del nombre de una función
+const MAX_FEATURE_LEN: usize = 64;
+
+/// Cada cuánto se intenta enviar la cola
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+fn endpoint() -> Option<&'static str> {
+    TELEMETRY_URL.map(str::trim).filter(|u| !u.is_empty())
+}
+
+/// Evento que registra el frontend
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// Uso de una función ("export.docx", "analysis.run", "view.timeline"...)
+    FeatureUsed { feature: String },
+    /// Análisis terminado
+    AnalysisCompleted { duration_ms: u64 },
+}
+
+/// Nombre de función normalizado: minúsculas, `[a-z0-9._-]`, sin datos libres
+fn sanitize_feature(feature: &str) -> Option<String> {
+    let feature = feature.trim().to_ascii_lowercase();
+    let valid = !feature.is_empty()
+        && feature.len() <= MAX_FEATURE_LEN
+        && feature
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c));
+    valid.then_some(feature)
+}
+
+/// Agregados pendientes de envío
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct TelemetryQueue {
+    /// Inicio del periodo (segundos UNIX)
+    pub since: u64,
+    pub feature_counts: BTreeMap<String, u64>,
+    pub analysis_durations_secs: Vec<u64>,
+    pub crash_counts: BTreeMap<String, u64>,
+    /// Último informe de fallo ya contado
+    crash_watermark: u64,
+}
+
+impl TelemetryQueue {
+    fn record(&mut self, event: TelemetryEvent) -> Result<(), String> {
+        if self.since == 0 {
+            self.since = now_secs();
+        }
+        match event {
+            TelemetryEvent::FeatureUsed { feature } => {
+                let feature = sanitize_feature(&feature)
+                    .ok_or_else(|| format!("Nombre de funcion no valido: {}", feature))?;
+                *self.feature_counts.entry(feature).or_default() += 1;
+            }
+            TelemetryEvent::AnalysisCompleted { duration_ms } => {
+                if self.analysis_durations_secs.len() >= MAX_DURATIONS {
+                    self.analysis_durations_secs.remove(0);
+                }
+                self.analysis_durations_secs
+                    .push(duration_ms.div_ceil(1000));
+            }
+        }
+        Ok(())
+    }
+
+    /// Cuenta los informes de fallo posteriores al último contado
+    fn record_crashes(&mut self, occurrences: &[(u64, crash::CrashKind)]) -> bool {
+        let mut changed = false;
+        for (timestamp, kind) in occurrences {
+            if *timestamp > self.crash_watermark {
+                *self.crash_counts.entry(kind.as_str().into()).or_default() += 1;
+                changed = true;
+            }
+        }
+        if let Some(latest) = occurrences.iter().map(|(t, _)| *t).max() {
+            self.crash_watermark = self.crash_watermark.max(latest);
+        }
+        changed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.feature_counts.is_empty()
+            && self.analysis_durations_secs.is_empty()
+            && self.crash_counts.is_empty()
+    }
+
+    /// Devuelve a la cola un lote que no se pudo enviar
+    fn merge(&mut self, other: TelemetryQueue) {
+        for (feature, count) in other.feature_counts {
+            *self.feature_counts.entry(feature).or_default() += count;
+        }
+        for (kind, count) in other.crash_counts {
+            *self.crash_counts.entry(kind).or_default() += count;
+        }
+        let mut durations = other.analysis_durations_secs;
+        durations.append(&mut self.analysis_durations_secs);
+        let excess = durations.len().saturating_sub(MAX_DURATIONS);
+        durations.drain(..excess);
+        self.analysis_durations_secs = durations;
+        if other.since != 0 {
+            self.since = other.since;
+        }
+        self.crash_watermark = self.crash_watermark.max(other.crash_watermark);
+    }
+
+    /// Vacía los agregados conservando la marca de fallos ya contados
+    fn clear(&mut self) {
+        *self = Self {
+            crash_watermark: self.crash_watermark,
+            ..Self::default()
+        };
+    }
+}
+
+/// Lote que se envía al endpoint
+#[derive(Serialize, Debug)]
+struct TelemetryBatch<'a> {
+    /// Id aleatorio de la instalación (no identifica al usuario)
+    install_id: &'a str,
+    app_version: &'a str,
+    os: &'a str,
+    arch: &'a str,
+    period_start: u64,
+    period_end: u64,
+    feature_counts: &'a BTreeMap<String, u64>,
+    analysis_durations_secs: &'a [u64],
+    crash_counts: &'a BTreeMap<String, u64>,
+}
+
+/// Cola en memoria, registrada con `.manage()`
+pub struct TelemetryState {
+    path: PathBuf,
+    queue: Mutex<TelemetryQueue>,
+}
+
+impl TelemetryState {
+    /// Carga telemetry.json del directorio de datos y cuenta los fallos nuevos
+    pub fn load_default() -> Self {
+        let state = Self::load(cleanup::app_data_dir().join(TELEMETRY_FILE_NAME));
+        let counted = state
+            .queue
+            .lock()
+            .unwrap()
+            .record_crashes(&crash::report_occurrences());
+        if counted {
+            state.persist();
+        }
+        state
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let queue = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            queue: Mutex::new(queue),
+        }
+    }
+
+    fn persist(&self) {
+        let json = serde_json::to_vec_pretty(&*self.queue.lock().unwrap());
+        let result = json
+            .map_err(std::io::Error::other)
+            .and_then(|json| settings::write_atomic(&self.path, &json));
+        if let Err(e) = result {
+            tracing::warn!(target: "telemetry", "Error guardando {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Registra un evento en la cola local (no envía nada)
+#[tauri::command]
+pub fn record_telemetry_event(
+    state: State<'_, TelemetryState>,
+    event: TelemetryEvent,
+) -> Result<(), String> {
+    state.queue.lock().unwrap().record(event)?;
+    state.persist();
+    Ok(())
+}
+
+/// Contenido de la cola tal como se enviaría (para mostrarlo en preferencias)
+#[tauri::command]
+pub fn get_telemetry_queue(state: State<'_, TelemetryState>) -> TelemetryQueue {
+    state.queue.lock().unwrap().clone()
+}
+
+/// Borra la cola sin enviarla
+#[tauri::command]
+pub fn clear_telemetry(state: State<'_, TelemetryState>) {
+    state.queue.lock().unwrap().clear();
+    state.persist();
+}
+
+/// Envía la cola si hay consentimiento y endpoint. Devuelve si se envió algo.
+async fn upload(app: &AppHandle) -> Result<bool, String> {
+    let store = app.state::<SettingsStore>();
+    let current = store.get();
+    let Some(url) = endpoint().filter(|_| current.telemetry_enabled) else {
+        return Ok(false);
+    };
+    let install_id = match current.telemetry_id {
+        Some(id) => id,
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            store.update(serde_json::json!({ "telemetry_id": id }))?;
+            id
+        }
+    };
+
+    // Se saca la cola antes de enviar: los eventos que lleguen durante el envío van a una nueva
+    let state = app.state::<TelemetryState>();
+    let queue = {
+        let mut pending = state.queue.lock().unwrap();
+        if pending.is_empty() {
+            return Ok(false);
+        }
+        let taken = pending.clone();
+        pending.clear();
+        taken
+    };
+    let batch = TelemetryBatch {
+        install_id: &install_id,
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        period_start: queue.since,
+        period_end: now_secs(),
+        feature_counts: &queue.feature_counts,
+        analysis_durations_secs: &queue.analysis_durations_secs,
+        crash_counts: &queue.crash_counts,
+    };
+    let result = proxy::http_client(app)
+        .await?
+        .post(url)
+        .timeout(Duration::from_secs(15))
+        .json(&batch)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status());
+
+    if let Err(e) = result {
+        state.queue.lock().unwrap().merge(queue);
+        return Err(format!("Error enviando telemetria: {}", e));
+    }
+    state.persist();
+    Ok(true)
+}
+
+/// Fuerza el envío de la cola (respeta el consentimiento)
+#[tauri::command]
+pub async fn upload_telemetry(app: AppHandle) -> Result<bool, String> {
+    upload(&app).await
+}
+
+/// Envío periódico en segundo plano
+pub async fn background_uploads(app: AppHandle) {
+    loop {
+        // Sin conexión la cola se conserva para el siguiente envío
+        if !network::is_offline(&app) {
+            match upload(&app).await {
+                Ok(true) => tracing::info!(target: "telemetry", "Telemetria enviada"),
+                Ok(false) => {}
+                Err(e) => tracing::warn!(target: "telemetry", "{}", e),
+            }
+        }
+        tokio::time::sleep(UPLOAD_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crash::CrashKind;
+
+    #[test]
+    fn queue_aggregates_events_without_free_text() {
+        let mut queue = TelemetryQueue::default();
+        for _ in 0..2 {
+            queue
+                .record(TelemetryEvent::FeatureUsed {
+                    feature: "Export.DOCX".into(),
+                })
+                .unwrap();
+        }
+        queue
+            .record(TelemetryEvent::AnalysisCompleted { duration_ms: 1500 })
+            .unwrap();
+
+        assert!(queue
+            .record(TelemetryEvent::FeatureUsed {
+                feature: "/home/ana/novela.docx".into()
+            })
+            .is_err());
+        assert_eq!(queue.feature_counts.get("export.docx"), Some(&2));
+        assert_eq!(queue.analysis_durations_secs, vec![2]);
+    }
+
+    #[test]
+    fn crashes_are_counted_once() {
+        let mut queue = TelemetryQueue::default();
+        let crashes = [(100, CrashKind::Panic), (200, CrashKind::RuntimeError)];
+
+        assert!(queue.record_crashes(&crashes));
+        assert!(!queue.record_crashes(&crashes));
+        queue.clear();
+        assert!(queue.is_empty());
+        assert!(!queue.record_crashes(&crashes));
+        assert!(queue.record_crashes(&[(300, CrashKind::Panic)]));
+        assert_eq!(queue.crash_counts.get("panic"), Some(&1));
+    }
+
+    #[test]
+    fn failed_batch_is_merged_back() {
+        let mut sent = TelemetryQueue::default();
+        sent.record(TelemetryEvent::FeatureUsed {
+            feature: "analysis.run".into(),
+        })
+        .unwrap();
+        let mut pending = TelemetryQueue::default();
+        pending
+            .record(TelemetryEvent::FeatureUsed {
+                feature: "analysis.run".into(),
+            })
+            .unwrap();
+
+        pending.merge(sent);
+
+        assert_eq!(pending.feature_counts.get("analysis.run"), Some(&2));
+    }
+
+    #[test]
+    fn queue_persists_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(TELEMETRY_FILE_NAME);
+        let state = TelemetryState::load(path.clone());
+        state
+            .queue
+            .lock()
+            .unwrap()
+            .record(TelemetryEvent::FeatureUsed {
+                feature: "view.timeline".into(),
+            })
+            .unwrap();
+        state.persist();
+
+        let reloaded = TelemetryState::load(path);
+        assert_eq!(
+            reloaded
+                .queue
+                .lock()
+                .unwrap()
+                .feature_counts
+                .get("view.timeline"),
+            Some(&1)
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// Detección de la codificación de manuscritos en texto plano en Narrative Assistant
+//
+// Muchos `.txt` de autores españoles salen de exportaciones antiguas de Word
+// o del Bloc de notas en Windows-1252/ISO-8859-1 o en UTF-16, y el backend los
+// leía como UTF-8 con las tildes rotas ("canciÃ³n"). Antes de importar un
+// `.txt` o `.md` el shell detecta la codificación y, si no es UTF-8, guarda
+// una copia transcodificada en `<datos>/documents` que es la que se importa.
+//
+// - BOM de UTF-8 o UTF-16 (LE/BE): manda el BOM.
+// - UTF-16 sin BOM: se reconoce por los bytes nulos alternos del texto latino.
+// - UTF-8 válido: se deja tal cual.
+// - Resto: chardetng, con preferencia por las codificaciones del español
+//   (`.es`). ISO-8859-1 se lee como Windows-1252, su superconjunto, como hacen
+//   los navegadores.
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+use crate::drop_import;
+use crate::settings::SettingsStore;
+
+/// Bytes que se examinan para detectar UTF-16 sin BOM
+const UTF16_SNIFF_BYTES: usize = 4096;
+
+/// Resultado de `normalize_text_import`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct TextImport {
+    /// Archivo que se importa: el original o su copia en UTF-8
+    pub path: String,
+    /// Codificación detectada (nombre WHATWG, p. ej. "windows-1252")
+    pub encoding: String,
+    /// Si se ha guardado una copia transcodificada
+    pub transcoded: bool,
+}
+
+/// UTF-16 sin BOM: en texto latino uno de cada dos bytes es nulo
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(UTF16_SNIFF_BYTES) & !1];
+    if sample.len() < 4 {
+        return None;
+    }
+    let pairs = sample.len() / 2;
+    let nulls_at = |offset: usize| {
+        sample
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|b| **b == 0)
+            .count()
+    };
+    let (even, odd) = (nulls_at(0), nulls_at(1));
+    if odd * 10 >= pairs * 7 && even * 10 < pairs {
+        Some(UTF_16LE)
+    } else if even * 10 >= pairs * 7 && odd * 10 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Codificación de un texto
+pub(crate) fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if let Some(encoding) = sniff_utf16(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(Some(b"es"), true)
+}
+
+/// Texto en UTF-8 (sin BOM) y codificación detectada
+pub(crate) fn decode(bytes: &[u8]) -> (String, &'static Encoding) {
+    let encoding = detect(bytes);
+    // `decode_with_bom_removal` quita el BOM de la codificación detectada
+    let (text, _) = encoding.decode_with_bom_removal(bytes);
+    (text.into_owned(), encoding)
+}
+
+/// Si `bytes` no es UTF-8, el texto transcodificado y la codificación original
+pub(crate) fn transcode(bytes: &[u8]) -> Option<(String, &'static Encoding)> {
+    let (text, encoding) = decode(bytes);
+    (encoding != UTF_8).then_some((text, encoding))
+}
+
+/// Detecta la codificación de un `.txt`/`.md` y, si no es UTF-8, guarda una copia en UTF-8
+#[tauri::command]
+pub async fn normalize_text_import(
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<TextImport, String> {
+    let documents = drop_import::documents_dir(&store.get())
+        .ok_or_else(|| "No se encontro el directorio de datos".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = Path::new(&path);
+        let bytes = fs::read(source).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        let Some((text, encoding)) = transcode(&bytes) else {
+            return Ok(TextImport {
+                path,
+                encoding: UTF_8.name().to_string(),
+                transcoded: false,
+            });
+        };
+        fs::create_dir_all(&documents)
+            .map_err(|e| format!("No se pudo crear {}: {}", documents.display(), e))?;
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "manuscrito.txt".into());
+        let target = documents.join(format!("{}_{}", uuid::Uuid::new_v4().simple(), name));
+        fs::write(&target, text).map_err(|e| format!("No se pudo guardar la copia: {}", e))?;
+        tracing::info!(
+            target: "text_encoding",
+            "{} en {} transcodificado a {}",
+            path,
+            encoding.name(),
+            target.display()
+        );
+        Ok(TextImport {
+            path: target.to_string_lossy().into_owned(),
+            encoding: encoding.name().to_string(),
+            transcoded: true,
+        })
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de codificacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spanish_text_is_decoded_from_common_encodings() {
+        let text = "—¿Adónde vas, Begoña? —preguntó la señora desde el balcón.\n\
+                    Él no contestó: siguió calle abajo, pensando en la canción.\n";
+
+        let (cp1252, _, _) = encoding_rs::WINDOWS_1252.encode(text);
+        assert_eq!(detect(&cp1252), encoding_rs::WINDOWS_1252);
+        assert_eq!(decode(&cp1252).0, text);
+
+        let utf16le: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert_eq!(decode(&utf16le), (text.to_string(), UTF_16LE));
+        let mut with_bom = vec![0xFE, 0xFF];
+        with_bom.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        assert_eq!(decode(&with_bom), (text.to_string(), UTF_16BE));
+
+        let mut utf8 = b"\xEF\xBB\xBF".to_vec();
+        utf8.extend(text.as_bytes());
+        assert_eq!(decode(&utf8), (text.to_string(), UTF_8));
+        assert_eq!(transcode(text.as_bytes()), None);
+        assert_eq!(
+            transcode(&cp1252).map(|(_, e)| e),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+    }
+}
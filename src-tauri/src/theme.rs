@@ -0,0 +1,129 @@
+// Tema del sistema de Narrative Assistant
+//
+// El shell lee la preferencia clara/oscura del sistema al arrancar
+// (`get_system_theme`) y emite `system-theme-changed` cuando cambia, con
+// lo que el modo "auto" del frontend no depende de `prefers-color-scheme`
+// del webview, que no todos los webviews de Linux actualizan.
+//
+// `set_window_theme` aplica a todas las ventanas el tema elegido (marcos,
+// barras de título y menús nativos) y lo guarda en `settings.theme`. Con un
+// tema forzado, los `ThemeChanged` de las ventanas son los del tema forzado
+// y se ignoran; la preferencia del sistema se vuelve a leer al quitarlo.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::settings::{self, SettingsStore};
+use crate::windows::MAIN_WINDOW;
+
+/// Estado del tema, registrado con `.manage()`
+pub struct ThemeState {
+    /// Preferencia del sistema
+    system: Mutex<tauri::Theme>,
+    /// Tema forzado en las ventanas (`None` = el del sistema)
+    forced: Mutex<Option<tauri::Theme>>,
+}
+
+impl ThemeState {
+    pub fn new() -> Self {
+        Self {
+            system: Mutex::new(tauri::Theme::Light),
+            forced: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct SystemThemePayload {
+    theme: tauri::Theme,
+}
+
+/// Tema nativo de las ventanas para una preferencia
+fn forced_theme(theme: settings::Theme) -> Option<tauri::Theme> {
+    match theme {
+        settings::Theme::System => None,
+        settings::Theme::Light => Some(tauri::Theme::Light),
+        settings::Theme::Dark => Some(tauri::Theme::Dark),
+    }
+}
+
+fn apply(app: &AppHandle, theme: settings::Theme) {
+    let forced = forced_theme(theme);
+    // Antes de cambiarlo: los ThemeChanged que provoque no son del sistema
+    *app.state::<ThemeState>().forced.lock().unwrap() = forced;
+    app.set_theme(forced);
+}
+
+/// Lee y emite el tema del sistema y aplica el de las preferencias
+pub fn setup(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        if let Ok(theme) = window.theme() {
+            *app.state::<ThemeState>().system.lock().unwrap() = theme;
+            tracing::info!(target: "theme", "Tema del sistema: {}", theme);
+            let _ = app.emit("system-theme-changed", SystemThemePayload { theme });
+        }
+    }
+    apply(app, app.state::<SettingsStore>().get().theme);
+}
+
+/// `WindowEvent::ThemeChanged`: con el tema sin forzar, es un cambio del sistema
+pub fn on_theme_changed(app: &AppHandle, theme: tauri::Theme) {
+    let state = app.state::<ThemeState>();
+    if state.forced.lock().unwrap().is_some() {
+        return;
+    }
+    let mut system = state.system.lock().unwrap();
+    // Cada ventana recibe el evento; se emite una sola vez
+    if *system == theme {
+        return;
+    }
+    *system = theme;
+    tracing::info!(target: "theme", "Tema del sistema cambiado: {}", theme);
+    let _ = app.emit("system-theme-changed", SystemThemePayload { theme });
+}
+
+/// Preferencia clara/oscura del sistema ("light" | "dark")
+#[tauri::command]
+pub fn get_system_theme(state: State<'_, ThemeState>) -> tauri::Theme {
+    *state.system.lock().unwrap()
+}
+
+/// Aplica el tema a las ventanas nativas y lo guarda en las preferencias
+#[tauri::command]
+pub fn set_window_theme(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    theme: settings::Theme,
+) -> Result<(), String> {
+    apply(&app, theme);
+    if store.get().theme != theme {
+        let updated = store.update(serde_json::json!({ "theme": theme }))?;
+        let _ = app.emit("shell-settings-changed", &updated);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_preference_does_not_force_window_theme() {
+        assert_eq!(forced_theme(settings::Theme::System), None);
+        assert_eq!(
+            forced_theme(settings::Theme::Dark),
+            Some(tauri::Theme::Dark)
+        );
+        assert_eq!(
+            forced_theme(settings::Theme::Light),
+            Some(tauri::Theme::Light)
+        );
+
+        let payload = serde_json::to_value(SystemThemePayload {
+            theme: tauri::Theme::Dark,
+        })
+        .unwrap();
+        assert_eq!(payload, serde_json::json!({ "theme": "dark" }));
+    }
+}
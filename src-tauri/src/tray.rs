@@ -0,0 +1,133 @@
+// Icono de bandeja del sistema para Narrative Assistant
+//
+// Muestra el estado del backend (a partir de los eventos del tema `backend` que
+// publican el arranque y el watchdog en status_channel.rs) y permite mantener la app en segundo plano:
+// si la preferencia `minimize_to_tray` está activa, cerrar la ventana solo la
+// oculta y el backend sigue procesando los análisis en cola.
+
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Listener, Manager,
+};
+
+use crate::status_channel;
+
+/// Id del icono de bandeja
+const TRAY_ID: &str = "main";
+
+/// Ids de los elementos del menú de la bandeja
+const STATUS_ITEM: &str = "tray_status";
+const SHOW_ITEM: &str = "tray_show";
+const QUIT_ITEM: &str = "tray_quit";
+
+const APP_NAME: &str = "Narrative Assistant";
+
+/// Texto para cada estado del backend (`status` de `i18n::status_payload`)
+fn status_label(status: &str) -> &'static str {
+    match status {
+        "running" => "Servidor activo",
+        "starting" => "Iniciando servidor...",
+        "restarting" => "Reiniciando servidor...",
+        "error" => "Servidor detenido por un error",
+        _ => "Estado del servidor desconocido",
+    }
+}
+
+/// Si el icono de bandeja existe (puede fallar en escritorios Linux sin soporte)
+pub fn is_available(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+/// Muestra y enfoca la ventana principal
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Crea el icono de bandeja y lo suscribe al estado del backend
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let status_item = MenuItem::with_id(
+        app,
+        STATUS_ITEM,
+        status_label("starting"),
+        false,
+        None::<&str>,
+    )?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(
+                app,
+                SHOW_ITEM,
+                "Mostrar Narrative Assistant",
+                true,
+                None::<&str>,
+            )?,
+            &MenuItem::with_id(app, QUIT_ITEM, "Salir", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(APP_NAME)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            SHOW_ITEM => show_main_window(app),
+            QUIT_ITEM => {
+                crate::shutdown_backend(app);
+                crate::updater::install_staged_on_exit(app);
+                app.exit(0);
+            }
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    let tray_handle = app.clone();
+    app.listen_any(status_channel::STATUS_EVENT, move |event| {
+        let Some(status) = serde_json::from_str::<serde_json::Value>(event.payload())
+            .ok()
+            .filter(|v| v["topic"] == "backend")
+            .and_then(|v| v["payload"]["status"].as_str().map(String::from))
+        else {
+            return;
+        };
+        let label = status_label(&status);
+        let _ = status_item.set_text(label);
+        if let Some(tray) = tray_handle.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(format!("{} — {}", APP_NAME, label)));
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_labels_cover_backend_statuses() {
+        for status in ["running", "starting", "restarting", "error"] {
+            assert_ne!(status_label(status), status_label("unknown"));
+        }
+    }
+}
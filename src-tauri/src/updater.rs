@@ -0,0 +1,522 @@
+// Actualizaciones de Narrative Assistant
+//
+// Comprueba la última release publicada en GitHub, descarga el instalador de la
+// plataforma con eventos de progreso y verifica su firma minisign (formato de
+// `cargo tauri signer sign`) antes de dejarlo preparado. La instalación se hace
+// al salir de la app (o al pulsar "Reiniciar e instalar"), de modo que la nueva
+// versión arranca en el siguiente inicio. La firma se guarda junto al
+// instalador y se vuelve a verificar justo antes de lanzarlo: entre sesiones
+// cualquiera con acceso al directorio de descargas podría haberlo sustituido.
+//
+// La clave pública se fija en compilación con NA_UPDATER_PUBKEY. Sin clave, la
+// app avisa de las nuevas versiones pero no instala nada que no pueda verificar.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::clock::now_secs;
+use crate::settings::SettingsStore;
+use crate::{network, proxy};
+
+/// Última release publicada (excluye borradores y pre-releases)
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/pauubach/narrassist/releases/latest";
+
+/// Clave pública minisign (base64, como la genera `cargo tauri signer generate`)
+const UPDATER_PUBKEY: Option<&str> = option_env!("NA_UPDATER_PUBKEY");
+
+/// Tiempo máximo de las consultas a GitHub (la descarga del instalador no tiene límite)
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Extensión de las firmas publicadas junto a cada instalador
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// Fichero que recuerda la actualización preparada entre reinicios
+const STAGED_FILE: &str = "staged.json";
+
+/// Periodo de la comprobación automática (opt-in en preferencias)
+const WEEKLY_CHECK_INTERVAL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Cada cuánto se reevalúa si toca comprobar
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Directorio de descargas de actualizaciones (~/.narrative_assistant/updates)
+fn updates_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".narrative_assistant").join("updates"))
+}
+
+pub(crate) fn configured_pubkey() -> Option<&'static str> {
+    UPDATER_PUBKEY.map(str::trim).filter(|k| !k.is_empty())
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    body: Option<String>,
+    published_at: Option<String>,
+    assets: Vec<GithubAsset>,
+}
+
+/// Actualización disponible, tal como se muestra en la UI
+#[derive(Serialize, Clone, Debug)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub published_at: Option<String>,
+    /// Página de la release (descarga manual)
+    pub release_url: String,
+    /// Instalador para esta plataforma, si lo hay
+    pub asset_name: Option<String>,
+    pub asset_size: Option<u64>,
+    /// Si se puede descargar, verificar e instalar desde la app
+    pub can_install: bool,
+}
+
+/// Instalador descargado y verificado, pendiente de instalar
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct StagedUpdate {
+    version: String,
+    installer: PathBuf,
+}
+
+impl StagedUpdate {
+    /// Firma descargada junto al instalador (`<instalador>.sig`)
+    fn signature_path(&self) -> PathBuf {
+        let mut path = self.installer.clone().into_os_string();
+        path.push(SIGNATURE_SUFFIX);
+        PathBuf::from(path)
+    }
+}
+
+/// Estado del actualizador, registrado con `.manage()`
+#[derive(Default)]
+pub struct UpdaterState {
+    /// Release encontrada en la última comprobación
+    release: Mutex<Option<GithubRelease>>,
+    staged: Mutex<Option<StagedUpdate>>,
+}
+
+impl UpdaterState {
+    /// Carga la actualización preparada en una sesión anterior.
+    /// Si ya está instalada (versión <= actual), limpia las descargas.
+    pub fn load(current_version: &str) -> Self {
+        let staged = updates_dir().and_then(|dir| {
+            let raw = fs::read_to_string(dir.join(STAGED_FILE)).ok()?;
+            let staged: StagedUpdate = serde_json::from_str(&raw).ok()?;
+            // La firma se comprueba al lanzarlo; aquí solo se descarta lo incompleto
+            if is_newer(&staged.version, current_version)
+                && staged.installer.is_file()
+                && staged.signature_path().is_file()
+            {
+                Some(staged)
+            } else {
+                let _ = fs::remove_dir_all(&dir);
+                None
+            }
+        });
+        Self {
+            release: Mutex::new(None),
+            staged: Mutex::new(staged),
+        }
+    }
+}
+
+/// Componentes numéricos de una versión ("v0.11.15" -> [0, 11, 15]).
+/// Ignora sufijos de pre-release ("1.2.0-beta.1" -> [1, 2, 0]).
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Si `candidate` es posterior a `current`
+fn is_newer(candidate: &str, current: &str) -> bool {
+    let (mut a, mut b) = (parse_version(candidate), parse_version(current));
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a > b
+}
+
+/// Sufijo del instalador y etiqueta de arquitectura para esta plataforma
+fn platform_asset_pattern() -> Option<(&'static str, &'static str)> {
+    let arch = if cfg!(target_arch = "aarch64") {
+        if cfg!(target_os = "windows") {
+            "arm64"
+        } else {
+            "aarch64"
+        }
+    } else {
+        "x64"
+    };
+    if cfg!(target_os = "windows") {
+        Some(("-setup.exe", arch))
+    } else if cfg!(target_os = "macos") {
+        Some((".dmg", arch))
+    } else {
+        None
+    }
+}
+
+/// Elige el instalador de la plataforma; si solo hay uno con la extensión, lo usa
+fn select_asset<'a>(
+    assets: &'a [GithubAsset],
+    suffix: &str,
+    arch: &str,
+) -> Option<&'a GithubAsset> {
+    let candidates: Vec<&GithubAsset> =
+        assets.iter().filter(|a| a.name.ends_with(suffix)).collect();
+    candidates
+        .iter()
+        .find(|a| a.name.contains(arch))
+        .or_else(|| (candidates.len() == 1).then(|| &candidates[0]))
+        .copied()
+}
+
+/// Firma que acompaña a un instalador
+fn signature_asset<'a>(assets: &'a [GithubAsset], installer: &str) -> Option<&'a GithubAsset> {
+    let name = format!("{}{}", installer, SIGNATURE_SUFFIX);
+    assets.iter().find(|a| a.name == name)
+}
+
+/// Verifica una firma en formato Tauri (base64 del fichero minisign)
+pub(crate) fn verify_signature(
+    pubkey_b64: &str,
+    signature_b64: &str,
+    data: &[u8],
+) -> Result<(), String> {
+    let decode = |value: &str| -> Result<String, String> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(value.trim())
+            .map_err(|e| format!("base64 no valido: {}", e))?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    };
+    let pubkey = minisign_verify::PublicKey::decode(&decode(pubkey_b64)?)
+        .map_err(|e| format!("Clave publica no valida: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&decode(signature_b64)?)
+        .map_err(|e| format!("Firma no valida: {}", e))?;
+    pubkey
+        .verify(data, &signature, true)
+        .map_err(|e| format!("La firma del instalador no coincide: {}", e))
+}
+
+fn update_info(release: &GithubRelease, current_version: &str) -> UpdateInfo {
+    let asset = platform_asset_pattern()
+        .and_then(|(suffix, arch)| select_asset(&release.assets, suffix, arch));
+    let can_install = configured_pubkey().is_some()
+        && asset.is_some_and(|a| signature_asset(&release.assets, &a.name).is_some());
+    UpdateInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        current_version: current_version.to_string(),
+        notes: release.body.clone(),
+        published_at: release.published_at.clone(),
+        release_url: release.html_url.clone(),
+        asset_name: asset.map(|a| a.name.clone()),
+        asset_size: asset.map(|a| a.size),
+        can_install,
+    }
+}
+
+/// Consulta GitHub y guarda la release si es más nueva que la versión actual
+async fn check_latest(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let current_version = app.package_info().version.to_string();
+    let release: GithubRelease = proxy::http_client(app)
+        .await?
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .timeout(API_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("No se pudo consultar la ultima version: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta de GitHub no valida: {}", e))?;
+
+    let _ = app
+        .state::<SettingsStore>()
+        .update(serde_json::json!({ "last_update_check": now_secs() }));
+
+    if !is_newer(&release.tag_name, &current_version) {
+        *app.state::<UpdaterState>().release.lock().unwrap() = None;
+        return Ok(None);
+    }
+    let info = update_info(&release, &current_version);
+    *app.state::<UpdaterState>().release.lock().unwrap() = Some(release);
+    Ok(Some(info))
+}
+
+/// Comprueba si hay una versión nueva. `None` si la instalada es la última.
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check_latest(&app).await
+}
+
+/// Descarga `url` en `path` emitiendo `update-download-progress`
+async fn download_with_progress(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+) -> Result<(), String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Error descargando la actualizacion: {}", e))?;
+    let total = response.content_length();
+    let mut file =
+        fs::File::create(path).map_err(|e| format!("Error creando {}: {}", path.display(), e))?;
+    let mut downloaded: u64 = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Error descargando la actualizacion: {}", e))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))?;
+        downloaded += chunk.len() as u64;
+        let _ = app.emit(
+            "update-download-progress",
+            serde_json::json!({ "downloaded": downloaded, "total": total }),
+        );
+    }
+    file.sync_all().map_err(|e| e.to_string())
+}
+
+/// Descarga y verifica el instalador de la última comprobación y lo deja preparado
+/// para instalarse al salir. Emite `update-ready` al terminar.
+#[tauri::command]
+pub async fn download_update(app: AppHandle) -> Result<String, String> {
+    let pubkey = configured_pubkey()
+        .ok_or("Esta version no puede verificar actualizaciones: descarguela desde la web")?;
+    let release = app
+        .state::<UpdaterState>()
+        .release
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("No hay ninguna actualizacion pendiente: compruebe primero")?;
+    let (suffix, arch) = platform_asset_pattern().ok_or("Plataforma sin instalador")?;
+    let asset = select_asset(&release.assets, suffix, arch)
+        .ok_or("La release no incluye instalador para esta plataforma")?;
+    let signature = signature_asset(&release.assets, &asset.name)
+        .ok_or("La release no incluye la firma del instalador")?;
+
+    let dir = updates_dir().ok_or("No se pudo determinar el directorio home")?;
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Error creando {}: {}", dir.display(), e))?;
+    let installer = dir.join(&asset.name);
+
+    let client = proxy::http_client(&app).await?;
+    let signature_b64 = client
+        .get(&signature.browser_download_url)
+        .timeout(API_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Error descargando la firma: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Error descargando la firma: {}", e))?;
+    download_with_progress(&app, &client, &asset.browser_download_url, &installer).await?;
+
+    let bytes = fs::read(&installer).map_err(|e| e.to_string())?;
+    if let Err(e) = verify_signature(pubkey, &signature_b64, &bytes) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+
+    let staged = StagedUpdate {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        installer,
+    };
+    fs::write(staged.signature_path(), &signature_b64).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&staged).map_err(|e| e.to_string())?;
+    fs::write(dir.join(STAGED_FILE), json).map_err(|e| e.to_string())?;
+    tracing::info!(target: "updater", "Actualizacion {} preparada", staged.version);
+    let _ = app.emit("update-ready", &staged.version);
+    let version = staged.version.clone();
+    *app.state::<UpdaterState>().staged.lock().unwrap() = Some(staged);
+    Ok(version)
+}
+
+/// Vuelve a verificar el instalador preparado con su firma guardada
+fn verify_staged(staged: &StagedUpdate, pubkey: Option<&str>) -> Result<(), String> {
+    let pubkey = pubkey.ok_or("Esta version no puede verificar actualizaciones")?;
+    let signature_path = staged.signature_path();
+    let signature_b64 = fs::read_to_string(&signature_path)
+        .map_err(|e| format!("Error leyendo {}: {}", signature_path.display(), e))?;
+    let bytes = fs::read(&staged.installer)
+        .map_err(|e| format!("Error leyendo {}: {}", staged.installer.display(), e))?;
+    verify_signature(pubkey, &signature_b64, &bytes)
+}
+
+/// Lanza el instalador preparado (si lo hay) tras verificar de nuevo su firma.
+/// Devuelve si se lanzó.
+fn launch_installer(staged: &StagedUpdate) -> bool {
+    if let Err(e) = verify_staged(staged, configured_pubkey()) {
+        tracing::error!(
+            target: "updater",
+            "Instalador preparado rechazado ({}): {}",
+            staged.installer.display(),
+            e
+        );
+        if let Some(dir) = updates_dir() {
+            let _ = fs::remove_dir_all(dir);
+        }
+        return false;
+    }
+    let result = if cfg!(target_os = "macos") {
+        // El DMG se abre para que el usuario arrastre la app a Aplicaciones
+        std::process::Command::new("open")
+            .arg(&staged.installer)
+            .spawn()
+    } else {
+        std::process::Command::new(&staged.installer).spawn()
+    };
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::error!(target: "updater", "No se pudo lanzar el instalador: {}", e);
+            false
+        }
+    }
+}
+
+/// Instala la actualización preparada al cerrar la app (instalación en el siguiente arranque)
+pub fn install_staged_on_exit(app: &AppHandle) {
+    if let Some(staged) = app.state::<UpdaterState>().staged.lock().unwrap().take() {
+        tracing::info!(target: "updater", "Instalando actualizacion {} al salir", staged.version);
+        launch_installer(&staged);
+    }
+}
+
+/// Detiene el backend, lanza el instalador preparado y cierra la app
+#[tauri::command]
+pub fn install_update(app: AppHandle, state: State<'_, UpdaterState>) -> Result<(), String> {
+    let staged = state
+        .staged
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("No hay ninguna actualizacion descargada")?;
+    crate::shutdown_backend(&app);
+    if !launch_installer(&staged) {
+        *state.staged.lock().unwrap() = Some(staged);
+        return Err("No se pudo lanzar el instalador".into());
+    }
+    app.exit(0);
+    Ok(())
+}
+
+/// Comprobación semanal en segundo plano (si está activada en preferencias).
+/// Emite `update-available` con la `UpdateInfo` encontrada.
+pub async fn background_checks(app: AppHandle) {
+    loop {
+        let settings = app.state::<SettingsStore>().get();
+        let due = settings
+            .last_update_check
+            .is_none_or(|last| now_secs().saturating_sub(last) >= WEEKLY_CHECK_INTERVAL.as_secs());
+        // Sin conexión se espera al siguiente ciclo en lugar de fallar
+        if settings.check_updates_weekly && due && !network::is_offline(&app) {
+            match check_latest(&app).await {
+                Ok(Some(info)) => {
+                    let _ = app.emit("update-available", &info);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(target: "updater", "Comprobacion semanal fallida: {}", e),
+            }
+        }
+        tokio::time::sleep(BACKGROUND_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> GithubAsset {
+        GithubAsset {
+            name: name.into(),
+            browser_download_url: format!("https://example.invalid/{}", name),
+            size: 1,
+        }
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_newer("v0.11.16", "0.11.15"));
+        assert!(is_newer("0.12", "0.11.15"));
+        assert!(is_newer("v1.0.0", "0.99.99"));
+        assert!(!is_newer("v0.11.15", "0.11.15"));
+        assert!(!is_newer("0.11.15-beta.1", "0.11.15"));
+        assert!(!is_newer("0.9.0", "0.10.0"));
+    }
+
+    #[test]
+    fn selects_installer_for_architecture() {
+        let assets = vec![
+            asset("Narrative Assistant_0.12.0_aarch64.dmg"),
+            asset("Narrative Assistant_0.12.0_x64.dmg"),
+            asset("Narrative Assistant_0.12.0_x64-setup.exe"),
+            asset("Narrative Assistant_0.12.0_x64-setup.exe.sig"),
+        ];
+
+        let dmg = select_asset(&assets, ".dmg", "aarch64").unwrap();
+        assert_eq!(dmg.name, "Narrative Assistant_0.12.0_aarch64.dmg");
+        let exe = select_asset(&assets, "-setup.exe", "arm64").unwrap();
+        assert_eq!(exe.name, "Narrative Assistant_0.12.0_x64-setup.exe");
+        assert!(signature_asset(&assets, &exe.name).is_some());
+        assert!(signature_asset(&assets, &dmg.name).is_none());
+    }
+
+    #[test]
+    fn staged_installer_needs_its_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = StagedUpdate {
+            version: "9.0.0".into(),
+            installer: dir.path().join("Narrative Assistant_9.0.0_x64-setup.exe"),
+        };
+        fs::write(&staged.installer, b"instalador").unwrap();
+        assert_eq!(
+            staged.signature_path(),
+            dir.path()
+                .join("Narrative Assistant_9.0.0_x64-setup.exe.sig")
+        );
+        let key = base64::engine::general_purpose::STANDARD.encode("x");
+        // Sin firma guardada, sin clave o con una firma que no vale, no se lanza
+        assert!(verify_staged(&staged, Some(&key)).is_err());
+        fs::write(staged.signature_path(), &key).unwrap();
+        assert!(verify_staged(&staged, None).is_err());
+        assert!(verify_staged(&staged, Some(&key)).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        assert!(verify_signature("no-base64!", "abc", b"data").is_err());
+        let key = base64::engine::general_purpose::STANDARD.encode("untrusted comment: x\nRWQ=");
+        assert!(verify_signature(&key, &key, b"data").is_err());
+    }
+}
@@ -0,0 +1,336 @@
+// Guía de usuario sin conexión
+//
+// El manual (docs/user-manual, Markdown) se instala como recurso en
+// `user-guide/` y se sirve con el protocolo `guide://` a una ventana propia:
+//   - guide://localhost/             -> README.md (índice)
+//   - guide://localhost/02-first-analysis.html -> 02-first-analysis.md
+// Cada página se convierte a HTML al pedirla, con la lista de capítulos al
+// lado. Los enlaces entre capítulos (.md) pasan a .html; los que apuntan fuera
+// del manual se quedan en texto y los externos (http) se abren en el
+// navegador del sistema.
+//
+// En Windows el webview expone los protocolos propios como
+// http://guide.localhost/ (ver `guide_url`).
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime, Url, WebviewUrl, WebviewWindowBuilder};
+
+/// Protocolo con el que se sirve la guía
+pub const GUIDE_SCHEME: &str = "guide";
+
+/// Label de la ventana de la guía
+pub const GUIDE_WINDOW: &str = "user-guide";
+
+/// Directorio de la guía dentro de los recursos (tauri.conf.json)
+const GUIDE_RESOURCE_DIR: &str = "user-guide";
+
+/// Página de índice del manual
+const INDEX_FILE: &str = "README.md";
+
+/// Si `label` es la ventana de la guía (no guarda geometría ni recibe eventos de menú)
+pub fn is_user_guide(label: &str) -> bool {
+    label == GUIDE_WINDOW
+}
+
+/// Directorio con los .md del manual (el del repositorio en desarrollo)
+fn guide_dir<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    let installed = app
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join(GUIDE_RESOURCE_DIR))
+        .filter(|dir| dir.is_dir());
+    if installed.is_some() || !cfg!(debug_assertions) {
+        return installed;
+    }
+    Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/user-manual"))
+        .filter(|dir| dir.is_dir())
+}
+
+/// URL de una página de la guía (`page` = "" para el índice)
+fn guide_url(page: &str) -> Result<Url, String> {
+    let base = if cfg!(windows) {
+        format!("http://{}.localhost/", GUIDE_SCHEME)
+    } else {
+        format!("{}://localhost/", GUIDE_SCHEME)
+    };
+    Url::parse(&format!("{}{}", base, page)).map_err(|e| e.to_string())
+}
+
+/// Fichero .md de una ruta pedida ("/", "/index.html", "/03-entities.html").
+/// Solo nombres sencillos: nada de subdirectorios ni `..`.
+fn markdown_file(request_path: &str) -> Option<String> {
+    let page = request_path.trim_start_matches('/');
+    if page.is_empty() || page == "index.html" {
+        return Some(INDEX_FILE.into());
+    }
+    let stem = page.strip_suffix(".html")?;
+    let valid = !stem.is_empty()
+        && stem
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| format!("{}.md", stem))
+}
+
+/// Destino de un enlace del manual: .md del manual -> .html, externo tal cual,
+/// `None` si apunta fuera del manual
+fn rewrite_link(dest: &str) -> Option<String> {
+    if dest.starts_with("http://") || dest.starts_with("https://") || dest.starts_with('#') {
+        return Some(dest.into());
+    }
+    let (file, anchor) = dest.split_once('#').unwrap_or((dest, ""));
+    let stem = file.strip_suffix(".md")?;
+    if stem.contains('/') || stem.contains('\\') {
+        return None;
+    }
+    let page = if file == INDEX_FILE {
+        "index".to_string()
+    } else {
+        stem.to_string()
+    };
+    Some(if anchor.is_empty() {
+        format!("{}.html", page)
+    } else {
+        format!("{}.html#{}", page, anchor)
+    })
+}
+
+/// Convierte el Markdown a HTML reescribiendo los enlaces (ver `rewrite_link`)
+fn render_markdown(markdown: &str) -> String {
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    );
+    // Por cada enlace abierto, si se ha conservado (para descartar también su cierre)
+    let mut links = Vec::new();
+    let events = parser.filter_map(|event| match event {
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let rewritten = rewrite_link(&dest_url);
+            links.push(rewritten.is_some());
+            rewritten.map(|dest| {
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url: dest.into(),
+                    title,
+                    id,
+                })
+            })
+        }
+        Event::End(TagEnd::Link) => links.pop().unwrap_or(true).then_some(event),
+        other => Some(other),
+    });
+    let mut body = String::new();
+    html::push_html(&mut body, events);
+    body
+}
+
+fn escape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>']) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+    )
+}
+
+/// Capítulos (fichero .md, título) en orden: los "NN-nombre.md" del directorio
+fn chapters(dir: &Path) -> Vec<(String, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut chapters: Vec<(String, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.ends_with(".md") || !name.starts_with(|c: char| c.is_ascii_digit()) {
+                return None;
+            }
+            let contents = std::fs::read_to_string(entry.path()).ok()?;
+            let title = contents
+                .lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .unwrap_or(&name)
+                .trim()
+                .to_string();
+            Some((name, title))
+        })
+        .collect();
+    chapters.sort();
+    chapters
+}
+
+/// Página completa: lista de capítulos y contenido
+fn render_page(dir: &Path, file: &str, markdown: &str) -> String {
+    let nav: String = std::iter::once((INDEX_FILE.to_string(), "Índice".to_string()))
+        .chain(chapters(dir))
+        .map(|(name, title)| {
+            let href = rewrite_link(&name).unwrap_or_default();
+            let current = if name == file {
+                " aria-current=\"page\""
+            } else {
+                ""
+            };
+            format!(
+                "<li><a href=\"{}\"{}>{}</a></li>",
+                href,
+                current,
+                escape_html(&title)
+            )
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"es\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Guía de usuario - Narrative Assistant</title>\n<style>{}</style>\n</head>\n\
+         <body>\n<nav><ul>{}</ul></nav>\n<main>{}</main>\n</body>\n</html>\n",
+        GUIDE_CSS,
+        nav,
+        render_markdown(markdown)
+    )
+}
+
+const GUIDE_CSS: &str = "\
+:root { color-scheme: light dark; --accent: #3b82f6; --muted: #6b7280; --border: #e5e7eb; }
+@media (prefers-color-scheme: dark) { :root { --border: #374151; --muted: #9ca3af; } }
+body { margin: 0; display: flex; font: 15px/1.6 system-ui, sans-serif; }
+nav { width: 230px; flex-shrink: 0; height: 100vh; position: sticky; top: 0; overflow-y: auto;
+      border-right: 1px solid var(--border); padding: 1rem 0.5rem; box-sizing: border-box; }
+nav ul { list-style: none; margin: 0; padding: 0; }
+nav a { display: block; padding: 0.35rem 0.75rem; border-radius: 6px; color: inherit; text-decoration: none; }
+nav a[aria-current] { background: var(--accent); color: white; }
+main { flex: 1; max-width: 860px; padding: 1.5rem 2.5rem; }
+a { color: var(--accent); }
+table { border-collapse: collapse; } td, th { border: 1px solid var(--border); padding: 0.3rem 0.6rem; }
+code { font-size: 0.9em; } pre { overflow-x: auto; padding: 0.75rem; border: 1px solid var(--border); border-radius: 6px; }
+";
+
+fn response(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body)
+        .unwrap_or_default()
+}
+
+/// Responde a una petición `guide://` (registrado con `register_uri_scheme_protocol`)
+pub fn handle_request<R: Runtime>(
+    app: &AppHandle<R>,
+    request: &Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let not_found = || {
+        response(
+            StatusCode::NOT_FOUND,
+            "text/plain; charset=utf-8",
+            b"Pagina no encontrada".to_vec(),
+        )
+    };
+    let (Some(dir), Some(file)) = (guide_dir(app), markdown_file(request.uri().path())) else {
+        return not_found();
+    };
+    match std::fs::read_to_string(dir.join(&file)) {
+        Ok(markdown) => response(
+            StatusCode::OK,
+            "text/html; charset=utf-8",
+            render_page(&dir, &file, &markdown).into_bytes(),
+        ),
+        Err(_) => not_found(),
+    }
+}
+
+/// Abre `url` en el navegador del sistema
+fn open_in_browser(url: &Url) {
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("rundll32")
+        .args(["url.dll,FileProtocolHandler", url.as_str()])
+        .spawn();
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(url.as_str()).spawn();
+    #[cfg(target_os = "linux")]
+    let result = std::process::Command::new("xdg-open")
+        .arg(url.as_str())
+        .spawn();
+    if let Err(e) = result {
+        tracing::warn!(target: "user_guide", "No se pudo abrir {}: {}", url, e);
+    }
+}
+
+/// Abre la guía (en `chapter`, p. ej. "04-alerts", o en el índice)
+pub(crate) fn open(app: &AppHandle, chapter: Option<&str>) -> Result<(), String> {
+    let page = chapter
+        .and_then(|chapter| markdown_file(&format!("{}.html", chapter)))
+        .and_then(|file| rewrite_link(&file))
+        .unwrap_or_default();
+    let url = guide_url(&page)?;
+
+    if let Some(window) = app.get_webview_window(GUIDE_WINDOW) {
+        if chapter.is_some() {
+            window.navigate(url).map_err(|e| e.to_string())?;
+        }
+        let _ = window.unminimize();
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let window = WebviewWindowBuilder::new(app, GUIDE_WINDOW, WebviewUrl::CustomProtocol(url))
+        .title("Guía de usuario")
+        .inner_size(1100.0, 760.0)
+        .min_inner_size(640.0, 400.0)
+        .on_navigation(|url| {
+            let internal = url.scheme() == GUIDE_SCHEME
+                || url.host_str() == Some(&format!("{}.localhost", GUIDE_SCHEME));
+            if !internal && matches!(url.scheme(), "http" | "https") {
+                open_in_browser(url);
+            }
+            internal
+        })
+        .build()
+        .map_err(|e| format!("No se pudo abrir la guia de usuario: {}", e))?;
+    crate::accessibility::apply_to(&window);
+    Ok(())
+}
+
+/// Abre la guía de usuario en su ventana (menú Ayuda, F1)
+#[tauri::command]
+pub fn open_user_guide(app: AppHandle, chapter: Option<String>) -> Result<(), String> {
+    open(&app, chapter.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_paths_map_to_markdown_files_only() {
+        assert_eq!(markdown_file("/").as_deref(), Some(INDEX_FILE));
+        assert_eq!(markdown_file("/index.html").as_deref(), Some(INDEX_FILE));
+        assert_eq!(
+            markdown_file("/04-alerts.html").as_deref(),
+            Some("04-alerts.md")
+        );
+        assert_eq!(markdown_file("/../secret.html"), None);
+        assert_eq!(markdown_file("/sub/page.html"), None);
+        assert_eq!(markdown_file("/04-alerts.md"), None);
+    }
+
+    #[test]
+    fn links_point_to_chapters_or_are_dropped() {
+        let html = render_markdown(
+            "[Alertas](04-alerts.md) [Indice](README.md#top) [FAQ](../FAQ.md) \
+             [Issues](https://github.com/pauubach/narrassist/issues)",
+        );
+        assert!(html.contains("<a href=\"04-alerts.html\">Alertas</a>"));
+        assert!(html.contains("<a href=\"index.html#top\">Indice</a>"));
+        assert!(html.contains(" FAQ "));
+        assert!(!html.contains("FAQ.md"));
+        assert!(html.contains("href=\"https://github.com/pauubach/narrassist/issues\""));
+    }
+}
@@ -0,0 +1,497 @@
+// Exportación de visualizaciones a SVG/PNG en Narrative Assistant
+//
+// El grafo de relaciones y la cronología solo se podían sacar de la app como
+// captura de pantalla, a la resolución de la ventana. Para dosieres y
+// presentaciones editoriales, `export_visualization` recibe del frontend los
+// datos ya filtrados de la vista (nodos con la posición que les dio
+// vis-network, eventos y marcas del eje) y dibuja un SVG autónomo que se
+// escala sin perder calidad. Si la ruta termina en `.png`, el SVG se rasteriza
+// con resvg al doble de tamaño.
+//
+// El shell no calcula layouts: si a un nodo le faltan coordenadas se coloca
+// en un círculo, que basta para grafos pequeños.
+
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+use crate::export_scope::ExportGrants;
+
+/// Margen alrededor del dibujo, en px
+const MARGIN: f64 = 48.0;
+
+/// Radio de un nodo sin tamaño
+const DEFAULT_NODE_RADIUS: f64 = 12.0;
+
+/// Escala del PNG respecto al SVG
+const PNG_SCALE: f32 = 2.0;
+
+/// Caracteres de una etiqueta de evento antes de recortarla
+const MAX_EVENT_LABEL_CHARS: usize = 40;
+
+const FONT_FAMILY: &str = "Segoe UI, Helvetica, Arial, sans-serif";
+const DEFAULT_NODE_COLOR: &str = "#6366f1";
+const DEFAULT_EDGE_COLOR: &str = "#94a3b8";
+const TEXT_COLOR: &str = "#1f2937";
+const MUTED_COLOR: &str = "#6b7280";
+
+/// Visualización que se exporta
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VisualizationKind {
+    Graph,
+    Timeline,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub color: Option<String>,
+    /// Radio en px
+    pub size: Option<f64>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub width: Option<f64>,
+    #[serde(default)]
+    pub dashed: bool,
+}
+
+/// Grafo de relaciones tal como se ve en la vista
+#[derive(Deserialize, Clone, Debug)]
+pub struct GraphData {
+    pub title: Option<String>,
+    pub nodes: Vec<GraphNode>,
+    #[serde(default)]
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TimelineItem {
+    pub label: String,
+    /// Texto secundario, p. ej. "Cap. 3 · 12/05/1936"
+    pub detail: Option<String>,
+    /// Posición en el eje (capítulo, día relativo...)
+    pub position: f64,
+    pub color: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TimelineTick {
+    pub position: f64,
+    pub label: String,
+}
+
+/// Cronología: eventos sobre un eje horizontal
+#[derive(Deserialize, Clone, Debug)]
+pub struct TimelineData {
+    pub title: Option<String>,
+    pub events: Vec<TimelineItem>,
+    #[serde(default)]
+    pub ticks: Vec<TimelineTick>,
+}
+
+/// Resultado de `export_visualization`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ExportedVisualization {
+    pub path: String,
+    /// Tamaño del archivo generado, en px
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Documento SVG con su tamaño
+struct Svg {
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+impl Svg {
+    fn new(width: f64, height: f64) -> Self {
+        Self {
+            width: width.ceil(),
+            height: height.ceil(),
+            body: String::new(),
+        }
+    }
+
+    fn text(&mut self, x: f64, y: f64, size: f64, color: &str, anchor: &str, text: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{:.1}" y="{:.1}" font-size="{}" fill="{}" text-anchor="{}">{}</text>"#,
+            x,
+            y,
+            size,
+            escape(color),
+            anchor,
+            escape(text)
+        );
+    }
+
+    fn title(&mut self, title: Option<&str>) {
+        if let Some(title) = title.filter(|t| !t.trim().is_empty()) {
+            let x = self.width / 2.0;
+            self.text(x, MARGIN * 0.6, 18.0, TEXT_COLOR, "middle", title.trim());
+        }
+    }
+
+    fn finish(self) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"{font}\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n{body}</svg>\n",
+            w = self.width,
+            h = self.height,
+            font = FONT_FAMILY,
+            body = self.body
+        )
+    }
+}
+
+/// Posiciones de los nodos; los que no tienen se reparten en un círculo
+fn node_positions(nodes: &[GraphNode]) -> Vec<(f64, f64)> {
+    let missing = nodes
+        .iter()
+        .filter(|n| n.x.is_none() || n.y.is_none())
+        .count();
+    let radius = (missing as f64 * 40.0 / (2.0 * PI)).max(120.0);
+    let mut placed = 0;
+    nodes
+        .iter()
+        .map(|node| match (node.x, node.y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => {
+                let angle = 2.0 * PI * placed as f64 / missing as f64 - PI / 2.0;
+                placed += 1;
+                (radius * angle.cos(), radius * angle.sin())
+            }
+        })
+        .collect()
+}
+
+fn render_graph(data: &GraphData) -> Result<String, String> {
+    if data.nodes.is_empty() {
+        return Err("El grafo no tiene nodos".into());
+    }
+    let positions = node_positions(&data.nodes);
+    let radius = |node: &GraphNode| node.size.unwrap_or(DEFAULT_NODE_RADIUS).max(2.0);
+    // Caja del dibujo, con sitio para la etiqueta bajo cada nodo
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for (node, (x, y)) in data.nodes.iter().zip(&positions) {
+        let r = radius(node);
+        let half_label = (node.label.chars().count() as f64 * 3.5).max(r);
+        min_x = min_x.min(x - half_label);
+        max_x = max_x.max(x + half_label);
+        min_y = min_y.min(y - r);
+        max_y = max_y.max(y + r + 18.0);
+    }
+    let top = if data.title.is_some() {
+        MARGIN * 1.2
+    } else {
+        MARGIN
+    };
+    let offset = (MARGIN - min_x, top - min_y);
+    let mut svg = Svg::new(max_x - min_x + 2.0 * MARGIN, max_y - min_y + top + MARGIN);
+    svg.title(data.title.as_deref());
+
+    let index = |id: &str| data.nodes.iter().position(|n| n.id == id);
+    for edge in &data.edges {
+        let (Some(from), Some(to)) = (index(&edge.source), index(&edge.target)) else {
+            continue;
+        };
+        let (x1, y1) = (positions[from].0 + offset.0, positions[from].1 + offset.1);
+        let (x2, y2) = (positions[to].0 + offset.0, positions[to].1 + offset.1);
+        let _ = writeln!(
+            svg.body,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{:.1}"{}/>"#,
+            x1,
+            y1,
+            x2,
+            y2,
+            escape(edge.color.as_deref().unwrap_or(DEFAULT_EDGE_COLOR)),
+            edge.width.unwrap_or(1.5),
+            if edge.dashed {
+                r#" stroke-dasharray="6 4""#
+            } else {
+                ""
+            }
+        );
+        if let Some(label) = edge.label.as_deref().filter(|l| !l.is_empty()) {
+            svg.text(
+                (x1 + x2) / 2.0,
+                (y1 + y2) / 2.0 - 4.0,
+                10.0,
+                MUTED_COLOR,
+                "middle",
+                label,
+            );
+        }
+    }
+    for (node, (x, y)) in data.nodes.iter().zip(&positions) {
+        let (x, y, r) = (x + offset.0, y + offset.1, radius(node));
+        let _ = writeln!(
+            svg.body,
+            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}" stroke="#ffffff" stroke-width="2"/>"##,
+            x,
+            y,
+            r,
+            escape(node.color.as_deref().unwrap_or(DEFAULT_NODE_COLOR))
+        );
+        svg.text(x, y + r + 14.0, 12.0, TEXT_COLOR, "middle", &node.label);
+    }
+    Ok(svg.finish())
+}
+
+fn truncate(label: &str) -> String {
+    if label.chars().count() <= MAX_EVENT_LABEL_CHARS {
+        return label.to_string();
+    }
+    let cut: String = label.chars().take(MAX_EVENT_LABEL_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn render_timeline(data: &TimelineData) -> Result<String, String> {
+    if data.events.is_empty() {
+        return Err("La cronologia no tiene eventos".into());
+    }
+    let mut events: Vec<&TimelineItem> = data.events.iter().collect();
+    events.sort_by(|a, b| a.position.total_cmp(&b.position));
+    let positions = events
+        .iter()
+        .map(|e| e.position)
+        .chain(data.ticks.iter().map(|t| t.position));
+    let (min, max) = positions.fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p), hi.max(p)));
+    // Unos 70 px por evento para que las etiquetas escalonadas no se pisen
+    let span = (events.len() as f64 * 70.0).max(800.0);
+    let x_of = |position: f64| {
+        if max > min {
+            MARGIN * 2.0 + (position - min) / (max - min) * span
+        } else {
+            MARGIN * 2.0 + span / 2.0
+        }
+    };
+    // Tres niveles de etiquetas arriba y abajo del eje
+    let level_height = 34.0;
+    let top = if data.title.is_some() {
+        MARGIN * 1.2
+    } else {
+        MARGIN
+    };
+    let axis_y = top + level_height * 3.0 + 20.0;
+    let mut svg = Svg::new(span + MARGIN * 4.0, axis_y * 2.0 - top + MARGIN);
+    svg.title(data.title.as_deref());
+
+    let _ = writeln!(
+        svg.body,
+        r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="2"/>"#,
+        MARGIN,
+        axis_y,
+        svg.width - MARGIN,
+        axis_y,
+        DEFAULT_EDGE_COLOR
+    );
+    for tick in &data.ticks {
+        let x = x_of(tick.position);
+        let _ = writeln!(
+            svg.body,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="1"/>"#,
+            x,
+            axis_y - 5.0,
+            x,
+            axis_y + 5.0,
+            DEFAULT_EDGE_COLOR
+        );
+        svg.text(x, axis_y + 18.0, 10.0, MUTED_COLOR, "middle", &tick.label);
+    }
+    for (i, event) in events.iter().enumerate() {
+        let x = x_of(event.position);
+        let above = i % 2 == 0;
+        let level = 1.0 + ((i / 2) % 3) as f64;
+        let label_y = if above {
+            axis_y - level * level_height
+        } else {
+            axis_y + level * level_height + 14.0
+        };
+        let color = event.color.as_deref().unwrap_or(DEFAULT_NODE_COLOR);
+        let _ = writeln!(
+            svg.body,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="1" stroke-dasharray="2 2"/>"#,
+            x,
+            axis_y,
+            x,
+            if above { label_y + 4.0 } else { label_y - 24.0 },
+            escape(color)
+        );
+        let _ = writeln!(
+            svg.body,
+            r##"<circle cx="{:.1}" cy="{:.1}" r="6" fill="{}" stroke="#ffffff" stroke-width="2"/>"##,
+            x,
+            axis_y,
+            escape(color)
+        );
+        svg.text(
+            x,
+            label_y - 10.0,
+            12.0,
+            TEXT_COLOR,
+            "middle",
+            &truncate(&event.label),
+        );
+        if let Some(detail) = event.detail.as_deref().filter(|d| !d.is_empty()) {
+            svg.text(x, label_y + 2.0, 10.0, MUTED_COLOR, "middle", detail);
+        }
+    }
+    Ok(svg.finish())
+}
+
+/// SVG de la visualización `kind` descrita por `data`
+fn render(kind: VisualizationKind, data: serde_json::Value) -> Result<String, String> {
+    let invalid = |e: serde_json::Error| format!("Datos de visualizacion no validos: {}", e);
+    match kind {
+        VisualizationKind::Graph => render_graph(&serde_json::from_value(data).map_err(invalid)?),
+        VisualizationKind::Timeline => {
+            render_timeline(&serde_json::from_value(data).map_err(invalid)?)
+        }
+    }
+}
+
+/// Rasteriza el SVG; devuelve el PNG y su tamaño
+fn rasterize(svg: &str) -> Result<(Vec<u8>, u32, u32), String> {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree =
+        resvg::usvg::Tree::from_str(svg, &options).map_err(|e| format!("SVG no valido: {}", e))?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(PNG_SCALE)
+        .ok_or("Imagen demasiado grande")?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or("Imagen demasiado grande")?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::from_scale(PNG_SCALE, PNG_SCALE),
+        &mut pixmap.as_mut(),
+    );
+    let png = pixmap
+        .encode_png()
+        .map_err(|e| format!("Error codificando PNG: {}", e))?;
+    Ok((png, size.width(), size.height()))
+}
+
+fn export(
+    kind: VisualizationKind,
+    data: serde_json::Value,
+    path: &Path,
+) -> Result<ExportedVisualization, String> {
+    let svg = render(kind, data)?;
+    let is_png = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("png"));
+    let (bytes, width, height) = if is_png {
+        rasterize(&svg)?
+    } else {
+        let tree = resvg::usvg::Tree::from_str(&svg, &resvg::usvg::Options::default())
+            .map_err(|e| format!("SVG no valido: {}", e))?;
+        let size = tree.size().to_int_size();
+        (svg.into_bytes(), size.width(), size.height())
+    };
+    fs::write(path, bytes).map_err(|e| format!("No se pudo guardar {}: {}", path.display(), e))?;
+    Ok(ExportedVisualization {
+        path: path.to_string_lossy().into_owned(),
+        width,
+        height,
+    })
+}
+
+/// Exporta el grafo de relaciones o la cronología a SVG (o PNG, según la extensión de `path`)
+#[tauri::command]
+pub async fn export_visualization(
+    grants: State<'_, ExportGrants>,
+    kind: VisualizationKind,
+    data: serde_json::Value,
+    path: String,
+) -> Result<ExportedVisualization, String> {
+    grants.authorize(Path::new(&path))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let exported = export(kind, data, Path::new(&path))?;
+        tracing::info!(
+            target: "visualization_export",
+            "{:?} exportado a {} ({}x{})",
+            kind,
+            exported.path,
+            exported.width,
+            exported.height
+        );
+        Ok(exported)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de exportacion: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn graph_is_exported_as_svg_and_png() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = json!({
+            "title": "Relaciones de «Ana & Luis»",
+            "nodes": [
+                { "id": "1", "label": "Ana <narradora>", "x": -100.0, "y": 0.0, "color": "#e11d48" },
+                { "id": "2", "label": "Luis", "x": 100.0, "y": 50.0, "size": 20.0 },
+                { "id": "3", "label": "Begoña" }
+            ],
+            "edges": [
+                { "source": "1", "target": "2", "label": "hermanos", "dashed": true },
+                { "source": "1", "target": "99" }
+            ]
+        });
+        let svg_path = dir.path().join("grafo.svg");
+        let svg = export(VisualizationKind::Graph, data.clone(), &svg_path).unwrap();
+        let written = fs::read_to_string(&svg_path).unwrap();
+        assert!(written.contains("Ana &lt;narradora&gt;"));
+        assert!(written.contains("«Ana &amp; Luis»"));
+        // La arista a un nodo que no está en la vista se omite
+        assert_eq!(written.matches("<line").count(), 1);
+        assert!(svg.width > 200 && svg.height > 100);
+
+        let png_path = dir.path().join("grafo.png");
+        let png = export(VisualizationKind::Graph, data, &png_path).unwrap();
+        assert_eq!((png.width, png.height), (svg.width * 2, svg.height * 2));
+        assert!(fs::read(&png_path).unwrap().starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn timeline_orders_events_along_the_axis() {
+        let data: TimelineData = serde_json::from_value(json!({
+            "events": [
+                { "label": "Boda", "position": 3.0, "detail": "Cap. 3" },
+                { "label": "Llegada al pueblo", "position": 1.0 }
+            ],
+            "ticks": [{ "position": 1.0, "label": "Cap. 1" }]
+        }))
+        .unwrap();
+        let svg = render_timeline(&data).unwrap();
+        let first = svg.find("Llegada al pueblo").unwrap();
+        assert!(first < svg.find("Boda").unwrap());
+        assert!(svg.contains("Cap. 1") && svg.contains("Cap. 3"));
+        assert!(render(VisualizationKind::Timeline, json!({ "events": [] })).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Precarga de modelos en los ratos de inactividad en Narrative Assistant
+//
+// Cargar el LLM en Ollama y el modelo de embeddings en el backend tarda de
+// segundos a minutos, y lo paga la primera acción que los usa ("sugerir
+// reescritura", búsqueda semántica). Cuando el usuario lleva
+// `settings.warmup_idle_minutes` sin tocar el equipo y está enchufado a la
+// corriente, se cargan ambos para que la siguiente acción responda al momento.
+//
+// - LLM: `POST /api/generate` de Ollama sin prompt, que solo carga el modelo
+//   configurado en el backend (`/api/llm/status`) y lo mantiene en memoria
+//   `KEEP_ALIVE`.
+// - Embeddings: `POST /api/services/embeddings/warmup` del backend.
+//
+// Se precarga una vez por periodo de inactividad: hasta que el usuario vuelve
+// no se repite. Con batería, estado de alimentación desconocido, el backend en
+// mantenimiento o la precarga desactivada (0 minutos) no se hace nada.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::ollama::{self, OLLAMA_API_URL};
+use crate::settings::SettingsStore;
+use crate::{backend_auth, idle, power, proxy, BackendServer};
+
+/// Intervalo de comprobación
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tiempo que Ollama mantiene el modelo cargado tras la precarga
+const KEEP_ALIVE: &str = "30m";
+
+/// Límite para cargar un modelo (los grandes tardan en disco lento)
+const LOAD_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Si toca precargar en el periodo de inactividad actual
+#[derive(Default)]
+struct Schedule {
+    /// Ya se precargó desde la última vez que el usuario estuvo activo
+    done: bool,
+}
+
+impl Schedule {
+    /// `true` una sola vez por periodo de inactividad de al menos `threshold`,
+    /// y solo enchufado a la corriente
+    fn due(&mut self, idle_time: Duration, threshold: Duration, on_ac: Option<bool>) -> bool {
+        if idle_time < threshold {
+            self.done = false;
+            return false;
+        }
+        if self.done || on_ac != Some(true) {
+            return false;
+        }
+        self.done = true;
+        true
+    }
+}
+
+/// Modelo de Ollama que usa el backend, si el LLM está disponible
+async fn configured_llm() -> Option<String> {
+    let body: serde_json::Value = backend_auth::backend_client()
+        .get(backend_auth::backend_url("/api/llm/status"))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    let data = &body["data"];
+    if data["available"].as_bool() != Some(true) || data["backend"].as_str() != Some("ollama") {
+        return None;
+    }
+    data["model"].as_str().map(String::from)
+}
+
+async fn warm_llm() -> Result<Option<String>, String> {
+    let Some(model) = configured_llm().await else {
+        return Ok(None);
+    };
+    if !ollama::is_running().await {
+        return Ok(None);
+    }
+    let response = proxy::local_client()
+        .post(format!("{}/generate", OLLAMA_API_URL))
+        .json(&serde_json::json!({ "model": model, "keep_alive": KEEP_ALIVE }))
+        .timeout(LOAD_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("No se pudo contactar con Ollama: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Ollama respondio {} al cargar {}",
+            response.status(),
+            model
+        ));
+    }
+    Ok(Some(model))
+}
+
+async fn warm_embeddings() -> Result<(), String> {
+    let body: serde_json::Value = backend_auth::backend_client()
+        .post(backend_auth::backend_url("/api/services/embeddings/warmup"))
+        .timeout(LOAD_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("No se pudo contactar con el servidor: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta no valida del servidor: {}", e))?;
+    if body["success"].as_bool() != Some(true) {
+        return Err(body["error"]
+            .as_str()
+            .unwrap_or("Error cargando los embeddings")
+            .to_string());
+    }
+    Ok(())
+}
+
+/// Bucle en segundo plano
+pub async fn scheduler(app: AppHandle) {
+    let mut schedule = Schedule::default();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let minutes = app.state::<SettingsStore>().get().warmup_idle_minutes;
+        if minutes == 0 || app.state::<BackendServer>().paused.load(Ordering::SeqCst) {
+            continue;
+        }
+        let idle_time = idle::idle_time(&app);
+        let threshold = Duration::from_secs(u64::from(minutes) * 60);
+        // Solo se consulta la alimentación cuando el usuario lleva el tiempo inactivo
+        let on_ac = if idle_time >= threshold {
+            tauri::async_runtime::spawn_blocking(power::on_ac_power)
+                .await
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        if !schedule.due(idle_time, threshold, on_ac) {
+            continue;
+        }
+
+        match warm_llm().await {
+            Ok(Some(model)) => tracing::info!(target: "warmup", "LLM {} precargado", model),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(target: "warmup", "No se pudo precargar el LLM: {}", e),
+        }
+        match warm_embeddings().await {
+            Ok(()) => tracing::info!(target: "warmup", "Modelo de embeddings precargado"),
+            Err(e) => {
+                tracing::warn!(target: "warmup", "No se pudo precargar los embeddings: {}", e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warms_once_per_idle_period_and_only_on_ac() {
+        let mut schedule = Schedule::default();
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        let threshold = mins(10);
+
+        assert!(!schedule.due(mins(5), threshold, Some(true)));
+        // Con batería o sin saberlo se espera
+        assert!(!schedule.due(mins(10), threshold, Some(false)));
+        assert!(!schedule.due(mins(11), threshold, None));
+        assert!(schedule.due(mins(12), threshold, Some(true)));
+        assert!(!schedule.due(mins(20), threshold, Some(true)));
+        // El usuario vuelve: el siguiente periodo de inactividad precarga otra vez
+        assert!(!schedule.due(Duration::ZERO, threshold, Some(true)));
+        assert!(schedule.due(mins(10), threshold, Some(true)));
+    }
+}
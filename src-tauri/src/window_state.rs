@@ -0,0 +1,343 @@
+// Geometría de las ventanas de Narrative Assistant
+//
+// Guarda tamaño, posición, estado maximizado y monitor de cada ventana (por
+// label) en window-state.json dentro del directorio de datos de la app, y la
+// restaura al abrirla. Antes de aplicarla se ajusta a los monitores conectados
+// para no abrir la ventana fuera de la pantalla si se ha desconectado un monitor
+// o ha cambiado la resolución.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, Window};
+
+use crate::{cleanup, settings};
+
+/// Nombre del fichero de estado de las ventanas
+const WINDOW_STATE_FILE_NAME: &str = "window-state.json";
+
+/// Espera tras el último movimiento/redimensionado antes de escribir a disco
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Tamaño mínimo restaurable (coincide con minWidth/minHeight de tauri.conf.json)
+const MIN_WIDTH: u32 = 800;
+const MIN_HEIGHT: u32 = 600;
+
+/// Geometría de una ventana en píxeles físicos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    /// Nombre del monitor en el que estaba la ventana
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// Área de un monitor conectado
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MonitorArea {
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl MonitorArea {
+    fn from_monitor(monitor: &tauri::Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+
+    /// Área en común con la ventana
+    fn overlap(&self, geometry: &WindowGeometry) -> u64 {
+        let span = |a0: i32, a_len: u32, b0: i32, b_len: u32| -> u64 {
+            let start = a0.max(b0) as i64;
+            let end = (a0 as i64 + a_len as i64).min(b0 as i64 + b_len as i64);
+            (end - start).max(0) as u64
+        };
+        span(self.x, self.width, geometry.x, geometry.width)
+            * span(self.y, self.height, geometry.y, geometry.height)
+    }
+}
+
+/// Ajusta la geometría a los monitores conectados (el primero es el principal).
+/// Se usa el monitor guardado si sigue visible, si no el que más solapa con la
+/// ventana; si la ventana no cae en ninguno se centra en el principal.
+fn fit_to_monitors(geometry: &WindowGeometry, monitors: &[MonitorArea]) -> WindowGeometry {
+    let Some(primary) = monitors.first() else {
+        return geometry.clone();
+    };
+    let same_name = monitors
+        .iter()
+        .find(|m| m.name.is_some() && m.name == geometry.monitor && m.overlap(geometry) > 0);
+    let best_overlap = monitors
+        .iter()
+        .filter(|m| m.overlap(geometry) > 0)
+        .max_by_key(|m| m.overlap(geometry));
+    let (monitor, visible) = match same_name.or(best_overlap) {
+        Some(monitor) => (monitor, true),
+        None => (primary, false),
+    };
+
+    let width = geometry
+        .width
+        .clamp(MIN_WIDTH.min(monitor.width), monitor.width);
+    let height = geometry
+        .height
+        .clamp(MIN_HEIGHT.min(monitor.height), monitor.height);
+    let max_x = monitor.x + (monitor.width - width) as i32;
+    let max_y = monitor.y + (monitor.height - height) as i32;
+    let (x, y) = if visible {
+        (
+            geometry.x.clamp(monitor.x, max_x),
+            geometry.y.clamp(monitor.y, max_y),
+        )
+    } else {
+        (
+            monitor.x + ((monitor.width - width) / 2) as i32,
+            monitor.y + ((monitor.height - height) / 2) as i32,
+        )
+    };
+    WindowGeometry {
+        x,
+        y,
+        width,
+        height,
+        maximized: geometry.maximized,
+        monitor: monitor.name.clone(),
+    }
+}
+
+/// Geometrías en memoria, registradas con `.manage()`
+pub struct WindowStateStore {
+    path: PathBuf,
+    windows: Mutex<BTreeMap<String, WindowGeometry>>,
+    /// Se incrementa en cada cambio; el guardado diferido solo escribe si no hubo otro después
+    generation: AtomicU64,
+}
+
+impl WindowStateStore {
+    /// Carga window-state.json del directorio de datos de la app
+    pub fn load_default() -> Self {
+        Self::load(cleanup::app_data_dir().join(WINDOW_STATE_FILE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let windows = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            windows: Mutex::new(windows),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    fn get(&self, label: &str) -> Option<WindowGeometry> {
+        self.windows.lock().unwrap().get(label).cloned()
+    }
+
+    fn set(&self, label: &str, geometry: WindowGeometry) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.get(label) == Some(&geometry) {
+            return false;
+        }
+        windows.insert(label.to_string(), geometry);
+        true
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = {
+            let windows = self.windows.lock().unwrap();
+            serde_json::to_vec_pretty(&*windows).map_err(|e| e.to_string())?
+        };
+        settings::write_atomic(&self.path, &json)
+            .map_err(|e| format!("Error guardando {}: {}", self.path.display(), e))
+    }
+}
+
+/// Lee la geometría actual de una ventana. Mientras está maximizada se conservan
+/// el tamaño y la posición normales para poder restaurarla al desmaximizar.
+fn current_geometry<R: Runtime>(
+    window: &Window<R>,
+    previous: Option<&WindowGeometry>,
+) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+    if maximized {
+        if let Some(previous) = previous {
+            return Some(WindowGeometry {
+                maximized: true,
+                monitor,
+                ..previous.clone()
+            });
+        }
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+        monitor,
+    })
+}
+
+/// Registra la geometría de `window` tras un movimiento o redimensionado y
+/// programa el guardado
+pub fn track<R: Runtime>(window: &Window<R>) {
+    let app = window.app_handle();
+    let store = app.state::<WindowStateStore>();
+    let label = window.label();
+    let Some(geometry) = current_geometry(window, store.get(label).as_ref()) else {
+        return;
+    };
+    if !store.set(label, geometry) {
+        return;
+    }
+
+    let generation = store.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        let store = app.state::<WindowStateStore>();
+        if store.generation.load(Ordering::SeqCst) == generation {
+            if let Err(e) = store.save() {
+                tracing::warn!(target: "window", "{}", e);
+            }
+        }
+    });
+}
+
+/// Guarda la geometría de `window` inmediatamente (al cerrar)
+pub fn save_now<R: Runtime>(window: &Window<R>) {
+    let store = window.state::<WindowStateStore>();
+    if let Some(geometry) = current_geometry(window, store.get(window.label()).as_ref()) {
+        store.set(window.label(), geometry);
+    }
+    if let Err(e) = store.save() {
+        tracing::warn!(target: "window", "{}", e);
+    }
+}
+
+/// Aplica a `window` la geometría guardada, ajustada a los monitores actuales
+pub fn restore<R: Runtime>(app: &AppHandle<R>, window: &WebviewWindow<R>) {
+    let Some(saved) = app.state::<WindowStateStore>().get(window.label()) else {
+        return;
+    };
+    let mut monitors: Vec<MonitorArea> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorArea::from_monitor)
+        .collect();
+    if let Ok(Some(primary)) = window.primary_monitor() {
+        let primary = MonitorArea::from_monitor(&primary);
+        monitors.retain(|m| m != &primary);
+        monitors.insert(0, primary);
+    }
+
+    let geometry = fit_to_monitors(&saved, &monitors);
+    tracing::debug!(target: "window", "Restaurando {}: {:?}", window.label(), geometry);
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32) -> MonitorArea {
+        MonitorArea {
+            name: Some(name.into()),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32, monitor: &str) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            monitor: Some(monitor.into()),
+        }
+    }
+
+    #[test]
+    fn visible_geometry_is_kept() {
+        let monitors = [
+            monitor("A", 0, 0, 1920, 1080),
+            monitor("B", 1920, 0, 2560, 1440),
+        ];
+        let saved = geometry(2000, 100, 1400, 900, "B");
+
+        assert_eq!(fit_to_monitors(&saved, &monitors), saved);
+    }
+
+    #[test]
+    fn disconnected_monitor_centers_on_primary() {
+        let monitors = [monitor("A", 0, 0, 1920, 1080)];
+        let saved = geometry(3000, 200, 1200, 800, "B");
+
+        let fitted = fit_to_monitors(&saved, &monitors);
+
+        assert_eq!((fitted.x, fitted.y), (360, 140));
+        assert_eq!((fitted.width, fitted.height), (1200, 800));
+        assert_eq!(fitted.monitor.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn oversized_window_is_clamped_inside_monitor() {
+        let monitors = [monitor("A", 0, 0, 1366, 768)];
+        let saved = geometry(-50, 600, 1920, 1080, "A");
+
+        let fitted = fit_to_monitors(&saved, &monitors);
+
+        assert_eq!(fitted, geometry(0, 0, 1366, 768, "A"));
+    }
+
+    #[test]
+    fn store_roundtrips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(WINDOW_STATE_FILE_NAME);
+        let store = WindowStateStore::load(path.clone());
+        let saved = geometry(10, 20, 1000, 700, "A");
+
+        assert!(store.set("main", saved.clone()));
+        assert!(!store.set("main", saved.clone()));
+        store.save().unwrap();
+
+        assert_eq!(WindowStateStore::load(path).get("main"), Some(saved));
+    }
+}
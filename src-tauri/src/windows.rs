@@ -0,0 +1,110 @@
+// Ventanas de Narrative Assistant
+//
+// Además de la ventana principal, cada proyecto puede abrirse en su propia
+// ventana (`project-<id>`). Todas comparten el mismo backend: el servidor solo
+// se detiene al cerrar la última (ver `BackendServer::release_window`).
+//
+// Los eventos del menú nativo se envían a la ventana que tuvo el foco por
+// última vez, de modo que "Guardar" o "Ejecutar análisis" actúan sobre el
+// proyecto que el usuario está mirando.
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::window_state;
+use crate::BackendServer;
+
+/// Label de la ventana principal (tauri.conf.json)
+pub const MAIN_WINDOW: &str = "main";
+
+/// Prefijo de las ventanas de proyecto (coincide con capabilities/main.json)
+const PROJECT_WINDOW_PREFIX: &str = "project-";
+
+/// Ventana que tuvo el foco por última vez, registrada con `.manage()`
+pub struct ActiveWindow(Mutex<String>);
+
+impl ActiveWindow {
+    pub fn new() -> Self {
+        Self(Mutex::new(MAIN_WINDOW.to_string()))
+    }
+}
+
+pub(crate) fn project_window_label(project_id: i64) -> String {
+    format!("{}{}", PROJECT_WINDOW_PREFIX, project_id)
+}
+
+/// Id del proyecto de una ventana `project-<id>` (`None` para el resto)
+pub(crate) fn project_id_from_label(label: &str) -> Option<i64> {
+    label.strip_prefix(PROJECT_WINDOW_PREFIX)?.parse().ok()
+}
+
+/// URL de la ventana de proyecto. El router del frontend redirige
+/// `/index.html?project=<id>` a la vista del proyecto.
+fn project_window_url(project_id: i64, label: &str) -> String {
+    format!("index.html?project={}&window={}", project_id, label)
+}
+
+/// Registra la ventana que acaba de recibir el foco
+pub fn set_active(app: &AppHandle, label: &str) {
+    *app.state::<ActiveWindow>().0.lock().unwrap() = label.to_string();
+}
+
+/// Label de la ventana activa (la principal si la última activa ya no existe)
+pub fn active_window_label(app: &AppHandle) -> String {
+    let label = app.state::<ActiveWindow>().0.lock().unwrap().clone();
+    if app.get_webview_window(&label).is_some() {
+        label
+    } else {
+        MAIN_WINDOW.to_string()
+    }
+}
+
+/// Abre el proyecto en su propia ventana (o enfoca la existente). Devuelve su label.
+#[tauri::command]
+pub async fn open_project_window(app: AppHandle, project_id: i64) -> Result<String, String> {
+    if project_id <= 0 {
+        return Err(format!("Id de proyecto no valido: {}", project_id));
+    }
+    let label = project_window_label(project_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return Ok(label);
+    }
+
+    let window: WebviewWindow = WebviewWindowBuilder::new(
+        &app,
+        &label,
+        WebviewUrl::App(project_window_url(project_id, &label).into()),
+    )
+    .title("Narrative Assistant")
+    .inner_size(1200.0, 800.0)
+    .min_inner_size(800.0, 600.0)
+    .build()
+    .map_err(|e| format!("Error abriendo la ventana del proyecto: {}", e))?;
+
+    app.state::<BackendServer>().attach_window(&label);
+    window_state::restore(&app, &window);
+    crate::accessibility::apply_to(&window);
+    let _ = window.set_focus();
+    tracing::info!(target: "window", "Proyecto {} abierto en la ventana {}", project_id, label);
+    Ok(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_windows_have_own_label_and_query() {
+        let label = project_window_label(42);
+
+        assert_eq!(label, "project-42");
+        assert_eq!(
+            project_window_url(42, &label),
+            "index.html?project=42&window=project-42"
+        );
+        assert_eq!(project_id_from_label(&label), Some(42));
+        assert_eq!(project_id_from_label(MAIN_WINDOW), None);
+    }
+}
@@ -5,9 +5,11 @@ Este módulo proporciona un cliente thread-safe para interactuar con modelos
 LLM locales. Soporta:
 1. Ollama (servidor local con modelos como Llama, Mistral, etc.)
 2. Transformers (modelos HuggingFace descargados localmente)
+3. OpenAI (servidor remoto compatible con /chat/completions), solo si el
+   usuario lo configura en el shell (NA_LLM_BACKEND=openai)
 
-IMPORTANTE: Este módulo funciona 100% offline. No requiere acceso a internet
-una vez que los modelos están descargados.
+IMPORTANTE: Con Ollama y Transformers este módulo funciona 100% offline. No
+requiere acceso a internet una vez que los modelos están descargados.
 
 Instalación bajo demanda:
 - Ollama se instala solo cuando el usuario intenta usar funcionalidades LLM
@@ -38,7 +40,7 @@ _ollama_init_started = False
 # Estado granular del init para reportar al frontend
 _ollama_init_status: str = "not_needed"  # not_needed|installing|starting|downloading_model|ready|failed
 
-LLMBackend = Literal["ollama", "transformers", "none"]
+LLMBackend = Literal["ollama", "transformers", "openai", "none"]
 
 
 # =============================================================================
@@ -128,7 +130,7 @@ _installation_prompt_callback: Callable[[], bool] | None = None
 class LocalLLMConfig:
     """Configuración del cliente LLM local."""
 
-    # Backend a usar: ollama, transformers, none
+    # Backend a usar: ollama, transformers, openai, none
     backend: LLMBackend = "ollama"
 
     # Ollama config
@@ -139,6 +141,11 @@ class LocalLLMConfig:
     transformers_model_path: Path | None = None
     transformers_model_name: str = "meta-llama/Llama-3.2-3B-Instruct"
 
+    # Servidor remoto compatible con OpenAI (configurado desde el shell)
+    openai_base_url: str | None = None
+    openai_model: str | None = None
+    openai_api_key: str | None = None
+
     # Parámetros de generación
     max_tokens: int = 2048
     temperature: float = 0.3  # Bajo para análisis consistente
@@ -188,6 +195,18 @@ class LocalLLMClient:
 
     def _initialize_backend(self) -> None:
         """Inicializa el backend de LLM."""
+        # Servidor remoto elegido explícitamente: sin fallback a local
+        if self._config.backend == "openai":
+            if self._config.openai_base_url and self._config.openai_model:
+                self._backend = "openai"
+                logger.info(
+                    f"LLM remoto: {self._config.openai_model} en {self._config.openai_base_url}"
+                )
+            else:
+                logger.warning("NA_LLM_BACKEND=openai sin NA_OPENAI_BASE_URL o NA_OPENAI_MODEL")
+                self._backend = "none"
+            return
+
         # Intentar Ollama primero
         if self._config.backend in ("ollama", "auto") and self._try_init_ollama():
             return
@@ -519,6 +538,8 @@ class LocalLLMClient:
             return self._config.ollama_model
         elif self._backend == "transformers":
             return self._config.transformers_model_name
+        elif self._backend == "openai":
+            return self._config.openai_model or "none"
         return "none"
 
     def complete(
@@ -553,6 +574,9 @@ class LocalLLMClient:
                 return self._complete_ollama(prompt, system, max_tokens, temperature, model_name)
             elif self._backend == "transformers":
                 return self._complete_transformers(prompt, system, max_tokens, temperature)
+            elif self._backend == "openai":
+                # model_name se refiere a modelos de Ollama: el remoto usa el configurado
+                return self._complete_openai(prompt, system, max_tokens, temperature)
 
         return None
 
@@ -792,6 +816,50 @@ class LocalLLMClient:
             logger.error(f"Error en Transformers: {e}")
             return None
 
+    def _complete_openai(
+        self,
+        prompt: str,
+        system: str | None = None,
+        max_tokens: int | None = None,
+        temperature: float | None = None,
+    ) -> str | None:
+        """Genera respuesta usando un servidor compatible con OpenAI (/chat/completions)."""
+        try:
+            import httpx
+
+            messages: list[dict[str, str]] = []
+            if system:
+                messages.append({"role": "system", "content": system})
+            messages.append({"role": "user", "content": prompt})
+
+            headers = {}
+            if self._config.openai_api_key:
+                headers["Authorization"] = f"Bearer {self._config.openai_api_key}"
+
+            response = httpx.post(
+                f"{self._config.openai_base_url}/chat/completions",
+                headers=headers,
+                json={
+                    "model": self._config.openai_model,
+                    "messages": messages,
+                    "max_tokens": max_tokens if max_tokens is not None else self._config.max_tokens,
+                    "temperature": temperature if temperature is not None else self._config.temperature,
+                },
+                timeout=httpx.Timeout(connect=10.0, read=self._config.timeout, write=30.0, pool=10.0),
+            )
+            if response.status_code != 200:
+                logger.error(f"Error del LLM remoto: {response.status_code} - {response.text[:200]}")
+                return None
+
+            choices = response.json().get("choices") or []
+            if not choices:
+                return None
+            return choices[0].get("message", {}).get("content") or None
+
+        except Exception as e:
+            logger.error(f"Error en llamada al LLM remoto: {e}")
+            return None
+
     def analyze_json(
         self,
         prompt: str,
@@ -1118,10 +1186,12 @@ def _load_config() -> LocalLLMConfig:
     Carga la configuración del LLM local.
 
     Lee de variables de entorno:
-    - NA_LLM_BACKEND: ollama, transformers, auto
+    - NA_LLM_BACKEND: ollama, transformers, openai, auto
     - NA_OLLAMA_HOST: URL del servidor Ollama
     - NA_OLLAMA_MODEL: Modelo de Ollama a usar
     - NA_LLM_MODEL_PATH: Ruta al modelo local de Transformers
+    - NA_OPENAI_BASE_URL, NA_OPENAI_MODEL, NA_OPENAI_API_KEY: servidor remoto
+      compatible con OpenAI (los inyecta el shell desde sus preferencias)
     """
     backend_env = os.getenv("NA_LLM_BACKEND", "ollama")
     backend: LLMBackend
@@ -1129,6 +1199,8 @@ def _load_config() -> LocalLLMConfig:
         backend = "ollama"
     elif backend_env == "transformers":
         backend = "transformers"
+    elif backend_env == "openai":
+        backend = "openai"
     elif backend_env == "none":
         backend = "none"
     else:
@@ -1142,6 +1214,9 @@ def _load_config() -> LocalLLMConfig:
         backend=backend,
         ollama_host=ollama_host,
         ollama_model=os.getenv("NA_OLLAMA_MODEL", "llama3.2"),
+        openai_base_url=(os.getenv("NA_OPENAI_BASE_URL") or "").rstrip("/") or None,
+        openai_model=os.getenv("NA_OPENAI_MODEL") or None,
+        openai_api_key=os.getenv("NA_OPENAI_API_KEY") or None,
     )
 
     # A-04: Validar ruta del modelo desde variable de entorno
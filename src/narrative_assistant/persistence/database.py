@@ -5,6 +5,7 @@ Incluye:
 - Conexión segura con permisos restrictivos
 - Migraciones de schema
 - Transacciones y rollback
+- Cifrado opcional con SQLCipher (encryption.py)
 """
 
 import logging
@@ -17,6 +18,7 @@ from pathlib import Path
 from typing import ContextManager, cast
 
 from ..core.config import get_config
+from . import encryption
 
 logger = logging.getLogger(__name__)
 
@@ -2049,7 +2051,7 @@ class Database:
             # Verificación INDEPENDIENTE: abrir nueva conexión para confirmar persistencia
             if not self._is_memory:
                 logger.info("[SCHEMA] Verificación independiente con nueva conexión...")
-                verify_conn = sqlite3.connect(str(self.db_path))
+                verify_conn = encryption.connect(str(self.db_path))
                 try:
                     verify_tables = verify_conn.execute(
                         "SELECT name FROM sqlite_master WHERE type='table'"
@@ -2093,7 +2095,7 @@ class Database:
 
     def _create_connection(self) -> sqlite3.Connection:
         """Crea y configura una nueva conexión."""
-        conn = sqlite3.connect(
+        conn = encryption.connect(
             str(self.db_path),
             isolation_level="DEFERRED",
             check_same_thread=False,
@@ -2105,7 +2107,7 @@ class Database:
             conn.execute("PRAGMA journal_mode = WAL")
             conn.execute("PRAGMA busy_timeout = 5000")
         conn.execute("PRAGMA synchronous = NORMAL")
-        conn.row_factory = sqlite3.Row
+        conn.row_factory = encryption.driver().Row
         return conn
 
     @contextmanager
@@ -2267,8 +2269,8 @@ def repair_database() -> tuple[bool, str]:
 
         try:
             # Paso 1: Verificar integridad
-            conn = sqlite3.connect(str(db_path), timeout=30)
-            conn.row_factory = sqlite3.Row
+            conn = encryption.connect(str(db_path), timeout=30)
+            conn.row_factory = encryption.driver().Row
 
             integrity = conn.execute("PRAGMA integrity_check").fetchone()[0]
             logger.info(f"Integrity check: {integrity}")
@@ -0,0 +1,59 @@
+"""
+Cifrado de la base de datos con SQLCipher.
+
+El shell de Tauri guarda la clave en el almacén del sistema y la pasa al
+backend en `NA_DB_KEY` cuando la base de datos está cifrada (ver
+src-tauri/src/db_encryption.rs, que también cifra la base de datos existente).
+Con clave, las conexiones se abren con `sqlcipher3` (extra `encryption`) en
+lugar de `sqlite3`; sin ella, todo sigue como antes.
+
+La clave son 32 bytes en hexadecimal y se aplica en bruto (`x'...'`), sin
+derivación de contraseña.
+"""
+
+import logging
+import os
+import re
+import sqlite3
+from types import ModuleType
+from typing import Any
+
+logger = logging.getLogger(__name__)
+
+DB_KEY_ENV = "NA_DB_KEY"
+
+_KEY_PATTERN = re.compile(r"^[0-9a-fA-F]{64}$")
+
+
+def db_key() -> str | None:
+    """Clave de la base de datos recibida del shell, o None si no está cifrada."""
+    key = os.environ.get(DB_KEY_ENV, "").strip()
+    if not key:
+        return None
+    if not _KEY_PATTERN.match(key):
+        # Nunca se interpola en SQL una clave que no sea hexadecimal
+        raise ValueError(f"{DB_KEY_ENV} no es una clave válida (64 caracteres hexadecimales)")
+    return key
+
+
+def driver() -> ModuleType:
+    """Módulo DB-API con el que abrir la base de datos (`sqlite3` o `sqlcipher3`)."""
+    if db_key() is None:
+        return sqlite3
+    try:
+        from sqlcipher3 import dbapi2
+    except ImportError as e:
+        raise RuntimeError(
+            "La base de datos está cifrada y falta sqlcipher3 "
+            "(pip install narrative-assistant[encryption])"
+        ) from e
+    return dbapi2
+
+
+def connect(database: str, **kwargs: Any) -> sqlite3.Connection:
+    """`sqlite3.connect` que aplica la clave si la base de datos está cifrada."""
+    key = db_key()
+    conn = driver().connect(database, **kwargs)
+    if key is not None and database != ":memory:":
+        conn.execute(f"PRAGMA key = \"x'{key}'\"")
+    return conn
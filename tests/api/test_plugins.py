@@ -0,0 +1,71 @@
+"""
+Tests de la carga de complementos (routers/plugins.py).
+
+Verifica:
+- Un punto de entrada de NA_PLUGINS registra sus endpoints bajo /api/plugins/{id}
+- Un complemento que falla al importar se omite y queda registrado el error
+"""
+
+import json
+
+from fastapi import FastAPI
+from fastapi.testclient import TestClient
+
+
+def _write_plugin(tmp_path, module_name, source):
+    plugin_dir = tmp_path / module_name
+    plugin_dir.mkdir()
+    (plugin_dir / f"{module_name}.py").write_text(source, encoding="utf-8")
+    return plugin_dir
+
+
+class TestPluginLoading:
+    """Tests de carga de complementos desde NA_PLUGINS."""
+
+    def test_entry_point_registers_namespaced_routes(self, tmp_path):
+        """Los endpoints del complemento quedan bajo su prefijo."""
+        from routers.plugins import load_plugins
+
+        plugin_dir = _write_plugin(
+            tmp_path,
+            "na_test_ritmo",
+            "def register(router):\n"
+            "    @router.post('/informe')\n"
+            "    def informe(payload: dict):\n"
+            "        return {'success': True, 'data': {'project_id': payload['project_id']}}\n",
+        )
+        raw = json.dumps(
+            [{"id": "ritmo", "dir": str(plugin_dir), "entry_point": "na_test_ritmo:register"}]
+        )
+        router, status = load_plugins(raw)
+        assert status == [{"id": "ritmo", "loaded": True, "error": None}]
+
+        app = FastAPI()
+        app.include_router(router)
+        response = TestClient(app).post("/api/plugins/ritmo/informe", json={"project_id": 7})
+        assert response.status_code == 200
+        assert response.json()["data"] == {"project_id": 7}
+
+    def test_broken_plugin_is_skipped(self, tmp_path):
+        """Un error al importar no impide cargar el resto."""
+        from routers.plugins import load_plugins
+
+        broken_dir = _write_plugin(tmp_path, "na_test_roto", "raise RuntimeError('roto')\n")
+        raw = json.dumps(
+            [
+                {"id": "roto", "dir": str(broken_dir), "entry_point": "na_test_roto:register"},
+                {"id": "../fuera", "dir": str(tmp_path), "entry_point": "x:y"},
+            ]
+        )
+        router, status = load_plugins(raw)
+        assert [s["loaded"] for s in status] == [False, False]
+        assert "roto" in status[0]["error"]
+        assert router.routes == []
+
+    def test_missing_env_loads_nothing(self):
+        """Sin NA_PLUGINS no hay complementos."""
+        from routers.plugins import load_plugins
+
+        router, status = load_plugins(None)
+        assert status == []
+        assert router.routes == []
@@ -0,0 +1,39 @@
+"""
+Tests de contrato para las notas rápidas del proyecto.
+
+Verifica:
+- POST /api/projects/{id}/notes guarda la nota en settings["quick_notes"]
+- GET /api/projects/{id}/notes devuelve las notas de la más reciente a la más antigua
+- Notas vacías y proyectos inexistentes se rechazan
+"""
+
+
+class TestProjectNotesContract:
+    """Tests de contrato API para notas rápidas."""
+
+    def test_add_and_list_notes(self, test_client, sample_project):
+        """Las notas añadidas se listan en orden inverso."""
+        for text in ["Primera nota", "  Segunda nota  "]:
+            response = test_client.post(
+                f"/api/projects/{sample_project.id}/notes",
+                json={"text": text, "source": "quick_note"},
+            )
+            assert response.status_code == 200
+            assert response.json()["success"] is True
+
+        response = test_client.get(f"/api/projects/{sample_project.id}/notes")
+        notes = response.json()["data"]
+        assert [n["text"] for n in notes] == ["Segunda nota", "Primera nota"]
+        assert all(n["source"] == "quick_note" and n["id"] for n in notes)
+
+    def test_empty_note_is_rejected(self, test_client, sample_project):
+        """Una nota vacía devuelve 400."""
+        response = test_client.post(
+            f"/api/projects/{sample_project.id}/notes", json={"text": "   "}
+        )
+        assert response.status_code == 400
+
+    def test_unknown_project_returns_404(self, test_client):
+        """Una nota para un proyecto inexistente devuelve 404."""
+        response = test_client.post("/api/projects/999999/notes", json={"text": "Nota"})
+        assert response.status_code == 404
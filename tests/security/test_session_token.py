@@ -0,0 +1,70 @@
+"""
+Tests del token de sesión que el shell exige en la API (middleware/session_token.py).
+"""
+
+import sys
+from pathlib import Path
+
+from fastapi import FastAPI
+from fastapi.testclient import TestClient
+
+sys.path.insert(0, str(Path(__file__).parent.parent.parent / "api-server"))
+
+from middleware.session_token import SHELL_TOKEN_HEADER, TOKEN_HEADER, SessionTokenMiddleware
+
+
+def _client(token: str, shell_token: str = "") -> TestClient:
+    app = FastAPI()
+    app.add_middleware(SessionTokenMiddleware, token=token, shell_token=shell_token)
+
+    @app.get("/api/health")
+    def health():
+        return {"status": "ok"}
+
+    @app.get("/api/projects")
+    def projects():
+        return {"success": True, "data": []}
+
+    @app.post("/api/projects/{project_id}/save-file")
+    def save_file(project_id: int):
+        return {"success": True}
+
+    return TestClient(app)
+
+
+def test_requests_without_the_session_token_are_rejected():
+    client = _client("s3cret")
+
+    assert client.get("/api/health").status_code == 200
+    rejected = client.get("/api/projects")
+    assert rejected.status_code == 401
+    assert rejected.json()["success"] is False
+    assert client.get("/api/projects", headers={TOKEN_HEADER: "otro"}).status_code == 401
+    assert client.get("/api/projects", headers={TOKEN_HEADER: "s3cret"}).status_code == 200
+
+
+def test_token_is_not_required_when_the_shell_did_not_set_one():
+    assert _client("").get("/api/projects").status_code == 200
+
+
+def test_save_file_only_accepts_requests_from_the_shell():
+    client = _client("s3cret", shell_token="sh3ll")
+    session = {TOKEN_HEADER: "s3cret"}
+
+    # El frontend tiene el token de sesión, pero no el del shell
+    rejected = client.post("/api/projects/7/save-file", headers=session)
+    assert rejected.status_code == 403
+    assert rejected.json()["success"] is False
+    assert (
+        client.post(
+            "/api/projects/7/save-file", headers={**session, SHELL_TOKEN_HEADER: "otro"}
+        ).status_code
+        == 403
+    )
+    assert (
+        client.post(
+            "/api/projects/7/save-file", headers={**session, SHELL_TOKEN_HEADER: "sh3ll"}
+        ).status_code
+        == 200
+    )
+    assert client.get("/api/projects", headers=session).status_code == 200
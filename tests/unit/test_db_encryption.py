@@ -0,0 +1,46 @@
+"""
+Tests del cifrado opcional de la base de datos (persistence/encryption.py).
+"""
+
+import sqlite3
+
+import pytest
+
+from narrative_assistant.persistence import encryption
+
+KEY = "0123456789abcdef" * 4
+
+
+def test_without_key_connections_use_plain_sqlite(monkeypatch, tmp_path):
+    monkeypatch.delenv(encryption.DB_KEY_ENV, raising=False)
+
+    assert encryption.db_key() is None
+    assert encryption.driver() is sqlite3
+    conn = encryption.connect(str(tmp_path / "plain.db"))
+    conn.execute("CREATE TABLE t (x)")
+    conn.close()
+    assert (tmp_path / "plain.db").read_bytes().startswith(b"SQLite format 3\0")
+
+
+def test_invalid_keys_are_never_used(monkeypatch):
+    monkeypatch.setenv(encryption.DB_KEY_ENV, "x'; DROP TABLE projects; --")
+
+    with pytest.raises(ValueError):
+        encryption.connect(":memory:")
+
+
+def test_encrypted_database_needs_the_key(monkeypatch, tmp_path):
+    pytest.importorskip("sqlcipher3")
+    monkeypatch.setenv(encryption.DB_KEY_ENV, KEY)
+    db = tmp_path / "encrypted.db"
+
+    conn = encryption.connect(str(db))
+    conn.execute("CREATE TABLE projects (name TEXT)")
+    conn.execute("INSERT INTO projects VALUES ('Encargo confidencial')")
+    conn.commit()
+    conn.close()
+
+    assert not db.read_bytes().startswith(b"SQLite format 3\0")
+    conn = encryption.connect(str(db))
+    assert conn.execute("SELECT name FROM projects").fetchone()[0] == "Encargo confidencial"
+    conn.close()
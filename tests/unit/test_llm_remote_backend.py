@@ -0,0 +1,64 @@
+"""
+Tests del backend LLM remoto compatible con OpenAI (NA_LLM_BACKEND=openai).
+
+El shell inyecta NA_OPENAI_BASE_URL, NA_OPENAI_MODEL y NA_OPENAI_API_KEY
+desde sus preferencias (src-tauri/src/remote_llm.rs).
+"""
+
+import httpx
+
+from narrative_assistant.llm import client as client_mod
+from narrative_assistant.llm.client import LocalLLMClient, _load_config
+
+
+class _Response:
+    status_code = 200
+    text = ""
+
+    def json(self):
+        return {"choices": [{"message": {"content": "Texto reescrito"}}]}
+
+
+def test_load_config_reads_remote_endpoint(monkeypatch):
+    monkeypatch.setenv("NA_LLM_BACKEND", "openai")
+    monkeypatch.setenv("NA_OPENAI_BASE_URL", "https://llm.example.com/v1/")
+    monkeypatch.setenv("NA_OPENAI_MODEL", "gpt-4o-mini")
+    monkeypatch.setenv("NA_OPENAI_API_KEY", "sk-test")
+
+    config = _load_config()
+
+    assert config.backend == "openai"
+    assert config.openai_base_url == "https://llm.example.com/v1"
+    assert config.openai_model == "gpt-4o-mini"
+    assert config.openai_api_key == "sk-test"
+
+
+def test_remote_backend_posts_chat_completions(monkeypatch):
+    calls = []
+
+    def fake_post(url, headers=None, json=None, timeout=None):
+        calls.append((url, headers, json))
+        return _Response()
+
+    monkeypatch.setattr(httpx, "post", fake_post)
+    config = client_mod.LocalLLMConfig(
+        backend="openai",
+        openai_base_url="https://llm.example.com/v1",
+        openai_model="gpt-4o-mini",
+        openai_api_key="sk-test",
+    )
+    llm = LocalLLMClient(config)
+
+    assert llm.backend_name == "openai"
+    assert llm.model_name == "gpt-4o-mini"
+    assert llm.complete("Reescribe", system="Eres editor", model_name="qwen3") == "Texto reescrito"
+    url, headers, body = calls[0]
+    assert url == "https://llm.example.com/v1/chat/completions"
+    assert headers == {"Authorization": "Bearer sk-test"}
+    assert body["model"] == "gpt-4o-mini"
+    assert body["messages"][0] == {"role": "system", "content": "Eres editor"}
+
+
+def test_remote_backend_without_model_is_unavailable():
+    llm = LocalLLMClient(client_mod.LocalLLMConfig(backend="openai", openai_base_url="https://x/v1"))
+    assert not llm.is_available